@@ -222,12 +222,16 @@ fn testnet_genesis(
 		},
 		bridge_rialto_messages: BridgeRialtoMessagesConfig {
 			owner: Some(get_account_id_from_seed::<sr25519::Public>(RIALTO_MESSAGES_PALLET_OWNER)),
+			opened_lanes: vec![millau_runtime::rialto_messages::DEFAULT_XCM_LANE_TO_RIALTO],
 			..Default::default()
 		},
 		bridge_rialto_parachain_messages: BridgeRialtoParachainMessagesConfig {
 			owner: Some(get_account_id_from_seed::<sr25519::Public>(
 				RIALTO_PARACHAIN_MESSAGES_PALLET_OWNER,
 			)),
+			opened_lanes: vec![
+				millau_runtime::rialto_parachain_messages::DEFAULT_XCM_LANE_TO_RIALTO_PARACHAIN,
+			],
 			..Default::default()
 		},
 		xcm_pallet: Default::default(),
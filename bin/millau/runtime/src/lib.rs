@@ -44,7 +44,6 @@ use bp_runtime::{HeaderId, HeaderIdProvider};
 use bridge_runtime_common::messages::{
 	source::estimate_message_dispatch_and_delivery_fee, MessageBridge,
 };
-use codec::Decode;
 use pallet_grandpa::{
 	fg_primitives, AuthorityId as GrandpaId, AuthorityList as GrandpaAuthorityList,
 };
@@ -397,6 +396,8 @@ parameter_types! {
 	// Note that once this is hit the pallet will essentially throttle incoming requests down to one
 	// call per block.
 	pub const MaxRequests: u32 = 50;
+	// Quarter of the block weight may be spent on importing bridged headers.
+	pub const MaxRequestsWeight: Weight = bp_millau::MAXIMUM_BLOCK_WEIGHT / 4;
 }
 
 impl pallet_bridge_relayers::Config for Runtime {
@@ -426,20 +427,48 @@ parameter_types! {
 	pub const HeadersToKeep: u32 = 7 * bp_rialto::DAYS as u32;
 }
 
+parameter_types! {
+	/// Number of justifications of the most recent headers to keep.
+	///
+	/// Assuming the worst case of every header being finalized, we will keep justifications
+	/// for at least an hour.
+	pub const JustificationsToKeep: u32 = bp_rialto::HOURS as u32;
+	/// Maximal number of finality proofs in the single `submit_finality_proof_batch` call.
+	pub const MaxFinalityProofsInBatch: u32 = 16;
+}
+
 pub type RialtoGrandpaInstance = ();
 impl pallet_bridge_grandpa::Config for Runtime {
+	type Event = Event;
 	type BridgedChain = bp_rialto::Rialto;
 	type MaxRequests = MaxRequests;
+	type MaxRequestsWeight = MaxRequestsWeight;
 	type HeadersToKeep = HeadersToKeep;
+	type JustificationsToKeep = JustificationsToKeep;
+	type MaxJustificationSize =
+		frame_support::traits::ConstU32<{ bp_rialto::MAX_JUSTIFICATION_SIZE }>;
+	type MaxBridgedAuthorities =
+		frame_support::traits::ConstU32<{ bp_rialto::MAX_AUTHORITIES_COUNT }>;
+	type MaxBridgedHeaderSize = frame_support::traits::ConstU32<{ bp_rialto::MAX_HEADER_SIZE }>;
+	type MaxFinalityProofsInBatch = MaxFinalityProofsInBatch;
 
 	type WeightInfo = pallet_bridge_grandpa::weights::BridgeWeight<Runtime>;
 }
 
 pub type WestendGrandpaInstance = pallet_bridge_grandpa::Instance1;
 impl pallet_bridge_grandpa::Config<WestendGrandpaInstance> for Runtime {
+	type Event = Event;
 	type BridgedChain = bp_westend::Westend;
 	type MaxRequests = MaxRequests;
+	type MaxRequestsWeight = MaxRequestsWeight;
 	type HeadersToKeep = HeadersToKeep;
+	type JustificationsToKeep = JustificationsToKeep;
+	type MaxJustificationSize =
+		frame_support::traits::ConstU32<{ bp_westend::MAX_JUSTIFICATION_SIZE }>;
+	type MaxBridgedAuthorities =
+		frame_support::traits::ConstU32<{ bp_westend::MAX_AUTHORITIES_COUNT }>;
+	type MaxBridgedHeaderSize = frame_support::traits::ConstU32<{ bp_westend::MAX_HEADER_SIZE }>;
+	type MaxFinalityProofsInBatch = MaxFinalityProofsInBatch;
 
 	type WeightInfo = pallet_bridge_grandpa::weights::BridgeWeight<Runtime>;
 }
@@ -448,10 +477,12 @@ impl pallet_shift_session_manager::Config for Runtime {}
 
 parameter_types! {
 	pub const MaxMessagesToPruneAtOnce: bp_messages::MessageNonce = 8;
+	pub const MaxOutboundQueueLen: bp_messages::MessageNonce = 4096;
 	pub const MaxUnrewardedRelayerEntriesAtInboundLane: bp_messages::MessageNonce =
 		bp_rialto::MAX_UNREWARDED_RELAYERS_IN_CONFIRMATION_TX;
 	pub const MaxUnconfirmedMessagesAtInboundLane: bp_messages::MessageNonce =
 		bp_rialto::MAX_UNCONFIRMED_MESSAGES_IN_CONFIRMATION_TX;
+	pub const MaxRecentDeliveries: u32 = 64;
 	// `IdentityFee` is used by Millau => we may use weight directly
 	pub const GetDeliveryConfirmationTransactionFee: Balance =
 		bp_millau::MAX_SINGLE_MESSAGE_DELIVERY_CONFIRMATION_TX_WEIGHT as _;
@@ -468,14 +499,22 @@ impl pallet_bridge_messages::Config<WithRialtoMessagesInstance> for Runtime {
 	type WeightInfo = pallet_bridge_messages::weights::BridgeWeight<Runtime>;
 	type Parameter = rialto_messages::MillauToRialtoMessagesParameter;
 	type MaxMessagesToPruneAtOnce = MaxMessagesToPruneAtOnce;
+	type MaxOutboundQueueLen = MaxOutboundQueueLen;
 	type MaxUnrewardedRelayerEntriesAtInboundLane = MaxUnrewardedRelayerEntriesAtInboundLane;
 	type MaxUnconfirmedMessagesAtInboundLane = MaxUnconfirmedMessagesAtInboundLane;
+	type MaxRecentDeliveries = MaxRecentDeliveries;
+	type MessageExpiryBlocks = ();
+	type OutboundMessagesRateLimit = ();
+	type RateLimitedSender = ();
+	type MessageSubmitter = ();
+	type IndexMessagesHistory = ();
 
 	type MaximalOutboundPayloadSize = crate::rialto_messages::ToRialtoMaximalOutboundPayloadSize;
 	type OutboundPayload = crate::rialto_messages::ToRialtoMessagePayload;
 	type OutboundMessageFee = Balance;
 
 	type InboundPayload = crate::rialto_messages::FromRialtoMessagePayload;
+	type MaximalInboundPayloadSize = crate::rialto_messages::FromRialtoMaximalInboundPayloadSize;
 	type InboundMessageFee = bp_rialto::Balance;
 	type InboundRelayer = bp_rialto::AccountId;
 
@@ -491,6 +530,9 @@ impl pallet_bridge_messages::Config<WithRialtoMessagesInstance> for Runtime {
 	type OnDeliveryConfirmed = ();
 
 	type SourceHeaderChain = crate::rialto_messages::Rialto;
+	type MaxMessagesProofSize = crate::rialto_messages::FromRialtoMaximalMessagesProofSize;
+	type MaxMessagesDeliveryProofSize =
+		crate::rialto_messages::ToRialtoMaximalMessagesDeliveryProofSize;
 	type MessageDispatch = crate::rialto_messages::FromRialtoMessageDispatch;
 	type BridgedChainId = RialtoChainId;
 }
@@ -503,8 +545,15 @@ impl pallet_bridge_messages::Config<WithRialtoParachainMessagesInstance> for Run
 	type WeightInfo = pallet_bridge_messages::weights::BridgeWeight<Runtime>;
 	type Parameter = rialto_parachain_messages::MillauToRialtoParachainMessagesParameter;
 	type MaxMessagesToPruneAtOnce = MaxMessagesToPruneAtOnce;
+	type MaxOutboundQueueLen = MaxOutboundQueueLen;
 	type MaxUnrewardedRelayerEntriesAtInboundLane = MaxUnrewardedRelayerEntriesAtInboundLane;
 	type MaxUnconfirmedMessagesAtInboundLane = MaxUnconfirmedMessagesAtInboundLane;
+	type MaxRecentDeliveries = MaxRecentDeliveries;
+	type MessageExpiryBlocks = ();
+	type OutboundMessagesRateLimit = ();
+	type RateLimitedSender = ();
+	type MessageSubmitter = ();
+	type IndexMessagesHistory = ();
 
 	type MaximalOutboundPayloadSize =
 		crate::rialto_parachain_messages::ToRialtoParachainMaximalOutboundPayloadSize;
@@ -512,6 +561,8 @@ impl pallet_bridge_messages::Config<WithRialtoParachainMessagesInstance> for Run
 	type OutboundMessageFee = Balance;
 
 	type InboundPayload = crate::rialto_parachain_messages::FromRialtoParachainMessagePayload;
+	type MaximalInboundPayloadSize =
+		crate::rialto_parachain_messages::FromRialtoParachainMaximalInboundPayloadSize;
 	type InboundMessageFee = bp_rialto_parachain::Balance;
 	type InboundRelayer = bp_rialto_parachain::AccountId;
 
@@ -527,6 +578,10 @@ impl pallet_bridge_messages::Config<WithRialtoParachainMessagesInstance> for Run
 	type OnDeliveryConfirmed = ();
 
 	type SourceHeaderChain = crate::rialto_parachain_messages::RialtoParachain;
+	type MaxMessagesProofSize =
+		crate::rialto_parachain_messages::FromRialtoParachainMaximalMessagesProofSize;
+	type MaxMessagesDeliveryProofSize =
+		crate::rialto_parachain_messages::ToRialtoParachainMaximalMessagesDeliveryProofSize;
 	type MessageDispatch = crate::rialto_parachain_messages::FromRialtoParachainMessageDispatch;
 	type BridgedChainId = RialtoParachainChainId;
 }
@@ -534,6 +589,8 @@ impl pallet_bridge_messages::Config<WithRialtoParachainMessagesInstance> for Run
 parameter_types! {
 	pub const RialtoParasPalletName: &'static str = bp_rialto::PARAS_PALLET_NAME;
 	pub const WestendParasPalletName: &'static str = bp_westend::PARAS_PALLET_NAME;
+	pub const MaxRialtoParaHeadsProofSize: u32 = bp_rialto::MAX_PARA_HEADS_PROOF_SIZE;
+	pub const MaxWestendParaHeadsProofSize: u32 = bp_westend::MAX_PARA_HEADS_PROOF_SIZE;
 }
 
 /// Instance of the with-Rialto parachains pallet.
@@ -546,6 +603,7 @@ impl pallet_bridge_parachains::Config<WithRialtoParachainsInstance> for Runtime
 	type ParasPalletName = RialtoParasPalletName;
 	type TrackedParachains = frame_support::traits::Everything;
 	type HeadsToKeep = HeadersToKeep;
+	type MaxParaHeadsProofSize = MaxRialtoParaHeadsProofSize;
 }
 
 /// Instance of the with-Westend parachains pallet.
@@ -558,6 +616,7 @@ impl pallet_bridge_parachains::Config<WithWestendParachainsInstance> for Runtime
 	type ParasPalletName = WestendParasPalletName;
 	type TrackedParachains = frame_support::traits::Everything;
 	type HeadsToKeep = HeadersToKeep;
+	type MaxParaHeadsProofSize = MaxWestendParaHeadsProofSize;
 }
 
 construct_runtime!(
@@ -589,11 +648,11 @@ construct_runtime!(
 
 		// Rialto bridge modules.
 		BridgeRelayers: pallet_bridge_relayers::{Pallet, Call, Storage, Event<T>},
-		BridgeRialtoGrandpa: pallet_bridge_grandpa::{Pallet, Call, Storage},
+		BridgeRialtoGrandpa: pallet_bridge_grandpa::{Pallet, Call, Storage, Event<T>},
 		BridgeRialtoMessages: pallet_bridge_messages::{Pallet, Call, Storage, Event<T>, Config<T>},
 
 		// Westend bridge modules.
-		BridgeWestendGrandpa: pallet_bridge_grandpa::<Instance1>::{Pallet, Call, Config<T>, Storage},
+		BridgeWestendGrandpa: pallet_bridge_grandpa::<Instance1>::{Pallet, Call, Config<T>, Storage, Event<T>},
 		BridgeWestendParachains: pallet_bridge_parachains::<Instance1>::{Pallet, Call, Storage, Event<T>},
 
 		// RialtoParachain bridge modules.
@@ -860,12 +919,74 @@ impl_runtime_apis! {
 		fn best_finalized() -> Option<HeaderId<bp_rialto::Hash, bp_rialto::BlockNumber>> {
 			BridgeRialtoGrandpa::best_finalized().map(|header| header.id())
 		}
+
+		fn finalized_header(
+			number: bp_rialto::BlockNumber,
+		) -> Option<HeaderId<bp_rialto::Hash, bp_rialto::BlockNumber>> {
+			BridgeRialtoGrandpa::finalized_header(number)
+		}
+
+		fn accepts_free_header(header: bp_rialto::Header) -> bool {
+			BridgeRialtoGrandpa::is_free_header(&header)
+		}
+
+		fn is_known_header(hash: bp_rialto::Hash) -> bool {
+			BridgeRialtoGrandpa::is_known_header(hash)
+		}
+
+		fn stored_justification(hash: bp_rialto::Hash) -> Option<bp_runtime::EncodedJustification> {
+			BridgeRialtoGrandpa::stored_justification(hash)
+		}
+	}
+
+	impl bp_rialto::RialtoFinalityOperatingModeApi<Block> for Runtime {
+		fn operating_state() -> bp_runtime::FinalityOperatingState<bp_rialto::BlockNumber> {
+			BridgeRialtoGrandpa::operating_state()
+		}
+	}
+
+	impl bp_rialto::RialtoSyncedHeadersGrandpaInfoApi<Block> for Runtime {
+		fn synced_headers_grandpa_info(
+		) -> bp_runtime::SyncedHeadersGrandpaInfo<bp_rialto::Hash, bp_rialto::BlockNumber> {
+			BridgeRialtoGrandpa::synced_headers_grandpa_info()
+		}
 	}
 
 	impl bp_westend::WestendFinalityApi<Block> for Runtime {
 		fn best_finalized() -> Option<HeaderId<bp_westend::Hash, bp_westend::BlockNumber>> {
 			BridgeWestendGrandpa::best_finalized().map(|header| header.id())
 		}
+
+		fn finalized_header(
+			number: bp_westend::BlockNumber,
+		) -> Option<HeaderId<bp_westend::Hash, bp_westend::BlockNumber>> {
+			BridgeWestendGrandpa::finalized_header(number)
+		}
+
+		fn accepts_free_header(header: bp_westend::Header) -> bool {
+			BridgeWestendGrandpa::is_free_header(&header)
+		}
+
+		fn is_known_header(hash: bp_westend::Hash) -> bool {
+			BridgeWestendGrandpa::is_known_header(hash)
+		}
+
+		fn stored_justification(hash: bp_westend::Hash) -> Option<bp_runtime::EncodedJustification> {
+			BridgeWestendGrandpa::stored_justification(hash)
+		}
+	}
+
+	impl bp_westend::WestendFinalityOperatingModeApi<Block> for Runtime {
+		fn operating_state() -> bp_runtime::FinalityOperatingState<bp_westend::BlockNumber> {
+			BridgeWestendGrandpa::operating_state()
+		}
+	}
+
+	impl bp_westend::WestendSyncedHeadersGrandpaInfoApi<Block> for Runtime {
+		fn synced_headers_grandpa_info(
+		) -> bp_runtime::SyncedHeadersGrandpaInfo<bp_westend::Hash, bp_westend::BlockNumber> {
+			BridgeWestendGrandpa::synced_headers_grandpa_info()
+		}
 	}
 
 	impl bp_westend::WestmintFinalityApi<Block> for Runtime {
@@ -877,9 +998,41 @@ impl_runtime_apis! {
 				Runtime,
 				WithWestendParachainsInstance,
 			>::best_parachain_head(WESTMINT_PARACHAIN_ID.into())?;
-			let head = bp_westend::Header::decode(&mut &encoded_head.0[..]).ok()?;
+			let head = encoded_head.decode_header::<bp_westend::Header>().ok()?;
 			Some(head.id())
 		}
+
+		fn finalized_header(
+			number: bp_westend::BlockNumber,
+		) -> Option<HeaderId<bp_westend::Hash, bp_westend::BlockNumber>> {
+			// the parachains finality pallet only stores the best parachain head
+			use bp_westend::WESTMINT_PARACHAIN_ID;
+			let encoded_head = pallet_bridge_parachains::Pallet::<
+				Runtime,
+				WithWestendParachainsInstance,
+			>::best_parachain_head(WESTMINT_PARACHAIN_ID.into())?;
+			let head = encoded_head.decode_header::<bp_westend::Header>().ok()?;
+			Some(head.id()).filter(|id| id.0 == number)
+		}
+
+		fn accepts_free_header(_header: bp_westend::Header) -> bool {
+			// parachain heads are never imported for free
+			false
+		}
+
+		fn is_known_header(hash: bp_westend::Hash) -> bool {
+			use bp_westend::WESTMINT_PARACHAIN_ID;
+			pallet_bridge_parachains::Pallet::<
+				Runtime,
+				WithWestendParachainsInstance,
+			>::parachain_head(WESTMINT_PARACHAIN_ID.into(), hash)
+			.is_some()
+		}
+
+		fn stored_justification(_hash: bp_westend::Hash) -> Option<bp_runtime::EncodedJustification> {
+			// parachain heads are imported without justifications
+			None
+		}
 	}
 
 	impl bp_rialto_parachain::RialtoParachainFinalityApi<Block> for Runtime {
@@ -890,12 +1043,42 @@ impl_runtime_apis! {
 				Runtime,
 				WithRialtoParachainsInstance,
 			>::best_parachain_head(bp_rialto_parachain::RIALTO_PARACHAIN_ID.into())?;
-			let head = bp_rialto_parachain::Header::decode(&mut &encoded_head.0[..]).ok()?;
+			let head = encoded_head.decode_header::<bp_rialto_parachain::Header>().ok()?;
 			Some(head.id())
 		}
+
+		fn finalized_header(
+			number: bp_rialto::BlockNumber,
+		) -> Option<HeaderId<bp_rialto::Hash, bp_rialto::BlockNumber>> {
+			// the parachains finality pallet only stores the best parachain head
+			let encoded_head = pallet_bridge_parachains::Pallet::<
+				Runtime,
+				WithRialtoParachainsInstance,
+			>::best_parachain_head(bp_rialto_parachain::RIALTO_PARACHAIN_ID.into())?;
+			let head = encoded_head.decode_header::<bp_rialto_parachain::Header>().ok()?;
+			Some(head.id()).filter(|id| id.0 == number)
+		}
+
+		fn accepts_free_header(_header: bp_rialto_parachain::Header) -> bool {
+			// parachain heads are never imported for free
+			false
+		}
+
+		fn is_known_header(hash: bp_rialto::Hash) -> bool {
+			pallet_bridge_parachains::Pallet::<
+				Runtime,
+				WithRialtoParachainsInstance,
+			>::parachain_head(bp_rialto_parachain::RIALTO_PARACHAIN_ID.into(), hash)
+			.is_some()
+		}
+
+		fn stored_justification(_hash: bp_rialto::Hash) -> Option<bp_runtime::EncodedJustification> {
+			// parachain heads are imported without justifications
+			None
+		}
 	}
 
-	impl bp_rialto::ToRialtoOutboundLaneApi<Block, Balance, ToRialtoMessagePayload> for Runtime {
+	impl bp_rialto::ToRialtoOutboundLaneApi<Block, Balance, ToRialtoMessagePayload, AccountId> for Runtime {
 		fn estimate_message_delivery_and_dispatch_fee(
 			_lane_id: bp_messages::LaneId,
 			payload: ToRialtoMessagePayload,
@@ -916,11 +1099,26 @@ impl_runtime_apis! {
 			bridge_runtime_common::messages_api::outbound_message_details::<
 				Runtime,
 				WithRialtoMessagesInstance,
+				rialto_messages::WithRialtoMessageBridge,
 			>(lane, begin, end)
 		}
+
+		fn recent_relayers(
+			lane: bp_messages::LaneId,
+		) -> Vec<(AccountId, bp_messages::MessageNonce)> {
+			pallet_bridge_messages::Pallet::<Runtime, WithRialtoMessagesInstance>::recent_relayers(lane)
+		}
+
+		fn outbound_messages_allowance(sender: AccountId) -> Option<u32> {
+			pallet_bridge_messages::Pallet::<Runtime, WithRialtoMessagesInstance>::outbound_messages_allowance(sender)
+		}
+
+		fn outbound_lane_state(lane: bp_messages::LaneId) -> bp_messages::OutboundLaneData {
+			pallet_bridge_messages::Pallet::<Runtime, WithRialtoMessagesInstance>::outbound_lane_state(lane)
+		}
 	}
 
-	impl bp_rialto::FromRialtoInboundLaneApi<Block, bp_rialto::Balance> for Runtime {
+	impl bp_rialto::FromRialtoInboundLaneApi<Block, bp_rialto::Balance, bp_rialto::AccountId> for Runtime {
 		fn message_details(
 			lane: bp_messages::LaneId,
 			messages: Vec<(bp_messages::MessagePayload, bp_messages::OutboundMessageDetails<bp_rialto::Balance>)>,
@@ -930,9 +1128,19 @@ impl_runtime_apis! {
 				WithRialtoMessagesInstance,
 			>(lane, messages)
 		}
+
+		fn unrewarded_relayers_summary(
+			lane: bp_messages::LaneId,
+		) -> Vec<(bp_rialto::AccountId, bp_messages::MessageNonce)> {
+			pallet_bridge_messages::Pallet::<Runtime, WithRialtoMessagesInstance>::unrewarded_relayers_summary(lane)
+		}
+
+		fn inbound_lane_state(lane: bp_messages::LaneId) -> bp_messages::InboundLaneState {
+			pallet_bridge_messages::Pallet::<Runtime, WithRialtoMessagesInstance>::inbound_lane_state(lane)
+		}
 	}
 
-	impl bp_rialto_parachain::ToRialtoParachainOutboundLaneApi<Block, Balance, ToRialtoParachainMessagePayload> for Runtime {
+	impl bp_rialto_parachain::ToRialtoParachainOutboundLaneApi<Block, Balance, ToRialtoParachainMessagePayload, AccountId> for Runtime {
 		fn estimate_message_delivery_and_dispatch_fee(
 			_lane_id: bp_messages::LaneId,
 			payload: ToRialtoParachainMessagePayload,
@@ -953,11 +1161,26 @@ impl_runtime_apis! {
 			bridge_runtime_common::messages_api::outbound_message_details::<
 				Runtime,
 				WithRialtoParachainMessagesInstance,
+				rialto_parachain_messages::WithRialtoParachainMessageBridge,
 			>(lane, begin, end)
 		}
+
+		fn recent_relayers(
+			lane: bp_messages::LaneId,
+		) -> Vec<(AccountId, bp_messages::MessageNonce)> {
+			pallet_bridge_messages::Pallet::<Runtime, WithRialtoParachainMessagesInstance>::recent_relayers(lane)
+		}
+
+		fn outbound_messages_allowance(sender: AccountId) -> Option<u32> {
+			pallet_bridge_messages::Pallet::<Runtime, WithRialtoParachainMessagesInstance>::outbound_messages_allowance(sender)
+		}
+
+		fn outbound_lane_state(lane: bp_messages::LaneId) -> bp_messages::OutboundLaneData {
+			pallet_bridge_messages::Pallet::<Runtime, WithRialtoParachainMessagesInstance>::outbound_lane_state(lane)
+		}
 	}
 
-	impl bp_rialto_parachain::FromRialtoParachainInboundLaneApi<Block, bp_rialto_parachain::Balance> for Runtime {
+	impl bp_rialto_parachain::FromRialtoParachainInboundLaneApi<Block, bp_rialto_parachain::Balance, bp_rialto_parachain::AccountId> for Runtime {
 		fn message_details(
 			lane: bp_messages::LaneId,
 			messages: Vec<(bp_messages::MessagePayload, bp_messages::OutboundMessageDetails<bp_rialto_parachain::Balance>)>,
@@ -967,6 +1190,16 @@ impl_runtime_apis! {
 				WithRialtoParachainMessagesInstance,
 			>(lane, messages)
 		}
+
+		fn unrewarded_relayers_summary(
+			lane: bp_messages::LaneId,
+		) -> Vec<(bp_rialto_parachain::AccountId, bp_messages::MessageNonce)> {
+			pallet_bridge_messages::Pallet::<Runtime, WithRialtoParachainMessagesInstance>::unrewarded_relayers_summary(lane)
+		}
+
+		fn inbound_lane_state(lane: bp_messages::LaneId) -> bp_messages::InboundLaneState {
+			pallet_bridge_messages::Pallet::<Runtime, WithRialtoParachainMessagesInstance>::inbound_lane_state(lane)
+		}
 	}
 
 	#[cfg(feature = "runtime-benchmarks")]
@@ -1133,4 +1366,49 @@ mod tests {
 		const MAX_CALL_SIZE: usize = 230; // value from polkadot-runtime tests
 		assert!(core::mem::size_of::<Call>() <= MAX_CALL_SIZE);
 	}
+
+	#[test]
+	fn ensure_chain_primitives_constants_are_correct() {
+		bridge_runtime_common::integrity::assert_transaction_extra_bytes(
+			UncheckedExtrinsic::new_signed(
+				Call::System(SystemCall::remark { remark: vec![] }),
+				AccountId::from([0u8; 32]).into(),
+				sp_core::sr25519::Signature::from_raw([0u8; 64]).into(),
+				(
+					frame_system::CheckNonZeroSender::new(),
+					frame_system::CheckSpecVersion::new(),
+					frame_system::CheckTxVersion::new(),
+					frame_system::CheckGenesis::new(),
+					frame_system::CheckEra::from(sp_runtime::generic::Era::Immortal),
+					frame_system::CheckNonce::from(0),
+					frame_system::CheckWeight::new(),
+					pallet_transaction_payment::ChargeTransactionPayment::from(0),
+					BridgeRejectObsoleteHeadersAndMessages,
+				),
+			),
+			bp_millau::TX_EXTRA_BYTES,
+		);
+
+		bridge_runtime_common::integrity::assert_inbound_lane_limits::<
+			Runtime,
+			WithRialtoMessagesInstance,
+		>(bridge_runtime_common::integrity::AssertInboundLaneLimits {
+			max_unrewarded_relayers_in_confirmation_tx:
+				bp_rialto::MAX_UNREWARDED_RELAYERS_IN_CONFIRMATION_TX,
+			max_unconfirmed_messages_in_confirmation_tx:
+				bp_rialto::MAX_UNCONFIRMED_MESSAGES_IN_CONFIRMATION_TX,
+		});
+
+		bridge_runtime_common::integrity::assert_inbound_lane_limits::<
+			Runtime,
+			WithRialtoParachainMessagesInstance,
+		>(bridge_runtime_common::integrity::AssertInboundLaneLimits {
+			max_unrewarded_relayers_in_confirmation_tx:
+				bp_rialto::MAX_UNREWARDED_RELAYERS_IN_CONFIRMATION_TX,
+			max_unconfirmed_messages_in_confirmation_tx:
+				bp_rialto::MAX_UNCONFIRMED_MESSAGES_IN_CONFIRMATION_TX,
+		});
+
+		assert_eq!(Period::get(), bp_millau::SESSION_LENGTH);
+	}
 }
@@ -22,8 +22,9 @@ use bp_messages::{
 	source_chain::{SenderOrigin, TargetHeaderChain},
 	target_chain::{ProvedMessages, SourceHeaderChain},
 	InboundLaneData, LaneId, Message, MessageNonce, Parameter as MessagesParameter,
+	VerificationError,
 };
-use bp_runtime::{Chain, ChainId, MILLAU_CHAIN_ID, RIALTO_CHAIN_ID};
+use bp_runtime::{messages::DispatchFeePayment, Chain, ChainId, MILLAU_CHAIN_ID, RIALTO_CHAIN_ID};
 use bridge_runtime_common::messages::{
 	self, BasicConfirmationTransactionEstimation, MessageBridge, MessageTransaction,
 };
@@ -38,7 +39,7 @@ use sp_runtime::{traits::Saturating, FixedPointNumber, FixedU128};
 use sp_std::convert::TryFrom;
 
 /// Default lane that is used to send messages to Rialto.
-pub const DEFAULT_XCM_LANE_TO_RIALTO: LaneId = [0, 0, 0, 0];
+pub const DEFAULT_XCM_LANE_TO_RIALTO: LaneId = LaneId([0, 0, 0, 0]);
 /// Initial value of `RialtoToMillauConversionRate` parameter.
 pub const INITIAL_RIALTO_TO_MILLAU_CONVERSION_RATE: FixedU128 =
 	FixedU128::from_inner(FixedU128::DIV);
@@ -54,6 +55,19 @@ parameter_types! {
 	pub storage RialtoToMillauConversionRate: FixedU128 = INITIAL_RIALTO_TO_MILLAU_CONVERSION_RATE;
 	/// Fee multiplier value at Rialto chain.
 	pub storage RialtoFeeMultiplier: FixedU128 = INITIAL_RIALTO_FEE_MULTIPLIER;
+	/// Maximal size of Rialto -> Millau messages proof.
+	pub FromRialtoMaximalMessagesProofSize: u32 =
+		messages::target::maximal_messages_proof_size(
+			bp_millau::Millau::max_extrinsic_size(),
+			bp_rialto::EXTRA_STORAGE_PROOF_SIZE,
+		);
+	/// Maximal size of Millau -> Rialto messages delivery proof.
+	pub ToRialtoMaximalMessagesDeliveryProofSize: u32 =
+		messages::source::maximal_messages_delivery_proof_size::<bp_millau::AccountId>(
+			bp_rialto::EXTRA_STORAGE_PROOF_SIZE,
+			bp_rialto::MAX_UNREWARDED_RELAYERS_IN_CONFIRMATION_TX,
+			bp_rialto::MAX_UNCONFIRMED_MESSAGES_IN_CONFIRMATION_TX,
+		);
 }
 
 /// Message payload for Millau -> Rialto messages.
@@ -66,6 +80,10 @@ pub type ToRialtoMessageVerifier =
 /// Message payload for Rialto -> Millau messages.
 pub type FromRialtoMessagePayload = messages::target::FromBridgedChainMessagePayload<Call>;
 
+/// Default maximal inbound payload size of Rialto -> Millau messages.
+pub type FromRialtoMaximalInboundPayloadSize =
+	messages::target::FromBridgedChainMaximalInboundPayloadSize<bp_millau::Millau>;
+
 /// Messages proof for Rialto -> Millau messages.
 pub type FromRialtoMessagesProof = messages::target::FromBridgedChainMessagesProof<bp_rialto::Hash>;
 
@@ -96,6 +114,7 @@ impl MessageBridge for WithRialtoMessageBridge {
 	const THIS_CHAIN_ID: ChainId = MILLAU_CHAIN_ID;
 	const BRIDGED_CHAIN_ID: ChainId = RIALTO_CHAIN_ID;
 	const BRIDGED_MESSAGES_PALLET_NAME: &'static str = bp_millau::WITH_MILLAU_MESSAGES_PALLET_NAME;
+	const DISPATCH_FEE_PAYMENT: DispatchFeePayment = DispatchFeePayment::AtTargetChain;
 
 	type ThisChain = Millau;
 	type BridgedChain = Rialto;
@@ -151,7 +170,7 @@ impl messages::ThisChainWithMessages for Millau {
 			},
 		}
 
-		*lane == DEFAULT_XCM_LANE_TO_RIALTO || *lane == [0, 0, 0, 1]
+		*lane == DEFAULT_XCM_LANE_TO_RIALTO || *lane == LaneId([0, 0, 0, 1])
 	}
 
 	fn maximal_pending_messages_at_outbound_lane() -> MessageNonce {
@@ -237,7 +256,7 @@ impl messages::BridgedChainWithMessages for Rialto {
 }
 
 impl TargetHeaderChain<ToRialtoMessagePayload, bp_millau::AccountId> for Rialto {
-	type Error = &'static str;
+	type Error = VerificationError;
 	// The proof is:
 	// - hash of the header this proof has been created with;
 	// - the storage proof or one or several keys;
@@ -260,7 +279,7 @@ impl TargetHeaderChain<ToRialtoMessagePayload, bp_millau::AccountId> for Rialto
 }
 
 impl SourceHeaderChain<bp_rialto::Balance> for Rialto {
-	type Error = &'static str;
+	type Error = VerificationError;
 	// The proof is:
 	// - hash of the header this proof has been created with;
 	// - the storage proof or one or several keys;
@@ -308,6 +327,7 @@ mod tests {
 	use super::*;
 	use crate::{DbWeight, RialtoGrandpaInstance, Runtime, WithRialtoMessagesInstance};
 
+	use bp_messages::{MessageData, MessageKey};
 	use bp_runtime::Chain;
 	use bridge_runtime_common::{
 		assert_complete_bridge_types,
@@ -315,9 +335,81 @@ mod tests {
 			assert_complete_bridge_constants, AssertBridgeMessagesPalletConstants,
 			AssertBridgePalletNames, AssertChainConstants, AssertCompleteBridgeConstants,
 		},
-		messages,
+		messages, messages_api,
 	};
 
+	/// Same as `WithRialtoMessageBridge`, but dispatch fee is paid at the source chain.
+	#[derive(RuntimeDebug, Clone, Copy)]
+	struct WithRialtoPrepaidMessageBridge;
+
+	impl MessageBridge for WithRialtoPrepaidMessageBridge {
+		const RELAYER_FEE_PERCENT: u32 = WithRialtoMessageBridge::RELAYER_FEE_PERCENT;
+		const THIS_CHAIN_ID: ChainId = WithRialtoMessageBridge::THIS_CHAIN_ID;
+		const BRIDGED_CHAIN_ID: ChainId = WithRialtoMessageBridge::BRIDGED_CHAIN_ID;
+		const BRIDGED_MESSAGES_PALLET_NAME: &'static str =
+			WithRialtoMessageBridge::BRIDGED_MESSAGES_PALLET_NAME;
+		const DISPATCH_FEE_PAYMENT: DispatchFeePayment = DispatchFeePayment::AtSourceChain;
+
+		type ThisChain = Millau;
+		type BridgedChain = Rialto;
+
+		fn bridged_balance_to_this_balance(
+			bridged_balance: bp_rialto::Balance,
+			bridged_to_this_conversion_rate_override: Option<FixedU128>,
+		) -> bp_millau::Balance {
+			WithRialtoMessageBridge::bridged_balance_to_this_balance(
+				bridged_balance,
+				bridged_to_this_conversion_rate_override,
+			)
+		}
+	}
+
+	fn outbound_message_details<Bridge: MessageBridge>(
+	) -> Vec<bp_messages::OutboundMessageDetails<bp_millau::Balance>> {
+		sp_io::TestExternalities::default().execute_with(|| {
+			pallet_bridge_messages::OutboundMessages::<Runtime, WithRialtoMessagesInstance>::insert(
+				MessageKey { lane_id: LaneId::default(), nonce: 1 },
+				MessageData { payload: vec![42; 16], fee: 100 },
+			);
+
+			messages_api::outbound_message_details::<Runtime, WithRialtoMessagesInstance, Bridge>(
+				LaneId::default(),
+				1,
+				1,
+			)
+		})
+	}
+
+	#[test]
+	fn outbound_message_details_reports_dispatch_fee_paid_at_target_chain() {
+		assert_eq!(
+			outbound_message_details::<WithRialtoMessageBridge>(),
+			vec![bp_messages::OutboundMessageDetails {
+				nonce: 1,
+				dispatch_weight: 0,
+				size: 16,
+				delivery_and_dispatch_fee: 100,
+				dispatch_fee_payment: DispatchFeePayment::AtTargetChain,
+				submitter: None,
+			}],
+		);
+	}
+
+	#[test]
+	fn outbound_message_details_reports_dispatch_fee_paid_at_source_chain() {
+		assert_eq!(
+			outbound_message_details::<WithRialtoPrepaidMessageBridge>(),
+			vec![bp_messages::OutboundMessageDetails {
+				nonce: 1,
+				dispatch_weight: 0,
+				size: 16,
+				delivery_and_dispatch_fee: 100,
+				dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+				submitter: None,
+			}],
+		);
+	}
+
 	#[test]
 	fn ensure_millau_message_lane_weights_are_correct() {
 		type Weights = pallet_bridge_messages::weights::BridgeWeight<Runtime>;
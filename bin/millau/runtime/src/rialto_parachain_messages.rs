@@ -22,9 +22,12 @@ use bp_messages::{
 	source_chain::TargetHeaderChain,
 	target_chain::{ProvedMessages, SourceHeaderChain},
 	InboundLaneData, LaneId, Message, MessageNonce, Parameter as MessagesParameter,
+	VerificationError,
 };
 use bp_polkadot_core::parachains::ParaId;
-use bp_runtime::{Chain, ChainId, MILLAU_CHAIN_ID, RIALTO_PARACHAIN_CHAIN_ID};
+use bp_runtime::{
+	messages::DispatchFeePayment, Chain, ChainId, MILLAU_CHAIN_ID, RIALTO_PARACHAIN_CHAIN_ID,
+};
 use bridge_runtime_common::messages::{
 	self, BasicConfirmationTransactionEstimation, MessageBridge, MessageTransaction,
 };
@@ -39,7 +42,7 @@ use sp_runtime::{traits::Saturating, FixedPointNumber, FixedU128};
 use sp_std::convert::TryFrom;
 
 /// Default lane that is used to send messages to Rialto parachain.
-pub const DEFAULT_XCM_LANE_TO_RIALTO_PARACHAIN: LaneId = [0, 0, 0, 0];
+pub const DEFAULT_XCM_LANE_TO_RIALTO_PARACHAIN: LaneId = LaneId([0, 0, 0, 0]);
 /// Weight of 2 XCM instructions is for simple `Trap(42)` program, coming through bridge
 /// (it is prepended with `UniversalOrigin` instruction). It is used just for simplest manual
 /// tests, confirming that we don't break encoding somewhere between.
@@ -57,6 +60,19 @@ parameter_types! {
 	pub storage RialtoParachainToMillauConversionRate: FixedU128 = INITIAL_RIALTO_PARACHAIN_TO_MILLAU_CONVERSION_RATE;
 	/// Fee multiplier value at RialtoParachain chain.
 	pub storage RialtoParachainFeeMultiplier: FixedU128 = INITIAL_RIALTO_PARACHAIN_FEE_MULTIPLIER;
+	/// Maximal size of RialtoParachain -> Millau messages proof.
+	pub FromRialtoParachainMaximalMessagesProofSize: u32 =
+		messages::target::maximal_messages_proof_size(
+			bp_millau::Millau::max_extrinsic_size(),
+			bp_rialto_parachain::EXTRA_STORAGE_PROOF_SIZE,
+		);
+	/// Maximal size of Millau -> RialtoParachain messages delivery proof.
+	pub ToRialtoParachainMaximalMessagesDeliveryProofSize: u32 =
+		messages::source::maximal_messages_delivery_proof_size::<bp_millau::AccountId>(
+			bp_rialto_parachain::EXTRA_STORAGE_PROOF_SIZE,
+			bp_rialto_parachain::MAX_UNREWARDED_RELAYERS_IN_CONFIRMATION_TX,
+			bp_rialto_parachain::MAX_UNCONFIRMED_MESSAGES_IN_CONFIRMATION_TX,
+		);
 }
 
 /// Message payload for Millau -> RialtoParachain messages.
@@ -70,6 +86,10 @@ pub type ToRialtoParachainMessageVerifier =
 pub type FromRialtoParachainMessagePayload =
 	messages::target::FromBridgedChainMessagePayload<crate::Call>;
 
+/// Default maximal inbound payload size of RialtoParachain -> Millau messages.
+pub type FromRialtoParachainMaximalInboundPayloadSize =
+	messages::target::FromBridgedChainMaximalInboundPayloadSize<bp_millau::Millau>;
+
 /// Messages proof for RialtoParachain -> Millau messages.
 type FromRialtoParachainMessagesProof =
 	messages::target::FromBridgedChainMessagesProof<bp_rialto_parachain::Hash>;
@@ -101,6 +121,7 @@ impl MessageBridge for WithRialtoParachainMessageBridge {
 	const THIS_CHAIN_ID: ChainId = MILLAU_CHAIN_ID;
 	const BRIDGED_CHAIN_ID: ChainId = RIALTO_PARACHAIN_CHAIN_ID;
 	const BRIDGED_MESSAGES_PALLET_NAME: &'static str = bp_millau::WITH_MILLAU_MESSAGES_PALLET_NAME;
+	const DISPATCH_FEE_PAYMENT: DispatchFeePayment = DispatchFeePayment::AtTargetChain;
 
 	type ThisChain = Millau;
 	type BridgedChain = RialtoParachain;
@@ -140,7 +161,7 @@ impl messages::ThisChainWithMessages for Millau {
 	>;
 
 	fn is_message_accepted(_send_origin: &Self::Origin, lane: &LaneId) -> bool {
-		*lane == DEFAULT_XCM_LANE_TO_RIALTO_PARACHAIN || *lane == [0, 0, 0, 1]
+		*lane == DEFAULT_XCM_LANE_TO_RIALTO_PARACHAIN || *lane == LaneId([0, 0, 0, 1])
 	}
 
 	fn maximal_pending_messages_at_outbound_lane() -> MessageNonce {
@@ -230,7 +251,7 @@ impl messages::BridgedChainWithMessages for RialtoParachain {
 }
 
 impl TargetHeaderChain<ToRialtoParachainMessagePayload, bp_millau::AccountId> for RialtoParachain {
-	type Error = &'static str;
+	type Error = VerificationError;
 	// The proof is:
 	// - hash of the header this proof has been created with;
 	// - the storage proof or one or several keys;
@@ -254,7 +275,7 @@ impl TargetHeaderChain<ToRialtoParachainMessagePayload, bp_millau::AccountId> fo
 }
 
 impl SourceHeaderChain<bp_rialto_parachain::Balance> for RialtoParachain {
-	type Error = &'static str;
+	type Error = VerificationError;
 	// The proof is:
 	// - hash of the header this proof has been created with;
 	// - the storage proof or one or several keys;
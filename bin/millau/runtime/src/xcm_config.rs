@@ -254,24 +254,31 @@ impl XcmBridge for ToRialtoParachainBridge {
 mod tests {
 	use super::*;
 	use bp_messages::{
-		target_chain::{DispatchMessage, DispatchMessageData, MessageDispatch},
-		MessageKey,
+		target_chain::{DispatchMessage, MessageDispatch},
+		MessageData, MessageKey,
 	};
-	use bp_runtime::messages::MessageDispatchResult;
+	use bp_runtime::messages::{MessageDispatchError, MessageDispatchResult};
 	use bridge_runtime_common::messages::target::FromBridgedChainMessageDispatch;
 	use codec::Encode;
+	use frame_support::traits::Get;
+	use xcm::VersionedXcm;
 
 	fn new_test_ext() -> sp_io::TestExternalities {
-		sp_io::TestExternalities::new(
+		let mut ext = sp_io::TestExternalities::new(
 			frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap(),
-		)
+		);
+		ext.execute_with(|| {
+			pallet_bridge_messages::OpenedLanes::<Runtime, crate::WithRialtoMessagesInstance>::insert(DEFAULT_XCM_LANE_TO_RIALTO, ());
+			pallet_bridge_messages::OpenedLanes::<Runtime, crate::WithRialtoParachainMessagesInstance>::insert(DEFAULT_XCM_LANE_TO_RIALTO_PARACHAIN, ());
+		});
+		ext
 	}
 
 	#[test]
 	fn xcm_messages_are_sent_using_bridge_router() {
 		new_test_ext().execute_with(|| {
 			let xcm: Xcm<()> = vec![Instruction::Trap(42)].into();
-			let expected_fee = MultiAssets::from((Here, 4_259_858_152_u64));
+			let expected_fee = MultiAssets::from((Here, 4_259_858_153_u64));
 			let expected_hash =
 				([0u8, 0u8, 0u8, 0u8], 1u64).using_encoded(sp_io::hashing::blake2_256);
 
@@ -288,6 +295,29 @@ mod tests {
 		})
 	}
 
+	#[test]
+	fn xcm_messages_are_rejected_while_lane_queue_is_full() {
+		new_test_ext().execute_with(|| {
+			let xcm: Xcm<()> = vec![Instruction::Trap(42)].into();
+			let dest: MultiLocation = (Parent, X1(GlobalConsensus(RialtoNetwork::get()))).into();
+
+			// lane has `MaxOutboundQueueLen` undelivered messages
+			pallet_bridge_messages::OutboundLanes::<Runtime, WithRialtoMessagesInstance>::mutate(
+				DEFAULT_XCM_LANE_TO_RIALTO,
+				|data| data.latest_generated_nonce = crate::MaxOutboundQueueLen::get(),
+			);
+			let send_result = send_xcm::<XcmRouter>(dest.clone(), xcm.clone());
+			assert_eq!(send_result, Err(SendError::Transport("Bridge has rejected the message")));
+
+			// delivery of the first message is confirmed => there's room for new message
+			pallet_bridge_messages::OutboundLanes::<Runtime, WithRialtoMessagesInstance>::mutate(
+				DEFAULT_XCM_LANE_TO_RIALTO,
+				|data| data.latest_received_nonce = 1,
+			);
+			assert!(send_xcm::<XcmRouter>(dest, xcm).is_ok());
+		})
+	}
+
 	#[test]
 	fn xcm_messages_from_rialto_are_dispatched() {
 		type XcmExecutor = xcm_executor::XcmExecutor<XcmConfig>;
@@ -304,8 +334,12 @@ mod tests {
 			let xcm: Xcm<Call> = vec![Instruction::Trap(42)].into();
 
 			let mut incoming_message = DispatchMessage {
-				key: MessageKey { lane_id: [0, 0, 0, 0], nonce: 1 },
-				data: DispatchMessageData { payload: Ok((location, xcm).into()), fee: 0 },
+				key: MessageKey { lane_id: LaneId([0, 0, 0, 0]), nonce: 1 },
+				data: MessageData {
+					payload: (location, VersionedXcm::from(xcm)).encode().encode(),
+					fee: 0,
+				}
+				.into(),
 			};
 
 			let dispatch_weight = MessageDispatcher::dispatch_weight(&mut incoming_message);
@@ -319,6 +353,7 @@ mod tests {
 					dispatch_result: true,
 					unspent_weight: 0,
 					dispatch_fee_paid_during_dispatch: false,
+					dispatch_error: Some(MessageDispatchError::Trapped(42)),
 				}
 			);
 		})
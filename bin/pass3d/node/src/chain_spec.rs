@@ -278,6 +278,7 @@ fn testnet_genesis(
 		paras: Default::default(),
 		bridge_pass3dt_messages: BridgePass3dtMessagesConfig {
 			owner: Some(get_account_id_from_seed::<sr25519::Public>(PASS3DT_MESSAGES_PALLET_OWNER)),
+			opened_lanes: vec![bp_pass3d::XCM_LANE],
 			..Default::default()
 		},
 		xcm_pallet: Default::default(),
@@ -24,6 +24,13 @@ pub struct Cli {
 
 	#[structopt(flatten)]
 	pub run: RunCmd,
+
+	/// Port of the `bridge_messageHistory` RPC server. If not specified, the server is not
+	/// started.
+	///
+	/// The history is only available if node is started with `--enable-offchain-indexing true`.
+	#[structopt(long)]
+	pub messages_history_rpc_port: Option<u16>,
 }
 
 /// Possible subcommands of the main binary.
@@ -185,6 +185,7 @@ pub fn run() -> sc_cli::Result<()> {
 			// let telemetry_worker_handler = None;
 			// let is_collator = crate::service::IsCollator::No;
 			let overseer_gen = polkadot_service::overseer::RealOverseerGen;
+			let messages_history_rpc_port = cli.messages_history_rpc_port;
 			runner.run_node_until_exit(|config| async move {
 				let is_collator = polkadot_service::IsCollator::No;
 				let grandpa_pause = None;
@@ -194,22 +195,33 @@ pub fn run() -> sc_cli::Result<()> {
 				let program_path = None;
 				let overseer_enable_anyways = false;
 
-				polkadot_service::new_full::<pass3d_runtime::RuntimeApi, ExecutorDispatch, _>(
-					config,
-					is_collator,
-					grandpa_pause,
-					enable_beefy,
-					jaeger_agent,
-					telemetry_worker_handle,
-					program_path,
-					overseer_enable_anyways,
-					overseer_gen,
-					None,
-					None,
-					None,
-				)
-				.map(|full| full.task_manager)
-				.map_err(service_error)
+				let full =
+					polkadot_service::new_full::<pass3d_runtime::RuntimeApi, ExecutorDispatch, _>(
+						config,
+						is_collator,
+						grandpa_pause,
+						enable_beefy,
+						jaeger_agent,
+						telemetry_worker_handle,
+						program_path,
+						overseer_enable_anyways,
+						overseer_gen,
+						None,
+						None,
+						None,
+					)
+					.map_err(service_error)?;
+
+				if let Some(port) = messages_history_rpc_port {
+					crate::rpc::start_server::<polkadot_service::Block, _>(
+						&full.task_manager,
+						full.backend.clone(),
+						port,
+					)
+					.await?;
+				}
+
+				Ok::<_, sc_cli::Error>(full.task_manager)
 			})
 		},
 	}
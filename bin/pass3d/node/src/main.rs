@@ -21,6 +21,7 @@
 mod chain_spec;
 mod cli;
 mod command;
+mod rpc;
 
 /// Run the Pass3d Node
 fn main() -> sc_cli::Result<()> {
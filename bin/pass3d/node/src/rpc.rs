@@ -0,0 +1,175 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Bridge messages history RPC.
+//!
+//! The messages pallet records accepted, delivered and confirmed messages in the offchain index
+//! (see `pallet_bridge_messages::Config::IndexMessagesHistory`). This RPC reads the index, so
+//! explorers don't need to scan every block to find messages of given lane. Entries are only
+//! available if the node is started with `--enable-offchain-indexing true`.
+//!
+//! Our node is using `polkadot_service`, which doesn't support custom RPC extensions. So the
+//! RPC is served by a separate server, started on a dedicated port.
+
+use bp_messages::{
+	storage_keys::message_history_index_key, LaneId, MessageEventKind, MessageNonce,
+};
+use codec::Decode;
+use jsonrpsee::{
+	core::{Error as JsonRpseeError, RpcResult},
+	proc_macros::rpc,
+	types::error::{CallError, ErrorCode, ErrorObject},
+	ws_server::WsServerBuilder,
+};
+use pass3d_runtime::BlockNumber;
+use sc_client_api::Backend;
+use sc_service::TaskManager;
+use serde::{Deserialize, Serialize};
+use sp_core::offchain::{OffchainStorage, STORAGE_PREFIX};
+use sp_runtime::traits::Block as BlockT;
+use std::{marker::PhantomData, net::SocketAddr, sync::Arc};
+
+/// Maximal number of nonces that may be requested by single `bridge_messageHistory` call.
+pub const MAX_NONCES_PER_REQUEST: MessageNonce = 1024;
+
+/// Single event of the messages history.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageHistoryEntry {
+	/// Message nonce.
+	pub nonce: MessageNonce,
+	/// Kind of the event.
+	pub kind: MessageEventKind,
+	/// Number of the block, where the event has happened.
+	pub block_number: BlockNumber,
+}
+
+/// Bridge messages history RPC API.
+#[rpc(server)]
+pub trait MessagesHistoryApi {
+	/// Returns all known events of messages in the `from_nonce..=to_nonce` range of given lane.
+	///
+	/// Lane is a hex-encoded lane id (e.g. `0x00000000`). Events of every message are ordered
+	/// by their kind: `Accepted`, `Delivered`, `Confirmed`.
+	#[method(name = "bridge_messageHistory")]
+	fn message_history(
+		&self,
+		lane: String,
+		from_nonce: MessageNonce,
+		to_nonce: MessageNonce,
+	) -> RpcResult<Vec<MessageHistoryEntry>>;
+}
+
+/// Implements the `MessagesHistoryApi` RPC trait by reading the offchain index.
+pub struct MessagesHistory<Block, B> {
+	backend: Arc<B>,
+	_phantom: PhantomData<Block>,
+}
+
+impl<Block, B> MessagesHistory<Block, B> {
+	/// Create new `MessagesHistory` with the given reference to the client backend.
+	pub fn new(backend: Arc<B>) -> Self {
+		MessagesHistory { backend, _phantom: PhantomData }
+	}
+}
+
+impl<Block, B> MessagesHistoryApiServer for MessagesHistory<Block, B>
+where
+	Block: BlockT,
+	B: Backend<Block> + Send + Sync + 'static,
+{
+	fn message_history(
+		&self,
+		lane: String,
+		from_nonce: MessageNonce,
+		to_nonce: MessageNonce,
+	) -> RpcResult<Vec<MessageHistoryEntry>> {
+		let lane: LaneId = lane
+			.parse()
+			.map_err(|e| rpc_error(ErrorCode::InvalidParams, format!("Invalid lane id: {}", e)))?;
+		if from_nonce > to_nonce || to_nonce - from_nonce >= MAX_NONCES_PER_REQUEST {
+			return Err(rpc_error(
+				ErrorCode::InvalidParams,
+				format!(
+					"Invalid nonces range: {}..={}. At most {} nonces may be requested at once",
+					from_nonce, to_nonce, MAX_NONCES_PER_REQUEST,
+				),
+			))
+		}
+
+		let storage = self.backend.offchain_storage().ok_or_else(|| {
+			rpc_error(ErrorCode::InternalError, "Offchain storage is not available")
+		})?;
+		let mut entries = Vec::new();
+		for nonce in from_nonce..=to_nonce {
+			for kind in MessageEventKind::ALL {
+				let key = message_history_index_key(
+					bp_pass3dt::WITH_PASS3DT_MESSAGES_PALLET_NAME,
+					&lane,
+					nonce,
+					kind,
+				);
+				let value = match storage.get(STORAGE_PREFIX, &key) {
+					Some(value) => value,
+					None => continue,
+				};
+				let (block_number, kind) =
+					<(BlockNumber, MessageEventKind)>::decode(&mut &value[..]).map_err(|e| {
+						rpc_error(
+							ErrorCode::InternalError,
+							format!("Failed to decode messages history entry: {}", e),
+						)
+					})?;
+				entries.push(MessageHistoryEntry { nonce, kind, block_number });
+			}
+		}
+
+		Ok(entries)
+	}
+}
+
+/// Start the messages history RPC server at given port of the local interface.
+pub async fn start_server<Block, B>(
+	task_manager: &TaskManager,
+	backend: Arc<B>,
+	port: u16,
+) -> Result<(), sc_service::Error>
+where
+	Block: BlockT,
+	B: Backend<Block> + Send + Sync + 'static,
+{
+	let map_err = |e| sc_service::Error::Other(format!("{}", e));
+	let address = SocketAddr::from(([127, 0, 0, 1], port));
+	let server = WsServerBuilder::default().build(address).await.map_err(map_err)?;
+	let handle = server
+		.start(MessagesHistory::<Block, B>::new(backend).into_rpc())
+		.map_err(map_err)?;
+	log::info!(target: "bridge", "Messages history RPC server is listening on {}", address);
+
+	// keep the server handle alive until the node is stopped
+	task_manager
+		.spawn_handle()
+		.spawn("bridge-messages-history-rpc", None, async move {
+			let _handle = handle;
+			futures::future::pending::<()>().await
+		});
+
+	Ok(())
+}
+
+fn rpc_error(code: ErrorCode, message: impl Into<String>) -> JsonRpseeError {
+	CallError::Custom(ErrorObject::owned(code.code(), message.into(), None::<()>)).into()
+}
@@ -35,8 +35,10 @@ use crate::pass3dt_messages::{ToPass3dtMessagePayload, WithPass3dtMessageBridge}
 
 use beefy_primitives::{crypto::AuthorityId as BeefyId, mmr::MmrLeafVersion, ValidatorSet};
 use bp_runtime::{HeaderId, HeaderIdProvider};
-use bridge_runtime_common::messages::{
-	source::estimate_message_dispatch_and_delivery_fee, MessageBridge,
+use bridge_runtime_common::{
+	generate_bridge_reject_obsolete_headers_and_messages,
+	messages::{source::estimate_message_dispatch_and_delivery_fee, MessageBridge},
+	LookThroughProxy,
 };
 use pallet_grandpa::{
 	fg_primitives, AuthorityId as GrandpaId, AuthorityList as GrandpaAuthorityList,
@@ -71,6 +73,7 @@ pub use frame_system::Call as SystemCall;
 pub use pallet_balances::Call as BalancesCall;
 pub use pallet_bridge_grandpa::Call as BridgeGrandpaCall;
 pub use pallet_bridge_messages::Call as MessagesCall;
+pub use pallet_proxy::Call as ProxyCall;
 pub use pallet_sudo::Call as SudoCall;
 pub use pallet_timestamp::Call as TimestampCall;
 pub use pallet_xcm::Call as XcmCall;
@@ -158,7 +161,7 @@ parameter_types! {
 		read: 60_000_000, // ~0.06 ms = ~60 µs
 		write: 200_000_000, // ~0.2 ms = 200 µs
 	};
-	pub const SS58Prefix: u8 = 48;
+	pub const SS58Prefix: u16 = bp_pass3d::SS58_PREFIX;
 }
 
 impl frame_system::Config for Runtime {
@@ -379,6 +382,76 @@ impl pallet_sudo::Config for Runtime {
 	type Call = Call;
 }
 
+/// The type used to represent the kinds of proxying allowed.
+#[derive(
+	Copy,
+	Clone,
+	Eq,
+	PartialEq,
+	Ord,
+	PartialOrd,
+	codec::Encode,
+	codec::Decode,
+	codec::MaxEncodedLen,
+	frame_support::RuntimeDebug,
+	scale_info::TypeInfo,
+)]
+pub enum ProxyType {
+	/// All calls may be made.
+	Any,
+	/// All calls, except balances transfers, may be made. This is the proxy type that is
+	/// suggested for relayer accounts.
+	NonTransfer,
+}
+
+impl Default for ProxyType {
+	fn default() -> Self {
+		ProxyType::Any
+	}
+}
+
+impl frame_support::traits::InstanceFilter<Call> for ProxyType {
+	fn filter(&self, call: &Call) -> bool {
+		match self {
+			ProxyType::Any => true,
+			ProxyType::NonTransfer => !matches!(call, Call::Balances(..)),
+		}
+	}
+
+	fn is_superset(&self, other: &Self) -> bool {
+		match (self, other) {
+			(ProxyType::Any, _) => true,
+			(_, ProxyType::Any) => false,
+			(ProxyType::NonTransfer, ProxyType::NonTransfer) => true,
+		}
+	}
+}
+
+parameter_types! {
+	pub const ProxyDepositBase: Balance = 1_000;
+	pub const ProxyDepositFactor: Balance = 100;
+	pub const MaxProxies: u16 = 32;
+	pub const AnnouncementDepositBase: Balance = 1_000;
+	pub const AnnouncementDepositFactor: Balance = 200;
+	pub const MaxPending: u16 = 32;
+}
+
+impl pallet_proxy::Config for Runtime {
+	type Event = Event;
+	type Call = Call;
+	type Currency = Balances;
+	type ProxyType = ProxyType;
+	type ProxyDepositBase = ProxyDepositBase;
+	type ProxyDepositFactor = ProxyDepositFactor;
+	type MaxProxies = MaxProxies;
+	// TODO: update me (https://github.com/paritytech/parity-bridges-common/issues/78)
+	type WeightInfo = ();
+	type MaxPending = MaxPending;
+	type CallHasher = Hashing;
+	type AnnouncementDepositBase = AnnouncementDepositBase;
+	type AnnouncementDepositFactor = AnnouncementDepositFactor;
+}
+
 impl pallet_session::Config for Runtime {
 	type Event = Event;
 	type ValidatorId = <Self as frame_system::Config>::AccountId;
@@ -410,29 +483,59 @@ parameter_types! {
 	/// call per block.
 	pub const MaxRequests: u32 = 50;
 
+	/// Quarter of the block weight may be spent on importing bridged headers.
+	pub const MaxRequestsWeight: Weight = bp_pass3d::MAXIMUM_BLOCK_WEIGHT / 4;
+
 	/// Number of headers to keep.
 	///
 	/// Assuming the worst case of every header being finalized, we will keep headers at least for a
 	/// week.
 	pub const HeadersToKeep: u32 = 7 * bp_pass3d::DAYS as u32;
+
+	/// Number of justifications of the most recent headers to keep.
+	///
+	/// Assuming the worst case of every header being finalized, we will keep justifications
+	/// for at least an hour.
+	pub const JustificationsToKeep: u32 = bp_pass3d::HOURS as u32;
+	/// Maximal number of finality proofs in the single `submit_finality_proof_batch` call.
+	pub const MaxFinalityProofsInBatch: u32 = 16;
 }
 
 pub type Pass3dtGrandpaInstance = ();
 impl pallet_bridge_grandpa::Config for Runtime {
+	type Event = Event;
 	type BridgedChain = bp_pass3dt::Pass3dt;
 	type MaxRequests = MaxRequests;
+	type MaxRequestsWeight = MaxRequestsWeight;
 	type HeadersToKeep = HeadersToKeep;
-	type WeightInfo = (); //pallet_bridge_grandpa::weights::Pass3dtWeight<Runtime>;
+	type JustificationsToKeep = JustificationsToKeep;
+	type MaxJustificationSize =
+		frame_support::traits::ConstU32<{ bp_pass3dt::MAX_JUSTIFICATION_SIZE }>;
+	type MaxBridgedAuthorities =
+		frame_support::traits::ConstU32<{ bp_pass3dt::MAX_AUTHORITIES_COUNT }>;
+	type MaxBridgedHeaderSize = frame_support::traits::ConstU32<{ bp_pass3dt::MAX_HEADER_SIZE }>;
+	type MaxFinalityProofsInBatch = MaxFinalityProofsInBatch;
+	// weights generated on the Bridge testnets. Pass3d weights are generated on the Pass3d
+	// reference hardware by the `scripts/update-pass3d-weights.sh`
+	type WeightInfo = pallet_bridge_grandpa::weights::BridgeWeight<Runtime>;
 }
 
 impl pallet_shift_session_manager::Config for Runtime {}
 
 parameter_types! {
 	pub const MaxMessagesToPruneAtOnce: bp_messages::MessageNonce = 8;
+	pub const MaxOutboundQueueLen: bp_messages::MessageNonce = 4096;
 	pub const MaxUnrewardedRelayerEntriesAtInboundLane: bp_messages::MessageNonce =
 		bp_pass3dt::MAX_UNREWARDED_RELAYERS_IN_CONFIRMATION_TX;
 	pub const MaxUnconfirmedMessagesAtInboundLane: bp_messages::MessageNonce =
 		bp_pass3dt::MAX_UNCONFIRMED_MESSAGES_IN_CONFIRMATION_TX;
+	pub const MaxRecentDeliveries: u32 = 64;
+	// a single account may send at most 64 messages per minute
+	pub const OutboundMessagesRateLimit: Option<pallet_bridge_messages::OutboundRateLimit<BlockNumber>> =
+		Some(pallet_bridge_messages::OutboundRateLimit {
+			max_messages: 64,
+			window: bp_pass3d::time_units::MINUTES,
+		});
 	// `IdentityFee` is used by Pass3d => we may use weight directly
 	pub const GetDeliveryConfirmationTransactionFee: Balance =
 		bp_pass3d::MAX_SINGLE_MESSAGE_DELIVERY_CONFIRMATION_TX_WEIGHT as _;
@@ -445,17 +548,27 @@ pub type WithPass3dtMessagesInstance = ();
 
 impl pallet_bridge_messages::Config<WithPass3dtMessagesInstance> for Runtime {
 	type Event = Event;
-	type WeightInfo = (); //pallet_bridge_messages::weights::Pass3dtWeight<Runtime>;
+	// weights generated on the Bridge testnets. Pass3d weights are generated on the Pass3d
+	// reference hardware by the `scripts/update-pass3d-weights.sh`
+	type WeightInfo = pallet_bridge_messages::weights::BridgeWeight<Runtime>;
 	type Parameter = pass3dt_messages::Pass3dToPass3dtMessagesParameter;
 	type MaxMessagesToPruneAtOnce = MaxMessagesToPruneAtOnce;
+	type MaxOutboundQueueLen = MaxOutboundQueueLen;
 	type MaxUnrewardedRelayerEntriesAtInboundLane = MaxUnrewardedRelayerEntriesAtInboundLane;
 	type MaxUnconfirmedMessagesAtInboundLane = MaxUnconfirmedMessagesAtInboundLane;
+	type MaxRecentDeliveries = MaxRecentDeliveries;
+	type MessageExpiryBlocks = ();
+	type OutboundMessagesRateLimit = OutboundMessagesRateLimit;
+	type RateLimitedSender = crate::pass3dt_messages::RateLimitedSender;
+	type MessageSubmitter = ();
+	type IndexMessagesHistory = frame_support::traits::ConstBool<true>;
 
 	type MaximalOutboundPayloadSize = crate::pass3dt_messages::ToPass3dtMaximalOutboundPayloadSize;
 	type OutboundPayload = crate::pass3dt_messages::ToPass3dtMessagePayload;
 	type OutboundMessageFee = Balance;
 
 	type InboundPayload = crate::pass3dt_messages::FromPass3dtMessagePayload;
+	type MaximalInboundPayloadSize = crate::pass3dt_messages::FromPass3dtMaximalInboundPayloadSize;
 	type InboundMessageFee = bp_pass3dt::Balance;
 	type InboundRelayer = bp_pass3dt::AccountId;
 
@@ -471,6 +584,9 @@ impl pallet_bridge_messages::Config<WithPass3dtMessagesInstance> for Runtime {
 	type OnDeliveryConfirmed = ();
 
 	type SourceHeaderChain = crate::pass3dt_messages::Pass3dt;
+	type MaxMessagesProofSize = crate::pass3dt_messages::FromPass3dtMaximalMessagesProofSize;
+	type MaxMessagesDeliveryProofSize =
+		crate::pass3dt_messages::ToPass3dtMaximalMessagesDeliveryProofSize;
 	type MessageDispatch = crate::pass3dt_messages::FromPass3dtMessageDispatch;
 	type BridgedChainId = BridgedChainId;
 }
@@ -504,9 +620,12 @@ construct_runtime!(
 
 		// Pass3dt bridge modules.
 		BridgeRelayers: pallet_bridge_relayers::{Pallet, Call, Storage, Event<T>},
-		BridgePass3dtGrandpa: pallet_bridge_grandpa::{Pallet, Call, Storage},
+		BridgePass3dtGrandpa: pallet_bridge_grandpa::{Pallet, Call, Storage, Event<T>},
 		BridgePass3dtMessages: pallet_bridge_messages::{Pallet, Call, Storage, Event<T>, Config<T>},
 
+		// Proxy support, so that relayers may use cold accounts to hold funds.
+		Proxy: pallet_proxy::{Pallet, Call, Storage, Event<T>},
+
 		// Parachain modules.
 		// ParachainsOrigin: polkadot_runtime_parachains::origin::{Pallet, Origin},
 		// Configuration: polkadot_runtime_parachains::configuration::{Pallet, Call, Storage, Config<T>},
@@ -531,6 +650,14 @@ construct_runtime!(
 	}
 );
 
+generate_bridge_reject_obsolete_headers_and_messages! {
+	Call, AccountId,
+	// Grandpa
+	LookThroughProxy<Runtime, BridgePass3dtGrandpa>,
+	// Messages
+	LookThroughProxy<Runtime, BridgePass3dtMessages>
+}
+
 /// The address format for describing accounts.
 pub type Address = sp_runtime::MultiAddress<AccountId, ()>;
 /// Block header type as expected by this runtime.
@@ -551,6 +678,7 @@ pub type SignedExtra = (
 	frame_system::CheckNonce<Runtime>,
 	frame_system::CheckWeight<Runtime>,
 	pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+	BridgeRejectObsoleteHeadersAndMessages,
 );
 /// The payload being signed in transactions.
 pub type SignedPayload = generic::SignedPayload<Call, SignedExtra>;
@@ -565,8 +693,16 @@ pub type Executive = frame_executive::Executive<
 	frame_system::ChainContext<Runtime>,
 	Runtime,
 	AllPalletsWithSystem,
+	Migrations,
 >;
 
+/// Storage migrations that are applied on runtime upgrade.
+pub type Migrations = (
+	pallet_bridge_grandpa::migration::v1::MigrateToV1<Runtime, Pass3dtGrandpaInstance>,
+	pallet_bridge_messages::migration::v1::MigrateToV1<Runtime, WithPass3dtMessagesInstance>,
+	pallet_bridge_messages::migration::v2::MigrateToV2<Runtime, WithPass3dtMessagesInstance>,
+);
+
 impl_runtime_apis! {
 	impl sp_api::Core<Block> for Runtime {
 		fn version() -> RuntimeVersion {
@@ -697,6 +833,37 @@ impl_runtime_apis! {
 		fn best_finalized() -> Option<HeaderId<bp_pass3dt::Hash, bp_pass3dt::BlockNumber>> {
 			BridgePass3dtGrandpa::best_finalized().map(|header| header.id())
 		}
+
+		fn finalized_header(
+			number: bp_pass3dt::BlockNumber,
+		) -> Option<HeaderId<bp_pass3dt::Hash, bp_pass3dt::BlockNumber>> {
+			BridgePass3dtGrandpa::finalized_header(number)
+		}
+
+		fn accepts_free_header(header: bp_pass3dt::Header) -> bool {
+			BridgePass3dtGrandpa::is_free_header(&header)
+		}
+
+		fn is_known_header(hash: bp_pass3dt::Hash) -> bool {
+			BridgePass3dtGrandpa::is_known_header(hash)
+		}
+
+		fn stored_justification(hash: bp_pass3dt::Hash) -> Option<bp_runtime::EncodedJustification> {
+			BridgePass3dtGrandpa::stored_justification(hash)
+		}
+	}
+
+	impl bp_pass3dt::Pass3dtFinalityOperatingModeApi<Block> for Runtime {
+		fn operating_state() -> bp_runtime::FinalityOperatingState<bp_pass3dt::BlockNumber> {
+			BridgePass3dtGrandpa::operating_state()
+		}
+	}
+
+	impl bp_pass3dt::Pass3dtSyncedHeadersGrandpaInfoApi<Block> for Runtime {
+		fn synced_headers_grandpa_info(
+		) -> bp_runtime::SyncedHeadersGrandpaInfo<bp_pass3dt::Hash, bp_pass3dt::BlockNumber> {
+			BridgePass3dtGrandpa::synced_headers_grandpa_info()
+		}
 	}
 
 	impl sp_transaction_pool::runtime_api::TaggedTransactionQueue<Block> for Runtime {
@@ -929,7 +1096,7 @@ impl_runtime_apis! {
 		}
 	}
 
-	impl bp_pass3dt::ToPass3dtOutboundLaneApi<Block, Balance, ToPass3dtMessagePayload> for Runtime {
+	impl bp_pass3dt::ToPass3dtOutboundLaneApi<Block, Balance, ToPass3dtMessagePayload, AccountId> for Runtime {
 		fn estimate_message_delivery_and_dispatch_fee(
 			_lane_id: bp_messages::LaneId,
 			payload: ToPass3dtMessagePayload,
@@ -950,11 +1117,26 @@ impl_runtime_apis! {
 			bridge_runtime_common::messages_api::outbound_message_details::<
 				Runtime,
 				WithPass3dtMessagesInstance,
+				pass3dt_messages::WithPass3dtMessageBridge,
 			>(lane, begin, end)
 		}
+
+		fn recent_relayers(
+			lane: bp_messages::LaneId,
+		) -> Vec<(AccountId, bp_messages::MessageNonce)> {
+			pallet_bridge_messages::Pallet::<Runtime, WithPass3dtMessagesInstance>::recent_relayers(lane)
+		}
+
+		fn outbound_messages_allowance(sender: AccountId) -> Option<u32> {
+			pallet_bridge_messages::Pallet::<Runtime, WithPass3dtMessagesInstance>::outbound_messages_allowance(sender)
+		}
+
+		fn outbound_lane_state(lane: bp_messages::LaneId) -> bp_messages::OutboundLaneData {
+			pallet_bridge_messages::Pallet::<Runtime, WithPass3dtMessagesInstance>::outbound_lane_state(lane)
+		}
 	}
 
-	impl bp_pass3dt::FromPass3dtInboundLaneApi<Block, bp_pass3dt::Balance> for Runtime {
+	impl bp_pass3dt::FromPass3dtInboundLaneApi<Block, bp_pass3dt::Balance, bp_pass3dt::AccountId> for Runtime {
 		fn message_details(
 			lane: bp_messages::LaneId,
 			messages: Vec<(bp_messages::MessagePayload, bp_messages::OutboundMessageDetails<bp_pass3dt::Balance>)>,
@@ -964,6 +1146,148 @@ impl_runtime_apis! {
 				WithPass3dtMessagesInstance,
 			>(lane, messages)
 		}
+
+		fn unrewarded_relayers_summary(
+			lane: bp_messages::LaneId,
+		) -> Vec<(bp_pass3dt::AccountId, bp_messages::MessageNonce)> {
+			pallet_bridge_messages::Pallet::<Runtime, WithPass3dtMessagesInstance>::unrewarded_relayers_summary(lane)
+		}
+
+		fn inbound_lane_state(lane: bp_messages::LaneId) -> bp_messages::InboundLaneState {
+			pallet_bridge_messages::Pallet::<Runtime, WithPass3dtMessagesInstance>::inbound_lane_state(lane)
+		}
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	impl frame_benchmarking::Benchmark<Block> for Runtime {
+		fn benchmark_metadata(extra: bool) -> (
+			Vec<frame_benchmarking::BenchmarkList>,
+			Vec<frame_support::traits::StorageInfo>,
+		) {
+			use frame_benchmarking::{list_benchmark, Benchmarking, BenchmarkList};
+			use frame_support::traits::StorageInfoTrait;
+
+			use pallet_bridge_messages::benchmarking::Pallet as MessagesBench;
+
+			let mut list = Vec::<BenchmarkList>::new();
+
+			list_benchmark!(
+				list,
+				extra,
+				pallet_bridge_messages,
+				MessagesBench::<Runtime, WithPass3dtMessagesInstance>
+			);
+			list_benchmark!(list, extra, pallet_bridge_grandpa, BridgePass3dtGrandpa);
+
+			let storage_info = AllPalletsWithSystem::storage_info();
+
+			return (list, storage_info)
+		}
+
+		fn dispatch_benchmark(
+			config: frame_benchmarking::BenchmarkConfig,
+		) -> Result<Vec<frame_benchmarking::BenchmarkBatch>, sp_runtime::RuntimeString> {
+			use frame_benchmarking::{Benchmarking, BenchmarkBatch, TrackedStorageKey, add_benchmark};
+
+			let whitelist: Vec<TrackedStorageKey> = vec![
+				// Block Number
+				hex_literal::hex!("26aa394eea5630e07c48ae0c9558cef702a5c1b19ab7a04f536c519aca4983ac").to_vec().into(),
+				// Execution Phase
+				hex_literal::hex!("26aa394eea5630e07c48ae0c9558cef7ff553b5a9862a516939d82b3d3d8661a").to_vec().into(),
+				// Event Count
+				hex_literal::hex!("26aa394eea5630e07c48ae0c9558cef70a98fdbe9ce6c55837576c60c7af3850").to_vec().into(),
+				// System Events
+				hex_literal::hex!("26aa394eea5630e07c48ae0c9558cef780d41e5e16056765bc8461851072c9d7").to_vec().into(),
+			];
+
+			let mut batches = Vec::<BenchmarkBatch>::new();
+			let params = (&config, &whitelist);
+
+			use bridge_runtime_common::messages_benchmarking::{
+				prepare_message_delivery_proof, prepare_message_proof, prepare_outbound_message,
+			};
+			use bridge_runtime_common::messages;
+			use pallet_bridge_messages::benchmarking::{
+				Pallet as MessagesBench,
+				Config as MessagesConfig,
+				MessageDeliveryProofParams,
+				MessageParams,
+				MessageProofParams,
+			};
+
+			impl MessagesConfig<WithPass3dtMessagesInstance> for Runtime {
+				fn maximal_message_size() -> u32 {
+					messages::source::maximal_message_size::<WithPass3dtMessageBridge>()
+				}
+
+				fn bridged_relayer_id() -> Self::InboundRelayer {
+					[0u8; 32].into()
+				}
+
+				fn account_balance(account: &Self::AccountId) -> Self::OutboundMessageFee {
+					pallet_balances::Pallet::<Runtime>::free_balance(account)
+				}
+
+				fn endow_account(account: &Self::AccountId) {
+					pallet_balances::Pallet::<Runtime>::make_free_balance_be(
+						account,
+						Balance::MAX / 100,
+					);
+				}
+
+				fn prepare_outbound_message(
+					params: MessageParams<Self::AccountId>,
+				) -> (ToPass3dtMessagePayload, Balance) {
+					(prepare_outbound_message::<WithPass3dtMessageBridge>(params), Self::message_fee())
+				}
+
+				fn prepare_message_proof(
+					params: MessageProofParams,
+				) -> (pass3dt_messages::FromPass3dtMessagesProof, Weight) {
+					prepare_message_proof::<
+						Runtime,
+						(),
+						Pass3dtGrandpaInstance,
+						WithPass3dtMessageBridge,
+						bp_pass3dt::Header,
+						bp_pass3dt::Hasher,
+					>(params)
+				}
+
+				fn prepare_message_delivery_proof(
+					params: MessageDeliveryProofParams<Self::AccountId>,
+				) -> pass3dt_messages::ToPass3dtMessagesDeliveryProof {
+					prepare_message_delivery_proof::<
+						Runtime,
+						Pass3dtGrandpaInstance,
+						WithPass3dtMessageBridge,
+						bp_pass3dt::Header,
+						bp_pass3dt::Hasher,
+					>(params)
+				}
+
+				fn is_message_dispatched(nonce: bp_messages::MessageNonce) -> bool {
+					// dispatch result of every received message (either successful or not) is
+					// recorded in the unrewarded relayers entry at the inbound lane
+					use pallet_bridge_messages::InboundLanes;
+
+					InboundLanes::<Runtime, WithPass3dtMessagesInstance>::get(Self::bench_lane_id())
+						.relayers
+						.iter()
+						.any(|entry| entry.messages.get_dispatch_result(nonce).is_some())
+				}
+			}
+
+			add_benchmark!(
+				params,
+				batches,
+				pallet_bridge_messages,
+				MessagesBench::<Runtime, WithPass3dtMessagesInstance>
+			);
+			add_benchmark!(params, batches, pallet_bridge_grandpa, BridgePass3dtGrandpa);
+
+			Ok(batches)
+		}
 	}
 }
 
@@ -988,4 +1312,138 @@ mod tests {
 		const MAX_CALL_SIZE: usize = 232;
 		assert!(core::mem::size_of::<Call>() <= MAX_CALL_SIZE);
 	}
+
+	#[test]
+	fn ensure_chain_primitives_constants_are_correct() {
+		use codec::Encode;
+		use sp_core::Pair;
+
+		// signed extensions are constructed the same way as in the relay `sign_transaction`,
+		// but with mortal era and maximal nonce to get the largest encoding of both
+		const MAX_TX_EXTRA_BYTES_MARGIN: u32 = 2;
+		let signer = sp_core::sr25519::Pair::from_seed(&[42u8; 32]);
+		let genesis_hash = Hash::default();
+		let era = sp_runtime::generic::Era::mortal(64, 1_000);
+		// large enough call to have 4-byte length prefix of the encoded transaction
+		let call = Call::System(SystemCall::remark { remark: vec![0u8; 16 * 1024] });
+		let payload = SignedPayload::from_raw(
+			call,
+			(
+				frame_system::CheckNonZeroSender::new(),
+				frame_system::CheckSpecVersion::new(),
+				frame_system::CheckTxVersion::new(),
+				frame_system::CheckGenesis::new(),
+				frame_system::CheckEra::from(era),
+				frame_system::CheckNonce::from(Index::MAX),
+				frame_system::CheckWeight::new(),
+				pallet_transaction_payment::ChargeTransactionPayment::from(0),
+				BridgeRejectObsoleteHeadersAndMessages,
+			),
+			(
+				(),
+				VERSION.spec_version,
+				VERSION.transaction_version,
+				genesis_hash,
+				genesis_hash,
+				(),
+				(),
+				(),
+				(),
+			),
+		);
+		let signature = payload.using_encoded(|payload| signer.sign(payload));
+		let (call, extra, _) = payload.deconstruct();
+		bridge_runtime_common::integrity::assert_transaction_extra_bytes_within_margin(
+			UncheckedExtrinsic::new_signed(
+				call,
+				AccountId::from(signer.public()).into(),
+				signature.into(),
+				extra,
+			),
+			bp_pass3d::TX_EXTRA_BYTES,
+			MAX_TX_EXTRA_BYTES_MARGIN,
+		);
+
+		bridge_runtime_common::integrity::assert_inbound_lane_limits::<
+			Runtime,
+			WithPass3dtMessagesInstance,
+		>(bridge_runtime_common::integrity::AssertInboundLaneLimits {
+			max_unrewarded_relayers_in_confirmation_tx:
+				bp_pass3dt::MAX_UNREWARDED_RELAYERS_IN_CONFIRMATION_TX,
+			max_unconfirmed_messages_in_confirmation_tx:
+				bp_pass3dt::MAX_UNCONFIRMED_MESSAGES_IN_CONFIRMATION_TX,
+		});
+
+		assert_eq!(EpochDuration::get(), bp_pass3d::SESSION_LENGTH as u64);
+	}
+
+	#[test]
+	fn messages_pallet_storage_key_providers_are_correct() {
+		use bp_messages::{
+			storage_keys::{
+				InboundLanesKeyProvider, OutboundLanesKeyProvider, OutboundMessagesKeyProvider,
+			},
+			LaneId, MessageKey,
+		};
+		use bp_runtime::StorageMapKeyProvider;
+		use frame_support::storage::generator::StorageMap;
+
+		type OutboundMessages =
+			pallet_bridge_messages::OutboundMessages<Runtime, WithPass3dtMessagesInstance>;
+		type OutboundLanes =
+			pallet_bridge_messages::OutboundLanes<Runtime, WithPass3dtMessagesInstance>;
+		type InboundLanes =
+			pallet_bridge_messages::InboundLanes<Runtime, WithPass3dtMessagesInstance>;
+
+		let pallet_name = bp_pass3dt::WITH_PASS3DT_MESSAGES_PALLET_NAME;
+		let lane = LaneId(*b"test");
+		let message_key = MessageKey { lane_id: lane, nonce: 42 };
+
+		assert_eq!(
+			OutboundMessagesKeyProvider::<Balance>::final_key(pallet_name, &message_key).0,
+			OutboundMessages::storage_map_final_key(message_key),
+		);
+		assert_eq!(
+			OutboundLanesKeyProvider::final_key(pallet_name, &lane).0,
+			OutboundLanes::storage_map_final_key(lane),
+		);
+		assert_eq!(
+			InboundLanesKeyProvider::<bp_pass3dt::AccountId>::final_key(pallet_name, &lane).0,
+			InboundLanes::storage_map_final_key(lane),
+		);
+	}
+
+	#[test]
+	fn obsolete_finality_proofs_are_rejected_at_pool_validation() {
+		use sp_runtime::{traits::SignedExtension, transaction_validity::InvalidTransaction};
+
+		fn validate_submit_finality_proof(
+			number: bp_pass3dt::BlockNumber,
+		) -> sp_runtime::transaction_validity::TransactionValidity {
+			let header = bp_test_utils::test_header::<bp_pass3dt::Header>(number);
+			let call = Call::BridgePass3dtGrandpa(BridgeGrandpaCall::submit_finality_proof {
+				finality_target: Box::new(header.clone()),
+				justification: bp_test_utils::make_default_justification(&header),
+			});
+			BridgeRejectObsoleteHeadersAndMessages.validate(
+				&AccountId::from([0u8; 32]),
+				&call,
+				&Default::default(),
+				0,
+			)
+		}
+
+		sp_io::TestExternalities::default().execute_with(|| {
+			pallet_bridge_grandpa::BestFinalized::<Runtime, Pass3dtGrandpaInstance>::put((
+				10,
+				bp_pass3dt::Hash::default(),
+			));
+
+			// when two relayers are racing, the proof of already finalized header is rejected
+			assert_eq!(validate_submit_finality_proof(5), InvalidTransaction::Stale.into());
+			assert_eq!(validate_submit_finality_proof(10), InvalidTransaction::Stale.into());
+			// while the proof of better header is accepted
+			assert!(validate_submit_finality_proof(11).is_ok());
+		});
+	}
 }
@@ -22,8 +22,9 @@ use bp_messages::{
 	source_chain::{SenderOrigin, TargetHeaderChain},
 	target_chain::{ProvedMessages, SourceHeaderChain},
 	InboundLaneData, LaneId, Message, MessageNonce, Parameter as MessagesParameter,
+	VerificationError,
 };
-use bp_runtime::{Chain, ChainId, PASS3DT_CHAIN_ID, PASS3D_CHAIN_ID};
+use bp_runtime::{messages::DispatchFeePayment, Chain, ChainId, PASS3DT_CHAIN_ID, PASS3D_CHAIN_ID};
 use bridge_runtime_common::messages::{
 	self, BasicConfirmationTransactionEstimation, MessageBridge, MessageTransaction,
 };
@@ -34,7 +35,10 @@ use frame_support::{
 	RuntimeDebug,
 };
 use scale_info::TypeInfo;
-use sp_runtime::{traits::Saturating, FixedPointNumber, FixedU128};
+use sp_runtime::{
+	traits::{Convert, Saturating},
+	FixedPointNumber, FixedU128,
+};
 use sp_std::convert::TryFrom;
 
 /// Initial value of `Pass3dtToPass3dConversionRate` parameter.
@@ -42,6 +46,8 @@ pub const INITIAL_PASS3DT_TO_PASS3D_CONVERSION_RATE: FixedU128 =
 	FixedU128::from_inner(FixedU128::DIV);
 /// Initial value of `Pass3dtFeeMultiplier` parameter.
 pub const INITIAL_PASS3DT_FEE_MULTIPLIER: FixedU128 = FixedU128::from_inner(FixedU128::DIV);
+/// Initial value of `BridgedXcmVersion` parameter.
+pub const INITIAL_BRIDGED_XCM_VERSION: xcm::Version = xcm::latest::VERSION;
 /// Weight of 2 XCM instructions is for simple `Trap(42)` program, coming through bridge
 /// (it is prepended with `UniversalOrigin` instruction). It is used just for simplest manual
 /// tests, confirming that we don't break encoding somewhere between.
@@ -52,6 +58,21 @@ parameter_types! {
 	pub storage Pass3dtToPass3dConversionRate: FixedU128 = INITIAL_PASS3DT_TO_PASS3D_CONVERSION_RATE;
 	/// Fee multiplier value at Pass3dt chain.
 	pub storage Pass3dtFeeMultiplier: FixedU128 = INITIAL_PASS3DT_FEE_MULTIPLIER;
+	/// XCM version that is supported by the Pass3dt chain.
+	pub storage BridgedXcmVersion: xcm::Version = INITIAL_BRIDGED_XCM_VERSION;
+	/// Maximal size of Pass3dt -> Pass3d messages proof.
+	pub FromPass3dtMaximalMessagesProofSize: u32 =
+		messages::target::maximal_messages_proof_size(
+			bp_pass3d::Pass3d::max_extrinsic_size(),
+			bp_pass3dt::EXTRA_STORAGE_PROOF_SIZE,
+		);
+	/// Maximal size of Pass3d -> Pass3dt messages delivery proof.
+	pub ToPass3dtMaximalMessagesDeliveryProofSize: u32 =
+		messages::source::maximal_messages_delivery_proof_size::<bp_pass3d::AccountId>(
+			bp_pass3dt::EXTRA_STORAGE_PROOF_SIZE,
+			bp_pass3dt::MAX_UNREWARDED_RELAYERS_IN_CONFIRMATION_TX,
+			bp_pass3dt::MAX_UNCONFIRMED_MESSAGES_IN_CONFIRMATION_TX,
+		);
 }
 
 /// Message payload for Pass3d -> Pass3dt messages.
@@ -64,6 +85,10 @@ pub type ToPass3dtMessageVerifier =
 /// Message payload for Pass3dt -> Pass3d messages.
 pub type FromPass3dtMessagePayload = messages::target::FromBridgedChainMessagePayload<Call>;
 
+/// Default maximal inbound payload size of Pass3dt -> Pass3d messages.
+pub type FromPass3dtMaximalInboundPayloadSize =
+	messages::target::FromBridgedChainMaximalInboundPayloadSize<bp_pass3d::Pass3d>;
+
 /// Call-dispatch based message dispatch for Pass3dt -> Pass3d messages.
 pub type FromPass3dtMessageDispatch = messages::target::FromBridgedChainMessageDispatch<
 	WithPass3dtMessageBridge,
@@ -93,6 +118,7 @@ impl MessageBridge for WithPass3dtMessageBridge {
 	const THIS_CHAIN_ID: ChainId = PASS3D_CHAIN_ID;
 	const BRIDGED_CHAIN_ID: ChainId = PASS3DT_CHAIN_ID;
 	const BRIDGED_MESSAGES_PALLET_NAME: &'static str = bp_pass3d::WITH_PASS3D_MESSAGES_PALLET_NAME;
+	const DISPATCH_FEE_PAYMENT: DispatchFeePayment = DispatchFeePayment::AtTargetChain;
 
 	type ThisChain = Pass3d;
 	type BridgedChain = Pass3dt;
@@ -148,7 +174,7 @@ impl messages::ThisChainWithMessages for Pass3d {
 			},
 		}
 
-		*lane == [0, 0, 0, 0] || *lane == [0, 0, 0, 1]
+		bp_pass3d::all_active_lanes().contains(lane)
 	}
 
 	fn maximal_pending_messages_at_outbound_lane() -> MessageNonce {
@@ -234,7 +260,7 @@ impl messages::BridgedChainWithMessages for Pass3dt {
 }
 
 impl TargetHeaderChain<ToPass3dtMessagePayload, bp_pass3d::AccountId> for Pass3dt {
-	type Error = &'static str;
+	type Error = VerificationError;
 	// The proof is:
 	// - hash of the header this proof has been created with;
 	// - the storage proof of one or several keys;
@@ -257,7 +283,7 @@ impl TargetHeaderChain<ToPass3dtMessagePayload, bp_pass3d::AccountId> for Pass3d
 }
 
 impl SourceHeaderChain<bp_pass3dt::Balance> for Pass3dt {
-	type Error = &'static str;
+	type Error = VerificationError;
 	// The proof is:
 	// - hash of the header this proof has been created with;
 	// - the storage proof of one or several keys;
@@ -284,11 +310,28 @@ impl SenderOrigin<crate::AccountId> for crate::Origin {
 	}
 }
 
+/// Account that is subject to the outbound messages rate limit.
+///
+/// Only messages that are sent by signed origins are rate-limited. Root and XCM origins (the
+/// XCM executor only sends messages from trusted locations) are exempt.
+pub struct RateLimitedSender;
+
+impl Convert<crate::Origin, Option<crate::AccountId>> for RateLimitedSender {
+	fn convert(origin: crate::Origin) -> Option<crate::AccountId> {
+		match origin.caller {
+			OriginCaller::system(frame_system::RawOrigin::Signed(account)) => Some(account),
+			_ => None,
+		}
+	}
+}
+
 /// Pass3d -> Pass3dt message lane pallet parameters.
 #[derive(RuntimeDebug, Clone, Encode, Decode, PartialEq, Eq, TypeInfo)]
 pub enum Pass3dToPass3dtMessagesParameter {
 	/// The conversion formula we use is: `Pass3dTokens = Pass3dtTokens * conversion_rate`.
 	Pass3dtToPass3dConversionRate(FixedU128),
+	/// XCM version that is supported by the Pass3dt chain.
+	BridgedXcmVersion(xcm::Version),
 }
 
 impl MessagesParameter for Pass3dToPass3dtMessagesParameter {
@@ -296,6 +339,8 @@ impl MessagesParameter for Pass3dToPass3dtMessagesParameter {
 		match *self {
 			Pass3dToPass3dtMessagesParameter::Pass3dtToPass3dConversionRate(ref conversion_rate) =>
 				Pass3dtToPass3dConversionRate::set(conversion_rate),
+			Pass3dToPass3dtMessagesParameter::BridgedXcmVersion(ref version) =>
+				BridgedXcmVersion::set(version),
 		}
 	}
 }
@@ -304,6 +349,7 @@ impl MessagesParameter for Pass3dToPass3dtMessagesParameter {
 mod tests {
 	use super::*;
 	use crate::{DbWeight, Pass3dtGrandpaInstance, Runtime, WithPass3dtMessagesInstance};
+	use bp_messages::{MessageData, MessageKey, OutboundMessageDetails};
 	use bp_runtime::Chain;
 	use bridge_runtime_common::{
 		assert_complete_bridge_types,
@@ -311,11 +357,139 @@ mod tests {
 			assert_complete_bridge_constants, AssertBridgeMessagesPalletConstants,
 			AssertBridgePalletNames, AssertChainConstants, AssertCompleteBridgeConstants,
 		},
+		messages::{BridgedChainWithMessages, ChainWithMessages, DeclaredMessageDispatch},
+		messages_api,
 	};
 
+	/// Pass3dt chain, which accepts messages that are declaring their dispatch weight and dispatch
+	/// fee payment location.
+	#[derive(RuntimeDebug, Clone, Copy)]
+	struct Pass3dtWithDeclaredDispatch;
+
+	impl ChainWithMessages for Pass3dtWithDeclaredDispatch {
+		type Hash = <Pass3dt as ChainWithMessages>::Hash;
+		type AccountId = <Pass3dt as ChainWithMessages>::AccountId;
+		type Signer = <Pass3dt as ChainWithMessages>::Signer;
+		type Signature = <Pass3dt as ChainWithMessages>::Signature;
+		type Weight = <Pass3dt as ChainWithMessages>::Weight;
+		type Balance = <Pass3dt as ChainWithMessages>::Balance;
+	}
+
+	impl BridgedChainWithMessages for Pass3dtWithDeclaredDispatch {
+		fn maximal_extrinsic_size() -> u32 {
+			Pass3dt::maximal_extrinsic_size()
+		}
+
+		fn verify_dispatch_weight(message_payload: &[u8]) -> bool {
+			Pass3dt::verify_dispatch_weight(message_payload)
+		}
+
+		fn declared_message_dispatch(message_payload: &[u8]) -> Option<DeclaredMessageDispatch> {
+			let (dispatch_weight, dispatch_fee_payment) =
+				Decode::decode(&mut &message_payload[..]).ok()?;
+			Some(DeclaredMessageDispatch { dispatch_weight, dispatch_fee_payment })
+		}
+
+		fn estimate_delivery_transaction(
+			message_payload: &[u8],
+			include_pay_dispatch_fee_cost: bool,
+			message_dispatch_weight: Weight,
+		) -> MessageTransaction<Weight> {
+			Pass3dt::estimate_delivery_transaction(
+				message_payload,
+				include_pay_dispatch_fee_cost,
+				message_dispatch_weight,
+			)
+		}
+
+		fn transaction_payment(transaction: MessageTransaction<Weight>) -> bp_pass3dt::Balance {
+			Pass3dt::transaction_payment(transaction)
+		}
+	}
+
+	#[derive(RuntimeDebug, Clone, Copy)]
+	struct WithPass3dtDeclaredDispatchMessageBridge;
+
+	impl MessageBridge for WithPass3dtDeclaredDispatchMessageBridge {
+		const RELAYER_FEE_PERCENT: u32 = WithPass3dtMessageBridge::RELAYER_FEE_PERCENT;
+		const THIS_CHAIN_ID: ChainId = WithPass3dtMessageBridge::THIS_CHAIN_ID;
+		const BRIDGED_CHAIN_ID: ChainId = WithPass3dtMessageBridge::BRIDGED_CHAIN_ID;
+		const BRIDGED_MESSAGES_PALLET_NAME: &'static str =
+			WithPass3dtMessageBridge::BRIDGED_MESSAGES_PALLET_NAME;
+		const DISPATCH_FEE_PAYMENT: DispatchFeePayment =
+			WithPass3dtMessageBridge::DISPATCH_FEE_PAYMENT;
+
+		type ThisChain = Pass3d;
+		type BridgedChain = Pass3dtWithDeclaredDispatch;
+
+		fn bridged_balance_to_this_balance(
+			bridged_balance: bp_pass3dt::Balance,
+			bridged_to_this_conversion_rate_override: Option<FixedU128>,
+		) -> bp_pass3d::Balance {
+			WithPass3dtMessageBridge::bridged_balance_to_this_balance(
+				bridged_balance,
+				bridged_to_this_conversion_rate_override,
+			)
+		}
+	}
+
+	fn outbound_message_details<Bridge: MessageBridge>(
+		payload: Vec<u8>,
+	) -> Vec<OutboundMessageDetails<bp_pass3d::Balance>> {
+		sp_io::TestExternalities::default().execute_with(|| {
+			pallet_bridge_messages::OutboundMessages::<Runtime, WithPass3dtMessagesInstance>::insert(
+				MessageKey { lane_id: LaneId::default(), nonce: 1 },
+				MessageData { payload, fee: 100 },
+			);
+
+			messages_api::outbound_message_details::<Runtime, WithPass3dtMessagesInstance, Bridge>(
+				LaneId::default(),
+				1,
+				1,
+			)
+		})
+	}
+
+	#[test]
+	fn outbound_message_details_reports_zero_dispatch_weight_of_xcm_message() {
+		let payload = (1_000_000 as Weight, DispatchFeePayment::AtSourceChain).encode();
+		let payload_size = payload.len() as u32;
+		assert_eq!(
+			outbound_message_details::<WithPass3dtMessageBridge>(payload),
+			vec![OutboundMessageDetails {
+				nonce: 1,
+				dispatch_weight: 0,
+				size: payload_size,
+				delivery_and_dispatch_fee: 100,
+				dispatch_fee_payment: DispatchFeePayment::AtTargetChain,
+				submitter: None,
+			}],
+		);
+	}
+
+	#[test]
+	fn outbound_message_details_reports_declared_dispatch_weight_and_fee_payment() {
+		let payload = (1_000_000 as Weight, DispatchFeePayment::AtSourceChain).encode();
+		let payload_size = payload.len() as u32;
+		assert_eq!(
+			outbound_message_details::<WithPass3dtDeclaredDispatchMessageBridge>(payload),
+			vec![OutboundMessageDetails {
+				nonce: 1,
+				dispatch_weight: 1_000_000,
+				size: payload_size,
+				delivery_and_dispatch_fee: 100,
+				dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+				submitter: None,
+			}],
+		);
+	}
+
 	#[test]
 	fn ensure_pass3d_message_lane_weights_are_correct() {
-		type Weights = pallet_bridge_messages::weights::Pass3dtWeight<Runtime>;
+		// the check must use weights that are actually configured in the runtime, because the relay
+		// sizes message batches using `bp_pass3d` constants
+		type Weights =
+			<Runtime as pallet_bridge_messages::Config<WithPass3dtMessagesInstance>>::WeightInfo;
 
 		pallet_bridge_messages::ensure_weights_are_correct::<Weights>(
 			bp_pass3d::DEFAULT_MESSAGE_DELIVERY_TX_WEIGHT,
@@ -397,4 +571,67 @@ mod tests {
 			.0,
 		);
 	}
+
+	#[test]
+	fn conversion_rate_parameter_name_is_not_changed() {
+		// the name is used by deployed relays to update the parameter, so it must not be changed
+		let parameter_name = bp_pass3d::PASS3DT_TO_PASS3D_CONVERSION_RATE_PARAMETER_NAME;
+		assert_eq!(parameter_name, "Pass3dtToPass3dConversionRate");
+
+		// relays are also selecting the `Pass3dToPass3dtMessagesParameter` variant by this name
+		match *<Pass3dToPass3dtMessagesParameter as scale_info::TypeInfo>::type_info().type_def() {
+			scale_info::TypeDef::Variant(ref variants) =>
+				assert!(variants.variants().iter().any(|variant| *variant.name() == parameter_name)),
+			ref type_def => panic!("Unexpected type of messages parameter: {:?}", type_def),
+		}
+	}
+
+	#[test]
+	fn estimate_fee_from_components_matches_runtime_estimation() {
+		use messages::ThisChainWithMessages;
+
+		sp_io::TestExternalities::default().execute_with(|| {
+			for payload_size in [0, 1, 128, 1_024, 64 * 1_024] {
+				let payload = vec![42u8; payload_size];
+				let delivery_transaction =
+					Pass3dt::estimate_delivery_transaction(&payload.encode(), true, 0);
+				let confirmation_transaction = Pass3d::estimate_delivery_confirmation_transaction();
+
+				assert_eq!(
+					bp_pass3dt::estimate_fee_from_components(
+						Pass3dt::transaction_payment(delivery_transaction),
+						Pass3d::transaction_payment(confirmation_transaction),
+						Pass3dtToPass3dConversionRate::get(),
+						WithPass3dtMessageBridge::RELAYER_FEE_PERCENT,
+					),
+					messages::source::estimate_message_dispatch_and_delivery_fee::<
+						WithPass3dtMessageBridge,
+					>(&payload, WithPass3dtMessageBridge::RELAYER_FEE_PERCENT, None)
+					.ok(),
+					"Fee mismatch for payload of {} bytes",
+					payload_size,
+				);
+			}
+		});
+	}
+
+	#[test]
+	fn only_registered_lanes_are_open() {
+		use messages::ThisChainWithMessages;
+
+		let origin: crate::Origin = frame_system::RawOrigin::Root.into();
+		for lane_byte in 0..=u8::MAX {
+			for lane in [LaneId([0, 0, 0, lane_byte]), LaneId([lane_byte, 0, 0, 0])] {
+				assert_eq!(
+					Pass3d::is_message_accepted(&origin, &lane),
+					bp_pass3d::all_active_lanes().contains(&lane),
+					"Lane {:?} is either open and not registered or registered and not open",
+					lane,
+				);
+			}
+		}
+
+		assert!(bp_pass3d::all_active_lanes().contains(&bp_pass3d::XCM_LANE));
+		assert_eq!(bp_pass3d::all_active_lanes(), bp_pass3dt::all_active_lanes());
+	}
 }
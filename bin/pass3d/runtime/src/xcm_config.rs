@@ -17,8 +17,9 @@
 //! XCM configurations for the Pass3d runtime.
 
 use super::{
-	pass3dt_messages::WithPass3dtMessageBridge, AccountId, AllPalletsWithSystem, Balances, Call,
-	Event, Origin, Runtime, WithPass3dtMessagesInstance, XcmPallet,
+	pass3dt_messages::{BridgedXcmVersion, WithPass3dtMessageBridge},
+	AccountId, AllPalletsWithSystem, Balances, Call, Event, Origin, Runtime,
+	WithPass3dtMessagesInstance, XcmPallet,
 };
 use bp_pass3d::WeightToFee;
 use bridge_runtime_common::{
@@ -36,6 +37,7 @@ use xcm_builder::{
 	CurrencyAdapter as XcmCurrencyAdapter, IsConcrete, SignedAccountId32AsNative,
 	SignedToAccountId32, SovereignSignedViaLocation, TakeWeightCredit, UsingComponents,
 };
+use xcm_executor::traits::Convert;
 
 parameter_types! {
 	/// The location of the `MLAU` token, from the context of this chain. Since this token is native to this
@@ -60,8 +62,30 @@ parameter_types! {
 pub type SovereignAccountOf = (
 	// We can directly alias an `AccountId32` into a local account.
 	AccountId32Aliases<ThisNetwork, AccountId>,
+	// Accounts of the Pass3dt chain are mapped to derived local accounts.
+	Pass3dtAccountId32ToAccountId,
 );
 
+/// Converts location of the `AccountId32` at the Pass3dt chain into the derived Pass3d account.
+///
+/// The account is derived using `bp_pass3d::derive_account_id_from_remote`.
+pub struct Pass3dtAccountId32ToAccountId;
+
+impl Convert<MultiLocation, AccountId> for Pass3dtAccountId32ToAccountId {
+	fn convert(location: MultiLocation) -> Result<AccountId, MultiLocation> {
+		match location {
+			MultiLocation {
+				parents: 1,
+				interior: X2(GlobalConsensus(network), AccountId32 { id, .. }),
+			} if network == Pass3dtNetwork::get() => Ok(bp_pass3d::derive_account_id_from_remote(
+				bp_runtime::PASS3DT_CHAIN_ID,
+				id.into(),
+			)),
+			_ => Err(location),
+		}
+	}
+}
+
 /// Our asset transactor. This is what allows us to interest with the runtime facilities from the
 /// point of view of XCM-only concepts like `MultiLocation` and `MultiAsset`.
 ///
@@ -209,7 +233,11 @@ impl XcmBridge for ToPass3dtBridge {
 	}
 
 	fn xcm_lane() -> bp_messages::LaneId {
-		[0, 0, 0, 0]
+		bp_pass3d::XCM_LANE
+	}
+
+	fn bridged_xcm_version() -> xcm::Version {
+		BridgedXcmVersion::get()
 	}
 }
 
@@ -217,34 +245,82 @@ impl XcmBridge for ToPass3dtBridge {
 mod tests {
 	use super::*;
 	use bp_messages::{
-		target_chain::{DispatchMessage, DispatchMessageData, MessageDispatch},
-		MessageKey,
+		target_chain::{DispatchMessage, MessageDispatch},
+		MessageData, MessageKey,
 	};
-	use bp_runtime::messages::MessageDispatchResult;
+	use bp_runtime::messages::{MessageDispatchError, MessageDispatchResult};
 	use bridge_runtime_common::messages::target::FromBridgedChainMessageDispatch;
-	use codec::Encode;
+	use codec::{Decode, Encode};
+	use xcm::VersionedXcm;
 
 	fn new_test_ext() -> sp_io::TestExternalities {
-		sp_io::TestExternalities::new(
+		let mut ext = sp_io::TestExternalities::new(
 			frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap(),
-		)
+		);
+		ext.execute_with(|| {
+			pallet_bridge_messages::OpenedLanes::<Runtime, crate::WithPass3dtMessagesInstance>::insert(bp_pass3d::XCM_LANE, ());
+		});
+		ext
 	}
 
 	#[test]
 	fn xcm_messages_to_pass3dt_are_sent() {
 		new_test_ext().execute_with(|| {
-			// the encoded message (origin ++ xcm) is 0x010109030419A8
+			// the encoded message (origin ++ xcm) is 0x01010903030419A8
 			let dest = (Parent, X1(GlobalConsensus(Pass3dtNetwork::get())));
 			let xcm: Xcm<()> = vec![Instruction::Trap(42)].into();
 
 			let send_result = send_xcm::<XcmRouter>(dest.into(), xcm);
-			let expected_fee = MultiAssets::from((Here, 4_259_858_152_u128));
+			let expected_fee = MultiAssets::from((Here, 4_259_858_153_u128));
 			let expected_hash =
 				([0u8, 0u8, 0u8, 0u8], 1u64).using_encoded(sp_io::hashing::blake2_256);
 			assert_eq!(send_result, Ok((expected_hash, expected_fee)),);
 		})
 	}
 
+	#[test]
+	fn xcm_messages_to_pass3dt_are_downgraded_to_bridged_xcm_version() {
+		new_test_ext().execute_with(|| {
+			BridgedXcmVersion::set(&2);
+
+			let dest = (Parent, X1(GlobalConsensus(Pass3dtNetwork::get())));
+			let xcm: Xcm<()> = vec![Instruction::Trap(42)].into();
+			assert!(send_xcm::<XcmRouter>(dest.into(), xcm).is_ok());
+
+			let message = pallet_bridge_messages::OutboundMessages::<
+				Runtime,
+				WithPass3dtMessagesInstance,
+			>::get(MessageKey {
+				lane_id: bp_messages::LaneId([0, 0, 0, 0]),
+				nonce: 1,
+			})
+			.unwrap();
+			let payload: Vec<u8> = Decode::decode(&mut &message.payload[..]).unwrap();
+			let (route, message): (MultiLocation, VersionedXcm<()>) =
+				Decode::decode(&mut &payload[..]).unwrap();
+			assert_eq!(route, ToPass3dtBridge::build_destination());
+			assert_eq!(
+				message,
+				VersionedXcm::V2(xcm::v2::Xcm(vec![xcm::v2::Instruction::Trap(42)]))
+			);
+		})
+	}
+
+	#[test]
+	fn xcm_messages_to_pass3dt_are_rejected_if_they_cant_be_downgraded() {
+		new_test_ext().execute_with(|| {
+			BridgedXcmVersion::set(&2);
+
+			let dest = (Parent, X1(GlobalConsensus(Pass3dtNetwork::get())));
+			let xcm: Xcm<()> =
+				vec![Instruction::UniversalOrigin(GlobalConsensus(ThisNetwork::get()))].into();
+			assert_eq!(
+				send_xcm::<XcmRouter>(dest.into(), xcm),
+				Err(SendError::DestinationUnsupported),
+			);
+		})
+	}
+
 	#[test]
 	fn xcm_messages_from_pass3dt_are_dispatched() {
 		type XcmExecutor = xcm_executor::XcmExecutor<XcmConfig>;
@@ -261,9 +337,52 @@ mod tests {
 			let xcm: Xcm<Call> = vec![Instruction::Trap(42)].into();
 
 			let mut incoming_message = DispatchMessage {
-				key: MessageKey { lane_id: [0, 0, 0, 0], nonce: 1 },
-				data: DispatchMessageData { payload: Ok((location, xcm).into()), fee: 0 },
+				key: MessageKey { lane_id: bp_messages::LaneId([0, 0, 0, 0]), nonce: 1 },
+				data: MessageData {
+					payload: (location, VersionedXcm::from(xcm)).encode().encode(),
+					fee: 0,
+				}
+				.into(),
+			};
+
+			let dispatch_weight = MessageDispatcher::dispatch_weight(&mut incoming_message);
+			assert_eq!(dispatch_weight, 1_000_000_000);
+
+			let dispatch_result =
+				MessageDispatcher::dispatch(&AccountId::from([0u8; 32]), incoming_message);
+			assert_eq!(
+				dispatch_result,
+				MessageDispatchResult {
+					dispatch_result: true,
+					unspent_weight: 0,
+					dispatch_fee_paid_during_dispatch: false,
+					dispatch_error: Some(MessageDispatchError::Trapped(42)),
+				}
+			);
+		})
+	}
+
+	#[test]
+	fn downgraded_xcm_messages_from_pass3dt_are_dispatched() {
+		type XcmExecutor = xcm_executor::XcmExecutor<XcmConfig>;
+		type MessageDispatcher = FromBridgedChainMessageDispatch<
+			WithPass3dtMessageBridge,
+			XcmExecutor,
+			XcmWeigher,
+			frame_support::traits::ConstU64<BASE_XCM_WEIGHT>,
+		>;
+
+		new_test_ext().execute_with(|| {
+			let location: MultiLocation =
+				(Parent, X1(GlobalConsensus(Pass3dtNetwork::get()))).into();
+			let xcm: VersionedXcm<Call> =
+				VersionedXcm::V2(xcm::v2::Xcm(vec![xcm::v2::Instruction::Trap(42)]));
+
+			let mut incoming_message = DispatchMessage {
+				key: MessageKey { lane_id: bp_messages::LaneId([0, 0, 0, 0]), nonce: 1 },
+				data: MessageData { payload: (location, xcm).encode().encode(), fee: 0 }.into(),
 			};
+			assert!(incoming_message.data.payload.decoded().is_ok());
 
 			let dispatch_weight = MessageDispatcher::dispatch_weight(&mut incoming_message);
 			assert_eq!(dispatch_weight, 1_000_000_000);
@@ -276,8 +395,61 @@ mod tests {
 					dispatch_result: true,
 					unspent_weight: 0,
 					dispatch_fee_paid_during_dispatch: false,
+					dispatch_error: Some(MessageDispatchError::Trapped(42)),
+				}
+			);
+		})
+	}
+
+	#[test]
+	fn xcm_messages_from_pass3dt_with_corrupted_payload_are_not_dispatched() {
+		type XcmExecutor = xcm_executor::XcmExecutor<XcmConfig>;
+		type MessageDispatcher = FromBridgedChainMessageDispatch<
+			WithPass3dtMessageBridge,
+			XcmExecutor,
+			XcmWeigher,
+			frame_support::traits::ConstU64<BASE_XCM_WEIGHT>,
+		>;
+
+		new_test_ext().execute_with(|| {
+			let mut incoming_message = DispatchMessage {
+				key: MessageKey { lane_id: bp_messages::LaneId([0, 0, 0, 0]), nonce: 1 },
+				data: MessageData { payload: vec![42], fee: 0 }.into(),
+			};
+			assert!(incoming_message.data.payload.decoded().is_err());
+
+			let dispatch_weight = MessageDispatcher::dispatch_weight(&mut incoming_message);
+			assert_eq!(dispatch_weight, 0);
+
+			let dispatch_result =
+				MessageDispatcher::dispatch(&AccountId::from([0u8; 32]), incoming_message);
+			assert_eq!(
+				dispatch_result,
+				MessageDispatchResult {
+					dispatch_result: false,
+					unspent_weight: 0,
+					dispatch_fee_paid_during_dispatch: false,
+					dispatch_error: Some(MessageDispatchError::UndecodablePayload),
 				}
 			);
 		})
 	}
+
+	#[test]
+	fn pass3dt_account_is_converted_to_derived_account() {
+		let location = MultiLocation::new(
+			1,
+			X2(
+				GlobalConsensus(Pass3dtNetwork::get()),
+				AccountId32 { network: None, id: [1u8; 32] },
+			),
+		);
+		assert_eq!(
+			<SovereignAccountOf as Convert<MultiLocation, AccountId>>::convert(location),
+			Ok(bp_pass3d::derive_account_id_from_remote(
+				bp_runtime::PASS3DT_CHAIN_ID,
+				[1u8; 32].into()
+			)),
+		);
+	}
 }
@@ -222,6 +222,7 @@ fn testnet_genesis(
 		},
 		bridge_pass3d_messages: BridgePass3dMessagesConfig {
 			owner: Some(get_account_id_from_seed::<sr25519::Public>(PASS3D_MESSAGES_PALLET_OWNER)),
+			opened_lanes: vec![bp_pass3dt::XCM_LANE],
 			..Default::default()
 		},
 		bridge_pass3d_parachain_messages: BridgePass3dParachainMessagesConfig {
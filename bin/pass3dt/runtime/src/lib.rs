@@ -77,11 +77,14 @@ pub use frame_system::Call as SystemCall;
 pub use pallet_balances::Call as BalancesCall;
 pub use pallet_bridge_grandpa::Call as BridgeGrandpaCall;
 pub use pallet_bridge_messages::Call as MessagesCall;
+pub use pallet_proxy::Call as ProxyCall;
 pub use pallet_sudo::Call as SudoCall;
 pub use pallet_timestamp::Call as TimestampCall;
 pub use pallet_xcm::Call as XcmCall;
 
-use bridge_runtime_common::generate_bridge_reject_obsolete_headers_and_messages;
+use bridge_runtime_common::{
+	generate_bridge_reject_obsolete_headers_and_messages, LookThroughProxy,
+};
 #[cfg(any(feature = "std", test))]
 pub use sp_runtime::BuildStorage;
 pub use sp_runtime::{Perbill, Permill};
@@ -162,7 +165,7 @@ parameter_types! {
 		read: 60_000_000, // ~0.06 ms = ~60 µs
 		write: 200_000_000, // ~0.2 ms = 200 µs
 	};
-	pub const SS58Prefix: u8 = 60;
+	pub const SS58Prefix: u16 = bp_pass3dt::SS58_PREFIX;
 }
 
 impl frame_system::Config for Runtime {
@@ -366,6 +369,76 @@ impl pallet_sudo::Config for Runtime {
 	type Call = Call;
 }
 
+/// The type used to represent the kinds of proxying allowed.
+#[derive(
+	Copy,
+	Clone,
+	Eq,
+	PartialEq,
+	Ord,
+	PartialOrd,
+	codec::Encode,
+	codec::Decode,
+	codec::MaxEncodedLen,
+	frame_support::RuntimeDebug,
+	scale_info::TypeInfo,
+)]
+pub enum ProxyType {
+	/// All calls may be made.
+	Any,
+	/// All calls, except balances transfers, may be made. This is the proxy type that is
+	/// suggested for relayer accounts.
+	NonTransfer,
+}
+
+impl Default for ProxyType {
+	fn default() -> Self {
+		ProxyType::Any
+	}
+}
+
+impl frame_support::traits::InstanceFilter<Call> for ProxyType {
+	fn filter(&self, call: &Call) -> bool {
+		match self {
+			ProxyType::Any => true,
+			ProxyType::NonTransfer => !matches!(call, Call::Balances(..)),
+		}
+	}
+
+	fn is_superset(&self, other: &Self) -> bool {
+		match (self, other) {
+			(ProxyType::Any, _) => true,
+			(_, ProxyType::Any) => false,
+			(ProxyType::NonTransfer, ProxyType::NonTransfer) => true,
+		}
+	}
+}
+
+parameter_types! {
+	pub const ProxyDepositBase: Balance = 1_000;
+	pub const ProxyDepositFactor: Balance = 100;
+	pub const MaxProxies: u16 = 32;
+	pub const AnnouncementDepositBase: Balance = 1_000;
+	pub const AnnouncementDepositFactor: Balance = 200;
+	pub const MaxPending: u16 = 32;
+}
+
+impl pallet_proxy::Config for Runtime {
+	type Event = Event;
+	type Call = Call;
+	type Currency = Balances;
+	type ProxyType = ProxyType;
+	type ProxyDepositBase = ProxyDepositBase;
+	type ProxyDepositFactor = ProxyDepositFactor;
+	type MaxProxies = MaxProxies;
+	// TODO: update me (https://github.com/paritytech/parity-bridges-common/issues/78)
+	type WeightInfo = ();
+	type MaxPending = MaxPending;
+	type CallHasher = Hashing;
+	type AnnouncementDepositBase = AnnouncementDepositBase;
+	type AnnouncementDepositFactor = AnnouncementDepositFactor;
+}
+
 parameter_types! {
 	/// Authorities are changing every 5 minutes.
 	pub const Period: BlockNumber = bp_pass3dt::SESSION_LENGTH;
@@ -391,6 +464,8 @@ parameter_types! {
 	// Note that once this is hit the pallet will essentially throttle incoming requests down to one
 	// call per block.
 	pub const MaxRequests: u32 = 50;
+	// Quarter of the block weight may be spent on importing bridged headers.
+	pub const MaxRequestsWeight: Weight = bp_pass3dt::MAXIMUM_BLOCK_WEIGHT / 4;
 }
 
 impl pallet_bridge_relayers::Config for Runtime {
@@ -420,32 +495,60 @@ parameter_types! {
 	pub const HeadersToKeep: u32 = 7 * bp_pass3d::DAYS as u32;
 }
 
+parameter_types! {
+	/// Number of justifications of the most recent headers to keep.
+	///
+	/// Assuming the worst case of every header being finalized, we will keep justifications
+	/// for at least an hour.
+	pub const JustificationsToKeep: u32 = bp_pass3d::HOURS as u32;
+	/// Maximal number of finality proofs in the single `submit_finality_proof_batch` call.
+	pub const MaxFinalityProofsInBatch: u32 = 16;
+}
+
 pub type Pass3dGrandpaInstance = ();
 impl pallet_bridge_grandpa::Config for Runtime {
+	type Event = Event;
 	type BridgedChain = bp_pass3d::Pass3d;
 	type MaxRequests = MaxRequests;
+	type MaxRequestsWeight = MaxRequestsWeight;
 	type HeadersToKeep = HeadersToKeep;
+	type JustificationsToKeep = JustificationsToKeep;
+	type MaxJustificationSize =
+		frame_support::traits::ConstU32<{ bp_pass3d::MAX_JUSTIFICATION_SIZE }>;
+	type MaxBridgedAuthorities =
+		frame_support::traits::ConstU32<{ bp_pass3d::MAX_AUTHORITIES_COUNT }>;
+	type MaxBridgedHeaderSize = frame_support::traits::ConstU32<{ bp_pass3d::MAX_HEADER_SIZE }>;
+	type MaxFinalityProofsInBatch = MaxFinalityProofsInBatch;
 
 	type WeightInfo = (); //pallet_bridge_grandpa::weights::Pass3dtWeight<Runtime>;
 }
 
-pub type WestendGrandpaInstance = pallet_bridge_grandpa::Instance1;
-impl pallet_bridge_grandpa::Config<WestendGrandpaInstance> for Runtime {
-	type BridgedChain = bp_westend::Westend;
-	type MaxRequests = MaxRequests;
-	type HeadersToKeep = HeadersToKeep;
-
-	type WeightInfo = (); //pallet_bridge_grandpa::weights::Pass3dtWeight<Runtime>;
-}
+// pub type WestendGrandpaInstance = pallet_bridge_grandpa::Instance1;
+// impl pallet_bridge_grandpa::Config<WestendGrandpaInstance> for Runtime {
+// 	type Event = Event;
+// 	type BridgedChain = bp_westend::Westend;
+// 	type MaxRequests = MaxRequests;
+// 	type HeadersToKeep = HeadersToKeep;
+//
+// 	type WeightInfo = (); //pallet_bridge_grandpa::weights::Pass3dtWeight<Runtime>;
+// }
 
 impl pallet_shift_session_manager::Config for Runtime {}
 
 parameter_types! {
 	pub const MaxMessagesToPruneAtOnce: bp_messages::MessageNonce = 8;
+	pub const MaxOutboundQueueLen: bp_messages::MessageNonce = 4096;
 	pub const MaxUnrewardedRelayerEntriesAtInboundLane: bp_messages::MessageNonce =
 		bp_pass3d::MAX_UNREWARDED_RELAYERS_IN_CONFIRMATION_TX;
 	pub const MaxUnconfirmedMessagesAtInboundLane: bp_messages::MessageNonce =
 		bp_pass3d::MAX_UNCONFIRMED_MESSAGES_IN_CONFIRMATION_TX;
+	pub const MaxRecentDeliveries: u32 = 64;
+	// a single account may send at most 64 messages per minute
+	pub const OutboundMessagesRateLimit: Option<pallet_bridge_messages::OutboundRateLimit<BlockNumber>> =
+		Some(pallet_bridge_messages::OutboundRateLimit {
+			max_messages: 64,
+			window: bp_pass3dt::time_units::MINUTES,
+		});
 	// `IdentityFee` is used by Pass3dt => we may use weight directly
 	pub const GetDeliveryConfirmationTransactionFee: Balance =
 		bp_pass3dt::MAX_SINGLE_MESSAGE_DELIVERY_CONFIRMATION_TX_WEIGHT as _;
@@ -462,14 +565,22 @@ impl pallet_bridge_messages::Config<WithPass3dMessagesInstance> for Runtime {
 	type WeightInfo = (); //pallet_bridge_messages::weights::Pass3dtWeight<Runtime>;
 	type Parameter = pass3d_messages::Pass3dtToPass3dMessagesParameter;
 	type MaxMessagesToPruneAtOnce = MaxMessagesToPruneAtOnce;
+	type MaxOutboundQueueLen = MaxOutboundQueueLen;
 	type MaxUnrewardedRelayerEntriesAtInboundLane = MaxUnrewardedRelayerEntriesAtInboundLane;
 	type MaxUnconfirmedMessagesAtInboundLane = MaxUnconfirmedMessagesAtInboundLane;
+	type MaxRecentDeliveries = MaxRecentDeliveries;
+	type MessageExpiryBlocks = ();
+	type OutboundMessagesRateLimit = OutboundMessagesRateLimit;
+	type RateLimitedSender = crate::pass3d_messages::RateLimitedSender;
+	type MessageSubmitter = ();
+	type IndexMessagesHistory = ();
 
 	type MaximalOutboundPayloadSize = crate::pass3d_messages::ToPass3dMaximalOutboundPayloadSize;
 	type OutboundPayload = crate::pass3d_messages::ToPass3dMessagePayload;
 	type OutboundMessageFee = Balance;
 
 	type InboundPayload = crate::pass3d_messages::FromPass3dMessagePayload;
+	type MaximalInboundPayloadSize = crate::pass3d_messages::FromPass3dMaximalInboundPayloadSize;
 	type InboundMessageFee = bp_pass3d::Balance;
 	type InboundRelayer = bp_pass3d::AccountId;
 
@@ -485,6 +596,9 @@ impl pallet_bridge_messages::Config<WithPass3dMessagesInstance> for Runtime {
 	type OnDeliveryConfirmed = ();
 
 	type SourceHeaderChain = crate::pass3d_messages::Pass3d;
+	type MaxMessagesProofSize = crate::pass3d_messages::FromPass3dMaximalMessagesProofSize;
+	type MaxMessagesDeliveryProofSize =
+		crate::pass3d_messages::ToPass3dMaximalMessagesDeliveryProofSize;
 	type MessageDispatch = crate::pass3d_messages::FromPass3dMessageDispatch;
 	type BridgedChainId = Pass3dChainId;
 }
@@ -583,11 +697,14 @@ construct_runtime!(
 
 		// Pass3d bridge modules.
 		BridgeRelayers: pallet_bridge_relayers::{Pallet, Call, Storage, Event<T>},
-		BridgePass3dGrandpa: pallet_bridge_grandpa::{Pallet, Call, Storage},
+		BridgePass3dGrandpa: pallet_bridge_grandpa::{Pallet, Call, Storage, Event<T>},
 		BridgePass3dMessages: pallet_bridge_messages::{Pallet, Call, Storage, Event<T>, Config<T>},
 
+		// Proxy support, so that relayers may use cold accounts to hold funds.
+		Proxy: pallet_proxy::{Pallet, Call, Storage, Event<T>},
+
 		// Westend bridge modules.
-		// BridgeWestendGrandpa: pallet_bridge_grandpa::<Instance1>::{Pallet, Call, Config<T>, Storage},
+		// BridgeWestendGrandpa: pallet_bridge_grandpa::<Instance1>::{Pallet, Call, Config<T>, Storage, Event<T>},
 		// BridgeWestendParachains: pallet_bridge_parachains::<Instance1>::{Pallet, Call, Storage, Event<T>},
 
 		// Pass3dParachain bridge modules.
@@ -602,9 +719,9 @@ construct_runtime!(
 generate_bridge_reject_obsolete_headers_and_messages! {
 	Call, AccountId,
 	// Grandpa
-	BridgePass3dGrandpa,
+	LookThroughProxy<Runtime, BridgePass3dGrandpa>,
 	//Messages
-	BridgePass3dMessages
+	LookThroughProxy<Runtime, BridgePass3dMessages>
 }
 
 /// The address format for describing accounts.
@@ -642,8 +759,16 @@ pub type Executive = frame_executive::Executive<
 	frame_system::ChainContext<Runtime>,
 	Runtime,
 	AllPalletsWithSystem,
+	Migrations,
 >;
 
+/// Storage migrations that are applied on runtime upgrade.
+pub type Migrations = (
+	pallet_bridge_grandpa::migration::v1::MigrateToV1<Runtime, Pass3dGrandpaInstance>,
+	pallet_bridge_messages::migration::v1::MigrateToV1<Runtime, WithPass3dMessagesInstance>,
+	pallet_bridge_messages::migration::v2::MigrateToV2<Runtime, WithPass3dMessagesInstance>,
+);
+
 impl_runtime_apis! {
 	impl sp_api::Core<Block> for Runtime {
 		fn version() -> RuntimeVersion {
@@ -852,9 +977,40 @@ impl_runtime_apis! {
 		fn best_finalized() -> Option<HeaderId<bp_pass3d::Hash, bp_pass3d::BlockNumber>> {
 			BridgePass3dGrandpa::best_finalized().map(|header| header.id())
 		}
+
+		fn finalized_header(
+			number: bp_pass3d::BlockNumber,
+		) -> Option<HeaderId<bp_pass3d::Hash, bp_pass3d::BlockNumber>> {
+			BridgePass3dGrandpa::finalized_header(number)
+		}
+
+		fn accepts_free_header(header: bp_pass3d::Header) -> bool {
+			BridgePass3dGrandpa::is_free_header(&header)
+		}
+
+		fn is_known_header(hash: bp_pass3d::Hash) -> bool {
+			BridgePass3dGrandpa::is_known_header(hash)
+		}
+
+		fn stored_justification(hash: bp_pass3d::Hash) -> Option<bp_runtime::EncodedJustification> {
+			BridgePass3dGrandpa::stored_justification(hash)
+		}
+	}
+
+	impl bp_pass3d::Pass3dFinalityOperatingModeApi<Block> for Runtime {
+		fn operating_state() -> bp_runtime::FinalityOperatingState<bp_pass3d::BlockNumber> {
+			BridgePass3dGrandpa::operating_state()
+		}
 	}
 
-	impl bp_pass3d::ToPass3dOutboundLaneApi<Block, Balance, ToPass3dMessagePayload> for Runtime {
+	impl bp_pass3d::Pass3dSyncedHeadersGrandpaInfoApi<Block> for Runtime {
+		fn synced_headers_grandpa_info(
+		) -> bp_runtime::SyncedHeadersGrandpaInfo<bp_pass3d::Hash, bp_pass3d::BlockNumber> {
+			BridgePass3dGrandpa::synced_headers_grandpa_info()
+		}
+	}
+
+	impl bp_pass3d::ToPass3dOutboundLaneApi<Block, Balance, ToPass3dMessagePayload, AccountId> for Runtime {
 		fn estimate_message_delivery_and_dispatch_fee(
 			_lane_id: bp_messages::LaneId,
 			payload: ToPass3dMessagePayload,
@@ -875,11 +1031,26 @@ impl_runtime_apis! {
 			bridge_runtime_common::messages_api::outbound_message_details::<
 				Runtime,
 				WithPass3dMessagesInstance,
+				pass3d_messages::WithPass3dMessageBridge,
 			>(lane, begin, end)
 		}
+
+		fn recent_relayers(
+			lane: bp_messages::LaneId,
+		) -> Vec<(AccountId, bp_messages::MessageNonce)> {
+			pallet_bridge_messages::Pallet::<Runtime, WithPass3dMessagesInstance>::recent_relayers(lane)
+		}
+
+		fn outbound_messages_allowance(sender: AccountId) -> Option<u32> {
+			pallet_bridge_messages::Pallet::<Runtime, WithPass3dMessagesInstance>::outbound_messages_allowance(sender)
+		}
+
+		fn outbound_lane_state(lane: bp_messages::LaneId) -> bp_messages::OutboundLaneData {
+			pallet_bridge_messages::Pallet::<Runtime, WithPass3dMessagesInstance>::outbound_lane_state(lane)
+		}
 	}
 
-	impl bp_pass3d::FromPass3dInboundLaneApi<Block, bp_pass3d::Balance> for Runtime {
+	impl bp_pass3d::FromPass3dInboundLaneApi<Block, bp_pass3d::Balance, bp_pass3d::AccountId> for Runtime {
 		fn message_details(
 			lane: bp_messages::LaneId,
 			messages: Vec<(bp_messages::MessagePayload, bp_messages::OutboundMessageDetails<bp_pass3d::Balance>)>,
@@ -889,6 +1060,16 @@ impl_runtime_apis! {
 				WithPass3dMessagesInstance,
 			>(lane, messages)
 		}
+
+		fn unrewarded_relayers_summary(
+			lane: bp_messages::LaneId,
+		) -> Vec<(bp_pass3d::AccountId, bp_messages::MessageNonce)> {
+			pallet_bridge_messages::Pallet::<Runtime, WithPass3dMessagesInstance>::unrewarded_relayers_summary(lane)
+		}
+
+		fn inbound_lane_state(lane: bp_messages::LaneId) -> bp_messages::InboundLaneState {
+			pallet_bridge_messages::Pallet::<Runtime, WithPass3dMessagesInstance>::inbound_lane_state(lane)
+		}
 	}
 
 	#[cfg(feature = "runtime-benchmarks")]
@@ -1055,4 +1236,102 @@ mod tests {
 		const MAX_CALL_SIZE: usize = 230; // value from polkadot-runtime tests
 		assert!(core::mem::size_of::<Call>() <= MAX_CALL_SIZE);
 	}
+
+	#[test]
+	fn ensure_chain_primitives_constants_are_correct() {
+		use codec::Encode;
+		use sp_core::Pair;
+
+		// signed extensions are constructed the same way as in the relay `sign_transaction`,
+		// but with mortal era and maximal nonce to get the largest encoding of both
+		const MAX_TX_EXTRA_BYTES_MARGIN: u32 = 2;
+		let signer = sp_core::sr25519::Pair::from_seed(&[42u8; 32]);
+		let genesis_hash = Hash::default();
+		let era = sp_runtime::generic::Era::mortal(64, 1_000);
+		// large enough call to have 4-byte length prefix of the encoded transaction
+		let call = Call::System(SystemCall::remark { remark: vec![0u8; 16 * 1024] });
+		let payload = SignedPayload::from_raw(
+			call,
+			(
+				frame_system::CheckNonZeroSender::new(),
+				frame_system::CheckSpecVersion::new(),
+				frame_system::CheckTxVersion::new(),
+				frame_system::CheckGenesis::new(),
+				frame_system::CheckEra::from(era),
+				frame_system::CheckNonce::from(Index::MAX),
+				frame_system::CheckWeight::new(),
+				pallet_transaction_payment::ChargeTransactionPayment::from(0),
+				BridgeRejectObsoleteHeadersAndMessages,
+			),
+			(
+				(),
+				VERSION.spec_version,
+				VERSION.transaction_version,
+				genesis_hash,
+				genesis_hash,
+				(),
+				(),
+				(),
+				(),
+			),
+		);
+		let signature = payload.using_encoded(|payload| signer.sign(payload));
+		let (call, extra, _) = payload.deconstruct();
+		bridge_runtime_common::integrity::assert_transaction_extra_bytes_within_margin(
+			UncheckedExtrinsic::new_signed(
+				call,
+				AccountId::from(signer.public()).into(),
+				signature.into(),
+				extra,
+			),
+			bp_pass3dt::TX_EXTRA_BYTES,
+			MAX_TX_EXTRA_BYTES_MARGIN,
+		);
+
+		bridge_runtime_common::integrity::assert_inbound_lane_limits::<
+			Runtime,
+			WithPass3dMessagesInstance,
+		>(bridge_runtime_common::integrity::AssertInboundLaneLimits {
+			max_unrewarded_relayers_in_confirmation_tx:
+				bp_pass3d::MAX_UNREWARDED_RELAYERS_IN_CONFIRMATION_TX,
+			max_unconfirmed_messages_in_confirmation_tx:
+				bp_pass3d::MAX_UNCONFIRMED_MESSAGES_IN_CONFIRMATION_TX,
+		});
+
+		assert_eq!(Period::get(), bp_pass3dt::SESSION_LENGTH);
+	}
+
+	#[test]
+	fn obsolete_finality_proofs_are_rejected_at_pool_validation() {
+		use sp_runtime::{traits::SignedExtension, transaction_validity::InvalidTransaction};
+
+		fn validate_submit_finality_proof(
+			number: bp_pass3d::BlockNumber,
+		) -> sp_runtime::transaction_validity::TransactionValidity {
+			let header = bp_test_utils::test_header::<bp_pass3d::Header>(number);
+			let call = Call::BridgePass3dGrandpa(BridgeGrandpaCall::submit_finality_proof {
+				finality_target: Box::new(header.clone()),
+				justification: bp_test_utils::make_default_justification(&header),
+			});
+			BridgeRejectObsoleteHeadersAndMessages.validate(
+				&AccountId::from([0u8; 32]),
+				&call,
+				&Default::default(),
+				0,
+			)
+		}
+
+		sp_io::TestExternalities::default().execute_with(|| {
+			pallet_bridge_grandpa::BestFinalized::<Runtime, Pass3dGrandpaInstance>::put((
+				10,
+				bp_pass3d::Hash::default(),
+			));
+
+			// when two relayers are racing, the proof of already finalized header is rejected
+			assert_eq!(validate_submit_finality_proof(5), InvalidTransaction::Stale.into());
+			assert_eq!(validate_submit_finality_proof(10), InvalidTransaction::Stale.into());
+			// while the proof of better header is accepted
+			assert!(validate_submit_finality_proof(11).is_ok());
+		});
+	}
 }
@@ -22,8 +22,9 @@ use bp_messages::{
 	source_chain::{SenderOrigin, TargetHeaderChain},
 	target_chain::{ProvedMessages, SourceHeaderChain},
 	InboundLaneData, LaneId, Message, MessageNonce, Parameter as MessagesParameter,
+	VerificationError,
 };
-use bp_runtime::{Chain, ChainId, PASS3DT_CHAIN_ID, PASS3D_CHAIN_ID};
+use bp_runtime::{messages::DispatchFeePayment, Chain, ChainId, PASS3DT_CHAIN_ID, PASS3D_CHAIN_ID};
 use bridge_runtime_common::messages::{
 	self, BasicConfirmationTransactionEstimation, MessageBridge, MessageTransaction,
 };
@@ -34,16 +35,19 @@ use frame_support::{
 	RuntimeDebug,
 };
 use scale_info::TypeInfo;
-use sp_runtime::{traits::Saturating, FixedPointNumber, FixedU128};
+use sp_runtime::{
+	traits::{Convert, Saturating},
+	FixedPointNumber, FixedU128,
+};
 use sp_std::convert::TryFrom;
 
-/// Default lane that is used to send messages to Pass3d.
-pub const DEFAULT_XCM_LANE_TO_PASS3D: LaneId = [0, 0, 0, 0];
 /// Initial value of `Pass3dToPass3dtConversionRate` parameter.
 pub const INITIAL_PASS3D_TO_PASS3DT_CONVERSION_RATE: FixedU128 =
 	FixedU128::from_inner(FixedU128::DIV);
 /// Initial value of `Pass3dFeeMultiplier` parameter.
 pub const INITIAL_PASS3D_FEE_MULTIPLIER: FixedU128 = FixedU128::from_inner(FixedU128::DIV);
+/// Initial value of `BridgedXcmVersion` parameter.
+pub const INITIAL_BRIDGED_XCM_VERSION: xcm::Version = xcm::latest::VERSION;
 /// Weight of 2 XCM instructions is for simple `Trap(42)` program, coming through bridge
 /// (it is prepended with `UniversalOrigin` instruction). It is used just for simplest manual
 /// tests, confirming that we don't break encoding somewhere between.
@@ -54,6 +58,21 @@ parameter_types! {
 	pub storage Pass3dToPass3dtConversionRate: FixedU128 = INITIAL_PASS3D_TO_PASS3DT_CONVERSION_RATE;
 	/// Fee multiplier value at Pass3d chain.
 	pub storage Pass3dFeeMultiplier: FixedU128 = INITIAL_PASS3D_FEE_MULTIPLIER;
+	/// XCM version that is supported by the Pass3d chain.
+	pub storage BridgedXcmVersion: xcm::Version = INITIAL_BRIDGED_XCM_VERSION;
+	/// Maximal size of Pass3d -> Pass3dt messages proof.
+	pub FromPass3dMaximalMessagesProofSize: u32 =
+		messages::target::maximal_messages_proof_size(
+			bp_pass3dt::Pass3dt::max_extrinsic_size(),
+			bp_pass3d::EXTRA_STORAGE_PROOF_SIZE,
+		);
+	/// Maximal size of Pass3dt -> Pass3d messages delivery proof.
+	pub ToPass3dMaximalMessagesDeliveryProofSize: u32 =
+		messages::source::maximal_messages_delivery_proof_size::<bp_pass3dt::AccountId>(
+			bp_pass3d::EXTRA_STORAGE_PROOF_SIZE,
+			bp_pass3d::MAX_UNREWARDED_RELAYERS_IN_CONFIRMATION_TX,
+			bp_pass3d::MAX_UNCONFIRMED_MESSAGES_IN_CONFIRMATION_TX,
+		);
 }
 
 /// Message payload for Pass3dt -> Pass3d messages.
@@ -66,6 +85,10 @@ pub type ToPass3dMessageVerifier =
 /// Message payload for Pass3d -> Pass3dt messages.
 pub type FromPass3dMessagePayload = messages::target::FromBridgedChainMessagePayload<Call>;
 
+/// Default maximal inbound payload size of Pass3d -> Pass3dt messages.
+pub type FromPass3dMaximalInboundPayloadSize =
+	messages::target::FromBridgedChainMaximalInboundPayloadSize<bp_pass3dt::Pass3dt>;
+
 /// Messages proof for Pass3d -> Pass3dt messages.
 pub type FromPass3dMessagesProof = messages::target::FromBridgedChainMessagesProof<bp_pass3d::Hash>;
 
@@ -96,6 +119,7 @@ impl MessageBridge for WithPass3dMessageBridge {
 	const THIS_CHAIN_ID: ChainId = PASS3DT_CHAIN_ID;
 	const BRIDGED_CHAIN_ID: ChainId = PASS3D_CHAIN_ID;
 	const BRIDGED_MESSAGES_PALLET_NAME: &'static str = bp_pass3dt::WITH_PASS3DT_MESSAGES_PALLET_NAME;
+	const DISPATCH_FEE_PAYMENT: DispatchFeePayment = DispatchFeePayment::AtTargetChain;
 
 	type ThisChain = Pass3dt;
 	type BridgedChain = Pass3d;
@@ -151,7 +175,7 @@ impl messages::ThisChainWithMessages for Pass3dt {
 			},
 		}
 
-		*lane == DEFAULT_XCM_LANE_TO_PASS3D || *lane == [0, 0, 0, 1]
+		bp_pass3dt::all_active_lanes().contains(lane)
 	}
 
 	fn maximal_pending_messages_at_outbound_lane() -> MessageNonce {
@@ -237,7 +261,7 @@ impl messages::BridgedChainWithMessages for Pass3d {
 }
 
 impl TargetHeaderChain<ToPass3dMessagePayload, bp_pass3dt::AccountId> for Pass3d {
-	type Error = &'static str;
+	type Error = VerificationError;
 	// The proof is:
 	// - hash of the header this proof has been created with;
 	// - the storage proof or one or several keys;
@@ -260,7 +284,7 @@ impl TargetHeaderChain<ToPass3dMessagePayload, bp_pass3dt::AccountId> for Pass3d
 }
 
 impl SourceHeaderChain<bp_pass3d::Balance> for Pass3d {
-	type Error = &'static str;
+	type Error = VerificationError;
 	// The proof is:
 	// - hash of the header this proof has been created with;
 	// - the storage proof or one or several keys;
@@ -287,11 +311,28 @@ impl SenderOrigin<crate::AccountId> for crate::Origin {
 	}
 }
 
+/// Account that is subject to the outbound messages rate limit.
+///
+/// Only messages that are sent by signed origins are rate-limited. Root and XCM origins (the
+/// XCM executor only sends messages from trusted locations) are exempt.
+pub struct RateLimitedSender;
+
+impl Convert<crate::Origin, Option<crate::AccountId>> for RateLimitedSender {
+	fn convert(origin: crate::Origin) -> Option<crate::AccountId> {
+		match origin.caller {
+			OriginCaller::system(frame_system::RawOrigin::Signed(account)) => Some(account),
+			_ => None,
+		}
+	}
+}
+
 /// Pass3dt -> Pass3d message lane pallet parameters.
 #[derive(RuntimeDebug, Clone, Encode, Decode, PartialEq, Eq, TypeInfo)]
 pub enum Pass3dtToPass3dMessagesParameter {
 	/// The conversion formula we use is: `Pass3dtTokens = Pass3dTokens * conversion_rate`.
 	Pass3dToPass3dtConversionRate(FixedU128),
+	/// XCM version that is supported by the Pass3d chain.
+	BridgedXcmVersion(xcm::Version),
 }
 
 impl MessagesParameter for Pass3dtToPass3dMessagesParameter {
@@ -299,6 +340,8 @@ impl MessagesParameter for Pass3dtToPass3dMessagesParameter {
 		match *self {
 			Pass3dtToPass3dMessagesParameter::Pass3dToPass3dtConversionRate(ref conversion_rate) =>
 				Pass3dToPass3dtConversionRate::set(conversion_rate),
+			Pass3dtToPass3dMessagesParameter::BridgedXcmVersion(ref version) =>
+				BridgedXcmVersion::set(version),
 		}
 	}
 }
@@ -320,7 +363,10 @@ mod tests {
 
 	#[test]
 	fn ensure_pass3dt_message_lane_weights_are_correct() {
-		type Weights = pallet_bridge_messages::weights::Pass3dtWeight<Runtime>;
+		// the check must use weights that are actually configured in the runtime, because the relay
+		// sizes message batches using `bp_pass3dt` constants
+		type Weights =
+			<Runtime as pallet_bridge_messages::Config<WithPass3dMessagesInstance>>::WeightInfo;
 
 		pallet_bridge_messages::ensure_weights_are_correct::<Weights>(
 			bp_pass3dt::DEFAULT_MESSAGE_DELIVERY_TX_WEIGHT,
@@ -402,4 +448,38 @@ mod tests {
 			.0,
 		);
 	}
+
+	#[test]
+	fn conversion_rate_parameter_name_is_not_changed() {
+		// the name is used by deployed relays to update the parameter, so it must not be changed
+		let parameter_name = bp_pass3dt::PASS3D_TO_PASS3DT_CONVERSION_RATE_PARAMETER_NAME;
+		assert_eq!(parameter_name, "Pass3dToPass3dtConversionRate");
+
+		// relays are also selecting the `Pass3dtToPass3dMessagesParameter` variant by this name
+		match *<Pass3dtToPass3dMessagesParameter as scale_info::TypeInfo>::type_info().type_def() {
+			scale_info::TypeDef::Variant(ref variants) =>
+				assert!(variants.variants().iter().any(|variant| *variant.name() == parameter_name)),
+			ref type_def => panic!("Unexpected type of messages parameter: {:?}", type_def),
+		}
+	}
+
+	#[test]
+	fn only_registered_lanes_are_open() {
+		use messages::ThisChainWithMessages;
+
+		let origin: crate::Origin = frame_system::RawOrigin::Root.into();
+		for lane_byte in 0..=u8::MAX {
+			for lane in [LaneId([0, 0, 0, lane_byte]), LaneId([lane_byte, 0, 0, 0])] {
+				assert_eq!(
+					Pass3dt::is_message_accepted(&origin, &lane),
+					bp_pass3dt::all_active_lanes().contains(&lane),
+					"Lane {:?} is either open and not registered or registered and not open",
+					lane,
+				);
+			}
+		}
+
+		assert!(bp_pass3dt::all_active_lanes().contains(&bp_pass3dt::XCM_LANE));
+		assert_eq!(bp_pass3dt::all_active_lanes(), bp_pass3d::all_active_lanes());
+	}
 }
@@ -17,9 +17,9 @@
 //! XCM configurations for the Pass3dt runtime.
 
 use super::{
-	pass3d_messages::{WithPass3dMessageBridge, DEFAULT_XCM_LANE_TO_PASS3D},
+	pass3d_messages::{BridgedXcmVersion, WithPass3dMessageBridge},
 	AccountId, AllPalletsWithSystem, Balances, Call, Event, Origin, Runtime,
-	WithPass3dMessagesInstance,XcmPallet,
+	WithPass3dMessagesInstance, XcmPallet,
 };
 use bp_messages::LaneId;
 use bp_pass3dt::WeightToFee;
@@ -38,6 +38,7 @@ use xcm_builder::{
 	CurrencyAdapter as XcmCurrencyAdapter, IsConcrete, SignedAccountId32AsNative,
 	SignedToAccountId32, SovereignSignedViaLocation, TakeWeightCredit, UsingComponents,
 };
+use xcm_executor::traits::Convert;
 
 parameter_types! {
 	/// The location of the `MLAU` token, from the context of this chain. Since this token is native to this
@@ -62,8 +63,30 @@ parameter_types! {
 pub type SovereignAccountOf = (
 	// We can directly alias an `AccountId32` into a local account.
 	AccountId32Aliases<ThisNetwork, AccountId>,
+	// Accounts of the Pass3d chain are mapped to derived local accounts.
+	Pass3dAccountId32ToAccountId,
 );
 
+/// Converts location of the `AccountId32` at the Pass3d chain into the derived Pass3dt account.
+///
+/// The account is derived using `bp_pass3dt::derive_account_id_from_remote`.
+pub struct Pass3dAccountId32ToAccountId;
+
+impl Convert<MultiLocation, AccountId> for Pass3dAccountId32ToAccountId {
+	fn convert(location: MultiLocation) -> Result<AccountId, MultiLocation> {
+		match location {
+			MultiLocation {
+				parents: 1,
+				interior: X2(GlobalConsensus(network), AccountId32 { id, .. }),
+			} if network == Pass3dNetwork::get() => Ok(bp_pass3dt::derive_account_id_from_remote(
+				bp_runtime::PASS3D_CHAIN_ID,
+				id.into(),
+			)),
+			_ => Err(location),
+		}
+	}
+}
+
 /// Our asset transactor. This is what allows us to interest with the runtime facilities from the
 /// point of view of XCM-only concepts like `MultiLocation` and `MultiAsset`.
 ///
@@ -211,7 +234,11 @@ impl XcmBridge for ToPass3dBridge {
 	}
 
 	fn xcm_lane() -> LaneId {
-		DEFAULT_XCM_LANE_TO_PASS3D
+		bp_pass3dt::XCM_LANE
+	}
+
+	fn bridged_xcm_version() -> xcm::Version {
+		BridgedXcmVersion::get()
 	}
 }
 
@@ -219,24 +246,29 @@ impl XcmBridge for ToPass3dBridge {
 mod tests {
 	use super::*;
 	use bp_messages::{
-		target_chain::{DispatchMessage, DispatchMessageData, MessageDispatch},
-		MessageKey,
+		target_chain::{DispatchMessage, MessageDispatch},
+		MessageData, MessageKey,
 	};
-	use bp_runtime::messages::MessageDispatchResult;
+	use bp_runtime::messages::{MessageDispatchError, MessageDispatchResult};
 	use bridge_runtime_common::messages::target::FromBridgedChainMessageDispatch;
 	use codec::Encode;
+	use xcm::VersionedXcm;
 
 	fn new_test_ext() -> sp_io::TestExternalities {
-		sp_io::TestExternalities::new(
+		let mut ext = sp_io::TestExternalities::new(
 			frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap(),
-		)
+		);
+		ext.execute_with(|| {
+			pallet_bridge_messages::OpenedLanes::<Runtime, crate::WithPass3dMessagesInstance>::insert(bp_pass3dt::XCM_LANE, ());
+		});
+		ext
 	}
 
 	#[test]
 	fn xcm_messages_are_sent_using_bridge_router() {
 		new_test_ext().execute_with(|| {
 			let xcm: Xcm<()> = vec![Instruction::Trap(42)].into();
-			let expected_fee = MultiAssets::from((Here, 4_259_858_152_u64));
+			let expected_fee = MultiAssets::from((Here, 4_259_858_153_u64));
 			let expected_hash =
 				([0u8, 0u8, 0u8, 0u8], 1u64).using_encoded(sp_io::hashing::blake2_256);
 
@@ -269,8 +301,12 @@ mod tests {
 			let xcm: Xcm<Call> = vec![Instruction::Trap(42)].into();
 
 			let mut incoming_message = DispatchMessage {
-				key: MessageKey { lane_id: [0, 0, 0, 0], nonce: 1 },
-				data: DispatchMessageData { payload: Ok((location, xcm).into()), fee: 0 },
+				key: MessageKey { lane_id: LaneId([0, 0, 0, 0]), nonce: 1 },
+				data: MessageData {
+					payload: (location, VersionedXcm::from(xcm)).encode().encode(),
+					fee: 0,
+				}
+				.into(),
 			};
 
 			let dispatch_weight = MessageDispatcher::dispatch_weight(&mut incoming_message);
@@ -284,8 +320,24 @@ mod tests {
 					dispatch_result: true,
 					unspent_weight: 0,
 					dispatch_fee_paid_during_dispatch: false,
+					dispatch_error: Some(MessageDispatchError::Trapped(42)),
 				}
 			);
 		})
 	}
+
+	#[test]
+	fn pass3d_account_is_converted_to_derived_account() {
+		let location = MultiLocation::new(
+			1,
+			X2(GlobalConsensus(Pass3dNetwork::get()), AccountId32 { network: None, id: [1u8; 32] }),
+		);
+		assert_eq!(
+			<SovereignAccountOf as Convert<MultiLocation, AccountId>>::convert(location),
+			Ok(bp_pass3dt::derive_account_id_from_remote(
+				bp_runtime::PASS3D_CHAIN_ID,
+				[1u8; 32].into()
+			)),
+		);
+	}
 }
@@ -192,7 +192,30 @@ fn testnet_genesis(
 		aura_ext: Default::default(),
 		bridge_millau_messages: BridgeMillauMessagesConfig {
 			owner: Some(get_account_id_from_seed::<sr25519::Public>(MILLAU_MESSAGES_PALLET_OWNER)),
+			opened_lanes: vec![
+				rialto_parachain_runtime::millau_messages::DEFAULT_XCM_LANE_TO_MILLAU,
+			],
 			..Default::default()
 		},
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn rialto_parachain_id_matches_local_test_network() {
+		let network = include_str!("../../../../deployments/networks/rialto-parachain.yml");
+		let args = network.split_whitespace().collect::<Vec<_>>();
+		let para_ids = args
+			.windows(2)
+			.filter(|pair| pair[0] == "--parachain-id")
+			.map(|pair| pair[1])
+			.collect::<Vec<_>>();
+		assert!(!para_ids.is_empty());
+		for para_id in para_ids {
+			assert_eq!(para_id.parse::<u32>(), Ok(bp_rialto_parachain::RIALTO_PARACHAIN_ID));
+		}
+	}
+}
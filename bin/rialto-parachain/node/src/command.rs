@@ -77,7 +77,7 @@ impl SubstrateCli for Cli {
 	}
 
 	fn load_spec(&self, id: &str) -> std::result::Result<Box<dyn sc_service::ChainSpec>, String> {
-		load_spec(id, self.parachain_id.unwrap_or(2000).into())
+		load_spec(id, self.parachain_id.unwrap_or(bp_rialto_parachain::RIALTO_PARACHAIN_ID).into())
 	}
 
 	fn native_runtime_version(_: &Box<dyn ChainSpec>) -> &'static RuntimeVersion {
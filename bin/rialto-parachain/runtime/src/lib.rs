@@ -27,7 +27,7 @@
 include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
 use crate::millau_messages::{
-	ToMillauMessagePayload, WithMillauMessageBridge, DEFAULT_XCM_LANE_TO_MILLAU,
+	BridgedXcmVersion, ToMillauMessagePayload, WithMillauMessageBridge, DEFAULT_XCM_LANE_TO_MILLAU,
 };
 
 use bridge_runtime_common::messages::{
@@ -459,6 +459,10 @@ impl XcmBridge for ToMillauBridge {
 	fn xcm_lane() -> bp_messages::LaneId {
 		DEFAULT_XCM_LANE_TO_MILLAU
 	}
+
+	fn bridged_xcm_version() -> xcm::Version {
+		BridgedXcmVersion::get()
+	}
 }
 
 impl pallet_xcm::Config for Runtime {
@@ -526,27 +530,49 @@ parameter_types! {
 	/// call per block.
 	pub const MaxRequests: u32 = 50;
 
+	/// Quarter of the block weight may be spent on importing bridged headers.
+	pub const MaxRequestsWeight: Weight = MAXIMUM_BLOCK_WEIGHT / 4;
+
 	/// Number of headers to keep.
 	///
 	/// Assuming the worst case of every header being finalized, we will keep headers at least for a
 	/// week.
 	pub const HeadersToKeep: u32 = 7 * bp_millau::DAYS as u32;
+
+	/// Number of justifications of the most recent headers to keep.
+	///
+	/// Assuming the worst case of every header being finalized, we will keep justifications
+	/// for at least an hour.
+	pub const JustificationsToKeep: u32 = bp_millau::HOURS as u32;
+	/// Maximal number of finality proofs in the single `submit_finality_proof_batch` call.
+	pub const MaxFinalityProofsInBatch: u32 = 16;
 }
 
 pub type MillauGrandpaInstance = ();
 impl pallet_bridge_grandpa::Config for Runtime {
+	type Event = Event;
 	type BridgedChain = bp_millau::Millau;
 	type MaxRequests = MaxRequests;
+	type MaxRequestsWeight = MaxRequestsWeight;
 	type HeadersToKeep = HeadersToKeep;
+	type JustificationsToKeep = JustificationsToKeep;
+	type MaxJustificationSize =
+		frame_support::traits::ConstU32<{ bp_millau::MAX_JUSTIFICATION_SIZE }>;
+	type MaxBridgedAuthorities =
+		frame_support::traits::ConstU32<{ bp_millau::MAX_AUTHORITIES_COUNT }>;
+	type MaxBridgedHeaderSize = frame_support::traits::ConstU32<{ bp_millau::MAX_HEADER_SIZE }>;
+	type MaxFinalityProofsInBatch = MaxFinalityProofsInBatch;
 	type WeightInfo = pallet_bridge_grandpa::weights::BridgeWeight<Runtime>;
 }
 
 parameter_types! {
 	pub const MaxMessagesToPruneAtOnce: bp_messages::MessageNonce = 8;
+	pub const MaxOutboundQueueLen: bp_messages::MessageNonce = 4096;
 	pub const MaxUnrewardedRelayerEntriesAtInboundLane: bp_messages::MessageNonce =
 		bp_millau::MAX_UNREWARDED_RELAYERS_IN_CONFIRMATION_TX;
 	pub const MaxUnconfirmedMessagesAtInboundLane: bp_messages::MessageNonce =
 		bp_millau::MAX_UNCONFIRMED_MESSAGES_IN_CONFIRMATION_TX;
+	pub const MaxRecentDeliveries: u32 = 64;
 	// `IdentityFee` is used by Rialto => we may use weight directly
 	pub const GetDeliveryConfirmationTransactionFee: Balance =
 		bp_rialto_parachain::MAX_SINGLE_MESSAGE_DELIVERY_CONFIRMATION_TX_WEIGHT as _;
@@ -562,14 +588,22 @@ impl pallet_bridge_messages::Config<WithMillauMessagesInstance> for Runtime {
 	type WeightInfo = pallet_bridge_messages::weights::BridgeWeight<Runtime>;
 	type Parameter = millau_messages::RialtoParachainToMillauMessagesParameter;
 	type MaxMessagesToPruneAtOnce = MaxMessagesToPruneAtOnce;
+	type MaxOutboundQueueLen = MaxOutboundQueueLen;
 	type MaxUnrewardedRelayerEntriesAtInboundLane = MaxUnrewardedRelayerEntriesAtInboundLane;
 	type MaxUnconfirmedMessagesAtInboundLane = MaxUnconfirmedMessagesAtInboundLane;
+	type MaxRecentDeliveries = MaxRecentDeliveries;
+	type MessageExpiryBlocks = ();
+	type OutboundMessagesRateLimit = ();
+	type RateLimitedSender = ();
+	type MessageSubmitter = ();
+	type IndexMessagesHistory = ();
 
 	type MaximalOutboundPayloadSize = crate::millau_messages::ToMillauMaximalOutboundPayloadSize;
 	type OutboundPayload = crate::millau_messages::ToMillauMessagePayload;
 	type OutboundMessageFee = Balance;
 
 	type InboundPayload = crate::millau_messages::FromMillauMessagePayload;
+	type MaximalInboundPayloadSize = crate::millau_messages::FromMillauMaximalInboundPayloadSize;
 	type InboundMessageFee = bp_millau::Balance;
 	type InboundRelayer = bp_millau::AccountId;
 
@@ -585,6 +619,9 @@ impl pallet_bridge_messages::Config<WithMillauMessagesInstance> for Runtime {
 	type OnDeliveryConfirmed = ();
 
 	type SourceHeaderChain = crate::millau_messages::Millau;
+	type MaxMessagesProofSize = crate::millau_messages::FromMillauMaximalMessagesProofSize;
+	type MaxMessagesDeliveryProofSize =
+		crate::millau_messages::ToMillauMaximalMessagesDeliveryProofSize;
 	type MessageDispatch = crate::millau_messages::FromMillauMessageDispatch;
 	type BridgedChainId = BridgedChainId;
 }
@@ -618,7 +655,7 @@ construct_runtime!(
 
 		// Millau bridge modules.
 		BridgeRelayers: pallet_bridge_relayers::{Pallet, Call, Storage, Event<T>},
-		BridgeMillauGrandpa: pallet_bridge_grandpa::{Pallet, Call, Storage},
+		BridgeMillauGrandpa: pallet_bridge_grandpa::{Pallet, Call, Storage, Event<T>},
 		BridgeMillauMessages: pallet_bridge_messages::{Pallet, Call, Storage, Event<T>, Config<T>},
 	}
 );
@@ -736,9 +773,40 @@ impl_runtime_apis! {
 		fn best_finalized() -> Option<HeaderId<bp_millau::Hash, bp_millau::BlockNumber>> {
 			BridgeMillauGrandpa::best_finalized().map(|header| header.id())
 		}
+
+		fn finalized_header(
+			number: bp_millau::BlockNumber,
+		) -> Option<HeaderId<bp_millau::Hash, bp_millau::BlockNumber>> {
+			BridgeMillauGrandpa::finalized_header(number)
+		}
+
+		fn accepts_free_header(header: bp_millau::Header) -> bool {
+			BridgeMillauGrandpa::is_free_header(&header)
+		}
+
+		fn is_known_header(hash: bp_millau::Hash) -> bool {
+			BridgeMillauGrandpa::is_known_header(hash)
+		}
+
+		fn stored_justification(hash: bp_millau::Hash) -> Option<bp_runtime::EncodedJustification> {
+			BridgeMillauGrandpa::stored_justification(hash)
+		}
 	}
 
-	impl bp_millau::ToMillauOutboundLaneApi<Block, Balance, ToMillauMessagePayload> for Runtime {
+	impl bp_millau::MillauFinalityOperatingModeApi<Block> for Runtime {
+		fn operating_state() -> bp_runtime::FinalityOperatingState<bp_millau::BlockNumber> {
+			BridgeMillauGrandpa::operating_state()
+		}
+	}
+
+	impl bp_millau::MillauSyncedHeadersGrandpaInfoApi<Block> for Runtime {
+		fn synced_headers_grandpa_info(
+		) -> bp_runtime::SyncedHeadersGrandpaInfo<bp_millau::Hash, bp_millau::BlockNumber> {
+			BridgeMillauGrandpa::synced_headers_grandpa_info()
+		}
+	}
+
+	impl bp_millau::ToMillauOutboundLaneApi<Block, Balance, ToMillauMessagePayload, AccountId> for Runtime {
 		fn estimate_message_delivery_and_dispatch_fee(
 			_lane_id: bp_messages::LaneId,
 			payload: ToMillauMessagePayload,
@@ -759,11 +827,26 @@ impl_runtime_apis! {
 			bridge_runtime_common::messages_api::outbound_message_details::<
 				Runtime,
 				WithMillauMessagesInstance,
+				millau_messages::WithMillauMessageBridge,
 			>(lane, begin, end)
 		}
+
+		fn recent_relayers(
+			lane: bp_messages::LaneId,
+		) -> Vec<(AccountId, bp_messages::MessageNonce)> {
+			pallet_bridge_messages::Pallet::<Runtime, WithMillauMessagesInstance>::recent_relayers(lane)
+		}
+
+		fn outbound_messages_allowance(sender: AccountId) -> Option<u32> {
+			pallet_bridge_messages::Pallet::<Runtime, WithMillauMessagesInstance>::outbound_messages_allowance(sender)
+		}
+
+		fn outbound_lane_state(lane: bp_messages::LaneId) -> bp_messages::OutboundLaneData {
+			pallet_bridge_messages::Pallet::<Runtime, WithMillauMessagesInstance>::outbound_lane_state(lane)
+		}
 	}
 
-	impl bp_millau::FromMillauInboundLaneApi<Block, bp_millau::Balance> for Runtime {
+	impl bp_millau::FromMillauInboundLaneApi<Block, bp_millau::Balance, bp_millau::AccountId> for Runtime {
 		fn message_details(
 			lane: bp_messages::LaneId,
 			messages: Vec<(bp_messages::MessagePayload, bp_messages::OutboundMessageDetails<bp_millau::Balance>)>,
@@ -773,6 +856,16 @@ impl_runtime_apis! {
 				WithMillauMessagesInstance,
 			>(lane, messages)
 		}
+
+		fn unrewarded_relayers_summary(
+			lane: bp_messages::LaneId,
+		) -> Vec<(bp_millau::AccountId, bp_messages::MessageNonce)> {
+			pallet_bridge_messages::Pallet::<Runtime, WithMillauMessagesInstance>::unrewarded_relayers_summary(lane)
+		}
+
+		fn inbound_lane_state(lane: bp_messages::LaneId) -> bp_messages::InboundLaneState {
+			pallet_bridge_messages::Pallet::<Runtime, WithMillauMessagesInstance>::inbound_lane_state(lane)
+		}
 	}
 
 	#[cfg(feature = "runtime-benchmarks")]
@@ -850,28 +943,36 @@ cumulus_pallet_parachain_system::register_validate_block!(
 mod tests {
 	use super::*;
 	use bp_messages::{
-		target_chain::{DispatchMessage, DispatchMessageData, MessageDispatch},
-		MessageKey,
+		target_chain::{DispatchMessage, MessageDispatch},
+		MessageData, MessageKey,
 	};
-	use bp_runtime::messages::MessageDispatchResult;
+	use bp_runtime::messages::{MessageDispatchError, MessageDispatchResult};
 	use bridge_runtime_common::messages::target::FromBridgedChainMessageDispatch;
 	use codec::Encode;
+	use xcm::VersionedXcm;
 
 	fn new_test_ext() -> sp_io::TestExternalities {
-		sp_io::TestExternalities::new(
+		let mut ext = sp_io::TestExternalities::new(
 			frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap(),
-		)
+		);
+		ext.execute_with(|| {
+			pallet_bridge_messages::OpenedLanes::<Runtime, WithMillauMessagesInstance>::insert(
+				DEFAULT_XCM_LANE_TO_MILLAU,
+				(),
+			);
+		});
+		ext
 	}
 
 	#[test]
 	fn xcm_messages_to_millau_are_sent() {
 		new_test_ext().execute_with(|| {
-			// the encoded message (origin ++ xcm) is 0x010109020419A8
+			// the encoded message (origin ++ xcm) is 0x01010902030419A8
 			let dest = (Parent, X1(GlobalConsensus(MillauNetwork::get())));
 			let xcm: Xcm<()> = vec![Instruction::Trap(42)].into();
 
 			let send_result = send_xcm::<XcmRouter>(dest.into(), xcm);
-			let expected_fee = MultiAssets::from((Here, Fungibility::Fungible(4_259_858_152_u128)));
+			let expected_fee = MultiAssets::from((Here, Fungibility::Fungible(4_259_858_153_u128)));
 			let expected_hash =
 				([0u8, 0u8, 0u8, 0u8], 1u64).using_encoded(sp_io::hashing::blake2_256);
 			assert_eq!(send_result, Ok((expected_hash, expected_fee)),);
@@ -894,8 +995,12 @@ mod tests {
 			let xcm: Xcm<Call> = vec![Instruction::Trap(42)].into();
 
 			let mut incoming_message = DispatchMessage {
-				key: MessageKey { lane_id: [0, 0, 0, 0], nonce: 1 },
-				data: DispatchMessageData { payload: Ok((location, xcm).into()), fee: 0 },
+				key: MessageKey { lane_id: bp_messages::LaneId([0, 0, 0, 0]), nonce: 1 },
+				data: MessageData {
+					payload: (location, VersionedXcm::from(xcm)).encode().encode(),
+					fee: 0,
+				}
+				.into(),
 			};
 
 			let dispatch_weight = MessageDispatcher::dispatch_weight(&mut incoming_message);
@@ -909,6 +1014,41 @@ mod tests {
 					dispatch_result: true,
 					unspent_weight: 0,
 					dispatch_fee_paid_during_dispatch: false,
+					dispatch_error: Some(MessageDispatchError::Trapped(42)),
+				}
+			);
+		})
+	}
+
+	#[test]
+	fn xcm_messages_from_millau_with_corrupted_payload_are_not_dispatched() {
+		type XcmExecutor = xcm_executor::XcmExecutor<XcmConfig>;
+		type MessageDispatcher = FromBridgedChainMessageDispatch<
+			WithMillauMessageBridge,
+			XcmExecutor,
+			XcmWeigher,
+			frame_support::traits::ConstU64<BASE_XCM_WEIGHT>,
+		>;
+
+		new_test_ext().execute_with(|| {
+			let mut incoming_message = DispatchMessage {
+				key: MessageKey { lane_id: bp_messages::LaneId([0, 0, 0, 0]), nonce: 1 },
+				data: MessageData { payload: vec![42], fee: 0 }.into(),
+			};
+			assert!(incoming_message.data.payload.decoded().is_err());
+
+			let dispatch_weight = MessageDispatcher::dispatch_weight(&mut incoming_message);
+			assert_eq!(dispatch_weight, 0);
+
+			let dispatch_result =
+				MessageDispatcher::dispatch(&AccountId::from([0u8; 32]), incoming_message);
+			assert_eq!(
+				dispatch_result,
+				MessageDispatchResult {
+					dispatch_result: false,
+					unspent_weight: 0,
+					dispatch_fee_paid_during_dispatch: false,
+					dispatch_error: Some(MessageDispatchError::UndecodablePayload),
 				}
 			);
 		})
@@ -25,8 +25,11 @@ use bp_messages::{
 	source_chain::{SenderOrigin, TargetHeaderChain},
 	target_chain::{ProvedMessages, SourceHeaderChain},
 	InboundLaneData, LaneId, Message, MessageNonce, Parameter as MessagesParameter,
+	VerificationError,
+};
+use bp_runtime::{
+	messages::DispatchFeePayment, Chain, ChainId, MILLAU_CHAIN_ID, RIALTO_PARACHAIN_CHAIN_ID,
 };
-use bp_runtime::{Chain, ChainId, MILLAU_CHAIN_ID, RIALTO_PARACHAIN_CHAIN_ID};
 use bridge_runtime_common::messages::{
 	self, BasicConfirmationTransactionEstimation, MessageBridge, MessageTransaction,
 };
@@ -41,12 +44,14 @@ use sp_runtime::{traits::Saturating, FixedPointNumber, FixedU128};
 use sp_std::convert::TryFrom;
 
 /// Default lane that is used to send messages to Millau.
-pub const DEFAULT_XCM_LANE_TO_MILLAU: LaneId = [0, 0, 0, 0];
+pub const DEFAULT_XCM_LANE_TO_MILLAU: LaneId = LaneId([0, 0, 0, 0]);
 /// Initial value of `MillauToRialtoParachainConversionRate` parameter.
 pub const INITIAL_MILLAU_TO_RIALTO_PARACHAIN_CONVERSION_RATE: FixedU128 =
 	FixedU128::from_inner(FixedU128::DIV);
 /// Initial value of `MillauFeeMultiplier` parameter.
 pub const INITIAL_MILLAU_FEE_MULTIPLIER: FixedU128 = FixedU128::from_inner(FixedU128::DIV);
+/// Initial value of `BridgedXcmVersion` parameter.
+pub const INITIAL_BRIDGED_XCM_VERSION: xcm::Version = xcm::latest::VERSION;
 /// Weight of 2 XCM instructions is for simple `Trap(42)` program, coming through bridge
 /// (it is prepended with `UniversalOrigin` instruction). It is used just for simplest manual
 /// tests, confirming that we don't break encoding somewhere between.
@@ -57,6 +62,21 @@ parameter_types! {
 	pub storage MillauToRialtoParachainConversionRate: FixedU128 = INITIAL_MILLAU_TO_RIALTO_PARACHAIN_CONVERSION_RATE;
 	/// Fee multiplier value at Millau chain.
 	pub storage MillauFeeMultiplier: FixedU128 = INITIAL_MILLAU_FEE_MULTIPLIER;
+	/// XCM version that is supported by the Millau chain.
+	pub storage BridgedXcmVersion: xcm::Version = INITIAL_BRIDGED_XCM_VERSION;
+	/// Maximal size of Millau -> RialtoParachain messages proof.
+	pub FromMillauMaximalMessagesProofSize: u32 =
+		messages::target::maximal_messages_proof_size(
+			bp_rialto_parachain::RialtoParachain::max_extrinsic_size(),
+			bp_millau::EXTRA_STORAGE_PROOF_SIZE,
+		);
+	/// Maximal size of RialtoParachain -> Millau messages delivery proof.
+	pub ToMillauMaximalMessagesDeliveryProofSize: u32 =
+		messages::source::maximal_messages_delivery_proof_size::<bp_rialto_parachain::AccountId>(
+			bp_millau::EXTRA_STORAGE_PROOF_SIZE,
+			bp_millau::MAX_UNREWARDED_RELAYERS_IN_CONFIRMATION_TX,
+			bp_millau::MAX_UNCONFIRMED_MESSAGES_IN_CONFIRMATION_TX,
+		);
 }
 
 /// Message payload for RialtoParachain -> Millau messages.
@@ -69,6 +89,12 @@ pub type ToMillauMessageVerifier =
 /// Message payload for Millau -> RialtoParachain messages.
 pub type FromMillauMessagePayload = messages::target::FromBridgedChainMessagePayload<crate::Call>;
 
+/// Default maximal inbound payload size of Millau -> RialtoParachain messages.
+pub type FromMillauMaximalInboundPayloadSize =
+	messages::target::FromBridgedChainMaximalInboundPayloadSize<
+		bp_rialto_parachain::RialtoParachain,
+	>;
+
 /// Call-dispatch based message dispatch for Millau -> RialtoParachain messages.
 pub type FromMillauMessageDispatch = messages::target::FromBridgedChainMessageDispatch<
 	WithMillauMessageBridge,
@@ -100,6 +126,7 @@ impl MessageBridge for WithMillauMessageBridge {
 	const BRIDGED_CHAIN_ID: ChainId = MILLAU_CHAIN_ID;
 	const BRIDGED_MESSAGES_PALLET_NAME: &'static str =
 		bp_rialto_parachain::WITH_RIALTO_PARACHAIN_MESSAGES_PALLET_NAME;
+	const DISPATCH_FEE_PAYMENT: DispatchFeePayment = DispatchFeePayment::AtTargetChain;
 
 	type ThisChain = RialtoParachain;
 	type BridgedChain = Millau;
@@ -154,7 +181,7 @@ impl messages::ThisChainWithMessages for RialtoParachain {
 			},
 		}
 
-		*lane == [0, 0, 0, 0] || *lane == [0, 0, 0, 1]
+		*lane == LaneId([0, 0, 0, 0]) || *lane == LaneId([0, 0, 0, 1])
 	}
 
 	fn maximal_pending_messages_at_outbound_lane() -> MessageNonce {
@@ -244,7 +271,7 @@ impl messages::BridgedChainWithMessages for Millau {
 }
 
 impl TargetHeaderChain<ToMillauMessagePayload, bp_rialto_parachain::AccountId> for Millau {
-	type Error = &'static str;
+	type Error = VerificationError;
 	// The proof is:
 	// - hash of the header this proof has been created with;
 	// - the storage proof of one or several keys;
@@ -267,7 +294,7 @@ impl TargetHeaderChain<ToMillauMessagePayload, bp_rialto_parachain::AccountId> f
 }
 
 impl SourceHeaderChain<bp_millau::Balance> for Millau {
-	type Error = &'static str;
+	type Error = VerificationError;
 	// The proof is:
 	// - hash of the header this proof has been created with;
 	// - the storage proof of one or several keys;
@@ -302,6 +329,8 @@ impl SenderOrigin<crate::AccountId> for crate::Origin {
 pub enum RialtoParachainToMillauMessagesParameter {
 	/// The conversion formula we use is: `RialtoParachainTokens = MillauTokens * conversion_rate`.
 	MillauToRialtoParachainConversionRate(FixedU128),
+	/// XCM version that is supported by the Millau chain.
+	BridgedXcmVersion(xcm::Version),
 }
 
 impl MessagesParameter for RialtoParachainToMillauMessagesParameter {
@@ -310,6 +339,8 @@ impl MessagesParameter for RialtoParachainToMillauMessagesParameter {
 			RialtoParachainToMillauMessagesParameter::MillauToRialtoParachainConversionRate(
 				ref conversion_rate,
 			) => MillauToRialtoParachainConversionRate::set(conversion_rate),
+			RialtoParachainToMillauMessagesParameter::BridgedXcmVersion(ref version) =>
+				BridgedXcmVersion::set(version),
 		}
 	}
 }
@@ -412,18 +412,38 @@ parameter_types! {
 	/// call per block.
 	pub const MaxRequests: u32 = 50;
 
+	/// Quarter of the block weight may be spent on importing bridged headers.
+	pub const MaxRequestsWeight: Weight = bp_rialto::MAXIMUM_BLOCK_WEIGHT / 4;
+
 	/// Number of headers to keep.
 	///
 	/// Assuming the worst case of every header being finalized, we will keep headers at least for a
 	/// week.
 	pub const HeadersToKeep: u32 = 7 * bp_rialto::DAYS as u32;
+
+	/// Number of justifications of the most recent headers to keep.
+	///
+	/// Assuming the worst case of every header being finalized, we will keep justifications
+	/// for at least an hour.
+	pub const JustificationsToKeep: u32 = bp_rialto::HOURS as u32;
+	/// Maximal number of finality proofs in the single `submit_finality_proof_batch` call.
+	pub const MaxFinalityProofsInBatch: u32 = 16;
 }
 
 pub type MillauGrandpaInstance = ();
 impl pallet_bridge_grandpa::Config for Runtime {
+	type Event = Event;
 	type BridgedChain = bp_millau::Millau;
 	type MaxRequests = MaxRequests;
+	type MaxRequestsWeight = MaxRequestsWeight;
 	type HeadersToKeep = HeadersToKeep;
+	type JustificationsToKeep = JustificationsToKeep;
+	type MaxJustificationSize =
+		frame_support::traits::ConstU32<{ bp_millau::MAX_JUSTIFICATION_SIZE }>;
+	type MaxBridgedAuthorities =
+		frame_support::traits::ConstU32<{ bp_millau::MAX_AUTHORITIES_COUNT }>;
+	type MaxBridgedHeaderSize = frame_support::traits::ConstU32<{ bp_millau::MAX_HEADER_SIZE }>;
+	type MaxFinalityProofsInBatch = MaxFinalityProofsInBatch;
 	type WeightInfo = pallet_bridge_grandpa::weights::BridgeWeight<Runtime>;
 }
 
@@ -431,10 +451,12 @@ impl pallet_shift_session_manager::Config for Runtime {}
 
 parameter_types! {
 	pub const MaxMessagesToPruneAtOnce: bp_messages::MessageNonce = 8;
+	pub const MaxOutboundQueueLen: bp_messages::MessageNonce = 4096;
 	pub const MaxUnrewardedRelayerEntriesAtInboundLane: bp_messages::MessageNonce =
 		bp_millau::MAX_UNREWARDED_RELAYERS_IN_CONFIRMATION_TX;
 	pub const MaxUnconfirmedMessagesAtInboundLane: bp_messages::MessageNonce =
 		bp_millau::MAX_UNCONFIRMED_MESSAGES_IN_CONFIRMATION_TX;
+	pub const MaxRecentDeliveries: u32 = 64;
 	// `IdentityFee` is used by Rialto => we may use weight directly
 	pub const GetDeliveryConfirmationTransactionFee: Balance =
 		bp_rialto::MAX_SINGLE_MESSAGE_DELIVERY_CONFIRMATION_TX_WEIGHT as _;
@@ -450,14 +472,22 @@ impl pallet_bridge_messages::Config<WithMillauMessagesInstance> for Runtime {
 	type WeightInfo = pallet_bridge_messages::weights::BridgeWeight<Runtime>;
 	type Parameter = millau_messages::RialtoToMillauMessagesParameter;
 	type MaxMessagesToPruneAtOnce = MaxMessagesToPruneAtOnce;
+	type MaxOutboundQueueLen = MaxOutboundQueueLen;
 	type MaxUnrewardedRelayerEntriesAtInboundLane = MaxUnrewardedRelayerEntriesAtInboundLane;
 	type MaxUnconfirmedMessagesAtInboundLane = MaxUnconfirmedMessagesAtInboundLane;
+	type MaxRecentDeliveries = MaxRecentDeliveries;
+	type MessageExpiryBlocks = ();
+	type OutboundMessagesRateLimit = ();
+	type RateLimitedSender = ();
+	type MessageSubmitter = ();
+	type IndexMessagesHistory = ();
 
 	type MaximalOutboundPayloadSize = crate::millau_messages::ToMillauMaximalOutboundPayloadSize;
 	type OutboundPayload = crate::millau_messages::ToMillauMessagePayload;
 	type OutboundMessageFee = Balance;
 
 	type InboundPayload = crate::millau_messages::FromMillauMessagePayload;
+	type MaximalInboundPayloadSize = crate::millau_messages::FromMillauMaximalInboundPayloadSize;
 	type InboundMessageFee = bp_millau::Balance;
 	type InboundRelayer = bp_millau::AccountId;
 
@@ -473,6 +503,9 @@ impl pallet_bridge_messages::Config<WithMillauMessagesInstance> for Runtime {
 	type OnDeliveryConfirmed = ();
 
 	type SourceHeaderChain = crate::millau_messages::Millau;
+	type MaxMessagesProofSize = crate::millau_messages::FromMillauMaximalMessagesProofSize;
+	type MaxMessagesDeliveryProofSize =
+		crate::millau_messages::ToMillauMaximalMessagesDeliveryProofSize;
 	type MessageDispatch = crate::millau_messages::FromMillauMessageDispatch;
 	type BridgedChainId = BridgedChainId;
 }
@@ -506,7 +539,7 @@ construct_runtime!(
 
 		// Millau bridge modules.
 		BridgeRelayers: pallet_bridge_relayers::{Pallet, Call, Storage, Event<T>},
-		BridgeMillauGrandpa: pallet_bridge_grandpa::{Pallet, Call, Storage},
+		BridgeMillauGrandpa: pallet_bridge_grandpa::{Pallet, Call, Storage, Event<T>},
 		BridgeMillauMessages: pallet_bridge_messages::{Pallet, Call, Storage, Event<T>, Config<T>},
 
 		// Parachain modules.
@@ -699,6 +732,37 @@ impl_runtime_apis! {
 		fn best_finalized() -> Option<HeaderId<bp_millau::Hash, bp_millau::BlockNumber>> {
 			BridgeMillauGrandpa::best_finalized().map(|header| header.id())
 		}
+
+		fn finalized_header(
+			number: bp_millau::BlockNumber,
+		) -> Option<HeaderId<bp_millau::Hash, bp_millau::BlockNumber>> {
+			BridgeMillauGrandpa::finalized_header(number)
+		}
+
+		fn accepts_free_header(header: bp_millau::Header) -> bool {
+			BridgeMillauGrandpa::is_free_header(&header)
+		}
+
+		fn is_known_header(hash: bp_millau::Hash) -> bool {
+			BridgeMillauGrandpa::is_known_header(hash)
+		}
+
+		fn stored_justification(hash: bp_millau::Hash) -> Option<bp_runtime::EncodedJustification> {
+			BridgeMillauGrandpa::stored_justification(hash)
+		}
+	}
+
+	impl bp_millau::MillauFinalityOperatingModeApi<Block> for Runtime {
+		fn operating_state() -> bp_runtime::FinalityOperatingState<bp_millau::BlockNumber> {
+			BridgeMillauGrandpa::operating_state()
+		}
+	}
+
+	impl bp_millau::MillauSyncedHeadersGrandpaInfoApi<Block> for Runtime {
+		fn synced_headers_grandpa_info(
+		) -> bp_runtime::SyncedHeadersGrandpaInfo<bp_millau::Hash, bp_millau::BlockNumber> {
+			BridgeMillauGrandpa::synced_headers_grandpa_info()
+		}
 	}
 
 	impl sp_transaction_pool::runtime_api::TaggedTransactionQueue<Block> for Runtime {
@@ -931,7 +995,7 @@ impl_runtime_apis! {
 		}
 	}
 
-	impl bp_millau::ToMillauOutboundLaneApi<Block, Balance, ToMillauMessagePayload> for Runtime {
+	impl bp_millau::ToMillauOutboundLaneApi<Block, Balance, ToMillauMessagePayload, AccountId> for Runtime {
 		fn estimate_message_delivery_and_dispatch_fee(
 			_lane_id: bp_messages::LaneId,
 			payload: ToMillauMessagePayload,
@@ -952,11 +1016,26 @@ impl_runtime_apis! {
 			bridge_runtime_common::messages_api::outbound_message_details::<
 				Runtime,
 				WithMillauMessagesInstance,
+				millau_messages::WithMillauMessageBridge,
 			>(lane, begin, end)
 		}
+
+		fn recent_relayers(
+			lane: bp_messages::LaneId,
+		) -> Vec<(AccountId, bp_messages::MessageNonce)> {
+			pallet_bridge_messages::Pallet::<Runtime, WithMillauMessagesInstance>::recent_relayers(lane)
+		}
+
+		fn outbound_messages_allowance(sender: AccountId) -> Option<u32> {
+			pallet_bridge_messages::Pallet::<Runtime, WithMillauMessagesInstance>::outbound_messages_allowance(sender)
+		}
+
+		fn outbound_lane_state(lane: bp_messages::LaneId) -> bp_messages::OutboundLaneData {
+			pallet_bridge_messages::Pallet::<Runtime, WithMillauMessagesInstance>::outbound_lane_state(lane)
+		}
 	}
 
-	impl bp_millau::FromMillauInboundLaneApi<Block, bp_millau::Balance> for Runtime {
+	impl bp_millau::FromMillauInboundLaneApi<Block, bp_millau::Balance, bp_millau::AccountId> for Runtime {
 		fn message_details(
 			lane: bp_messages::LaneId,
 			messages: Vec<(bp_messages::MessagePayload, bp_messages::OutboundMessageDetails<bp_millau::Balance>)>,
@@ -966,6 +1045,16 @@ impl_runtime_apis! {
 				WithMillauMessagesInstance,
 			>(lane, messages)
 		}
+
+		fn unrewarded_relayers_summary(
+			lane: bp_messages::LaneId,
+		) -> Vec<(bp_millau::AccountId, bp_messages::MessageNonce)> {
+			pallet_bridge_messages::Pallet::<Runtime, WithMillauMessagesInstance>::unrewarded_relayers_summary(lane)
+		}
+
+		fn inbound_lane_state(lane: bp_messages::LaneId) -> bp_messages::InboundLaneState {
+			pallet_bridge_messages::Pallet::<Runtime, WithMillauMessagesInstance>::inbound_lane_state(lane)
+		}
 	}
 }
 
@@ -990,4 +1079,38 @@ mod tests {
 		const MAX_CALL_SIZE: usize = 232;
 		assert!(core::mem::size_of::<Call>() <= MAX_CALL_SIZE);
 	}
+
+	#[test]
+	fn ensure_chain_primitives_constants_are_correct() {
+		bridge_runtime_common::integrity::assert_transaction_extra_bytes(
+			UncheckedExtrinsic::new_signed(
+				Call::System(SystemCall::remark { remark: vec![] }),
+				AccountId::from([0u8; 32]).into(),
+				sp_core::sr25519::Signature::from_raw([0u8; 64]).into(),
+				(
+					frame_system::CheckNonZeroSender::new(),
+					frame_system::CheckSpecVersion::new(),
+					frame_system::CheckTxVersion::new(),
+					frame_system::CheckGenesis::new(),
+					frame_system::CheckEra::from(sp_runtime::generic::Era::Immortal),
+					frame_system::CheckNonce::from(0),
+					frame_system::CheckWeight::new(),
+					pallet_transaction_payment::ChargeTransactionPayment::from(0),
+				),
+			),
+			bp_rialto::TX_EXTRA_BYTES,
+		);
+
+		bridge_runtime_common::integrity::assert_inbound_lane_limits::<
+			Runtime,
+			WithMillauMessagesInstance,
+		>(bridge_runtime_common::integrity::AssertInboundLaneLimits {
+			max_unrewarded_relayers_in_confirmation_tx:
+				bp_millau::MAX_UNREWARDED_RELAYERS_IN_CONFIRMATION_TX,
+			max_unconfirmed_messages_in_confirmation_tx:
+				bp_millau::MAX_UNCONFIRMED_MESSAGES_IN_CONFIRMATION_TX,
+		});
+
+		assert_eq!(EpochDuration::get(), bp_rialto::SESSION_LENGTH as u64);
+	}
 }
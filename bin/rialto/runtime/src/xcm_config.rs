@@ -209,7 +209,7 @@ impl XcmBridge for ToMillauBridge {
 	}
 
 	fn xcm_lane() -> bp_messages::LaneId {
-		[0, 0, 0, 0]
+		crate::millau_messages::DEFAULT_XCM_LANE_TO_MILLAU
 	}
 }
 
@@ -217,28 +217,33 @@ impl XcmBridge for ToMillauBridge {
 mod tests {
 	use super::*;
 	use bp_messages::{
-		target_chain::{DispatchMessage, DispatchMessageData, MessageDispatch},
-		MessageKey,
+		target_chain::{DispatchMessage, MessageDispatch},
+		MessageData, MessageKey,
 	};
-	use bp_runtime::messages::MessageDispatchResult;
+	use bp_runtime::messages::{MessageDispatchError, MessageDispatchResult};
 	use bridge_runtime_common::messages::target::FromBridgedChainMessageDispatch;
 	use codec::Encode;
+	use xcm::VersionedXcm;
 
 	fn new_test_ext() -> sp_io::TestExternalities {
-		sp_io::TestExternalities::new(
+		let mut ext = sp_io::TestExternalities::new(
 			frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap(),
-		)
+		);
+		ext.execute_with(|| {
+			pallet_bridge_messages::OpenedLanes::<Runtime, crate::WithMillauMessagesInstance>::insert(crate::millau_messages::DEFAULT_XCM_LANE_TO_MILLAU, ());
+		});
+		ext
 	}
 
 	#[test]
 	fn xcm_messages_to_millau_are_sent() {
 		new_test_ext().execute_with(|| {
-			// the encoded message (origin ++ xcm) is 0x010109030419A8
+			// the encoded message (origin ++ xcm) is 0x01010903030419A8
 			let dest = (Parent, X1(GlobalConsensus(MillauNetwork::get())));
 			let xcm: Xcm<()> = vec![Instruction::Trap(42)].into();
 
 			let send_result = send_xcm::<XcmRouter>(dest.into(), xcm);
-			let expected_fee = MultiAssets::from((Here, 4_259_858_152_u128));
+			let expected_fee = MultiAssets::from((Here, 4_259_858_153_u128));
 			let expected_hash =
 				([0u8, 0u8, 0u8, 0u8], 1u64).using_encoded(sp_io::hashing::blake2_256);
 			assert_eq!(send_result, Ok((expected_hash, expected_fee)),);
@@ -261,8 +266,12 @@ mod tests {
 			let xcm: Xcm<Call> = vec![Instruction::Trap(42)].into();
 
 			let mut incoming_message = DispatchMessage {
-				key: MessageKey { lane_id: [0, 0, 0, 0], nonce: 1 },
-				data: DispatchMessageData { payload: Ok((location, xcm).into()), fee: 0 },
+				key: MessageKey { lane_id: bp_messages::LaneId([0, 0, 0, 0]), nonce: 1 },
+				data: MessageData {
+					payload: (location, VersionedXcm::from(xcm)).encode().encode(),
+					fee: 0,
+				}
+				.into(),
 			};
 
 			let dispatch_weight = MessageDispatcher::dispatch_weight(&mut incoming_message);
@@ -276,6 +285,7 @@ mod tests {
 					dispatch_result: true,
 					unspent_weight: 0,
 					dispatch_fee_paid_during_dispatch: false,
+					dispatch_error: Some(MessageDispatchError::Trapped(42)),
 				}
 			);
 		})
@@ -22,10 +22,15 @@
 use crate::messages::MessageBridge;
 
 use bp_messages::MessageNonce;
-use bp_runtime::{Chain, ChainId};
+use bp_runtime::{Chain, ChainId, ChainIdName};
 use codec::Encode;
-use frame_support::{storage::generator::StorageValue, traits::Get};
+use frame_support::{
+	storage::generator::StorageValue,
+	traits::Get,
+	weights::{DispatchClass, Weight},
+};
 use frame_system::limits;
+use sp_runtime::{generic::UncheckedExtrinsic, traits::SignedExtension};
 
 /// Macro that ensures that the runtime configuration and chain primitives crate are sharing
 /// the same types (index, block number, hash, hasher, account id and header).
@@ -175,7 +180,8 @@ pub struct AssertChainConstants {
 /// In particular, this test ensures that:
 ///
 /// 1) block weight limits are matching;
-/// 2) block size limits are matching.
+/// 2) block size limits are matching;
+/// 3) maximal extrinsic size and weight, computed by the chain primitives crate, are matching.
 pub fn assert_chain_constants<R, C>(params: AssertChainConstants)
 where
 	R: frame_system::Config,
@@ -205,6 +211,110 @@ where
 		R::BlockWeights::get(),
 		params.block_weights,
 	);
+	assert_eq!(
+		*R::BlockLength::get().max.get(DispatchClass::Normal),
+		C::max_extrinsic_size(),
+		"Maximal extrinsic size from runtime ({}) differ from hardcoded: {}",
+		*R::BlockLength::get().max.get(DispatchClass::Normal),
+		C::max_extrinsic_size(),
+	);
+	assert_eq!(
+		R::BlockWeights::get()
+			.get(DispatchClass::Normal)
+			.max_extrinsic
+			.unwrap_or(Weight::MAX),
+		C::max_extrinsic_weight(),
+		"Maximal extrinsic weight from runtime ({:?}) differ from hardcoded: {}",
+		R::BlockWeights::get().get(DispatchClass::Normal).max_extrinsic,
+		C::max_extrinsic_weight(),
+	);
+}
+
+/// Test that the hardcoded number of extra bytes (`TX_EXTRA_BYTES`), included in the signed
+/// transaction apart from the encoded call itself, is not lower than the actual value.
+///
+/// The `transaction` is expected to be signed with immortal era, zero nonce and zero tip - that's
+/// how relayers are signing their transactions and that's what the constant is computed for.
+pub fn assert_transaction_extra_bytes<Address, Call, Signature, Extra>(
+	transaction: UncheckedExtrinsic<Address, Call, Signature, Extra>,
+	tx_extra_bytes: u32,
+) where
+	Address: Encode,
+	Call: Encode,
+	Signature: Encode,
+	Extra: SignedExtension,
+{
+	let extra_bytes_in_transaction =
+		transaction.encoded_size().saturating_sub(transaction.function.encoded_size());
+	assert!(
+		tx_extra_bytes as usize >= extra_bytes_in_transaction,
+		"Hardcoded number of extra bytes in transaction ({}) is lower than actual value: {}",
+		tx_extra_bytes,
+		extra_bytes_in_transaction,
+	);
+}
+
+/// Test that the hardcoded number of extra bytes (`TX_EXTRA_BYTES`), included in the signed
+/// transaction apart from the encoded call itself, is an upper bound of the actual value, which
+/// is larger than the actual value by at most `max_margin` bytes.
+///
+/// The `transaction` is expected to be signed with the largest possible encoding of the signed
+/// extensions (mortal era, maximal nonce) and to have a 4-byte length prefix.
+pub fn assert_transaction_extra_bytes_within_margin<Address, Call, Signature, Extra>(
+	transaction: UncheckedExtrinsic<Address, Call, Signature, Extra>,
+	tx_extra_bytes: u32,
+	max_margin: u32,
+) where
+	Address: Encode,
+	Call: Encode,
+	Signature: Encode,
+	Extra: SignedExtension,
+{
+	let extra_bytes_in_transaction =
+		transaction.encoded_size().saturating_sub(transaction.function.encoded_size());
+	assert_transaction_extra_bytes(transaction, tx_extra_bytes);
+	assert!(
+		tx_extra_bytes as usize <= extra_bytes_in_transaction.saturating_add(max_margin as usize),
+		"Hardcoded number of extra bytes in transaction ({}) is too large. Actual value: {}",
+		tx_extra_bytes,
+		extra_bytes_in_transaction,
+	);
+}
+
+/// Parameters for asserting inbound lane limits of the messages pallet.
+#[derive(Debug)]
+pub struct AssertInboundLaneLimits {
+	/// Hardcoded maximal number of unrewarded relayer entries in the confirmation transaction.
+	pub max_unrewarded_relayers_in_confirmation_tx: MessageNonce,
+	/// Hardcoded maximal number of unconfirmed messages in the confirmation transaction.
+	pub max_unconfirmed_messages_in_confirmation_tx: MessageNonce,
+}
+
+/// Test that the hardcoded `MAX_UNREWARDED_RELAYERS_IN_CONFIRMATION_TX` and
+/// `MAX_UNCONFIRMED_MESSAGES_IN_CONFIRMATION_TX` constants are matching inbound lane limits of
+/// the messages pallet instance.
+///
+/// The confirmation transaction carries the inbound lane data, so the bridged chain may only
+/// accept it if these constants are matching the actual limits.
+pub fn assert_inbound_lane_limits<R, MI>(params: AssertInboundLaneLimits)
+where
+	R: pallet_bridge_messages::Config<MI>,
+	MI: 'static,
+{
+	assert_eq!(
+		R::MaxUnrewardedRelayerEntriesAtInboundLane::get(),
+		params.max_unrewarded_relayers_in_confirmation_tx,
+		"MaxUnrewardedRelayerEntriesAtInboundLane ({}) differs from hardcoded: {}",
+		R::MaxUnrewardedRelayerEntriesAtInboundLane::get(),
+		params.max_unrewarded_relayers_in_confirmation_tx,
+	);
+	assert_eq!(
+		R::MaxUnconfirmedMessagesAtInboundLane::get(),
+		params.max_unconfirmed_messages_in_confirmation_tx,
+		"MaxUnconfirmedMessagesAtInboundLane ({}) differs from hardcoded: {}",
+		R::MaxUnconfirmedMessagesAtInboundLane::get(),
+		params.max_unconfirmed_messages_in_confirmation_tx,
+	);
 }
 
 /// Test that the constants, used in GRANDPA pallet configuration are valid.
@@ -214,9 +324,16 @@ where
 	GI: 'static,
 {
 	assert!(
-		R::MaxRequests::get() > 0,
-		"MaxRequests ({}) must be larger than zero",
+		pallet_bridge_grandpa::MAX_REQUESTS_BOUNDS.contains(&R::MaxRequests::get()),
+		"MaxRequests ({}) must be within {:?}",
 		R::MaxRequests::get(),
+		pallet_bridge_grandpa::MAX_REQUESTS_BOUNDS,
+	);
+	assert!(
+		pallet_bridge_grandpa::HEADERS_TO_KEEP_BOUNDS.contains(&R::HeadersToKeep::get()),
+		"HeadersToKeep ({}) must be within {:?}",
+		R::HeadersToKeep::get(),
+		pallet_bridge_grandpa::HEADERS_TO_KEEP_BOUNDS,
 	);
 }
 
@@ -243,6 +360,11 @@ where
 		"MaxMessagesToPruneAtOnce ({}) must be larger than zero",
 		R::MaxMessagesToPruneAtOnce::get(),
 	);
+	assert!(
+		R::MaxOutboundQueueLen::get() > 0,
+		"MaxOutboundQueueLen ({}) must be larger than zero",
+		R::MaxOutboundQueueLen::get(),
+	);
 	assert!(
 		R::MaxUnrewardedRelayerEntriesAtInboundLane::get() <= params.max_unrewarded_relayers_in_bridged_confirmation_tx,
 		"MaxUnrewardedRelayerEntriesAtInboundLane ({}) must be <= than the hardcoded value for bridged chain: {}",
@@ -255,7 +377,11 @@ where
 		R::MaxUnconfirmedMessagesAtInboundLane::get(),
 		params.max_unconfirmed_messages_in_bridged_confirmation_tx,
 	);
-	assert_eq!(R::BridgedChainId::get(), params.bridged_chain_id);
+	assert_eq!(
+		ChainIdName(R::BridgedChainId::get()),
+		ChainIdName(params.bridged_chain_id),
+		"Messages pallet is configured to bridge with unexpected chain",
+	);
 }
 
 /// Parameters for asserting bridge pallet names.
@@ -19,14 +19,18 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use bp_runtime::FilterCall;
+use frame_support::{dispatch::CallableCallFor, traits::IsSubType};
 use sp_runtime::transaction_validity::TransactionValidity;
+use sp_std::marker::PhantomData;
 use xcm::v3::NetworkId;
 
 pub mod messages;
 pub mod messages_api;
 pub mod messages_benchmarking;
 pub mod messages_extension;
+pub mod messages_generation;
 pub mod parachains_benchmarking;
+pub mod test_helpers;
 
 #[cfg(feature = "integrity-test")]
 pub mod integrity;
@@ -58,6 +62,28 @@ where
 	}
 }
 
+/// Bridge call filter that also applies to the bridge calls, wrapped into the
+/// `pallet_proxy::Call::proxy` call.
+///
+/// Relayers may be using proxy accounts to submit bridge transactions. Without this adapter,
+/// obsolete bridge calls that are wrapped into the proxy call, would be accepted by the
+/// `BridgeRejectObsoleteHeadersAndMessages` signed extension.
+pub struct LookThroughProxy<Runtime, Filter>(PhantomData<(Runtime, Filter)>);
+
+impl<Call, Runtime, Filter> BridgeRuntimeFilterCall<Call> for LookThroughProxy<Runtime, Filter>
+where
+	Call: IsSubType<CallableCallFor<pallet_proxy::Pallet<Runtime>, Runtime>>,
+	Runtime: pallet_proxy::Config<Call = Call>,
+	Filter: BridgeRuntimeFilterCall<Call>,
+{
+	fn validate(call: &Call) -> TransactionValidity {
+		match call.is_sub_type() {
+			Some(pallet_proxy::Call::<Runtime>::proxy { ref call, .. }) => Self::validate(call),
+			_ => Filter::validate(call),
+		}
+	}
+}
+
 /// Declares a runtime-specific `BridgeRejectObsoleteHeadersAndMessages` signed extension.
 ///
 /// ## Example
@@ -24,9 +24,13 @@ use bp_messages::{
 	source_chain::LaneMessageVerifier,
 	target_chain::{DispatchMessage, MessageDispatch, ProvedLaneMessages, ProvedMessages},
 	InboundLaneData, LaneId, Message, MessageData, MessageKey, MessageNonce, OutboundLaneData,
+	VerificationError,
 };
 use bp_polkadot_core::parachains::{ParaHash, ParaHasher, ParaId};
-use bp_runtime::{messages::MessageDispatchResult, ChainId, Size, StorageProofChecker};
+use bp_runtime::{
+	messages::{DispatchFeePayment, MessageDispatchError, MessageDispatchResult},
+	ChainId, Size, StorageProofChecker,
+};
 use codec::{Decode, DecodeLimit, Encode, MaxEncodedLen};
 use frame_support::{traits::Get, weights::Weight, RuntimeDebug};
 use hash_db::Hasher;
@@ -37,7 +41,7 @@ use sp_runtime::{
 };
 use sp_std::{cmp::PartialOrd, convert::TryFrom, fmt::Debug, marker::PhantomData, vec::Vec};
 use sp_trie::StorageProof;
-use xcm::latest::prelude::*;
+use xcm::{latest::prelude::*, IntoVersion, Version as XcmVersion, VersionedXcm};
 
 /// Bidirectional message bridge.
 pub trait MessageBridge {
@@ -52,6 +56,11 @@ pub trait MessageBridge {
 	///
 	/// Should be the name that is used in the `construct_runtime!()` macro.
 	const BRIDGED_MESSAGES_PALLET_NAME: &'static str;
+	/// Where the dispatch fee of messages, sent over this bridge, is paid.
+	///
+	/// Relayers are using this to compute the cost of message delivery. If the fee is paid at
+	/// the source chain, relayer is paying for the message dispatch at the target chain.
+	const DISPATCH_FEE_PAYMENT: DispatchFeePayment;
 
 	/// This chain in context of message bridge.
 	type ThisChain: ThisChainWithMessages;
@@ -100,6 +109,15 @@ pub struct MessageTransaction<Weight> {
 	pub size: u32,
 }
 
+/// Message dispatch parameters, declared by the message payload.
+#[derive(RuntimeDebug, Clone, Copy, PartialEq, Eq)]
+pub struct DeclaredMessageDispatch {
+	/// Dispatch weight of the message at the target chain.
+	pub dispatch_weight: Weight,
+	/// Where the dispatch fee of the message is paid.
+	pub dispatch_fee_payment: DispatchFeePayment,
+}
+
 /// Helper trait for estimating the size and weight of a single message delivery confirmation
 /// transaction.
 pub trait ConfirmationTransactionEstimation<Weight> {
@@ -175,6 +193,15 @@ pub trait BridgedChainWithMessages: ChainWithMessages {
 	/// that the message is too heavy to be sent over the bridge and shall be rejected.
 	fn verify_dispatch_weight(message_payload: &[u8]) -> bool;
 
+	/// Returns dispatch parameters, declared by the message payload.
+	///
+	/// Returns `None` if payload doesn't declare them (e.g. if it is an opaque XCM message, that
+	/// may only be weighed at the Bridged chain). Then relayers are given zero dispatch weight
+	/// and the `MessageBridge::DISPATCH_FEE_PAYMENT`.
+	fn declared_message_dispatch(_message_payload: &[u8]) -> Option<DeclaredMessageDispatch> {
+		None
+	}
+
 	/// Estimate size and weight of single message delivery transaction at the Bridged chain.
 	fn estimate_delivery_transaction(
 		message_payload: &[u8],
@@ -284,6 +311,23 @@ pub mod source {
 	pub type ParsedMessagesDeliveryProofFromBridgedChain<B> =
 		(LaneId, InboundLaneData<AccountIdOf<ThisChain<B>>>);
 
+	/// Return maximal size of messages delivery proof, given the storage proof overhead and limits
+	/// of the inbound lane at the Bridged chain.
+	///
+	/// The delivery proof contains the inbound lane data, where relayers are identified by This
+	/// chain accounts.
+	pub fn maximal_messages_delivery_proof_size<ThisChainAccountId: MaxEncodedLen>(
+		bridged_extra_storage_proof_size: u32,
+		bridged_max_unrewarded_relayers: MessageNonce,
+		bridged_max_unconfirmed_messages: MessageNonce,
+	) -> u32 {
+		InboundLaneData::<ThisChainAccountId>::encoded_size_hint_u32(
+			bridged_max_unrewarded_relayers as _,
+			bridged_max_unconfirmed_messages as _,
+		)
+		.saturating_add(bridged_extra_storage_proof_size)
+	}
+
 	/// Message verifier that is doing all basic checks.
 	///
 	/// This verifier assumes following:
@@ -301,17 +345,6 @@ pub mod source {
 	#[derive(RuntimeDebug)]
 	pub struct FromThisChainMessageVerifier<B>(PhantomData<B>);
 
-	/// The error message returned from LaneMessageVerifier when outbound lane is disabled.
-	pub const MESSAGE_REJECTED_BY_OUTBOUND_LANE: &str =
-		"The outbound message lane has rejected the message.";
-	/// The error message returned from LaneMessageVerifier when too many pending messages at the
-	/// lane.
-	pub const TOO_MANY_PENDING_MESSAGES: &str = "Too many pending messages at the lane.";
-	/// The error message returned from LaneMessageVerifier when call origin is mismatch.
-	pub const BAD_ORIGIN: &str = "Unable to match the source origin to expected target origin.";
-	/// The error message returned from LaneMessageVerifier when the message fee is too low.
-	pub const TOO_LOW_FEE: &str = "Provided fee is below minimal threshold required by the lane.";
-
 	impl<B>
 		LaneMessageVerifier<
 			OriginOf<ThisChain<B>>,
@@ -325,7 +358,7 @@ pub mod source {
 			+ Into<Result<frame_system::RawOrigin<AccountIdOf<ThisChain<B>>>, OriginOf<ThisChain<B>>>>,
 		AccountIdOf<ThisChain<B>>: PartialEq + Clone,
 	{
-		type Error = &'static str;
+		type Error = VerificationError;
 
 		fn verify_message(
 			submitter: &OriginOf<ThisChain<B>>,
@@ -336,7 +369,7 @@ pub mod source {
 		) -> Result<(), Self::Error> {
 			// reject message if lane is blocked
 			if !ThisChain::<B>::is_message_accepted(submitter, lane) {
-				return Err(MESSAGE_REJECTED_BY_OUTBOUND_LANE)
+				return Err(VerificationError::ClosedLane)
 			}
 
 			// reject message if there are too many pending messages at this lane
@@ -345,18 +378,19 @@ pub mod source {
 				.latest_generated_nonce
 				.saturating_sub(lane_outbound_data.latest_received_nonce);
 			if pending_messages > max_pending_messages {
-				return Err(TOO_MANY_PENDING_MESSAGES)
+				return Err(VerificationError::TooManyPendingMessages)
 			}
 
 			let minimal_fee_in_this_tokens = estimate_message_dispatch_and_delivery_fee::<B>(
 				payload,
 				B::RELAYER_FEE_PERCENT,
 				None,
-			)?;
+			)
+			.map_err(VerificationError::Other)?;
 
 			// compare with actual fee paid
 			if *delivery_and_dispatch_fee < minimal_fee_in_this_tokens {
-				return Err(TOO_LOW_FEE)
+				return Err(VerificationError::TooLowFee)
 			}
 
 			Ok(())
@@ -375,9 +409,9 @@ pub mod source {
 	/// check) that would reject message (see `FromThisChainMessageVerifier`).
 	pub fn verify_chain_message<B: MessageBridge>(
 		payload: &FromThisChainMessagePayload,
-	) -> Result<(), &'static str> {
+	) -> Result<(), VerificationError> {
 		if !BridgedChain::<B>::verify_dispatch_weight(payload) {
-			return Err("Incorrect message weight declared")
+			return Err(VerificationError::InvalidDispatchWeight)
 		}
 
 		// The maximal size of extrinsic at Substrate-based chain depends on the
@@ -391,7 +425,7 @@ pub mod source {
 		// transaction also contains signatures and signed extensions. Because of this, we reserve
 		// 1/3 of the the maximal extrinsic weight for this data.
 		if payload.len() > maximal_message_size::<B>() as usize {
-			return Err("The message is too large to be sent over the lane")
+			return Err(VerificationError::MessageTooLarge)
 		}
 
 		Ok(())
@@ -446,7 +480,7 @@ pub mod source {
 	/// parachains, please use the `verify_messages_delivery_proof_from_parachain`.
 	pub fn verify_messages_delivery_proof<B: MessageBridge, ThisRuntime, GrandpaInstance: 'static>(
 		proof: FromBridgedChainMessagesDeliveryProof<HashOf<BridgedChain<B>>>,
-	) -> Result<ParsedMessagesDeliveryProofFromBridgedChain<B>, &'static str>
+	) -> Result<ParsedMessagesDeliveryProofFromBridgedChain<B>, VerificationError>
 	where
 		ThisRuntime: pallet_bridge_grandpa::Config<GrandpaInstance>,
 		HashOf<BridgedChain<B>>: Into<
@@ -467,7 +501,7 @@ pub mod source {
 				>,
 			>(lane, storage),
 		)
-		.map_err(<&'static str>::from)?
+		.map_err(|err| VerificationError::Other(err.into()))?
 	}
 
 	/// Verify proof of This -> Bridged chain messages delivery.
@@ -485,7 +519,7 @@ pub mod source {
 	>(
 		bridged_parachain: ParaId,
 		proof: FromBridgedChainMessagesDeliveryProof<HashOf<BridgedChain<B>>>,
-	) -> Result<ParsedMessagesDeliveryProofFromBridgedChain<B>, &'static str>
+	) -> Result<ParsedMessagesDeliveryProofFromBridgedChain<B>, VerificationError>
 	where
 		B: MessageBridge,
 		B::BridgedChain: ChainWithMessages<Hash = ParaHash>,
@@ -494,21 +528,20 @@ pub mod source {
 	{
 		let FromBridgedChainMessagesDeliveryProof { bridged_header_hash, storage_proof, lane } =
 			proof;
-		pallet_bridge_parachains::Pallet::<ThisRuntime, ParachainsInstance>::parse_finalized_storage_proof(
+		pallet_bridge_parachains::Pallet::<ThisRuntime, ParachainsInstance>::parse_finalized_storage_proof::<BridgedHeader, _>(
 			bridged_parachain,
 			bridged_header_hash,
 			StorageProof::new(storage_proof),
-			|para_head| BridgedHeader::decode(&mut &para_head.0[..]).ok().map(|h| *h.state_root()),
 			|storage| do_verify_messages_delivery_proof::<B, ParaHasher>(lane, storage),
 		)
-		.map_err(<&'static str>::from)?
+		.map_err(|err| VerificationError::Other(err.into()))?
 	}
 
 	/// The essense of This -> Bridged chain messages delivery proof verification.
 	fn do_verify_messages_delivery_proof<B: MessageBridge, H: Hasher>(
 		lane: LaneId,
 		storage: bp_runtime::StorageProofChecker<H>,
-	) -> Result<ParsedMessagesDeliveryProofFromBridgedChain<B>, &'static str> {
+	) -> Result<ParsedMessagesDeliveryProofFromBridgedChain<B>, VerificationError> {
 		// Messages delivery proof is just proof of single storage key read => any error
 		// is fatal.
 		let storage_inbound_lane_data_key = bp_messages::storage_keys::inbound_lane_data_key(
@@ -517,10 +550,10 @@ pub mod source {
 		);
 		let raw_inbound_lane_data = storage
 			.read_value(storage_inbound_lane_data_key.0.as_ref())
-			.map_err(|_| "Failed to read inbound lane state from storage proof")?
-			.ok_or("Inbound lane state is missing from the messages proof")?;
+			.map_err(|_| VerificationError::MissingInboundLaneState)?
+			.ok_or(VerificationError::MissingInboundLaneState)?;
 		let inbound_lane_data = InboundLaneData::decode(&mut &raw_inbound_lane_data[..])
-			.map_err(|_| "Failed to decode inbound lane state from the proof")?;
+			.map_err(|_| VerificationError::FailedToDecodeInboundLaneState)?;
 
 		Ok((lane, inbound_lane_data))
 	}
@@ -545,6 +578,14 @@ pub mod source {
 		fn build_destination() -> MultiLocation;
 		/// Return message lane used to deliver XCM messages.
 		fn xcm_lane() -> LaneId;
+		/// Return XCM version that is supported by the bridged chain.
+		///
+		/// Version discovery doesn't work across the bridge, so XCM programs are converted to
+		/// this version before they're sent. The route itself is always encoded using the
+		/// latest version, because it is a part of bridge protocol.
+		fn bridged_xcm_version() -> XcmVersion {
+			xcm::latest::VERSION
+		}
 	}
 
 	/// XCM bridge adapter for `bridge-messages` pallet.
@@ -568,7 +609,22 @@ pub mod source {
 			}
 
 			let route = T::build_destination();
-			let msg = (route, msg.take().ok_or(SendError::MissingArgument)?).encode();
+			let version = T::bridged_xcm_version();
+			let xcm = VersionedXcm::from(msg.take().ok_or(SendError::MissingArgument)?);
+			let xcm = match xcm.into_version(version) {
+				Ok(xcm) => xcm,
+				Err(()) => {
+					log::trace!(
+						target: "runtime::bridge",
+						"Failed to convert XCM message to {:?} to version {}",
+						bp_runtime::ChainIdName(T::MessageBridge::BRIDGED_CHAIN_ID),
+						version,
+					);
+					*dest = Some(d);
+					return Err(SendError::DestinationUnsupported)
+				},
+			};
+			let msg = (route, xcm).encode();
 
 			let fee = estimate_message_dispatch_and_delivery_fee::<T::MessageBridge>(
 				&msg,
@@ -581,7 +637,7 @@ pub mod source {
 					log::trace!(
 						target: "runtime::bridge",
 						"Failed to comupte fee for XCM message to {:?}: {:?}",
-						T::MessageBridge::BRIDGED_CHAIN_ID,
+						bp_runtime::ChainIdName(T::MessageBridge::BRIDGED_CHAIN_ID),
 						e,
 					);
 					*dest = Some(d);
@@ -612,7 +668,7 @@ pub mod source {
 						"Sent XCM message {:?}/{} to {:?}: {:?}",
 						lane,
 						artifacts.nonce,
-						T::MessageBridge::BRIDGED_CHAIN_ID,
+						bp_runtime::ChainIdName(T::MessageBridge::BRIDGED_CHAIN_ID),
 						hash,
 					);
 					hash
@@ -622,7 +678,7 @@ pub mod source {
 						target: "runtime::bridge",
 						"Failed to send XCM message over lane {:?} to {:?}: {:?}",
 						lane,
-						T::MessageBridge::BRIDGED_CHAIN_ID,
+						bp_runtime::ChainIdName(T::MessageBridge::BRIDGED_CHAIN_ID),
 						e,
 					);
 					SendError::Transport("Bridge has rejected the message")
@@ -647,14 +703,13 @@ pub mod target {
 	impl<Call: Decode> Decode for FromBridgedChainMessagePayload<Call> {
 		fn decode<I: codec::Input>(input: &mut I) -> Result<Self, codec::Error> {
 			let _: codec::Compact<u32> = Decode::decode(input)?;
-			type XcmPairType<Call> = (xcm::v3::MultiLocation, xcm::v3::Xcm<Call>);
-			Ok(FromBridgedChainMessagePayload {
-				xcm: XcmPairType::<Call>::decode_with_depth_limit(
-					sp_api::MAX_EXTRINSIC_DEPTH,
-					input,
-				)?,
-				weight: None,
-			})
+			type XcmPairType<Call> = (xcm::v3::MultiLocation, VersionedXcm<Call>);
+			let (location, xcm) =
+				XcmPairType::<Call>::decode_with_depth_limit(sp_api::MAX_EXTRINSIC_DEPTH, input)?;
+			// the sender may have downgraded the message to the version we support
+			let xcm = xcm::v3::Xcm::<Call>::try_from(xcm)
+				.map_err(|_| codec::Error::from("Unsupported XCM message version"))?;
+			Ok(FromBridgedChainMessagePayload { xcm: (location, xcm), weight: None })
 		}
 	}
 
@@ -716,8 +771,8 @@ pub mod target {
 		fn dispatch_weight(
 			message: &mut DispatchMessage<Self::DispatchPayload, BalanceOf<BridgedChain<B>>>,
 		) -> frame_support::weights::Weight {
-			match message.data.payload {
-				Ok(ref mut payload) => {
+			match message.data.payload.decoded() {
+				Ok(payload) => {
 					// I have no idea why this method takes `&mut` reference and there's nothing
 					// about that in documentation. Hope it'll only mutate iff error is returned.
 					let weight = XcmWeigher::weight(&mut payload.xcm.1);
@@ -749,7 +804,7 @@ pub mod target {
 			let message_id = (message.key.lane_id, message.key.nonce);
 			let do_dispatch = move || -> sp_std::result::Result<Outcome, codec::Error> {
 				let FromBridgedChainMessagePayload { xcm: (location, xcm), weight: weight_limit } =
-					message.data.payload?;
+					message.data.payload.into_decoded()?;
 				log::trace!(
 					target: "runtime::bridge-dispatch",
 					"Going to execute message {:?} (weight limit: {:?}): {:?} {:?}",
@@ -776,13 +831,35 @@ pub mod target {
 			let xcm_outcome = do_dispatch();
 			log::trace!(target: "runtime::bridge-dispatch", "Incoming message {:?} dispatched with result: {:?}", message_id, xcm_outcome);
 			MessageDispatchResult {
-				dispatch_result: true,
+				// undecodable message is never dispatched
+				dispatch_result: xcm_outcome.is_ok(),
 				unspent_weight: 0,
 				dispatch_fee_paid_during_dispatch: false,
+				dispatch_error: match xcm_outcome {
+					Ok(ref xcm_outcome) => xcm_outcome_dispatch_error(xcm_outcome),
+					Err(_) => Some(MessageDispatchError::UndecodablePayload),
+				},
 			}
 		}
 	}
 
+	/// Returns compact description of the XCM execution error, or `None` if the XCM has been
+	/// executed successfully.
+	pub fn xcm_outcome_dispatch_error(xcm_outcome: &Outcome) -> Option<MessageDispatchError> {
+		let xcm_error = match *xcm_outcome {
+			Outcome::Complete(_) => return None,
+			Outcome::Incomplete(_, ref xcm_error) | Outcome::Error(ref xcm_error) => xcm_error,
+		};
+
+		Some(match *xcm_error {
+			XcmError::Barrier => MessageDispatchError::Rejected,
+			XcmError::WeightLimitReached(_) => MessageDispatchError::WeightLimitReached,
+			XcmError::Trap(code) => MessageDispatchError::Trapped(code),
+			ref xcm_error =>
+				MessageDispatchError::Other(xcm_error.encode().first().copied().unwrap_or(u8::MAX)),
+		})
+	}
+
 	/// Return maximal dispatch weight of the message we're able to receive.
 	pub fn maximal_incoming_message_dispatch_weight(maximal_extrinsic_weight: Weight) -> Weight {
 		maximal_extrinsic_weight / 2
@@ -793,6 +870,31 @@ pub mod target {
 		maximal_extrinsic_size / 3 * 2
 	}
 
+	/// Default maximal size of inbound message payload at This chain.
+	pub struct FromBridgedChainMaximalInboundPayloadSize<ThisChain>(PhantomData<ThisChain>);
+
+	impl<ThisChain: bp_runtime::Chain> Get<u32>
+		for FromBridgedChainMaximalInboundPayloadSize<ThisChain>
+	{
+		fn get() -> u32 {
+			maximal_incoming_message_size(ThisChain::max_extrinsic_size())
+		}
+	}
+
+	/// Return maximal size of messages proof, given maximal extrinsic size at This chain and the
+	/// storage proof overhead at the Bridged chain.
+	///
+	/// The largest proof is the proof of single message of maximal size. Relayers never build
+	/// larger batches, because the total size of messages in the batch is limited by the third
+	/// of This chain maximal extrinsic size.
+	pub fn maximal_messages_proof_size(
+		this_maximal_extrinsic_size: u32,
+		bridged_extra_storage_proof_size: u32,
+	) -> u32 {
+		maximal_incoming_message_size(this_maximal_extrinsic_size)
+			.saturating_add(bridged_extra_storage_proof_size)
+	}
+
 	/// Verify proof of Bridged -> This chain messages.
 	///
 	/// This function is used when Bridged chain is directly using GRANDPA finality. For Bridged
@@ -804,7 +906,7 @@ pub mod target {
 	pub fn verify_messages_proof<B: MessageBridge, ThisRuntime, GrandpaInstance: 'static>(
 		proof: FromBridgedChainMessagesProof<HashOf<BridgedChain<B>>>,
 		messages_count: u32,
-	) -> Result<ProvedMessages<Message<BalanceOf<BridgedChain<B>>>>, &'static str>
+	) -> Result<ProvedMessages<Message<BalanceOf<BridgedChain<B>>>>, VerificationError>
 	where
 		ThisRuntime: pallet_bridge_grandpa::Config<GrandpaInstance>,
 		HashOf<BridgedChain<B>>: Into<
@@ -826,10 +928,9 @@ pub mod target {
 					storage,
 					_dummy: Default::default(),
 				})
-				.map_err(|err| MessageProofError::Custom(err.into()))
+				.map_err(|err| VerificationError::Other(err.into()))
 			},
 		)
-		.map_err(Into::into)
 	}
 
 	/// Verify proof of Bridged -> This chain messages.
@@ -852,7 +953,7 @@ pub mod target {
 		bridged_parachain: ParaId,
 		proof: FromBridgedChainMessagesProof<HashOf<BridgedChain<B>>>,
 		messages_count: u32,
-	) -> Result<ProvedMessages<Message<BalanceOf<BridgedChain<B>>>>, &'static str>
+	) -> Result<ProvedMessages<Message<BalanceOf<BridgedChain<B>>>>, VerificationError>
 	where
 		B: MessageBridge,
 		B::BridgedChain: ChainWithMessages<Hash = ParaHash>,
@@ -863,47 +964,19 @@ pub mod target {
 			proof,
 			messages_count,
 			|bridged_header_hash, bridged_storage_proof| {
-				pallet_bridge_parachains::Pallet::<ThisRuntime, ParachainsInstance>::parse_finalized_storage_proof(
+				pallet_bridge_parachains::Pallet::<ThisRuntime, ParachainsInstance>::parse_finalized_storage_proof::<BridgedHeader, _>(
 					bridged_parachain,
 					bridged_header_hash,
 					StorageProof::new(bridged_storage_proof),
-					|para_head| BridgedHeader::decode(&mut &para_head.0[..]).ok().map(|h| *h.state_root()),
 					|storage_adapter| storage_adapter,
 				)
 				.map(|storage| StorageProofCheckerAdapter::<_, B> {
 					storage,
 					_dummy: Default::default(),
 				})
-				.map_err(|err| MessageProofError::Custom(err.into()))
+				.map_err(|err| VerificationError::Other(err.into()))
 			},
 		)
-		.map_err(Into::into)
-	}
-
-	#[derive(Debug, PartialEq, Eq)]
-	pub(crate) enum MessageProofError {
-		Empty,
-		MessagesCountMismatch,
-		MissingRequiredMessage,
-		FailedToDecodeMessage,
-		FailedToDecodeOutboundLaneState,
-		Custom(&'static str),
-	}
-
-	impl From<MessageProofError> for &'static str {
-		fn from(err: MessageProofError) -> &'static str {
-			match err {
-				MessageProofError::Empty => "Messages proof is empty",
-				MessageProofError::MessagesCountMismatch =>
-					"Declared messages count doesn't match actual value",
-				MessageProofError::MissingRequiredMessage => "Message is missing from the proof",
-				MessageProofError::FailedToDecodeMessage =>
-					"Failed to decode message from the proof",
-				MessageProofError::FailedToDecodeOutboundLaneState =>
-					"Failed to decode outbound lane data from the proof",
-				MessageProofError::Custom(err) => err,
-			}
-		}
 	}
 
 	pub(crate) trait MessageProofParser {
@@ -944,10 +1017,10 @@ pub mod target {
 		proof: FromBridgedChainMessagesProof<HashOf<BridgedChain<B>>>,
 		messages_count: u32,
 		build_parser: BuildParser,
-	) -> Result<ProvedMessages<Message<BalanceOf<BridgedChain<B>>>>, MessageProofError>
+	) -> Result<ProvedMessages<Message<BalanceOf<BridgedChain<B>>>>, VerificationError>
 	where
 		BuildParser:
-			FnOnce(HashOf<BridgedChain<B>>, RawStorageProof) -> Result<Parser, MessageProofError>,
+			FnOnce(HashOf<BridgedChain<B>>, RawStorageProof) -> Result<Parser, VerificationError>,
 		Parser: MessageProofParser,
 	{
 		let FromBridgedChainMessagesProof {
@@ -965,7 +1038,7 @@ pub mod target {
 				// (this bounds maximal capacity of messages vec below)
 				let messages_in_the_proof = nonces_difference.saturating_add(1);
 				if messages_in_the_proof != MessageNonce::from(messages_count) {
-					return Err(MessageProofError::MessagesCountMismatch)
+					return Err(VerificationError::MessagesCountMismatch)
 				}
 
 				messages_in_the_proof
@@ -984,10 +1057,10 @@ pub mod target {
 			let message_key = MessageKey { lane_id: lane, nonce };
 			let raw_message_data = parser
 				.read_raw_message(&message_key)
-				.ok_or(MessageProofError::MissingRequiredMessage)?;
+				.ok_or(VerificationError::MissingRequiredMessage)?;
 			let message_data =
 				MessageData::<BalanceOf<BridgedChain<B>>>::decode(&mut &raw_message_data[..])
-					.map_err(|_| MessageProofError::FailedToDecodeMessage)?;
+					.map_err(|_| VerificationError::FailedToDecodeMessage)?;
 			messages.push(Message { key: message_key, data: message_data });
 		}
 
@@ -998,13 +1071,13 @@ pub mod target {
 		if let Some(raw_outbound_lane_data) = raw_outbound_lane_data {
 			proved_lane_messages.lane_state = Some(
 				OutboundLaneData::decode(&mut &raw_outbound_lane_data[..])
-					.map_err(|_| MessageProofError::FailedToDecodeOutboundLaneState)?,
+					.map_err(|_| VerificationError::FailedToDecodeOutboundLaneState)?,
 			);
 		}
 
 		// Now we may actually check if the proof is empty or not.
 		if proved_lane_messages.lane_state.is_none() && proved_lane_messages.messages.is_empty() {
-			return Err(MessageProofError::Empty)
+			return Err(VerificationError::EmptyMessageProof)
 		}
 
 		// We only support single lane messages in this generated_schema
@@ -1147,6 +1220,7 @@ mod tests {
 		const THIS_CHAIN_ID: ChainId = *b"this";
 		const BRIDGED_CHAIN_ID: ChainId = *b"brdg";
 		const BRIDGED_MESSAGES_PALLET_NAME: &'static str = "";
+		const DISPATCH_FEE_PAYMENT: DispatchFeePayment = DispatchFeePayment::AtTargetChain;
 
 		type ThisChain = ThisChain;
 		type BridgedChain = BridgedChain;
@@ -1172,6 +1246,7 @@ mod tests {
 		const THIS_CHAIN_ID: ChainId = *b"brdg";
 		const BRIDGED_CHAIN_ID: ChainId = *b"this";
 		const BRIDGED_MESSAGES_PALLET_NAME: &'static str = "";
+		const DISPATCH_FEE_PAYMENT: DispatchFeePayment = DispatchFeePayment::AtTargetChain;
 
 		type ThisChain = BridgedChain;
 		type BridgedChain = ThisChain;
@@ -1456,7 +1531,7 @@ mod tests {
 				&test_lane_outbound_data(),
 				&payload,
 			),
-			Err(source::TOO_LOW_FEE)
+			Err(VerificationError::TooLowFee)
 		);
 		assert!(source::FromThisChainMessageVerifier::<OnThisChainBridge>::verify_message(
 			&ThisChainOrigin(Ok(frame_system::RawOrigin::Root)),
@@ -1478,7 +1553,7 @@ mod tests {
 				&test_lane_outbound_data(),
 				&regular_outbound_message_payload(),
 			),
-			Err(source::MESSAGE_REJECTED_BY_OUTBOUND_LANE)
+			Err(VerificationError::ClosedLane)
 		);
 	}
 
@@ -1496,7 +1571,7 @@ mod tests {
 				},
 				&regular_outbound_message_payload(),
 			),
-			Err(source::TOO_MANY_PENDING_MESSAGES)
+			Err(VerificationError::TooManyPendingMessages)
 		);
 	}
 
@@ -1598,7 +1673,7 @@ mod tests {
 				5,
 				|_, _| unreachable!(),
 			),
-			Err(target::MessageProofError::MessagesCountMismatch),
+			Err(VerificationError::MessagesCountMismatch),
 		);
 	}
 
@@ -1610,7 +1685,7 @@ mod tests {
 				15,
 				|_, _| unreachable!(),
 			),
-			Err(target::MessageProofError::MessagesCountMismatch),
+			Err(VerificationError::MessagesCountMismatch),
 		);
 	}
 
@@ -1620,9 +1695,9 @@ mod tests {
 			target::verify_messages_proof_with_parser::<OnThisChainBridge, _, TestMessageProofParser>(
 				messages_proof(10),
 				10,
-				|_, _| Err(target::MessageProofError::Custom("test")),
+				|_, _| Err(VerificationError::Other("test")),
 			),
-			Err(target::MessageProofError::Custom("test")),
+			Err(VerificationError::Other("test")),
 		);
 	}
 
@@ -1638,7 +1713,7 @@ mod tests {
 					outbound_lane_data: None,
 				}),
 			),
-			Err(target::MessageProofError::MissingRequiredMessage),
+			Err(VerificationError::MissingRequiredMessage),
 		);
 	}
 
@@ -1654,7 +1729,7 @@ mod tests {
 					outbound_lane_data: None,
 				}),
 			),
-			Err(target::MessageProofError::FailedToDecodeMessage),
+			Err(VerificationError::FailedToDecodeMessage),
 		);
 	}
 
@@ -1674,7 +1749,7 @@ mod tests {
 					}),
 				}),
 			),
-			Err(target::MessageProofError::FailedToDecodeOutboundLaneState),
+			Err(VerificationError::FailedToDecodeOutboundLaneState),
 		);
 	}
 
@@ -1690,7 +1765,7 @@ mod tests {
 					outbound_lane_data: None,
 				}),
 			),
-			Err(target::MessageProofError::Empty),
+			Err(VerificationError::EmptyMessageProof),
 		);
 	}
 
@@ -1777,7 +1852,7 @@ mod tests {
 					}),
 				}),
 			),
-			Err(target::MessageProofError::MessagesCountMismatch),
+			Err(VerificationError::MessagesCountMismatch),
 		);
 	}
 
@@ -1829,4 +1904,30 @@ mod tests {
 
 		assert!(regular_fee < overrided_fee);
 	}
+
+	#[test]
+	fn xcm_outcome_is_converted_to_dispatch_error() {
+		use target::xcm_outcome_dispatch_error;
+
+		// this is what XCM executor returns when `Trap` instruction is dispatched with
+		// insufficient weight
+		assert_eq!(
+			xcm_outcome_dispatch_error(&Outcome::Error(XcmError::WeightLimitReached(1_000))),
+			Some(MessageDispatchError::WeightLimitReached),
+		);
+		// ... and when it is dispatched with enough weight
+		assert_eq!(
+			xcm_outcome_dispatch_error(&Outcome::Incomplete(1_000, XcmError::Trap(42))),
+			Some(MessageDispatchError::Trapped(42)),
+		);
+		assert_eq!(
+			xcm_outcome_dispatch_error(&Outcome::Error(XcmError::Barrier)),
+			Some(MessageDispatchError::Rejected),
+		);
+		assert_eq!(
+			xcm_outcome_dispatch_error(&Outcome::Error(XcmError::Unimplemented)),
+			Some(MessageDispatchError::Other(XcmError::Unimplemented.encode()[0])),
+		);
+		assert_eq!(xcm_outcome_dispatch_error(&Outcome::Complete(1_000)), None);
+	}
 }
@@ -16,34 +16,51 @@
 
 //! Helpers for implementing various message-related runtime API mthods.
 
+use crate::messages::{BridgedChain, BridgedChainWithMessages, MessageBridge};
+
 use bp_messages::{
 	InboundMessageDetails, LaneId, MessageNonce, MessagePayload, OutboundMessageDetails,
 };
 use sp_std::vec::Vec;
 
 /// Implementation of the `To*OutboundLaneApi::message_details`.
-pub fn outbound_message_details<Runtime, MessagesPalletInstance>(
+pub fn outbound_message_details<Runtime, MessagesPalletInstance, Bridge>(
 	lane: LaneId,
 	begin: MessageNonce,
 	end: MessageNonce,
-) -> Vec<OutboundMessageDetails<Runtime::OutboundMessageFee>>
+) -> Vec<
+	OutboundMessageDetails<
+		Runtime::OutboundMessageFee,
+		pallet_bridge_messages::SubmitterOf<Runtime, MessagesPalletInstance>,
+	>,
+>
 where
 	Runtime: pallet_bridge_messages::Config<MessagesPalletInstance>,
 	MessagesPalletInstance: 'static,
+	Bridge: MessageBridge,
 {
 	(begin..=end)
 		.filter_map(|nonce| {
 			let message_data =
 				pallet_bridge_messages::Pallet::<Runtime, MessagesPalletInstance>::outbound_message_data(lane, nonce)?;
+			let submitter =
+				pallet_bridge_messages::Pallet::<Runtime, MessagesPalletInstance>::outbound_message_submitter(lane, nonce);
+			// if payload doesn't declare dispatch weight (e.g. XCM message, which we can't decode
+			// here), then we report zero weight. Relayers are asking the target chain for the
+			// actual weight
+			let declared_dispatch =
+				BridgedChain::<Bridge>::declared_message_dispatch(&message_data.payload);
 			Some(OutboundMessageDetails {
 				nonce,
-				// dispatch message weight is always zero at the source chain, since we're paying for
-				// dispatch at the target chain
-				dispatch_weight: 0,
+				dispatch_weight: declared_dispatch
+					.map(|declared_dispatch| declared_dispatch.dispatch_weight)
+					.unwrap_or(0),
 				size: message_data.payload.len() as _,
 				delivery_and_dispatch_fee: message_data.fee,
-				// we're delivering XCM messages here, so fee is always paid at the target chain
-				dispatch_fee_payment: bp_runtime::messages::DispatchFeePayment::AtTargetChain,
+				dispatch_fee_payment: declared_dispatch
+					.map(|declared_dispatch| declared_dispatch.dispatch_fee_payment)
+					.unwrap_or(Bridge::DISPATCH_FEE_PAYMENT),
+				submitter,
 			})
 		})
 		.collect()
@@ -20,14 +20,49 @@ use crate::{
 	},
 	BridgeRuntimeFilterCall,
 };
-use frame_support::{dispatch::CallableCallFor, traits::IsSubType};
+use bp_messages::{target_chain::SourceHeaderChain, InboundLaneData, LaneId, MessageNonce};
+use frame_support::{
+	dispatch::CallableCallFor,
+	traits::{Get, IsSubType},
+};
 use pallet_bridge_messages::{Config, Pallet};
-use sp_runtime::transaction_validity::TransactionValidity;
+use sp_runtime::transaction_validity::{
+	InvalidTransaction, TransactionPriority, TransactionValidity, ValidTransaction,
+};
+
+/// Priority boost that the messages delivery transaction gets for every new message it brings.
+///
+/// Without the boost, the priority of delivery transaction depends on the tip only, so the large
+/// delivery transaction may stay in the pool behind many small transactions (e.g. transfers).
+/// Priorities, returned by all bridge filters (and by other signed extensions) are summed up
+/// (see `ValidTransaction::combine_with`), so this boost is simply added to the priorities that
+/// are assigned to the transaction by other extensions. Since every bridge call is validated by
+/// at most one filter, the boost never stacks with boosts of other bridge filters.
+///
+/// Header import transactions (including mandatory headers, which are imported for free) are
+/// not boosted, so their priority depends on the tip only. Since the boost is capped at
+/// `MAX_PRIORITY_BOOSTED_MESSAGES` messages, the delivery transaction gets at most
+/// `64 * PRIORITY_BOOST_PER_MESSAGE` extra priority. The mandatory header import transaction
+/// needs a tip that outweighs this value to be included before boosted delivery transactions.
+pub const PRIORITY_BOOST_PER_MESSAGE: TransactionPriority = 1_000_000;
+
+/// Maximal number of messages that are increasing priority of the delivery transaction.
+pub const MAX_PRIORITY_BOOSTED_MESSAGES: MessageNonce = 64;
+
+/// Returns priority boost of the delivery transaction that brings given number of new messages.
+pub fn messages_delivery_priority_boost(new_messages: MessageNonce) -> TransactionPriority {
+	PRIORITY_BOOST_PER_MESSAGE.saturating_mul(new_messages.min(MAX_PRIORITY_BOOSTED_MESSAGES))
+}
 
 /// Validate messages in order to avoid "mining" messages delivery and delivery confirmation
 /// transactions, that are delivering outdated messages/confirmations. Without this validation,
 /// even honest relayers may lose their funds if there are multiple relays running and submitting
 /// the same messages/confirmations.
+///
+/// Valid delivery transactions are getting priority boost, proportional to the number of new
+/// messages. If the inbound lane has no room for new unrewarded relayer entries, the delivery
+/// transaction is only valid if its proof brings the outbound lane state that allows to prune
+/// some entries. The proof is only verified in this (rare) case.
 impl<
 		BridgedHeaderHash,
 		SourceHeaderChain: bp_messages::target_chain::SourceHeaderChain<
@@ -49,6 +84,7 @@ impl<
 		match call.is_sub_type() {
 			Some(pallet_bridge_messages::Call::<T, I>::receive_messages_proof {
 				ref proof,
+				messages_count,
 				..
 			}) => {
 				let inbound_lane_data =
@@ -63,8 +99,35 @@ impl<
 						inbound_lane_data.last_delivered_nonce(),
 					);
 
-					return sp_runtime::transaction_validity::InvalidTransaction::Stale.into()
+					return InvalidTransaction::Stale.into()
+				}
+
+				let is_lane_full = inbound_lane_data.relayers.len() as MessageNonce >=
+					T::MaxUnrewardedRelayerEntriesAtInboundLane::get();
+				let proof_prunes_entry = || {
+					proof_prunes_unrewarded_relayer_entry::<T, I>(
+						&inbound_lane_data,
+						proof.lane,
+						proof.clone(),
+						*messages_count,
+					)
+				};
+				if is_lane_full && !proof_prunes_entry() {
+					log::trace!(
+						target: pallet_bridge_messages::LOG_TARGET,
+						"Rejecting messages delivery transaction: lane {:?} has no room for new \
+							unrewarded relayer entries",
+						proof.lane,
+					);
+
+					return InvalidTransaction::ExhaustsResources.into()
 				}
+
+				let new_messages = proof.nonces_end - inbound_lane_data.last_delivered_nonce();
+				return Ok(ValidTransaction {
+					priority: messages_delivery_priority_boost(new_messages),
+					..Default::default()
+				})
 			},
 			Some(pallet_bridge_messages::Call::<T, I>::receive_messages_delivery_proof {
 				ref proof,
@@ -85,13 +148,44 @@ impl<
 						outbound_lane_data.latest_received_nonce,
 					);
 
-					return sp_runtime::transaction_validity::InvalidTransaction::Stale.into()
+					return InvalidTransaction::Stale.into()
 				}
 			},
 			_ => {},
 		}
 
-		Ok(sp_runtime::transaction_validity::ValidTransaction::default())
+		Ok(ValidTransaction::default())
+	}
+}
+
+/// Returns true if the messages proof brings the outbound lane state that allows to prune at
+/// least one unrewarded relayer entry of the inbound lane.
+fn proof_prunes_unrewarded_relayer_entry<T: Config<I>, I: 'static>(
+	inbound_lane_data: &InboundLaneData<T::InboundRelayer>,
+	lane: LaneId,
+	proof: <T::SourceHeaderChain as SourceHeaderChain<T::InboundMessageFee>>::MessagesProof,
+	messages_count: u32,
+) -> bool {
+	let latest_received_nonce =
+		match T::SourceHeaderChain::verify_messages_proof(proof, messages_count) {
+			Ok(mut messages) => messages
+				.remove(&lane)
+				.and_then(|lane_messages| lane_messages.lane_state)
+				.map(|lane_state| lane_state.latest_received_nonce),
+			Err(_) => None,
+		};
+
+	// the same conditions are checked by the inbound lane when the state update is received
+	match latest_received_nonce {
+		Some(latest_received_nonce) =>
+			latest_received_nonce <= inbound_lane_data.last_delivered_nonce() &&
+				latest_received_nonce > inbound_lane_data.last_confirmed_nonce &&
+				inbound_lane_data
+					.relayers
+					.front()
+					.map(|entry| entry.messages.end <= latest_received_nonce)
+					.unwrap_or(false),
+		None => false,
 	}
 }
 
@@ -111,7 +205,7 @@ mod tests {
 
 	fn deliver_message_10() {
 		pallet_bridge_messages::InboundLanes::<Runtime, WithRialtoMessagesInstance>::insert(
-			[0, 0, 0, 0],
+			bp_messages::LaneId([0, 0, 0, 0]),
 			bp_messages::InboundLaneData { relayers: Default::default(), last_confirmed_nonce: 10 },
 		);
 	}
@@ -129,7 +223,7 @@ mod tests {
 					proof: FromBridgedChainMessagesProof {
 						bridged_header_hash: Default::default(),
 						storage_proof: vec![],
-						lane: [0, 0, 0, 0],
+						lane: bp_messages::LaneId([0, 0, 0, 0]),
 						nonces_start,
 						nonces_end,
 					},
@@ -171,7 +265,7 @@ mod tests {
 
 	fn confirm_message_10() {
 		pallet_bridge_messages::OutboundLanes::<Runtime, WithRialtoMessagesInstance>::insert(
-			[0, 0, 0, 0],
+			bp_messages::LaneId([0, 0, 0, 0]),
 			bp_messages::OutboundLaneData {
 				oldest_unpruned_nonce: 0,
 				latest_received_nonce: 10,
@@ -189,7 +283,7 @@ mod tests {
 				proof: FromBridgedChainMessagesDeliveryProof {
 					bridged_header_hash: Default::default(),
 					storage_proof: Vec::new(),
-					lane: [0, 0, 0, 0],
+					lane: bp_messages::LaneId([0, 0, 0, 0]),
 				},
 				relayers_state: UnrewardedRelayersState {
 					last_delivered_nonce,
@@ -229,4 +323,251 @@ mod tests {
 			assert!(validate_message_confirmation(15));
 		});
 	}
+
+	mod full_lane {
+		use crate::{
+			messages_extension::messages_delivery_priority_boost,
+			test_helpers::make_messages_proof_test_vector, BridgeRuntimeFilterCall,
+		};
+		use bp_header_chain::InitializationData;
+		use bp_messages::{
+			DeliveredMessages, InboundLaneData, LaneId, MessageData, MessageNonce,
+			OutboundLaneData, UnrewardedRelayer,
+		};
+		use bp_runtime::BasicOperatingMode;
+		use frame_support::{assert_ok, traits::Get};
+		use millau_runtime::{
+			rialto_messages::WithRialtoMessageBridge, Call,
+			MaxUnrewardedRelayerEntriesAtInboundLane, Origin, RialtoGrandpaInstance, Runtime,
+			WithRialtoMessagesInstance,
+		};
+		use sp_runtime::{
+			traits::BlakeTwo256,
+			transaction_validity::{InvalidTransaction, TransactionValidity},
+		};
+
+		type BridgedHeader = pallet_bridge_grandpa::BridgedHeader<Runtime, RialtoGrandpaInstance>;
+
+		const TEST_LANE_ID: LaneId = LaneId([0, 0, 0, 0]);
+
+		/// Fill the inbound lane with unrewarded relayer entries. Every entry has one message.
+		fn fill_inbound_lane() -> MessageNonce {
+			let max_entries = MaxUnrewardedRelayerEntriesAtInboundLane::get();
+			pallet_bridge_messages::InboundLanes::<Runtime, WithRialtoMessagesInstance>::insert(
+				TEST_LANE_ID,
+				InboundLaneData {
+					relayers: (1..=max_entries)
+						.map(|nonce| UnrewardedRelayer {
+							relayer: [nonce as u8; 32].into(),
+							messages: DeliveredMessages::new(nonce, true),
+						})
+						.collect(),
+					last_confirmed_nonce: 0,
+				},
+			);
+			max_entries
+		}
+
+		/// Validate delivery of the next message with the proof of given outbound lane state.
+		fn validate_message_delivery_with_lane_state(
+			last_delivered_nonce: MessageNonce,
+			latest_received_nonce: MessageNonce,
+		) -> TransactionValidity {
+			let vector = make_messages_proof_test_vector::<
+				WithRialtoMessageBridge,
+				BridgedHeader,
+				BlakeTwo256,
+			>(
+				10,
+				TEST_LANE_ID,
+				last_delivered_nonce + 1,
+				vec![MessageData { fee: 0, payload: vec![42] }],
+				Some(OutboundLaneData {
+					oldest_unpruned_nonce: 1,
+					latest_received_nonce,
+					latest_generated_nonce: last_delivered_nonce + 1,
+				}),
+			);
+			assert_ok!(
+				pallet_bridge_grandpa::Pallet::<Runtime, RialtoGrandpaInstance>::initialize(
+					Origin::root(),
+					InitializationData {
+						header: Box::new(vector.header),
+						authority_list: vec![],
+						set_id: 0,
+						operating_mode: BasicOperatingMode::Normal,
+						checkpoints: vec![],
+					},
+				)
+			);
+
+			pallet_bridge_messages::Pallet::<Runtime, WithRialtoMessagesInstance>::validate(
+				&Call::BridgeRialtoMessages(pallet_bridge_messages::Call::<
+					Runtime,
+					WithRialtoMessagesInstance,
+				>::receive_messages_proof {
+					relayer_id_at_bridged_chain: [0u8; 32].into(),
+					messages_count: vector.messages_count,
+					dispatch_weight: 0,
+					proof: vector.proof,
+				}),
+			)
+		}
+
+		#[test]
+		fn delivery_transaction_is_accepted_if_proof_prunes_unrewarded_relayers() {
+			sp_io::TestExternalities::new(Default::default()).execute_with(|| {
+				let last_delivered_nonce = fill_inbound_lane();
+				assert_eq!(
+					validate_message_delivery_with_lane_state(last_delivered_nonce, 1)
+						.map(|validity| validity.priority),
+					Ok(messages_delivery_priority_boost(1)),
+				);
+			});
+		}
+
+		#[test]
+		fn delivery_transaction_is_rejected_if_proof_does_not_prune_unrewarded_relayers() {
+			sp_io::TestExternalities::new(Default::default()).execute_with(|| {
+				let last_delivered_nonce = fill_inbound_lane();
+				assert_eq!(
+					validate_message_delivery_with_lane_state(last_delivered_nonce, 0),
+					Err(InvalidTransaction::ExhaustsResources.into()),
+				);
+			});
+		}
+	}
+
+	mod pass3dt {
+		use crate::messages_extension::{
+			messages_delivery_priority_boost, MAX_PRIORITY_BOOSTED_MESSAGES,
+			PRIORITY_BOOST_PER_MESSAGE,
+		};
+		use bp_messages::{DeliveredMessages, InboundLaneData, MessageNonce, UnrewardedRelayer};
+		use frame_support::traits::Get;
+		use pass3dt_runtime::{
+			bridge_runtime_common::{
+				messages::target::FromBridgedChainMessagesProof, BridgeRuntimeFilterCall,
+				LookThroughProxy,
+			},
+			BridgePass3dMessages, Call, MaxUnrewardedRelayerEntriesAtInboundLane, Runtime,
+			WithPass3dMessagesInstance,
+		};
+		use sp_runtime::transaction_validity::{
+			InvalidTransaction, TransactionPriority, TransactionValidity,
+		};
+
+		/// Every message in `1..=messages` is delivered by a separate relayer.
+		fn deliver_messages(messages: MessageNonce) {
+			pallet_bridge_messages::InboundLanes::<Runtime, WithPass3dMessagesInstance>::insert(
+				bp_messages::LaneId([0, 0, 0, 0]),
+				InboundLaneData {
+					relayers: (1..=messages)
+						.map(|nonce| UnrewardedRelayer {
+							relayer: [nonce as u8; 32].into(),
+							messages: DeliveredMessages::new(nonce, true),
+						})
+						.collect(),
+					last_confirmed_nonce: 0,
+				},
+			);
+		}
+
+		fn message_delivery_call(nonces_start: MessageNonce, nonces_end: MessageNonce) -> Call {
+			Call::BridgePass3dMessages(pallet_bridge_messages::Call::<
+				Runtime,
+				WithPass3dMessagesInstance,
+			>::receive_messages_proof {
+				relayer_id_at_bridged_chain: [0u8; 32].into(),
+				messages_count: (nonces_end - nonces_start + 1) as u32,
+				dispatch_weight: 0,
+				proof: FromBridgedChainMessagesProof {
+					bridged_header_hash: Default::default(),
+					storage_proof: vec![],
+					lane: bp_messages::LaneId([0, 0, 0, 0]),
+					nonces_start,
+					nonces_end,
+				},
+			})
+		}
+
+		fn proxied(call: Call) -> Call {
+			Call::Proxy(pallet_proxy::Call::<Runtime>::proxy {
+				real: [42u8; 32].into(),
+				force_proxy_type: None,
+				call: Box::new(call),
+			})
+		}
+
+		fn validate_message_delivery(
+			nonces_start: MessageNonce,
+			nonces_end: MessageNonce,
+		) -> TransactionValidity {
+			pallet_bridge_messages::Pallet::<Runtime, WithPass3dMessagesInstance>::validate(
+				&message_delivery_call(nonces_start, nonces_end),
+			)
+		}
+
+		fn delivery_priority(
+			nonces_start: MessageNonce,
+			nonces_end: MessageNonce,
+		) -> TransactionPriority {
+			validate_message_delivery(nonces_start, nonces_end).unwrap().priority
+		}
+
+		#[test]
+		fn delivery_transaction_priority_is_boosted_by_new_messages() {
+			sp_io::TestExternalities::new(Default::default()).execute_with(|| {
+				deliver_messages(10);
+
+				assert_eq!(delivery_priority(11, 11), PRIORITY_BOOST_PER_MESSAGE);
+				assert_eq!(delivery_priority(11, 15), 5 * PRIORITY_BOOST_PER_MESSAGE);
+				// messages that are already delivered are not boosting priority
+				assert_eq!(delivery_priority(8, 15), 5 * PRIORITY_BOOST_PER_MESSAGE);
+				// boost is bounded
+				assert_eq!(
+					delivery_priority(11, 10 + MAX_PRIORITY_BOOSTED_MESSAGES * 2),
+					messages_delivery_priority_boost(MAX_PRIORITY_BOOSTED_MESSAGES),
+				);
+			});
+		}
+
+		#[test]
+		fn delivery_transaction_is_rejected_if_lane_has_too_many_unrewarded_relayers() {
+			sp_io::TestExternalities::new(Default::default()).execute_with(|| {
+				// the proof doesn't bring lane state that prunes some entries
+				deliver_messages(MaxUnrewardedRelayerEntriesAtInboundLane::get());
+				assert_eq!(
+					validate_message_delivery(
+						MaxUnrewardedRelayerEntriesAtInboundLane::get() + 1,
+						MaxUnrewardedRelayerEntriesAtInboundLane::get() + 1,
+					),
+					Err(InvalidTransaction::ExhaustsResources.into()),
+				);
+			});
+		}
+
+		#[test]
+		fn proxied_obsolete_delivery_transaction_is_rejected() {
+			sp_io::TestExternalities::new(Default::default()).execute_with(|| {
+				deliver_messages(10);
+
+				let validate = |call| {
+					LookThroughProxy::<Runtime, BridgePass3dMessages>::validate(&proxied(call))
+				};
+				assert_eq!(
+					validate(message_delivery_call(8, 10)),
+					Err(InvalidTransaction::Stale.into()),
+				);
+				assert_eq!(
+					validate(proxied(message_delivery_call(8, 10))),
+					Err(InvalidTransaction::Stale.into()),
+				);
+				assert_eq!(
+					validate(message_delivery_call(11, 11)),
+					validate_message_delivery(11, 11),
+				);
+			});
+		}
+	}
 }
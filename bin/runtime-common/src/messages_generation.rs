@@ -0,0 +1,143 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Helpers for generating storage proofs of messages and lane states, based on
+//! `bridge_runtime_common::messages` implementation. They're used by benchmarks and tests.
+
+#![cfg(any(feature = "runtime-benchmarks", feature = "test-helpers", test))]
+
+use crate::messages::{
+	AccountIdOf, BalanceOf, BridgedChain, HashOf, MessageBridge, RawStorageProof, ThisChain,
+};
+
+use bp_messages::{
+	storage_keys, InboundLaneData, LaneId, MessageData, MessageNonce, OutboundLaneData,
+};
+use bp_runtime::{record_all_trie_keys, StorageProofSize};
+use codec::Encode;
+use sp_core::Hasher;
+use sp_std::prelude::*;
+use sp_trie::{trie_types::TrieDBMutBuilderV1, LayoutV1, MemoryDB, Recorder, TrieMut};
+
+/// Prepare storage proof of given messages and (optionally) outbound lane state.
+///
+/// Returns state trie root and nodes with prepared messages.
+pub fn prepare_messages_storage_proof<B, BHH>(
+	lane: LaneId,
+	messages: &[(MessageNonce, MessageData<BalanceOf<BridgedChain<B>>>)],
+	outbound_lane_data: Option<&OutboundLaneData>,
+	size: StorageProofSize,
+) -> (HashOf<BridgedChain<B>>, RawStorageProof)
+where
+	B: MessageBridge,
+	BHH: Hasher<Out = HashOf<BridgedChain<B>>>,
+	HashOf<BridgedChain<B>>: Copy + Default,
+{
+	// prepare Bridged chain storage with messages and (optionally) outbound lane state
+	let mut root = Default::default();
+	let mut mdb = MemoryDB::default();
+	{
+		let mut trie = TrieDBMutBuilderV1::<BHH>::new(&mut mdb, &mut root).build();
+
+		// insert messages
+		for (nonce, message_data) in messages {
+			let storage_key =
+				storage_keys::message_key(B::BRIDGED_MESSAGES_PALLET_NAME, &lane, *nonce).0;
+			trie.insert(&storage_key, &message_data.encode())
+				.map_err(|_| "TrieMut::insert has failed")
+				.expect("TrieMut::insert should not fail");
+		}
+
+		// insert outbound lane state
+		if let Some(outbound_lane_data) = outbound_lane_data {
+			let storage_key =
+				storage_keys::outbound_lane_data_key(B::BRIDGED_MESSAGES_PALLET_NAME, &lane).0;
+			trie.insert(&storage_key, &outbound_lane_data.encode())
+				.map_err(|_| "TrieMut::insert has failed")
+				.expect("TrieMut::insert should not fail");
+		}
+	}
+	root = grow_trie(root, &mut mdb, size);
+
+	(root, prove_all_trie_keys(&mdb, &root))
+}
+
+/// Prepare storage proof of given inbound lane state.
+///
+/// Returns state trie root and nodes with prepared inbound lane state.
+pub fn prepare_message_delivery_storage_proof<B, BHH>(
+	lane: LaneId,
+	inbound_lane_data: &InboundLaneData<AccountIdOf<ThisChain<B>>>,
+	size: StorageProofSize,
+) -> (HashOf<BridgedChain<B>>, RawStorageProof)
+where
+	B: MessageBridge,
+	BHH: Hasher<Out = HashOf<BridgedChain<B>>>,
+	HashOf<BridgedChain<B>>: Copy + Default,
+{
+	// prepare Bridged chain storage with inbound lane state
+	let storage_key = storage_keys::inbound_lane_data_key(B::BRIDGED_MESSAGES_PALLET_NAME, &lane).0;
+	let mut root = Default::default();
+	let mut mdb = MemoryDB::default();
+	{
+		let mut trie = TrieDBMutBuilderV1::<BHH>::new(&mut mdb, &mut root).build();
+		trie.insert(&storage_key, &inbound_lane_data.encode())
+			.map_err(|_| "TrieMut::insert has failed")
+			.expect("TrieMut::insert should not fail");
+	}
+	root = grow_trie(root, &mut mdb, size);
+
+	(root, prove_all_trie_keys(&mdb, &root))
+}
+
+/// Populate trie with dummy keys+values until trie has at least given size.
+pub fn grow_trie<H: Hasher>(
+	mut root: H::Out,
+	mdb: &mut MemoryDB<H>,
+	trie_size: StorageProofSize,
+) -> H::Out {
+	let (iterations, leaf_size, minimal_trie_size) = match trie_size {
+		StorageProofSize::Minimal(_) => return root,
+		StorageProofSize::HasLargeLeaf(size) => (1, size, size),
+		StorageProofSize::HasExtraNodes(size) => (8, 1, size),
+	};
+
+	let mut key_index = 0;
+	loop {
+		let size: usize = prove_all_trie_keys(mdb, &root).iter().map(|n| n.len()).sum();
+		if size > minimal_trie_size as _ {
+			return root
+		}
+
+		let mut trie = TrieDBMutBuilderV1::<H>::from_existing(mdb, &mut root).build();
+		for _ in 0..iterations {
+			trie.insert(&key_index.encode(), &vec![42u8; leaf_size as _])
+				.map_err(|_| "TrieMut::insert has failed")
+				.expect("TrieMut::insert should not fail");
+			key_index += 1;
+		}
+		trie.commit();
+	}
+}
+
+/// Generate storage proof of all keys of the trie with given root.
+fn prove_all_trie_keys<H: Hasher>(mdb: &MemoryDB<H>, root: &H::Out) -> RawStorageProof {
+	let mut proof_recorder = Recorder::<LayoutV1<H>>::new();
+	record_all_trie_keys::<LayoutV1<H>, _>(mdb, root, &mut proof_recorder)
+		.map_err(|_| "record_all_trie_keys has failed")
+		.expect("record_all_trie_keys should not fail");
+	proof_recorder.drain().into_iter().map(|n| n.data.to_vec()).collect()
+}
@@ -0,0 +1,443 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Test vectors for runtimes that are using bridge pallets.
+//!
+//! Every generator returns valid proof, that may be submitted to the bridge pallets, along with
+//! the values that proof verification is expected to return.
+//!
+//! Bridge pallets can't use these helpers in their own tests, because this crate depends on
+//! them. So pallets tests keep using their mock proofs.
+
+#![cfg(any(feature = "test-helpers", test))]
+
+use crate::{
+	messages::{
+		source::{
+			FromBridgedChainMessagesDeliveryProof, ParsedMessagesDeliveryProofFromBridgedChain,
+		},
+		target::FromBridgedChainMessagesProof,
+		AccountIdOf, BalanceOf, BridgedChain, HashOf, MessageBridge, ThisChain,
+	},
+	messages_generation::{prepare_message_delivery_storage_proof, prepare_messages_storage_proof},
+};
+
+use bp_header_chain::InitializationData;
+use bp_messages::{
+	target_chain::{ProvedLaneMessages, ProvedMessages},
+	InboundLaneData, LaneId, Message, MessageData, MessageKey, MessageNonce, OutboundLaneData,
+};
+use bp_runtime::{BasicOperatingMode, StorageProofSize};
+use bp_test_utils::{
+	accounts, make_justification_for_header, test_header, JustificationGeneratorParams,
+};
+use codec::Encode;
+use sp_core::Hasher;
+use sp_runtime::traits::{Header, Zero};
+use sp_std::prelude::*;
+
+/// GRANDPA justification of the bridged chain header.
+pub struct FinalityProofTestVector<H: Header> {
+	/// Data that the bridge GRANDPA pallet needs to be initialized with.
+	pub init_data: InitializationData<H>,
+	/// Header that is finalized by the justification.
+	pub header: H,
+	/// SCALE-encoded GRANDPA justification of the `header`.
+	pub justification: Vec<u8>,
+}
+
+/// Proof of bridged chain messages.
+pub struct MessagesProofTestVector<B: MessageBridge, H> {
+	/// Bridged chain header with the state root that the proof is generated against.
+	pub header: H,
+	/// Messages proof for the `receive_messages_proof` call.
+	pub proof: FromBridgedChainMessagesProof<HashOf<BridgedChain<B>>>,
+	/// Number of messages in the proof.
+	pub messages_count: u32,
+	/// Messages that are expected to be returned by the proof verification.
+	pub expected: ProvedMessages<Message<BalanceOf<BridgedChain<B>>>>,
+}
+
+/// Proof of messages delivery to the bridged chain.
+pub struct MessagesDeliveryProofTestVector<B: MessageBridge, H> {
+	/// Bridged chain header with the state root that the proof is generated against.
+	pub header: H,
+	/// Messages delivery proof for the `receive_messages_delivery_proof` call.
+	pub proof: FromBridgedChainMessagesDeliveryProof<HashOf<BridgedChain<B>>>,
+	/// Inbound lane state that is expected to be returned by the proof verification.
+	pub expected: ParsedMessagesDeliveryProofFromBridgedChain<B>,
+}
+
+impl<B: MessageBridge, H> MessagesProofTestVector<B, H> {
+	/// Returns SCALE-encoded messages proof.
+	pub fn encoded_proof(&self) -> Vec<u8> {
+		self.proof.encode()
+	}
+}
+
+impl<B: MessageBridge, H> MessagesDeliveryProofTestVector<B, H> {
+	/// Returns SCALE-encoded messages delivery proof.
+	pub fn encoded_proof(&self) -> Vec<u8> {
+		self.proof.encode()
+	}
+}
+
+/// Generate GRANDPA justification of given header, signed by the generated authority set of
+/// given size.
+///
+/// The returned initialization data is referencing the header zero, so the `header` number must
+/// be larger than zero. There must be at least three authorities in the set.
+pub fn make_finality_proof_test_vector<H: Header>(
+	header: H,
+	authorities_count: u16,
+	set_id: u64,
+) -> FinalityProofTestVector<H> {
+	assert!(!header.number().is_zero(), "Header zero is used to initialize the pallet");
+
+	let authorities = accounts(authorities_count)
+		.into_iter()
+		.map(|account| (account, 1))
+		.collect::<Vec<_>>();
+	let justification = make_justification_for_header(JustificationGeneratorParams {
+		header: header.clone(),
+		set_id,
+		authorities: authorities.clone(),
+		..Default::default()
+	});
+
+	FinalityProofTestVector {
+		init_data: InitializationData {
+			header: Box::new(test_header(Zero::zero())),
+			authority_list: authorities
+				.into_iter()
+				.map(|(account, weight)| (account.into(), weight))
+				.collect(),
+			set_id,
+			operating_mode: BasicOperatingMode::Normal,
+			checkpoints: vec![],
+		},
+		header,
+		justification: justification.encode(),
+	}
+}
+
+/// Generate proof of given messages (with consecutive nonces, starting from `nonces_start`) and,
+/// optionally, outbound lane state.
+pub fn make_messages_proof_test_vector<B, H, HH>(
+	header_number: H::Number,
+	lane: LaneId,
+	nonces_start: MessageNonce,
+	messages: Vec<MessageData<BalanceOf<BridgedChain<B>>>>,
+	outbound_lane_data: Option<OutboundLaneData>,
+) -> MessagesProofTestVector<B, H>
+where
+	B: MessageBridge,
+	H: Header<Hash = HashOf<BridgedChain<B>>>,
+	HH: Hasher<Out = HashOf<BridgedChain<B>>>,
+	HashOf<BridgedChain<B>>: Copy + Default,
+	BalanceOf<BridgedChain<B>>: Clone,
+{
+	let messages = messages
+		.into_iter()
+		.enumerate()
+		.map(|(index, data)| Message {
+			key: MessageKey { lane_id: lane, nonce: nonces_start + index as MessageNonce },
+			data,
+		})
+		.collect::<Vec<_>>();
+	let (state_root, storage_proof) = prepare_messages_storage_proof::<B, HH>(
+		lane,
+		&messages
+			.iter()
+			.map(|message| (message.key.nonce, message.data.clone()))
+			.collect::<Vec<_>>(),
+		outbound_lane_data.as_ref(),
+		StorageProofSize::Minimal(0),
+	);
+	let header = header_with_state_root::<H>(header_number, state_root);
+
+	let messages_count = messages.len() as u32;
+	let mut expected = ProvedMessages::new();
+	expected.insert(lane, ProvedLaneMessages { lane_state: outbound_lane_data, messages });
+
+	MessagesProofTestVector {
+		proof: FromBridgedChainMessagesProof {
+			bridged_header_hash: header.hash(),
+			storage_proof,
+			lane,
+			nonces_start,
+			nonces_end: (nonces_start + messages_count as MessageNonce).saturating_sub(1),
+		},
+		header,
+		messages_count,
+		expected,
+	}
+}
+
+/// Generate proof of given inbound lane state.
+pub fn make_messages_delivery_proof_test_vector<B, H, HH>(
+	header_number: H::Number,
+	lane: LaneId,
+	inbound_lane_data: InboundLaneData<AccountIdOf<ThisChain<B>>>,
+) -> MessagesDeliveryProofTestVector<B, H>
+where
+	B: MessageBridge,
+	H: Header<Hash = HashOf<BridgedChain<B>>>,
+	HH: Hasher<Out = HashOf<BridgedChain<B>>>,
+	HashOf<BridgedChain<B>>: Copy + Default,
+{
+	let (state_root, storage_proof) = prepare_message_delivery_storage_proof::<B, HH>(
+		lane,
+		&inbound_lane_data,
+		StorageProofSize::Minimal(0),
+	);
+	let header = header_with_state_root::<H>(header_number, state_root);
+
+	MessagesDeliveryProofTestVector {
+		proof: FromBridgedChainMessagesDeliveryProof {
+			bridged_header_hash: header.hash(),
+			storage_proof,
+			lane,
+		},
+		header,
+		expected: (lane, inbound_lane_data),
+	}
+}
+
+/// Create bridged chain header with given state root.
+fn header_with_state_root<H: Header>(number: H::Number, state_root: H::Hash) -> H {
+	H::new(number, Default::default(), state_root, Default::default(), Default::default())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::messages::{source, target};
+	use bp_header_chain::TrustedCheckpoint;
+	use bp_messages::{UnrewardedRelayer, VerificationError};
+	use codec::Decode;
+	use frame_support::assert_ok;
+	use millau_runtime::{
+		rialto_messages::WithRialtoMessageBridge, Origin, RialtoGrandpaInstance, Runtime,
+	};
+	use sp_runtime::traits::BlakeTwo256;
+
+	type BridgedHeader = pallet_bridge_grandpa::BridgedHeader<Runtime, RialtoGrandpaInstance>;
+	type GrandpaPallet = pallet_bridge_grandpa::Pallet<Runtime, RialtoGrandpaInstance>;
+
+	const TEST_LANE_ID: LaneId = LaneId([0, 0, 0, 1]);
+
+	fn run_test(test: impl FnOnce()) {
+		sp_io::TestExternalities::new(Default::default()).execute_with(test)
+	}
+
+	fn import_header(header: BridgedHeader) {
+		assert_ok!(GrandpaPallet::initialize(
+			Origin::root(),
+			InitializationData {
+				header: Box::new(header),
+				authority_list: vec![],
+				set_id: 0,
+				operating_mode: BasicOperatingMode::Normal,
+				checkpoints: vec![],
+			},
+		));
+	}
+
+	#[test]
+	fn finality_proof_test_vector_is_accepted_by_grandpa_pallet() {
+		run_test(|| {
+			let vector = make_finality_proof_test_vector(test_header::<BridgedHeader>(5), 5, 7);
+			assert_ok!(GrandpaPallet::initialize(Origin::root(), vector.init_data));
+			assert_ok!(GrandpaPallet::submit_finality_proof(
+				Origin::signed([1u8; 32].into()),
+				Box::new(vector.header.clone()),
+				Decode::decode(&mut &vector.justification[..]).unwrap(),
+			));
+			assert_eq!(GrandpaPallet::best_finalized(), Some(vector.header));
+		});
+	}
+
+	#[test]
+	fn messages_proof_test_vector_is_verified() {
+		run_test(|| {
+			let vector = make_messages_proof_test_vector::<
+				WithRialtoMessageBridge,
+				BridgedHeader,
+				BlakeTwo256,
+			>(
+				10,
+				TEST_LANE_ID,
+				42,
+				vec![
+					MessageData { fee: 1, payload: vec![1, 2, 3] },
+					MessageData { fee: 2, payload: vec![] },
+					MessageData { fee: 3, payload: vec![42; 100] },
+				],
+				Some(OutboundLaneData {
+					oldest_unpruned_nonce: 40,
+					latest_received_nonce: 41,
+					latest_generated_nonce: 44,
+				}),
+			);
+			assert_eq!(vector.messages_count, 3);
+			assert_eq!(
+				FromBridgedChainMessagesProof::decode(&mut &vector.encoded_proof()[..]).unwrap(),
+				vector.proof,
+			);
+
+			import_header(vector.header);
+			assert_eq!(
+				target::verify_messages_proof::<
+					WithRialtoMessageBridge,
+					Runtime,
+					RialtoGrandpaInstance,
+				>(vector.proof, vector.messages_count,),
+				Ok(vector.expected),
+			);
+		});
+	}
+
+	fn initialize_with_checkpoint(
+		checkpoint: TrustedCheckpoint<
+			<BridgedHeader as Header>::Number,
+			<BridgedHeader as Header>::Hash,
+		>,
+	) {
+		assert_ok!(GrandpaPallet::initialize(
+			Origin::root(),
+			InitializationData {
+				header: Box::new(test_header(100)),
+				authority_list: vec![],
+				set_id: 0,
+				operating_mode: BasicOperatingMode::Normal,
+				checkpoints: vec![checkpoint],
+			},
+		));
+	}
+
+	#[test]
+	fn messages_proof_anchored_at_trusted_checkpoint_is_verified() {
+		run_test(|| {
+			let messages = vec![MessageData { fee: 1, payload: vec![1, 2, 3] }];
+			let vector = make_messages_proof_test_vector::<
+				WithRialtoMessageBridge,
+				BridgedHeader,
+				BlakeTwo256,
+			>(10, TEST_LANE_ID, 1, messages, None);
+
+			initialize_with_checkpoint(TrustedCheckpoint {
+				number: *vector.header.number(),
+				hash: vector.header.hash(),
+				state_root: *vector.header.state_root(),
+			});
+			assert_eq!(
+				target::verify_messages_proof::<
+					WithRialtoMessageBridge,
+					Runtime,
+					RialtoGrandpaInstance,
+				>(vector.proof, vector.messages_count,),
+				Ok(vector.expected),
+			);
+		});
+	}
+
+	#[test]
+	fn messages_proof_anchored_at_unknown_header_is_rejected_with_trusted_checkpoints() {
+		run_test(|| {
+			let messages = vec![MessageData { fee: 1, payload: vec![1, 2, 3] }];
+			let vector = make_messages_proof_test_vector::<
+				WithRialtoMessageBridge,
+				BridgedHeader,
+				BlakeTwo256,
+			>(10, TEST_LANE_ID, 1, messages, None);
+
+			// the checkpoint has the same state root, but the proof is anchored at another header
+			initialize_with_checkpoint(TrustedCheckpoint {
+				number: 11,
+				hash: test_header::<BridgedHeader>(11).hash(),
+				state_root: *vector.header.state_root(),
+			});
+			assert_eq!(
+				target::verify_messages_proof::<
+					WithRialtoMessageBridge,
+					Runtime,
+					RialtoGrandpaInstance,
+				>(vector.proof, vector.messages_count,),
+				Err(VerificationError::Other("UnknownHeader")),
+			);
+		});
+	}
+
+	#[test]
+	fn messages_proof_test_vector_with_lane_state_only_is_verified() {
+		run_test(|| {
+			let vector = make_messages_proof_test_vector::<
+				WithRialtoMessageBridge,
+				BridgedHeader,
+				BlakeTwo256,
+			>(10, TEST_LANE_ID, 1, vec![], Some(OutboundLaneData::default()));
+			assert_eq!(vector.messages_count, 0);
+
+			import_header(vector.header);
+			assert_eq!(
+				target::verify_messages_proof::<
+					WithRialtoMessageBridge,
+					Runtime,
+					RialtoGrandpaInstance,
+				>(vector.proof, vector.messages_count,),
+				Ok(vector.expected),
+			);
+		});
+	}
+
+	#[test]
+	fn messages_delivery_proof_test_vector_is_verified() {
+		run_test(|| {
+			let vector = make_messages_delivery_proof_test_vector::<
+				WithRialtoMessageBridge,
+				BridgedHeader,
+				BlakeTwo256,
+			>(
+				10,
+				TEST_LANE_ID,
+				InboundLaneData {
+					relayers: vec![UnrewardedRelayer {
+						relayer: [1u8; 32].into(),
+						messages: bp_messages::DeliveredMessages::new(1, true),
+					}]
+					.into(),
+					last_confirmed_nonce: 0,
+				},
+			);
+			assert_eq!(
+				FromBridgedChainMessagesDeliveryProof::decode(&mut &vector.encoded_proof()[..])
+					.unwrap(),
+				vector.proof,
+			);
+
+			import_header(vector.header);
+			assert_eq!(
+				source::verify_messages_delivery_proof::<
+					WithRialtoMessageBridge,
+					Runtime,
+					RialtoGrandpaInstance,
+				>(vector.proof),
+				Ok(vector.expected),
+			);
+		});
+	}
+}
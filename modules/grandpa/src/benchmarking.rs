@@ -41,17 +41,19 @@
 
 use crate::*;
 
+use bp_header_chain::justification::{max_expected_justification_size, SIGNED_PRECOMMIT_SIZE};
 use bp_runtime::BasicOperatingMode;
 use bp_test_utils::{
 	accounts, make_justification_for_header, JustificationGeneratorParams, TEST_GRANDPA_ROUND,
 	TEST_GRANDPA_SET_ID,
 };
+use codec::Encode;
 use frame_benchmarking::{benchmarks_instance_pallet, whitelisted_caller};
 use frame_support::traits::Get;
 use frame_system::RawOrigin;
 use sp_finality_grandpa::AuthorityId;
-use sp_runtime::traits::Zero;
-use sp_std::vec::Vec;
+use sp_runtime::{traits::Zero, DigestItem};
+use sp_std::{vec, vec::Vec};
 
 // The maximum number of vote ancestries to include in a justification.
 //
@@ -66,19 +68,44 @@ const MAX_VALIDATOR_SET_SIZE: u32 = 1024;
 // `1..MAX_VALIDATOR_SET_SIZE` and `1..MAX_VOTE_ANCESTRIES` are too large && benchmarks are
 // running for almost 40m (steps=50, repeat=20) on a decent laptop, which is too much. Since
 // we're building linear function here, let's just select some limited subrange for benchmarking.
-const VALIDATOR_SET_SIZE_RANGE_BEGIN: u32 = MAX_VALIDATOR_SET_SIZE / 20;
-const VALIDATOR_SET_SIZE_RANGE_END: u32 =
-	VALIDATOR_SET_SIZE_RANGE_BEGIN + VALIDATOR_SET_SIZE_RANGE_BEGIN;
-const MAX_VOTE_ANCESTRIES_RANGE_BEGIN: u32 = MAX_VOTE_ANCESTRIES / 20;
-const MAX_VOTE_ANCESTRIES_RANGE_END: u32 =
-	MAX_VOTE_ANCESTRIES_RANGE_BEGIN + MAX_VOTE_ANCESTRIES_RANGE_BEGIN;
+const VALIDATOR_SET_SIZE_RANGE_END: u32 = MAX_VALIDATOR_SET_SIZE / 10;
+const MAX_VOTE_ANCESTRIES_RANGE_END: u32 = MAX_VOTE_ANCESTRIES / 10;
+
+/// Returns maximal number of pre-commits in the benchmarked justification.
+///
+/// Half of the `MaxJustificationSize` (excluding fixed justification fields) is reserved for
+/// pre-commits, so that the benchmarked justification is never rejected by the pallet. The
+/// number of pre-commits is also limited by the `MaxBridgedAuthorities`.
+fn max_precommits<T: Config<I>, I: 'static>() -> u32 {
+	let max_precommits = available_justification_size::<T, I>() / SIGNED_PRECOMMIT_SIZE;
+	max_precommits
+		.min(T::MaxBridgedAuthorities::get())
+		.clamp(2, VALIDATOR_SET_SIZE_RANGE_END)
+}
+
+/// Returns maximal number of vote ancestries in the benchmarked justification.
+///
+/// Half of the `MaxJustificationSize` (excluding fixed justification fields) is reserved for
+/// vote ancestries, so that the benchmarked justification is never rejected by the pallet.
+fn max_vote_ancestries<T: Config<I>, I: 'static>() -> u32 {
+	let mut max_ancestry_header: BridgedHeader<T, I> = bp_test_utils::test_header(u32::MAX.into());
+	max_ancestry_header.digest_mut().logs.push(DigestItem::Other(u32::MAX.encode()));
+	let max_vote_ancestries =
+		available_justification_size::<T, I>() / max_ancestry_header.encoded_size() as u32;
+	max_vote_ancestries.clamp(2, MAX_VOTE_ANCESTRIES_RANGE_END)
+}
+
+/// Returns half of `MaxJustificationSize`, excluding size of fixed justification fields.
+fn available_justification_size<T: Config<I>, I: 'static>() -> u32 {
+	T::MaxJustificationSize::get().saturating_sub(max_expected_justification_size(0, 0)) / 2
+}
 
 /// Returns number of first header to be imported.
 ///
 /// Since we bootstrap the pallet with `HeadersToKeep` already imported headers,
 /// this function computes the next expected header number to import.
 fn header_number<T: Config<I>, I: 'static, N: From<u32>>() -> N {
-	(T::HeadersToKeep::get() + 1).into()
+	(crate::Pallet::<T, I>::headers_to_keep() + 1).into()
 }
 
 /// Prepare header and its justification to submit using `submit_finality_proof`.
@@ -96,6 +123,7 @@ fn prepare_benchmark_data<T: Config<I>, I: 'static>(
 		authority_list,
 		set_id: TEST_GRANDPA_SET_ID,
 		operating_mode: BasicOperatingMode::Normal,
+		checkpoints: Vec::new(),
 	};
 
 	bootstrap_bridge::<T, I>(init_data);
@@ -113,12 +141,35 @@ fn prepare_benchmark_data<T: Config<I>, I: 'static>(
 	(header, justification)
 }
 
+/// Prepare `proofs` consecutive headers and their minimal justifications to submit using
+/// `submit_finality_proof_batch`.
+fn prepare_batch_benchmark_data<T: Config<I>, I: 'static>(proofs: u32) -> Vec<FinalityProof<T, I>> {
+	const PRECOMMITS: u32 = 2;
+
+	let (header, justification) = prepare_benchmark_data::<T, I>(PRECOMMITS, 1);
+	let mut finality_proofs = vec![(Box::new(header), justification)];
+	for i in 1..proofs {
+		let header: BridgedHeader<T, I> =
+			bp_test_utils::test_header(header_number::<T, I, u32>().saturating_add(i).into());
+		let params = JustificationGeneratorParams {
+			header: header.clone(),
+			round: TEST_GRANDPA_ROUND,
+			set_id: TEST_GRANDPA_SET_ID,
+			authorities: accounts(PRECOMMITS as u16).iter().map(|k| (*k, 1)).collect::<Vec<_>>(),
+			ancestors: 1,
+			forks: 1,
+		};
+		finality_proofs.push((Box::new(header), make_justification_for_header(params)));
+	}
+	finality_proofs
+}
+
 benchmarks_instance_pallet! {
 	// This is the "gold standard" benchmark for this extrinsic, and it's what should be used to
 	// annotate the weight in the pallet.
 	submit_finality_proof {
-		let p in VALIDATOR_SET_SIZE_RANGE_BEGIN..VALIDATOR_SET_SIZE_RANGE_END;
-		let v in MAX_VOTE_ANCESTRIES_RANGE_BEGIN..MAX_VOTE_ANCESTRIES_RANGE_END;
+		let p in 1..max_precommits::<T, I>();
+		let v in 1..max_vote_ancestries::<T, I>();
 		let caller: T::AccountId = whitelisted_caller();
 		let (header, justification) = prepare_benchmark_data::<T, I>(p, v);
 	}: submit_finality_proof(RawOrigin::Signed(caller), Box::new(header), justification)
@@ -129,4 +180,21 @@ benchmarks_instance_pallet! {
 		assert_eq!(<BestFinalized<T, I>>::get().unwrap().1, expected_hash);
 		assert!(<ImportedHeaders<T, I>>::contains_key(expected_hash));
 	}
+
+	// The pallet only uses the batch overhead from this benchmark, i.e. results must be reduced by
+	// the weight of `n` minimal `submit_finality_proof` calls. Weights of individual proofs are
+	// added to it by the pallet, using the `submit_finality_proof` weight formula.
+	submit_finality_proof_batch {
+		let n in 1..T::MaxFinalityProofsInBatch::get();
+		let caller: T::AccountId = whitelisted_caller();
+		let finality_proofs = prepare_batch_benchmark_data::<T, I>(n);
+	}: submit_finality_proof_batch(RawOrigin::Signed(caller), finality_proofs)
+	verify {
+		let last_header_number = header_number::<T, I, u32>() + n - 1;
+		let header: BridgedHeader<T, I> = bp_test_utils::test_header(last_header_number.into());
+		let expected_hash = header.hash();
+
+		assert_eq!(<BestFinalized<T, I>>::get().unwrap().1, expected_hash);
+		assert!(<ImportedHeaders<T, I>>::contains_key(expected_hash));
+	}
 }
@@ -35,6 +35,12 @@ impl<
 		let bundled_block_number = match call.is_sub_type() {
 			Some(crate::Call::<T, I>::submit_finality_proof { ref finality_target, .. }) =>
 				*finality_target.number(),
+			// the batch is obsolete if its first header is obsolete
+			Some(crate::Call::<T, I>::submit_finality_proof_batch { ref finality_proofs }) =>
+				match finality_proofs.first() {
+					Some((finality_target, _)) => *finality_target.number(),
+					None => return InvalidTransaction::Call.into(),
+				},
 			_ => return Ok(ValidTransaction::default()),
 		};
 
@@ -63,19 +69,62 @@ impl<
 mod tests {
 	use super::FilterCall;
 	use crate::{
-		mock::{run_test, test_header, Call, TestNumber, TestRuntime},
+		mock::{run_test, test_header, Call, Origin, TestHeader, TestNumber, TestRuntime},
 		BestFinalized,
 	};
-	use bp_test_utils::make_default_justification;
+	use bp_header_chain::InitializationData;
+	use bp_runtime::BasicOperatingMode;
+	use bp_test_utils::{authority_list, make_default_justification};
+	use frame_support::assert_ok;
+	use sp_runtime::transaction_validity::{InvalidTransaction, TransactionValidity};
 
-	fn validate_block_submit(num: TestNumber) -> bool {
+	fn initialization_data() -> InitializationData<TestHeader> {
+		InitializationData {
+			header: Box::new(test_header(0)),
+			authority_list: authority_list(),
+			set_id: 1,
+			operating_mode: BasicOperatingMode::Normal,
+			checkpoints: vec![],
+		}
+	}
+
+	fn validate_call(num: TestNumber) -> TransactionValidity {
 		crate::Pallet::<TestRuntime>::validate(&Call::Grandpa(
 			crate::Call::<TestRuntime, ()>::submit_finality_proof {
 				finality_target: Box::new(test_header(num)),
 				justification: make_default_justification(&test_header(num)),
 			},
 		))
-		.is_ok()
+	}
+
+	fn validate_batch_call(nums: &[TestNumber]) -> TransactionValidity {
+		crate::Pallet::<TestRuntime>::validate(&Call::Grandpa(
+			crate::Call::<TestRuntime, ()>::submit_finality_proof_batch {
+				finality_proofs: nums
+					.iter()
+					.map(|num| {
+						(
+							Box::new(test_header(*num)),
+							make_default_justification(&test_header(*num)),
+						)
+					})
+					.collect(),
+			},
+		))
+	}
+
+	fn validate_block_submit(num: TestNumber) -> bool {
+		validate_call(num).is_ok()
+	}
+
+	fn import_header(num: TestNumber) {
+		let header = test_header(num);
+		let justification = make_default_justification(&header);
+		assert_ok!(crate::Pallet::<TestRuntime>::submit_finality_proof(
+			Origin::signed(1),
+			Box::new(header),
+			justification,
+		));
 	}
 
 	fn sync_to_header_10() {
@@ -112,4 +161,67 @@ mod tests {
 			assert!(validate_block_submit(15));
 		});
 	}
+
+	#[test]
+	fn extension_accepts_initialization() {
+		run_test(|| {
+			// when the pallet is not yet initialized, the `initialize` call must not be rejected
+			let call = Call::Grandpa(crate::Call::<TestRuntime, ()>::initialize {
+				init_data: initialization_data(),
+			});
+			assert_ok!(crate::Pallet::<TestRuntime>::validate(&call));
+		});
+	}
+
+	#[test]
+	fn extension_rejects_loser_of_race_for_the_same_header() {
+		run_test(|| {
+			assert_ok!(crate::Pallet::<TestRuntime>::initialize(
+				Origin::root(),
+				initialization_data()
+			));
+
+			// two relayers are racing to submit proofs of header#1 => both transactions are
+			// accepted by the pool
+			assert_ok!(validate_call(1));
+			assert_ok!(validate_call(1));
+
+			// when the first transaction is included, the second one is rejected on revalidation
+			import_header(1);
+			assert_eq!(validate_call(1), InvalidTransaction::Stale.into());
+		});
+	}
+
+	#[test]
+	fn extension_rejects_loser_of_race_for_different_headers() {
+		run_test(|| {
+			assert_ok!(crate::Pallet::<TestRuntime>::initialize(
+				Origin::root(),
+				initialization_data()
+			));
+
+			// first relayer submits proof of header#5 and second relayer submits proof of
+			// header#3 => both transactions are accepted by the pool
+			assert_ok!(validate_call(5));
+			assert_ok!(validate_call(3));
+
+			// when the header#5 is imported, the proof of header#3 becomes obsolete, but proofs
+			// of better headers are still accepted
+			import_header(5);
+			assert_eq!(validate_call(3), InvalidTransaction::Stale.into());
+			assert_ok!(validate_call(6));
+		});
+	}
+
+	#[test]
+	fn extension_validates_batch_by_its_first_header() {
+		run_test(|| {
+			// when current best finalized is #10, the batch is rejected if it is empty or if its
+			// first header is obsolete
+			sync_to_header_10();
+			assert_eq!(validate_batch_call(&[]), InvalidTransaction::Call.into());
+			assert_eq!(validate_batch_call(&[10, 11]), InvalidTransaction::Stale.into());
+			assert!(validate_batch_call(&[11, 12]).is_ok());
+		});
+	}
 }
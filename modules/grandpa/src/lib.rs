@@ -24,7 +24,9 @@
 //!
 //! The pallet is responsible for tracking GRANDPA validator set hand-offs. We only import headers
 //! with justifications signed by the current validator set we know of. The header is inspected for
-//! a `ScheduledChanges` digest item, which is then used to update to next validator set.
+//! a `ScheduledChanges` digest item, which is then used to update to next validator set. If the
+//! change is delayed, it is kept pending until the enacting header. Descendants of the enacting
+//! header are verified using the next validator set.
 //!
 //! Since this pallet only tracks finalized headers it does not deal with forks. Forks can only
 //! occur if the GRANDPA validator set on the bridged chain is either colluding or there is a severe
@@ -36,19 +38,24 @@
 // Runtime-generated enums
 #![allow(clippy::large_enum_variant)]
 
-use bp_header_chain::{justification::GrandpaJustification, InitializationData};
-use bp_runtime::{BlockNumberOf, Chain, HashOf, HasherOf, HeaderOf, OwnedBridgeModule};
+use bp_header_chain::{
+	find_grandpa_authorities_forced_change, find_grandpa_authorities_scheduled_change,
+	justification::GrandpaJustification, InitializationData, ScheduledAuthoritySetChange,
+};
+use bp_runtime::{BlockNumberOf, Chain, HashOf, HasherOf, HeaderId, HeaderOf, OwnedBridgeModule};
 use finality_grandpa::voter_set::VoterSet;
 use frame_support::{ensure, fail};
 use frame_system::ensure_signed;
-use sp_finality_grandpa::{ConsensusLog, GRANDPA_ENGINE_ID};
-use sp_runtime::traits::{Header as HeaderT, Zero};
-use sp_std::{boxed::Box, convert::TryInto};
+use sp_runtime::traits::{Header as HeaderT, Saturating};
+use sp_std::{boxed::Box, convert::TryInto, ops::RangeInclusive, vec::Vec};
 
 mod extension;
 #[cfg(test)]
 mod mock;
 
+/// Module, containing storage migrations of this pallet.
+pub mod migration;
+
 /// Module, containing weights for this pallet.
 pub mod weights;
 
@@ -62,6 +69,24 @@ pub use weights::WeightInfo;
 /// The target that will be used when publishing logs related to this pallet.
 pub const LOG_TARGET: &str = "runtime::bridge-grandpa";
 
+/// Allowed values of the `HeadersToKeep` storage value.
+///
+/// We always need to keep at least the best finalized header. The upper bound limits the
+/// number of ring buffer entries that may need to be pruned when the value is decreased.
+pub const HEADERS_TO_KEEP_BOUNDS: RangeInclusive<u32> = 1..=1_000_000;
+
+/// Allowed values of the `MaxRequests` storage value.
+///
+/// Zero would block all header imports forever, so the pallet requires at least one request
+/// per block window.
+pub const MAX_REQUESTS_BOUNDS: RangeInclusive<u32> = 1..=10_000;
+
+/// Maximal value of the `MinHeaderGap` storage value.
+///
+/// Larger gaps would leave only mandatory headers importable for too long, so messages and
+/// parachain heads proofs would have no recent headers to be verified against.
+pub const MAX_MIN_HEADER_GAP: u32 = 10_000;
+
 /// Block number of the bridged chain.
 pub type BridgedBlockNumber<T, I> = BlockNumberOf<<T as Config<I>>::BridgedChain>;
 /// Block hash of the bridged chain.
@@ -70,43 +95,97 @@ pub type BridgedBlockHash<T, I> = HashOf<<T as Config<I>>::BridgedChain>;
 pub type BridgedBlockHasher<T, I> = HasherOf<<T as Config<I>>::BridgedChain>;
 /// Header of the bridged chain.
 pub type BridgedHeader<T, I> = HeaderOf<<T as Config<I>>::BridgedChain>;
+/// Header of the bridged chain and its finality proof.
+pub type FinalityProof<T, I> =
+	(Box<BridgedHeader<T, I>>, GrandpaJustification<BridgedHeader<T, I>>);
 
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
 	use bp_runtime::BasicOperatingMode;
-	use frame_support::pallet_prelude::*;
+	use frame_support::{pallet_prelude::*, weights::PostDispatchInfo};
 	use frame_system::pallet_prelude::*;
 
 	#[pallet::config]
 	pub trait Config<I: 'static = ()>: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self, I>> + IsType<<Self as frame_system::Config>::Event>;
+
 		/// The chain we are bridging to here.
 		type BridgedChain: Chain;
 
-		/// The upper bound on the number of requests allowed by the pallet.
+		/// The initial upper bound on the number of requests allowed by the pallet.
 		///
 		/// A request refers to an action which writes a header to storage.
 		///
 		/// Once this bound is reached the pallet will not allow any dispatchables to be called
 		/// until the request count has decreased.
+		///
+		/// This is only the initial value of the `MaxRequests` storage value. The actual value
+		/// may be changed by the pallet owner using the `set_max_requests` call.
 		#[pallet::constant]
 		type MaxRequests: Get<u32>;
 
-		/// Maximal number of finalized headers to keep in the storage.
+		/// Maximal weight of header imports in a single block.
+		///
+		/// Every import consumes its actual (post-dispatch) weight from this budget, so more
+		/// headers with small justifications may be imported in a single block than headers with
+		/// huge justifications. The `MaxRequests` is still a hard limit on the number of imports.
+		#[pallet::constant]
+		type MaxRequestsWeight: Get<Weight>;
+
+		/// Initial maximal number of finalized headers to keep in the storage.
 		///
 		/// The setting is there to prevent growing the on-chain state indefinitely. Note
 		/// the setting does not relate to block numbers - we will simply keep as much items
 		/// in the storage, so it doesn't guarantee any fixed timeframe for finality headers.
 		///
-		/// Incautious change of this constant may lead to orphan entries in the runtime storage.
+		/// This is only the initial value of the `HeadersToKeep` storage value. The actual value
+		/// may be changed by the pallet owner using the `set_headers_to_keep` call.
 		#[pallet::constant]
 		type HeadersToKeep: Get<u32>;
 
+		/// Maximal number of justifications of the most recently imported headers to keep in
+		/// the storage.
+		///
+		/// Stored justifications may be used to build equivocation evidence. They are pruned
+		/// together with their headers, so the actual number of stored justifications never
+		/// exceeds the `HeadersToKeep` storage value. Zero disables justifications storage.
+		#[pallet::constant]
+		type JustificationsToKeep: Get<u32>;
+
+		/// Maximal size (in bytes) of the encoded justification that the pallet accepts.
+		///
+		/// Larger justifications are rejected before any verification. The value should be
+		/// computed from the maximal number of authorities of the bridged chain (see
+		/// `bp_header_chain::justification::max_expected_justification_size`).
+		#[pallet::constant]
+		type MaxJustificationSize: Get<u32>;
+
+		/// Maximal number of authorities in the GRANDPA authority set of the bridged chain.
+		///
+		/// Authority sets and justifications with more authorities are rejected by the pallet.
+		#[pallet::constant]
+		type MaxBridgedAuthorities: Get<u32>;
+
+		/// Maximal size (in bytes) of the encoded header of the bridged chain that the pallet
+		/// accepts.
+		#[pallet::constant]
+		type MaxBridgedHeaderSize: Get<u32>;
+
+		/// Maximal number of finality proofs in the `submit_finality_proof_batch` call.
+		#[pallet::constant]
+		type MaxFinalityProofsInBatch: Get<u32>;
+
 		/// Weights gathered through benchmarking.
 		type WeightInfo: WeightInfo;
 	}
 
+	/// The current storage version of the pallet.
+	pub(crate) const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	#[pallet::without_storage_info]
 	pub struct Pallet<T, I = ()>(PhantomData<(T, I)>);
 
@@ -114,16 +193,18 @@ pub mod pallet {
 	impl<T: Config<I>, I: 'static> Hooks<BlockNumberFor<T>> for Pallet<T, I> {
 		fn on_initialize(_n: T::BlockNumber) -> frame_support::weights::Weight {
 			<RequestCount<T, I>>::mutate(|count| *count = count.saturating_sub(1));
+			<RemainingRequestsWeight<T, I>>::put(T::MaxRequestsWeight::get());
 
 			(0_u64)
 				.saturating_add(T::DbWeight::get().reads(1))
-				.saturating_add(T::DbWeight::get().writes(1))
+				.saturating_add(T::DbWeight::get().writes(2))
 		}
 	}
 
 	impl<T: Config<I>, I: 'static> OwnedBridgeModule<T> for Pallet<T, I> {
 		const LOG_TARGET: &'static str = LOG_TARGET;
 		type OwnerStorage = PalletOwner<T, I>;
+		type PendingOwnerStorage = PalletPendingOwner<T, I>;
 		type OperatingMode = BasicOperatingMode;
 		type OperatingModeStorage = PalletOperatingMode<T, I>;
 	}
@@ -137,10 +218,10 @@ pub mod pallet {
 		///
 		/// If successful in verification, it will write the target header to the underlying storage
 		/// pallet.
-		#[pallet::weight(T::WeightInfo::submit_finality_proof(
-			justification.commit.precommits.len().try_into().unwrap_or(u32::MAX),
-			justification.votes_ancestries.len().try_into().unwrap_or(u32::MAX),
-		))]
+		///
+		/// The first successful import of a mandatory header, enacting the authority set change,
+		/// is free for the submitter. Regular headers and failed imports are always paid.
+		#[pallet::weight(submit_finality_proof_weight::<T, I>(justification))]
 		pub fn submit_finality_proof(
 			origin: OriginFor<T>,
 			finality_target: Box<BridgedHeader<T, I>>,
@@ -148,59 +229,16 @@ pub mod pallet {
 		) -> DispatchResultWithPostInfo {
 			Self::ensure_not_halted().map_err(Error::<T, I>::BridgeModule)?;
 			let _ = ensure_signed(origin)?;
+			ensure!(Self::request_count() < Self::max_requests(), <Error<T, I>>::TooManyRequests);
 
-			ensure!(Self::request_count() < T::MaxRequests::get(), <Error<T, I>>::TooManyRequests);
-
-			let (hash, number) = (finality_target.hash(), finality_target.number());
-			log::trace!(
-				target: LOG_TARGET,
-				"Going to try and finalize header {:?}",
-				finality_target
-			);
-
-			let best_finalized = BestFinalized::<T, I>::get();
-			let best_finalized =
-				best_finalized.and_then(|(_, hash)| ImportedHeaders::<T, I>::get(hash));
-			let best_finalized = match best_finalized {
-				Some(best_finalized) => best_finalized,
-				None => {
-					log::error!(
-						target: LOG_TARGET,
-						"Cannot finalize header {:?} because pallet is not yet initialized",
-						finality_target,
-					);
-					fail!(<Error<T, I>>::NotInitialized);
-				},
-			};
-
-			// We do a quick check here to ensure that our header chain is making progress and isn't
-			// "travelling back in time" (which could be indicative of something bad, e.g a
-			// hard-fork).
-			ensure!(best_finalized.number() < number, <Error<T, I>>::OldHeader);
-
-			let authority_set = <CurrentAuthoritySet<T, I>>::get();
-			let set_id = authority_set.set_id;
-			verify_justification::<T, I>(&justification, hash, *number, authority_set)?;
-
-			let is_authorities_change_enacted =
-				try_enact_authority_change::<T, I>(&finality_target, set_id)?;
+			let (actual_weight, pays_fee) =
+				import_finality_proof::<T, I>(*finality_target, justification)?;
 			<RequestCount<T, I>>::mutate(|count| *count += 1);
-			insert_header::<T, I>(*finality_target, hash);
-			log::info!(
-				target: LOG_TARGET,
-				"Successfully imported finalized header with hash {:?}!",
-				hash
-			);
 
-			// mandatory header is a header that changes authorities set. The pallet can't go
-			// further without importing this header. So every bridge MUST import mandatory headers.
-			//
-			// We don't want to charge extra costs for mandatory operations. So relayer is not
-			// paying fee for mandatory headers import transactions.
-			let is_mandatory_header = is_authorities_change_enacted;
-			let pays_fee = if is_mandatory_header { Pays::No } else { Pays::Yes };
-
-			Ok(pays_fee.into())
+			Ok(match pays_fee {
+				Pays::Yes => Pays::Yes.into(),
+				Pays::No => PostDispatchInfo { actual_weight: Some(actual_weight), pays_fee },
+			})
 		}
 
 		/// Bootstrap the bridge pallet with an initial header and authority set from which to sync.
@@ -212,7 +250,17 @@ pub mod pallet {
 		/// This function is only allowed to be called from a trusted origin and writes to storage
 		/// with practically no checks in terms of the validity of the data. It is important that
 		/// you ensure that valid data is being passed in.
-		#[pallet::weight((T::DbWeight::get().reads_writes(2, 5), DispatchClass::Operational))]
+		///
+		/// Only the number and the state root of trusted checkpoints from the `init_data` are
+		/// stored. They may be used to verify storage proofs and are never pruned. Checkpoints
+		/// must not collide with the initial header.
+		#[pallet::weight((
+			T::DbWeight::get().reads_writes(
+				2,
+				5u64.saturating_add(init_data.checkpoints.len() as u64),
+			),
+			DispatchClass::Operational,
+		))]
 		pub fn initialize(
 			origin: OriginFor<T>,
 			init_data: super::InitializationData<BridgedHeader<T, I>>,
@@ -221,7 +269,17 @@ pub mod pallet {
 
 			let init_allowed = !<BestFinalized<T, I>>::exists();
 			ensure!(init_allowed, <Error<T, I>>::AlreadyInitialized);
+			ensure!(
+				init_data.authority_list.len() <= T::MaxBridgedAuthorities::get() as usize,
+				<Error<T, I>>::TooManyAuthoritiesInSet
+			);
+			ensure_checkpoints_are_valid::<T, I>(&init_data)?;
 			initialize_bridge::<T, I>(init_data.clone());
+			Self::deposit_event(Event::UpdatedBestFinalizedHeader {
+				number: *init_data.header.number(),
+				hash: init_data.header.hash(),
+			});
+			Self::deposit_event(Event::AuthoritySetChanged { new_set_id: init_data.set_id });
 
 			log::info!(
 				target: LOG_TARGET,
@@ -234,21 +292,278 @@ pub mod pallet {
 
 		/// Change `PalletOwner`.
 		///
-		/// May only be called either by root, or by `PalletOwner`.
+		/// May only be called by root.
 		#[pallet::weight((T::DbWeight::get().reads_writes(1, 1), DispatchClass::Operational))]
 		pub fn set_owner(origin: OriginFor<T>, new_owner: Option<T::AccountId>) -> DispatchResult {
 			<Self as OwnedBridgeModule<_>>::set_owner(origin, new_owner)
 		}
 
-		/// Halt or resume all pallet operations.
+		/// Propose new `PalletOwner`.
 		///
-		/// May only be called either by root, or by `PalletOwner`.
+		/// May only be called either by root, or by `PalletOwner`. The ownership is only
+		/// transferred after the proposed account calls `accept_ownership`.
 		#[pallet::weight((T::DbWeight::get().reads_writes(1, 1), DispatchClass::Operational))]
+		pub fn propose_owner(origin: OriginFor<T>, new_owner: T::AccountId) -> DispatchResult {
+			<Self as OwnedBridgeModule<_>>::propose_owner(origin, new_owner.clone())?;
+			Self::deposit_event(Event::OwnershipTransferProposed { pending_owner: new_owner });
+			Ok(())
+		}
+
+		/// Accept pallet ownership.
+		///
+		/// May only be called by the account that has been proposed by the `propose_owner` call.
+		#[pallet::weight((T::DbWeight::get().reads_writes(2, 2), DispatchClass::Operational))]
+		pub fn accept_ownership(origin: OriginFor<T>) -> DispatchResult {
+			let (old_owner, new_owner) = <Self as OwnedBridgeModule<_>>::accept_ownership(origin)
+				.map_err(Error::<T, I>::BridgeModule)?;
+			Self::deposit_event(Event::OwnershipTransferred { old_owner, new_owner });
+			Ok(())
+		}
+
+		/// Cancel pending `PalletOwner` change.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::weight((T::DbWeight::get().reads_writes(2, 1), DispatchClass::Operational))]
+		pub fn cancel_pending_owner(origin: OriginFor<T>) -> DispatchResult {
+			let pending_owner = <Self as OwnedBridgeModule<_>>::cancel_pending_owner(origin)?
+				.ok_or(Error::<T, I>::BridgeModule(
+					bp_runtime::OwnedBridgeModuleError::NoPendingOwner,
+				))?;
+			Self::deposit_event(Event::OwnershipTransferCancelled { pending_owner });
+			Ok(())
+		}
+
+		/// Halt or resume headers import.
+		///
+		/// Halted pallet rejects new finality proofs, but keeps serving the best finalized header
+		/// and imported headers. When the pallet is halted, the number of the best finalized
+		/// header is saved to the `FrozenAt` storage value.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::weight((T::DbWeight::get().reads_writes(4, 2), DispatchClass::Operational))]
 		pub fn set_operating_mode(
 			origin: OriginFor<T>,
 			operating_mode: BasicOperatingMode,
 		) -> DispatchResult {
-			<Self as OwnedBridgeModule<_>>::set_operating_mode(origin, operating_mode)
+			Self::ensure_owner_or_root(origin)?;
+			let old_operating_mode =
+				<Self as OwnedBridgeModule<_>>::set_operating_mode(operating_mode)
+					.map_err(Error::<T, I>::BridgeModule)?;
+			update_frozen_at::<T, I>(operating_mode);
+			Self::deposit_event(Event::OperatingModeChanged {
+				old_operating_mode,
+				new_operating_mode: operating_mode,
+			});
+			Ok(())
+		}
+
+		/// Change the maximal number of finalized headers to keep in the storage.
+		///
+		/// If the new value is lower than the current one, the oldest headers that are above
+		/// the new limit are pruned immediately. If it is larger, the pallet simply starts
+		/// keeping more headers - already pruned headers are not restored.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::weight((
+			T::DbWeight::get().reads_writes(3, 1).saturating_add(
+				T::DbWeight::get()
+					.reads_writes(1, 2)
+					.saturating_mul(headers_to_prune::<T, I>(*headers_to_keep).into())
+			),
+			DispatchClass::Operational,
+		))]
+		pub fn set_headers_to_keep(origin: OriginFor<T>, headers_to_keep: u32) -> DispatchResult {
+			Self::ensure_owner_or_root(origin)?;
+			ensure!(
+				HEADERS_TO_KEEP_BOUNDS.contains(&headers_to_keep),
+				Error::<T, I>::InvalidHeadersToKeep,
+			);
+
+			let pruned_headers = prune_headers::<T, I>(Self::headers_to_keep(), headers_to_keep);
+			<HeadersToKeep<T, I>>::put(headers_to_keep);
+
+			log::info!(
+				target: LOG_TARGET,
+				"Setting headers to keep to {}. Pruned {} headers",
+				headers_to_keep,
+				pruned_headers,
+			);
+			Self::deposit_event(Event::HeadersToKeepUpdated { headers_to_keep, pruned_headers });
+			Ok(())
+		}
+
+		/// Change the upper bound on the number of requests allowed by the pallet.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::weight((T::DbWeight::get().reads_writes(2, 1), DispatchClass::Operational))]
+		pub fn set_max_requests(origin: OriginFor<T>, max_requests: u32) -> DispatchResult {
+			Self::ensure_owner_or_root(origin)?;
+			ensure!(MAX_REQUESTS_BOUNDS.contains(&max_requests), Error::<T, I>::InvalidMaxRequests);
+
+			<MaxRequests<T, I>>::put(max_requests);
+
+			log::info!(target: LOG_TARGET, "Setting max requests to {}", max_requests);
+			Self::deposit_event(Event::MaxRequestsUpdated { max_requests });
+			Ok(())
+		}
+
+		/// Re-initialize already initialized pallet with a new header and authority set.
+		///
+		/// This is the recovery procedure for the case when the pallet is stuck at the stale
+		/// authority set, e.g. because relayers have been offline for too long and the bridged
+		/// chain doesn't keep justifications for mandatory headers anymore. The header and the
+		/// authority set id from the `init_data` must be ahead of the current pallet state.
+		///
+		/// All imported headers are removed from the storage. Trusted checkpoints from the
+		/// `init_data` are added to the existing checkpoints.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::weight((
+			T::DbWeight::get()
+				.reads_writes(
+					4,
+					7u64.saturating_add(init_data.checkpoints.len() as u64),
+				)
+				.saturating_add(
+					T::DbWeight::get()
+						.reads_writes(1, 2)
+						.saturating_mul(Pallet::<T, I>::headers_to_keep().into())
+				),
+			DispatchClass::Operational,
+		))]
+		pub fn reinitialize(
+			origin: OriginFor<T>,
+			init_data: super::InitializationData<BridgedHeader<T, I>>,
+		) -> DispatchResultWithPostInfo {
+			Self::ensure_owner_or_root(origin)?;
+
+			let (best_finalized_number, _) =
+				<BestFinalized<T, I>>::get().ok_or(<Error<T, I>>::NotInitialized)?;
+			let current_set_id = <CurrentAuthoritySet<T, I>>::get().set_id;
+			ensure!(
+				*init_data.header.number() > best_finalized_number &&
+					init_data.set_id > current_set_id,
+				<Error<T, I>>::ObsoleteInitializationData
+			);
+			ensure!(
+				init_data.authority_list.len() <= T::MaxBridgedAuthorities::get() as usize,
+				<Error<T, I>>::TooManyAuthoritiesInSet
+			);
+			ensure_checkpoints_are_valid::<T, I>(&init_data)?;
+
+			let pruned_headers = prune_headers::<T, I>(Self::headers_to_keep(), 0);
+			<LastFreeHeaderSetId<T, I>>::kill();
+			initialize_bridge::<T, I>(init_data.clone());
+
+			let (number, hash) = (*init_data.header.number(), init_data.header.hash());
+			Self::deposit_event(Event::UpdatedBestFinalizedHeader { number, hash });
+			Self::deposit_event(Event::AuthoritySetChanged { new_set_id: init_data.set_id });
+			Self::deposit_event(Event::Reinitialized { number, hash, set_id: init_data.set_id });
+
+			log::info!(
+				target: LOG_TARGET,
+				"Pallet has been re-initialized with: {:?}. Pruned {} headers",
+				init_data,
+				pruned_headers,
+			);
+
+			Ok(().into())
+		}
+
+		/// Verify and import a contiguous range of headers, finalized according to the given
+		/// finality proofs.
+		///
+		/// Proofs are imported in order, exactly like with the `submit_finality_proof` call. The
+		/// import stops at the first rejected proof. If it is the first proof of the batch, the
+		/// call fails. Otherwise already imported headers are kept, the
+		/// `FinalityProofBatchInterrupted` event is deposited and the weight of remaining proofs
+		/// is refunded.
+		///
+		/// The whole batch counts as a single request. The batch is free for the submitter only if
+		/// all imported headers are free.
+		#[pallet::weight(submit_finality_proof_batch_weight::<T, I>(finality_proofs))]
+		pub fn submit_finality_proof_batch(
+			origin: OriginFor<T>,
+			finality_proofs: Vec<FinalityProof<T, I>>,
+		) -> DispatchResultWithPostInfo {
+			Self::ensure_not_halted().map_err(Error::<T, I>::BridgeModule)?;
+			let _ = ensure_signed(origin)?;
+			ensure!(!finality_proofs.is_empty(), <Error<T, I>>::EmptyFinalityProofsBatch);
+			ensure!(
+				finality_proofs.len() <= T::MaxFinalityProofsInBatch::get() as usize,
+				<Error<T, I>>::TooManyFinalityProofsInBatch
+			);
+			ensure!(Self::request_count() < Self::max_requests(), <Error<T, I>>::TooManyRequests);
+
+			let mut processed_proofs = 0;
+			let mut actual_weight: Weight = 0;
+			let mut pays_fee = Pays::No;
+			for (index, (finality_target, justification)) in finality_proofs.into_iter().enumerate()
+			{
+				processed_proofs += 1;
+				let declared_weight = submit_finality_proof_weight::<T, I>(&justification);
+				match import_finality_proof::<T, I>(*finality_target, justification) {
+					Ok((weight, pays)) => {
+						actual_weight = actual_weight.saturating_add(weight);
+						if pays == Pays::Yes {
+							pays_fee = Pays::Yes;
+						}
+					},
+					Err(error) if index == 0 => return Err(error.into()),
+					Err(error) => {
+						log::debug!(
+							target: LOG_TARGET,
+							"Finality proofs batch has been interrupted at proof {}: {:?}",
+							index,
+							error,
+						);
+
+						actual_weight = actual_weight.saturating_add(declared_weight);
+						pays_fee = Pays::Yes;
+						Self::deposit_event(Event::FinalityProofBatchInterrupted {
+							index: index as u32,
+							error,
+						});
+						break
+					},
+				}
+			}
+			<RequestCount<T, I>>::mutate(|count| *count += 1);
+
+			Ok(PostDispatchInfo {
+				actual_weight: Some(
+					actual_weight.saturating_add(T::WeightInfo::submit_finality_proof_batch(
+						processed_proofs,
+					)),
+				),
+				pays_fee,
+			})
+		}
+
+		/// Change the minimal gap between the best finalized header and the imported regular
+		/// header.
+		///
+		/// Regular (non-mandatory) headers with number less than `best_finalized + gap` are
+		/// rejected. Mandatory headers are always accepted. Zero and one gaps don't restrict
+		/// imports. The gap can't be larger than `MAX_MIN_HEADER_GAP`.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::weight((T::DbWeight::get().reads_writes(2, 1), DispatchClass::Operational))]
+		pub fn set_min_header_gap(
+			origin: OriginFor<T>,
+			min_header_gap: BridgedBlockNumber<T, I>,
+		) -> DispatchResult {
+			Self::ensure_owner_or_root(origin)?;
+			ensure!(
+				min_header_gap <= MAX_MIN_HEADER_GAP.into(),
+				Error::<T, I>::InvalidMinHeaderGap,
+			);
+
+			<MinHeaderGap<T, I>>::put(min_header_gap);
+
+			log::info!(target: LOG_TARGET, "Setting min header gap to {:?}", min_header_gap);
+			Self::deposit_event(Event::MinHeaderGapUpdated { min_header_gap });
+			Ok(())
 		}
 	}
 
@@ -263,6 +578,58 @@ pub mod pallet {
 	#[pallet::getter(fn request_count)]
 	pub(super) type RequestCount<T: Config<I>, I: 'static = ()> = StorageValue<_, u32, ValueQuery>;
 
+	/// Default value of the `RemainingRequestsWeight` storage value.
+	#[pallet::type_value]
+	pub fn DefaultRequestsWeight<T: Config<I>, I: 'static>() -> Weight {
+		T::MaxRequestsWeight::get()
+	}
+
+	/// Weight that may still be spent on header imports in the current block.
+	///
+	/// It is reset to the `Config::MaxRequestsWeight` at the beginning of every block and is
+	/// decreased by the actual weight of every import.
+	#[pallet::storage]
+	#[pallet::getter(fn remaining_requests_weight)]
+	pub type RemainingRequestsWeight<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, Weight, ValueQuery, DefaultRequestsWeight<T, I>>;
+
+	/// Default value of the `MaxRequests` storage value.
+	#[pallet::type_value]
+	pub fn DefaultMaxRequests<T: Config<I>, I: 'static>() -> u32 {
+		T::MaxRequests::get()
+	}
+
+	/// The upper bound on the number of requests allowed by the pallet.
+	///
+	/// Initialized from the `Config::MaxRequests` and may be changed by the pallet owner.
+	#[pallet::storage]
+	#[pallet::getter(fn max_requests)]
+	pub type MaxRequests<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, u32, ValueQuery, DefaultMaxRequests<T, I>>;
+
+	/// Default value of the `HeadersToKeep` storage value.
+	#[pallet::type_value]
+	pub fn DefaultHeadersToKeep<T: Config<I>, I: 'static>() -> u32 {
+		T::HeadersToKeep::get()
+	}
+
+	/// Maximal number of finalized headers to keep in the storage.
+	///
+	/// Initialized from the `Config::HeadersToKeep` and may be changed by the pallet owner.
+	#[pallet::storage]
+	#[pallet::getter(fn headers_to_keep)]
+	pub type HeadersToKeep<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, u32, ValueQuery, DefaultHeadersToKeep<T, I>>;
+
+	/// Minimal gap between the best finalized header and the imported regular header.
+	///
+	/// Mandatory headers are imported regardless of this value. May be changed by the pallet
+	/// owner.
+	#[pallet::storage]
+	#[pallet::getter(fn min_header_gap)]
+	pub type MinHeaderGap<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, BridgedBlockNumber<T, I>, ValueQuery>;
+
 	/// Hash of the header used to bootstrap the pallet.
 	#[pallet::storage]
 	pub(super) type InitialHash<T: Config<I>, I: 'static = ()> =
@@ -274,11 +641,14 @@ pub mod pallet {
 		StorageValue<_, (BridgedBlockNumber<T, I>, BridgedBlockHash<T, I>), OptionQuery>;
 
 	/// A ring buffer of imported hashes. Ordered by the insertion time.
+	///
+	/// The buffer is indexed by the (wrapping) insertion counter, so it holds entries at
+	/// `ImportedHashesPointer - HeadersToKeep..ImportedHashesPointer`.
 	#[pallet::storage]
 	pub(super) type ImportedHashes<T: Config<I>, I: 'static = ()> =
 		StorageMap<_, Identity, u32, BridgedBlockHash<T, I>>;
 
-	/// Current ring buffer position.
+	/// Current ring buffer position - index of the next inserted hash.
 	#[pallet::storage]
 	pub(super) type ImportedHashesPointer<T: Config<I>, I: 'static = ()> =
 		StorageValue<_, u32, ValueQuery>;
@@ -288,11 +658,46 @@ pub mod pallet {
 	pub type ImportedHeaders<T: Config<I>, I: 'static = ()> =
 		StorageMap<_, Identity, BridgedBlockHash<T, I>, BridgedHeader<T, I>>;
 
+	/// Encoded justifications of the most recently imported headers.
+	///
+	/// At most `min(JustificationsToKeep, HeadersToKeep)` justifications are stored. The
+	/// justification is removed when its header is pruned.
+	#[pallet::storage]
+	pub type StoredJustifications<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Identity, BridgedBlockHash<T, I>, Vec<u8>>;
+
+	/// Map of trusted checkpoint header hash => (number, state root) of this header.
+	///
+	/// Checkpoints are provided at initialization. We don't know full checkpoint headers, so
+	/// they are never used for anything but verifying storage proofs. They are never pruned.
+	#[pallet::storage]
+	pub type TrustedCheckpoints<T: Config<I>, I: 'static = ()> = StorageMap<
+		_,
+		Identity,
+		BridgedBlockHash<T, I>,
+		(BridgedBlockNumber<T, I>, BridgedBlockHash<T, I>),
+	>;
+
+	/// Id of the authority set, that has been changed by the last mandatory header, imported
+	/// for free.
+	///
+	/// It is used to make sure that at most one mandatory header is imported for free by
+	/// every authority set.
+	#[pallet::storage]
+	pub(super) type LastFreeHeaderSetId<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, sp_finality_grandpa::SetId, OptionQuery>;
+
 	/// The current GRANDPA Authority set.
 	#[pallet::storage]
 	pub(super) type CurrentAuthoritySet<T: Config<I>, I: 'static = ()> =
 		StorageValue<_, bp_header_chain::AuthoritySet, ValueQuery>;
 
+	/// GRANDPA authority set change that has been scheduled by the imported header, but is not
+	/// yet enacted.
+	#[pallet::storage]
+	pub(super) type PendingAuthoritySetChange<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, ScheduledAuthoritySetChange<BridgedBlockNumber<T, I>>, OptionQuery>;
+
 	/// Optional pallet owner.
 	///
 	/// Pallet owner has a right to halt all pallet operations and then resume it. If it is
@@ -303,13 +708,28 @@ pub mod pallet {
 	pub type PalletOwner<T: Config<I>, I: 'static = ()> =
 		StorageValue<_, T::AccountId, OptionQuery>;
 
+	/// Account that has been proposed to become the new pallet owner.
+	///
+	/// The ownership is transferred when this account calls `accept_ownership`.
+	#[pallet::storage]
+	pub type PalletPendingOwner<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, T::AccountId, OptionQuery>;
+
 	/// The current operating mode of the pallet.
 	///
-	/// Depending on the mode either all, or no transactions will be allowed.
+	/// When the pallet is halted, new headers are not accepted. Already imported headers are
+	/// still available.
 	#[pallet::storage]
 	pub type PalletOperatingMode<T: Config<I>, I: 'static = ()> =
 		StorageValue<_, BasicOperatingMode, ValueQuery>;
 
+	/// Number of the best finalized header at the moment when the pallet has been halted.
+	///
+	/// This value is informational only. It is removed when the pallet is resumed.
+	#[pallet::storage]
+	pub type FrozenAt<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, BridgedBlockNumber<T, I>, OptionQuery>;
+
 	#[pallet::genesis_config]
 	pub struct GenesisConfig<T: Config<I>, I: 'static = ()> {
 		/// Optional module owner account.
@@ -332,7 +752,14 @@ pub mod pallet {
 				<PalletOwner<T, I>>::put(owner);
 			}
 
+			<MaxRequests<T, I>>::put(T::MaxRequests::get());
+			<HeadersToKeep<T, I>>::put(T::HeadersToKeep::get());
+
 			if let Some(init_data) = self.init_data.clone() {
+				assert!(
+					ensure_checkpoints_are_valid::<T, I>(&init_data).is_ok(),
+					"Trusted checkpoints must not collide with the initial header",
+				);
 				initialize_bridge::<T, I>(init_data);
 			} else {
 				// Since the bridge hasn't been initialized we shouldn't allow anyone to perform
@@ -342,6 +769,50 @@ pub mod pallet {
 		}
 	}
 
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config<I>, I: 'static = ()> {
+		/// New pallet owner has been proposed.
+		OwnershipTransferProposed { pending_owner: T::AccountId },
+		/// Pallet ownership has been transferred.
+		OwnershipTransferred { old_owner: Option<T::AccountId>, new_owner: T::AccountId },
+		/// Pending pallet ownership transfer has been cancelled.
+		OwnershipTransferCancelled { pending_owner: T::AccountId },
+		/// Maximal number of finalized headers to keep in the storage has been changed.
+		HeadersToKeepUpdated { headers_to_keep: u32, pruned_headers: u32 },
+		/// Upper bound on the number of requests allowed by the pallet has been changed.
+		MaxRequestsUpdated { max_requests: u32 },
+		/// Minimal gap between the best finalized header and the imported regular header has
+		/// been changed.
+		MinHeaderGapUpdated { min_header_gap: BridgedBlockNumber<T, I> },
+		/// Pallet operating mode has been changed.
+		OperatingModeChanged {
+			old_operating_mode: BasicOperatingMode,
+			new_operating_mode: BasicOperatingMode,
+		},
+		/// Best finalized header of the bridged chain has been updated.
+		UpdatedBestFinalizedHeader {
+			number: BridgedBlockNumber<T, I>,
+			hash: BridgedBlockHash<T, I>,
+		},
+		/// Authority set of the bridged chain has been changed.
+		AuthoritySetChanged { new_set_id: sp_finality_grandpa::SetId },
+		/// Authority set change has been scheduled. It is enacted by the header with given
+		/// number.
+		AuthoritySetChangeScheduled {
+			new_set_id: sp_finality_grandpa::SetId,
+			enacted_at: BridgedBlockNumber<T, I>,
+		},
+		/// The pallet has been re-initialized with the new header and authority set.
+		Reinitialized {
+			number: BridgedBlockNumber<T, I>,
+			hash: BridgedBlockHash<T, I>,
+			set_id: sp_finality_grandpa::SetId,
+		},
+		/// Import of the finality proofs batch has been interrupted by the rejected proof.
+		FinalityProofBatchInterrupted { index: u32, error: sp_runtime::DispatchError },
+	}
+
 	#[pallet::error]
 	pub enum Error<T, I = ()> {
 		/// The given justification is invalid for the given header.
@@ -356,62 +827,284 @@ pub mod pallet {
 		UnknownHeader,
 		/// The scheduled authority set change found in the header is unsupported by the pallet.
 		///
-		/// This is the case for non-standard (e.g forced) authority set changes.
+		/// This is the case for non-standard (e.g forced) authority set changes and for changes
+		/// that are scheduled while the previous change is still pending.
 		UnsupportedScheduledChange,
 		/// The pallet is not yet initialized.
 		NotInitialized,
 		/// The pallet has already been initialized.
 		AlreadyInitialized,
+		/// The header or the authority set id of the re-initialization data is not ahead of the
+		/// current pallet state.
+		ObsoleteInitializationData,
 		/// The storage proof doesn't contains storage root. So it is invalid for given header.
 		StorageRootMismatch,
+		/// The requested number of headers to keep is out of `HEADERS_TO_KEEP_BOUNDS`.
+		InvalidHeadersToKeep,
+		/// The requested maximal number of requests is out of `MAX_REQUESTS_BOUNDS`.
+		InvalidMaxRequests,
+		/// The justification is larger than `MaxJustificationSize`.
+		JustificationIsTooLarge,
+		/// The justification has more precommits than `MaxBridgedAuthorities`.
+		TooManyAuthoritiesInJustification,
+		/// The authority set has more authorities than `MaxBridgedAuthorities`.
+		TooManyAuthoritiesInSet,
+		/// The header is larger than `MaxBridgedHeaderSize`.
+		HeaderIsTooLarge,
+		/// The finality proofs batch is empty.
+		EmptyFinalityProofsBatch,
+		/// The finality proofs batch has more proofs than `MaxFinalityProofsInBatch`.
+		TooManyFinalityProofsInBatch,
+		/// The regular header is closer to the best finalized header than `MinHeaderGap`.
+		HeaderGapIsTooSmall,
+		/// The weight of the import exceeds the remaining weight budget of the current block.
+		RequestsWeightExhausted,
+		/// The trusted checkpoint has the same number or hash as the initial header.
+		CheckpointCollidesWithInitialHeader,
+		/// The requested minimal header gap is larger than `MAX_MIN_HEADER_GAP`.
+		InvalidMinHeaderGap,
 		/// Error generated by the `OwnedBridgeModule` trait.
 		BridgeModule(bp_runtime::OwnedBridgeModuleError),
 	}
 
-	/// Check the given header for a GRANDPA scheduled authority set change. If a change
-	/// is found it will be enacted immediately.
+	/// Verify a target header is finalized according to the given finality proof and import it.
+	///
+	/// All checks are performed before the storage is modified, so the storage is left intact if
+	/// the proof is rejected. Returns actual weight of the import and whether the submitter pays
+	/// for it.
+	pub(crate) fn import_finality_proof<T: Config<I>, I: 'static>(
+		finality_target: BridgedHeader<T, I>,
+		justification: GrandpaJustification<BridgedHeader<T, I>>,
+	) -> Result<(Weight, Pays), sp_runtime::DispatchError> {
+		ensure!(
+			justification.encoded_size() <= T::MaxJustificationSize::get() as usize,
+			<Error<T, I>>::JustificationIsTooLarge
+		);
+		ensure!(
+			justification.commit.precommits.len() <= T::MaxBridgedAuthorities::get() as usize,
+			<Error<T, I>>::TooManyAuthoritiesInJustification
+		);
+		ensure!(
+			finality_target.encoded_size() <= T::MaxBridgedHeaderSize::get() as usize,
+			<Error<T, I>>::HeaderIsTooLarge
+		);
+
+		// the first import in the block is always allowed, so that the pallet can make progress
+		// even if the weight of the single import exceeds the whole budget
+		let remaining_requests_weight = RemainingRequestsWeight::<T, I>::get();
+		ensure!(
+			submit_finality_proof_weight::<T, I>(&justification) <= remaining_requests_weight ||
+				remaining_requests_weight == T::MaxRequestsWeight::get(),
+			<Error<T, I>>::RequestsWeightExhausted
+		);
+
+		let (hash, number) = (finality_target.hash(), *finality_target.number());
+		log::trace!(target: LOG_TARGET, "Going to try and finalize header {:?}", finality_target);
+
+		let best_finalized = BestFinalized::<T, I>::get();
+		let best_finalized =
+			best_finalized.and_then(|(_, hash)| ImportedHeaders::<T, I>::get(hash));
+		let best_finalized = match best_finalized {
+			Some(best_finalized) => best_finalized,
+			None => {
+				log::error!(
+					target: LOG_TARGET,
+					"Cannot finalize header {:?} because pallet is not yet initialized",
+					finality_target,
+				);
+				fail!(<Error<T, I>>::NotInitialized);
+			},
+		};
+
+		// We do a quick check here to ensure that our header chain is making progress and isn't
+		// "travelling back in time" (which could be indicative of something bad, e.g a
+		// hard-fork).
+		ensure!(*best_finalized.number() < number, <Error<T, I>>::OldHeader);
+
+		// regular headers may be throttled by the pallet owner, but the pallet can't go further
+		// without importing mandatory headers
+		ensure!(
+			number >= best_finalized.number().saturating_add(MinHeaderGap::<T, I>::get()) ||
+				find_grandpa_authorities_scheduled_change(&finality_target).is_some(),
+			<Error<T, I>>::HeaderGapIsTooSmall
+		);
+
+		let authority_set = finalizing_authority_set::<T, I>(number);
+		let set_id = authority_set.set_id;
+		let authorities_count = authority_set.authorities.len();
+		verify_justification::<T, I>(&justification, hash, number, authority_set)?;
+
+		let is_mandatory_header = try_enact_authority_change::<T, I>(&finality_target, set_id)?;
+		insert_header::<T, I>(finality_target, hash);
+		store_justification::<T, I>(hash, &justification);
+		log::info!(target: LOG_TARGET, "Successfully imported finalized header with hash {:?}!", hash);
+		Pallet::<T, I>::deposit_event(Event::UpdatedBestFinalizedHeader { number, hash });
+
+		// mandatory header is a header that schedules authorities set change. The pallet can't
+		// go further without importing this header. So every bridge MUST import mandatory
+		// headers.
+		//
+		// We don't want to charge extra costs for mandatory operations. So relayer is not
+		// paying fee for mandatory headers import transactions. To avoid spam, only single
+		// mandatory header may be imported for free by every authority set.
+		let (actual_weight, pays_fee) =
+			if !is_mandatory_header || LastFreeHeaderSetId::<T, I>::get() == Some(set_id) {
+				(submit_finality_proof_weight::<T, I>(&justification), Pays::Yes)
+			} else {
+				LastFreeHeaderSetId::<T, I>::put(set_id);
+
+				// precommits of unknown authorities and duplicate precommits are ignored by the
+				// justification verification, so we're refunding their weight
+				let actual_weight = T::WeightInfo::submit_finality_proof(
+					justification
+						.commit
+						.precommits
+						.len()
+						.min(authorities_count)
+						.try_into()
+						.unwrap_or(u32::MAX),
+					justification.votes_ancestries.len().try_into().unwrap_or(u32::MAX),
+				)
+				.saturating_add(store_justification_weight::<T, I>());
+				(actual_weight, Pays::No)
+			};
+		RemainingRequestsWeight::<T, I>::mutate(|weight| {
+			*weight = weight.saturating_sub(actual_weight)
+		});
+
+		Ok((actual_weight, pays_fee))
+	}
+
+	/// Returns weight of the `submit_finality_proof` call with given justification.
+	pub(crate) fn submit_finality_proof_weight<T: Config<I>, I: 'static>(
+		justification: &GrandpaJustification<BridgedHeader<T, I>>,
+	) -> Weight {
+		T::WeightInfo::submit_finality_proof(
+			justification.commit.precommits.len().try_into().unwrap_or(u32::MAX),
+			justification.votes_ancestries.len().try_into().unwrap_or(u32::MAX),
+		)
+		.saturating_add(store_justification_weight::<T, I>())
+	}
+
+	/// Returns weight of the `submit_finality_proof_batch` call with given finality proofs.
+	///
+	/// It is the sum of weights of individual `submit_finality_proof` calls and the batch
+	/// overhead.
+	pub(crate) fn submit_finality_proof_batch_weight<T: Config<I>, I: 'static>(
+		finality_proofs: &[FinalityProof<T, I>],
+	) -> Weight {
+		finality_proofs.iter().fold(
+			T::WeightInfo::submit_finality_proof_batch(
+				finality_proofs.len().try_into().unwrap_or(u32::MAX),
+			),
+			|weight, (_, justification)| {
+				weight.saturating_add(submit_finality_proof_weight::<T, I>(justification))
+			},
+		)
+	}
+
+	/// Returns the authority set that has finalized the header with given number.
+	///
+	/// If there's a pending authority set change and the header is a descendant of the header
+	/// that enacts this change, the header is finalized by the next authority set.
+	pub(crate) fn finalizing_authority_set<T: Config<I>, I: 'static>(
+		number: BridgedBlockNumber<T, I>,
+	) -> bp_header_chain::AuthoritySet {
+		match <PendingAuthoritySetChange<T, I>>::get() {
+			Some(change) if change.is_finalized_by_next_set(number) => change.next_authority_set,
+			_ => <CurrentAuthoritySet<T, I>>::get(),
+		}
+	}
+
+	/// Check the given header for a GRANDPA scheduled authority set change and enact the pending
+	/// change if the header is enacting it (or is a descendant of the enacting header).
 	///
-	/// This function does not support forced changes, or scheduled changes with delays
-	/// since these types of changes are indicative of abnormal behavior from GRANDPA.
+	/// A change with zero delay is enacted immediately. A delayed change is kept in the
+	/// `PendingAuthoritySetChange` until the header that enacts it is imported. This function
+	/// does not support forced changes, since these types of changes are indicative of abnormal
+	/// behavior from GRANDPA.
 	///
-	/// Returned value will indicate if a change was enacted or not.
+	/// Returned value will indicate if the header is mandatory, i.e. it schedules a change.
 	pub(crate) fn try_enact_authority_change<T: Config<I>, I: 'static>(
 		header: &BridgedHeader<T, I>,
 		current_set_id: sp_finality_grandpa::SetId,
 	) -> Result<bool, sp_runtime::DispatchError> {
-		let mut change_enacted = false;
-
 		// We don't support forced changes - at that point governance intervention is required.
 		ensure!(
-			super::find_forced_change(header).is_none(),
+			find_grandpa_authorities_forced_change(header).is_none(),
 			<Error<T, I>>::UnsupportedScheduledChange
 		);
 
-		if let Some(change) = super::find_scheduled_change(header) {
-			// GRANDPA only includes a `delay` for forced changes, so this isn't valid.
-			ensure!(change.delay == Zero::zero(), <Error<T, I>>::UnsupportedScheduledChange);
+		// all checks are done before the storage is modified, so that the rejected header
+		// leaves no traces
+		let number = *header.number();
+		let (enacted_change, pending_change) = match <PendingAuthoritySetChange<T, I>>::get() {
+			Some(change) if change.is_enacted_by(number) => (Some(change), None),
+			pending_change => (None, pending_change),
+		};
+		let current_set_id = enacted_change
+			.as_ref()
+			.map(|change| change.next_authority_set.set_id)
+			.unwrap_or(current_set_id);
+		let change = ScheduledAuthoritySetChange::from_header(header, current_set_id);
+		if let Some(ref change) = change {
+			// GRANDPA doesn't allow scheduling new standard change while there's a pending one.
+			ensure!(pending_change.is_none(), <Error<T, I>>::UnsupportedScheduledChange);
+			ensure!(
+				change.next_authority_set.authorities.len() <=
+					T::MaxBridgedAuthorities::get() as usize,
+				<Error<T, I>>::TooManyAuthoritiesInSet
+			);
+		}
 
-			// TODO [#788]: Stop manually increasing the `set_id` here.
-			let next_authorities = bp_header_chain::AuthoritySet {
-				authorities: change.next_authorities,
-				set_id: current_set_id + 1,
-			};
+		if let Some(enacted_change) = enacted_change {
+			<PendingAuthoritySetChange<T, I>>::kill();
+			enact_authority_set::<T, I>(enacted_change.next_authority_set);
+		}
 
-			// Since our header schedules a change and we know the delay is 0, it must also enact
-			// the change.
-			<CurrentAuthoritySet<T, I>>::put(&next_authorities);
-			change_enacted = true;
+		let change = match change {
+			Some(change) => change,
+			None => return Ok(false),
+		};
 
+		if change.is_enacted_by(number) {
+			enact_authority_set::<T, I>(change.next_authority_set);
+		} else {
 			log::info!(
 				target: LOG_TARGET,
-				"Transitioned from authority set {} to {}! New authorities are: {:?}",
+				"Scheduled transition from authority set {} to {} at header {:?}",
 				current_set_id,
-				current_set_id + 1,
-				next_authorities,
+				change.next_authority_set.set_id,
+				change.enacted_at,
 			);
-		};
 
-		Ok(change_enacted)
+			Pallet::<T, I>::deposit_event(Event::AuthoritySetChangeScheduled {
+				new_set_id: change.next_authority_set.set_id,
+				enacted_at: change.enacted_at,
+			});
+			<PendingAuthoritySetChange<T, I>>::put(change);
+		}
+
+		Ok(true)
+	}
+
+	/// Make the given authority set current.
+	fn enact_authority_set<T: Config<I>, I: 'static>(
+		next_authorities: bp_header_chain::AuthoritySet,
+	) {
+		let previous_set_id = <CurrentAuthoritySet<T, I>>::get().set_id;
+		<CurrentAuthoritySet<T, I>>::put(&next_authorities);
+		Pallet::<T, I>::deposit_event(Event::AuthoritySetChanged {
+			new_set_id: next_authorities.set_id,
+		});
+
+		log::info!(
+			target: LOG_TARGET,
+			"Transitioned from authority set {} to {}! New authorities are: {:?}",
+			previous_set_id,
+			next_authorities.set_id,
+			next_authorities,
+		);
 	}
 
 	/// Verify a GRANDPA justification (finality proof) for a given header.
@@ -458,17 +1151,95 @@ pub mod pallet {
 		hash: BridgedBlockHash<T, I>,
 	) {
 		let index = <ImportedHashesPointer<T, I>>::get();
-		let pruning = <ImportedHashes<T, I>>::try_get(index);
+		let pruning =
+			<ImportedHashes<T, I>>::take(index.wrapping_sub(Pallet::<T, I>::headers_to_keep()));
 		<BestFinalized<T, I>>::put((*header.number(), hash));
 		<ImportedHeaders<T, I>>::insert(hash, header);
 		<ImportedHashes<T, I>>::insert(index, hash);
 
 		// Update ring buffer pointer and remove old header.
-		<ImportedHashesPointer<T, I>>::put((index + 1) % T::HeadersToKeep::get());
-		if let Ok(hash) = pruning {
-			log::debug!(target: LOG_TARGET, "Pruning old header: {:?}.", hash);
-			<ImportedHeaders<T, I>>::remove(hash);
+		<ImportedHashesPointer<T, I>>::put(index.wrapping_add(1));
+		if let Some(hash) = pruning {
+			prune_header::<T, I>(hash);
+		}
+	}
+
+	/// Remove imported header and its justification from the storage.
+	fn prune_header<T: Config<I>, I: 'static>(hash: BridgedBlockHash<T, I>) {
+		<StoredJustifications<T, I>>::remove(hash);
+		log::debug!(target: LOG_TARGET, "Pruning old header: {:?}.", hash);
+		<ImportedHeaders<T, I>>::remove(hash);
+	}
+
+	/// Save encoded justification of the just imported header, pruning the justification that
+	/// is no longer among `JustificationsToKeep` most recent ones.
+	///
+	/// Must be called right after the header has been inserted with `insert_header`.
+	pub(crate) fn store_justification<T: Config<I>, I: 'static>(
+		hash: BridgedBlockHash<T, I>,
+		justification: &GrandpaJustification<BridgedHeader<T, I>>,
+	) {
+		let justifications_to_keep =
+			T::JustificationsToKeep::get().min(Pallet::<T, I>::headers_to_keep());
+		if justifications_to_keep == 0 {
+			return
+		}
+
+		let index = <ImportedHashesPointer<T, I>>::get().wrapping_sub(1);
+		if let Some(pruning) =
+			<ImportedHashes<T, I>>::get(index.wrapping_sub(justifications_to_keep))
+		{
+			<StoredJustifications<T, I>>::remove(pruning);
+		}
+		<StoredJustifications<T, I>>::insert(hash, justification.encode());
+	}
+
+	/// Returns additional weight of storing the justification in `submit_finality_proof`.
+	pub(crate) fn store_justification_weight<T: Config<I>, I: 'static>() -> Weight {
+		if T::JustificationsToKeep::get() == 0 {
+			return 0
 		}
+
+		T::DbWeight::get().reads_writes(3, 2)
+	}
+
+	/// Returns number of ring buffer entries that need to be checked when the number of headers
+	/// to keep is changed to `new_headers_to_keep`.
+	pub(crate) fn headers_to_prune<T: Config<I>, I: 'static>(new_headers_to_keep: u32) -> u32 {
+		Pallet::<T, I>::headers_to_keep().saturating_sub(new_headers_to_keep)
+	}
+
+	/// Prune the oldest headers so that at most `new_headers_to_keep` most recent headers are
+	/// left in the ring buffer.
+	///
+	/// Returns number of actually pruned headers.
+	pub(crate) fn prune_headers<T: Config<I>, I: 'static>(
+		old_headers_to_keep: u32,
+		new_headers_to_keep: u32,
+	) -> u32 {
+		let next_index = <ImportedHashesPointer<T, I>>::get();
+		let mut pruned_headers = 0;
+		for distance in new_headers_to_keep.saturating_add(1)..=old_headers_to_keep {
+			if let Some(hash) = <ImportedHashes<T, I>>::take(next_index.wrapping_sub(distance)) {
+				prune_header::<T, I>(hash);
+				pruned_headers += 1;
+			}
+		}
+		pruned_headers
+	}
+
+	/// Ensure that trusted checkpoints from the initialization data don't collide with the
+	/// initial header.
+	pub(crate) fn ensure_checkpoints_are_valid<T: Config<I>, I: 'static>(
+		init_data: &super::InitializationData<BridgedHeader<T, I>>,
+	) -> Result<(), Error<T, I>> {
+		let (initial_number, initial_hash) = (*init_data.header.number(), init_data.header.hash());
+		let is_colliding = init_data.checkpoints.iter().any(|checkpoint| {
+			checkpoint.number == initial_number || checkpoint.hash == initial_hash
+		});
+		ensure!(!is_colliding, <Error<T, I>>::CheckpointCollidesWithInitialHeader);
+
+		Ok(())
 	}
 
 	/// Since this writes to storage with no real checks this should only be used in functions that
@@ -476,18 +1247,49 @@ pub mod pallet {
 	pub(crate) fn initialize_bridge<T: Config<I>, I: 'static>(
 		init_params: super::InitializationData<BridgedHeader<T, I>>,
 	) {
-		let super::InitializationData { header, authority_list, set_id, operating_mode } =
-			init_params;
+		let super::InitializationData {
+			header,
+			authority_list,
+			set_id,
+			operating_mode,
+			checkpoints,
+		} = init_params;
 
 		let initial_hash = header.hash();
 		<InitialHash<T, I>>::put(initial_hash);
 		<ImportedHashesPointer<T, I>>::put(0);
 		insert_header::<T, I>(*header, initial_hash);
 
+		for checkpoint in checkpoints {
+			<TrustedCheckpoints<T, I>>::insert(
+				checkpoint.hash,
+				(checkpoint.number, checkpoint.state_root),
+			);
+		}
+
 		let authority_set = bp_header_chain::AuthoritySet::new(authority_list, set_id);
 		<CurrentAuthoritySet<T, I>>::put(authority_set);
+		<PendingAuthoritySetChange<T, I>>::kill();
 
 		<PalletOperatingMode<T, I>>::put(operating_mode);
+		<FrozenAt<T, I>>::kill();
+		update_frozen_at::<T, I>(operating_mode);
+	}
+
+	/// Save number of the best finalized header when the pallet is halted and forget it when the
+	/// pallet is resumed.
+	pub(crate) fn update_frozen_at<T: Config<I>, I: 'static>(operating_mode: BasicOperatingMode) {
+		match operating_mode {
+			BasicOperatingMode::Halted => {
+				// if the pallet is already halted, keep the original freeze point
+				if !<FrozenAt<T, I>>::exists() {
+					if let Some((number, _)) = <BestFinalized<T, I>>::get() {
+						<FrozenAt<T, I>>::put(number);
+					}
+				}
+			},
+			BasicOperatingMode::Normal => <FrozenAt<T, I>>::kill(),
+		}
 	}
 
 	#[cfg(feature = "runtime-benchmarks")]
@@ -495,7 +1297,7 @@ pub mod pallet {
 		init_params: super::InitializationData<BridgedHeader<T, I>>,
 	) {
 		let start_number = *init_params.header.number();
-		let end_number = start_number + T::HeadersToKeep::get().into();
+		let end_number = start_number + Pallet::<T, I>::headers_to_keep().into();
 		initialize_bridge::<T, I>(init_params);
 
 		let mut number = start_number;
@@ -524,64 +1326,147 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		<ImportedHeaders<T, I>>::get(hash)
 	}
 
+	/// Get the current operating mode of the pallet, along with the number of the best finalized
+	/// header at the moment when the pallet has been halted.
+	pub fn operating_state() -> bp_runtime::FinalityOperatingState<BridgedBlockNumber<T, I>> {
+		bp_runtime::FinalityOperatingState {
+			operating_mode: <PalletOperatingMode<T, I>>::get(),
+			frozen_at: <FrozenAt<T, I>>::get(),
+		}
+	}
+
+	/// Get the current GRANDPA authority set id, hash of the current authorities list and the
+	/// best finalized header, known to the pallet.
+	pub fn synced_headers_grandpa_info(
+	) -> bp_runtime::SyncedHeadersGrandpaInfo<BridgedBlockHash<T, I>, BridgedBlockNumber<T, I>> {
+		let authority_set = <CurrentAuthoritySet<T, I>>::get();
+		bp_runtime::SyncedHeadersGrandpaInfo {
+			set_id: authority_set.set_id,
+			authority_list_hash: authority_set.authority_list_hash(),
+			best_finalized: <BestFinalized<T, I>>::get()
+				.map(|(number, hash)| HeaderId(number, hash)),
+		}
+	}
+
+	/// Get identifier of the finalized header with given number, if it is known to the pallet.
+	///
+	/// Only headers that have not yet been pruned are known. Trusted checkpoints are never
+	/// returned, because they're not imported and we only know their state roots. Headers are
+	/// imported in the ascending numbers order, so the `ImportedHashes` ring buffer is binary
+	/// searched. At most `log2(HeadersToKeep) + 1` headers are read.
+	pub fn finalized_header(
+		number: BridgedBlockNumber<T, I>,
+	) -> Option<HeaderId<BridgedBlockHash<T, I>, BridgedBlockNumber<T, I>>> {
+		match <BestFinalized<T, I>>::get() {
+			Some((best_number, best_hash)) if best_number == number =>
+				return Some(HeaderId(best_number, best_hash)),
+			Some((best_number, _)) if best_number > number => (),
+			_ => return None,
+		}
+
+		// the most recent header is at distance 1 from the pointer and the oldest header is at
+		// distance `HeadersToKeep`. Pruned entries are always the oldest ones
+		let pointer = <ImportedHashesPointer<T, I>>::get();
+		let (mut min_distance, mut max_distance) = (1, Self::headers_to_keep());
+		while min_distance <= max_distance {
+			let distance = min_distance + (max_distance - min_distance) / 2;
+			let hash = <ImportedHashes<T, I>>::get(pointer.wrapping_sub(distance));
+			let header_number =
+				hash.and_then(<ImportedHeaders<T, I>>::get).map(|header| *header.number());
+			match (hash, header_number) {
+				(Some(hash), Some(header_number)) if header_number == number =>
+					return Some(HeaderId(number, hash)),
+				(_, Some(header_number)) if header_number > number => min_distance = distance + 1,
+				_ => max_distance = distance - 1,
+			}
+		}
+
+		None
+	}
+
+	/// Get hash of the finalized header with given number, if it is known to the pallet.
+	///
+	/// See `finalized_header` for details.
+	pub fn finalized_header_hash(
+		number: BridgedBlockNumber<T, I>,
+	) -> Option<BridgedBlockHash<T, I>> {
+		Self::finalized_header(number).map(|id| id.1)
+	}
+
+	/// Returns true if the given header may be imported for free right now.
+	///
+	/// The pallet doesn't charge fees for mandatory headers, i.e. headers that are scheduling
+	/// the authority set change. The header also needs to be better than the best finalized
+	/// header and the change must be supported by the pallet. Only single mandatory header may
+	/// be imported for free by every authority set. The justification is not checked here, so
+	/// the header may still be rejected by the `submit_finality_proof` call.
+	pub fn is_free_header(header: &BridgedHeader<T, I>) -> bool {
+		if Self::is_halted() || find_grandpa_authorities_forced_change(header).is_some() {
+			return false
+		}
+
+		let number = *header.number();
+		let is_better_than_best_finalized = <BestFinalized<T, I>>::get()
+			.map(|(best_number, _)| number > best_number)
+			.unwrap_or(false);
+		let is_pending_change_enacted = <PendingAuthoritySetChange<T, I>>::get()
+			.map(|change| change.is_enacted_by(number))
+			.unwrap_or(true);
+		let is_supported_change = find_grandpa_authorities_scheduled_change(header)
+			.map(|change| {
+				is_pending_change_enacted &&
+					change.next_authorities.len() <= T::MaxBridgedAuthorities::get() as usize
+			})
+			.unwrap_or(false);
+
+		let is_free_import_allowed = <LastFreeHeaderSetId<T, I>>::get() !=
+			Some(finalizing_authority_set::<T, I>(number).set_id);
+
+		is_better_than_best_finalized && is_supported_change && is_free_import_allowed
+	}
+
 	/// Check if a particular header is known to the bridge pallet.
+	///
+	/// Imported headers are only known until they're pruned. Trusted checkpoints are not
+	/// imported headers (see `is_trusted_checkpoint`).
 	pub fn is_known_header(hash: BridgedBlockHash<T, I>) -> bool {
 		<ImportedHeaders<T, I>>::contains_key(hash)
 	}
 
+	/// Returns encoded justification of the recently imported header, if it is still stored.
+	///
+	/// Justifications are only stored if `JustificationsToKeep` is non-zero and are pruned
+	/// together with their headers.
+	pub fn stored_justification(hash: BridgedBlockHash<T, I>) -> Option<Vec<u8>> {
+		<StoredJustifications<T, I>>::get(hash)
+	}
+
+	/// Check if a particular header is a trusted checkpoint, provided at initialization.
+	pub fn is_trusted_checkpoint(hash: BridgedBlockHash<T, I>) -> bool {
+		<TrustedCheckpoints<T, I>>::contains_key(hash)
+	}
+
 	/// Verify that the passed storage proof is valid, given it is crafted using
-	/// known finalized header. If the proof is valid, then the `parse` callback
-	/// is called and the function returns its result.
+	/// known finalized header or a trusted checkpoint. If the proof is valid, then the `parse`
+	/// callback is called and the function returns its result.
 	pub fn parse_finalized_storage_proof<R>(
 		hash: BridgedBlockHash<T, I>,
 		storage_proof: sp_trie::StorageProof,
 		parse: impl FnOnce(bp_runtime::StorageProofChecker<BridgedBlockHasher<T, I>>) -> R,
 	) -> Result<R, sp_runtime::DispatchError> {
-		let header = <ImportedHeaders<T, I>>::get(hash).ok_or(Error::<T, I>::UnknownHeader)?;
-		let storage_proof_checker =
-			bp_runtime::StorageProofChecker::new(*header.state_root(), storage_proof)
-				.map_err(|_| Error::<T, I>::StorageRootMismatch)?;
+		let state_root = match <ImportedHeaders<T, I>>::get(hash) {
+			Some(header) => *header.state_root(),
+			None => <TrustedCheckpoints<T, I>>::get(hash)
+				.map(|(_, state_root)| state_root)
+				.ok_or(Error::<T, I>::UnknownHeader)?,
+		};
+		let storage_proof_checker = bp_runtime::StorageProofChecker::new(state_root, storage_proof)
+			.map_err(|_| Error::<T, I>::StorageRootMismatch)?;
 
 		Ok(parse(storage_proof_checker))
 	}
 }
 
-pub(crate) fn find_scheduled_change<H: HeaderT>(
-	header: &H,
-) -> Option<sp_finality_grandpa::ScheduledChange<H::Number>> {
-	use sp_runtime::generic::OpaqueDigestItemId;
-
-	let id = OpaqueDigestItemId::Consensus(&GRANDPA_ENGINE_ID);
-
-	let filter_log = |log: ConsensusLog<H::Number>| match log {
-		ConsensusLog::ScheduledChange(change) => Some(change),
-		_ => None,
-	};
-
-	// find the first consensus digest with the right ID which converts to
-	// the right kind of consensus log.
-	header.digest().convert_first(|l| l.try_to(id).and_then(filter_log))
-}
-
-/// Checks the given header for a consensus digest signaling a **forced** scheduled change and
-/// extracts it.
-pub(crate) fn find_forced_change<H: HeaderT>(
-	header: &H,
-) -> Option<(H::Number, sp_finality_grandpa::ScheduledChange<H::Number>)> {
-	use sp_runtime::generic::OpaqueDigestItemId;
-
-	let id = OpaqueDigestItemId::Consensus(&GRANDPA_ENGINE_ID);
-
-	let filter_log = |log: ConsensusLog<H::Number>| match log {
-		ConsensusLog::ForcedChange(delay, change) => Some((delay, change)),
-		_ => None,
-	};
-
-	// find the first consensus digest with the right ID which converts to
-	// the right kind of consensus log.
-	header.digest().convert_first(|l| l.try_to(id).and_then(filter_log))
-}
-
 /// (Re)initialize bridge with given header for using it in `pallet-bridge-messages` benchmarks.
 #[cfg(feature = "runtime-benchmarks")]
 pub fn initialize_for_benchmarks<T: Config<I>, I: 'static>(header: BridgedHeader<T, I>) {
@@ -591,6 +1476,7 @@ pub fn initialize_for_benchmarks<T: Config<I>, I: 'static>(header: BridgedHeader
 		                                          * benchmarks */
 		set_id: 0,
 		operating_mode: bp_runtime::BasicOperatingMode::Normal,
+		checkpoints: sp_std::vec::Vec::new(),
 	});
 }
 
@@ -598,9 +1484,10 @@ pub fn initialize_for_benchmarks<T: Config<I>, I: 'static>(header: BridgedHeader
 mod tests {
 	use super::*;
 	use crate::mock::{run_test, test_header, Origin, TestHeader, TestNumber, TestRuntime};
+	use bp_header_chain::TrustedCheckpoint;
 	use bp_runtime::BasicOperatingMode;
 	use bp_test_utils::{
-		authority_list, generate_owned_bridge_module_tests, make_default_justification,
+		accounts, authority_list, generate_owned_bridge_module_tests, make_default_justification,
 		make_justification_for_header, JustificationGeneratorParams, ALICE, BOB,
 	};
 	use codec::Encode;
@@ -608,7 +1495,11 @@ mod tests {
 		assert_err, assert_noop, assert_ok, storage::generator::StorageValue,
 		weights::PostDispatchInfo,
 	};
-	use sp_runtime::{Digest, DigestItem, DispatchError};
+	use sp_finality_grandpa::{ConsensusLog, GRANDPA_ENGINE_ID};
+	use sp_runtime::{
+		traits::{BlakeTwo256, Hash as HashT},
+		Digest, DigestItem, DispatchError,
+	};
 
 	fn initialize_substrate_bridge() {
 		assert_ok!(init_with_origin(Origin::root()));
@@ -627,6 +1518,7 @@ mod tests {
 			authority_list: authority_list(),
 			set_id: 1,
 			operating_mode: BasicOperatingMode::Normal,
+			checkpoints: vec![],
 		};
 
 		Pallet::<TestRuntime>::initialize(origin, init_data.clone()).map(|_| init_data)
@@ -737,6 +1629,88 @@ mod tests {
 		})
 	}
 
+	#[test]
+	fn pallet_serves_reads_if_halted() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			assert_ok!(submit_finality_proof(1));
+
+			let (state_root, storage_proof) = bp_runtime::craft_valid_storage_proof();
+			let mut header = test_header(2);
+			header.set_state_root(state_root);
+			let hash = header.hash();
+			<BestFinalized<TestRuntime>>::put((2, hash));
+			<ImportedHeaders<TestRuntime>>::insert(hash, header.clone());
+
+			assert_ok!(Pallet::<TestRuntime>::set_operating_mode(
+				Origin::root(),
+				BasicOperatingMode::Halted
+			));
+
+			assert_eq!(Pallet::<TestRuntime>::best_finalized(), Some(header));
+			assert!(Pallet::<TestRuntime>::is_known_header(hash));
+			assert_ok!(
+				Pallet::<TestRuntime>::parse_finalized_storage_proof(hash, storage_proof, |_| (),),
+				(),
+			);
+		})
+	}
+
+	#[test]
+	fn frozen_at_is_saved_when_pallet_is_halted() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			assert_ok!(submit_finality_proof(1));
+			assert_eq!(
+				Pallet::<TestRuntime>::operating_state(),
+				bp_runtime::FinalityOperatingState {
+					operating_mode: BasicOperatingMode::Normal,
+					frozen_at: None,
+				},
+			);
+
+			assert_ok!(Pallet::<TestRuntime>::set_operating_mode(
+				Origin::root(),
+				BasicOperatingMode::Halted
+			));
+			assert_eq!(
+				Pallet::<TestRuntime>::operating_state(),
+				bp_runtime::FinalityOperatingState {
+					operating_mode: BasicOperatingMode::Halted,
+					frozen_at: Some(1),
+				},
+			);
+
+			// halting already halted pallet doesn't change the freeze point
+			assert_ok!(Pallet::<TestRuntime>::set_operating_mode(
+				Origin::root(),
+				BasicOperatingMode::Halted
+			));
+			assert_eq!(FrozenAt::<TestRuntime>::get(), Some(1));
+
+			assert_ok!(Pallet::<TestRuntime>::set_operating_mode(
+				Origin::root(),
+				BasicOperatingMode::Normal
+			));
+			assert_eq!(FrozenAt::<TestRuntime>::get(), None);
+		})
+	}
+
+	#[test]
+	fn frozen_at_is_saved_when_pallet_is_initialized_in_halted_mode() {
+		run_test(|| {
+			let init_data = InitializationData {
+				header: Box::new(test_header(5)),
+				authority_list: authority_list(),
+				set_id: 1,
+				operating_mode: BasicOperatingMode::Halted,
+				checkpoints: vec![],
+			};
+			assert_ok!(Pallet::<TestRuntime>::initialize(Origin::root(), init_data));
+			assert_eq!(FrozenAt::<TestRuntime>::get(), Some(5));
+		})
+	}
+
 	#[test]
 	fn pallet_rejects_header_if_not_initialized_yet() {
 		run_test(|| {
@@ -762,6 +1736,125 @@ mod tests {
 		})
 	}
 
+	#[test]
+	fn accepts_justification_of_maximal_size() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			let header = test_header(1);
+			let justification = make_default_justification(&header);
+			crate::mock::MaxJustificationSize::set(&(justification.encoded_size() as u32));
+
+			assert_ok!(Pallet::<TestRuntime>::submit_finality_proof(
+				Origin::signed(1),
+				Box::new(header),
+				justification,
+			));
+		})
+	}
+
+	#[test]
+	fn rejects_justification_with_too_many_authorities() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			let header = test_header(1);
+			let justification = make_default_justification(&header);
+			crate::mock::MaxBridgedAuthorities::set(
+				&(justification.commit.precommits.len() as u32 - 1),
+			);
+
+			assert_noop!(
+				Pallet::<TestRuntime>::submit_finality_proof(
+					Origin::signed(1),
+					Box::new(header),
+					justification,
+				),
+				<Error<TestRuntime>>::TooManyAuthoritiesInJustification
+			);
+		})
+	}
+
+	#[test]
+	fn rejects_header_that_is_too_large() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			let header = test_header(1);
+			let justification = make_default_justification(&header);
+			crate::mock::MaxBridgedHeaderSize::set(&(header.encoded_size() as u32 - 1));
+
+			assert_noop!(
+				Pallet::<TestRuntime>::submit_finality_proof(
+					Origin::signed(1),
+					Box::new(header),
+					justification,
+				),
+				<Error<TestRuntime>>::HeaderIsTooLarge
+			);
+		})
+	}
+
+	#[test]
+	fn init_rejects_authority_set_with_too_many_authorities() {
+		run_test(|| {
+			crate::mock::MaxBridgedAuthorities::set(&(authority_list().len() as u32 - 1));
+
+			assert_noop!(
+				init_with_origin(Origin::root()),
+				<Error<TestRuntime>>::TooManyAuthoritiesInSet
+			);
+		})
+	}
+
+	#[test]
+	fn importing_header_rejects_authority_set_change_with_too_many_authorities() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			let next_authorities = accounts(6).into_iter().map(|id| (id.into(), 1)).collect();
+			let consensus_log =
+				ConsensusLog::<TestNumber>::ScheduledChange(sp_finality_grandpa::ScheduledChange {
+					next_authorities,
+					delay: 0,
+				});
+			let mut header = test_header(2);
+			header.digest = Digest {
+				logs: vec![DigestItem::Consensus(GRANDPA_ENGINE_ID, consensus_log.encode())],
+			};
+			let justification = make_default_justification(&header);
+
+			assert_noop!(
+				Pallet::<TestRuntime>::submit_finality_proof(
+					Origin::signed(1),
+					Box::new(header),
+					justification,
+				),
+				<Error<TestRuntime>>::TooManyAuthoritiesInSet
+			);
+		})
+	}
+
+	#[test]
+	fn rejects_justification_that_is_too_large() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			let header = test_header(1);
+			let justification = make_default_justification(&header);
+			crate::mock::MaxJustificationSize::set(&(justification.encoded_size() as u32 - 1));
+
+			assert_noop!(
+				Pallet::<TestRuntime>::submit_finality_proof(
+					Origin::signed(1),
+					Box::new(header),
+					justification,
+				),
+				<Error<TestRuntime>>::JustificationIsTooLarge
+			);
+		})
+	}
+
 	#[test]
 	fn rejects_justification_that_skips_authority_set_transition() {
 		run_test(|| {
@@ -815,6 +1908,7 @@ mod tests {
 				authority_list: invalid_authority_list,
 				set_id: 1,
 				operating_mode: BasicOperatingMode::Normal,
+				checkpoints: vec![],
 			};
 
 			assert_ok!(Pallet::<TestRuntime>::initialize(Origin::root(), init_data));
@@ -859,6 +1953,10 @@ mod tests {
 
 			// Create a valid justification for the header
 			let justification = make_default_justification(&header);
+			let expected_weight = <TestRuntime as Config>::WeightInfo::submit_finality_proof(
+				justification.commit.precommits.len() as u32,
+				justification.votes_ancestries.len() as u32,
+			);
 
 			// Let's import our test header
 			assert_ok!(
@@ -868,7 +1966,7 @@ mod tests {
 					justification
 				),
 				PostDispatchInfo {
-					actual_weight: None,
+					actual_weight: Some(expected_weight),
 					pays_fee: frame_support::weights::Pays::No,
 				},
 			);
@@ -886,71 +1984,380 @@ mod tests {
 	}
 
 	#[test]
-	fn importing_header_rejects_header_with_scheduled_change_delay() {
+	fn synced_headers_grandpa_info_follows_authority_set_changes() {
 		run_test(|| {
 			initialize_substrate_bridge();
+			let genesis = test_header(0);
+			assert_eq!(
+				Pallet::<TestRuntime>::synced_headers_grandpa_info(),
+				bp_runtime::SyncedHeadersGrandpaInfo {
+					set_id: 1,
+					authority_list_hash: BlakeTwo256::hash_of(&authority_list()),
+					best_finalized: Some(HeaderId(0, genesis.hash())),
+				},
+			);
 
-			// Need to update the header digest to indicate that our header signals an authority set
-			// change. However, the change doesn't happen until the next block.
+			let next_authorities: sp_finality_grandpa::AuthorityList =
+				vec![(ALICE.into(), 1), (BOB.into(), 1)];
 			let mut header = test_header(2);
-			header.digest = change_log(1);
-
-			// Create a valid justification for the header
+			header.digest = change_log(0);
 			let justification = make_default_justification(&header);
+			assert_ok!(Pallet::<TestRuntime>::submit_finality_proof(
+				Origin::signed(1),
+				Box::new(header.clone()),
+				justification
+			));
 
-			// Should not be allowed to import this header
-			assert_err!(
-				Pallet::<TestRuntime>::submit_finality_proof(
-					Origin::signed(1),
-					Box::new(header),
-					justification
-				),
-				<Error<TestRuntime>>::UnsupportedScheduledChange
+			assert_eq!(
+				Pallet::<TestRuntime>::synced_headers_grandpa_info(),
+				bp_runtime::SyncedHeadersGrandpaInfo {
+					set_id: 2,
+					authority_list_hash: BlakeTwo256::hash_of(&next_authorities),
+					best_finalized: Some(HeaderId(2, header.hash())),
+				},
 			);
 		})
 	}
 
 	#[test]
-	fn importing_header_rejects_header_with_forced_changes() {
+	fn mandatory_header_import_refunds_weight_of_ignored_precommits() {
 		run_test(|| {
 			initialize_substrate_bridge();
 
-			// Need to update the header digest to indicate that it signals a forced authority set
-			// change.
 			let mut header = test_header(2);
-			header.digest = forced_change_log(0);
+			header.digest = change_log(0);
 
-			// Create a valid justification for the header
-			let justification = make_default_justification(&header);
+			// duplicate precommits are ignored by the justification verification
+			let mut justification = make_default_justification(&header);
+			let authorities_count = justification.commit.precommits.len() as u32;
+			let duplicate_precommit = justification.commit.precommits[0].clone();
+			justification.commit.precommits.push(duplicate_precommit.clone());
+			justification.commit.precommits.push(duplicate_precommit);
+			let votes_ancestries = justification.votes_ancestries.len() as u32;
 
-			// Should not be allowed to import this header
-			assert_err!(
+			assert_ok!(
 				Pallet::<TestRuntime>::submit_finality_proof(
 					Origin::signed(1),
 					Box::new(header),
 					justification
 				),
-				<Error<TestRuntime>>::UnsupportedScheduledChange
+				PostDispatchInfo {
+					actual_weight: Some(
+						<TestRuntime as Config>::WeightInfo::submit_finality_proof(
+							authorities_count,
+							votes_ancestries,
+						)
+					),
+					pays_fee: frame_support::weights::Pays::No,
+				},
 			);
 		})
 	}
 
 	#[test]
-	fn parse_finalized_storage_proof_rejects_proof_on_unknown_header() {
+	fn only_single_mandatory_header_is_imported_for_free_by_authority_set() {
 		run_test(|| {
-			assert_noop!(
-				Pallet::<TestRuntime>::parse_finalized_storage_proof(
-					Default::default(),
-					sp_trie::StorageProof::new(vec![]),
-					|_| (),
-				),
-				Error::<TestRuntime>::UnknownHeader,
+			initialize_substrate_bridge();
+
+			let submit_mandatory_header = |number| {
+				let mut header = test_header(number);
+				header.digest = change_log(0);
+				let justification = make_default_justification(&header);
+				Pallet::<TestRuntime>::submit_finality_proof(
+					Origin::signed(1),
+					Box::new(header),
+					justification,
+				)
+			};
+
+			// the first import of mandatory header is free
+			assert_eq!(
+				submit_mandatory_header(2).map(|info| info.pays_fee),
+				Ok(frame_support::weights::Pays::No),
 			);
-		});
+			assert_eq!(LastFreeHeaderSetId::<TestRuntime>::get(), Some(1));
+
+			// the duplicate submission fails and is paid
+			let error = submit_mandatory_header(2).unwrap_err();
+			assert_eq!(error.error, Error::<TestRuntime>::OldHeader.into());
+			assert_eq!(error.post_info.pays_fee, frame_support::weights::Pays::Yes);
+
+			// emulate the same authority set (e.g. the set has been reverted by the governance) =>
+			// the mandatory header is imported, but relayer is paying for it
+			CurrentAuthoritySet::<TestRuntime>::put(bp_header_chain::AuthoritySet::new(
+				authority_list(),
+				1,
+			));
+			assert_eq!(submit_mandatory_header(3), Ok(frame_support::weights::Pays::Yes.into()));
+			assert_eq!(LastFreeHeaderSetId::<TestRuntime>::get(), Some(1));
+		})
 	}
 
 	#[test]
-	fn parse_finalized_storage_accepts_valid_proof() {
+	fn events_are_deposited_on_initialization_and_import() {
+		run_test(|| {
+			frame_system::Pallet::<TestRuntime>::set_block_number(1);
+			initialize_substrate_bridge();
+
+			let genesis = test_header(0);
+			let mut header = test_header(2);
+			header.digest = change_log(0);
+			assert_ok!(submit_finality_proof(1));
+			assert_ok!(Pallet::<TestRuntime>::submit_finality_proof(
+				Origin::signed(1),
+				Box::new(header.clone()),
+				make_default_justification(&header),
+			));
+
+			assert_eq!(
+				frame_system::Pallet::<TestRuntime>::events()
+					.into_iter()
+					.map(|record| record.event)
+					.collect::<Vec<_>>(),
+				vec![
+					crate::mock::Event::Grandpa(Event::UpdatedBestFinalizedHeader {
+						number: 0,
+						hash: genesis.hash(),
+					}),
+					crate::mock::Event::Grandpa(Event::AuthoritySetChanged { new_set_id: 1 }),
+					crate::mock::Event::Grandpa(Event::UpdatedBestFinalizedHeader {
+						number: 1,
+						hash: test_header(1).hash(),
+					}),
+					crate::mock::Event::Grandpa(Event::AuthoritySetChanged { new_set_id: 2 }),
+					crate::mock::Event::Grandpa(Event::UpdatedBestFinalizedHeader {
+						number: 2,
+						hash: header.hash(),
+					}),
+				],
+			);
+		})
+	}
+
+	#[test]
+	fn is_free_header_works() {
+		run_test(|| {
+			let header_with_change = |number, digest| {
+				let mut header = test_header(number);
+				header.digest = digest;
+				header
+			};
+
+			// pallet is not initialized
+			assert!(!Pallet::<TestRuntime>::is_free_header(&header_with_change(2, change_log(0))));
+
+			initialize_substrate_bridge();
+			assert!(Pallet::<TestRuntime>::is_free_header(&header_with_change(2, change_log(0))));
+			assert!(!Pallet::<TestRuntime>::is_free_header(&test_header(2)));
+			assert!(Pallet::<TestRuntime>::is_free_header(&header_with_change(2, change_log(1))));
+			assert!(!Pallet::<TestRuntime>::is_free_header(&header_with_change(
+				2,
+				forced_change_log(0)
+			)));
+			assert!(!Pallet::<TestRuntime>::is_free_header(&header_with_change(0, change_log(0))));
+
+			// free header has been already imported by the current authority set
+			LastFreeHeaderSetId::<TestRuntime>::put(1);
+			assert!(!Pallet::<TestRuntime>::is_free_header(&header_with_change(2, change_log(0))));
+			LastFreeHeaderSetId::<TestRuntime>::kill();
+
+			PalletOperatingMode::<TestRuntime>::put(BasicOperatingMode::Halted);
+			assert!(!Pallet::<TestRuntime>::is_free_header(&header_with_change(2, change_log(0))));
+		})
+	}
+
+	fn header_with_digest(number: TestNumber, digest: Digest) -> TestHeader {
+		let mut header = test_header(number);
+		header.digest = digest;
+		header
+	}
+
+	fn submit_finality_proof_by_set(
+		header: TestHeader,
+		set_id: sp_finality_grandpa::SetId,
+	) -> frame_support::dispatch::DispatchResultWithPostInfo {
+		let justification = make_justification_for_header(JustificationGeneratorParams {
+			header: header.clone(),
+			set_id,
+			..Default::default()
+		});
+
+		Pallet::<TestRuntime>::submit_finality_proof(
+			Origin::signed(1),
+			Box::new(header),
+			justification,
+		)
+	}
+
+	#[test]
+	fn importing_header_schedules_delayed_authority_set_change() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			frame_system::Pallet::<TestRuntime>::set_block_number(1);
+
+			// header 2 schedules the change that is enacted by header 4
+			let post_info =
+				submit_finality_proof_by_set(header_with_digest(2, change_log(2)), 1).unwrap();
+			assert_eq!(post_info.pays_fee, frame_support::weights::Pays::No);
+
+			let next_authority_set =
+				bp_header_chain::AuthoritySet::new(vec![(ALICE.into(), 1), (BOB.into(), 1)], 2);
+			assert_eq!(
+				<PendingAuthoritySetChange<TestRuntime>>::get(),
+				Some(ScheduledAuthoritySetChange {
+					next_authority_set: next_authority_set.clone(),
+					enacted_at: 4,
+				}),
+			);
+			assert_eq!(<CurrentAuthoritySet<TestRuntime>>::get().set_id, 1);
+			assert!(frame_system::Pallet::<TestRuntime>::events().iter().any(
+				|event| event.event ==
+					crate::mock::Event::Grandpa(Event::AuthoritySetChangeScheduled {
+						new_set_id: 2,
+						enacted_at: 4,
+					})
+			));
+
+			// headers up to the enacting header are finalized by the current set
+			next_block();
+			assert_ok!(submit_finality_proof_by_set(test_header(3), 1));
+			assert!(<PendingAuthoritySetChange<TestRuntime>>::exists());
+			next_block();
+			assert_ok!(submit_finality_proof_by_set(test_header(4), 1));
+			assert_eq!(<PendingAuthoritySetChange<TestRuntime>>::get(), None);
+			assert_eq!(<CurrentAuthoritySet<TestRuntime>>::get(), next_authority_set);
+
+			// and its descendants are finalized by the next set
+			next_block();
+			assert_noop!(
+				submit_finality_proof_by_set(test_header(5), 1),
+				<Error<TestRuntime>>::InvalidJustification
+			);
+			assert_ok!(submit_finality_proof_by_set(test_header(5), 2));
+			assert_eq!(<BestFinalized<TestRuntime>>::get(), Some((5, test_header(5).hash())));
+		})
+	}
+
+	#[test]
+	fn enacting_header_is_finalized_by_current_authority_set() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			assert_ok!(submit_finality_proof_by_set(header_with_digest(2, change_log(2)), 1));
+			next_block();
+
+			assert_noop!(
+				submit_finality_proof_by_set(test_header(4), 2),
+				<Error<TestRuntime>>::InvalidJustification
+			);
+			assert_ok!(submit_finality_proof_by_set(test_header(4), 1));
+			assert_eq!(<CurrentAuthoritySet<TestRuntime>>::get().set_id, 2);
+		})
+	}
+
+	#[test]
+	fn descendant_of_enacting_header_enacts_pending_authority_set_change() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			assert_ok!(submit_finality_proof_by_set(header_with_digest(2, change_log(2)), 1));
+			next_block();
+
+			// the enacting header 4 is skipped by the relayer
+			assert_noop!(
+				submit_finality_proof_by_set(test_header(6), 1),
+				<Error<TestRuntime>>::InvalidJustification
+			);
+			assert_ok!(submit_finality_proof_by_set(test_header(6), 2));
+			assert_eq!(<PendingAuthoritySetChange<TestRuntime>>::get(), None);
+			assert_eq!(<CurrentAuthoritySet<TestRuntime>>::get().set_id, 2);
+			assert_eq!(<BestFinalized<TestRuntime>>::get(), Some((6, test_header(6).hash())));
+
+			// and the next change may be scheduled by the next set
+			next_block();
+			assert_ok!(submit_finality_proof_by_set(header_with_digest(7, change_log(0)), 2));
+			assert_eq!(<CurrentAuthoritySet<TestRuntime>>::get().set_id, 3);
+		})
+	}
+
+	#[test]
+	fn importing_header_rejects_change_scheduled_while_previous_change_is_pending() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			assert_ok!(submit_finality_proof_by_set(header_with_digest(2, change_log(2)), 1));
+			next_block();
+
+			// only the pending change prevents free imports below
+			LastFreeHeaderSetId::<TestRuntime>::kill();
+			assert!(!Pallet::<TestRuntime>::is_free_header(&header_with_digest(3, change_log(0))));
+			assert_noop!(
+				submit_finality_proof_by_set(header_with_digest(3, change_log(0)), 1),
+				<Error<TestRuntime>>::UnsupportedScheduledChange
+			);
+
+			// the enacting header may schedule the next change
+			assert!(Pallet::<TestRuntime>::is_free_header(&header_with_digest(4, change_log(1))));
+			assert_ok!(submit_finality_proof_by_set(header_with_digest(4, change_log(1)), 1));
+			assert_eq!(<CurrentAuthoritySet<TestRuntime>>::get().set_id, 2);
+			assert_eq!(
+				<PendingAuthoritySetChange<TestRuntime>>::get()
+					.map(|change| (change.next_authority_set.set_id, change.enacted_at)),
+				Some((3, 5)),
+			);
+		})
+	}
+
+	#[test]
+	fn reinitialize_forgets_pending_authority_set_change() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			assert_ok!(submit_finality_proof_by_set(header_with_digest(2, change_log(2)), 1));
+			assert!(<PendingAuthoritySetChange<TestRuntime>>::exists());
+
+			assert_ok!(Pallet::<TestRuntime>::reinitialize(Origin::root(), reinit_data(10, 5)));
+			assert_eq!(<PendingAuthoritySetChange<TestRuntime>>::get(), None);
+		})
+	}
+
+	#[test]
+	fn importing_header_rejects_header_with_forced_changes() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			// Need to update the header digest to indicate that it signals a forced authority set
+			// change.
+			let mut header = test_header(2);
+			header.digest = forced_change_log(0);
+
+			// Create a valid justification for the header
+			let justification = make_default_justification(&header);
+
+			// Should not be allowed to import this header
+			assert_err!(
+				Pallet::<TestRuntime>::submit_finality_proof(
+					Origin::signed(1),
+					Box::new(header),
+					justification
+				),
+				<Error<TestRuntime>>::UnsupportedScheduledChange
+			);
+		})
+	}
+
+	#[test]
+	fn parse_finalized_storage_proof_rejects_proof_on_unknown_header() {
+		run_test(|| {
+			assert_noop!(
+				Pallet::<TestRuntime>::parse_finalized_storage_proof(
+					Default::default(),
+					sp_trie::StorageProof::new(vec![]),
+					|_| (),
+				),
+				Error::<TestRuntime>::UnknownHeader,
+			);
+		});
+	}
+
+	#[test]
+	fn parse_finalized_storage_accepts_valid_proof() {
 		run_test(|| {
 			let (state_root, storage_proof) = bp_runtime::craft_valid_storage_proof();
 
@@ -968,6 +2375,275 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn parse_finalized_storage_accepts_proof_at_trusted_checkpoint() {
+		run_test(|| {
+			let (state_root, storage_proof) = bp_runtime::craft_valid_storage_proof();
+			let checkpoint_hash = TestHeader::new(
+				42,
+				Default::default(),
+				state_root,
+				Default::default(),
+				Default::default(),
+			)
+			.hash();
+
+			assert_ok!(Pallet::<TestRuntime>::initialize(
+				Origin::root(),
+				InitializationData {
+					header: Box::new(test_header(100)),
+					authority_list: authority_list(),
+					set_id: 1,
+					operating_mode: BasicOperatingMode::Normal,
+					checkpoints: vec![TrustedCheckpoint {
+						number: 42,
+						hash: checkpoint_hash,
+						state_root,
+					}],
+				},
+			));
+
+			assert!(Pallet::<TestRuntime>::is_trusted_checkpoint(checkpoint_hash));
+			assert_ok!(
+				Pallet::<TestRuntime>::parse_finalized_storage_proof(
+					checkpoint_hash,
+					storage_proof.clone(),
+					|_| (),
+				),
+				(),
+			);
+			assert_noop!(
+				Pallet::<TestRuntime>::parse_finalized_storage_proof(
+					test_header(42).hash(),
+					storage_proof,
+					|_| (),
+				),
+				Error::<TestRuntime>::UnknownHeader,
+			);
+		});
+	}
+
+	#[test]
+	fn trusted_checkpoints_are_never_pruned() {
+		run_test(|| {
+			let (state_root, storage_proof) = bp_runtime::craft_valid_storage_proof();
+			let checkpoint_hash = test_header(0).hash();
+			assert_ok!(Pallet::<TestRuntime>::initialize(
+				Origin::root(),
+				InitializationData {
+					header: Box::new(test_header(1)),
+					authority_list: authority_list(),
+					set_id: 1,
+					operating_mode: BasicOperatingMode::Normal,
+					checkpoints: vec![TrustedCheckpoint {
+						number: 0,
+						hash: checkpoint_hash,
+						state_root,
+					}],
+				},
+			));
+
+			for number in 2..=7 {
+				assert_ok!(submit_finality_proof(number));
+				next_block();
+			}
+
+			// the initial header has been pruned
+			assert!(!Pallet::<TestRuntime>::is_known_header(test_header(1).hash()));
+			// but storage proofs may still be verified using the trusted checkpoint
+			assert!(Pallet::<TestRuntime>::is_trusted_checkpoint(checkpoint_hash));
+			assert_ok!(
+				Pallet::<TestRuntime>::parse_finalized_storage_proof(
+					checkpoint_hash,
+					storage_proof,
+					|_| (),
+				),
+				(),
+			);
+		});
+	}
+
+	#[test]
+	fn trusted_checkpoints_are_not_imported_headers() {
+		run_test(|| {
+			let checkpoint_hash = test_header(0).hash();
+			assert_ok!(Pallet::<TestRuntime>::initialize(
+				Origin::root(),
+				InitializationData {
+					header: Box::new(test_header(1)),
+					authority_list: authority_list(),
+					set_id: 1,
+					operating_mode: BasicOperatingMode::Normal,
+					checkpoints: vec![TrustedCheckpoint {
+						number: 0,
+						hash: checkpoint_hash,
+						state_root: Default::default(),
+					}],
+				},
+			));
+
+			assert!(Pallet::<TestRuntime>::is_trusted_checkpoint(checkpoint_hash));
+			assert!(!Pallet::<TestRuntime>::is_known_header(checkpoint_hash));
+			assert!(!ImportedHeaders::<TestRuntime>::contains_key(checkpoint_hash));
+			assert_eq!(
+				TrustedCheckpoints::<TestRuntime>::get(checkpoint_hash),
+				Some((0, Default::default())),
+			);
+		});
+	}
+
+	#[test]
+	fn initialize_rejects_checkpoints_colliding_with_initial_header() {
+		run_test(|| {
+			let initial_header = test_header(10);
+			let init_data = |checkpoint| InitializationData {
+				header: Box::new(initial_header.clone()),
+				authority_list: authority_list(),
+				set_id: 1,
+				operating_mode: BasicOperatingMode::Normal,
+				checkpoints: vec![checkpoint],
+			};
+
+			// checkpoint with the same hash
+			assert_noop!(
+				Pallet::<TestRuntime>::initialize(
+					Origin::root(),
+					init_data(TrustedCheckpoint {
+						number: 5,
+						hash: initial_header.hash(),
+						state_root: Default::default(),
+					}),
+				),
+				Error::<TestRuntime>::CheckpointCollidesWithInitialHeader,
+			);
+
+			// checkpoint with the same number
+			assert_noop!(
+				Pallet::<TestRuntime>::initialize(
+					Origin::root(),
+					init_data(TrustedCheckpoint {
+						number: 10,
+						hash: [10u8; 32].into(),
+						state_root: Default::default(),
+					}),
+				),
+				Error::<TestRuntime>::CheckpointCollidesWithInitialHeader,
+			);
+		});
+	}
+
+	#[test]
+	fn finalized_header_returns_known_headers_only() {
+		run_test(|| {
+			let genesis = test_header(0);
+			assert_ok!(Pallet::<TestRuntime>::initialize(
+				Origin::root(),
+				InitializationData {
+					header: Box::new(genesis.clone()),
+					authority_list: authority_list(),
+					set_id: 1,
+					operating_mode: BasicOperatingMode::Normal,
+					checkpoints: vec![TrustedCheckpoint {
+						number: 100,
+						hash: [100u8; 32].into(),
+						state_root: Default::default(),
+					}],
+				},
+			));
+
+			for number in 1..=6 {
+				assert_ok!(submit_finality_proof(number));
+				next_block();
+			}
+
+			// the best finalized header
+			assert_eq!(
+				Pallet::<TestRuntime>::finalized_header(6),
+				Some(HeaderId(6, test_header(6).hash()))
+			);
+			// header that is still in the ring buffer
+			assert_eq!(
+				Pallet::<TestRuntime>::finalized_header(5),
+				Some(HeaderId(5, test_header(5).hash()))
+			);
+			// trusted checkpoint isn't a finalized header that is known to the pallet
+			assert!(Pallet::<TestRuntime>::is_trusted_checkpoint([100u8; 32].into()));
+			assert_eq!(Pallet::<TestRuntime>::finalized_header(100), None);
+			// pruned header
+			assert_eq!(Pallet::<TestRuntime>::finalized_header(1), None);
+			// header that has never been imported
+			assert_eq!(Pallet::<TestRuntime>::finalized_header(7), None);
+		});
+	}
+
+	#[test]
+	fn finalized_header_finds_headers_in_ring_buffer() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			for number in [2, 4, 6, 8] {
+				assert_ok!(submit_finality_proof(number));
+				next_block();
+			}
+
+			// all imported headers are found and gaps are skipped
+			for number in [0, 2, 4, 6, 8] {
+				assert_eq!(
+					Pallet::<TestRuntime>::finalized_header(number),
+					Some(HeaderId(number, test_header(number).hash()))
+				);
+			}
+			for number in [1, 3, 5, 7, 9] {
+				assert_eq!(Pallet::<TestRuntime>::finalized_header(number), None);
+			}
+
+			// oldest entries of the ring buffer are missing after pruning
+			assert_ok!(Pallet::<TestRuntime>::set_headers_to_keep(Origin::root(), 3));
+			assert_ok!(Pallet::<TestRuntime>::set_headers_to_keep(Origin::root(), 5));
+			assert_eq!(Pallet::<TestRuntime>::finalized_header(0), None);
+			assert_eq!(Pallet::<TestRuntime>::finalized_header(2), None);
+			for number in [4, 6, 8] {
+				assert_eq!(
+					Pallet::<TestRuntime>::finalized_header(number),
+					Some(HeaderId(number, test_header(number).hash()))
+				);
+			}
+		});
+	}
+
+	#[test]
+	fn known_headers_are_reported_at_pruning_boundary() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			for number in 1..=6 {
+				assert_ok!(submit_finality_proof(number));
+				next_block();
+			}
+
+			// `HeadersToKeep` is 5, so headers 2..=6 are kept and headers 0 and 1 are pruned
+			assert!(Pallet::<TestRuntime>::is_known_header(test_header(2).hash()));
+			assert_eq!(
+				Pallet::<TestRuntime>::finalized_header_hash(2),
+				Some(test_header(2).hash())
+			);
+			assert!(!Pallet::<TestRuntime>::is_known_header(test_header(1).hash()));
+			assert_eq!(Pallet::<TestRuntime>::finalized_header_hash(1), None);
+
+			// importing the next header prunes the oldest kept header
+			assert_ok!(submit_finality_proof(7));
+			assert!(!Pallet::<TestRuntime>::is_known_header(test_header(2).hash()));
+			assert_eq!(Pallet::<TestRuntime>::finalized_header_hash(2), None);
+			assert!(Pallet::<TestRuntime>::is_known_header(test_header(3).hash()));
+			assert_eq!(
+				Pallet::<TestRuntime>::finalized_header_hash(3),
+				Some(test_header(3).hash())
+			);
+			assert_eq!(
+				Pallet::<TestRuntime>::finalized_header_hash(7),
+				Some(test_header(7).hash())
+			);
+		});
+	}
+
 	#[test]
 	fn rate_limiter_disallows_imports_once_limit_is_hit_in_single_block() {
 		run_test(|| {
@@ -1075,7 +2751,162 @@ mod tests {
 		})
 	}
 
-	#[test]
+	fn last_event() -> crate::mock::Event {
+		frame_system::Pallet::<TestRuntime>::events()
+			.pop()
+			.expect("no events deposited")
+			.event
+	}
+
+	#[test]
+	fn rate_limiter_uses_max_requests_from_storage() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			assert_eq!(Pallet::<TestRuntime>::max_requests(), 2);
+
+			assert_ok!(Pallet::<TestRuntime>::set_max_requests(Origin::root(), 3));
+			assert_ok!(submit_finality_proof(1));
+			assert_ok!(submit_finality_proof(2));
+			assert_ok!(submit_finality_proof(3));
+			assert_err!(submit_finality_proof(4), <Error<TestRuntime>>::TooManyRequests);
+
+			next_block();
+			assert_ok!(Pallet::<TestRuntime>::set_max_requests(Origin::root(), 1));
+			assert_err!(submit_finality_proof(4), <Error<TestRuntime>>::TooManyRequests);
+		})
+	}
+
+	#[test]
+	fn set_max_requests_works() {
+		run_test(|| {
+			next_block();
+
+			assert_noop!(
+				Pallet::<TestRuntime>::set_max_requests(Origin::signed(1), 10),
+				DispatchError::BadOrigin,
+			);
+			assert_noop!(
+				Pallet::<TestRuntime>::set_max_requests(Origin::root(), 0),
+				Error::<TestRuntime>::InvalidMaxRequests,
+			);
+			assert_noop!(
+				Pallet::<TestRuntime>::set_max_requests(
+					Origin::root(),
+					*MAX_REQUESTS_BOUNDS.end() + 1
+				),
+				Error::<TestRuntime>::InvalidMaxRequests,
+			);
+
+			PalletOwner::<TestRuntime>::put(1);
+			assert_ok!(Pallet::<TestRuntime>::set_max_requests(Origin::signed(1), 10));
+			assert_eq!(Pallet::<TestRuntime>::max_requests(), 10);
+			assert_eq!(
+				last_event(),
+				crate::mock::Event::Grandpa(Event::MaxRequestsUpdated { max_requests: 10 }),
+			);
+		})
+	}
+
+	#[test]
+	fn set_headers_to_keep_checks_origin_and_bounds() {
+		run_test(|| {
+			assert_noop!(
+				Pallet::<TestRuntime>::set_headers_to_keep(Origin::signed(1), 10),
+				DispatchError::BadOrigin,
+			);
+			assert_noop!(
+				Pallet::<TestRuntime>::set_headers_to_keep(Origin::root(), 0),
+				Error::<TestRuntime>::InvalidHeadersToKeep,
+			);
+			assert_noop!(
+				Pallet::<TestRuntime>::set_headers_to_keep(
+					Origin::root(),
+					*HEADERS_TO_KEEP_BOUNDS.end() + 1
+				),
+				Error::<TestRuntime>::InvalidHeadersToKeep,
+			);
+
+			PalletOwner::<TestRuntime>::put(1);
+			assert_ok!(Pallet::<TestRuntime>::set_headers_to_keep(Origin::signed(1), 10));
+			assert_eq!(Pallet::<TestRuntime>::headers_to_keep(), 10);
+		})
+	}
+
+	#[test]
+	fn shrinking_headers_to_keep_prunes_headers_immediately() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			let mut hashes = vec![Pallet::<TestRuntime>::best_finalized().unwrap().hash()];
+			for header in 1..=4 {
+				next_block();
+				next_block();
+				assert_ok!(submit_finality_proof(header));
+				hashes.push(Pallet::<TestRuntime>::best_finalized().unwrap().hash());
+			}
+			assert!(hashes.iter().all(|hash| Pallet::<TestRuntime>::is_known_header(*hash)));
+
+			assert_ok!(Pallet::<TestRuntime>::set_headers_to_keep(Origin::root(), 2));
+			assert_eq!(
+				last_event(),
+				crate::mock::Event::Grandpa(Event::HeadersToKeepUpdated {
+					headers_to_keep: 2,
+					pruned_headers: 3,
+				}),
+			);
+			assert!(hashes[..3].iter().all(|hash| !Pallet::<TestRuntime>::is_known_header(*hash)));
+			assert!(hashes[3..].iter().all(|hash| Pallet::<TestRuntime>::is_known_header(*hash)));
+
+			// and the new value is used when next header is imported
+			next_block();
+			assert_ok!(submit_finality_proof(5));
+			assert!(!Pallet::<TestRuntime>::is_known_header(hashes[3]));
+			assert!(Pallet::<TestRuntime>::is_known_header(hashes[4]));
+		})
+	}
+
+	#[test]
+	fn enlarging_headers_to_keep_does_not_restore_pruned_headers() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			let mut hashes = vec![Pallet::<TestRuntime>::best_finalized().unwrap().hash()];
+			for header in 1..=6 {
+				next_block();
+				next_block();
+				assert_ok!(submit_finality_proof(header));
+				hashes.push(Pallet::<TestRuntime>::best_finalized().unwrap().hash());
+			}
+			// with `HeadersToKeep = 5`, headers 0 and 1 are pruned
+			assert!(hashes[..2].iter().all(|hash| !Pallet::<TestRuntime>::is_known_header(*hash)));
+
+			assert_ok!(Pallet::<TestRuntime>::set_headers_to_keep(Origin::root(), 7));
+			assert_eq!(
+				last_event(),
+				crate::mock::Event::Grandpa(Event::HeadersToKeepUpdated {
+					headers_to_keep: 7,
+					pruned_headers: 0,
+				}),
+			);
+			assert!(hashes[..2].iter().all(|hash| !Pallet::<TestRuntime>::is_known_header(*hash)));
+			assert!(hashes[2..].iter().all(|hash| Pallet::<TestRuntime>::is_known_header(*hash)));
+
+			// two more headers may be imported without pruning anything
+			for header in 7..=8 {
+				next_block();
+				next_block();
+				assert_ok!(submit_finality_proof(header));
+				hashes.push(Pallet::<TestRuntime>::best_finalized().unwrap().hash());
+			}
+			assert!(hashes[2..].iter().all(|hash| Pallet::<TestRuntime>::is_known_header(*hash)));
+
+			// and then the oldest header is pruned again
+			next_block();
+			assert_ok!(submit_finality_proof(9));
+			assert!(!Pallet::<TestRuntime>::is_known_header(hashes[2]));
+			assert!(hashes[3..].iter().all(|hash| Pallet::<TestRuntime>::is_known_header(*hash)));
+		})
+	}
+
+	#[test]
 	fn storage_keys_computed_properly() {
 		assert_eq!(
 			PalletOperatingMode::<TestRuntime>::storage_value_final_key().to_vec(),
@@ -1086,6 +2917,543 @@ mod tests {
 			BestFinalized::<TestRuntime>::storage_value_final_key().to_vec(),
 			bp_header_chain::storage_keys::best_finalized_key("Grandpa").0,
 		);
+
+		assert_eq!(
+			FrozenAt::<TestRuntime>::storage_value_final_key().to_vec(),
+			bp_header_chain::storage_keys::frozen_at_key("Grandpa").0,
+		);
+
+		assert_eq!(
+			PendingAuthoritySetChange::<TestRuntime>::storage_value_final_key().to_vec(),
+			bp_header_chain::storage_keys::pending_authority_set_change_key("Grandpa").0,
+		);
+	}
+
+	#[test]
+	fn import_prunes_single_oldest_header_after_gap() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			for number in 1..=4 {
+				assert_ok!(submit_finality_proof(number));
+				next_block();
+			}
+
+			// `HeadersToKeep` is 5, so all headers 0..=4 are kept
+			assert!((0..=4).all(|n| Pallet::<TestRuntime>::is_known_header(test_header(n).hash())));
+
+			// importing header that is far ahead of the best finalized only prunes the oldest
+			// header, no matter how large the gap is
+			assert_ok!(submit_finality_proof(200));
+			assert!(!Pallet::<TestRuntime>::is_known_header(test_header(0).hash()));
+			assert!((1..=4).all(|n| Pallet::<TestRuntime>::is_known_header(test_header(n).hash())));
+			assert!(Pallet::<TestRuntime>::is_known_header(test_header(200).hash()));
+			assert_eq!(ImportedHashes::<TestRuntime>::iter().count(), 5);
+		})
+	}
+
+	#[test]
+	fn stored_justifications_stay_within_bound() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			for number in 1..=10 {
+				assert_ok!(submit_finality_proof(number));
+				next_block();
+
+				// `JustificationsToKeep` is 3
+				assert!(StoredJustifications::<TestRuntime>::iter().count() <= 3);
+			}
+
+			// only justifications of the most recent headers are stored
+			assert_eq!(StoredJustifications::<TestRuntime>::iter().count(), 3);
+			assert!((1..=7)
+				.all(|n| Pallet::<TestRuntime>::stored_justification(test_header(n).hash())
+					.is_none()));
+			assert!((6..=7).all(|n| Pallet::<TestRuntime>::is_known_header(test_header(n).hash())));
+			let header = test_header(10);
+			assert_eq!(
+				Pallet::<TestRuntime>::stored_justification(header.hash()),
+				Some(make_default_justification(&header).encode()),
+			);
+		})
+	}
+
+	#[test]
+	fn stored_justifications_are_pruned_with_headers() {
+		run_test(|| {
+			crate::mock::JustificationsToKeep::set(&10);
+			initialize_substrate_bridge();
+			for number in 1..=8 {
+				assert_ok!(submit_finality_proof(number));
+				next_block();
+			}
+
+			// `HeadersToKeep` is 5, so there are no more than 5 justifications
+			assert_eq!(StoredJustifications::<TestRuntime>::iter().count(), 5);
+			assert!((4..=8)
+				.all(|n| Pallet::<TestRuntime>::stored_justification(test_header(n).hash())
+					.is_some()));
+
+			assert_ok!(Pallet::<TestRuntime>::set_headers_to_keep(Origin::root(), 2));
+			assert_eq!(StoredJustifications::<TestRuntime>::iter().count(), 2);
+			assert!((7..=8)
+				.all(|n| Pallet::<TestRuntime>::stored_justification(test_header(n).hash())
+					.is_some()));
+		})
+	}
+
+	#[test]
+	fn justifications_are_not_stored_if_disabled() {
+		run_test(|| {
+			crate::mock::JustificationsToKeep::set(&0);
+			initialize_substrate_bridge();
+			for number in 1..=3 {
+				assert_ok!(submit_finality_proof(number));
+				next_block();
+			}
+
+			assert_eq!(StoredJustifications::<TestRuntime>::iter().count(), 0);
+			assert_eq!(Pallet::<TestRuntime>::stored_justification(test_header(3).hash()), None);
+		})
+	}
+
+	fn reinit_data(number: TestNumber, set_id: u64) -> InitializationData<TestHeader> {
+		InitializationData {
+			header: Box::new(test_header(number)),
+			authority_list: authority_list(),
+			set_id,
+			operating_mode: BasicOperatingMode::Normal,
+			checkpoints: vec![],
+		}
+	}
+
+	#[test]
+	fn reinitialize_works() {
+		run_test(|| {
+			let genesis = test_header(0);
+			assert_ok!(Pallet::<TestRuntime>::initialize(
+				Origin::root(),
+				InitializationData {
+					checkpoints: vec![TrustedCheckpoint {
+						number: 0,
+						hash: genesis.hash(),
+						state_root: *genesis.state_root(),
+					}],
+					..reinit_data(1, 1)
+				},
+			));
+			assert_ok!(submit_finality_proof(2));
+			assert_ok!(submit_finality_proof(3));
+			next_block();
+			next_block();
+
+			// the bridged chain has moved to the set#5 while the pallet has been halted
+			assert_ok!(Pallet::<TestRuntime>::set_operating_mode(
+				Origin::root(),
+				BasicOperatingMode::Halted
+			));
+			frame_system::Pallet::<TestRuntime>::reset_events();
+			let header = test_header(10);
+			assert_ok!(Pallet::<TestRuntime>::reinitialize(Origin::root(), reinit_data(10, 5)));
+
+			assert_eq!(<BestFinalized<TestRuntime>>::get(), Some((10, header.hash())));
+			assert_eq!(
+				<CurrentAuthoritySet<TestRuntime>>::get(),
+				bp_header_chain::AuthoritySet::new(authority_list(), 5),
+			);
+			assert_eq!(PalletOperatingMode::<TestRuntime>::get(), BasicOperatingMode::Normal);
+			assert!(Pallet::<TestRuntime>::is_known_header(header.hash()));
+			assert!(!Pallet::<TestRuntime>::is_known_header(test_header(3).hash()));
+			assert!(!Pallet::<TestRuntime>::is_known_header(test_header(2).hash()));
+			assert!(!Pallet::<TestRuntime>::is_known_header(test_header(1).hash()));
+			// trusted checkpoints are kept
+			assert!(Pallet::<TestRuntime>::is_trusted_checkpoint(genesis.hash()));
+			assert_eq!(
+				frame_system::Pallet::<TestRuntime>::events()
+					.into_iter()
+					.map(|record| record.event)
+					.collect::<Vec<_>>(),
+				vec![
+					crate::mock::Event::Grandpa(Event::UpdatedBestFinalizedHeader {
+						number: 10,
+						hash: header.hash(),
+					}),
+					crate::mock::Event::Grandpa(Event::AuthoritySetChanged { new_set_id: 5 }),
+					crate::mock::Event::Grandpa(Event::Reinitialized {
+						number: 10,
+						hash: header.hash(),
+						set_id: 5,
+					}),
+				],
+			);
+
+			// headers, finalized by the new authority set, may be imported
+			let header = test_header(11);
+			let justification = make_justification_for_header(JustificationGeneratorParams {
+				header: header.clone(),
+				set_id: 5,
+				..Default::default()
+			});
+			assert_ok!(Pallet::<TestRuntime>::submit_finality_proof(
+				Origin::signed(1),
+				Box::new(header.clone()),
+				justification,
+			));
+			assert_eq!(<BestFinalized<TestRuntime>>::get(), Some((11, header.hash())));
+		})
+	}
+
+	#[test]
+	fn reinitialize_rejects_obsolete_data() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			assert_ok!(submit_finality_proof(5));
+
+			// same or older authority set
+			assert_noop!(
+				Pallet::<TestRuntime>::reinitialize(Origin::root(), reinit_data(10, 1)),
+				Error::<TestRuntime>::ObsoleteInitializationData
+			);
+			assert_noop!(
+				Pallet::<TestRuntime>::reinitialize(Origin::root(), reinit_data(10, 0)),
+				Error::<TestRuntime>::ObsoleteInitializationData
+			);
+			// same or older header
+			assert_noop!(
+				Pallet::<TestRuntime>::reinitialize(Origin::root(), reinit_data(5, 2)),
+				Error::<TestRuntime>::ObsoleteInitializationData
+			);
+			assert_noop!(
+				Pallet::<TestRuntime>::reinitialize(Origin::root(), reinit_data(4, 2)),
+				Error::<TestRuntime>::ObsoleteInitializationData
+			);
+		})
+	}
+
+	#[test]
+	fn reinitialize_requires_initialized_pallet() {
+		run_test(|| {
+			assert_noop!(
+				Pallet::<TestRuntime>::reinitialize(Origin::root(), reinit_data(10, 2)),
+				Error::<TestRuntime>::NotInitialized
+			);
+		})
+	}
+
+	#[test]
+	fn reinitialize_may_only_be_called_by_owner_or_root() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			assert_noop!(
+				Pallet::<TestRuntime>::reinitialize(Origin::signed(1), reinit_data(10, 2)),
+				DispatchError::BadOrigin
+			);
+
+			PalletOwner::<TestRuntime>::put(1);
+			assert_ok!(Pallet::<TestRuntime>::reinitialize(Origin::signed(1), reinit_data(10, 2)));
+		})
+	}
+
+	fn finality_proof_by_set(
+		header: TestHeader,
+		set_id: sp_finality_grandpa::SetId,
+	) -> FinalityProof<TestRuntime, ()> {
+		let justification = make_justification_for_header(JustificationGeneratorParams {
+			header: header.clone(),
+			set_id,
+			..Default::default()
+		});
+		(Box::new(header), justification)
+	}
+
+	fn submit_finality_proof_batch(
+		finality_proofs: Vec<FinalityProof<TestRuntime, ()>>,
+	) -> frame_support::dispatch::DispatchResultWithPostInfo {
+		Pallet::<TestRuntime>::submit_finality_proof_batch(Origin::signed(1), finality_proofs)
+	}
+
+	#[test]
+	fn batch_imports_headers_in_order_and_counts_as_single_request() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			let finality_proofs =
+				(1..=3).map(|number| finality_proof_by_set(test_header(number), 1)).collect();
+			assert_eq!(
+				submit_finality_proof_batch(finality_proofs).map(|info| info.pays_fee),
+				Ok(frame_support::weights::Pays::Yes),
+			);
+
+			assert_eq!(<BestFinalized<TestRuntime>>::get(), Some((3, test_header(3).hash())));
+			assert!((1..=3).all(|number| <ImportedHeaders<TestRuntime>>::contains_key(
+				test_header(number).hash()
+			)));
+			assert_eq!(<RequestCount<TestRuntime>>::get(), 1);
+		})
+	}
+
+	#[test]
+	fn batch_import_stops_at_first_rejected_proof() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			frame_system::Pallet::<TestRuntime>::set_block_number(1);
+
+			// the second proof is signed by unknown authority set
+			let finality_proofs = vec![
+				finality_proof_by_set(test_header(1), 1),
+				finality_proof_by_set(test_header(2), 2),
+				finality_proof_by_set(test_header(3), 1),
+			];
+			let expected_weight =
+				submit_finality_proof_weight::<TestRuntime, ()>(&finality_proofs[0].1)
+					.saturating_add(submit_finality_proof_weight::<TestRuntime, ()>(
+						&finality_proofs[1].1,
+					))
+					.saturating_add(
+						<TestRuntime as Config>::WeightInfo::submit_finality_proof_batch(2),
+					);
+
+			assert_ok!(
+				submit_finality_proof_batch(finality_proofs),
+				PostDispatchInfo {
+					actual_weight: Some(expected_weight),
+					pays_fee: frame_support::weights::Pays::Yes,
+				},
+			);
+			assert_eq!(
+				last_event(),
+				crate::mock::Event::Grandpa(Event::FinalityProofBatchInterrupted {
+					index: 1,
+					error: <Error<TestRuntime>>::InvalidJustification.into(),
+				}),
+			);
+
+			assert_eq!(<BestFinalized<TestRuntime>>::get(), Some((1, test_header(1).hash())));
+			assert!(!<ImportedHeaders<TestRuntime>>::contains_key(test_header(2).hash()));
+			assert!(!<ImportedHeaders<TestRuntime>>::contains_key(test_header(3).hash()));
+			assert_eq!(<RequestCount<TestRuntime>>::get(), 1);
+		})
+	}
+
+	#[test]
+	fn batch_fails_if_first_proof_is_rejected() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			assert_noop!(
+				submit_finality_proof_batch(vec![
+					finality_proof_by_set(test_header(0), 1),
+					finality_proof_by_set(test_header(1), 1),
+				]),
+				<Error<TestRuntime>>::OldHeader
+			);
+		})
+	}
+
+	#[test]
+	fn batch_rejects_empty_and_oversized_batches() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			assert_noop!(
+				submit_finality_proof_batch(vec![]),
+				<Error<TestRuntime>>::EmptyFinalityProofsBatch
+			);
+
+			let max_proofs = <TestRuntime as Config>::MaxFinalityProofsInBatch::get();
+			assert_noop!(
+				submit_finality_proof_batch(
+					(1..=max_proofs + 1)
+						.map(|number| finality_proof_by_set(test_header(number.into()), 1))
+						.collect()
+				),
+				<Error<TestRuntime>>::TooManyFinalityProofsInBatch
+			);
+		})
+	}
+
+	#[test]
+	fn batch_imports_header_finalized_by_authority_set_enacted_in_the_same_batch() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			// mandatory header 2 enacts set 2 and header 3 is finalized by that set
+			assert_eq!(
+				submit_finality_proof_batch(vec![
+					finality_proof_by_set(header_with_digest(2, change_log(0)), 1),
+					finality_proof_by_set(test_header(3), 2),
+				])
+				.map(|info| info.pays_fee),
+				Ok(frame_support::weights::Pays::Yes),
+			);
+			assert_eq!(<CurrentAuthoritySet<TestRuntime>>::get().set_id, 2);
+			assert_eq!(<BestFinalized<TestRuntime>>::get(), Some((3, test_header(3).hash())));
+
+			// the batch of free headers is free
+			next_block();
+			assert_eq!(
+				submit_finality_proof_batch(vec![finality_proof_by_set(
+					header_with_digest(4, change_log(0)),
+					2
+				)])
+				.map(|info| info.pays_fee),
+				Ok(frame_support::weights::Pays::No),
+			);
+		})
+	}
+
+	#[test]
+	fn zero_min_header_gap_does_not_restrict_imports() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			assert_eq!(Pallet::<TestRuntime>::min_header_gap(), 0);
+
+			assert_ok!(submit_finality_proof(1));
+			assert_ok!(submit_finality_proof(2));
+			assert_eq!(<BestFinalized<TestRuntime>>::get(), Some((2, test_header(2).hash())));
+		})
+	}
+
+	#[test]
+	fn regular_headers_below_min_header_gap_are_rejected() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			assert_ok!(Pallet::<TestRuntime>::set_min_header_gap(Origin::root(), 3));
+
+			assert_noop!(submit_finality_proof(1), <Error<TestRuntime>>::HeaderGapIsTooSmall);
+			assert_noop!(submit_finality_proof(2), <Error<TestRuntime>>::HeaderGapIsTooSmall);
+			assert_ok!(submit_finality_proof(3));
+			assert_eq!(<BestFinalized<TestRuntime>>::get(), Some((3, test_header(3).hash())));
+
+			// mandatory headers are always imported
+			next_block();
+			assert_ok!(submit_finality_proof_by_set(header_with_digest(4, change_log(0)), 1));
+			assert_eq!(<CurrentAuthoritySet<TestRuntime>>::get().set_id, 2);
+		})
+	}
+
+	#[test]
+	fn set_min_header_gap_works() {
+		run_test(|| {
+			next_block();
+
+			assert_noop!(
+				Pallet::<TestRuntime>::set_min_header_gap(Origin::signed(1), 10),
+				DispatchError::BadOrigin,
+			);
+
+			PalletOwner::<TestRuntime>::put(1);
+			assert_ok!(Pallet::<TestRuntime>::set_min_header_gap(Origin::signed(1), 10));
+			assert_eq!(Pallet::<TestRuntime>::min_header_gap(), 10);
+			assert_eq!(
+				last_event(),
+				crate::mock::Event::Grandpa(Event::MinHeaderGapUpdated { min_header_gap: 10 }),
+			);
+		})
+	}
+
+	#[test]
+	fn set_min_header_gap_rejects_too_large_gap() {
+		run_test(|| {
+			let max_gap = MAX_MIN_HEADER_GAP as TestNumber;
+			assert_noop!(
+				Pallet::<TestRuntime>::set_min_header_gap(Origin::root(), max_gap + 1),
+				Error::<TestRuntime>::InvalidMinHeaderGap,
+			);
+
+			assert_ok!(Pallet::<TestRuntime>::set_min_header_gap(Origin::root(), max_gap));
+			assert_eq!(Pallet::<TestRuntime>::min_header_gap(), max_gap);
+		})
+	}
+
+	fn import_headers_until_requests_weight_is_exhausted(
+		make_justification: impl Fn(&TestHeader) -> GrandpaJustification<TestHeader>,
+	) -> u32 {
+		let mut imported_headers = 0;
+		loop {
+			let header = test_header(<BestFinalized<TestRuntime>>::get().unwrap().0 + 1);
+			let justification = make_justification(&header);
+			match Pallet::<TestRuntime>::submit_finality_proof(
+				Origin::signed(1),
+				Box::new(header),
+				justification,
+			) {
+				Ok(_) => imported_headers += 1,
+				Err(e) => {
+					assert_eq!(e.error, <Error<TestRuntime>>::RequestsWeightExhausted.into());
+					return imported_headers
+				},
+			}
+		}
+	}
+
+	#[test]
+	fn requests_weight_allows_more_imports_with_small_justifications() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			assert_ok!(Pallet::<TestRuntime>::set_max_requests(Origin::root(), 10));
+			crate::mock::MaxJustificationSize::set(&u32::MAX);
+
+			let make_small_justification = |header: &TestHeader| make_default_justification(header);
+			let make_huge_justification = |header: &TestHeader| {
+				let mut justification =
+					make_justification_for_header(JustificationGeneratorParams {
+						header: header.clone(),
+						ancestors: 10,
+						..Default::default()
+					});
+				// duplicate precommits are ignored by the verification, but are paid for
+				let duplicate_precommit = justification.commit.precommits[0].clone();
+				justification.commit.precommits.push(duplicate_precommit.clone());
+				justification.commit.precommits.push(duplicate_precommit);
+				justification
+			};
+			let small_weight = submit_finality_proof_weight::<TestRuntime, ()>(
+				&make_small_justification(&test_header(1)),
+			);
+			let huge_weight = submit_finality_proof_weight::<TestRuntime, ()>(
+				&make_huge_justification(&test_header(1)),
+			);
+			crate::mock::MaxRequestsWeight::set(&(3 * small_weight));
+
+			next_block();
+			assert_eq!(
+				import_headers_until_requests_weight_is_exhausted(make_small_justification),
+				3
+			);
+			assert_eq!(Pallet::<TestRuntime>::remaining_requests_weight(), 0);
+
+			next_block();
+			assert_eq!(Pallet::<TestRuntime>::remaining_requests_weight(), 3 * small_weight);
+			assert_eq!(
+				import_headers_until_requests_weight_is_exhausted(make_huge_justification),
+				2
+			);
+			assert_eq!(
+				Pallet::<TestRuntime>::remaining_requests_weight(),
+				3 * small_weight - 2 * huge_weight,
+			);
+		})
+	}
+
+	#[test]
+	fn requests_weight_allows_single_import_that_exceeds_it() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			assert_ok!(Pallet::<TestRuntime>::set_max_requests(Origin::root(), 10));
+			crate::mock::MaxRequestsWeight::set(&1);
+
+			next_block();
+			assert_eq!(
+				import_headers_until_requests_weight_is_exhausted(make_default_justification),
+				1
+			);
+			next_block();
+			assert_eq!(
+				import_headers_until_requests_weight_is_exhausted(make_default_justification),
+				1
+			);
+		})
 	}
 
 	generate_owned_bridge_module_tests!(BasicOperatingMode::Normal, BasicOperatingMode::Halted);
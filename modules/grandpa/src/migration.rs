@@ -0,0 +1,345 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Storage migrations of the GRANDPA pallet.
+
+use crate::{
+	Config, HeadersToKeep, ImportedHashes, ImportedHashesPointer, MaxRequests, Pallet, LOG_TARGET,
+};
+
+#[cfg(feature = "try-runtime")]
+use crate::BridgedBlockHash;
+#[cfg(feature = "try-runtime")]
+use frame_support::{ensure, traits::OnRuntimeUpgradeHelpersExt};
+use frame_support::{
+	traits::{Get, GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+	weights::Weight,
+};
+use sp_std::marker::PhantomData;
+#[cfg(feature = "try-runtime")]
+use sp_std::vec::Vec;
+
+/// Migration from the storage version 0 to the storage version 1.
+///
+/// The version 1 has moved `MaxRequests` and `HeadersToKeep` from the runtime configuration
+/// to the pallet storage. It also changes the ring buffer of imported hashes, so that it is
+/// indexed by the (wrapping) insertion counter instead of the `index % HeadersToKeep`.
+/// Otherwise the buffer can't be resized without rebuilding it.
+pub mod v1 {
+	use super::*;
+
+	/// Storage migration from version 0 to version 1.
+	///
+	/// Weight of this migration is proportional to the number of imported hashes that are
+	/// stored before the ring buffer pointer.
+	pub struct MigrateToV1<T, I = ()>(PhantomData<(T, I)>);
+
+	/// Key of the temporary storage, used to pass imported hashes from `pre_upgrade` to
+	/// `post_upgrade`.
+	#[cfg(feature = "try-runtime")]
+	const IMPORTED_HASHES_TEMP_KEY: &str = "BridgeGrandpaMigrateToV1ImportedHashes";
+
+	impl<T: Config<I>, I: 'static> OnRuntimeUpgrade for MigrateToV1<T, I> {
+		fn on_runtime_upgrade() -> Weight {
+			let on_chain_version = Pallet::<T, I>::on_chain_storage_version();
+			if on_chain_version >= 1 {
+				log::info!(
+					target: LOG_TARGET,
+					"Skipping migration to v1: on-chain storage version is {:?}",
+					on_chain_version,
+				);
+				return T::DbWeight::get().reads(1)
+			}
+
+			let max_requests = T::MaxRequests::get();
+			let headers_to_keep = T::HeadersToKeep::get();
+			MaxRequests::<T, I>::put(max_requests);
+			HeadersToKeep::<T, I>::put(headers_to_keep);
+
+			// previously the buffer has been indexed by `index % HeadersToKeep`. If the slot at the
+			// pointer is occupied, the buffer has wrapped and hashes at `0..pointer` are newer than
+			// hashes at `pointer..HeadersToKeep`. So we move newer hashes after the older ones.
+			let pointer = ImportedHashesPointer::<T, I>::get();
+			let mut moved_hashes: u64 = 0;
+			if ImportedHashes::<T, I>::contains_key(pointer) {
+				for index in 0..pointer {
+					if let Some(hash) = ImportedHashes::<T, I>::take(index) {
+						ImportedHashes::<T, I>::insert(headers_to_keep.wrapping_add(index), hash);
+						moved_hashes += 1;
+					}
+				}
+				ImportedHashesPointer::<T, I>::put(headers_to_keep.wrapping_add(pointer));
+			}
+
+			StorageVersion::new(1).put::<Pallet<T, I>>();
+
+			log::info!(
+				target: LOG_TARGET,
+				"Migrated to v1: max_requests={}, headers_to_keep={}, moved {} imported hashes",
+				max_requests,
+				headers_to_keep,
+				moved_hashes,
+			);
+
+			T::DbWeight::get().reads_writes(3, 5).saturating_add(
+				T::DbWeight::get().reads_writes(1, 2).saturating_mul(pointer.into()),
+			)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			if Pallet::<T, I>::on_chain_storage_version() >= 1 {
+				// the migration is skipped, so there's nothing to check
+				Self::set_temp_storage(
+					Vec::<BridgedBlockHash<T, I>>::new(),
+					IMPORTED_HASHES_TEMP_KEY,
+				);
+				return Ok(())
+			}
+
+			// remember imported hashes in the insertion order, so that we may check that the
+			// order is preserved by the migration
+			let pointer = ImportedHashesPointer::<T, I>::get();
+			let headers_to_keep = T::HeadersToKeep::get();
+			ensure!(pointer < headers_to_keep, "Legacy ring buffer pointer is out of bounds");
+			let slots: Vec<u32> = if ImportedHashes::<T, I>::contains_key(pointer) {
+				(pointer..headers_to_keep).chain(0..pointer).collect()
+			} else {
+				(0..pointer).collect()
+			};
+			let imported_hashes: Vec<BridgedBlockHash<T, I>> =
+				slots.into_iter().filter_map(ImportedHashes::<T, I>::get).collect();
+			Self::set_temp_storage(imported_hashes, IMPORTED_HASHES_TEMP_KEY);
+
+			Ok(())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			ensure!(
+				Pallet::<T, I>::on_chain_storage_version() == 1,
+				"Storage version has not been updated to v1",
+			);
+			ensure!(MaxRequests::<T, I>::exists(), "MaxRequests has not been initialized");
+			ensure!(HeadersToKeep::<T, I>::exists(), "HeadersToKeep has not been initialized");
+
+			let imported_hashes: Vec<BridgedBlockHash<T, I>> =
+				Self::get_temp_storage(IMPORTED_HASHES_TEMP_KEY)
+					.ok_or("Imported hashes have not been saved by the pre_upgrade")?;
+
+			// hashes must be stored right before the pointer, in the insertion order
+			let pointer = ImportedHashesPointer::<T, I>::get();
+			let first_index = pointer.wrapping_sub(imported_hashes.len() as u32);
+			for (offset, hash) in imported_hashes.into_iter().enumerate() {
+				ensure!(
+					ImportedHashes::<T, I>::get(first_index.wrapping_add(offset as u32)) ==
+						Some(hash),
+					"Imported hashes have been reordered by the migration",
+				);
+			}
+
+			Ok(())
+		}
+	}
+
+	#[cfg(test)]
+	mod tests {
+		use super::*;
+		use crate::{
+			mock::{run_test, test_header, TestRuntime},
+			ImportedHeaders,
+		};
+		use bp_runtime::HashOf;
+		use sp_runtime::traits::Header;
+
+		type TestHash = HashOf<<TestRuntime as Config>::BridgedChain>;
+
+		fn insert_legacy_headers(headers: u64) -> Vec<TestHash> {
+			let headers_to_keep = <TestRuntime as Config>::HeadersToKeep::get();
+			(0..headers)
+				.map(|number| {
+					let header = test_header(number);
+					let hash = header.hash();
+					let index = number as u32 % headers_to_keep;
+					if let Some(pruned) = ImportedHashes::<TestRuntime>::get(index) {
+						ImportedHeaders::<TestRuntime>::remove(pruned);
+					}
+					ImportedHeaders::<TestRuntime>::insert(hash, header);
+					ImportedHashes::<TestRuntime>::insert(index, hash);
+					ImportedHashesPointer::<TestRuntime>::put((index + 1) % headers_to_keep);
+					hash
+				})
+				.collect()
+		}
+
+		fn run_migration() {
+			StorageVersion::new(0).put::<Pallet<TestRuntime>>();
+			MigrateToV1::<TestRuntime>::on_runtime_upgrade();
+			assert_eq!(Pallet::<TestRuntime>::on_chain_storage_version(), 1);
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn run_migration_with_checks() -> Result<(), &'static str> {
+			StorageVersion::new(0).put::<Pallet<TestRuntime>>();
+			MigrateToV1::<TestRuntime>::pre_upgrade()?;
+			MigrateToV1::<TestRuntime>::on_runtime_upgrade();
+			MigrateToV1::<TestRuntime>::post_upgrade()
+		}
+
+		fn import_and_prune(number: u64) {
+			let header = test_header(number);
+			let hash = header.hash();
+			crate::insert_header::<TestRuntime, ()>(header, hash);
+		}
+
+		#[test]
+		fn migration_initializes_storage_values() {
+			run_test(|| {
+				run_migration();
+
+				assert!(MaxRequests::<TestRuntime>::exists());
+				assert!(HeadersToKeep::<TestRuntime>::exists());
+				assert_eq!(
+					Pallet::<TestRuntime>::max_requests(),
+					<TestRuntime as Config>::MaxRequests::get(),
+				);
+				assert_eq!(
+					Pallet::<TestRuntime>::headers_to_keep(),
+					<TestRuntime as Config>::HeadersToKeep::get(),
+				);
+			});
+		}
+
+		#[test]
+		fn migration_keeps_not_wrapped_ring_buffer() {
+			run_test(|| {
+				let hashes = insert_legacy_headers(3);
+				run_migration();
+
+				assert_eq!(ImportedHashesPointer::<TestRuntime>::get(), 3);
+				for (index, hash) in hashes.iter().enumerate() {
+					assert_eq!(ImportedHashes::<TestRuntime>::get(index as u32), Some(*hash));
+				}
+
+				// headers are pruned in the insertion order
+				import_and_prune(3);
+				import_and_prune(4);
+				assert!(hashes
+					.iter()
+					.all(|hash| ImportedHeaders::<TestRuntime>::contains_key(hash)));
+				import_and_prune(5);
+				assert!(!ImportedHeaders::<TestRuntime>::contains_key(hashes[0]));
+			});
+		}
+
+		#[test]
+		fn migration_reorders_wrapped_ring_buffer() {
+			run_test(|| {
+				// with `HeadersToKeep = 5`, headers 3..=7 are kept and headers 5..=7 are stored
+				// at slots `0..3`
+				let hashes = insert_legacy_headers(8);
+				assert_eq!(ImportedHashesPointer::<TestRuntime>::get(), 3);
+				run_migration();
+
+				assert_eq!(ImportedHashesPointer::<TestRuntime>::get(), 8);
+				for number in 3..8 {
+					assert_eq!(
+						ImportedHashes::<TestRuntime>::get(number as u32),
+						Some(hashes[number]),
+					);
+				}
+				assert!(!ImportedHashes::<TestRuntime>::contains_key(0));
+
+				// headers are pruned in the insertion order
+				import_and_prune(8);
+				import_and_prune(9);
+				import_and_prune(10);
+				assert!(hashes[..6]
+					.iter()
+					.all(|hash| !ImportedHeaders::<TestRuntime>::contains_key(hash)));
+				assert!(hashes[6..]
+					.iter()
+					.all(|hash| ImportedHeaders::<TestRuntime>::contains_key(hash)));
+			});
+		}
+
+		#[test]
+		fn migration_is_not_applied_twice() {
+			run_test(|| {
+				insert_legacy_headers(8);
+				run_migration();
+
+				HeadersToKeep::<TestRuntime>::put(10);
+				MigrateToV1::<TestRuntime>::on_runtime_upgrade();
+
+				assert_eq!(Pallet::<TestRuntime>::headers_to_keep(), 10);
+				assert_eq!(ImportedHashesPointer::<TestRuntime>::get(), 8);
+			});
+		}
+
+		#[cfg(feature = "try-runtime")]
+		#[test]
+		fn try_runtime_checks_pass_for_not_wrapped_ring_buffer() {
+			run_test(|| {
+				insert_legacy_headers(3);
+				assert_eq!(run_migration_with_checks(), Ok(()));
+			});
+		}
+
+		#[cfg(feature = "try-runtime")]
+		#[test]
+		fn try_runtime_checks_pass_for_wrapped_ring_buffer() {
+			run_test(|| {
+				insert_legacy_headers(8);
+				assert_eq!(run_migration_with_checks(), Ok(()));
+			});
+		}
+
+		#[cfg(feature = "try-runtime")]
+		#[test]
+		fn try_runtime_checks_pass_when_migration_is_skipped() {
+			run_test(|| {
+				insert_legacy_headers(8);
+				assert_eq!(run_migration_with_checks(), Ok(()));
+
+				assert_eq!(MigrateToV1::<TestRuntime>::pre_upgrade(), Ok(()));
+				MigrateToV1::<TestRuntime>::on_runtime_upgrade();
+				assert_eq!(MigrateToV1::<TestRuntime>::post_upgrade(), Ok(()));
+			});
+		}
+
+		#[cfg(feature = "try-runtime")]
+		#[test]
+		fn try_runtime_post_upgrade_detects_reordered_hashes() {
+			run_test(|| {
+				insert_legacy_headers(8);
+				StorageVersion::new(0).put::<Pallet<TestRuntime>>();
+				assert_eq!(MigrateToV1::<TestRuntime>::pre_upgrade(), Ok(()));
+				MigrateToV1::<TestRuntime>::on_runtime_upgrade();
+
+				let oldest = ImportedHashes::<TestRuntime>::get(3).unwrap();
+				let newest = ImportedHashes::<TestRuntime>::get(7).unwrap();
+				ImportedHashes::<TestRuntime>::insert(3, newest);
+				ImportedHashes::<TestRuntime>::insert(7, oldest);
+
+				assert_eq!(
+					MigrateToV1::<TestRuntime>::post_upgrade(),
+					Err("Imported hashes have been reordered by the migration"),
+				);
+			});
+		}
+	}
+}
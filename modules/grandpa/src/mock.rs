@@ -42,7 +42,7 @@ construct_runtime! {
 		UncheckedExtrinsic = UncheckedExtrinsic,
 	{
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
-		Grandpa: grandpa::{Pallet, Call},
+		Grandpa: grandpa::{Pallet, Call, Event<T>},
 	}
 }
 
@@ -63,7 +63,7 @@ impl frame_system::Config for TestRuntime {
 	type AccountId = AccountId;
 	type Lookup = IdentityLookup<Self::AccountId>;
 	type Header = Header;
-	type Event = ();
+	type Event = Event;
 	type BlockHashCount = BlockHashCount;
 	type Version = ();
 	type PalletInfo = PalletInfo;
@@ -82,15 +82,28 @@ impl frame_system::Config for TestRuntime {
 
 parameter_types! {
 	pub const MaxRequests: u32 = 2;
+	pub storage MaxRequestsWeight: Weight = Weight::MAX;
 	pub const HeadersToKeep: u32 = 5;
+	pub storage JustificationsToKeep: u32 = 3;
 	pub const SessionLength: u64 = 5;
 	pub const NumValidators: u32 = 5;
+	pub storage MaxJustificationSize: u32 = 4096;
+	pub storage MaxBridgedAuthorities: u32 = 5;
+	pub storage MaxBridgedHeaderSize: u32 = 1024;
+	pub const MaxFinalityProofsInBatch: u32 = 4;
 }
 
 impl grandpa::Config for TestRuntime {
+	type Event = Event;
 	type BridgedChain = TestBridgedChain;
 	type MaxRequests = MaxRequests;
+	type MaxRequestsWeight = MaxRequestsWeight;
 	type HeadersToKeep = HeadersToKeep;
+	type JustificationsToKeep = JustificationsToKeep;
+	type MaxJustificationSize = MaxJustificationSize;
+	type MaxBridgedAuthorities = MaxBridgedAuthorities;
+	type MaxBridgedHeaderSize = MaxBridgedHeaderSize;
+	type MaxFinalityProofsInBatch = MaxFinalityProofsInBatch;
 	type WeightInfo = ();
 }
 
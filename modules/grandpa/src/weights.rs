@@ -51,6 +51,7 @@ use sp_std::marker::PhantomData;
 /// Weight functions needed for `pallet_bridge_grandpa`.
 pub trait WeightInfo {
 	fn submit_finality_proof(p: u32, v: u32) -> Weight;
+	fn submit_finality_proof_batch(n: u32) -> Weight;
 }
 
 /// Weights for `pallet_bridge_grandpa` that are generated using one of the Bridge testnets.
@@ -62,8 +63,14 @@ impl<T: frame_system::Config> WeightInfo for BridgeWeight<T> {
 		(105_417_000 as Weight)
 			.saturating_add((40_923_000 as Weight).saturating_mul(p as Weight))
 			.saturating_add((1_691_000 as Weight).saturating_mul(v as Weight))
-			.saturating_add(T::DbWeight::get().reads(7 as Weight))
-			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+			.saturating_add(T::DbWeight::get().reads(9 as Weight))
+			.saturating_add(T::DbWeight::get().writes(7 as Weight))
+	}
+	fn submit_finality_proof_batch(n: u32) -> Weight {
+		(3_291_000 as Weight)
+			.saturating_add((1_527_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
 	}
 }
 
@@ -73,7 +80,13 @@ impl WeightInfo for () {
 		(105_417_000 as Weight)
 			.saturating_add((40_923_000 as Weight).saturating_mul(p as Weight))
 			.saturating_add((1_691_000 as Weight).saturating_mul(v as Weight))
-			.saturating_add(RocksDbWeight::get().reads(7 as Weight))
-			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+			.saturating_add(RocksDbWeight::get().reads(9 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(7 as Weight))
+	}
+	fn submit_finality_proof_batch(n: u32) -> Weight {
+		(3_291_000 as Weight)
+			.saturating_add((1_527_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
 	}
 }
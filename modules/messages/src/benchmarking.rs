@@ -19,7 +19,7 @@
 use crate::{
 	inbound_lane::InboundLaneStorage, inbound_lane_storage, outbound_lane,
 	outbound_lane::ReceivalConfirmationResult, weights_ext::EXPECTED_DEFAULT_MESSAGE_LENGTH, Call,
-	OutboundLanes, OutboundMessages,
+	OpenedLanes, OutboundLanes, OutboundMessages,
 };
 
 use bp_messages::{
@@ -128,6 +128,7 @@ benchmarks_instance_pallet! {
 		let lane_id = T::bench_lane_id();
 		let sender = account("sender", 0, SEED);
 		T::endow_account(&sender);
+		open_bench_lane::<T, I>();
 
 		// 'send' messages that are to be pruned when our message is sent
 		for _nonce in 1..=T::MaxMessagesToPruneAtOnce::get() {
@@ -159,6 +160,7 @@ benchmarks_instance_pallet! {
 		let lane_id = T::bench_lane_id();
 		let sender = account("sender", 0, SEED);
 		T::endow_account(&sender);
+		open_bench_lane::<T, I>();
 
 		// 'send' messages that are to be pruned when our message is sent
 		for _nonce in 1..=T::MaxMessagesToPruneAtOnce::get() {
@@ -196,6 +198,7 @@ benchmarks_instance_pallet! {
 		let lane_id = T::bench_lane_id();
 		let sender = account("sender", 0, SEED);
 		T::endow_account(&sender);
+		open_bench_lane::<T, I>();
 
 		// 'send' messages that are to be pruned when our message is sent
 		for _nonce in 1..=T::MaxMessagesToPruneAtOnce::get() {
@@ -277,6 +280,7 @@ benchmarks_instance_pallet! {
 		let relayer_id_on_source = T::bridged_relayer_id();
 		let relayer_id_on_target = account("relayer", 0, SEED);
 		T::endow_account(&relayer_id_on_target);
+		open_bench_lane::<T, I>();
 
 		// mark messages 1..=20 as delivered
 		receive_messages::<T, I>(20);
@@ -312,6 +316,7 @@ benchmarks_instance_pallet! {
 		let relayer_id_on_source = T::bridged_relayer_id();
 		let relayer_id_on_target = account("relayer", 0, SEED);
 		T::endow_account(&relayer_id_on_target);
+		open_bench_lane::<T, I>();
 
 		// mark messages 1..=20 as delivered
 		receive_messages::<T, I>(20);
@@ -347,6 +352,7 @@ benchmarks_instance_pallet! {
 		let relayer_id_on_source = T::bridged_relayer_id();
 		let relayer_id_on_target = account("relayer", 0, SEED);
 		T::endow_account(&relayer_id_on_target);
+		open_bench_lane::<T, I>();
 
 		// mark messages 1..=20 as delivered
 		receive_messages::<T, I>(20);
@@ -383,6 +389,7 @@ benchmarks_instance_pallet! {
 		let relayer_id_on_source = T::bridged_relayer_id();
 		let relayer_id_on_target = account("relayer", 0, SEED);
 		T::endow_account(&relayer_id_on_target);
+		open_bench_lane::<T, I>();
 
 		// mark messages 1..=20 as delivered
 		receive_messages::<T, I>(20);
@@ -418,6 +425,7 @@ benchmarks_instance_pallet! {
 		let relayer_id_on_source = T::bridged_relayer_id();
 		let relayer_id_on_target = account("relayer", 0, SEED);
 		T::endow_account(&relayer_id_on_target);
+		open_bench_lane::<T, I>();
 
 		// mark messages 1..=20 as delivered
 		receive_messages::<T, I>(20);
@@ -452,6 +460,7 @@ benchmarks_instance_pallet! {
 		let relayer_id_on_source = T::bridged_relayer_id();
 		let relayer_id_on_target = account("relayer", 0, SEED);
 		T::endow_account(&relayer_id_on_target);
+		open_bench_lane::<T, I>();
 
 		// mark messages 1..=20 as delivered
 		receive_messages::<T, I>(20);
@@ -528,7 +537,7 @@ benchmarks_instance_pallet! {
 			last_delivered_nonce: 2,
 		};
 		let mut delivered_messages = DeliveredMessages::new(1, true);
-		delivered_messages.note_dispatched_message(true);
+		assert!(delivered_messages.try_note_dispatched_message(true));
 		let proof = T::prepare_message_delivery_proof(MessageDeliveryProofParams {
 			lane: T::bench_lane_id(),
 			inbound_lane_data: InboundLaneData {
@@ -591,6 +600,10 @@ benchmarks_instance_pallet! {
 	}
 }
 
+fn open_bench_lane<T: Config<I>, I: 'static>() {
+	OpenedLanes::<T, I>::insert(T::bench_lane_id(), ());
+}
+
 fn send_regular_message<T: Config<I>, I: 'static>() {
 	let mut outbound_lane = outbound_lane::<T, I>(T::bench_lane_id());
 	outbound_lane.send_message(MessageData { payload: vec![], fee: T::message_fee() });
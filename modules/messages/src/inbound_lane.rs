@@ -124,6 +124,11 @@ pub enum ReceivalResult {
 	TooManyUnrewardedRelayers,
 	/// There are too many unconfirmed messages at the lane.
 	TooManyUnconfirmedMessages,
+	/// Message payload is larger than the maximal inbound payload size. The message has been
+	/// received (so the lane is not blocked), but it has not been dispatched.
+	PayloadTooLarge,
+	/// The lane has run out of message nonces. The whole delivery transaction must be rejected.
+	NonceOverflow,
 }
 
 /// Inbound messages lane.
@@ -182,6 +187,16 @@ impl<S: InboundLaneStorage> InboundLane<S> {
 		Some(outbound_lane_data.latest_received_nonce)
 	}
 
+	/// Returns true if the lane is able to accept messages from new relayer.
+	///
+	/// If there's no room for new unrewarded relayer entry, every new message is rejected with
+	/// the `ReceivalResult::TooManyUnrewardedRelayers` until some entries are pruned by the
+	/// `receive_state_update`.
+	pub fn has_room_for_unrewarded_relayer_entry(&self) -> bool {
+		(self.storage.data().relayers.len() as MessageNonce) <
+			self.storage.max_unrewarded_relayer_entries()
+	}
+
 	/// Receive new message.
 	pub fn receive_message<P: MessageDispatch<AccountId, S::MessageFee>, AccountId>(
 		&mut self,
@@ -189,9 +204,42 @@ impl<S: InboundLaneStorage> InboundLane<S> {
 		relayer_at_this_chain: &AccountId,
 		nonce: MessageNonce,
 		message_data: DispatchMessageData<P::DispatchPayload, S::MessageFee>,
+	) -> ReceivalResult {
+		self.receive_message_with(relayer_at_bridged_chain, nonce, |key| {
+			ReceivalResult::Dispatched(P::dispatch(
+				relayer_at_this_chain,
+				DispatchMessage { key, data: message_data },
+			))
+		})
+	}
+
+	/// Receive new message with the payload that exceeds maximal inbound payload size.
+	///
+	/// The message is not dispatched, but it is still marked as delivered (with failed dispatch
+	/// result), so that the lane is able to make progress.
+	pub fn receive_oversized_message(
+		&mut self,
+		relayer_at_bridged_chain: &S::Relayer,
+		nonce: MessageNonce,
+	) -> ReceivalResult {
+		self.receive_message_with(relayer_at_bridged_chain, nonce, |_| {
+			ReceivalResult::PayloadTooLarge
+		})
+	}
+
+	/// Receive new message, using given closure to process it.
+	fn receive_message_with(
+		&mut self,
+		relayer_at_bridged_chain: &S::Relayer,
+		nonce: MessageNonce,
+		process: impl FnOnce(MessageKey) -> ReceivalResult,
 	) -> ReceivalResult {
 		let mut data = self.storage.data();
-		let is_correct_message = nonce == data.last_delivered_nonce() + 1;
+		let last_delivered_nonce = data.last_delivered_nonce();
+		let is_correct_message = match last_delivered_nonce.checked_add(1) {
+			Some(expected_nonce) => nonce == expected_nonce,
+			None => return ReceivalResult::NonceOverflow,
+		};
 		if !is_correct_message {
 			return ReceivalResult::InvalidNonce
 		}
@@ -207,32 +255,31 @@ impl<S: InboundLaneStorage> InboundLane<S> {
 			return ReceivalResult::TooManyUnconfirmedMessages
 		}
 
-		// then, dispatch message
-		let dispatch_result = P::dispatch(
-			relayer_at_this_chain,
-			DispatchMessage {
-				key: MessageKey { lane_id: self.storage.id(), nonce },
-				data: message_data,
-			},
-		);
+		// then, process (dispatch) message
+		let receival_result = process(MessageKey { lane_id: self.storage.id(), nonce });
+		let dispatch_result = match receival_result {
+			ReceivalResult::Dispatched(ref dispatch_result) => dispatch_result.dispatch_result,
+			_ => false,
+		};
 
-		// now let's update inbound lane storage
-		let push_new = match data.relayers.back_mut() {
+		// now let's update inbound lane storage. Consecutive deliveries by the same relayer are
+		// collapsed into the single entry to keep delivery confirmation proofs small
+		match data.relayers.back_mut() {
 			Some(entry) if entry.relayer == *relayer_at_bridged_chain => {
-				entry.messages.note_dispatched_message(dispatch_result.dispatch_result);
-				false
+				// the entry ends with the `last_delivered_nonce`, so it can't overflow here. But
+				// if it does, the caller rejects the whole transaction, reverting the dispatch
+				if !entry.messages.try_note_dispatched_message(dispatch_result) {
+					return ReceivalResult::NonceOverflow
+				}
 			},
-			_ => true,
-		};
-		if push_new {
-			data.relayers.push_back(UnrewardedRelayer {
+			_ => data.relayers.push_back(UnrewardedRelayer {
 				relayer: (*relayer_at_bridged_chain).clone(),
-				messages: DeliveredMessages::new(nonce, dispatch_result.dispatch_result),
-			});
+				messages: DeliveredMessages::new(nonce, dispatch_result),
+			}),
 		}
 		self.storage.set_data(data);
 
-		ReceivalResult::Dispatched(dispatch_result)
+		receival_result
 	}
 }
 
@@ -242,12 +289,15 @@ mod tests {
 	use crate::{
 		inbound_lane,
 		mock::{
-			dispatch_result, message_data, run_test, unrewarded_relayer, TestMessageDispatch,
-			TestRuntime, REGULAR_PAYLOAD, TEST_LANE_ID, TEST_RELAYER_A, TEST_RELAYER_B,
-			TEST_RELAYER_C,
+			dispatch_result, message_data, run_test, unrewarded_relayer,
+			MaxUnconfirmedMessagesAtInboundLane, MaxUnrewardedRelayerEntriesAtInboundLane,
+			TestMessageDispatch, TestRelayer, TestRuntime, REGULAR_PAYLOAD, TEST_LANE_ID,
+			TEST_RELAYER_A, TEST_RELAYER_B, TEST_RELAYER_C,
 		},
-		RuntimeInboundLaneStorage,
+		InboundLanes, Pallet, RuntimeInboundLaneStorage,
 	};
+	use frame_support::traits::StorageInfoTrait;
+	use sp_std::collections::vec_deque::VecDeque;
 
 	fn receive_regular_message(
 		lane: &mut InboundLane<RuntimeInboundLaneStorage<TestRuntime, ()>>,
@@ -515,6 +565,31 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn consecutive_deliveries_by_the_same_relayer_are_collapsed_into_single_entry() {
+		run_test(|| {
+			let mut lane = inbound_lane::<TestRuntime, _>(TEST_LANE_ID);
+			receive_regular_message(&mut lane, 1);
+			receive_regular_message(&mut lane, 2);
+			assert_eq!(
+				lane.receive_oversized_message(&TEST_RELAYER_A, 3),
+				ReceivalResult::PayloadTooLarge,
+			);
+			receive_regular_message(&mut lane, 4);
+
+			let relayers = lane.storage.data().relayers;
+			assert_eq!(relayers.len(), 1);
+			assert_eq!(relayers[0].relayer, TEST_RELAYER_A);
+			assert_eq!((relayers[0].messages.begin, relayers[0].messages.end), (1, 4));
+			assert_eq!(
+				(1..=4)
+					.map(|nonce| relayers[0].messages.get_dispatch_result(nonce))
+					.collect::<Vec<_>>(),
+				vec![Some(true), Some(true), Some(false), Some(true)],
+			);
+		});
+	}
+
 	#[test]
 	fn rejects_same_message_from_two_different_relayers() {
 		run_test(|| {
@@ -540,6 +615,62 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn receives_message_with_maximal_nonce() {
+		run_test(|| {
+			let mut lane = inbound_lane::<TestRuntime, _>(TEST_LANE_ID);
+			lane.storage.set_data(InboundLaneData {
+				relayers: vec![unrewarded_relayer(
+					MessageNonce::MAX - 1,
+					MessageNonce::MAX - 1,
+					TEST_RELAYER_A,
+				)]
+				.into_iter()
+				.collect(),
+				last_confirmed_nonce: MessageNonce::MAX - 2,
+			});
+			receive_regular_message(&mut lane, MessageNonce::MAX);
+
+			let data = lane.storage.data();
+			assert_eq!(data.last_delivered_nonce(), MessageNonce::MAX);
+			assert_eq!(data.relayers.len(), 1);
+			assert_eq!(
+				(data.relayers[0].messages.begin, data.relayers[0].messages.end),
+				(MessageNonce::MAX - 1, MessageNonce::MAX),
+			);
+		});
+	}
+
+	#[test]
+	fn rejects_message_if_nonce_overflows() {
+		run_test(|| {
+			let mut lane = inbound_lane::<TestRuntime, _>(TEST_LANE_ID);
+			let lane_data = InboundLaneData {
+				relayers: vec![unrewarded_relayer(
+					MessageNonce::MAX,
+					MessageNonce::MAX,
+					TEST_RELAYER_A,
+				)]
+				.into_iter()
+				.collect(),
+				last_confirmed_nonce: MessageNonce::MAX - 1,
+			};
+			lane.storage.set_data(lane_data.clone());
+			for nonce in [0, 1, MessageNonce::MAX] {
+				assert_eq!(
+					lane.receive_message::<TestMessageDispatch, _>(
+						&TEST_RELAYER_A,
+						&TEST_RELAYER_A,
+						nonce,
+						message_data(REGULAR_PAYLOAD).into()
+					),
+					ReceivalResult::NonceOverflow,
+				);
+			}
+			assert_eq!(lane.storage.data(), lane_data);
+		});
+	}
+
 	#[test]
 	fn correct_message_is_processed_instantly() {
 		run_test(|| {
@@ -566,4 +697,99 @@ mod tests {
 			);
 		});
 	}
+
+	#[test]
+	fn oversized_message_is_received_but_not_dispatched() {
+		run_test(|| {
+			let mut lane = inbound_lane::<TestRuntime, _>(TEST_LANE_ID);
+			receive_regular_message(&mut lane, 1);
+			assert_eq!(
+				lane.receive_oversized_message(&TEST_RELAYER_A, 2),
+				ReceivalResult::PayloadTooLarge,
+			);
+			receive_regular_message(&mut lane, 3);
+
+			let data = lane.storage.data();
+			assert_eq!(data.last_delivered_nonce(), 3);
+			assert_eq!(data.relayers.len(), 1);
+			assert_eq!(data.relayers[0].messages.get_dispatch_result(1), Some(true));
+			assert_eq!(data.relayers[0].messages.get_dispatch_result(2), Some(false));
+			assert_eq!(data.relayers[0].messages.get_dispatch_result(3), Some(true));
+		});
+	}
+
+	#[test]
+	fn oversized_message_with_incorrect_nonce_is_rejected() {
+		run_test(|| {
+			let mut lane = inbound_lane::<TestRuntime, _>(TEST_LANE_ID);
+			assert_eq!(
+				lane.receive_oversized_message(&TEST_RELAYER_A, 10),
+				ReceivalResult::InvalidNonce,
+			);
+			assert_eq!(lane.storage.data().last_delivered_nonce(), 0);
+		});
+	}
+
+	#[test]
+	fn stored_inbound_lane_data_max_encoded_len_is_not_exceeded() {
+		let max_entries = MaxUnrewardedRelayerEntriesAtInboundLane::get();
+		let max_messages = MaxUnconfirmedMessagesAtInboundLane::get();
+		// worst case is when every entry, except the last one, has a single partially filled
+		// byte of dispatch results, and the last entry has all remaining messages
+		let mut relayers = (1..max_entries)
+			.map(|nonce| unrewarded_relayer(nonce, nonce, TestRelayer::MAX))
+			.collect::<VecDeque<_>>();
+		relayers.push_back(unrewarded_relayer(max_entries, max_messages, TestRelayer::MAX));
+		let data = InboundLaneData { relayers, last_confirmed_nonce: MessageNonce::MAX };
+
+		let max_encoded_len = StoredInboundLaneData::<TestRuntime, ()>::max_encoded_len();
+		assert!(
+			data.encode().len() <= max_encoded_len,
+			"Encoded inbound lane data size ({}) exceeds max_encoded_len ({})",
+			data.encode().len(),
+			max_encoded_len,
+		);
+		assert_eq!(
+			Some(max_encoded_len),
+			InboundLaneData::<TestRelayer>::encoded_size_hint(
+				max_entries as usize,
+				max_messages as usize
+			),
+		);
+	}
+
+	#[test]
+	fn inbound_lanes_storage_is_bounded() {
+		let inbound_lanes_info = Pallet::<TestRuntime>::storage_info()
+			.into_iter()
+			.find(|info| info.storage_name == b"InboundLanes".to_vec())
+			.expect("InboundLanes is declared in the pallet");
+		// Blake2_128Concat hash + lane id + inbound lane data
+		let expected_max_size = 16 +
+			LaneId::max_encoded_len() +
+			StoredInboundLaneData::<TestRuntime, ()>::max_encoded_len();
+		assert_eq!(inbound_lanes_info.max_size, Some(expected_max_size as u32));
+	}
+
+	#[test]
+	fn stored_inbound_lane_data_decodes_legacy_encoded_value() {
+		run_test(|| {
+			let data = InboundLaneData {
+				relayers: vec![
+					unrewarded_relayer(1, 1, TEST_RELAYER_A),
+					unrewarded_relayer(2, 3, TEST_RELAYER_B),
+				]
+				.into_iter()
+				.collect(),
+				last_confirmed_nonce: 0,
+			};
+			frame_support::storage::unhashed::put_raw(
+				&InboundLanes::<TestRuntime, ()>::hashed_key_for(TEST_LANE_ID),
+				&data.encode(),
+			);
+
+			let lane = inbound_lane::<TestRuntime, _>(TEST_LANE_ID);
+			assert_eq!(lane.storage.data(), data);
+		});
+	}
 }
@@ -52,25 +52,33 @@ use crate::{
 
 use bp_messages::{
 	source_chain::{
-		LaneMessageVerifier, MessageDeliveryAndDispatchPayment, OnDeliveryConfirmed,
-		OnMessageAccepted, RelayersRewards, SendMessageArtifacts, TargetHeaderChain,
+		LaneMessageVerifier, MessageDeliveryAndDispatchPayment, MessageSubmitter,
+		OnDeliveryConfirmed, OnMessageAccepted, RelayersRewards, SendMessageArtifacts,
+		TargetHeaderChain,
 	},
 	target_chain::{
 		DispatchMessage, MessageDispatch, ProvedLaneMessages, ProvedMessages, SourceHeaderChain,
 	},
-	total_unrewarded_messages, DeliveredMessages, InboundLaneData, InboundMessageDetails, LaneId,
-	MessageData, MessageKey, MessageNonce, MessagePayload, MessagesOperatingMode, OutboundLaneData,
-	OutboundMessageDetails, Parameter as MessagesParameter, UnrewardedRelayer,
-	UnrewardedRelayersState,
+	DeliveredMessages, InboundLaneData, InboundLaneState, InboundMessageDetails, LaneId, Message,
+	MessageData, MessageEventKind, MessageKey, MessageNonce, MessagePayload,
+	MessageRejectionReason, MessagesOperatingMode, OutboundLaneData, OutboundMessageDetails,
+	Parameter as MessagesParameter, UnrewardedRelayer, UnrewardedRelayersState, VerificationError,
+};
+use bp_runtime::{
+	messages::MessageDispatchError, BasicOperatingMode, ChainId, OperatingMode, OwnedBridgeModule,
+	Size,
 };
-use bp_runtime::{BasicOperatingMode, ChainId, OwnedBridgeModule, Size};
 use codec::{Decode, Encode, MaxEncodedLen};
 use frame_support::{
 	ensure, fail,
-	traits::Get,
+	storage::StoragePrefixedMap,
+	traits::{Get, PalletInfoAccess},
 	weights::{Pays, PostDispatchInfo},
+	RuntimeDebug,
 };
-use num_traits::{SaturatingAdd, Zero};
+use num_traits::{CheckedAdd, SaturatingAdd, Zero};
+use scale_info::TypeInfo;
+use sp_runtime::traits::{AtLeast32BitUnsigned, Convert};
 use sp_std::{
 	cell::RefCell, cmp::PartialOrd, collections::vec_deque::VecDeque, marker::PhantomData,
 	ops::RangeInclusive, prelude::*,
@@ -80,6 +88,9 @@ mod inbound_lane;
 mod outbound_lane;
 mod weights_ext;
 
+/// Module, containing storage migrations of this pallet.
+pub mod migration;
+
 pub mod weights;
 
 #[cfg(feature = "runtime-benchmarks")]
@@ -93,6 +104,29 @@ pub use pallet::*;
 /// The target that will be used when publishing logs related to this pallet.
 pub const LOG_TARGET: &str = "runtime::bridge-messages";
 
+/// Maximal number of `OutboundMessagesPerAccount` entries that are pruned in single block.
+pub const MAX_RATE_LIMIT_ENTRIES_TO_PRUNE_AT_ONCE: usize = 64;
+
+/// Per-account rate limit of outbound messages.
+#[derive(Clone, Copy, Decode, Encode, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct OutboundRateLimit<BlockNumber> {
+	/// Maximal number of messages that a single account may send within the window.
+	pub max_messages: u32,
+	/// Length of the window in blocks.
+	pub window: BlockNumber,
+}
+
+impl<BlockNumber: AtLeast32BitUnsigned + Copy> OutboundRateLimit<BlockNumber> {
+	/// Return number of the first block of the window that the given block belongs to.
+	pub fn window_start(&self, block_number: BlockNumber) -> BlockNumber {
+		if self.window.is_zero() {
+			return block_number
+		}
+
+		block_number - block_number % self.window
+	}
+}
+
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
@@ -121,6 +155,13 @@ pub mod pallet {
 		/// whenever new message is sent. The reason is that if you want to use lane, you should
 		/// be ready to pay for its maintenance.
 		type MaxMessagesToPruneAtOnce: Get<MessageNonce>;
+		/// Maximal number of undelivered messages at a single outbound lane. Undelivered here
+		/// means that the delivery of the message hasn't been confirmed yet.
+		///
+		/// New messages are rejected while the lane queue is full. This limits the storage
+		/// that may be occupied by a single lane if its messages are not delivered.
+		#[pallet::constant]
+		type MaxOutboundQueueLen: Get<MessageNonce>;
 		/// Maximal number of unrewarded relayer entries at inbound lane. Unrewarded means that the
 		/// relayer has delivered messages, but either confirmations haven't been delivered back to
 		/// the source chain, or we haven't received reward confirmations yet.
@@ -144,6 +185,49 @@ pub mod pallet {
 		/// Transaction that is declaring more messages than this value, will be rejected. Even if
 		/// these messages are from different lanes.
 		type MaxUnconfirmedMessagesAtInboundLane: Get<MessageNonce>;
+		/// Maximal number of recent confirmed deliveries that are tracked at every outbound lane.
+		///
+		/// Delivery is a single entry of the `InboundLaneData::relayers`, confirmed by the
+		/// `receive_messages_delivery_proof` call. Recent deliveries are used to compute the set of
+		/// relayers that are actively serving the lane (see `Pallet::recent_relayers`).
+		type MaxRecentDeliveries: Get<u32>;
+		/// Number of blocks after which undelivered outbound messages expire. If it is zero,
+		/// outbound messages never expire.
+		///
+		/// The same number of blocks is used to decide whether the outbound lane is stalled: the
+		/// lane is stalled if it has queued messages and has not received any delivery
+		/// confirmations for `MessageExpiryBlocks` blocks.
+		///
+		/// Only messages of stalled lanes expire. Expired messages are replaced with empty
+		/// messages when the block has some spare weight (see `Hooks::on_idle`) and their fees
+		/// are refunded to their submitters. Empty messages still need to be delivered to the
+		/// bridged chain, because lanes may only advance in the nonces order. Delivery proofs,
+		/// claiming that expired (or cancelled) messages have been dispatched, are rejected.
+		#[pallet::constant]
+		type MessageExpiryBlocks: Get<Self::BlockNumber>;
+
+		/// Per-account rate limit of outbound messages. If it is `None`, outbound messages are
+		/// not rate-limited.
+		///
+		/// Changing the window length requires clearing the `OutboundMessagesPerAccount` map,
+		/// because its entries are keyed by the window start.
+		type OutboundMessagesRateLimit: Get<Option<OutboundRateLimit<Self::BlockNumber>>>;
+		/// Returns account that is subject to the outbound messages rate limit.
+		///
+		/// If it returns `None` for some origin (e.g. for root or for trusted XCM origin), messages
+		/// of this origin are never rate-limited. Messages of the pallet owner are also never
+		/// rate-limited.
+		type RateLimitedSender: Convert<Self::Origin, Option<Self::AccountId>>;
+		/// Identifies submitters of outbound messages, that are stored along with messages.
+		///
+		/// Use `()` if submitters of outbound messages don't need to be stored.
+		type MessageSubmitter: MessageSubmitter<Self::Origin>;
+		/// If `true`, accepted, delivered and confirmed messages are recorded in the offchain
+		/// index, so that explorers may query messages history without scanning all blocks.
+		///
+		/// Entries are only persisted by nodes that are started with offchain indexing enabled.
+		/// On all other nodes, writing the index is a no-op.
+		type IndexMessagesHistory: Get<bool>;
 
 		/// Maximal size of the outbound payload.
 		#[pallet::constant]
@@ -156,6 +240,7 @@ pub mod pallet {
 			+ PartialOrd
 			+ Parameter
 			+ SaturatingAdd
+			+ CheckedAdd
 			+ Zero
 			+ Copy
 			+ MaxEncodedLen;
@@ -167,6 +252,13 @@ pub mod pallet {
 		/// Identifier of relayer that deliver messages to this chain. Relayer reward is paid on the
 		/// bridged chain.
 		type InboundRelayer: Parameter + MaxEncodedLen;
+		/// Default maximal size of the inbound message payload.
+		///
+		/// Messages with larger payloads are received (so the lane is not blocked), but they
+		/// are not dispatched. The pallet owner may change this limit, either for all lanes or
+		/// for the single lane, using the `set_inbound_lane_max_payload_size` call.
+		#[pallet::constant]
+		type MaximalInboundPayloadSize: Get<u32>;
 
 		// Types that are used by outbound_lane (on source chain).
 
@@ -193,6 +285,16 @@ pub mod pallet {
 
 		/// Source header chain, as it is represented on target chain.
 		type SourceHeaderChain: SourceHeaderChain<Self::InboundMessageFee>;
+		/// Maximal size (in bytes) of the messages proof that the pallet accepts.
+		///
+		/// Larger proofs are rejected before any verification.
+		#[pallet::constant]
+		type MaxMessagesProofSize: Get<u32>;
+		/// Maximal size (in bytes) of the messages delivery proof that the pallet accepts.
+		///
+		/// Larger proofs are rejected before any verification.
+		#[pallet::constant]
+		type MaxMessagesDeliveryProofSize: Get<u32>;
 		/// Message dispatch.
 		type MessageDispatch: MessageDispatch<
 			Self::AccountId,
@@ -201,6 +303,10 @@ pub mod pallet {
 		>;
 	}
 
+	/// Shortcut to the outbound message submitter type for Config.
+	pub type SubmitterOf<T, I> = <<T as Config<I>>::MessageSubmitter as MessageSubmitter<
+		<T as frame_system::Config>::Origin,
+	>>::Submitter;
 	/// Shortcut to messages proof type for Config.
 	type MessagesProofOf<T, I> = <<T as Config<I>>::SourceHeaderChain as SourceHeaderChain<
 		<T as Config<I>>::InboundMessageFee,
@@ -212,13 +318,29 @@ pub mod pallet {
 			<T as frame_system::Config>::AccountId,
 		>>::MessagesDeliveryProof;
 
+	/// The current storage version.
+	pub(crate) const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T, I = ()>(PhantomData<(T, I)>);
 
+	#[pallet::hooks]
+	impl<T: Config<I>, I: 'static> Hooks<BlockNumberFor<T>> for Pallet<T, I> {
+		fn on_initialize(n: T::BlockNumber) -> Weight {
+			prune_outbound_rate_limit_windows::<T, I>(n)
+		}
+
+		fn on_idle(n: T::BlockNumber, remaining_weight: Weight) -> Weight {
+			expire_outbound_messages::<T, I>(n, remaining_weight)
+		}
+	}
+
 	impl<T: Config<I>, I: 'static> OwnedBridgeModule<T> for Pallet<T, I> {
 		const LOG_TARGET: &'static str = LOG_TARGET;
 		type OwnerStorage = PalletOwner<T, I>;
+		type PendingOwnerStorage = PalletPendingOwner<T, I>;
 		type OperatingMode = MessagesOperatingMode;
 		type OperatingModeStorage = PalletOperatingMode<T, I>;
 	}
@@ -227,21 +349,66 @@ pub mod pallet {
 	impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		/// Change `PalletOwner`.
 		///
-		/// May only be called either by root, or by `PalletOwner`.
+		/// May only be called by root.
 		#[pallet::weight((T::DbWeight::get().reads_writes(1, 1), DispatchClass::Operational))]
 		pub fn set_owner(origin: OriginFor<T>, new_owner: Option<T::AccountId>) -> DispatchResult {
 			<Self as OwnedBridgeModule<_>>::set_owner(origin, new_owner)
 		}
 
+		/// Propose new `PalletOwner`.
+		///
+		/// May only be called either by root, or by `PalletOwner`. The ownership is only
+		/// transferred after the proposed account calls `accept_ownership`.
+		#[pallet::weight((T::DbWeight::get().reads_writes(1, 1), DispatchClass::Operational))]
+		pub fn propose_owner(origin: OriginFor<T>, new_owner: T::AccountId) -> DispatchResult {
+			<Self as OwnedBridgeModule<_>>::propose_owner(origin, new_owner.clone())?;
+			Self::deposit_event(Event::OwnershipTransferProposed { pending_owner: new_owner });
+			Ok(())
+		}
+
+		/// Accept pallet ownership.
+		///
+		/// May only be called by the account that has been proposed by the `propose_owner` call.
+		#[pallet::weight((T::DbWeight::get().reads_writes(2, 2), DispatchClass::Operational))]
+		pub fn accept_ownership(origin: OriginFor<T>) -> DispatchResult {
+			let (old_owner, new_owner) = <Self as OwnedBridgeModule<_>>::accept_ownership(origin)
+				.map_err(Error::<T, I>::BridgeModule)?;
+			Self::deposit_event(Event::OwnershipTransferred { old_owner, new_owner });
+			Ok(())
+		}
+
+		/// Cancel pending `PalletOwner` change.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::weight((T::DbWeight::get().reads_writes(2, 1), DispatchClass::Operational))]
+		pub fn cancel_pending_owner(origin: OriginFor<T>) -> DispatchResult {
+			let pending_owner = <Self as OwnedBridgeModule<_>>::cancel_pending_owner(origin)?
+				.ok_or(Error::<T, I>::BridgeModule(
+					bp_runtime::OwnedBridgeModuleError::NoPendingOwner,
+				))?;
+			Self::deposit_event(Event::OwnershipTransferCancelled { pending_owner });
+			Ok(())
+		}
+
 		/// Halt or resume all/some pallet operations.
 		///
+		/// Partially restricted modes may only be entered from the normal mode.
+		///
 		/// May only be called either by root, or by `PalletOwner`.
-		#[pallet::weight((T::DbWeight::get().reads_writes(1, 1), DispatchClass::Operational))]
+		#[pallet::weight((T::DbWeight::get().reads_writes(2, 1), DispatchClass::Operational))]
 		pub fn set_operating_mode(
 			origin: OriginFor<T>,
 			operating_mode: MessagesOperatingMode,
 		) -> DispatchResult {
-			<Self as OwnedBridgeModule<_>>::set_operating_mode(origin, operating_mode)
+			Self::ensure_owner_or_root(origin)?;
+			let old_operating_mode =
+				<Self as OwnedBridgeModule<_>>::set_operating_mode(operating_mode)
+					.map_err(Error::<T, I>::BridgeModule)?;
+			Self::deposit_event(Event::OperatingModeChanged {
+				old_operating_mode,
+				new_operating_mode: operating_mode,
+			});
+			Ok(())
 		}
 
 		/// Update pallet parameter.
@@ -301,6 +468,19 @@ pub mod pallet {
 				Error::<T, I>::MessageIsNotYetSent
 			);
 
+			// cancelled messages are never delivered, so the additional fee would be lost
+			let message_key = MessageKey { lane_id, nonce };
+			let mut message_data = OutboundMessages::<T, I>::get(message_key)
+				.expect("the message is sent and not yet delivered; so it is in the storage; qed");
+			ensure!(
+				!message_data.payload.is_empty() || !message_data.fee.is_zero(),
+				Error::<T, I>::MessageIsAlreadyCancelled
+			);
+			let new_fee = message_data
+				.fee
+				.checked_add(&additional_fee)
+				.ok_or(Error::<T, I>::MessageFeeOverflow)?;
+
 			// withdraw additional fee from submitter
 			T::MessageDeliveryAndDispatchPayment::pay_delivery_and_dispatch_fee(
 				&origin,
@@ -320,17 +500,11 @@ pub mod pallet {
 			})?;
 
 			// and finally update fee in the storage
-			let message_key = MessageKey { lane_id, nonce };
-			let message_size = OutboundMessages::<T, I>::mutate(message_key, |message_data| {
-				// saturating_add is fine here - overflow here means that someone controls all
-				// chain funds, which shouldn't ever happen + `pay_delivery_and_dispatch_fee`
-				// above will fail before we reach here
-				let message_data = message_data.as_mut().expect(
-					"the message is sent and not yet delivered; so it is in the storage; qed",
-				);
-				message_data.fee = message_data.fee.saturating_add(&additional_fee);
-				message_data.payload.len()
-			});
+			message_data.fee = new_fee;
+			let message_size = message_data.payload.len();
+			OutboundMessages::<T, I>::insert(message_key, message_data);
+
+			Self::deposit_event(Event::MessageFeeIncreased { lane_id, nonce, new_fee });
 
 			// compute actual dispatch weight that depends on the stored message size
 			let actual_weight = sp_std::cmp::min(
@@ -355,6 +529,7 @@ pub mod pallet {
 			dispatch_weight: Weight,
 		) -> DispatchResultWithPostInfo {
 			Self::ensure_not_halted().map_err(Error::<T, I>::BridgeModule)?;
+			ensure_inbound_messages_accepted::<T, I>()?;
 			let relayer_id_at_this_chain = ensure_signed(origin)?;
 
 			// reject transactions that are declaring too many messages
@@ -362,6 +537,11 @@ pub mod pallet {
 				MessageNonce::from(messages_count) <= T::MaxUnconfirmedMessagesAtInboundLane::get(),
 				Error::<T, I>::TooManyMessagesInTheProof
 			);
+			// reject too large proofs before verifying them
+			ensure!(
+				proof.size() <= T::MaxMessagesProofSize::get(),
+				Error::<T, I>::MessagesProofIsTooLarge
+			);
 
 			// why do we need to know the weight of this (`receive_messages_proof`) call? Because
 			// we may want to return some funds for not-dispatching (or partially dispatching) some
@@ -385,13 +565,19 @@ pub mod pallet {
 				T::SourceHeaderChain,
 				T::InboundMessageFee,
 				T::InboundPayload,
-			>(proof, messages_count)
+			>(proof, messages_count, Self::inbound_lane_max_payload_size)
 			.map_err(|err| {
 				log::trace!(target: LOG_TARGET, "Rejecting invalid messages proof: {:?}", err,);
 
-				Error::<T, I>::InvalidMessagesProof
+				verification_error::<T, I>(err.into(), Error::<T, I>::InvalidMessagesProof)
 			})?;
 
+			// reject the whole proof if it brings messages of unknown or restricted lane
+			for lane_id in messages.keys() {
+				ensure!(OpenedLanes::<T, I>::contains_key(lane_id), Error::<T, I>::UnknownLane);
+				ensure_lane_inbound_messages_accepted::<T, I>(lane_id)?;
+			}
+
 			// dispatch messages and (optionally) update lane(s) state(s)
 			let mut total_messages = 0;
 			let mut valid_messages = 0;
@@ -411,7 +597,62 @@ pub mod pallet {
 					}
 				}
 
-				for mut message in lane_data.messages {
+				// if the proof has not brought lane state that allows us to prune some unrewarded
+				// relayer entries, every message would be rejected by the lane => let's reject the
+				// whole transaction instead of mining it and delivering nothing
+				ensure!(
+					lane_data.messages.is_empty() || lane.has_room_for_unrewarded_relayer_entry(),
+					Error::<T, I>::TooManyUnrewardedRelayers,
+				);
+
+				for message in lane_data.messages {
+					let mut message = match message {
+						InboundMessage::Regular(message) => message,
+						InboundMessage::Oversized { key, payload_size } => {
+							debug_assert_eq!(key.lane_id, lane_id);
+							total_messages += 1;
+
+							// oversized message is never dispatched, but we still need to mark
+							// it as delivered - otherwise the lane will be blocked forever
+							let receival_result = lane
+								.receive_oversized_message(&relayer_id_at_bridged_chain, key.nonce);
+							ensure!(
+								receival_result != ReceivalResult::NonceOverflow,
+								Error::<T, I>::MessageNonceOverflow,
+							);
+							if receival_result == ReceivalResult::PayloadTooLarge {
+								index_message_event::<T, I>(
+									&lane_id,
+									key.nonce,
+									MessageEventKind::Delivered,
+								);
+								log::trace!(
+									target: LOG_TARGET,
+									"Message {:?}/{} has payload of {} bytes, which exceeds the \
+									maximal inbound payload size. It won't be dispatched",
+									lane_id,
+									key.nonce,
+									payload_size,
+								);
+								Self::deposit_event(Event::InboundMessagePayloadTooLarge {
+									lane_id,
+									nonce: key.nonce,
+									payload_size,
+								});
+								Self::deposit_event(Event::MessageRejected {
+									lane_id,
+									nonce: key.nonce,
+									reason: MessageRejectionReason::OversizedPayload,
+									dispatch_error: None,
+								});
+							}
+
+							// the message is not dispatched, so the dispatch fee is not paid
+							actual_weight = actual_weight
+								.saturating_sub(T::WeightInfo::pay_inbound_dispatch_fee_overhead());
+							continue
+						},
+					};
 					debug_assert_eq!(message.key.lane_id, lane_id);
 
 					// ensure that relayer has declared enough weight for dispatching next message
@@ -430,12 +671,51 @@ pub mod pallet {
 					}
 					total_messages += 1;
 
+					let is_payload_decodable = message.data.payload.decoded().is_ok();
 					let receival_result = lane.receive_message::<T::MessageDispatch, T::AccountId>(
 						&relayer_id_at_bridged_chain,
 						&relayer_id_at_this_chain,
 						message.key.nonce,
 						message.data,
 					);
+					// the lane is unable to accept any more messages - reject the whole transaction
+					ensure!(
+						receival_result != ReceivalResult::NonceOverflow,
+						Error::<T, I>::MessageNonceOverflow,
+					);
+					if let ReceivalResult::Dispatched(ref dispatch_result) = receival_result {
+						index_message_event::<T, I>(
+							&lane_id,
+							message.key.nonce,
+							MessageEventKind::Delivered,
+						);
+
+						let rejection_reason = if !is_payload_decodable {
+							Some(MessageRejectionReason::UndecodablePayload)
+						} else if !dispatch_result.dispatch_result ||
+							dispatch_result.dispatch_error.is_some()
+						{
+							Some(MessageRejectionReason::DispatchError)
+						} else {
+							None
+						};
+						if let Some(reason) = rejection_reason {
+							log::trace!(
+								target: LOG_TARGET,
+								"Message {:?}/{} has been rejected: {:?} ({:?})",
+								lane_id,
+								message.key.nonce,
+								reason,
+								dispatch_result.dispatch_error,
+							);
+							Self::deposit_event(Event::MessageRejected {
+								lane_id,
+								nonce: message.key.nonce,
+								reason,
+								dispatch_error: dispatch_result.dispatch_error,
+							});
+						}
+					}
 
 					// note that we're returning unspent weight to relayer even if message has been
 					// rejected by the lane. This allows relayers to submit spam transactions with
@@ -453,7 +733,9 @@ pub mod pallet {
 						},
 						ReceivalResult::InvalidNonce |
 						ReceivalResult::TooManyUnrewardedRelayers |
-						ReceivalResult::TooManyUnconfirmedMessages => (dispatch_weight, true),
+						ReceivalResult::TooManyUnconfirmedMessages |
+						ReceivalResult::PayloadTooLarge |
+						ReceivalResult::NonceOverflow => (dispatch_weight, true),
 					};
 
 					let unspent_weight = sp_std::cmp::min(unspent_weight, dispatch_weight);
@@ -495,6 +777,11 @@ pub mod pallet {
 			relayers_state: UnrewardedRelayersState,
 		) -> DispatchResultWithPostInfo {
 			Self::ensure_not_halted().map_err(Error::<T, I>::BridgeModule)?;
+			// reject too large proofs before verifying them
+			ensure!(
+				proof.size() <= T::MaxMessagesDeliveryProofSize::get(),
+				Error::<T, I>::MessagesDeliveryProofIsTooLarge
+			);
 
 			// why do we need to know the weight of this (`receive_messages_delivery_proof`) call?
 			// Because we may want to return some funds for messages that are not processed by the
@@ -523,23 +810,20 @@ pub mod pallet {
 						err,
 					);
 
-					Error::<T, I>::InvalidMessagesDeliveryProof
+					verification_error::<T, I>(
+						err.into(),
+						Error::<T, I>::InvalidMessagesDeliveryProof,
+					)
 				})?;
 
+			// confirmations are accepted at closed lanes, but not at halted lanes
+			ensure!(!LaneMode::<T, I>::get(lane_id).is_halted(), Error::<T, I>::LaneHalted);
+
 			// verify that the relayer has declared correct `lane_data::relayers` state
 			// (we only care about total number of entries and messages, because this affects call
-			// weight)
-			ensure!(
-				total_unrewarded_messages(&lane_data.relayers).unwrap_or(MessageNonce::MAX) ==
-					relayers_state.total_messages &&
-					lane_data.relayers.len() as MessageNonce ==
-						relayers_state.unrewarded_relayer_entries,
-				Error::<T, I>::InvalidUnrewardedRelayersState
-			);
-			// the `last_delivered_nonce` field may also be used by the signed extension. Even
-			// though providing wrong value isn't critical, let's also check it here.
+			// weight, and the `last_delivered_nonce`, which may be used by the signed extension)
 			ensure!(
-				lane_data.last_delivered_nonce() == relayers_state.last_delivered_nonce,
+				relayers_state.is_valid_for(&lane_data),
 				Error::<T, I>::InvalidUnrewardedRelayersState
 			);
 
@@ -578,6 +862,10 @@ pub mod pallet {
 			};
 
 			if let Some(confirmed_messages) = confirmed_messages {
+				// the proof is accepted even if it claims that refunded messages have been
+				// dispatched - otherwise the lane would be blocked forever
+				report_dispatched_refunded_messages::<T, I>(&lane_id, &confirmed_messages);
+
 				// handle messages delivery confirmation
 				let preliminary_callback_overhead =
 					relayers_state.total_messages.saturating_mul(single_message_callback_overhead);
@@ -611,8 +899,27 @@ pub mod pallet {
 					},
 				}
 
-				// emit 'delivered' event
+				// the lane has made progress
+				if !T::MessageExpiryBlocks::get().is_zero() {
+					let lane_data = lane.data();
+					if lane_data.latest_received_nonce < lane_data.latest_generated_nonce {
+						OutboundLaneProgressedAt::<T, I>::insert(
+							lane_id,
+							frame_system::Pallet::<T>::block_number(),
+						);
+					} else {
+						OutboundLaneProgressedAt::<T, I>::remove(lane_id);
+					}
+				}
+
+				// remember relayers that have delivered confirmed messages
 				let received_range = confirmed_messages.begin..=confirmed_messages.end;
+				track_recent_deliveries::<T, I>(&lane_id, &lane_data.relayers, &received_range);
+				for nonce in received_range.clone() {
+					index_message_event::<T, I>(&lane_id, nonce, MessageEventKind::Confirmed);
+				}
+
+				// emit 'delivered' event
 				Self::deposit_event(Event::MessagesDelivered {
 					lane_id,
 					messages: confirmed_messages,
@@ -636,6 +943,201 @@ pub mod pallet {
 
 			Ok(PostDispatchInfo { actual_weight: Some(actual_weight), pays_fee: Pays::Yes })
 		}
+
+		/// Set maximal size of the inbound message payload.
+		///
+		/// If `lane_id` is `None`, the limit for all lanes is updated. Otherwise, only the limit
+		/// of the given lane is updated. If `max_payload_size` is `None`, the limit is reset to
+		/// its default value.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::weight((T::DbWeight::get().reads_writes(1, 2), DispatchClass::Operational))]
+		pub fn set_inbound_lane_max_payload_size(
+			origin: OriginFor<T>,
+			lane_id: Option<LaneId>,
+			max_payload_size: Option<u32>,
+		) -> DispatchResult {
+			Self::ensure_owner_or_root(origin)?;
+			match lane_id {
+				Some(lane_id) => InboundLanesMaxPayloadSize::<T, I>::set(lane_id, max_payload_size),
+				None => match max_payload_size {
+					Some(max_payload_size) =>
+						InboundLaneMaxPayloadSize::<T, I>::put(max_payload_size),
+					None => InboundLaneMaxPayloadSize::<T, I>::kill(),
+				},
+			}
+			Self::deposit_event(Event::InboundLaneMaxPayloadSizeUpdated {
+				lane_id,
+				max_payload_size,
+			});
+			Ok(())
+		}
+
+		/// Open the lane, so that messages may be sent and received over it.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::weight((T::DbWeight::get().reads_writes(2, 2), DispatchClass::Operational))]
+		pub fn open_lane(origin: OriginFor<T>, lane_id: LaneId) -> DispatchResult {
+			Self::ensure_owner_or_root(origin)?;
+			ensure!(!OpenedLanes::<T, I>::contains_key(lane_id), Error::<T, I>::LaneAlreadyOpened);
+			OpenedLanes::<T, I>::insert(lane_id, ());
+			Self::deposit_event(Event::LaneOpened { lane_id });
+			Ok(())
+		}
+
+		/// Close the lane, so that no more messages may be sent or received over it.
+		///
+		/// Messages that have already been sent over the lane may still be confirmed.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::weight((T::DbWeight::get().reads_writes(2, 2), DispatchClass::Operational))]
+		pub fn close_lane(origin: OriginFor<T>, lane_id: LaneId) -> DispatchResult {
+			Self::ensure_owner_or_root(origin)?;
+			ensure!(OpenedLanes::<T, I>::contains_key(lane_id), Error::<T, I>::UnknownLane);
+			OpenedLanes::<T, I>::remove(lane_id);
+			Self::deposit_event(Event::LaneClosed { lane_id });
+			Ok(())
+		}
+
+		/// Halt or resume all/some operations at the given lane.
+		///
+		/// The lane mode is checked in addition to the pallet operating mode, so operations at
+		/// the lane are only allowed if both modes allow them. Partially restricted modes may only
+		/// be entered from the normal mode.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::weight((T::DbWeight::get().reads_writes(2, 2), DispatchClass::Operational))]
+		pub fn set_lane_operating_mode(
+			origin: OriginFor<T>,
+			lane_id: LaneId,
+			operating_mode: MessagesOperatingMode,
+		) -> DispatchResult {
+			Self::ensure_owner_or_root(origin)?;
+			let old_operating_mode = LaneMode::<T, I>::get(lane_id);
+			MessagesOperatingMode::ensure_can_transition(old_operating_mode, operating_mode)
+				.map_err(|_| {
+					Error::<T, I>::BridgeModule(
+						bp_runtime::OwnedBridgeModuleError::InvalidOperatingModeTransition,
+					)
+				})?;
+			if operating_mode == MessagesOperatingMode::default() {
+				LaneMode::<T, I>::remove(lane_id);
+			} else {
+				LaneMode::<T, I>::insert(lane_id, operating_mode);
+			}
+			log::info!(
+				target: LOG_TARGET,
+				"Changing operating mode of lane {:?} from {:?} to {:?}.",
+				lane_id,
+				old_operating_mode,
+				operating_mode,
+			);
+			Self::deposit_event(Event::LaneOperatingModeChanged {
+				lane_id,
+				old_operating_mode,
+				new_operating_mode: operating_mode,
+			});
+			Ok(())
+		}
+
+		/// Cancel the queued outbound message and refund its fee to the message submitter.
+		///
+		/// The message is replaced with the empty message with zero fee. The nonce is still
+		/// delivered to the bridged chain (where the message is rejected), so the lane isn't
+		/// blocked.
+		///
+		/// Only messages with nonces above the `latest_received_nonce` of the outbound lane may
+		/// be cancelled. This is the last delivered nonce of the bridged inbound lane, proven by
+		/// the latest accepted delivery proof. Messages above this nonce may still have been
+		/// delivered, but not yet confirmed. So messages may only be cancelled at stalled lanes:
+		/// the lane must be closed, halted (or the whole pallet must be halted), or it must have
+		/// queued messages and no delivery confirmations for `MessageExpiryBlocks` blocks. If the
+		/// cancelled message has actually been delivered and dispatched, its delivery proof is
+		/// still accepted, but the relayer is not rewarded (see `RefundedMessageDispatched`).
+		///
+		/// May only be called either by the message submitter, or by root, or by `PalletOwner`.
+		/// The fee is only refunded if the message submitter is known.
+		#[pallet::weight(T::DbWeight::get().reads_writes(9, 4))]
+		pub fn cancel_message(
+			origin: OriginFor<T>,
+			lane_id: LaneId,
+			nonce: MessageNonce,
+		) -> DispatchResult {
+			// delivered messages are already pruned and their fee is paid to relayers
+			let lane = outbound_lane::<T, I>(lane_id);
+			ensure!(
+				nonce > lane.data().latest_received_nonce,
+				Error::<T, I>::MessageIsAlreadyDelivered
+			);
+			ensure!(
+				nonce <= lane.data().latest_generated_nonce,
+				Error::<T, I>::MessageIsNotYetSent
+			);
+
+			let message_key = MessageKey { lane_id, nonce };
+			let submitter = OutboundMessageSubmitters::<T, I>::get(message_key);
+			let is_submitter =
+				submitter.is_some() && T::MessageSubmitter::submitter(&origin) == submitter;
+			if !is_submitter {
+				Self::ensure_owner_or_root(origin)?;
+			}
+
+			ensure!(
+				is_outbound_lane_stalled::<T, I>(
+					&lane_id,
+					frame_system::Pallet::<T>::block_number()
+				),
+				Error::<T, I>::LaneIsNotStalled
+			);
+
+			let message_data = OutboundMessages::<T, I>::get(message_key)
+				.expect("the message is sent and not yet delivered; so it is in the storage; qed");
+			ensure!(
+				!message_data.payload.is_empty() || !message_data.fee.is_zero(),
+				Error::<T, I>::MessageIsAlreadyCancelled
+			);
+
+			// refund fee to the submitter
+			let refund_origin = submitter.as_ref().and_then(T::MessageSubmitter::refund_origin);
+			if let Some(refund_origin) = refund_origin {
+				T::MessageDeliveryAndDispatchPayment::refund_delivery_and_dispatch_fee(
+					&refund_origin,
+					&message_data.fee,
+				)
+				.map_err(|err| {
+					log::trace!(
+						target: LOG_TARGET,
+						"Failed to refund fee {:?} of the message {:?}/{:?}: {:?}",
+						message_data.fee,
+						lane_id,
+						nonce,
+						err,
+					);
+
+					Error::<T, I>::FailedToRefundMessageFee
+				})?;
+			} else {
+				log::trace!(
+					target: LOG_TARGET,
+					"Submitter of the cancelled message {:?}/{:?} is unknown. Fee {:?} is not refunded",
+					lane_id,
+					nonce,
+					message_data.fee,
+				);
+			}
+
+			// and finally replace the message with the empty one
+			OutboundMessages::<T, I>::insert(
+				message_key,
+				StoredMessageData(MessageData { payload: Vec::new(), fee: Zero::zero() }),
+			);
+			OutboundMessageSubmitters::<T, I>::remove(message_key);
+			OutboundMessageSentAt::<T, I>::remove(message_key);
+			remember_refunded_outbound_nonce::<T, I>(&lane_id, nonce);
+
+			Self::deposit_event(Event::MessageCancelled { lane_id, nonce });
+			Ok(())
+		}
 	}
 
 	#[pallet::event]
@@ -647,6 +1149,52 @@ pub mod pallet {
 		MessageAccepted { lane_id: LaneId, nonce: MessageNonce },
 		/// Messages in the inclusive range have been delivered to the bridged chain.
 		MessagesDelivered { lane_id: LaneId, messages: DeliveredMessages },
+		/// New pallet owner has been proposed.
+		OwnershipTransferProposed { pending_owner: T::AccountId },
+		/// Pallet ownership has been transferred.
+		OwnershipTransferred { old_owner: Option<T::AccountId>, new_owner: T::AccountId },
+		/// Pending pallet ownership transfer has been cancelled.
+		OwnershipTransferCancelled { pending_owner: T::AccountId },
+		/// Maximal inbound payload size has been updated. If `lane_id` is `None`, then the
+		/// limit is updated for all lanes that have no explicit limit.
+		InboundLaneMaxPayloadSizeUpdated { lane_id: Option<LaneId>, max_payload_size: Option<u32> },
+		/// Inbound message has been received, but it hasn't been dispatched, because its payload
+		/// exceeds the maximal inbound payload size.
+		InboundMessagePayloadTooLarge { lane_id: LaneId, nonce: MessageNonce, payload_size: u32 },
+		/// Inbound message has been received, but it either hasn't been dispatched, or its
+		/// dispatch has failed. If the dispatcher has reported details of the failure, they're
+		/// provided in the `dispatch_error` field.
+		MessageRejected {
+			lane_id: LaneId,
+			nonce: MessageNonce,
+			reason: MessageRejectionReason,
+			dispatch_error: Option<MessageDispatchError>,
+		},
+		/// Pallet operating mode has been changed.
+		OperatingModeChanged {
+			old_operating_mode: MessagesOperatingMode,
+			new_operating_mode: MessagesOperatingMode,
+		},
+		/// The lane has been opened.
+		LaneOpened { lane_id: LaneId },
+		/// The lane has been closed.
+		LaneClosed { lane_id: LaneId },
+		/// Queued outbound message has been cancelled.
+		MessageCancelled { lane_id: LaneId, nonce: MessageNonce },
+		/// Queued outbound messages in the inclusive range have expired.
+		MessagesExpired { lane_id: LaneId, begin: MessageNonce, end: MessageNonce },
+		/// The message, which fee has been refunded (because it has been cancelled or expired),
+		/// has been delivered and dispatched at the bridged chain. The relayer isn't rewarded
+		/// for its delivery.
+		RefundedMessageDispatched { lane_id: LaneId, nonce: MessageNonce },
+		/// Fee of the queued outbound message has been increased.
+		MessageFeeIncreased { lane_id: LaneId, nonce: MessageNonce, new_fee: T::OutboundMessageFee },
+		/// Lane operating mode has been changed.
+		LaneOperatingModeChanged {
+			lane_id: LaneId,
+			old_operating_mode: MessagesOperatingMode,
+			new_operating_mode: MessagesOperatingMode,
+		},
 	}
 
 	#[pallet::error]
@@ -667,6 +1215,10 @@ pub mod pallet {
 		InvalidMessagesProof,
 		/// Invalid messages delivery proof has been submitted.
 		InvalidMessagesDeliveryProof,
+		/// The messages proof is larger than `MaxMessagesProofSize`.
+		MessagesProofIsTooLarge,
+		/// The messages delivery proof is larger than `MaxMessagesDeliveryProofSize`.
+		MessagesDeliveryProofIsTooLarge,
 		/// The bridged chain has invalid `UnrewardedRelayers` in its storage (fatal for the lane).
 		InvalidUnrewardedRelayers,
 		/// The relayer has declared invalid unrewarded relayers state in the
@@ -676,9 +1228,55 @@ pub mod pallet {
 		MessageIsAlreadyDelivered,
 		/// The message someone is trying to work with (i.e. increase fee) is not yet sent.
 		MessageIsNotYetSent,
+		/// The message someone is trying to work with (i.e. cancel or increase fee) is already
+		/// cancelled or expired.
+		MessageIsAlreadyCancelled,
+		/// Failed to refund fee of the cancelled message to its submitter.
+		FailedToRefundMessageFee,
+		/// The message can't be cancelled, because its lane is not stalled.
+		LaneIsNotStalled,
+		/// The message fee would overflow after adding the additional fee.
+		MessageFeeOverflow,
 		/// The number of actually confirmed messages is going to be larger than the number of
 		/// messages in the proof. This may mean that this or bridged chain storage is corrupted.
 		TryingToConfirmMoreMessagesThanExpected,
+		/// The sender has already sent maximal number of messages within the current rate limit
+		/// window.
+		MessagesRateLimitExceeded,
+		/// Pallet is in `RejectingInboundMessages` operating mode and doesn't accept delivery
+		/// transactions.
+		NotAcceptingInboundMessages,
+		/// The inbound lane has run out of message nonces, so no more messages may be
+		/// delivered over it.
+		MessageNonceOverflow,
+		/// The message fee is below the minimal fee, required by the lane.
+		TooLowFee,
+		/// The outbound lane is closed for the message sender.
+		LaneClosed,
+		/// There are too many pending (undelivered) messages at the outbound lane.
+		TooManyPendingMessages,
+		/// The message has declared incorrect dispatch weight.
+		InvalidMessageDispatchWeight,
+		/// The message sender origin can't be mapped to the target chain origin.
+		InvalidSourceOrigin,
+		/// The lane is not opened, so messages can't be sent or received over it.
+		UnknownLane,
+		/// The lane is already opened.
+		LaneAlreadyOpened,
+		/// All operations at the lane are halted.
+		LaneHalted,
+		/// The lane is in `RejectingOutboundMessages` operating mode and doesn't accept new
+		/// messages.
+		LaneNotAcceptingOutboundMessages,
+		/// The lane is in `RejectingInboundMessages` operating mode and doesn't accept delivery
+		/// transactions.
+		LaneNotAcceptingInboundMessages,
+		/// The outbound lane has `MaxOutboundQueueLen` undelivered messages and doesn't accept
+		/// new messages until some of them are delivered.
+		QueueFull,
+		/// The inbound lane has maximal number of unrewarded relayer entries and the delivery
+		/// transaction has not brought lane state that allows to prune any of them.
+		TooManyUnrewardedRelayers,
 		/// Error generated by the `OwnedBridgeModule` trait.
 		BridgeModule(bp_runtime::OwnedBridgeModuleError),
 	}
@@ -693,6 +1291,13 @@ pub mod pallet {
 	#[pallet::getter(fn module_owner)]
 	pub type PalletOwner<T: Config<I>, I: 'static = ()> = StorageValue<_, T::AccountId>;
 
+	/// Account that has been proposed to become the new pallet owner.
+	///
+	/// The ownership is transferred when this account calls `accept_ownership`.
+	#[pallet::storage]
+	pub type PalletPendingOwner<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, T::AccountId, OptionQuery>;
+
 	/// The current operating mode of the pallet.
 	///
 	/// Depending on the mode either all, some, or no transactions will be allowed.
@@ -701,6 +1306,36 @@ pub mod pallet {
 	pub type PalletOperatingMode<T: Config<I>, I: 'static = ()> =
 		StorageValue<_, MessagesOperatingMode, ValueQuery>;
 
+	/// Maximal size of the inbound message payload at lanes without explicit limit.
+	///
+	/// Messages with larger payloads are marked as delivered, but they are not dispatched.
+	#[pallet::storage]
+	pub type InboundLaneMaxPayloadSize<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, u32, ValueQuery, T::MaximalInboundPayloadSize>;
+
+	/// Map of lane id => maximal size of the inbound message payload at this lane.
+	///
+	/// If there's no entry for the lane, the `InboundLaneMaxPayloadSize` is used.
+	#[pallet::storage]
+	pub type InboundLanesMaxPayloadSize<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, LaneId, u32, OptionQuery>;
+
+	/// Lanes that are opened for sending and receiving messages.
+	///
+	/// Messages at lanes that are not in this map are rejected. Lanes are opened either at
+	/// genesis, or by the `open_lane` call.
+	#[pallet::storage]
+	pub type OpenedLanes<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, LaneId, (), OptionQuery>;
+
+	/// Map of lane id => operating mode of this lane.
+	///
+	/// Lanes without an entry are operating normally. The lane mode is checked in addition to
+	/// the `PalletOperatingMode`.
+	#[pallet::storage]
+	pub type LaneMode<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, LaneId, MessagesOperatingMode, ValueQuery>;
+
 	/// Map of lane id => inbound lane data.
 	#[pallet::storage]
 	pub type InboundLanes<T: Config<I>, I: 'static = ()> =
@@ -716,12 +1351,116 @@ pub mod pallet {
 	pub type OutboundMessages<T: Config<I>, I: 'static = ()> =
 		StorageMap<_, Blake2_128Concat, MessageKey, StoredMessageData<T, I>>;
 
+	/// Submitters of queued outbound messages.
+	///
+	/// Entries are only inserted if `Config::MessageSubmitter` returns submitter of the message
+	/// and are removed when the message is pruned.
+	#[pallet::storage]
+	pub type OutboundMessageSubmitters<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, MessageKey, SubmitterOf<T, I>>;
+
+	/// Numbers of blocks where queued outbound messages have been sent.
+	///
+	/// Entries are only inserted if `Config::MessageExpiryBlocks` is non-zero and are removed
+	/// when the message is pruned, cancelled or expired.
+	#[pallet::storage]
+	pub type OutboundMessageSentAt<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, MessageKey, T::BlockNumber>;
+
+	/// Map of lane id => number of the block where the outbound lane has made progress for the
+	/// last time.
+	///
+	/// The lane makes progress when the message is sent to the lane without queued messages
+	/// and when some of queued messages are confirmed. Entries are only inserted if
+	/// `Config::MessageExpiryBlocks` is non-zero and are removed when all queued messages are
+	/// confirmed.
+	#[pallet::storage]
+	pub type OutboundLaneProgressedAt<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, LaneId, T::BlockNumber>;
+
+	/// Map of lane id => latest outbound nonce that has been checked for expiry.
+	#[pallet::storage]
+	pub type LatestExpiryCheckedNonce<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, LaneId, MessageNonce, ValueQuery>;
+
+	/// The last lane that has been fully checked for expiry.
+	///
+	/// Next `on_idle` call starts checking lanes that follow this lane in the `OpenedLanes` map.
+	#[pallet::storage]
+	pub type LatestExpiryCheckedLane<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, LaneId, OptionQuery>;
+
+	/// Map of lane id => latest outbound nonce which fee has been refunded (because the message
+	/// has been cancelled or expired).
+	///
+	/// Delivery proofs, claiming that such messages have been dispatched at the bridged chain,
+	/// are reported with the `RefundedMessageDispatched` event.
+	#[pallet::storage]
+	pub type LatestRefundedOutboundNonce<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, LaneId, MessageNonce, ValueQuery>;
+
+	/// Map of lane id => index of the `RecentDeliveries` slot that will be used by the next
+	/// confirmed delivery at this lane.
+	#[pallet::storage]
+	pub type RecentDeliveriesNextSlot<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, LaneId, u32, ValueQuery>;
+
+	/// Sliding window of recent confirmed deliveries: (lane id, slot) => (relayer, number of
+	/// messages, delivered by the relayer).
+	///
+	/// There are at most `T::MaxRecentDeliveries` slots at every lane.
+	#[pallet::storage]
+	pub type RecentDeliveries<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		LaneId,
+		Twox64Concat,
+		u32,
+		(T::AccountId, MessageNonce),
+	>;
+
+	/// Map of (lane id, relayer) => number of messages, delivered by the relayer within the
+	/// `RecentDeliveries` window.
+	#[pallet::storage]
+	pub type RecentRelayers<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		LaneId,
+		Blake2_128Concat,
+		T::AccountId,
+		MessageNonce,
+		ValueQuery,
+	>;
+
+	/// Number of messages, sent by rate-limited accounts: (window start, account) => number of
+	/// messages, sent by the account within the window.
+	///
+	/// Entries of ended windows are pruned when new blocks are initialized.
+	#[pallet::storage]
+	pub type OutboundMessagesPerAccount<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::BlockNumber,
+		Blake2_128Concat,
+		T::AccountId,
+		u32,
+		ValueQuery,
+	>;
+
+	/// Start of the oldest rate limit window that may still have entries in the
+	/// `OutboundMessagesPerAccount` map.
+	#[pallet::storage]
+	pub type OldestOutboundRateLimitWindow<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, T::BlockNumber, OptionQuery>;
+
 	#[pallet::genesis_config]
 	pub struct GenesisConfig<T: Config<I>, I: 'static = ()> {
 		/// Initial pallet operating mode.
 		pub operating_mode: MessagesOperatingMode,
 		/// Initial pallet owner.
 		pub owner: Option<T::AccountId>,
+		/// Lanes that are opened at genesis.
+		pub opened_lanes: Vec<LaneId>,
 		/// Dummy marker.
 		pub phantom: sp_std::marker::PhantomData<I>,
 	}
@@ -732,6 +1471,7 @@ pub mod pallet {
 			Self {
 				operating_mode: Default::default(),
 				owner: Default::default(),
+				opened_lanes: Default::default(),
 				phantom: Default::default(),
 			}
 		}
@@ -744,6 +1484,9 @@ pub mod pallet {
 			if let Some(ref owner) = self.owner {
 				PalletOwner::<T, I>::put(owner);
 			}
+			for lane_id in &self.opened_lanes {
+				OpenedLanes::<T, I>::insert(lane_id, ());
+			}
 		}
 	}
 
@@ -756,6 +1499,60 @@ pub mod pallet {
 			OutboundMessages::<T, I>::get(MessageKey { lane_id: lane, nonce }).map(Into::into)
 		}
 
+		/// Get submitter of the outbound message with given nonce, if it has been recorded.
+		pub fn outbound_message_submitter(
+			lane: LaneId,
+			nonce: MessageNonce,
+		) -> Option<SubmitterOf<T, I>> {
+			OutboundMessageSubmitters::<T, I>::get(MessageKey { lane_id: lane, nonce })
+		}
+
+		/// Return maximal size of the inbound message payload at given lane.
+		pub fn inbound_lane_max_payload_size(lane: LaneId) -> u32 {
+			InboundLanesMaxPayloadSize::<T, I>::get(lane)
+				.unwrap_or_else(InboundLaneMaxPayloadSize::<T, I>::get)
+		}
+
+		/// Return number of messages that the account may still send within the current rate limit
+		/// window.
+		///
+		/// Returns `None` if the account is not rate-limited.
+		pub fn outbound_messages_allowance(account: T::AccountId) -> Option<u32> {
+			let rate_limit = T::OutboundMessagesRateLimit::get()?;
+			if PalletOwner::<T, I>::get().as_ref() == Some(&account) {
+				return None
+			}
+
+			let window_start = rate_limit.window_start(frame_system::Pallet::<T>::block_number());
+			let sent_messages = OutboundMessagesPerAccount::<T, I>::get(window_start, account);
+			Some(rate_limit.max_messages.saturating_sub(sent_messages))
+		}
+
+		/// Return relayers that have delivered messages at given lane within the recent deliveries
+		/// window, along with the number of messages they have delivered.
+		pub fn recent_relayers(lane: LaneId) -> Vec<(T::AccountId, MessageNonce)> {
+			RecentRelayers::<T, I>::iter_prefix(lane).collect()
+		}
+
+		/// Return relayers that have delivered messages at given inbound lane and are not yet
+		/// rewarded, along with the number of their unrewarded messages.
+		pub fn unrewarded_relayers_summary(lane: LaneId) -> Vec<(T::InboundRelayer, MessageNonce)>
+		where
+			T::InboundRelayer: Ord,
+		{
+			InboundLanes::<T, I>::get(lane).0.relayers_summary()
+		}
+
+		/// Return state of the given outbound lane.
+		pub fn outbound_lane_state(lane: LaneId) -> OutboundLaneData {
+			OutboundLanes::<T, I>::get(lane)
+		}
+
+		/// Return state of the given inbound lane.
+		pub fn inbound_lane_state(lane: LaneId) -> InboundLaneState {
+			(&InboundLanes::<T, I>::get(lane).0).into()
+		}
+
 		/// Prepare data, related to given inbound message.
 		pub fn inbound_message_data(
 			lane: LaneId,
@@ -769,6 +1566,7 @@ pub mod pallet {
 			};
 			InboundMessageDetails {
 				dispatch_weight: T::MessageDispatch::dispatch_weight(&mut dispatch_message),
+				is_payload_decodable: dispatch_message.data.payload.decoded().is_ok(),
 			}
 		}
 	}
@@ -807,7 +1605,7 @@ fn send_message<T: Config<I>, I: 'static>(
 	SendMessageArtifacts,
 	sp_runtime::DispatchErrorWithPostInfo<PostDispatchInfo>,
 > {
-	ensure_normal_operating_mode::<T, I>()?;
+	ensure_outbound_messages_accepted::<T, I>()?;
 
 	// the most lightweigh check is the message size check
 	ensure!(
@@ -815,8 +1613,22 @@ fn send_message<T: Config<I>, I: 'static>(
 		Error::<T, I>::MessageIsTooLarge,
 	);
 
-	// initially, actual (post-dispatch) weight is equal to pre-dispatch weight
-	let mut actual_weight = T::WeightInfo::send_message_weight(&payload, T::DbWeight::get());
+	// messages may only be sent over opened lanes that are accepting outbound messages
+	ensure!(OpenedLanes::<T, I>::contains_key(lane_id), Error::<T, I>::UnknownLane);
+	ensure_lane_outbound_messages_accepted::<T, I>(&lane_id)?;
+
+	// then let's check that the sender has not exceeded its rate limit
+	let rate_limited_sender = rate_limited_sender::<T, I>(&submitter);
+	if let Some((window_start, ref account, rate_limit)) = rate_limited_sender {
+		ensure!(
+			OutboundMessagesPerAccount::<T, I>::get(window_start, account) <
+				rate_limit.max_messages,
+			Error::<T, I>::MessagesRateLimitExceeded,
+		);
+	}
+
+	// initially, actual (post-dispatch) weight is equal to pre-dispatch weight
+	let mut actual_weight = T::WeightInfo::send_message_weight(&payload, T::DbWeight::get());
 
 	// let's first check if message can be delivered to target chain
 	T::TargetHeaderChain::verify_message(&payload).map_err(|err| {
@@ -827,16 +1639,22 @@ fn send_message<T: Config<I>, I: 'static>(
 			err,
 		);
 
-		Error::<T, I>::MessageRejectedByChainVerifier
+		verification_error::<T, I>(err.into(), Error::<T, I>::MessageRejectedByChainVerifier)
 	})?;
 
-	// now let's enforce any additional lane rules
+	// reject message if the lane queue is full
 	let mut lane = outbound_lane::<T, I>(lane_id);
+	let lane_data = lane.data();
+	let queue_len =
+		lane_data.latest_generated_nonce.saturating_sub(lane_data.latest_received_nonce);
+	ensure!(queue_len < T::MaxOutboundQueueLen::get(), Error::<T, I>::QueueFull);
+
+	// now let's enforce any additional lane rules
 	T::LaneMessageVerifier::verify_message(
 		&submitter,
 		&delivery_and_dispatch_fee,
 		&lane_id,
-		&lane.data(),
+		&lane_data,
 		&payload,
 	)
 	.map_err(|err| {
@@ -847,7 +1665,7 @@ fn send_message<T: Config<I>, I: 'static>(
 			err,
 		);
 
-		Error::<T, I>::MessageRejectedByLaneVerifier
+		verification_error::<T, I>(err.into(), Error::<T, I>::MessageRejectedByLaneVerifier)
 	})?;
 
 	// let's withdraw delivery and dispatch fee from submitter
@@ -872,6 +1690,24 @@ fn send_message<T: Config<I>, I: 'static>(
 	let encoded_payload_len = encoded_payload.len();
 	let nonce =
 		lane.send_message(MessageData { payload: encoded_payload, fee: delivery_and_dispatch_fee });
+	if let Some(message_submitter) = T::MessageSubmitter::submitter(&submitter) {
+		OutboundMessageSubmitters::<T, I>::insert(MessageKey { lane_id, nonce }, message_submitter);
+	}
+	if !T::MessageExpiryBlocks::get().is_zero() {
+		let block_number = frame_system::Pallet::<T>::block_number();
+		OutboundMessageSentAt::<T, I>::insert(MessageKey { lane_id, nonce }, block_number);
+		if queue_len == 0 {
+			OutboundLaneProgressedAt::<T, I>::insert(lane_id, block_number);
+		}
+	}
+	if let Some((window_start, account, _)) = rate_limited_sender {
+		OutboundMessagesPerAccount::<T, I>::mutate(window_start, account, |sent_messages| {
+			*sent_messages = sent_messages.saturating_add(1)
+		});
+		if OldestOutboundRateLimitWindow::<T, I>::get().is_none() {
+			OldestOutboundRateLimitWindow::<T, I>::put(window_start);
+		}
+	}
 	// Guaranteed to be called outside only when the message is accepted.
 	// We assume that the maximum weight call back used is `single_message_callback_overhead`, so do
 	// not perform complex db operation in callback. If you want to, put these magic logic in
@@ -921,6 +1757,7 @@ fn send_message<T: Config<I>, I: 'static>(
 		encoded_payload_len,
 	);
 
+	index_message_event::<T, I>(&lane_id, nonce, MessageEventKind::Accepted);
 	Pallet::<T, I>::deposit_event(Event::MessageAccepted { lane_id, nonce });
 
 	Ok(SendMessageArtifacts { nonce, weight: actual_weight })
@@ -957,15 +1794,431 @@ where
 	relayers_rewards
 }
 
-/// Ensure that the pallet is in normal operational mode.
-fn ensure_normal_operating_mode<T: Config<I>, I: 'static>() -> Result<(), Error<T, I>> {
-	if PalletOperatingMode::<T, I>::get() ==
-		MessagesOperatingMode::Basic(BasicOperatingMode::Normal)
-	{
-		return Ok(())
+/// Remember relayers that have delivered messages from the confirmed range.
+///
+/// Every confirmed delivery takes the oldest slot of the lane window, so the number of storage
+/// operations per delivery is constant.
+fn track_recent_deliveries<T: Config<I>, I: 'static>(
+	lane_id: &LaneId,
+	messages_relayers: &VecDeque<UnrewardedRelayer<T::AccountId>>,
+	received_range: &RangeInclusive<MessageNonce>,
+) {
+	let max_recent_deliveries = T::MaxRecentDeliveries::get();
+	if max_recent_deliveries == 0 {
+		return
+	}
+
+	// this loop is bounded by `T::MaxUnrewardedRelayerEntriesAtInboundLane` on the bridged chain
+	let mut slot = RecentDeliveriesNextSlot::<T, I>::get(lane_id) % max_recent_deliveries;
+	for entry in messages_relayers {
+		let nonce_begin = sp_std::cmp::max(entry.messages.begin, *received_range.start());
+		let nonce_end = sp_std::cmp::min(entry.messages.end, *received_range.end());
+		if nonce_end < nonce_begin {
+			continue
+		}
+
+		// forget the oldest delivery
+		if let Some((relayer, messages)) = RecentDeliveries::<T, I>::get(lane_id, slot) {
+			RecentRelayers::<T, I>::mutate_exists(lane_id, relayer, |total_messages| {
+				*total_messages = total_messages
+					.map(|total_messages| total_messages.saturating_sub(messages))
+					.filter(|total_messages| *total_messages != 0);
+			});
+		}
+
+		// remember the new delivery
+		let messages = nonce_end - nonce_begin + 1;
+		RecentDeliveries::<T, I>::insert(lane_id, slot, (entry.relayer.clone(), messages));
+		RecentRelayers::<T, I>::mutate(lane_id, &entry.relayer, |total_messages| {
+			*total_messages = total_messages.saturating_add(messages)
+		});
+
+		slot = (slot + 1) % max_recent_deliveries;
+	}
+	RecentDeliveriesNextSlot::<T, I>::insert(lane_id, slot);
+}
+
+/// Returns current rate limit window start, account and rate limit if the message sender is
+/// rate-limited.
+fn rate_limited_sender<T: Config<I>, I: 'static>(
+	submitter: &T::Origin,
+) -> Option<(T::BlockNumber, T::AccountId, OutboundRateLimit<T::BlockNumber>)> {
+	let rate_limit = T::OutboundMessagesRateLimit::get()?;
+	let account = T::RateLimitedSender::convert(submitter.clone())?;
+	if PalletOwner::<T, I>::get().as_ref() == Some(&account) {
+		return None
+	}
+
+	let window_start = rate_limit.window_start(frame_system::Pallet::<T>::block_number());
+	Some((window_start, account, rate_limit))
+}
+
+/// Prune `OutboundMessagesPerAccount` entries of ended rate limit windows.
+///
+/// At most `MAX_RATE_LIMIT_ENTRIES_TO_PRUNE_AT_ONCE` entries are pruned. Since only the current
+/// window is written to, pruning always keeps up with the window changes.
+fn prune_outbound_rate_limit_windows<T: Config<I>, I: 'static>(
+	block_number: T::BlockNumber,
+) -> frame_support::weights::Weight {
+	let db_weight = T::DbWeight::get();
+	let rate_limit = match T::OutboundMessagesRateLimit::get() {
+		Some(rate_limit) => rate_limit,
+		None => return 0,
+	};
+
+	let current_window_start = rate_limit.window_start(block_number);
+	let oldest_window_start = match OldestOutboundRateLimitWindow::<T, I>::get() {
+		Some(oldest_window_start) if oldest_window_start < current_window_start =>
+			oldest_window_start,
+		_ => return db_weight.reads(1),
+	};
+
+	let pruned_entries = OutboundMessagesPerAccount::<T, I>::drain_prefix(oldest_window_start)
+		.take(MAX_RATE_LIMIT_ENTRIES_TO_PRUNE_AT_ONCE)
+		.count();
+	if pruned_entries < MAX_RATE_LIMIT_ENTRIES_TO_PRUNE_AT_ONCE {
+		// all entries of the oldest window are pruned => let's move to the next window
+		let next_window_start = sp_std::cmp::min(
+			oldest_window_start.saturating_add(rate_limit.window.max(1u32.into())),
+			current_window_start,
+		);
+		OldestOutboundRateLimitWindow::<T, I>::put(next_window_start);
+	}
+
+	let pruned_entries = pruned_entries as u64;
+	db_weight.reads_writes(2 + pruned_entries, 1 + pruned_entries)
+}
+
+/// Returns true if there's no hope that queued messages of the outbound lane will be delivered
+/// (and confirmed) soon.
+///
+/// The lane is stalled if it is closed, if it (or the whole pallet) is halted or if it has not
+/// made any progress for `MessageExpiryBlocks` blocks.
+fn is_outbound_lane_stalled<T: Config<I>, I: 'static>(
+	lane_id: &LaneId,
+	block_number: T::BlockNumber,
+) -> bool {
+	!OpenedLanes::<T, I>::contains_key(lane_id) ||
+		Pallet::<T, I>::is_halted() ||
+		LaneMode::<T, I>::get(lane_id).is_halted() ||
+		has_outbound_lane_progress_stalled::<T, I>(lane_id, block_number)
+}
+
+/// Returns true if the outbound lane has queued messages, but has not received any delivery
+/// confirmations for `MessageExpiryBlocks` blocks.
+fn has_outbound_lane_progress_stalled<T: Config<I>, I: 'static>(
+	lane_id: &LaneId,
+	block_number: T::BlockNumber,
+) -> bool {
+	let expiry_blocks = T::MessageExpiryBlocks::get();
+	if expiry_blocks.is_zero() {
+		return false
+	}
+
+	OutboundLaneProgressedAt::<T, I>::get(lane_id)
+		.map(|progressed_at| block_number >= progressed_at.saturating_add(expiry_blocks))
+		.unwrap_or(false)
+}
+
+/// Remember that the fee of the outbound message has been refunded.
+fn remember_refunded_outbound_nonce<T: Config<I>, I: 'static>(
+	lane_id: &LaneId,
+	nonce: MessageNonce,
+) {
+	LatestRefundedOutboundNonce::<T, I>::mutate(lane_id, |latest_refunded_nonce| {
+		*latest_refunded_nonce = sp_std::cmp::max(*latest_refunded_nonce, nonce);
+	});
+}
+
+/// Report confirmed messages, which fee has been refunded, but which have been dispatched at
+/// the bridged chain.
+///
+/// Refunded messages are replaced with empty messages, which can't be dispatched at the bridged
+/// chain. So if the bridged chain reports that such message has been dispatched, the original
+/// message has been delivered before it has been cancelled or expired. The fee of refunded
+/// message is zero, so the relayer isn't rewarded for its delivery.
+fn report_dispatched_refunded_messages<T: Config<I>, I: 'static>(
+	lane_id: &LaneId,
+	confirmed_messages: &DeliveredMessages,
+) {
+	let latest_refunded_nonce = LatestRefundedOutboundNonce::<T, I>::get(lane_id);
+	if confirmed_messages.begin > latest_refunded_nonce {
+		return
+	}
+
+	let end = sp_std::cmp::min(confirmed_messages.end, latest_refunded_nonce);
+	for nonce in confirmed_messages.begin..=end {
+		if confirmed_messages.get_dispatch_result(nonce) != Some(true) {
+			continue
+		}
+
+		let is_refunded = OutboundMessages::<T, I>::get(MessageKey { lane_id: *lane_id, nonce })
+			.map(|message_data| message_data.payload.is_empty() && message_data.fee.is_zero())
+			.unwrap_or(false);
+		if is_refunded {
+			log::trace!(
+				target: LOG_TARGET,
+				"Refunded message {:?}/{:?} has been dispatched at the bridged chain",
+				lane_id,
+				nonce,
+			);
+
+			Pallet::<T, I>::deposit_event(Event::RefundedMessageDispatched {
+				lane_id: *lane_id,
+				nonce,
+			});
+		}
+	}
+}
+
+/// Expire outbound messages that have not been delivered within `MessageExpiryBlocks` blocks.
+///
+/// Only messages of stalled lanes (which have queued messages, but have not received any
+/// delivery confirmations for `MessageExpiryBlocks` blocks) are expired. Only nonces above the
+/// `latest_received_nonce` of the outbound lane (the last delivered nonce of the bridged inbound
+/// lane, proven by the latest accepted delivery proof) are checked. Messages of halted lanes (or
+/// of the halted pallet) are not expired. Messages that have been sent before the expiry has
+/// been enabled never expire.
+///
+/// Lanes are processed until the `remaining_weight` is exhausted. Every call starts with the
+/// lane that follows the last fully processed lane, so all lanes are eventually checked.
+fn expire_outbound_messages<T: Config<I>, I: 'static>(
+	block_number: T::BlockNumber,
+	remaining_weight: frame_support::weights::Weight,
+) -> frame_support::weights::Weight {
+	let expiry_blocks = T::MessageExpiryBlocks::get();
+	if expiry_blocks.is_zero() {
+		return 0
+	}
+
+	let db_weight = T::DbWeight::get();
+	// lane: opened lanes entry, lane mode, lane progress block, outbound lane data and latest
+	// checked nonce + update of the latest checked nonce and the latest refunded nonce
+	let lane_weight = db_weight.reads_writes(5, 2);
+	// message: sent-at block, message data, submitter and refund + update of message data,
+	// submitter and sent-at block
+	let message_weight = db_weight.reads_writes(4, 4);
+	// operating mode and latest checked lane + update of the latest checked lane
+	let mut used_weight = db_weight.reads_writes(2, 1);
+	if used_weight > remaining_weight {
+		return 0
+	}
+	if Pallet::<T, I>::is_halted() {
+		return used_weight
+	}
+
+	// start with lanes that follow the latest checked lane and then wrap around to lanes that
+	// precede it (including the latest checked lane itself)
+	let start_key = LatestExpiryCheckedLane::<T, I>::get()
+		.map(OpenedLanes::<T, I>::hashed_key_for)
+		.unwrap_or_else(|| OpenedLanes::<T, I>::final_prefix().to_vec());
+	let wrapped_lanes = {
+		let start_key = start_key.clone();
+		OpenedLanes::<T, I>::iter_keys()
+			.take_while(move |lane_id| OpenedLanes::<T, I>::hashed_key_for(lane_id) <= start_key)
+	};
+	let mut latest_checked_lane = None;
+	for lane_id in OpenedLanes::<T, I>::iter_keys_from(start_key).chain(wrapped_lanes) {
+		if used_weight.saturating_add(lane_weight).saturating_add(message_weight) > remaining_weight
+		{
+			break
+		}
+		used_weight = used_weight.saturating_add(lane_weight);
+		if LaneMode::<T, I>::get(lane_id).is_halted() ||
+			!has_outbound_lane_progress_stalled::<T, I>(&lane_id, block_number)
+		{
+			latest_checked_lane = Some(lane_id);
+			continue
+		}
+
+		let lane_data = OutboundLanes::<T, I>::get(lane_id);
+		let latest_checked_nonce = LatestExpiryCheckedNonce::<T, I>::get(lane_id);
+		let mut nonce = sp_std::cmp::max(latest_checked_nonce, lane_data.latest_received_nonce) + 1;
+		let mut expired_nonces: Option<(MessageNonce, MessageNonce)> = None;
+		let mut is_lane_checked = true;
+		while nonce <= lane_data.latest_generated_nonce {
+			if used_weight.saturating_add(message_weight) > remaining_weight {
+				is_lane_checked = false;
+				break
+			}
+			used_weight = used_weight.saturating_add(message_weight);
+
+			// messages are sent in the nonces order, so if this message is not yet expired, all
+			// following messages are also not expired. If we have failed to refund the message
+			// fee, we'll retry later - following messages must not expire before this message
+			let message_key = MessageKey { lane_id, nonce };
+			let is_expired = match OutboundMessageSentAt::<T, I>::get(message_key) {
+				Some(sent_at) if block_number < sent_at.saturating_add(expiry_blocks) => break,
+				Some(_) if !expire_outbound_message::<T, I>(message_key) => break,
+				Some(_) => true,
+				None => false,
+			};
+
+			// every event covers contiguous range of expired messages
+			if is_expired {
+				let begin = expired_nonces.map(|(begin, _)| begin).unwrap_or(nonce);
+				expired_nonces = Some((begin, nonce));
+			} else if let Some((begin, end)) = expired_nonces.take() {
+				remember_refunded_outbound_nonce::<T, I>(&lane_id, end);
+				Pallet::<T, I>::deposit_event(Event::MessagesExpired { lane_id, begin, end });
+			}
+
+			nonce += 1;
+		}
+
+		if let Some((begin, end)) = expired_nonces {
+			remember_refunded_outbound_nonce::<T, I>(&lane_id, end);
+			Pallet::<T, I>::deposit_event(Event::MessagesExpired { lane_id, begin, end });
+		}
+		if nonce - 1 > latest_checked_nonce {
+			LatestExpiryCheckedNonce::<T, I>::insert(lane_id, nonce - 1);
+		}
+		if !is_lane_checked {
+			break
+		}
+		latest_checked_lane = Some(lane_id);
+	}
+
+	if let Some(latest_checked_lane) = latest_checked_lane {
+		LatestExpiryCheckedLane::<T, I>::put(latest_checked_lane);
+	}
+
+	used_weight
+}
+
+/// Expire single outbound message.
+///
+/// The message fee is refunded to the submitter and the message is replaced with an empty
+/// message with zero fee. Returns false if the message has not been expired, because its fee
+/// can't be refunded. Such message stays in the queue and is delivered as usual, unless its
+/// fee is refunded later.
+fn expire_outbound_message<T: Config<I>, I: 'static>(message_key: MessageKey) -> bool {
+	let message_data = match OutboundMessages::<T, I>::get(message_key) {
+		Some(message_data) => message_data,
+		None => return false,
+	};
+
+	let submitter = OutboundMessageSubmitters::<T, I>::get(message_key);
+	let refund_origin = submitter.as_ref().and_then(T::MessageSubmitter::refund_origin);
+	if let Some(refund_origin) = refund_origin {
+		let refund_result = T::MessageDeliveryAndDispatchPayment::refund_delivery_and_dispatch_fee(
+			&refund_origin,
+			&message_data.fee,
+		);
+		if let Err(err) = refund_result {
+			log::warn!(
+				target: LOG_TARGET,
+				"Failed to refund fee {:?} of the expired message {:?}/{:?}: {:?}. The message \
+				is kept in the queue",
+				message_data.fee,
+				message_key.lane_id,
+				message_key.nonce,
+				err,
+			);
+			return false
+		}
+	} else {
+		log::trace!(
+			target: LOG_TARGET,
+			"Submitter of the expired message {:?}/{:?} is unknown. Fee {:?} is not refunded",
+			message_key.lane_id,
+			message_key.nonce,
+			message_data.fee,
+		);
+	}
+
+	OutboundMessages::<T, I>::insert(
+		message_key,
+		StoredMessageData(MessageData { payload: Vec::new(), fee: Zero::zero() }),
+	);
+	OutboundMessageSubmitters::<T, I>::remove(message_key);
+	OutboundMessageSentAt::<T, I>::remove(message_key);
+
+	true
+}
+
+/// Convert message verification error into pallet error.
+///
+/// Errors that have no dedicated pallet error are converted into the `default_error`.
+fn verification_error<T: Config<I>, I: 'static>(
+	error: VerificationError,
+	default_error: Error<T, I>,
+) -> Error<T, I> {
+	match error {
+		VerificationError::TooLowFee => Error::<T, I>::TooLowFee,
+		VerificationError::ClosedLane => Error::<T, I>::LaneClosed,
+		VerificationError::TooManyPendingMessages => Error::<T, I>::TooManyPendingMessages,
+		VerificationError::MessageTooLarge => Error::<T, I>::MessageIsTooLarge,
+		VerificationError::InvalidDispatchWeight => Error::<T, I>::InvalidMessageDispatchWeight,
+		VerificationError::InvalidSourceOrigin => Error::<T, I>::InvalidSourceOrigin,
+		_ => default_error,
+	}
+}
+
+/// Ensure that the pallet accepts new outbound messages.
+fn ensure_outbound_messages_accepted<T: Config<I>, I: 'static>() -> Result<(), Error<T, I>> {
+	match PalletOperatingMode::<T, I>::get() {
+		MessagesOperatingMode::Basic(BasicOperatingMode::Normal) |
+		MessagesOperatingMode::RejectingInboundMessages => Ok(()),
+		_ => Err(Error::<T, I>::NotOperatingNormally),
+	}
+}
+
+/// Ensure that the pallet accepts new inbound messages.
+///
+/// It doesn't check whether the pallet is halted - callers must do that themselves.
+fn ensure_inbound_messages_accepted<T: Config<I>, I: 'static>() -> Result<(), Error<T, I>> {
+	if PalletOperatingMode::<T, I>::get() == MessagesOperatingMode::RejectingInboundMessages {
+		return Err(Error::<T, I>::NotAcceptingInboundMessages)
+	}
+
+	Ok(())
+}
+
+/// Ensure that the given lane accepts new outbound messages.
+fn ensure_lane_outbound_messages_accepted<T: Config<I>, I: 'static>(
+	lane_id: &LaneId,
+) -> Result<(), Error<T, I>> {
+	match LaneMode::<T, I>::get(lane_id) {
+		MessagesOperatingMode::Basic(BasicOperatingMode::Normal) |
+		MessagesOperatingMode::RejectingInboundMessages => Ok(()),
+		MessagesOperatingMode::Basic(BasicOperatingMode::Halted) => Err(Error::<T, I>::LaneHalted),
+		MessagesOperatingMode::RejectingOutboundMessages =>
+			Err(Error::<T, I>::LaneNotAcceptingOutboundMessages),
+	}
+}
+
+/// Ensure that the given lane accepts new inbound messages.
+fn ensure_lane_inbound_messages_accepted<T: Config<I>, I: 'static>(
+	lane_id: &LaneId,
+) -> Result<(), Error<T, I>> {
+	match LaneMode::<T, I>::get(lane_id) {
+		MessagesOperatingMode::Basic(BasicOperatingMode::Normal) |
+		MessagesOperatingMode::RejectingOutboundMessages => Ok(()),
+		MessagesOperatingMode::Basic(BasicOperatingMode::Halted) => Err(Error::<T, I>::LaneHalted),
+		MessagesOperatingMode::RejectingInboundMessages =>
+			Err(Error::<T, I>::LaneNotAcceptingInboundMessages),
+	}
+}
+
+/// Record message event in the offchain index, if messages history indexing is enabled.
+fn index_message_event<T: Config<I>, I: 'static>(
+	lane_id: &LaneId,
+	nonce: MessageNonce,
+	kind: MessageEventKind,
+) {
+	if !T::IndexMessagesHistory::get() {
+		return
 	}
 
-	Err(Error::<T, I>::NotOperatingNormally)
+	let key = bp_messages::storage_keys::message_history_index_key(
+		<Pallet<T, I> as PalletInfoAccess>::name(),
+		lane_id,
+		nonce,
+		kind,
+	);
+	let value = (frame_system::Pallet::<T>::block_number(), kind).encode();
+	sp_io::offchain_index::set(&key, &value);
 }
 
 /// Creates new inbound lane object, backed by runtime storage.
@@ -1076,15 +2329,48 @@ impl<T: Config<I>, I: 'static> OutboundLaneStorage for RuntimeOutboundLaneStorag
 	}
 
 	fn remove_message(&mut self, nonce: &MessageNonce) {
-		OutboundMessages::<T, I>::remove(MessageKey { lane_id: self.lane_id, nonce: *nonce });
+		let key = MessageKey { lane_id: self.lane_id, nonce: *nonce };
+		OutboundMessages::<T, I>::remove(&key);
+		OutboundMessageSubmitters::<T, I>::remove(&key);
+		OutboundMessageSentAt::<T, I>::remove(&key);
+	}
+}
+
+/// Inbound message, which has been proved by the bridged chain.
+enum InboundMessage<DispatchPayload, Fee> {
+	/// Message with decoded payload, which may be dispatched.
+	Regular(DispatchMessage<DispatchPayload, Fee>),
+	/// Message with payload that exceeds maximal inbound payload size. The payload of such
+	/// message is never decoded and the message itself is never dispatched.
+	Oversized {
+		/// Message key.
+		key: MessageKey,
+		/// Size of the message payload.
+		payload_size: u32,
+	},
+}
+
+impl<DispatchPayload: Decode, Fee> InboundMessage<DispatchPayload, Fee> {
+	/// Check message payload size and decode it if it fits the limit.
+	fn new(message: Message<Fee>, max_payload_size: u32) -> Self {
+		let payload_size = u32::try_from(message.data.payload.len()).unwrap_or(u32::MAX);
+		if payload_size > max_payload_size {
+			InboundMessage::Oversized { key: message.key, payload_size }
+		} else {
+			InboundMessage::Regular(message.into())
+		}
 	}
 }
 
 /// Verify messages proof and return proved messages with decoded payload.
+///
+/// Messages with payloads that exceed the `max_payload_size` limit of their lane are not
+/// decoded.
 fn verify_and_decode_messages_proof<Chain: SourceHeaderChain<Fee>, Fee, DispatchPayload: Decode>(
 	proof: Chain::MessagesProof,
 	messages_count: u32,
-) -> Result<ProvedMessages<DispatchMessage<DispatchPayload, Fee>>, Chain::Error> {
+	max_payload_size: impl Fn(LaneId) -> u32,
+) -> Result<ProvedMessages<InboundMessage<DispatchPayload, Fee>>, Chain::Error> {
 	// `receive_messages_proof` weight formula and `MaxUnconfirmedMessagesAtInboundLane` check
 	// guarantees that the `message_count` is sane and Vec<Message> may be allocated.
 	// (tx with too many messages will either be rejected from the pool, or will fail earlier)
@@ -1092,11 +2378,16 @@ fn verify_and_decode_messages_proof<Chain: SourceHeaderChain<Fee>, Fee, Dispatch
 		messages_by_lane
 			.into_iter()
 			.map(|(lane, lane_data)| {
+				let max_payload_size = max_payload_size(lane);
 				(
 					lane,
 					ProvedLaneMessages {
 						lane_state: lane_data.lane_state,
-						messages: lane_data.messages.into_iter().map(Into::into).collect(),
+						messages: lane_data
+							.messages
+							.into_iter()
+							.map(|message| InboundMessage::new(message, max_payload_size))
+							.collect(),
 					},
 				)
 			})
@@ -1108,18 +2399,23 @@ fn verify_and_decode_messages_proof<Chain: SourceHeaderChain<Fee>, Fee, Dispatch
 mod tests {
 	use super::*;
 	use crate::mock::{
-		message, message_payload, run_test, unrewarded_relayer, Balance, Event as TestEvent,
-		Origin, TestMessageDeliveryAndDispatchPayment, TestMessagesDeliveryProof,
-		TestMessagesParameter, TestMessagesProof, TestOnDeliveryConfirmed1,
-		TestOnDeliveryConfirmed2, TestOnMessageAccepted, TestRuntime, TokenConversionRate,
-		MAX_OUTBOUND_PAYLOAD_SIZE, PAYLOAD_REJECTED_BY_TARGET_CHAIN, REGULAR_PAYLOAD, TEST_LANE_ID,
-		TEST_RELAYER_A, TEST_RELAYER_B,
+		message, message_payload, new_test_ext, run_test, unrewarded_relayer, AccountId, Balance,
+		Event as TestEvent, MaxUnrewardedRelayerEntriesAtInboundLane, Origin,
+		TestIndexMessagesHistory, TestMaxMessagesDeliveryProofSize, TestMaxMessagesProofSize,
+		TestMaxOutboundQueueLen, TestMessageDeliveryAndDispatchPayment, TestMessageExpiryBlocks,
+		TestMessagesDeliveryProof, TestMessagesParameter, TestMessagesProof,
+		TestOnDeliveryConfirmed1, TestOnDeliveryConfirmed2, TestOnMessageAccepted,
+		TestOutboundMessagesRateLimit, TestRuntime, TokenConversionRate, MAX_INBOUND_PAYLOAD_SIZE,
+		MAX_OUTBOUND_PAYLOAD_SIZE, PAYLOAD_REJECTED_BY_TARGET_CHAIN, REGULAR_PAYLOAD, TEST_ERROR,
+		TEST_LANE_ID, TEST_RELAYER_A, TEST_RELAYER_B, TEST_RELAYER_C,
 	};
 	use bp_messages::{UnrewardedRelayer, UnrewardedRelayersState};
 	use bp_test_utils::generate_owned_bridge_module_tests;
 	use frame_support::{
 		assert_noop, assert_ok,
+		dispatch::DispatchResultWithPostInfo,
 		storage::generator::{StorageMap, StorageValue},
+		traits::Hooks,
 		weights::Weight,
 	};
 	use frame_system::{EventRecord, Pallet as System, Phase};
@@ -1133,18 +2429,7 @@ mod tests {
 	fn inbound_unrewarded_relayers_state(
 		lane: bp_messages::LaneId,
 	) -> bp_messages::UnrewardedRelayersState {
-		let inbound_lane_data = InboundLanes::<TestRuntime, ()>::get(lane).0;
-		let last_delivered_nonce = inbound_lane_data.last_delivered_nonce();
-		let relayers = inbound_lane_data.relayers;
-		bp_messages::UnrewardedRelayersState {
-			unrewarded_relayer_entries: relayers.len() as _,
-			messages_in_oldest_entry: relayers
-				.front()
-				.map(|entry| 1 + entry.messages.end - entry.messages.begin)
-				.unwrap_or(0),
-			total_messages: total_unrewarded_messages(&relayers).unwrap_or(MessageNonce::MAX),
-			last_delivered_nonce,
-		}
+		(&InboundLanes::<TestRuntime, ()>::get(lane).0).into()
 	}
 
 	fn send_regular_message() -> Weight {
@@ -1436,94 +2721,1346 @@ mod tests {
 	}
 
 	#[test]
-	fn send_message_works() {
+	fn pallet_rejects_delivery_in_rejecting_inbound_messages_operating_mode() {
 		run_test(|| {
+			// send message first to be able to check that delivery_proof works later
 			send_regular_message();
-		});
-	}
 
-	#[test]
-	fn send_message_rejects_too_large_message() {
-		run_test(|| {
-			let mut message_payload = message_payload(1, 0);
-			// the payload isn't simply extra, so it'll definitely overflow
-			// `MAX_OUTBOUND_PAYLOAD_SIZE` if we add `MAX_OUTBOUND_PAYLOAD_SIZE` bytes to extra
-			message_payload
-				.extra
-				.extend_from_slice(&[0u8; MAX_OUTBOUND_PAYLOAD_SIZE as usize]);
-			assert_noop!(
-				Pallet::<TestRuntime>::send_message(
-					Origin::signed(1),
-					TEST_LANE_ID,
-					message_payload.clone(),
-					Balance::MAX,
-				),
-				Error::<TestRuntime, ()>::MessageIsTooLarge,
-			);
+			assert_ok!(Pallet::<TestRuntime>::set_operating_mode(
+				Origin::root(),
+				MessagesOperatingMode::RejectingInboundMessages,
+			));
 
-			// let's check that we're able to send `MAX_OUTBOUND_PAYLOAD_SIZE` messages
-			while message_payload.size() > MAX_OUTBOUND_PAYLOAD_SIZE {
-				message_payload.extra.pop();
-			}
-			assert_eq!(message_payload.size(), MAX_OUTBOUND_PAYLOAD_SIZE);
 			assert_ok!(Pallet::<TestRuntime>::send_message(
 				Origin::signed(1),
 				TEST_LANE_ID,
-				message_payload,
-				Balance::MAX,
-			),);
-		})
-	}
+				REGULAR_PAYLOAD,
+				REGULAR_PAYLOAD.declared_weight,
+			));
+
+			assert_ok!(Pallet::<TestRuntime>::increase_message_fee(
+				Origin::signed(1),
+				TEST_LANE_ID,
+				1,
+				1,
+			));
 
-	#[test]
-	fn chain_verifier_rejects_invalid_message_in_send_message() {
-		run_test(|| {
-			// messages with this payload are rejected by target chain verifier
 			assert_noop!(
-				Pallet::<TestRuntime>::send_message(
+				Pallet::<TestRuntime>::receive_messages_proof(
 					Origin::signed(1),
-					TEST_LANE_ID,
-					PAYLOAD_REJECTED_BY_TARGET_CHAIN,
-					PAYLOAD_REJECTED_BY_TARGET_CHAIN.declared_weight
+					TEST_RELAYER_A,
+					Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+					1,
+					REGULAR_PAYLOAD.declared_weight,
 				),
-				Error::<TestRuntime, ()>::MessageRejectedByChainVerifier,
+				Error::<TestRuntime, ()>::NotAcceptingInboundMessages,
 			);
+
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
+				Origin::signed(1),
+				TestMessagesDeliveryProof(Ok((
+					TEST_LANE_ID,
+					InboundLaneData {
+						last_confirmed_nonce: 1,
+						relayers: vec![unrewarded_relayer(1, 1, TEST_RELAYER_A)]
+							.into_iter()
+							.collect(),
+					},
+				))),
+				UnrewardedRelayersState {
+					unrewarded_relayer_entries: 1,
+					messages_in_oldest_entry: 1,
+					total_messages: 1,
+					last_delivered_nonce: 1,
+				},
+			));
 		});
 	}
 
 	#[test]
-	fn lane_verifier_rejects_invalid_message_in_send_message() {
+	fn operating_mode_change_is_reported() {
 		run_test(|| {
-			// messages with zero fee are rejected by lane verifier
-			assert_noop!(
-				Pallet::<TestRuntime>::send_message(
-					Origin::signed(1),
-					TEST_LANE_ID,
-					REGULAR_PAYLOAD,
-					0
-				),
-				Error::<TestRuntime, ()>::MessageRejectedByLaneVerifier,
+			get_ready_for_events();
+
+			assert_ok!(Pallet::<TestRuntime>::set_operating_mode(
+				Origin::root(),
+				MessagesOperatingMode::RejectingOutboundMessages,
+			));
+
+			assert_eq!(
+				PalletOperatingMode::<TestRuntime, ()>::get(),
+				MessagesOperatingMode::RejectingOutboundMessages,
+			);
+			assert_eq!(
+				System::<TestRuntime>::events(),
+				vec![EventRecord {
+					phase: Phase::Initialization,
+					event: TestEvent::Messages(Event::OperatingModeChanged {
+						old_operating_mode: MessagesOperatingMode::Basic(
+							BasicOperatingMode::Normal
+						),
+						new_operating_mode: MessagesOperatingMode::RejectingOutboundMessages,
+					}),
+					topics: vec![],
+				}],
 			);
 		});
 	}
 
 	#[test]
-	fn message_send_fails_if_submitter_cant_pay_message_fee() {
+	fn invalid_operating_mode_transition_is_rejected() {
 		run_test(|| {
-			TestMessageDeliveryAndDispatchPayment::reject_payments();
+			assert_ok!(Pallet::<TestRuntime>::set_operating_mode(
+				Origin::root(),
+				MessagesOperatingMode::RejectingInboundMessages,
+			));
+
 			assert_noop!(
-				Pallet::<TestRuntime>::send_message(
-					Origin::signed(1),
-					TEST_LANE_ID,
-					REGULAR_PAYLOAD,
-					REGULAR_PAYLOAD.declared_weight
+				Pallet::<TestRuntime>::set_operating_mode(
+					Origin::root(),
+					MessagesOperatingMode::RejectingOutboundMessages,
+				),
+				Error::<TestRuntime, ()>::BridgeModule(
+					bp_runtime::OwnedBridgeModuleError::InvalidOperatingModeTransition
 				),
-				Error::<TestRuntime, ()>::FailedToWithdrawMessageFee,
 			);
+
+			// but it is always possible to halt the pallet or to resume normal operations
+			assert_ok!(Pallet::<TestRuntime>::set_operating_mode(
+				Origin::root(),
+				MessagesOperatingMode::Basic(BasicOperatingMode::Halted),
+			));
+			assert_ok!(Pallet::<TestRuntime>::set_operating_mode(
+				Origin::root(),
+				MessagesOperatingMode::Basic(BasicOperatingMode::Normal),
+			));
 		});
 	}
 
-	#[test]
+	fn delivery_proof_of_first_message() -> (TestMessagesDeliveryProof, UnrewardedRelayersState) {
+		(
+			TestMessagesDeliveryProof(Ok((
+				TEST_LANE_ID,
+				InboundLaneData {
+					last_confirmed_nonce: 1,
+					relayers: vec![unrewarded_relayer(1, 1, TEST_RELAYER_A)].into_iter().collect(),
+				},
+			))),
+			UnrewardedRelayersState {
+				unrewarded_relayer_entries: 1,
+				messages_in_oldest_entry: 1,
+				total_messages: 1,
+				last_delivered_nonce: 1,
+			},
+		)
+	}
+
+	fn delivery_proof_of_first_undispatched_message(
+	) -> (TestMessagesDeliveryProof, UnrewardedRelayersState) {
+		let (mut proof, relayers_state) = delivery_proof_of_first_message();
+		if let Ok((_, ref mut lane_data)) = proof.0 {
+			lane_data.relayers[0].messages = DeliveredMessages::new(1, false);
+		}
+		(proof, relayers_state)
+	}
+
+	#[test]
+	fn halted_lane_rejects_transactions_while_pallet_is_operating_normally() {
+		run_test(|| {
+			// send message first to be able to check that delivery_proof fails later
+			send_regular_message();
+
+			let other_lane_id = LaneId([0, 0, 0, 2]);
+			OpenedLanes::<TestRuntime, ()>::insert(other_lane_id, ());
+			LaneMode::<TestRuntime, ()>::insert(
+				TEST_LANE_ID,
+				MessagesOperatingMode::Basic(BasicOperatingMode::Halted),
+			);
+
+			assert_noop!(
+				Pallet::<TestRuntime>::send_message(
+					Origin::signed(1),
+					TEST_LANE_ID,
+					REGULAR_PAYLOAD,
+					REGULAR_PAYLOAD.declared_weight,
+				),
+				Error::<TestRuntime, ()>::LaneHalted,
+			);
+
+			assert_noop!(
+				Pallet::<TestRuntime>::receive_messages_proof(
+					Origin::signed(1),
+					TEST_RELAYER_A,
+					Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+					1,
+					REGULAR_PAYLOAD.declared_weight,
+				),
+				Error::<TestRuntime, ()>::LaneHalted,
+			);
+
+			let (proof, relayers_state) = delivery_proof_of_first_message();
+			assert_noop!(
+				Pallet::<TestRuntime>::receive_messages_delivery_proof(
+					Origin::signed(1),
+					proof,
+					relayers_state,
+				),
+				Error::<TestRuntime, ()>::LaneHalted,
+			);
+
+			// other lanes are still operating normally
+			assert_ok!(Pallet::<TestRuntime>::send_message(
+				Origin::signed(1),
+				other_lane_id,
+				REGULAR_PAYLOAD,
+				REGULAR_PAYLOAD.declared_weight,
+			));
+		});
+	}
+
+	#[test]
+	fn normal_lane_rejects_transactions_while_pallet_is_halted() {
+		run_test(|| {
+			// send message first to be able to check that delivery_proof fails later
+			send_regular_message();
+
+			assert_ok!(Pallet::<TestRuntime>::set_lane_operating_mode(
+				Origin::root(),
+				TEST_LANE_ID,
+				MessagesOperatingMode::Basic(BasicOperatingMode::Normal),
+			));
+			PalletOperatingMode::<TestRuntime, ()>::put(MessagesOperatingMode::Basic(
+				BasicOperatingMode::Halted,
+			));
+
+			assert_noop!(
+				Pallet::<TestRuntime>::send_message(
+					Origin::signed(1),
+					TEST_LANE_ID,
+					REGULAR_PAYLOAD,
+					REGULAR_PAYLOAD.declared_weight,
+				),
+				Error::<TestRuntime, ()>::NotOperatingNormally,
+			);
+
+			assert_noop!(
+				Pallet::<TestRuntime>::receive_messages_proof(
+					Origin::signed(1),
+					TEST_RELAYER_A,
+					Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+					1,
+					REGULAR_PAYLOAD.declared_weight,
+				),
+				Error::<TestRuntime, ()>::BridgeModule(bp_runtime::OwnedBridgeModuleError::Halted),
+			);
+
+			let (proof, relayers_state) = delivery_proof_of_first_message();
+			assert_noop!(
+				Pallet::<TestRuntime>::receive_messages_delivery_proof(
+					Origin::signed(1),
+					proof,
+					relayers_state,
+				),
+				Error::<TestRuntime, ()>::BridgeModule(bp_runtime::OwnedBridgeModuleError::Halted),
+			);
+		});
+	}
+
+	#[test]
+	fn lane_rejects_new_messages_in_rejecting_outbound_messages_operating_mode() {
+		run_test(|| {
+			send_regular_message();
+
+			assert_ok!(Pallet::<TestRuntime>::set_lane_operating_mode(
+				Origin::root(),
+				TEST_LANE_ID,
+				MessagesOperatingMode::RejectingOutboundMessages,
+			));
+
+			assert_noop!(
+				Pallet::<TestRuntime>::send_message(
+					Origin::signed(1),
+					TEST_LANE_ID,
+					REGULAR_PAYLOAD,
+					REGULAR_PAYLOAD.declared_weight,
+				),
+				Error::<TestRuntime, ()>::LaneNotAcceptingOutboundMessages,
+			);
+
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+				1,
+				REGULAR_PAYLOAD.declared_weight,
+			));
+
+			let (proof, relayers_state) = delivery_proof_of_first_message();
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
+				Origin::signed(1),
+				proof,
+				relayers_state,
+			));
+		});
+	}
+
+	#[test]
+	fn lane_rejects_delivery_in_rejecting_inbound_messages_operating_mode() {
+		run_test(|| {
+			assert_ok!(Pallet::<TestRuntime>::set_lane_operating_mode(
+				Origin::root(),
+				TEST_LANE_ID,
+				MessagesOperatingMode::RejectingInboundMessages,
+			));
+
+			assert_ok!(Pallet::<TestRuntime>::send_message(
+				Origin::signed(1),
+				TEST_LANE_ID,
+				REGULAR_PAYLOAD,
+				REGULAR_PAYLOAD.declared_weight,
+			));
+
+			assert_noop!(
+				Pallet::<TestRuntime>::receive_messages_proof(
+					Origin::signed(1),
+					TEST_RELAYER_A,
+					Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+					1,
+					REGULAR_PAYLOAD.declared_weight,
+				),
+				Error::<TestRuntime, ()>::LaneNotAcceptingInboundMessages,
+			);
+
+			let (proof, relayers_state) = delivery_proof_of_first_message();
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
+				Origin::signed(1),
+				proof,
+				relayers_state,
+			));
+		});
+	}
+
+	#[test]
+	fn lane_operating_mode_may_be_changed_by_owner() {
+		run_test(|| {
+			get_ready_for_events();
+			PalletOwner::<TestRuntime>::put(2);
+
+			assert_noop!(
+				Pallet::<TestRuntime>::set_lane_operating_mode(
+					Origin::signed(1),
+					TEST_LANE_ID,
+					MessagesOperatingMode::Basic(BasicOperatingMode::Halted),
+				),
+				DispatchError::BadOrigin,
+			);
+
+			assert_ok!(Pallet::<TestRuntime>::set_lane_operating_mode(
+				Origin::signed(2),
+				TEST_LANE_ID,
+				MessagesOperatingMode::RejectingInboundMessages,
+			));
+			assert_eq!(
+				LaneMode::<TestRuntime, ()>::get(TEST_LANE_ID),
+				MessagesOperatingMode::RejectingInboundMessages,
+			);
+			assert_noop!(
+				Pallet::<TestRuntime>::set_lane_operating_mode(
+					Origin::signed(2),
+					TEST_LANE_ID,
+					MessagesOperatingMode::RejectingOutboundMessages,
+				),
+				Error::<TestRuntime, ()>::BridgeModule(
+					bp_runtime::OwnedBridgeModuleError::InvalidOperatingModeTransition
+				),
+			);
+
+			// resuming normal operations removes the lane entry
+			assert_ok!(Pallet::<TestRuntime>::set_lane_operating_mode(
+				Origin::signed(2),
+				TEST_LANE_ID,
+				MessagesOperatingMode::Basic(BasicOperatingMode::Normal),
+			));
+			assert!(!LaneMode::<TestRuntime, ()>::contains_key(TEST_LANE_ID));
+
+			assert_eq!(
+				System::<TestRuntime>::events()
+					.into_iter()
+					.map(|event_record| event_record.event)
+					.collect::<Vec<_>>(),
+				vec![
+					TestEvent::Messages(Event::LaneOperatingModeChanged {
+						lane_id: TEST_LANE_ID,
+						old_operating_mode: MessagesOperatingMode::Basic(
+							BasicOperatingMode::Normal
+						),
+						new_operating_mode: MessagesOperatingMode::RejectingInboundMessages,
+					}),
+					TestEvent::Messages(Event::LaneOperatingModeChanged {
+						lane_id: TEST_LANE_ID,
+						old_operating_mode: MessagesOperatingMode::RejectingInboundMessages,
+						new_operating_mode: MessagesOperatingMode::Basic(
+							BasicOperatingMode::Normal
+						),
+					}),
+				],
+			);
+		});
+	}
+
+	#[test]
+	fn send_message_works() {
+		run_test(|| {
+			send_regular_message();
+		});
+	}
+
+	#[test]
+	fn send_message_records_message_submitter() {
+		run_test(|| {
+			send_regular_message();
+			assert_eq!(Pallet::<TestRuntime>::outbound_message_submitter(TEST_LANE_ID, 1), Some(1));
+			assert_eq!(Pallet::<TestRuntime>::outbound_message_submitter(TEST_LANE_ID, 2), None);
+		});
+	}
+
+	#[test]
+	fn message_submitter_is_removed_when_message_is_pruned() {
+		run_test(|| {
+			send_regular_message();
+			OutboundLanes::<TestRuntime>::mutate(TEST_LANE_ID, |data| {
+				data.latest_received_nonce = 1
+			});
+
+			assert_eq!(outbound_lane::<TestRuntime, ()>(TEST_LANE_ID).prune_messages(1), 1);
+			assert_eq!(Pallet::<TestRuntime>::outbound_message_data(TEST_LANE_ID, 1), None);
+			assert_eq!(Pallet::<TestRuntime>::outbound_message_submitter(TEST_LANE_ID, 1), None);
+		});
+	}
+
+	fn halt_test_lane() {
+		LaneMode::<TestRuntime, ()>::insert(
+			TEST_LANE_ID,
+			MessagesOperatingMode::Basic(BasicOperatingMode::Halted),
+		);
+	}
+
+	#[test]
+	fn cancel_message_works_for_message_submitter() {
+		run_test(|| {
+			send_regular_message();
+			halt_test_lane();
+			System::<TestRuntime>::reset_events();
+
+			assert_ok!(Pallet::<TestRuntime>::cancel_message(Origin::signed(1), TEST_LANE_ID, 1));
+
+			assert!(TestMessageDeliveryAndDispatchPayment::is_fee_refunded(
+				1,
+				REGULAR_PAYLOAD.declared_weight
+			));
+			assert_eq!(
+				Pallet::<TestRuntime>::outbound_message_data(TEST_LANE_ID, 1),
+				Some(MessageData { payload: Vec::new(), fee: 0 }),
+			);
+			assert_eq!(Pallet::<TestRuntime>::outbound_message_submitter(TEST_LANE_ID, 1), None);
+			assert_eq!(
+				System::<TestRuntime>::events(),
+				vec![EventRecord {
+					phase: Phase::Initialization,
+					event: TestEvent::Messages(Event::MessageCancelled {
+						lane_id: TEST_LANE_ID,
+						nonce: 1,
+					}),
+					topics: vec![],
+				}],
+			);
+		});
+	}
+
+	#[test]
+	fn cancel_message_works_for_owner() {
+		run_test(|| {
+			send_regular_message();
+			halt_test_lane();
+			PalletOwner::<TestRuntime>::put(2);
+
+			assert_ok!(Pallet::<TestRuntime>::cancel_message(Origin::signed(2), TEST_LANE_ID, 1));
+
+			// the fee is refunded to the message submitter, not to the owner
+			assert!(TestMessageDeliveryAndDispatchPayment::is_fee_refunded(
+				1,
+				REGULAR_PAYLOAD.declared_weight
+			));
+		});
+	}
+
+	#[test]
+	fn cancel_message_rejects_arbitrary_submitter() {
+		run_test(|| {
+			send_regular_message();
+
+			assert_noop!(
+				Pallet::<TestRuntime>::cancel_message(Origin::signed(2), TEST_LANE_ID, 1),
+				DispatchError::BadOrigin,
+			);
+		});
+	}
+
+	#[test]
+	fn cancel_message_rejects_delivered_and_not_yet_sent_messages() {
+		run_test(|| {
+			send_regular_message();
+			// delivery confirmation has been included before the cancel transaction
+			receive_messages_delivery_proof();
+
+			assert_noop!(
+				Pallet::<TestRuntime>::cancel_message(Origin::root(), TEST_LANE_ID, 1),
+				Error::<TestRuntime, ()>::MessageIsAlreadyDelivered,
+			);
+			assert_noop!(
+				Pallet::<TestRuntime>::cancel_message(Origin::root(), TEST_LANE_ID, 2),
+				Error::<TestRuntime, ()>::MessageIsNotYetSent,
+			);
+		});
+	}
+
+	#[test]
+	fn cancel_message_rejects_message_of_active_lane() {
+		run_test(|| {
+			send_regular_message();
+
+			assert_noop!(
+				Pallet::<TestRuntime>::cancel_message(Origin::signed(1), TEST_LANE_ID, 1),
+				Error::<TestRuntime, ()>::LaneIsNotStalled,
+			);
+		});
+	}
+
+	#[test]
+	fn cancel_message_works_at_closed_lane() {
+		run_test(|| {
+			send_regular_message();
+			OpenedLanes::<TestRuntime, ()>::remove(TEST_LANE_ID);
+
+			assert_ok!(Pallet::<TestRuntime>::cancel_message(Origin::signed(1), TEST_LANE_ID, 1));
+		});
+	}
+
+	#[test]
+	fn cancel_message_works_if_lane_has_not_made_progress_for_expiry_blocks() {
+		run_test(|| {
+			TestMessageExpiryBlocks::set(&10);
+			send_regular_message();
+			send_regular_message();
+
+			// the first message is confirmed at block 5, so the lane is stalled at block 15
+			System::<TestRuntime>::set_block_number(5);
+			let (proof, relayers_state) = delivery_proof_of_first_message();
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
+				Origin::signed(1),
+				proof,
+				relayers_state,
+			));
+			assert_eq!(OutboundLaneProgressedAt::<TestRuntime, ()>::get(TEST_LANE_ID), Some(5));
+
+			System::<TestRuntime>::set_block_number(14);
+			assert_noop!(
+				Pallet::<TestRuntime>::cancel_message(Origin::signed(1), TEST_LANE_ID, 2),
+				Error::<TestRuntime, ()>::LaneIsNotStalled,
+			);
+
+			System::<TestRuntime>::set_block_number(15);
+			assert_ok!(Pallet::<TestRuntime>::cancel_message(Origin::signed(1), TEST_LANE_ID, 2));
+		});
+	}
+
+	#[test]
+	fn outbound_lane_progress_is_forgotten_when_all_messages_are_confirmed() {
+		run_test(|| {
+			TestMessageExpiryBlocks::set(&10);
+			send_regular_message();
+			assert_eq!(OutboundLaneProgressedAt::<TestRuntime, ()>::get(TEST_LANE_ID), Some(1));
+
+			receive_messages_delivery_proof();
+			assert_eq!(OutboundLaneProgressedAt::<TestRuntime, ()>::get(TEST_LANE_ID), None);
+		});
+	}
+
+	#[test]
+	fn cancel_message_rejects_already_cancelled_message() {
+		run_test(|| {
+			send_regular_message();
+			halt_test_lane();
+
+			assert_ok!(Pallet::<TestRuntime>::cancel_message(Origin::signed(1), TEST_LANE_ID, 1));
+			assert_noop!(
+				Pallet::<TestRuntime>::cancel_message(Origin::root(), TEST_LANE_ID, 1),
+				Error::<TestRuntime, ()>::MessageIsAlreadyCancelled,
+			);
+		});
+	}
+
+	#[test]
+	fn cancel_message_fails_if_fee_is_not_refunded() {
+		run_test(|| {
+			send_regular_message();
+			halt_test_lane();
+			TestMessageDeliveryAndDispatchPayment::reject_payments();
+
+			assert_noop!(
+				Pallet::<TestRuntime>::cancel_message(Origin::signed(1), TEST_LANE_ID, 1),
+				Error::<TestRuntime, ()>::FailedToRefundMessageFee,
+			);
+		});
+	}
+
+	#[test]
+	fn cancelled_message_delivery_may_be_confirmed() {
+		run_test(|| {
+			send_regular_message();
+			// the message is cancelled while the lane is halted and the delivery confirmation is
+			// in flight
+			halt_test_lane();
+			assert_ok!(Pallet::<TestRuntime>::cancel_message(Origin::signed(1), TEST_LANE_ID, 1));
+			LaneMode::<TestRuntime, ()>::remove(TEST_LANE_ID);
+
+			// the empty message has been delivered to the bridged chain, where it has failed to
+			// dispatch
+			let (proof, relayers_state) = delivery_proof_of_first_undispatched_message();
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
+				Origin::signed(1),
+				proof,
+				relayers_state,
+			));
+
+			// the lane has advanced, but the relayer isn't rewarded for the cancelled message
+			assert_eq!(
+				OutboundLanes::<TestRuntime, ()>::get(TEST_LANE_ID).latest_received_nonce,
+				1,
+			);
+			assert!(!TestMessageDeliveryAndDispatchPayment::is_reward_paid(
+				TEST_RELAYER_A,
+				REGULAR_PAYLOAD.declared_weight
+			));
+		});
+	}
+
+	#[test]
+	fn delivery_confirmation_of_dispatched_cancelled_message_is_reported() {
+		run_test(|| {
+			send_regular_message();
+			halt_test_lane();
+			assert_ok!(Pallet::<TestRuntime>::cancel_message(Origin::signed(1), TEST_LANE_ID, 1));
+			LaneMode::<TestRuntime, ()>::remove(TEST_LANE_ID);
+
+			// the original message has been delivered and dispatched at the bridged chain before
+			// it has been cancelled here, but the submitter has already got the refund
+			let (proof, relayers_state) = delivery_proof_of_first_message();
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
+				Origin::signed(1),
+				proof,
+				relayers_state,
+			));
+
+			// the lane has advanced, the event is emitted and the relayer isn't rewarded
+			assert_eq!(
+				OutboundLanes::<TestRuntime, ()>::get(TEST_LANE_ID).latest_received_nonce,
+				1,
+			);
+			assert_eq!(refunded_message_dispatched_events(), vec![1]);
+			assert!(!TestMessageDeliveryAndDispatchPayment::is_reward_paid(
+				TEST_RELAYER_A,
+				REGULAR_PAYLOAD.declared_weight
+			));
+		});
+	}
+
+	fn refunded_message_dispatched_events() -> Vec<MessageNonce> {
+		System::<TestRuntime>::events()
+			.into_iter()
+			.filter_map(|record| match record.event {
+				TestEvent::Messages(Event::RefundedMessageDispatched { nonce, .. }) => Some(nonce),
+				_ => None,
+			})
+			.collect()
+	}
+
+	fn messages_expired_events() -> Vec<Event<TestRuntime, ()>> {
+		System::<TestRuntime>::events()
+			.into_iter()
+			.filter_map(|record| match record.event {
+				TestEvent::Messages(event @ Event::MessagesExpired { .. }) => Some(event),
+				_ => None,
+			})
+			.collect()
+	}
+
+	#[test]
+	fn messages_never_expire_if_expiry_is_disabled() {
+		run_test(|| {
+			send_regular_message();
+
+			Pallet::<TestRuntime>::on_idle(1000, Weight::MAX);
+
+			assert_eq!(messages_expired_events(), vec![]);
+			assert_eq!(OutboundMessageSentAt::<TestRuntime>::iter().count(), 0);
+			assert!(!Pallet::<TestRuntime>::outbound_message_data(TEST_LANE_ID, 1)
+				.unwrap()
+				.payload
+				.is_empty());
+		});
+	}
+
+	#[test]
+	fn undelivered_messages_expire_and_their_fees_are_refunded() {
+		run_test(|| {
+			TestMessageExpiryBlocks::set(&10);
+			send_regular_message();
+			send_regular_message();
+
+			// messages are not expired until `MessageExpiryBlocks` blocks have passed
+			Pallet::<TestRuntime>::on_idle(10, Weight::MAX);
+			assert_eq!(messages_expired_events(), vec![]);
+
+			Pallet::<TestRuntime>::on_idle(11, Weight::MAX);
+			assert_eq!(
+				messages_expired_events(),
+				vec![Event::MessagesExpired { lane_id: TEST_LANE_ID, begin: 1, end: 2 }],
+			);
+			assert!(TestMessageDeliveryAndDispatchPayment::is_fee_refunded(
+				1,
+				REGULAR_PAYLOAD.declared_weight
+			));
+			for nonce in 1..=2 {
+				let message_data =
+					Pallet::<TestRuntime>::outbound_message_data(TEST_LANE_ID, nonce).unwrap();
+				assert!(message_data.payload.is_empty());
+				assert_eq!(message_data.fee, 0);
+				assert_eq!(
+					Pallet::<TestRuntime>::outbound_message_submitter(TEST_LANE_ID, nonce),
+					None
+				);
+			}
+
+			// expired nonces are still generated, but not yet received
+			let lane_data = OutboundLanes::<TestRuntime, ()>::get(TEST_LANE_ID);
+			assert_eq!(lane_data.latest_generated_nonce, 2);
+			assert_eq!(lane_data.latest_received_nonce, 0);
+
+			// expired messages are never expired again
+			System::<TestRuntime>::reset_events();
+			Pallet::<TestRuntime>::on_idle(12, Weight::MAX);
+			assert_eq!(messages_expired_events(), vec![]);
+
+			// and fee of expired message can't be increased
+			assert_noop!(
+				Pallet::<TestRuntime>::increase_message_fee(
+					Origin::signed(1),
+					TEST_LANE_ID,
+					1,
+					100
+				),
+				Error::<TestRuntime, ()>::MessageIsAlreadyCancelled,
+			);
+		});
+	}
+
+	#[test]
+	fn messages_are_expired_within_remaining_weight() {
+		run_test(|| {
+			TestMessageExpiryBlocks::set(&10);
+			send_regular_message();
+			send_regular_message();
+			send_regular_message();
+
+			// there's only enough weight to expire single message
+			let db_weight = crate::mock::DbWeight::get();
+			let remaining_weight = db_weight.reads_writes(2, 1) +
+				db_weight.reads_writes(5, 2) +
+				db_weight.reads_writes(4, 4);
+			assert_eq!(Pallet::<TestRuntime>::on_idle(11, remaining_weight), remaining_weight);
+			assert_eq!(
+				messages_expired_events(),
+				vec![Event::MessagesExpired { lane_id: TEST_LANE_ID, begin: 1, end: 1 }],
+			);
+			// the lane has not been fully checked
+			assert_eq!(LatestExpiryCheckedLane::<TestRuntime, ()>::get(), None);
+
+			// remaining messages are expired later
+			System::<TestRuntime>::reset_events();
+			Pallet::<TestRuntime>::on_idle(12, Weight::MAX);
+			assert_eq!(
+				messages_expired_events(),
+				vec![Event::MessagesExpired { lane_id: TEST_LANE_ID, begin: 2, end: 3 }],
+			);
+		});
+	}
+
+	#[test]
+	fn message_is_not_expired_if_its_fee_is_not_refunded() {
+		run_test(|| {
+			TestMessageExpiryBlocks::set(&10);
+			send_regular_message();
+			send_regular_message();
+			TestMessageDeliveryAndDispatchPayment::reject_payments();
+
+			Pallet::<TestRuntime>::on_idle(11, Weight::MAX);
+
+			// the first message isn't expired and the following message isn't checked
+			assert_eq!(messages_expired_events(), vec![]);
+			assert_eq!(LatestExpiryCheckedNonce::<TestRuntime, ()>::get(TEST_LANE_ID), 0);
+			assert_eq!(LatestRefundedOutboundNonce::<TestRuntime, ()>::get(TEST_LANE_ID), 0);
+			let message_data =
+				Pallet::<TestRuntime>::outbound_message_data(TEST_LANE_ID, 1).unwrap();
+			assert!(!message_data.payload.is_empty());
+			assert_eq!(message_data.fee, REGULAR_PAYLOAD.declared_weight);
+
+			// both messages are expired once the fee may be refunded
+			frame_support::storage::unhashed::kill(b":reject-message-fee:");
+			Pallet::<TestRuntime>::on_idle(12, Weight::MAX);
+			assert_eq!(
+				messages_expired_events(),
+				vec![Event::MessagesExpired { lane_id: TEST_LANE_ID, begin: 1, end: 2 }],
+			);
+			assert_eq!(LatestExpiryCheckedNonce::<TestRuntime, ()>::get(TEST_LANE_ID), 2);
+			assert_eq!(LatestRefundedOutboundNonce::<TestRuntime, ()>::get(TEST_LANE_ID), 2);
+		});
+	}
+
+	#[test]
+	fn messages_of_lane_that_makes_progress_are_not_expired() {
+		run_test(|| {
+			TestMessageExpiryBlocks::set(&10);
+			send_regular_message();
+			send_regular_message();
+
+			// the first message is confirmed at block 5
+			System::<TestRuntime>::set_block_number(5);
+			let (proof, relayers_state) = delivery_proof_of_first_message();
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
+				Origin::signed(1),
+				proof,
+				relayers_state,
+			));
+
+			// so the second message isn't expired at block 11, even though it has been sent at
+			// block 1
+			Pallet::<TestRuntime>::on_idle(11, Weight::MAX);
+			assert_eq!(messages_expired_events(), vec![]);
+
+			// but it is expired if lane makes no progress for `MessageExpiryBlocks` blocks
+			Pallet::<TestRuntime>::on_idle(15, Weight::MAX);
+			assert_eq!(
+				messages_expired_events(),
+				vec![Event::MessagesExpired { lane_id: TEST_LANE_ID, begin: 2, end: 2 }],
+			);
+		});
+	}
+
+	#[test]
+	fn lanes_are_checked_for_expiry_in_turns() {
+		run_test(|| {
+			TestMessageExpiryBlocks::set(&10);
+			let other_lane_id = LaneId([0, 0, 0, 2]);
+			OpenedLanes::<TestRuntime, ()>::insert(other_lane_id, ());
+			send_regular_message();
+			assert_ok!(Pallet::<TestRuntime>::send_message(
+				Origin::signed(1),
+				other_lane_id,
+				REGULAR_PAYLOAD,
+				REGULAR_PAYLOAD.declared_weight,
+			));
+			let lanes = OpenedLanes::<TestRuntime, ()>::iter_keys().collect::<Vec<_>>();
+			assert_eq!(lanes.len(), 2);
+
+			// there's only enough weight to check single lane with single message
+			let db_weight = crate::mock::DbWeight::get();
+			let remaining_weight = db_weight.reads_writes(2, 1) +
+				db_weight.reads_writes(5, 2) +
+				db_weight.reads_writes(4, 4);
+
+			System::<TestRuntime>::reset_events();
+			Pallet::<TestRuntime>::on_idle(11, remaining_weight);
+			assert_eq!(
+				messages_expired_events(),
+				vec![Event::MessagesExpired { lane_id: lanes[0], begin: 1, end: 1 }],
+			);
+			assert_eq!(LatestExpiryCheckedLane::<TestRuntime, ()>::get(), Some(lanes[0]));
+
+			// the next call starts with the lane that follows the latest checked lane
+			System::<TestRuntime>::reset_events();
+			Pallet::<TestRuntime>::on_idle(12, remaining_weight);
+			assert_eq!(
+				messages_expired_events(),
+				vec![Event::MessagesExpired { lane_id: lanes[1], begin: 1, end: 1 }],
+			);
+			assert_eq!(LatestExpiryCheckedLane::<TestRuntime, ()>::get(), Some(lanes[1]));
+
+			// and then wraps around to the first lane
+			Pallet::<TestRuntime>::on_idle(13, remaining_weight);
+			assert_eq!(LatestExpiryCheckedLane::<TestRuntime, ()>::get(), Some(lanes[0]));
+		});
+	}
+
+	#[test]
+	fn messages_of_halted_lane_are_not_expired() {
+		run_test(|| {
+			TestMessageExpiryBlocks::set(&10);
+			send_regular_message();
+			LaneMode::<TestRuntime, ()>::insert(
+				TEST_LANE_ID,
+				MessagesOperatingMode::Basic(BasicOperatingMode::Halted),
+			);
+
+			Pallet::<TestRuntime>::on_idle(11, Weight::MAX);
+
+			assert_eq!(messages_expired_events(), vec![]);
+		});
+	}
+
+	#[test]
+	fn delivery_confirmation_of_expired_message_is_accepted_without_reward() {
+		run_test(|| {
+			TestMessageExpiryBlocks::set(&10);
+			send_regular_message();
+			Pallet::<TestRuntime>::on_idle(11, Weight::MAX);
+
+			// the empty message has been delivered to the bridged chain, where it has failed to
+			// dispatch. Its delivery confirmation advances the lane, but the fee has already been
+			// refunded to the submitter => relayer isn't rewarded
+			let (proof, relayers_state) = delivery_proof_of_first_undispatched_message();
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
+				Origin::signed(1),
+				proof,
+				relayers_state,
+			));
+
+			assert_eq!(
+				OutboundLanes::<TestRuntime, ()>::get(TEST_LANE_ID).latest_received_nonce,
+				1,
+			);
+			assert!(TestMessageDeliveryAndDispatchPayment::is_fee_refunded(
+				1,
+				REGULAR_PAYLOAD.declared_weight
+			));
+			assert!(!TestMessageDeliveryAndDispatchPayment::is_reward_paid(
+				TEST_RELAYER_A,
+				REGULAR_PAYLOAD.declared_weight
+			));
+		});
+	}
+
+	#[test]
+	fn delivery_confirmation_of_expired_and_dispatched_message_is_reported() {
+		run_test(|| {
+			TestMessageExpiryBlocks::set(&10);
+			send_regular_message();
+			Pallet::<TestRuntime>::on_idle(11, Weight::MAX);
+
+			// the original message has been delivered and dispatched at the bridged chain before
+			// it has expired here, but the submitter has already got the refund
+			let (proof, relayers_state) = delivery_proof_of_first_message();
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
+				Origin::signed(1),
+				proof,
+				relayers_state,
+			));
+
+			// the lane has advanced, the event is emitted and the relayer isn't rewarded
+			assert_eq!(
+				OutboundLanes::<TestRuntime, ()>::get(TEST_LANE_ID).latest_received_nonce,
+				1,
+			);
+			assert_eq!(refunded_message_dispatched_events(), vec![1]);
+			assert!(!TestMessageDeliveryAndDispatchPayment::is_reward_paid(
+				TEST_RELAYER_A,
+				REGULAR_PAYLOAD.declared_weight
+			));
+		});
+	}
+
+	#[test]
+	fn message_delivered_before_expiry_and_confirmed_after_expiry_is_not_rewarded() {
+		run_test(|| {
+			TestMessageExpiryBlocks::set(&10);
+			send_regular_message();
+			send_regular_message();
+
+			// first message is delivered and confirmed before expiry => it never expires
+			System::<TestRuntime>::set_block_number(5);
+			let (proof, relayers_state) = delivery_proof_of_first_message();
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
+				Origin::signed(1),
+				proof,
+				relayers_state,
+			));
+
+			// second message is delivered and dispatched at the bridged chain at block 10, but
+			// the confirmation doesn't arrive before the lane stalls => it expires
+			System::<TestRuntime>::set_block_number(15);
+			Pallet::<TestRuntime>::on_idle(15, Weight::MAX);
+			assert_eq!(
+				messages_expired_events(),
+				vec![Event::MessagesExpired { lane_id: TEST_LANE_ID, begin: 2, end: 2 }],
+			);
+
+			// the late confirmation is accepted: the lane advances, but the relayer is not rewarded
+			let (proof, relayers_state) = (
+				TestMessagesDeliveryProof(Ok((
+					TEST_LANE_ID,
+					InboundLaneData {
+						last_confirmed_nonce: 1,
+						relayers: vec![unrewarded_relayer(2, 2, TEST_RELAYER_B)]
+							.into_iter()
+							.collect(),
+					},
+				))),
+				UnrewardedRelayersState {
+					unrewarded_relayer_entries: 1,
+					messages_in_oldest_entry: 1,
+					total_messages: 1,
+					last_delivered_nonce: 2,
+				},
+			);
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
+				Origin::signed(1),
+				proof,
+				relayers_state,
+			));
+			assert_eq!(
+				OutboundLanes::<TestRuntime, ()>::get(TEST_LANE_ID).latest_received_nonce,
+				2,
+			);
+			assert_eq!(refunded_message_dispatched_events(), vec![2]);
+			assert!(!TestMessageDeliveryAndDispatchPayment::is_reward_paid(
+				TEST_RELAYER_B,
+				REGULAR_PAYLOAD.declared_weight
+			));
+		});
+	}
+
+	#[test]
+	fn delivery_confirmation_wins_if_message_expires_in_the_same_block() {
+		run_test(|| {
+			TestMessageExpiryBlocks::set(&10);
+			send_regular_message();
+
+			// delivery confirmation transaction is included in the block where the message
+			// expires. Transactions are dispatched before `on_idle`, so the message is delivered
+			System::<TestRuntime>::set_block_number(11);
+			let (proof, relayers_state) = delivery_proof_of_first_message();
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
+				Origin::signed(1),
+				proof,
+				relayers_state,
+			));
+			Pallet::<TestRuntime>::on_idle(11, Weight::MAX);
+
+			assert_eq!(messages_expired_events(), vec![]);
+			assert!(!TestMessageDeliveryAndDispatchPayment::is_fee_refunded(
+				1,
+				REGULAR_PAYLOAD.declared_weight
+			));
+			assert!(TestMessageDeliveryAndDispatchPayment::is_reward_paid(
+				TEST_RELAYER_A,
+				REGULAR_PAYLOAD.declared_weight
+			));
+		});
+	}
+
+	#[test]
+	fn send_message_rejects_messages_if_lane_queue_is_full() {
+		run_test(|| {
+			TestMaxOutboundQueueLen::set(&2);
+			send_regular_message();
+			send_regular_message();
+
+			assert_noop!(
+				Pallet::<TestRuntime>::send_message(
+					Origin::signed(1),
+					TEST_LANE_ID,
+					REGULAR_PAYLOAD,
+					REGULAR_PAYLOAD.declared_weight,
+				),
+				Error::<TestRuntime, ()>::QueueFull,
+			);
+		});
+	}
+
+	#[test]
+	fn lane_queue_is_released_when_delivery_is_confirmed() {
+		run_test(|| {
+			TestMaxOutboundQueueLen::set(&1);
+			send_regular_message();
+			assert_noop!(
+				Pallet::<TestRuntime>::send_message(
+					Origin::signed(1),
+					TEST_LANE_ID,
+					REGULAR_PAYLOAD,
+					REGULAR_PAYLOAD.declared_weight,
+				),
+				Error::<TestRuntime, ()>::QueueFull,
+			);
+
+			// once the delivery of the first message is confirmed, there's room for new message
+			receive_messages_delivery_proof();
+			send_regular_message();
+			assert_eq!(
+				OutboundLanes::<TestRuntime, ()>::get(TEST_LANE_ID).latest_generated_nonce,
+				2,
+			);
+		});
+	}
+
+	#[test]
+	fn send_message_rejects_too_large_message() {
+		run_test(|| {
+			let mut message_payload = message_payload(1, 0);
+			// the payload isn't simply extra, so it'll definitely overflow
+			// `MAX_OUTBOUND_PAYLOAD_SIZE` if we add `MAX_OUTBOUND_PAYLOAD_SIZE` bytes to extra
+			message_payload
+				.extra
+				.extend_from_slice(&[0u8; MAX_OUTBOUND_PAYLOAD_SIZE as usize]);
+			assert_noop!(
+				Pallet::<TestRuntime>::send_message(
+					Origin::signed(1),
+					TEST_LANE_ID,
+					message_payload.clone(),
+					Balance::MAX,
+				),
+				Error::<TestRuntime, ()>::MessageIsTooLarge,
+			);
+
+			// let's check that we're able to send `MAX_OUTBOUND_PAYLOAD_SIZE` messages
+			while message_payload.size() > MAX_OUTBOUND_PAYLOAD_SIZE {
+				message_payload.extra.pop();
+			}
+			assert_eq!(message_payload.size(), MAX_OUTBOUND_PAYLOAD_SIZE);
+			assert_ok!(Pallet::<TestRuntime>::send_message(
+				Origin::signed(1),
+				TEST_LANE_ID,
+				message_payload,
+				Balance::MAX,
+			),);
+		})
+	}
+
+	fn send_message_from(origin: Origin) -> DispatchResultWithPostInfo {
+		Pallet::<TestRuntime>::send_message(
+			origin,
+			TEST_LANE_ID,
+			REGULAR_PAYLOAD,
+			REGULAR_PAYLOAD.declared_weight,
+		)
+	}
+
+	fn set_outbound_messages_rate_limit(max_messages: u32, window: u64) {
+		TestOutboundMessagesRateLimit::set(&Some(OutboundRateLimit { max_messages, window }));
+	}
+
+	#[test]
+	fn send_message_rejects_messages_exceeding_rate_limit() {
+		run_test(|| {
+			System::<TestRuntime>::set_block_number(1);
+			set_outbound_messages_rate_limit(2, 10);
+
+			assert_eq!(Pallet::<TestRuntime>::outbound_messages_allowance(1), Some(2));
+			assert_ok!(send_message_from(Origin::signed(1)));
+			assert_ok!(send_message_from(Origin::signed(1)));
+			assert_eq!(Pallet::<TestRuntime>::outbound_messages_allowance(1), Some(0));
+			assert_noop!(
+				send_message_from(Origin::signed(1)),
+				Error::<TestRuntime, ()>::MessagesRateLimitExceeded,
+			);
+
+			// other accounts have their own allowance
+			assert_eq!(Pallet::<TestRuntime>::outbound_messages_allowance(2), Some(2));
+			assert_ok!(send_message_from(Origin::signed(2)));
+		});
+	}
+
+	#[test]
+	fn root_and_owner_messages_are_not_rate_limited() {
+		run_test(|| {
+			System::<TestRuntime>::set_block_number(1);
+			set_outbound_messages_rate_limit(1, 10);
+			PalletOwner::<TestRuntime>::put(2);
+
+			assert_eq!(Pallet::<TestRuntime>::outbound_messages_allowance(2), None);
+			for _ in 0..3 {
+				assert_ok!(send_message_from(Origin::root()));
+				assert_ok!(send_message_from(Origin::signed(2)));
+			}
+			assert_eq!(OutboundMessagesPerAccount::<TestRuntime>::iter().count(), 0);
+		});
+	}
+
+	#[test]
+	fn outbound_messages_are_not_rate_limited_by_default() {
+		run_test(|| {
+			assert_eq!(Pallet::<TestRuntime>::outbound_messages_allowance(1), None);
+			for _ in 0..10 {
+				assert_ok!(send_message_from(Origin::signed(1)));
+			}
+			assert_eq!(OutboundMessagesPerAccount::<TestRuntime>::iter().count(), 0);
+		});
+	}
+
+	#[test]
+	fn outbound_messages_allowance_is_refilled_in_next_window() {
+		run_test(|| {
+			System::<TestRuntime>::set_block_number(5);
+			set_outbound_messages_rate_limit(1, 10);
+
+			assert_ok!(send_message_from(Origin::signed(1)));
+			assert_eq!(Pallet::<TestRuntime>::outbound_messages_allowance(1), Some(0));
+
+			// the window is the same until block 10
+			System::<TestRuntime>::set_block_number(9);
+			assert_noop!(
+				send_message_from(Origin::signed(1)),
+				Error::<TestRuntime, ()>::MessagesRateLimitExceeded,
+			);
+
+			System::<TestRuntime>::set_block_number(10);
+			assert_eq!(Pallet::<TestRuntime>::outbound_messages_allowance(1), Some(1));
+			assert_ok!(send_message_from(Origin::signed(1)));
+			assert_eq!(Pallet::<TestRuntime>::outbound_messages_allowance(1), Some(0));
+		});
+	}
+
+	#[test]
+	fn ended_rate_limit_windows_are_pruned() {
+		run_test(|| {
+			System::<TestRuntime>::set_block_number(1);
+			set_outbound_messages_rate_limit(1, 10);
+
+			let accounts = MAX_RATE_LIMIT_ENTRIES_TO_PRUNE_AT_ONCE as AccountId + 1;
+			for account in 1..=accounts {
+				assert_ok!(send_message_from(Origin::signed(account)));
+			}
+			assert_eq!(OldestOutboundRateLimitWindow::<TestRuntime>::get(), Some(0));
+
+			// nothing is pruned while the window is active
+			Pallet::<TestRuntime>::on_initialize(9);
+			assert_eq!(
+				OutboundMessagesPerAccount::<TestRuntime>::iter().count(),
+				accounts as usize
+			);
+
+			// when the window ends, entries are pruned in several blocks
+			System::<TestRuntime>::set_block_number(10);
+			assert_ok!(send_message_from(Origin::signed(1)));
+			Pallet::<TestRuntime>::on_initialize(10);
+			assert_eq!(OutboundMessagesPerAccount::<TestRuntime>::iter().count(), 2);
+			assert_eq!(OldestOutboundRateLimitWindow::<TestRuntime>::get(), Some(0));
+			Pallet::<TestRuntime>::on_initialize(11);
+			assert_eq!(OutboundMessagesPerAccount::<TestRuntime>::iter().count(), 1);
+			assert_eq!(OldestOutboundRateLimitWindow::<TestRuntime>::get(), Some(10));
+
+			// entries of the current window are kept
+			Pallet::<TestRuntime>::on_initialize(12);
+			assert_eq!(
+				OutboundMessagesPerAccount::<TestRuntime>::iter().collect::<Vec<_>>(),
+				vec![(10, 1, 1)],
+			);
+
+			// empty windows are skipped
+			System::<TestRuntime>::set_block_number(35);
+			Pallet::<TestRuntime>::on_initialize(35);
+			assert_eq!(OutboundMessagesPerAccount::<TestRuntime>::iter().count(), 0);
+			assert_eq!(OldestOutboundRateLimitWindow::<TestRuntime>::get(), Some(20));
+			Pallet::<TestRuntime>::on_initialize(36);
+			assert_eq!(OldestOutboundRateLimitWindow::<TestRuntime>::get(), Some(30));
+		});
+	}
+
+	#[test]
+	fn chain_verifier_rejects_invalid_message_in_send_message() {
+		run_test(|| {
+			// messages with this payload are rejected by target chain verifier
+			assert_noop!(
+				Pallet::<TestRuntime>::send_message(
+					Origin::signed(1),
+					TEST_LANE_ID,
+					PAYLOAD_REJECTED_BY_TARGET_CHAIN,
+					PAYLOAD_REJECTED_BY_TARGET_CHAIN.declared_weight
+				),
+				Error::<TestRuntime, ()>::MessageRejectedByChainVerifier,
+			);
+		});
+	}
+
+	#[test]
+	fn lane_verifier_rejects_invalid_message_in_send_message() {
+		run_test(|| {
+			// messages with zero fee are rejected by lane verifier
+			assert_noop!(
+				Pallet::<TestRuntime>::send_message(
+					Origin::signed(1),
+					TEST_LANE_ID,
+					REGULAR_PAYLOAD,
+					0
+				),
+				Error::<TestRuntime, ()>::TooLowFee,
+			);
+		});
+	}
+
+	#[test]
+	fn verification_errors_are_mapped_to_pallet_errors() {
+		let map = |error| {
+			verification_error::<TestRuntime, ()>(
+				error,
+				Error::<TestRuntime, ()>::MessageRejectedByLaneVerifier,
+			)
+		};
+
+		assert_eq!(map(VerificationError::TooLowFee), Error::<TestRuntime, ()>::TooLowFee);
+		assert_eq!(map(VerificationError::ClosedLane), Error::<TestRuntime, ()>::LaneClosed);
+		assert_eq!(
+			map(VerificationError::TooManyPendingMessages),
+			Error::<TestRuntime, ()>::TooManyPendingMessages,
+		);
+		assert_eq!(
+			map(VerificationError::MessageTooLarge),
+			Error::<TestRuntime, ()>::MessageIsTooLarge,
+		);
+		assert_eq!(
+			map(VerificationError::InvalidDispatchWeight),
+			Error::<TestRuntime, ()>::InvalidMessageDispatchWeight,
+		);
+		assert_eq!(
+			map(VerificationError::InvalidSourceOrigin),
+			Error::<TestRuntime, ()>::InvalidSourceOrigin,
+		);
+		assert_eq!(
+			map(VerificationError::EmptyMessageProof),
+			Error::<TestRuntime, ()>::MessageRejectedByLaneVerifier,
+		);
+		assert_eq!(
+			map(VerificationError::Other(TEST_ERROR)),
+			Error::<TestRuntime, ()>::MessageRejectedByLaneVerifier,
+		);
+	}
+
+	#[test]
+	fn message_send_fails_if_submitter_cant_pay_message_fee() {
+		run_test(|| {
+			TestMessageDeliveryAndDispatchPayment::reject_payments();
+			assert_noop!(
+				Pallet::<TestRuntime>::send_message(
+					Origin::signed(1),
+					TEST_LANE_ID,
+					REGULAR_PAYLOAD,
+					REGULAR_PAYLOAD.declared_weight
+				),
+				Error::<TestRuntime, ()>::FailedToWithdrawMessageFee,
+			);
+		});
+	}
+
+	#[test]
 	fn receive_messages_proof_works() {
 		run_test(|| {
 			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
@@ -1538,6 +4075,41 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn receive_messages_proof_accepts_proof_of_maximal_size() {
+		run_test(|| {
+			let proof: TestMessagesProof = Ok(vec![message(1, REGULAR_PAYLOAD)]).into();
+			TestMaxMessagesProofSize::set(&proof.size());
+
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				proof,
+				1,
+				REGULAR_PAYLOAD.declared_weight,
+			));
+		});
+	}
+
+	#[test]
+	fn receive_messages_proof_rejects_proof_that_is_too_large() {
+		run_test(|| {
+			let proof: TestMessagesProof = Ok(vec![message(1, REGULAR_PAYLOAD)]).into();
+			TestMaxMessagesProofSize::set(&(proof.size() - 1));
+
+			assert_noop!(
+				Pallet::<TestRuntime>::receive_messages_proof(
+					Origin::signed(1),
+					TEST_RELAYER_A,
+					proof,
+					1,
+					REGULAR_PAYLOAD.declared_weight,
+				),
+				Error::<TestRuntime, ()>::MessagesProofIsTooLarge,
+			);
+		});
+	}
+
 	#[test]
 	fn receive_messages_proof_updates_confirmed_message_nonce() {
 		run_test(|| {
@@ -1602,6 +4174,66 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn receive_messages_proof_rejects_proof_if_lane_has_too_many_unrewarded_relayers() {
+		run_test(|| {
+			let max_entries = MaxUnrewardedRelayerEntriesAtInboundLane::get();
+			InboundLanes::<TestRuntime, ()>::insert(
+				TEST_LANE_ID,
+				InboundLaneData {
+					last_confirmed_nonce: 0,
+					relayers: (1..=max_entries)
+						.map(|nonce| unrewarded_relayer(nonce, nonce, TEST_RELAYER_A + nonce))
+						.collect(),
+				},
+			);
+
+			assert_noop!(
+				Pallet::<TestRuntime>::receive_messages_proof(
+					Origin::signed(1),
+					TEST_RELAYER_A,
+					Ok(vec![message(max_entries + 1, REGULAR_PAYLOAD)]).into(),
+					1,
+					REGULAR_PAYLOAD.declared_weight,
+				),
+				Error::<TestRuntime, ()>::TooManyUnrewardedRelayers,
+			);
+		});
+	}
+
+	#[test]
+	fn receive_messages_proof_accepts_proof_if_lane_state_prunes_unrewarded_relayers() {
+		run_test(|| {
+			let max_entries = MaxUnrewardedRelayerEntriesAtInboundLane::get();
+			InboundLanes::<TestRuntime, ()>::insert(
+				TEST_LANE_ID,
+				InboundLaneData {
+					last_confirmed_nonce: 0,
+					relayers: (1..=max_entries)
+						.map(|nonce| unrewarded_relayer(nonce, nonce, TEST_RELAYER_A + nonce))
+						.collect(),
+				},
+			);
+
+			// message proof includes outbound lane state that confirms the first message
+			let mut message_proof: TestMessagesProof =
+				Ok(vec![message(max_entries + 1, REGULAR_PAYLOAD)]).into();
+			message_proof.result.as_mut().unwrap()[0].1.lane_state =
+				Some(OutboundLaneData { latest_received_nonce: 1, ..Default::default() });
+
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				message_proof,
+				1,
+				REGULAR_PAYLOAD.declared_weight,
+			));
+			let lane_data = InboundLanes::<TestRuntime>::get(TEST_LANE_ID).0;
+			assert_eq!(lane_data.last_delivered_nonce(), max_entries + 1);
+			assert_eq!(lane_data.relayers.len() as MessageNonce, max_entries);
+		});
+	}
+
 	#[test]
 	fn receive_messages_proof_does_not_accept_message_if_dispatch_weight_is_not_enough() {
 		run_test(|| {
@@ -1648,16 +4280,373 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn receive_messages_proof_rejects_proof_if_lane_nonces_are_exhausted() {
+		run_test(|| {
+			InboundLanes::<TestRuntime, ()>::insert(
+				TEST_LANE_ID,
+				InboundLaneData {
+					last_confirmed_nonce: MessageNonce::MAX - 1,
+					relayers: vec![unrewarded_relayer(
+						MessageNonce::MAX,
+						MessageNonce::MAX,
+						TEST_RELAYER_A,
+					)]
+					.into_iter()
+					.collect(),
+				},
+			);
+
+			assert_noop!(
+				Pallet::<TestRuntime, ()>::receive_messages_proof(
+					Origin::signed(1),
+					TEST_RELAYER_A,
+					Ok(vec![message(MessageNonce::MAX, REGULAR_PAYLOAD)]).into(),
+					1,
+					REGULAR_PAYLOAD.declared_weight,
+				),
+				Error::<TestRuntime, ()>::MessageNonceOverflow,
+			);
+		});
+	}
+
+	fn receive_regular_message_with_max_payload_size(max_payload_size: u32) {
+		InboundLaneMaxPayloadSize::<TestRuntime>::put(max_payload_size);
+		get_ready_for_events();
+
+		assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+			Origin::signed(1),
+			TEST_RELAYER_A,
+			Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+			1,
+			REGULAR_PAYLOAD.declared_weight,
+		));
+	}
+
+	#[test]
+	fn receive_messages_proof_dispatches_message_with_payload_at_the_limit() {
+		run_test(|| {
+			receive_regular_message_with_max_payload_size(REGULAR_PAYLOAD.encode().len() as u32);
+
+			let inbound_lane_data = InboundLanes::<TestRuntime>::get(TEST_LANE_ID).0;
+			assert_eq!(inbound_lane_data.last_delivered_nonce(), 1);
+			assert_eq!(inbound_lane_data.relayers[0].messages.get_dispatch_result(1), Some(true));
+			assert_eq!(System::<TestRuntime>::events(), vec![]);
+		});
+	}
+
+	#[test]
+	fn receive_messages_proof_does_not_dispatch_message_with_payload_over_the_limit() {
+		run_test(|| {
+			let payload_size = REGULAR_PAYLOAD.encode().len() as u32;
+			receive_regular_message_with_max_payload_size(payload_size - 1);
+
+			// message is delivered (so lane isn't blocked), but it isn't dispatched
+			let inbound_lane_data = InboundLanes::<TestRuntime>::get(TEST_LANE_ID).0;
+			assert_eq!(inbound_lane_data.last_delivered_nonce(), 1);
+			assert_eq!(inbound_lane_data.relayers[0].messages.get_dispatch_result(1), Some(false));
+			assert_eq!(
+				System::<TestRuntime>::events(),
+				vec![
+					EventRecord {
+						phase: Phase::Initialization,
+						event: TestEvent::Messages(Event::InboundMessagePayloadTooLarge {
+							lane_id: TEST_LANE_ID,
+							nonce: 1,
+							payload_size,
+						}),
+						topics: vec![],
+					},
+					EventRecord {
+						phase: Phase::Initialization,
+						event: TestEvent::Messages(Event::MessageRejected {
+							lane_id: TEST_LANE_ID,
+							nonce: 1,
+							reason: MessageRejectionReason::OversizedPayload,
+							dispatch_error: None,
+						}),
+						topics: vec![],
+					}
+				],
+			);
+		});
+	}
+
+	#[test]
+	fn receive_messages_proof_rejects_message_with_undecodable_payload() {
+		run_test(|| {
+			let mut undecodable_message = message(1, REGULAR_PAYLOAD);
+			undecodable_message.data.payload = vec![42];
+
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				Ok(vec![undecodable_message]).into(),
+				1,
+				REGULAR_PAYLOAD.declared_weight,
+			));
+
+			assert_eq!(InboundLanes::<TestRuntime>::get(TEST_LANE_ID).0.last_delivered_nonce(), 1);
+			assert_eq!(
+				System::<TestRuntime>::events(),
+				vec![EventRecord {
+					phase: Phase::Initialization,
+					event: TestEvent::Messages(Event::MessageRejected {
+						lane_id: TEST_LANE_ID,
+						nonce: 1,
+						reason: MessageRejectionReason::UndecodablePayload,
+						dispatch_error: None,
+					}),
+					topics: vec![],
+				}],
+			);
+		});
+	}
+
+	#[test]
+	fn receive_messages_proof_rejects_message_with_failed_dispatch() {
+		run_test(|| {
+			let mut payload = REGULAR_PAYLOAD;
+			payload.dispatch_result.dispatch_result = false;
+			payload.dispatch_result.dispatch_error = Some(MessageDispatchError::Trapped(42));
+
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				Ok(vec![message(1, payload)]).into(),
+				1,
+				REGULAR_PAYLOAD.declared_weight,
+			));
+
+			assert_eq!(
+				InboundLanes::<TestRuntime>::get(TEST_LANE_ID).0.relayers[0]
+					.messages
+					.get_dispatch_result(1),
+				Some(false),
+			);
+			assert_eq!(
+				System::<TestRuntime>::events(),
+				vec![EventRecord {
+					phase: Phase::Initialization,
+					event: TestEvent::Messages(Event::MessageRejected {
+						lane_id: TEST_LANE_ID,
+						nonce: 1,
+						reason: MessageRejectionReason::DispatchError,
+						dispatch_error: Some(MessageDispatchError::Trapped(42)),
+					}),
+					topics: vec![],
+				}],
+			);
+		});
+	}
+
+	#[test]
+	fn inbound_lane_max_payload_size_may_be_updated_by_owner() {
+		run_test(|| {
+			PalletOwner::<TestRuntime>::put(2);
+			assert_eq!(
+				Pallet::<TestRuntime>::inbound_lane_max_payload_size(TEST_LANE_ID),
+				MAX_INBOUND_PAYLOAD_SIZE,
+			);
+
+			// update limit for all lanes
+			assert_ok!(Pallet::<TestRuntime>::set_inbound_lane_max_payload_size(
+				Origin::signed(2),
+				None,
+				Some(100),
+			));
+			assert_eq!(Pallet::<TestRuntime>::inbound_lane_max_payload_size(TEST_LANE_ID), 100);
+			assert_eq!(
+				Pallet::<TestRuntime>::inbound_lane_max_payload_size(LaneId([0, 0, 0, 2])),
+				100
+			);
+
+			// update limit for single lane
+			assert_ok!(Pallet::<TestRuntime>::set_inbound_lane_max_payload_size(
+				Origin::root(),
+				Some(TEST_LANE_ID),
+				Some(200),
+			));
+			assert_eq!(Pallet::<TestRuntime>::inbound_lane_max_payload_size(TEST_LANE_ID), 200);
+			assert_eq!(
+				Pallet::<TestRuntime>::inbound_lane_max_payload_size(LaneId([0, 0, 0, 2])),
+				100
+			);
+
+			// reset limits
+			assert_ok!(Pallet::<TestRuntime>::set_inbound_lane_max_payload_size(
+				Origin::signed(2),
+				Some(TEST_LANE_ID),
+				None,
+			));
+			assert_ok!(Pallet::<TestRuntime>::set_inbound_lane_max_payload_size(
+				Origin::signed(2),
+				None,
+				None,
+			));
+			assert_eq!(
+				Pallet::<TestRuntime>::inbound_lane_max_payload_size(TEST_LANE_ID),
+				MAX_INBOUND_PAYLOAD_SIZE,
+			);
+		});
+	}
+
+	#[test]
+	fn inbound_lane_max_payload_size_cant_be_updated_by_arbitrary_submitter() {
+		run_test(|| {
+			assert_noop!(
+				Pallet::<TestRuntime>::set_inbound_lane_max_payload_size(
+					Origin::signed(2),
+					None,
+					Some(100),
+				),
+				DispatchError::BadOrigin,
+			);
+		});
+	}
+
+	#[test]
+	fn send_message_rejects_unknown_lane() {
+		run_test(|| {
+			assert_noop!(
+				Pallet::<TestRuntime>::send_message(
+					Origin::signed(1),
+					LaneId([0, 0, 0, 2]),
+					REGULAR_PAYLOAD,
+					REGULAR_PAYLOAD.declared_weight,
+				),
+				Error::<TestRuntime, ()>::UnknownLane,
+			);
+		});
+	}
+
+	#[test]
+	fn receive_messages_proof_rejects_unknown_lane() {
+		run_test(|| {
+			let mut unknown_lane_message = message(1, REGULAR_PAYLOAD);
+			unknown_lane_message.key.lane_id = LaneId([0, 0, 0, 2]);
+
+			assert_noop!(
+				Pallet::<TestRuntime>::receive_messages_proof(
+					Origin::signed(1),
+					TEST_RELAYER_A,
+					Ok(vec![message(1, REGULAR_PAYLOAD), unknown_lane_message]).into(),
+					2,
+					REGULAR_PAYLOAD.declared_weight * 2,
+				),
+				Error::<TestRuntime, ()>::UnknownLane,
+			);
+		});
+	}
+
+	#[test]
+	fn lane_may_be_opened_and_closed_by_owner() {
+		run_test(|| {
+			get_ready_for_events();
+			PalletOwner::<TestRuntime>::put(2);
+			let lane_id = LaneId([0, 0, 0, 2]);
+
+			assert_ok!(Pallet::<TestRuntime>::open_lane(Origin::signed(2), lane_id));
+			assert_noop!(
+				Pallet::<TestRuntime>::open_lane(Origin::root(), lane_id),
+				Error::<TestRuntime, ()>::LaneAlreadyOpened,
+			);
+			assert_ok!(Pallet::<TestRuntime>::send_message(
+				Origin::signed(1),
+				lane_id,
+				REGULAR_PAYLOAD,
+				REGULAR_PAYLOAD.declared_weight,
+			));
+
+			assert_ok!(Pallet::<TestRuntime>::close_lane(Origin::root(), lane_id));
+			assert_noop!(
+				Pallet::<TestRuntime>::close_lane(Origin::signed(2), lane_id),
+				Error::<TestRuntime, ()>::UnknownLane,
+			);
+			assert_noop!(
+				Pallet::<TestRuntime>::send_message(
+					Origin::signed(1),
+					lane_id,
+					REGULAR_PAYLOAD,
+					REGULAR_PAYLOAD.declared_weight,
+				),
+				Error::<TestRuntime, ()>::UnknownLane,
+			);
+
+			let events: Vec<_> = System::<TestRuntime>::events()
+				.into_iter()
+				.filter_map(|record| match record.event {
+					TestEvent::Messages(event @ Event::LaneOpened { .. }) |
+					TestEvent::Messages(event @ Event::LaneClosed { .. }) => Some(event),
+					_ => None,
+				})
+				.collect();
+			assert_eq!(events, vec![Event::LaneOpened { lane_id }, Event::LaneClosed { lane_id }]);
+		});
+	}
+
+	#[test]
+	fn lane_cant_be_opened_or_closed_by_arbitrary_submitter() {
+		run_test(|| {
+			assert_noop!(
+				Pallet::<TestRuntime>::open_lane(Origin::signed(2), LaneId([0, 0, 0, 2])),
+				DispatchError::BadOrigin,
+			);
+			assert_noop!(
+				Pallet::<TestRuntime>::close_lane(Origin::signed(2), TEST_LANE_ID),
+				DispatchError::BadOrigin,
+			);
+		});
+	}
+
 	#[test]
 	fn receive_messages_delivery_proof_works() {
 		run_test(|| {
 			send_regular_message();
 			receive_messages_delivery_proof();
 
-			assert_eq!(
-				OutboundLanes::<TestRuntime, ()>::get(TEST_LANE_ID).latest_received_nonce,
-				1,
+			assert_eq!(
+				OutboundLanes::<TestRuntime, ()>::get(TEST_LANE_ID).latest_received_nonce,
+				1,
+			);
+		});
+	}
+
+	#[test]
+	fn receive_messages_delivery_proof_checks_proof_size() {
+		run_test(|| {
+			send_regular_message();
+
+			let proof = TestMessagesDeliveryProof(Ok((
+				TEST_LANE_ID,
+				InboundLaneData {
+					relayers: vec![unrewarded_relayer(1, 1, TEST_RELAYER_A)].into_iter().collect(),
+					..Default::default()
+				},
+			)));
+			let relayers_state = UnrewardedRelayersState {
+				unrewarded_relayer_entries: 1,
+				total_messages: 1,
+				last_delivered_nonce: 1,
+				..Default::default()
+			};
+
+			TestMaxMessagesDeliveryProofSize::set(&(proof.size() - 1));
+			assert_noop!(
+				Pallet::<TestRuntime>::receive_messages_delivery_proof(
+					Origin::signed(1),
+					proof.clone(),
+					relayers_state.clone(),
+				),
+				Error::<TestRuntime, ()>::MessagesDeliveryProofIsTooLarge,
 			);
+
+			TestMaxMessagesDeliveryProofSize::set(&proof.size());
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
+				Origin::signed(1),
+				proof,
+				relayers_state,
+			));
 		});
 	}
 
@@ -1727,6 +4716,63 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn receive_messages_delivery_proof_rewards_relayer_for_merged_entry() {
+		run_test(|| {
+			for fee in [1000, 2000, 3000] {
+				assert_ok!(Pallet::<TestRuntime>::send_message(
+					Origin::signed(1),
+					TEST_LANE_ID,
+					REGULAR_PAYLOAD,
+					fee,
+				));
+			}
+
+			// this reports delivery of message 1 => reward is paid to TEST_RELAYER_A
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
+				Origin::signed(1),
+				TestMessagesDeliveryProof(Ok((
+					TEST_LANE_ID,
+					InboundLaneData {
+						relayers: vec![unrewarded_relayer(1, 1, TEST_RELAYER_A)]
+							.into_iter()
+							.collect(),
+						..Default::default()
+					}
+				))),
+				UnrewardedRelayersState {
+					unrewarded_relayer_entries: 1,
+					total_messages: 1,
+					last_delivered_nonce: 1,
+					..Default::default()
+				},
+			));
+			assert!(TestMessageDeliveryAndDispatchPayment::is_reward_paid(TEST_RELAYER_A, 1000));
+
+			// TEST_RELAYER_A has delivered messages 2 and 3 in the next batches, so they have been
+			// merged into the same entry => reward for messages 2 and 3 is paid to TEST_RELAYER_A
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
+				Origin::signed(1),
+				TestMessagesDeliveryProof(Ok((
+					TEST_LANE_ID,
+					InboundLaneData {
+						relayers: vec![unrewarded_relayer(1, 3, TEST_RELAYER_A)]
+							.into_iter()
+							.collect(),
+						..Default::default()
+					}
+				))),
+				UnrewardedRelayersState {
+					unrewarded_relayer_entries: 1,
+					total_messages: 3,
+					last_delivered_nonce: 3,
+					..Default::default()
+				},
+			));
+			assert!(TestMessageDeliveryAndDispatchPayment::is_reward_paid(TEST_RELAYER_A, 5000));
+		});
+	}
+
 	#[test]
 	fn receive_messages_delivery_proof_rejects_invalid_proof() {
 		run_test(|| {
@@ -1946,6 +4992,68 @@ mod tests {
 				100,
 			),);
 			assert!(TestMessageDeliveryAndDispatchPayment::is_fee_paid(1, 100));
+			assert_eq!(
+				Pallet::<TestRuntime>::outbound_message_data(TEST_LANE_ID, 1).unwrap().fee,
+				REGULAR_PAYLOAD.declared_weight + 100,
+			);
+			assert_eq!(
+				System::<TestRuntime>::events(),
+				vec![
+					EventRecord {
+						phase: Phase::Initialization,
+						event: TestEvent::Messages(Event::MessageAccepted {
+							lane_id: TEST_LANE_ID,
+							nonce: 1,
+						}),
+						topics: vec![],
+					},
+					EventRecord {
+						phase: Phase::Initialization,
+						event: TestEvent::Messages(Event::MessageFeeIncreased {
+							lane_id: TEST_LANE_ID,
+							nonce: 1,
+							new_fee: REGULAR_PAYLOAD.declared_weight + 100,
+						}),
+						topics: vec![],
+					},
+				],
+			);
+		});
+	}
+
+	#[test]
+	fn increase_message_fee_fails_if_fee_overflows() {
+		run_test(|| {
+			send_regular_message();
+
+			assert_noop!(
+				Pallet::<TestRuntime, ()>::increase_message_fee(
+					Origin::signed(1),
+					TEST_LANE_ID,
+					1,
+					u64::MAX,
+				),
+				Error::<TestRuntime, ()>::MessageFeeOverflow,
+			);
+		});
+	}
+
+	#[test]
+	fn increase_message_fee_fails_if_message_is_cancelled() {
+		run_test(|| {
+			send_regular_message();
+			halt_test_lane();
+			assert_ok!(Pallet::<TestRuntime>::cancel_message(Origin::signed(1), TEST_LANE_ID, 1));
+
+			assert_noop!(
+				Pallet::<TestRuntime, ()>::increase_message_fee(
+					Origin::signed(1),
+					TEST_LANE_ID,
+					1,
+					100,
+				),
+				Error::<TestRuntime, ()>::MessageIsAlreadyCancelled,
+			);
 		});
 	}
 
@@ -2018,7 +5126,7 @@ mod tests {
 			// messages 1+2 are confirmed in 1 tx, message 3 in a separate tx
 			// dispatch of message 2 has failed
 			let mut delivered_messages_1_and_2 = DeliveredMessages::new(1, true);
-			delivered_messages_1_and_2.note_dispatched_message(false);
+			assert!(delivered_messages_1_and_2.try_note_dispatched_message(false));
 			let messages_1_and_2_proof = Ok((
 				TEST_LANE_ID,
 				InboundLaneData {
@@ -2224,7 +5332,7 @@ mod tests {
 			let mut delivered_messages = DeliveredMessages::new(1, true);
 			for _ in 1..max_messages_to_prune {
 				assert_eq!(send_regular_message(), when_zero_messages_are_pruned);
-				delivered_messages.note_dispatched_message(true);
+				assert!(delivered_messages.try_note_dispatched_message(true));
 			}
 
 			// confirm delivery of all sent messages
@@ -2341,11 +5449,277 @@ mod tests {
 						delivery_and_dispatch_fee: 0,
 						dispatch_fee_payment:
 							bp_runtime::messages::DispatchFeePayment::AtTargetChain,
+						submitter: None,
+					},
+				),
+				InboundMessageDetails {
+					dispatch_weight: REGULAR_PAYLOAD.declared_weight,
+					is_payload_decodable: true,
+				},
+			);
+		});
+	}
+
+	#[test]
+	fn inbound_message_details_reports_undecodable_payload() {
+		run_test(|| {
+			assert_eq!(
+				Pallet::<TestRuntime>::inbound_message_data(
+					TEST_LANE_ID,
+					vec![42],
+					OutboundMessageDetails {
+						nonce: 0,
+						dispatch_weight: 0,
+						size: 0,
+						delivery_and_dispatch_fee: 0,
+						dispatch_fee_payment:
+							bp_runtime::messages::DispatchFeePayment::AtTargetChain,
+						submitter: None,
 					},
 				),
-				InboundMessageDetails { dispatch_weight: REGULAR_PAYLOAD.declared_weight },
+				InboundMessageDetails { dispatch_weight: 0, is_payload_decodable: false },
+			);
+		});
+	}
+
+	fn sorted_recent_relayers() -> Vec<(AccountId, MessageNonce)> {
+		let mut recent_relayers = Pallet::<TestRuntime>::recent_relayers(TEST_LANE_ID);
+		recent_relayers.sort();
+		recent_relayers
+	}
+
+	fn track_recent_delivery(begin: MessageNonce, end: MessageNonce, relayer: AccountId) {
+		track_recent_deliveries::<TestRuntime, ()>(
+			&TEST_LANE_ID,
+			&vec![unrewarded_relayer(begin, end, relayer)].into_iter().collect(),
+			&(begin..=end),
+		);
+	}
+
+	#[test]
+	fn recent_relayers_window_slides_with_interleaved_relayers() {
+		run_test(|| {
+			// window (of 4 deliveries) is filled by A, B, A, C
+			track_recent_delivery(1, 2, TEST_RELAYER_A);
+			track_recent_delivery(3, 3, TEST_RELAYER_B);
+			track_recent_delivery(4, 6, TEST_RELAYER_A);
+			track_recent_delivery(7, 7, TEST_RELAYER_C);
+			assert_eq!(
+				sorted_recent_relayers(),
+				vec![(TEST_RELAYER_A, 5), (TEST_RELAYER_B, 1), (TEST_RELAYER_C, 1)],
 			);
+
+			// B replaces the oldest delivery of A
+			track_recent_delivery(8, 9, TEST_RELAYER_B);
+			assert_eq!(
+				sorted_recent_relayers(),
+				vec![(TEST_RELAYER_A, 3), (TEST_RELAYER_B, 3), (TEST_RELAYER_C, 1)],
+			);
+
+			// B replaces its own delivery and then the last delivery of A
+			track_recent_delivery(10, 10, TEST_RELAYER_B);
+			track_recent_delivery(11, 11, TEST_RELAYER_B);
+			assert_eq!(sorted_recent_relayers(), vec![(TEST_RELAYER_B, 4), (TEST_RELAYER_C, 1)]);
+
+			// C replaces the delivery of B
+			track_recent_delivery(12, 12, TEST_RELAYER_C);
+			assert_eq!(sorted_recent_relayers(), vec![(TEST_RELAYER_B, 2), (TEST_RELAYER_C, 2)]);
+			assert_eq!(RecentDeliveries::<TestRuntime>::iter_prefix(TEST_LANE_ID).count(), 4);
+		});
+	}
+
+	#[test]
+	fn recent_relayers_only_counts_confirmed_messages() {
+		run_test(|| {
+			track_recent_deliveries::<TestRuntime, ()>(
+				&TEST_LANE_ID,
+				&vec![
+					unrewarded_relayer(1, 2, TEST_RELAYER_A),
+					unrewarded_relayer(3, 5, TEST_RELAYER_B),
+					unrewarded_relayer(6, 7, TEST_RELAYER_C),
+				]
+				.into_iter()
+				.collect(),
+				&(2..=4),
+			);
+			assert_eq!(sorted_recent_relayers(), vec![(TEST_RELAYER_A, 1), (TEST_RELAYER_B, 2)]);
+			assert_eq!(RecentDeliveriesNextSlot::<TestRuntime>::get(TEST_LANE_ID), 2);
+		});
+	}
+
+	#[test]
+	fn unrewarded_relayers_summary_groups_entries_of_the_same_relayer() {
+		run_test(|| {
+			InboundLanes::<TestRuntime, ()>::insert(
+				TEST_LANE_ID,
+				InboundLaneData {
+					last_confirmed_nonce: 0,
+					relayers: vec![
+						unrewarded_relayer(1, 2, TEST_RELAYER_B),
+						unrewarded_relayer(3, 3, TEST_RELAYER_A),
+						unrewarded_relayer(4, 6, TEST_RELAYER_B),
+					]
+					.into_iter()
+					.collect(),
+				},
+			);
+
+			assert_eq!(
+				Pallet::<TestRuntime>::unrewarded_relayers_summary(TEST_LANE_ID),
+				vec![(TEST_RELAYER_A, 1), (TEST_RELAYER_B, 5)],
+			);
+			assert_eq!(
+				Pallet::<TestRuntime>::unrewarded_relayers_summary(LaneId([0, 0, 0, 2])),
+				vec![]
+			);
+		});
+	}
+
+	#[test]
+	fn lane_state_getters_return_actual_lane_states() {
+		run_test(|| {
+			send_regular_message();
+			receive_messages_delivery_proof();
+
+			assert_eq!(
+				Pallet::<TestRuntime>::outbound_lane_state(TEST_LANE_ID),
+				OutboundLanes::<TestRuntime, ()>::get(TEST_LANE_ID),
+			);
+			assert_eq!(
+				Pallet::<TestRuntime>::outbound_lane_state(TEST_LANE_ID).latest_received_nonce,
+				1,
+			);
+
+			InboundLanes::<TestRuntime, ()>::insert(
+				TEST_LANE_ID,
+				InboundLaneData {
+					last_confirmed_nonce: 1,
+					relayers: vec![
+						unrewarded_relayer(2, 3, TEST_RELAYER_A),
+						unrewarded_relayer(4, 4, TEST_RELAYER_B),
+					]
+					.into_iter()
+					.collect(),
+				},
+			);
+			assert_eq!(
+				Pallet::<TestRuntime>::inbound_lane_state(TEST_LANE_ID),
+				InboundLaneState {
+					last_confirmed_nonce: 1,
+					unrewarded_relayers: UnrewardedRelayersState {
+						unrewarded_relayer_entries: 2,
+						messages_in_oldest_entry: 2,
+						total_messages: 3,
+						last_delivered_nonce: 4,
+					},
+				},
+			);
+			assert_eq!(
+				Pallet::<TestRuntime>::inbound_lane_state(LaneId([0, 0, 0, 2])),
+				InboundLaneState::default(),
+			);
+		});
+	}
+
+	#[test]
+	fn receive_messages_delivery_proof_tracks_recent_relayers() {
+		run_test(|| {
+			send_regular_message();
+			send_regular_message();
+
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
+				Origin::signed(1),
+				TestMessagesDeliveryProof(Ok((
+					TEST_LANE_ID,
+					InboundLaneData {
+						relayers: vec![
+							unrewarded_relayer(1, 1, TEST_RELAYER_A),
+							unrewarded_relayer(2, 2, TEST_RELAYER_B)
+						]
+						.into_iter()
+						.collect(),
+						..Default::default()
+					}
+				))),
+				UnrewardedRelayersState {
+					unrewarded_relayer_entries: 2,
+					total_messages: 2,
+					last_delivered_nonce: 2,
+					..Default::default()
+				},
+			));
+			assert_eq!(sorted_recent_relayers(), vec![(TEST_RELAYER_A, 1), (TEST_RELAYER_B, 1)]);
+		});
+	}
+
+	fn indexed_message_event(
+		ext: &sp_io::TestExternalities,
+		lane_id: LaneId,
+		nonce: MessageNonce,
+		kind: MessageEventKind,
+	) -> Option<(u64, MessageEventKind)> {
+		let key = bp_messages::storage_keys::message_history_index_key(
+			<Pallet<TestRuntime> as PalletInfoAccess>::name(),
+			&lane_id,
+			nonce,
+			kind,
+		);
+		ext.offchain_db()
+			.get(&key)
+			.map(|value| Decode::decode(&mut &value[..]).expect("entry is decodable"))
+	}
+
+	fn deliver_and_confirm_regular_message() {
+		send_regular_message();
+		assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+			Origin::signed(1),
+			TEST_RELAYER_A,
+			Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+			1,
+			REGULAR_PAYLOAD.declared_weight,
+		));
+		assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
+			Origin::signed(1),
+			TestMessagesDeliveryProof(Ok((
+				TEST_LANE_ID,
+				InboundLaneData {
+					last_confirmed_nonce: 1,
+					relayers: vec![unrewarded_relayer(1, 1, TEST_RELAYER_A)].into_iter().collect(),
+				},
+			))),
+			UnrewardedRelayersState {
+				unrewarded_relayer_entries: 1,
+				messages_in_oldest_entry: 1,
+				total_messages: 1,
+				last_delivered_nonce: 1,
+			},
+		));
+	}
+
+	#[test]
+	fn messages_history_is_indexed_if_enabled() {
+		let mut ext = new_test_ext();
+		ext.execute_with(|| {
+			TestIndexMessagesHistory::set(&true);
+			deliver_and_confirm_regular_message();
 		});
+		ext.persist_offchain_overlay();
+
+		for kind in MessageEventKind::ALL {
+			assert_eq!(indexed_message_event(&ext, TEST_LANE_ID, 1, kind), Some((1, kind)));
+			assert_eq!(indexed_message_event(&ext, TEST_LANE_ID, 2, kind), None);
+		}
+	}
+
+	#[test]
+	fn messages_history_is_not_indexed_if_disabled() {
+		let mut ext = new_test_ext();
+		ext.execute_with(deliver_and_confirm_regular_message);
+		ext.persist_offchain_overlay();
+
+		for kind in MessageEventKind::ALL {
+			assert_eq!(indexed_message_event(&ext, TEST_LANE_ID, 1, kind), None);
+		}
 	}
 
 	generate_owned_bridge_module_tests!(
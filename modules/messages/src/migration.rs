@@ -0,0 +1,200 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Storage migrations of the messages pallet.
+
+use crate::{Config, InboundLanes, OpenedLanes, OutboundLanes, Pallet, LOG_TARGET};
+
+use frame_support::{
+	traits::{Get, GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+	weights::Weight,
+};
+use sp_std::marker::PhantomData;
+
+/// Migration from the storage version 0 to the storage version 1.
+///
+/// The version 1 has added the `OutboundMessageSubmitters` map. Encoding of existing
+/// `OutboundMessages` entries is unchanged, so messages that have been queued before the
+/// upgrade simply have no recorded submitter and the migration only bumps the storage version.
+pub mod v1 {
+	use super::*;
+
+	/// Storage migration from version 0 to version 1.
+	pub struct MigrateToV1<T, I = ()>(PhantomData<(T, I)>);
+
+	impl<T: Config<I>, I: 'static> OnRuntimeUpgrade for MigrateToV1<T, I> {
+		fn on_runtime_upgrade() -> Weight {
+			let on_chain_version = Pallet::<T, I>::on_chain_storage_version();
+			if on_chain_version >= 1 {
+				log::info!(
+					target: LOG_TARGET,
+					"Skipping migration to v1: on-chain storage version is {:?}",
+					on_chain_version,
+				);
+				return T::DbWeight::get().reads(1)
+			}
+
+			StorageVersion::new(1).put::<Pallet<T, I>>();
+
+			log::info!(target: LOG_TARGET, "Migrated to v1");
+
+			T::DbWeight::get().reads_writes(1, 1)
+		}
+	}
+
+	#[cfg(test)]
+	mod tests {
+		use super::*;
+		use crate::{
+			mock::{message_data, run_test, TestRuntime, REGULAR_PAYLOAD, TEST_LANE_ID},
+			OutboundMessages,
+		};
+		use bp_messages::MessageKey;
+
+		#[test]
+		fn migration_keeps_queued_messages_without_submitters() {
+			run_test(|| {
+				StorageVersion::new(0).put::<Pallet<TestRuntime>>();
+				let key = MessageKey { lane_id: TEST_LANE_ID, nonce: 1 };
+				OutboundMessages::<TestRuntime>::insert(key, message_data(REGULAR_PAYLOAD));
+
+				MigrateToV1::<TestRuntime>::on_runtime_upgrade();
+
+				assert_eq!(Pallet::<TestRuntime>::on_chain_storage_version(), 1);
+				assert_eq!(
+					Pallet::<TestRuntime>::outbound_message_data(TEST_LANE_ID, 1),
+					Some(message_data(REGULAR_PAYLOAD)),
+				);
+				assert_eq!(
+					Pallet::<TestRuntime>::outbound_message_submitter(TEST_LANE_ID, 1),
+					None
+				);
+			});
+		}
+
+		#[test]
+		fn migration_is_skipped_if_storage_is_already_migrated() {
+			run_test(|| {
+				StorageVersion::new(1).put::<Pallet<TestRuntime>>();
+
+				assert_eq!(
+					MigrateToV1::<TestRuntime>::on_runtime_upgrade(),
+					<TestRuntime as frame_system::Config>::DbWeight::get().reads(1),
+				);
+				assert_eq!(Pallet::<TestRuntime>::on_chain_storage_version(), 1);
+			});
+		}
+	}
+}
+
+/// Migration from the storage version 1 to the storage version 2.
+///
+/// The version 2 has added the `OpenedLanes` registry and messages at lanes that are not in the
+/// registry are rejected. So the migration opens all lanes that have been used before the
+/// upgrade, i.e. have inbound or outbound lane data in the storage.
+pub mod v2 {
+	use super::*;
+
+	/// Storage migration from version 1 to version 2.
+	///
+	/// Weight of this migration is proportional to the number of used lanes.
+	pub struct MigrateToV2<T, I = ()>(PhantomData<(T, I)>);
+
+	impl<T: Config<I>, I: 'static> OnRuntimeUpgrade for MigrateToV2<T, I> {
+		fn on_runtime_upgrade() -> Weight {
+			let on_chain_version = Pallet::<T, I>::on_chain_storage_version();
+			if on_chain_version >= 2 {
+				log::info!(
+					target: LOG_TARGET,
+					"Skipping migration to v2: on-chain storage version is {:?}",
+					on_chain_version,
+				);
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut used_lanes: u64 = 0;
+			let mut opened_lanes: u64 = 0;
+			for lane_id in
+				InboundLanes::<T, I>::iter_keys().chain(OutboundLanes::<T, I>::iter_keys())
+			{
+				used_lanes += 1;
+				if !OpenedLanes::<T, I>::contains_key(lane_id) {
+					OpenedLanes::<T, I>::insert(lane_id, ());
+					opened_lanes += 1;
+				}
+			}
+
+			StorageVersion::new(2).put::<Pallet<T, I>>();
+
+			log::info!(target: LOG_TARGET, "Migrated to v2: opened {} lanes", opened_lanes);
+
+			T::DbWeight::get()
+				.reads_writes(1, 1)
+				.saturating_add(T::DbWeight::get().reads(2).saturating_mul(used_lanes))
+				.saturating_add(T::DbWeight::get().writes(opened_lanes))
+		}
+	}
+
+	#[cfg(test)]
+	mod tests {
+		use super::*;
+		use crate::{
+			mock::{run_test, TestRuntime, TEST_LANE_ID},
+			StoredInboundLaneData,
+		};
+		use bp_messages::{InboundLaneData, LaneId, OutboundLaneData};
+
+		#[test]
+		fn migration_opens_used_lanes() {
+			run_test(|| {
+				StorageVersion::new(1).put::<Pallet<TestRuntime>>();
+				let inbound_lane = LaneId([0, 0, 0, 2]);
+				let outbound_lane = LaneId([0, 0, 0, 3]);
+				InboundLanes::<TestRuntime>::insert(
+					inbound_lane,
+					StoredInboundLaneData(InboundLaneData::default()),
+				);
+				OutboundLanes::<TestRuntime>::insert(outbound_lane, OutboundLaneData::default());
+				OutboundLanes::<TestRuntime>::insert(TEST_LANE_ID, OutboundLaneData::default());
+
+				MigrateToV2::<TestRuntime>::on_runtime_upgrade();
+
+				assert_eq!(Pallet::<TestRuntime>::on_chain_storage_version(), 2);
+				assert!(OpenedLanes::<TestRuntime>::contains_key(inbound_lane));
+				assert!(OpenedLanes::<TestRuntime>::contains_key(outbound_lane));
+				assert!(OpenedLanes::<TestRuntime>::contains_key(TEST_LANE_ID));
+				assert!(!OpenedLanes::<TestRuntime>::contains_key(LaneId([0, 0, 0, 4])));
+			});
+		}
+
+		#[test]
+		fn migration_is_skipped_if_storage_is_already_migrated() {
+			run_test(|| {
+				StorageVersion::new(2).put::<Pallet<TestRuntime>>();
+				OutboundLanes::<TestRuntime>::insert(
+					LaneId([0, 0, 0, 2]),
+					OutboundLaneData::default(),
+				);
+
+				assert_eq!(
+					MigrateToV2::<TestRuntime>::on_runtime_upgrade(),
+					<TestRuntime as frame_system::Config>::DbWeight::get().reads(1),
+				);
+				assert!(!OpenedLanes::<TestRuntime>::contains_key(LaneId([0, 0, 0, 2])));
+			});
+		}
+	}
+}
@@ -17,31 +17,32 @@
 // From construct_runtime macro
 #![allow(clippy::from_over_into)]
 
-use crate::{calc_relayers_rewards, Config};
+use crate::{calc_relayers_rewards, Config, OutboundRateLimit};
 
 use bitvec::prelude::*;
 use bp_messages::{
 	source_chain::{
-		LaneMessageVerifier, MessageDeliveryAndDispatchPayment, OnDeliveryConfirmed,
-		OnMessageAccepted, SenderOrigin, TargetHeaderChain,
+		LaneMessageVerifier, LinkedAccountSubmitter, MessageDeliveryAndDispatchPayment,
+		OnDeliveryConfirmed, OnMessageAccepted, SenderOrigin, TargetHeaderChain,
 	},
 	target_chain::{
 		DispatchMessage, MessageDispatch, ProvedLaneMessages, ProvedMessages, SourceHeaderChain,
 	},
 	DeliveredMessages, InboundLaneData, LaneId, Message, MessageData, MessageKey, MessageNonce,
-	OutboundLaneData, Parameter as MessagesParameter, UnrewardedRelayer,
+	OutboundLaneData, Parameter as MessagesParameter, UnrewardedRelayer, VerificationError,
 };
 use bp_runtime::{messages::MessageDispatchResult, Size};
 use codec::{Decode, Encode};
 use frame_support::{
 	parameter_types,
+	traits::GenesisBuild,
 	weights::{RuntimeDbWeight, Weight},
 };
 use scale_info::TypeInfo;
 use sp_core::H256;
 use sp_runtime::{
 	testing::Header as SubstrateHeader,
-	traits::{BlakeTwo256, IdentityLookup},
+	traits::{BlakeTwo256, Convert, IdentityLookup},
 	FixedU128, Perbill,
 };
 use std::{
@@ -140,8 +141,15 @@ parameter_types! {
 	pub const MaxMessagesToPruneAtOnce: u64 = 10;
 	pub const MaxUnrewardedRelayerEntriesAtInboundLane: u64 = 16;
 	pub const MaxUnconfirmedMessagesAtInboundLane: u64 = 32;
+	pub const MaxRecentDeliveries: u32 = 4;
 	pub storage TokenConversionRate: FixedU128 = 1.into();
 	pub const TestBridgedChainId: bp_runtime::ChainId = *b"test";
+	pub storage TestOutboundMessagesRateLimit: Option<OutboundRateLimit<u64>> = None;
+	pub storage TestMessageExpiryBlocks: u64 = 0;
+	pub storage TestMaxOutboundQueueLen: u64 = 128;
+	pub storage TestIndexMessagesHistory: bool = false;
+	pub storage TestMaxMessagesProofSize: u32 = u32::MAX;
+	pub storage TestMaxMessagesDeliveryProofSize: u32 = u32::MAX;
 }
 
 #[derive(Debug, Clone, Encode, Decode, PartialEq, Eq, TypeInfo)]
@@ -163,8 +171,15 @@ impl Config for TestRuntime {
 	type WeightInfo = ();
 	type Parameter = TestMessagesParameter;
 	type MaxMessagesToPruneAtOnce = MaxMessagesToPruneAtOnce;
+	type MaxOutboundQueueLen = TestMaxOutboundQueueLen;
 	type MaxUnrewardedRelayerEntriesAtInboundLane = MaxUnrewardedRelayerEntriesAtInboundLane;
 	type MaxUnconfirmedMessagesAtInboundLane = MaxUnconfirmedMessagesAtInboundLane;
+	type MaxRecentDeliveries = MaxRecentDeliveries;
+	type MessageExpiryBlocks = TestMessageExpiryBlocks;
+	type OutboundMessagesRateLimit = TestOutboundMessagesRateLimit;
+	type RateLimitedSender = TestRateLimitedSender;
+	type MessageSubmitter = LinkedAccountSubmitter<AccountId>;
+	type IndexMessagesHistory = TestIndexMessagesHistory;
 
 	type MaximalOutboundPayloadSize = frame_support::traits::ConstU32<MAX_OUTBOUND_PAYLOAD_SIZE>;
 	type OutboundPayload = TestPayload;
@@ -173,6 +188,7 @@ impl Config for TestRuntime {
 	type InboundPayload = TestPayload;
 	type InboundMessageFee = TestMessageFee;
 	type InboundRelayer = TestRelayer;
+	type MaximalInboundPayloadSize = frame_support::traits::ConstU32<MAX_INBOUND_PAYLOAD_SIZE>;
 
 	type TargetHeaderChain = TestTargetHeaderChain;
 	type LaneMessageVerifier = TestLaneMessageVerifier;
@@ -181,10 +197,21 @@ impl Config for TestRuntime {
 	type OnDeliveryConfirmed = (TestOnDeliveryConfirmed1, TestOnDeliveryConfirmed2);
 
 	type SourceHeaderChain = TestSourceHeaderChain;
+	type MaxMessagesProofSize = TestMaxMessagesProofSize;
+	type MaxMessagesDeliveryProofSize = TestMaxMessagesDeliveryProofSize;
 	type MessageDispatch = TestMessageDispatch;
 	type BridgedChainId = TestBridgedChainId;
 }
 
+/// Rate-limited sender of outbound messages.
+pub struct TestRateLimitedSender;
+
+impl Convert<Origin, Option<AccountId>> for TestRateLimitedSender {
+	fn convert(origin: Origin) -> Option<AccountId> {
+		origin.linked_account()
+	}
+}
+
 impl SenderOrigin<AccountId> for Origin {
 	fn linked_account(&self) -> Option<AccountId> {
 		match self.caller {
@@ -204,6 +231,9 @@ impl Size for TestPayload {
 /// Maximal outbound payload size.
 pub const MAX_OUTBOUND_PAYLOAD_SIZE: u32 = 4096;
 
+/// Default maximal inbound payload size.
+pub const MAX_INBOUND_PAYLOAD_SIZE: u32 = 4096;
+
 /// Account that has balance to use in tests.
 pub const ENDOWED_ACCOUNT: AccountId = 0xDEAD;
 
@@ -220,7 +250,7 @@ pub const TEST_RELAYER_C: AccountId = 102;
 pub const TEST_ERROR: &str = "Test error";
 
 /// Lane that we're using in tests.
-pub const TEST_LANE_ID: LaneId = [0, 0, 0, 1];
+pub const TEST_LANE_ID: LaneId = LaneId([0, 0, 0, 1]);
 
 /// Regular message payload.
 pub const REGULAR_PAYLOAD: TestPayload = message_payload(0, 50);
@@ -239,7 +269,7 @@ pub struct TestMessagesProof {
 
 impl Size for TestMessagesProof {
 	fn size(&self) -> u32 {
-		0
+		self.encoded_size() as u32
 	}
 }
 
@@ -266,7 +296,7 @@ pub struct TestMessagesDeliveryProof(pub Result<(LaneId, InboundLaneData<TestRel
 
 impl Size for TestMessagesDeliveryProof {
 	fn size(&self) -> u32 {
-		0
+		self.encoded_size() as u32
 	}
 }
 
@@ -275,13 +305,13 @@ impl Size for TestMessagesDeliveryProof {
 pub struct TestTargetHeaderChain;
 
 impl TargetHeaderChain<TestPayload, TestRelayer> for TestTargetHeaderChain {
-	type Error = &'static str;
+	type Error = VerificationError;
 
 	type MessagesDeliveryProof = TestMessagesDeliveryProof;
 
 	fn verify_message(payload: &TestPayload) -> Result<(), Self::Error> {
 		if *payload == PAYLOAD_REJECTED_BY_TARGET_CHAIN {
-			Err(TEST_ERROR)
+			Err(VerificationError::Other(TEST_ERROR))
 		} else {
 			Ok(())
 		}
@@ -290,7 +320,7 @@ impl TargetHeaderChain<TestPayload, TestRelayer> for TestTargetHeaderChain {
 	fn verify_messages_delivery_proof(
 		proof: Self::MessagesDeliveryProof,
 	) -> Result<(LaneId, InboundLaneData<TestRelayer>), Self::Error> {
-		proof.0.map_err(|_| TEST_ERROR)
+		proof.0.map_err(|_| VerificationError::Other(TEST_ERROR))
 	}
 }
 
@@ -299,7 +329,7 @@ impl TargetHeaderChain<TestPayload, TestRelayer> for TestTargetHeaderChain {
 pub struct TestLaneMessageVerifier;
 
 impl LaneMessageVerifier<Origin, TestPayload, TestMessageFee> for TestLaneMessageVerifier {
-	type Error = &'static str;
+	type Error = VerificationError;
 
 	fn verify_message(
 		_submitter: &Origin,
@@ -311,7 +341,7 @@ impl LaneMessageVerifier<Origin, TestPayload, TestMessageFee> for TestLaneMessag
 		if *delivery_and_dispatch_fee != 0 {
 			Ok(())
 		} else {
-			Err(TEST_ERROR)
+			Err(VerificationError::TooLowFee)
 		}
 	}
 }
@@ -332,6 +362,11 @@ impl TestMessageDeliveryAndDispatchPayment {
 		frame_support::storage::unhashed::get(b":message-fee:") == Some((raw_origin.unwrap(), fee))
 	}
 
+	/// Returns true if given fee has been refunded to given submitter.
+	pub fn is_fee_refunded(submitter: AccountId, fee: TestMessageFee) -> bool {
+		frame_support::storage::unhashed::get(b":message-fee-refund:") == Some((submitter, fee))
+	}
+
 	/// Returns true if given relayer has been rewarded with given balance. The reward-paid flag is
 	/// cleared after the call.
 	pub fn is_reward_paid(relayer: AccountId, fee: TestMessageFee) -> bool {
@@ -358,6 +393,19 @@ impl MessageDeliveryAndDispatchPayment<Origin, AccountId, TestMessageFee>
 		Ok(())
 	}
 
+	fn refund_delivery_and_dispatch_fee(
+		submitter: &Origin,
+		fee: &TestMessageFee,
+	) -> Result<(), Self::Error> {
+		if frame_support::storage::unhashed::get(b":reject-message-fee:") == Some(true) {
+			return Err(TEST_ERROR)
+		}
+
+		let submitter = submitter.linked_account().expect("refunds are only paid to accounts");
+		frame_support::storage::unhashed::put(b":message-fee-refund:", &(submitter, fee));
+		Ok(())
+	}
+
 	fn pay_relayers_rewards(
 		lane_id: LaneId,
 		message_relayers: VecDeque<UnrewardedRelayer<AccountId>>,
@@ -460,7 +508,7 @@ impl OnDeliveryConfirmed for TestOnDeliveryConfirmed2 {
 pub struct TestSourceHeaderChain;
 
 impl SourceHeaderChain<TestMessageFee> for TestSourceHeaderChain {
-	type Error = &'static str;
+	type Error = VerificationError;
 
 	type MessagesProof = TestMessagesProof;
 
@@ -468,7 +516,10 @@ impl SourceHeaderChain<TestMessageFee> for TestSourceHeaderChain {
 		proof: Self::MessagesProof,
 		_messages_count: u32,
 	) -> Result<ProvedMessages<Message<TestMessageFee>>, Self::Error> {
-		proof.result.map(|proof| proof.into_iter().collect()).map_err(|_| TEST_ERROR)
+		proof
+			.result
+			.map(|proof| proof.into_iter().collect())
+			.map_err(|_| VerificationError::Other(TEST_ERROR))
 	}
 }
 
@@ -480,7 +531,7 @@ impl MessageDispatch<AccountId, TestMessageFee> for TestMessageDispatch {
 	type DispatchPayload = TestPayload;
 
 	fn dispatch_weight(message: &mut DispatchMessage<TestPayload, TestMessageFee>) -> Weight {
-		match message.data.payload.as_ref() {
+		match message.data.payload.decoded() {
 			Ok(payload) => payload.declared_weight,
 			Err(_) => 0,
 		}
@@ -490,8 +541,8 @@ impl MessageDispatch<AccountId, TestMessageFee> for TestMessageDispatch {
 		_relayer_account: &AccountId,
 		message: DispatchMessage<TestPayload, TestMessageFee>,
 	) -> MessageDispatchResult {
-		match message.data.payload.as_ref() {
-			Ok(payload) => payload.dispatch_result.clone(),
+		match message.data.payload.into_decoded() {
+			Ok(payload) => payload.dispatch_result,
 			Err(_) => dispatch_result(0),
 		}
 	}
@@ -518,6 +569,7 @@ pub const fn dispatch_result(unspent_weight: Weight) -> MessageDispatchResult {
 		dispatch_result: true,
 		unspent_weight,
 		dispatch_fee_paid_during_dispatch: true,
+		dispatch_error: None,
 	}
 }
 
@@ -541,12 +593,24 @@ pub fn unrewarded_relayer(
 	}
 }
 
-/// Run pallet test.
-pub fn run_test<T>(test: impl FnOnce() -> T) -> T {
+/// Creates externalities for pallet test.
+pub fn new_test_ext() -> sp_io::TestExternalities {
 	let mut t = frame_system::GenesisConfig::default().build_storage::<TestRuntime>().unwrap();
 	pallet_balances::GenesisConfig::<TestRuntime> { balances: vec![(ENDOWED_ACCOUNT, 1_000_000)] }
 		.assimilate_storage(&mut t)
 		.unwrap();
-	let mut ext = sp_io::TestExternalities::new(t);
-	ext.execute_with(test)
+	GenesisBuild::<TestRuntime>::assimilate_storage(
+		&pallet_bridge_messages::GenesisConfig::<TestRuntime> {
+			opened_lanes: vec![TEST_LANE_ID],
+			..Default::default()
+		},
+		&mut t,
+	)
+	.unwrap();
+	sp_io::TestExternalities::new(t)
+}
+
+/// Run pallet test.
+pub fn run_test<T>(test: impl FnOnce() -> T) -> T {
+	new_test_ext().execute_with(test)
 }
@@ -203,10 +203,16 @@ pub trait WeightInfoExt: WeightInfo {
 		let transaction_overhead = Self::send_message_overhead();
 		let message_size_overhead = Self::send_message_size_overhead(message.size());
 		let call_back_overhead = Self::single_message_callback_overhead(db_weight);
+		// rate limit check: owner, sent messages and oldest window reads + two updates
+		let rate_limit_overhead = db_weight.reads_writes(3, 2);
+		// message submitter may be saved along with the message
+		let submitter_overhead = db_weight.writes(1);
 
 		transaction_overhead
 			.saturating_add(message_size_overhead)
 			.saturating_add(call_back_overhead)
+			.saturating_add(rate_limit_overhead)
+			.saturating_add(submitter_overhead)
 	}
 
 	/// Weight of message delivery extrinsic.
@@ -266,11 +272,22 @@ pub trait WeightInfoExt: WeightInfo {
 			.total_messages
 			.saturating_mul(Self::single_message_callback_overhead(db_weight));
 
+		// and cost of remembering every confirmed delivery in the recent deliveries window
+		let recent_deliveries_overhead = relayers_state
+			.unrewarded_relayer_entries
+			.saturating_mul(Self::single_recent_delivery_overhead(db_weight))
+			.saturating_add(db_weight.reads_writes(1, 1));
+
+		// and cost of reporting confirmed messages that have been cancelled or expired
+		let refunded_messages_overhead = db_weight.reads(1);
+
 		transaction_overhead
 			.saturating_add(messages_overhead)
 			.saturating_add(relayers_overhead)
 			.saturating_add(proof_size_overhead)
 			.saturating_add(callback_overhead)
+			.saturating_add(recent_deliveries_overhead)
+			.saturating_add(refunded_messages_overhead)
 	}
 
 	// Functions that are used by extrinsics weights formulas.
@@ -384,6 +401,14 @@ pub trait WeightInfoExt: WeightInfo {
 	fn single_message_callback_overhead(db_weight: RuntimeDbWeight) -> Weight {
 		db_weight.reads_writes(1, 1)
 	}
+
+	/// Returns weight of remembering single confirmed delivery in the recent deliveries window.
+	///
+	/// We need to read the oldest delivery, update counters of both old and new relayers and
+	/// overwrite the oldest delivery.
+	fn single_recent_delivery_overhead(db_weight: RuntimeDbWeight) -> Weight {
+		db_weight.reads_writes(3, 3)
+	}
 }
 
 impl WeightInfoExt for () {
@@ -26,8 +26,11 @@
 pub use weights::WeightInfo;
 pub use weights_ext::WeightInfoExt;
 
-use bp_parachains::{parachain_head_storage_key_at_source, ParaInfo};
-use bp_polkadot_core::parachains::{ParaHash, ParaHasher, ParaHead, ParaHeadsProof, ParaId};
+use bp_parachains::ParaInfo;
+use bp_polkadot_core::parachains::{
+	parachain_head_storage_key_at_source, ParaHash, ParaHasher, ParaHead, ParaHeadsProof, ParaId,
+	ParaStoredHeaderData,
+};
 use bp_runtime::StorageProofError;
 use frame_support::{traits::Contains, weights::PostDispatchInfo};
 use sp_runtime::traits::Header as HeaderT;
@@ -69,7 +72,8 @@ pub mod pallet {
 	use super::*;
 	use bp_parachains::{BestParaHeadHash, ImportedParaHeadsKeyProvider, ParasInfoKeyProvider};
 	use bp_runtime::{
-		BasicOperatingMode, OwnedBridgeModule, StorageDoubleMapKeyProvider, StorageMapKeyProvider,
+		BasicOperatingMode, OwnedBridgeModule, Size, StorageDoubleMapKeyProvider,
+		StorageMapKeyProvider,
 	};
 	use frame_support::pallet_prelude::*;
 	use frame_system::pallet_prelude::*;
@@ -96,6 +100,17 @@ pub mod pallet {
 		RejectedObsoleteParachainHead { parachain: ParaId, parachain_head_hash: ParaHash },
 		/// Parachain head has been updated.
 		UpdatedParachainHead { parachain: ParaId, parachain_head_hash: ParaHash },
+		/// New pallet owner has been proposed.
+		OwnershipTransferProposed { pending_owner: T::AccountId },
+		/// Pallet ownership has been transferred.
+		OwnershipTransferred { old_owner: Option<T::AccountId>, new_owner: T::AccountId },
+		/// Pending pallet ownership transfer has been cancelled.
+		OwnershipTransferCancelled { pending_owner: T::AccountId },
+		/// Pallet operating mode has been changed.
+		OperatingModeChanged {
+			old_operating_mode: BasicOperatingMode,
+			new_operating_mode: BasicOperatingMode,
+		},
 	}
 
 	#[pallet::error]
@@ -112,6 +127,8 @@ pub mod pallet {
 		StorageRootMismatch,
 		/// Failed to extract state root from given parachain head.
 		FailedToExtractStateRoot,
+		/// The parachain heads proof is larger than `MaxParaHeadsProofSize`.
+		HeadsProofIsTooLarge,
 		/// Error generated by the `OwnedBridgeModule` trait.
 		BridgeModule(bp_runtime::OwnedBridgeModuleError),
 	}
@@ -156,6 +173,12 @@ pub mod pallet {
 		/// Incautious change of this constant may lead to orphan entries in the runtime storage.
 		#[pallet::constant]
 		type HeadsToKeep: Get<u32>;
+
+		/// Maximal size (in bytes) of the parachain heads proof that the pallet accepts.
+		///
+		/// Larger proofs are rejected before any verification.
+		#[pallet::constant]
+		type MaxParaHeadsProofSize: Get<u32>;
 	}
 
 	/// Optional pallet owner.
@@ -168,6 +191,13 @@ pub mod pallet {
 	pub type PalletOwner<T: Config<I>, I: 'static = ()> =
 		StorageValue<_, T::AccountId, OptionQuery>;
 
+	/// Account that has been proposed to become the new pallet owner.
+	///
+	/// The ownership is transferred when this account calls `accept_ownership`.
+	#[pallet::storage]
+	pub type PalletPendingOwner<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, T::AccountId, OptionQuery>;
+
 	/// The current operating mode of the pallet.
 	///
 	/// Depending on the mode either all, or no transactions will be allowed.
@@ -212,6 +242,7 @@ pub mod pallet {
 	impl<T: Config<I>, I: 'static> OwnedBridgeModule<T> for Pallet<T, I> {
 		const LOG_TARGET: &'static str = LOG_TARGET;
 		type OwnerStorage = PalletOwner<T, I>;
+		type PendingOwnerStorage = PalletPendingOwner<T, I>;
 		type OperatingMode = BasicOperatingMode;
 		type OperatingModeStorage = PalletOperatingMode<T, I>;
 	}
@@ -244,6 +275,11 @@ pub mod pallet {
 			parachain_heads_proof: ParaHeadsProof,
 		) -> DispatchResultWithPostInfo {
 			Self::ensure_not_halted().map_err(Error::<T, I>::BridgeModule)?;
+			ensure!(
+				parachain_heads_proof.size() <= T::MaxParaHeadsProofSize::get(),
+				Error::<T, I>::HeadsProofIsTooLarge,
+			);
+
 			// we'll need relay chain header to verify that parachains heads are always increasing.
 			let (relay_block_number, relay_block_hash) = at_relay_block;
 			let relay_block = pallet_bridge_grandpa::ImportedHeaders::<
@@ -359,21 +395,64 @@ pub mod pallet {
 
 		/// Change `PalletOwner`.
 		///
-		/// May only be called either by root, or by `PalletOwner`.
+		/// May only be called by root.
 		#[pallet::weight((T::DbWeight::get().reads_writes(1, 1), DispatchClass::Operational))]
 		pub fn set_owner(origin: OriginFor<T>, new_owner: Option<T::AccountId>) -> DispatchResult {
 			<Self as OwnedBridgeModule<_>>::set_owner(origin, new_owner)
 		}
 
+		/// Propose new `PalletOwner`.
+		///
+		/// May only be called either by root, or by `PalletOwner`. The ownership is only
+		/// transferred after the proposed account calls `accept_ownership`.
+		#[pallet::weight((T::DbWeight::get().reads_writes(1, 1), DispatchClass::Operational))]
+		pub fn propose_owner(origin: OriginFor<T>, new_owner: T::AccountId) -> DispatchResult {
+			<Self as OwnedBridgeModule<_>>::propose_owner(origin, new_owner.clone())?;
+			Self::deposit_event(Event::OwnershipTransferProposed { pending_owner: new_owner });
+			Ok(())
+		}
+
+		/// Accept pallet ownership.
+		///
+		/// May only be called by the account that has been proposed by the `propose_owner` call.
+		#[pallet::weight((T::DbWeight::get().reads_writes(2, 2), DispatchClass::Operational))]
+		pub fn accept_ownership(origin: OriginFor<T>) -> DispatchResult {
+			let (old_owner, new_owner) = <Self as OwnedBridgeModule<_>>::accept_ownership(origin)
+				.map_err(Error::<T, I>::BridgeModule)?;
+			Self::deposit_event(Event::OwnershipTransferred { old_owner, new_owner });
+			Ok(())
+		}
+
+		/// Cancel pending `PalletOwner` change.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::weight((T::DbWeight::get().reads_writes(2, 1), DispatchClass::Operational))]
+		pub fn cancel_pending_owner(origin: OriginFor<T>) -> DispatchResult {
+			let pending_owner = <Self as OwnedBridgeModule<_>>::cancel_pending_owner(origin)?
+				.ok_or(Error::<T, I>::BridgeModule(
+					bp_runtime::OwnedBridgeModuleError::NoPendingOwner,
+				))?;
+			Self::deposit_event(Event::OwnershipTransferCancelled { pending_owner });
+			Ok(())
+		}
+
 		/// Halt or resume all pallet operations.
 		///
 		/// May only be called either by root, or by `PalletOwner`.
-		#[pallet::weight((T::DbWeight::get().reads_writes(1, 1), DispatchClass::Operational))]
+		#[pallet::weight((T::DbWeight::get().reads_writes(2, 1), DispatchClass::Operational))]
 		pub fn set_operating_mode(
 			origin: OriginFor<T>,
 			operating_mode: BasicOperatingMode,
 		) -> DispatchResult {
-			<Self as OwnedBridgeModule<_>>::set_operating_mode(origin, operating_mode)
+			Self::ensure_owner_or_root(origin)?;
+			let old_operating_mode =
+				<Self as OwnedBridgeModule<_>>::set_operating_mode(operating_mode)
+					.map_err(Error::<T, I>::BridgeModule)?;
+			Self::deposit_event(Event::OperatingModeChanged {
+				old_operating_mode,
+				new_operating_mode: operating_mode,
+			});
+			Ok(())
 		}
 	}
 
@@ -389,20 +468,32 @@ pub mod pallet {
 			ImportedParaHeads::<T, I>::get(parachain, hash)
 		}
 
+		/// Get data of parachain head with given hash.
+		///
+		/// The pallet doesn't know the header type of the parachain, so the head is decoded
+		/// using the given header type `H`.
+		pub fn parachain_head_data<H: HeaderT>(
+			parachain: ParaId,
+			hash: ParaHash,
+		) -> Result<ParaStoredHeaderData<H::Number, H::Hash>, Error<T, I>> {
+			Self::parachain_head(parachain, hash)
+				.ok_or(Error::<T, I>::UnknownParaHead)?
+				.stored_header_data::<H>()
+				.map_err(|_| Error::<T, I>::FailedToExtractStateRoot)
+		}
+
 		/// Verify that the passed storage proof is valid, given it is crafted using
 		/// known finalized header. If the proof is valid, then the `parse` callback
 		/// is called and the function returns its result.
-		pub fn parse_finalized_storage_proof<R>(
+		///
+		/// The state root is read from the parachain head, decoded using header type `H`.
+		pub fn parse_finalized_storage_proof<H: HeaderT<Hash = ParaHash>, R>(
 			parachain: ParaId,
 			hash: ParaHash,
 			storage_proof: sp_trie::StorageProof,
-			decode_state_root: impl FnOnce(ParaHead) -> Option<ParaHash>,
 			parse: impl FnOnce(bp_runtime::StorageProofChecker<ParaHasher>) -> R,
 		) -> Result<R, sp_runtime::DispatchError> {
-			let para_head =
-				Self::parachain_head(parachain, hash).ok_or(Error::<T, I>::UnknownParaHead)?;
-			let state_root =
-				decode_state_root(para_head).ok_or(Error::<T, I>::FailedToExtractStateRoot)?;
+			let state_root = Self::parachain_head_data::<H>(parachain, hash)?.state_root;
 			let storage_proof_checker =
 				bp_runtime::StorageProofChecker::new(state_root, storage_proof)
 					.map_err(|_| Error::<T, I>::StorageRootMismatch)?;
@@ -575,14 +666,14 @@ pub mod pallet {
 mod tests {
 	use super::*;
 	use crate::mock::{
-		run_test, test_relay_header, Event as TestEvent, Origin, TestRuntime, PARAS_PALLET_NAME,
-		UNTRACKED_PARACHAIN_ID,
+		run_test, test_relay_header, Event as TestEvent, Origin, RelayBlockHeader, TestRuntime,
+		PARAS_PALLET_NAME, UNTRACKED_PARACHAIN_ID,
 	};
 	use codec::Encode;
 
 	use bp_parachains::{BestParaHeadHash, ImportedParaHeadsKeyProvider, ParasInfoKeyProvider};
 	use bp_runtime::{
-		record_all_trie_keys, BasicOperatingMode, OwnedBridgeModuleError,
+		record_all_trie_keys, BasicOperatingMode, OwnedBridgeModuleError, Size,
 		StorageDoubleMapKeyProvider, StorageMapKeyProvider,
 	};
 	use bp_test_utils::{
@@ -611,6 +702,7 @@ mod tests {
 				authority_list: authority_list(),
 				set_id: 1,
 				operating_mode: BasicOperatingMode::Normal,
+				checkpoints: vec![],
 			},
 		)
 		.unwrap();
@@ -733,6 +825,101 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn submit_parachain_heads_accepts_proof_of_maximal_size() {
+		let (state_root, proof, parachains) =
+			prepare_parachain_heads_proof(vec![(1, head_data(1, 0))]);
+
+		run_test(|| {
+			initialize(state_root);
+
+			crate::mock::MaxParaHeadsProofSize::set(&proof.size());
+			assert_ok!(import_parachain_1_head(0, state_root, parachains, proof));
+		});
+	}
+
+	#[test]
+	fn submit_parachain_heads_rejects_proof_that_is_too_large() {
+		let (state_root, proof, parachains) =
+			prepare_parachain_heads_proof(vec![(1, head_data(1, 0))]);
+
+		run_test(|| {
+			initialize(state_root);
+
+			crate::mock::MaxParaHeadsProofSize::set(&(proof.size() - 1));
+			assert_noop!(
+				import_parachain_1_head(0, state_root, parachains, proof),
+				Error::<TestRuntime>::HeadsProofIsTooLarge
+			);
+		});
+	}
+
+	#[test]
+	fn imports_parachain_heads_using_deduplicated_proof() {
+		let (state_root, proof, parachains) =
+			prepare_parachain_heads_proof(vec![(1, head_data(1, 0)), (3, head_data(3, 10))]);
+		let mut duplicated_nodes = proof.0.clone();
+		duplicated_nodes.extend(proof.0.iter().cloned());
+		let (deduplicated_nodes, deduplicated_size) =
+			bp_runtime::deduplicate_storage_proof(duplicated_nodes);
+		let deduplicated_proof = ParaHeadsProof(deduplicated_nodes);
+		assert_eq!(deduplicated_proof, proof);
+		assert_eq!(deduplicated_size.size(), proof.size());
+
+		run_test(|| {
+			initialize(state_root);
+
+			assert_ok!(Pallet::<TestRuntime>::submit_parachain_heads(
+				Origin::signed(1),
+				(0, test_relay_header(0, state_root).hash()),
+				parachains,
+				deduplicated_proof,
+			));
+			assert_eq!(ParasInfo::<TestRuntime>::get(ParaId(1)), Some(initial_best_head(1)));
+		});
+	}
+
+	#[test]
+	fn imports_parachain_heads_using_trimmed_proof() {
+		let (state_root, proof, parachains) =
+			prepare_parachain_heads_proof((1..=8).map(|i| (i, head_data(i, i * 10))).collect());
+		let imported_parachains = parachains
+			.into_iter()
+			.filter(|(parachain, _)| [ParaId(1), ParaId(3)].contains(parachain))
+			.collect::<Vec<_>>();
+		let (trimmed_nodes, trimmed_size) = bp_runtime::trim_storage_proof::<RelayBlockHasher>(
+			state_root,
+			sp_trie::StorageProof::new(proof.0.clone()),
+			imported_parachains.iter().map(|(parachain, _)| {
+				parachain_head_storage_key_at_source(PARAS_PALLET_NAME, *parachain)
+			}),
+		)
+		.unwrap();
+		let trimmed_proof = ParaHeadsProof(trimmed_nodes);
+		assert_eq!(trimmed_size.size(), trimmed_proof.size());
+		assert!(trimmed_proof.size() < proof.size());
+
+		run_test(|| {
+			initialize(state_root);
+
+			assert_ok!(Pallet::<TestRuntime>::submit_parachain_heads(
+				Origin::signed(1),
+				(0, test_relay_header(0, state_root).hash()),
+				imported_parachains,
+				trimmed_proof,
+			));
+			assert_eq!(
+				ParasInfo::<TestRuntime>::get(ParaId(1)).map(|info| info.best_head_hash.head_hash),
+				Some(head_data(1, 10).hash()),
+			);
+			assert_eq!(
+				ParasInfo::<TestRuntime>::get(ParaId(3)).map(|info| info.best_head_hash.head_hash),
+				Some(head_data(3, 30).hash()),
+			);
+			assert_eq!(ParasInfo::<TestRuntime>::get(ParaId(2)), None);
+		});
+	}
+
 	#[test]
 	fn imports_initial_parachain_heads() {
 		let (state_root, proof, parachains) =
@@ -1296,5 +1483,41 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn parachain_head_data_is_decoded_from_stored_head() {
+		run_test(|| {
+			let header = test_relay_header(5, RelayBlockHash::repeat_byte(42));
+			let para_head = ParaHead(header.encode());
+			ImportedParaHeads::<TestRuntime>::insert(
+				ParaId(1),
+				para_head.hash(),
+				para_head.clone(),
+			);
+			ImportedParaHeads::<TestRuntime>::insert(ParaId(1), head_hash(1, 0), head_data(1, 0));
+
+			assert_eq!(
+				Pallet::<TestRuntime>::parachain_head_data::<RelayBlockHeader>(
+					ParaId(1),
+					para_head.hash(),
+				),
+				Ok(ParaStoredHeaderData { number: 5, state_root: RelayBlockHash::repeat_byte(42) }),
+			);
+			assert_eq!(
+				Pallet::<TestRuntime>::parachain_head_data::<RelayBlockHeader>(
+					ParaId(1),
+					head_hash(1, 0),
+				),
+				Err(Error::<TestRuntime>::FailedToExtractStateRoot),
+			);
+			assert_eq!(
+				Pallet::<TestRuntime>::parachain_head_data::<RelayBlockHeader>(
+					ParaId(2),
+					para_head.hash(),
+				),
+				Err(Error::<TestRuntime>::UnknownParaHead),
+			);
+		});
+	}
+
 	generate_owned_bridge_module_tests!(BasicOperatingMode::Normal, BasicOperatingMode::Halted);
 }
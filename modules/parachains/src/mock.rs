@@ -44,8 +44,8 @@ construct_runtime! {
 		UncheckedExtrinsic = UncheckedExtrinsic,
 	{
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
-		Grandpa1: pallet_bridge_grandpa::<Instance1>::{Pallet},
-		Grandpa2: pallet_bridge_grandpa::<Instance2>::{Pallet},
+		Grandpa1: pallet_bridge_grandpa::<Instance1>::{Pallet, Event<T>},
+		Grandpa2: pallet_bridge_grandpa::<Instance2>::{Pallet, Event<T>},
 		Parachains: pallet_bridge_parachains::{Call, Pallet, Event<T>},
 	}
 }
@@ -86,22 +86,42 @@ impl frame_system::Config for TestRuntime {
 
 parameter_types! {
 	pub const MaxRequests: u32 = 2;
+	pub const MaxRequestsWeight: Weight = Weight::MAX;
 	pub const HeadersToKeep: u32 = 5;
+	pub const JustificationsToKeep: u32 = 0;
 	pub const SessionLength: u64 = 5;
 	pub const NumValidators: u32 = 5;
+	pub const MaxJustificationSize: u32 = 4096;
+	pub const MaxBridgedAuthorities: u32 = 5;
+	pub const MaxBridgedHeaderSize: u32 = 1024;
+	pub const MaxFinalityProofsInBatch: u32 = 4;
 }
 
 impl pallet_bridge_grandpa::Config<pallet_bridge_grandpa::Instance1> for TestRuntime {
+	type Event = Event;
 	type BridgedChain = TestBridgedChain;
 	type MaxRequests = MaxRequests;
+	type MaxRequestsWeight = MaxRequestsWeight;
 	type HeadersToKeep = HeadersToKeep;
+	type JustificationsToKeep = JustificationsToKeep;
+	type MaxJustificationSize = MaxJustificationSize;
+	type MaxBridgedAuthorities = MaxBridgedAuthorities;
+	type MaxBridgedHeaderSize = MaxBridgedHeaderSize;
+	type MaxFinalityProofsInBatch = MaxFinalityProofsInBatch;
 	type WeightInfo = ();
 }
 
 impl pallet_bridge_grandpa::Config<pallet_bridge_grandpa::Instance2> for TestRuntime {
+	type Event = Event;
 	type BridgedChain = TestBridgedChain;
 	type MaxRequests = MaxRequests;
+	type MaxRequestsWeight = MaxRequestsWeight;
 	type HeadersToKeep = HeadersToKeep;
+	type JustificationsToKeep = JustificationsToKeep;
+	type MaxJustificationSize = MaxJustificationSize;
+	type MaxBridgedAuthorities = MaxBridgedAuthorities;
+	type MaxBridgedHeaderSize = MaxBridgedHeaderSize;
+	type MaxFinalityProofsInBatch = MaxFinalityProofsInBatch;
 	type WeightInfo = ();
 }
 
@@ -109,6 +129,7 @@ parameter_types! {
 	pub const HeadsToKeep: u32 = 4;
 	pub const ParasPalletName: &'static str = PARAS_PALLET_NAME;
 	pub GetTenFirstParachains: Vec<ParaId> = (0..10).map(ParaId).collect();
+	pub storage MaxParaHeadsProofSize: u32 = 64 * 1024;
 }
 
 impl pallet_bridge_parachains::Config for TestRuntime {
@@ -118,6 +139,7 @@ impl pallet_bridge_parachains::Config for TestRuntime {
 	type ParasPalletName = ParasPalletName;
 	type TrackedParachains = IsInVec<GetTenFirstParachains>;
 	type HeadsToKeep = HeadsToKeep;
+	type MaxParaHeadsProofSize = MaxParaHeadsProofSize;
 }
 
 #[derive(Debug)]
@@ -99,8 +99,15 @@ impl pallet_bridge_messages::Config for TestRuntime {
 	type WeightInfo = ();
 	type Parameter = ();
 	type MaxMessagesToPruneAtOnce = frame_support::traits::ConstU64<0>;
+	type MaxOutboundQueueLen = frame_support::traits::ConstU64<128>;
 	type MaxUnrewardedRelayerEntriesAtInboundLane = frame_support::traits::ConstU64<8>;
 	type MaxUnconfirmedMessagesAtInboundLane = frame_support::traits::ConstU64<8>;
+	type MaxRecentDeliveries = frame_support::traits::ConstU32<8>;
+	type MessageExpiryBlocks = ();
+	type OutboundMessagesRateLimit = ();
+	type RateLimitedSender = ();
+	type MessageSubmitter = ();
+	type IndexMessagesHistory = ();
 
 	type MaximalOutboundPayloadSize = frame_support::traits::ConstU32<1024>;
 	type OutboundPayload = ();
@@ -108,6 +115,7 @@ impl pallet_bridge_messages::Config for TestRuntime {
 
 	type InboundPayload = ();
 	type InboundMessageFee = Balance;
+	type MaximalInboundPayloadSize = frame_support::traits::ConstU32<1024>;
 	type InboundRelayer = AccountId;
 
 	type TargetHeaderChain = ForbidOutboundMessages;
@@ -117,6 +125,8 @@ impl pallet_bridge_messages::Config for TestRuntime {
 	type OnDeliveryConfirmed = ();
 
 	type SourceHeaderChain = ForbidInboundMessages;
+	type MaxMessagesProofSize = frame_support::traits::ConstU32<1024>;
+	type MaxMessagesDeliveryProofSize = frame_support::traits::ConstU32<1024>;
 	type MessageDispatch = ForbidInboundMessages;
 	type BridgedChainId = TestBridgedChainId;
 }
@@ -49,6 +49,14 @@ where
 		Ok(())
 	}
 
+	fn refund_delivery_and_dispatch_fee(
+		_submitter: &T::Origin,
+		_fee: &T::Reward,
+	) -> Result<(), Self::Error> {
+		// nothing has been paid in `pay_delivery_and_dispatch_fee`, so there's nothing to refund
+		Ok(())
+	}
+
 	fn pay_relayers_rewards(
 		lane_id: bp_messages::LaneId,
 		messages_relayers: VecDeque<bp_messages::UnrewardedRelayer<T::AccountId>>,
@@ -18,10 +18,11 @@
 // RuntimeApi generated functions
 #![allow(clippy::too_many_arguments)]
 
+use bp_header_chain::justification::max_expected_justification_size;
 use bp_messages::{
 	InboundMessageDetails, LaneId, MessageNonce, MessagePayload, OutboundMessageDetails,
 };
-use bp_runtime::{decl_bridge_runtime_apis, Chain};
+use bp_runtime::{decl_bridge_conversion_rate_params, decl_bridge_runtime_apis, Chain, ChainId};
 use frame_support::{
 	weights::{constants::WEIGHT_PER_SECOND, DispatchClass, IdentityFee, Weight},
 	Parameter, RuntimeDebug,
@@ -29,7 +30,7 @@ use frame_support::{
 use frame_system::limits;
 use sp_core::Hasher as HasherT;
 use sp_runtime::{
-	traits::{BlakeTwo256, IdentifyAccount, Verify},
+	traits::{BlakeTwo256, Hash as HashT, IdentifyAccount, Verify},
 	FixedU128, MultiSignature, MultiSigner, Perbill,
 };
 use sp_std::prelude::*;
@@ -39,10 +40,31 @@ use sp_std::prelude::*;
 /// Some reserve is reserved to account future chain growth.
 pub const EXTRA_STORAGE_PROOF_SIZE: u32 = 1024;
 
+/// Maximal number of GRANDPA authorities at Pass3d chain.
+///
+/// This is the same value as the `MaxAuthorities` of the Pass3d runtime.
+pub const MAX_AUTHORITIES_COUNT: u32 = 10;
+
+/// Maximal size of encoded GRANDPA justification of Pass3d headers.
+///
+/// Apart from precommits of all authorities, `EXTRA_STORAGE_PROOF_SIZE` bytes are reserved
+/// for headers in the votes ancestries.
+pub const MAX_JUSTIFICATION_SIZE: u32 =
+	max_expected_justification_size(MAX_AUTHORITIES_COUNT, EXTRA_STORAGE_PROOF_SIZE);
+
+/// Maximal size of encoded Pass3d header.
+///
+/// Apart from 4096 bytes reserved for regular header fields and digest items, it leaves room
+/// for the GRANDPA authority set change digest with `MAX_AUTHORITIES_COUNT` authorities (40
+/// bytes per authority).
+pub const MAX_HEADER_SIZE: u32 = 4096 + MAX_AUTHORITIES_COUNT * 40;
+
 /// Number of bytes, included in the signed Pass3d transaction apart from the encoded call itself.
 ///
-/// Can be computed by subtracting encoded call size from raw transaction size.
-pub const TX_EXTRA_BYTES: u32 = 104;
+/// Can be computed by subtracting encoded call size from raw transaction size. The value is
+/// computed for transaction, signed with mortal era, maximal nonce and zero tip, whose encoded
+/// size needs 4-byte length prefix.
+pub const TX_EXTRA_BYTES: u32 = 111;
 
 /// Maximal weight of single Pass3d block.
 ///
@@ -82,7 +104,7 @@ pub const ADDITIONAL_MESSAGE_BYTE_DELIVERY_WEIGHT: Weight = 25_000;
 /// This value is a result of `pallet_bridge_messages::Pallet::receive_messages_delivery_proof`
 /// weight formula computation for the case when single message is confirmed. The result then must
 /// be rounded up to account possible future runtime upgrades.
-pub const MAX_SINGLE_MESSAGE_DELIVERY_CONFIRMATION_TX_WEIGHT: Weight = 2_000_000_000;
+pub const MAX_SINGLE_MESSAGE_DELIVERY_CONFIRMATION_TX_WEIGHT: Weight = 2_500_000_000;
 
 /// Weight of pay-dispatch-fee operation for inbound messages at Pass3d chain.
 ///
@@ -206,7 +228,60 @@ pub const WITH_PASS3D_GRANDPA_PALLET_NAME: &str = "BridgePass3dGrandpa";
 /// Name of the With-Pass3d messages pallet instance that is deployed at bridged chains.
 pub const WITH_PASS3D_MESSAGES_PALLET_NAME: &str = "BridgePass3dMessages";
 
-/// Name of the Pass3d->Pass3d (actually KSM->DOT) conversion rate stored in the Pass3d runtime.
-pub const PASS3DT_TO_PASS3D_CONVERSION_RATE_PARAMETER_NAME: &str = "Pass3dToPass3dConversionRate";
+/// Lane that is used to send XCM messages from Pass3d to Pass3dt.
+pub const XCM_LANE: LaneId = LaneId([0, 0, 0, 0]);
+
+/// Returns all outbound lanes of the Pass3d -> Pass3dt bridge that are open at Pass3d.
+///
+/// Relays are serving these lanes by default.
+pub fn all_active_lanes() -> &'static [LaneId] {
+	&[XCM_LANE, LaneId([0, 0, 0, 1])]
+}
+
+/// SS58 address format of Pass3d accounts.
+pub const SS58_PREFIX: u16 = 48;
+
+/// Derive Pass3d account that represents the account `remote` of the bridged chain `chain_id`.
+///
+/// The derived account is the `BlakeTwo256` hash of the SCALE-encoded
+/// `(chain_id, SS58_PREFIX, remote)` tuple, so it may be computed outside of the runtime.
+pub fn derive_account_id_from_remote(chain_id: ChainId, remote: AccountId) -> AccountId {
+	AccountId::new(BlakeTwo256::hash_of(&(chain_id, SS58_PREFIX, remote)).to_fixed_bytes())
+}
+
+/// Format Pass3d account as SS58 address, using the Pass3d `SS58_PREFIX`.
+#[cfg(feature = "std")]
+pub fn format_account(account: &AccountId) -> String {
+	use sp_core::crypto::Ss58Codec;
+	account.to_ss58check_with_version(SS58_PREFIX.into())
+}
+
+decl_bridge_conversion_rate_params!(pass3dt, pass3d);
 
 decl_bridge_runtime_apis!(pass3d);
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use hex_literal::hex;
+	use sp_core::crypto::Ss58Codec;
+
+	#[test]
+	fn formatted_account_round_trips() {
+		let account = AccountId::new([1u8; 32]);
+		let (decoded, format) =
+			AccountId::from_ss58check_with_version(&format_account(&account)).unwrap();
+		assert_eq!(decoded, account);
+		assert_eq!(u16::from(format), SS58_PREFIX);
+	}
+
+	#[test]
+	fn derive_account_id_from_remote_works() {
+		assert_eq!(
+			derive_account_id_from_remote(bp_runtime::PASS3DT_CHAIN_ID, AccountId::new([1u8; 32])),
+			AccountId::new(hex!(
+				"016d18d69c3791461a51f3c1c554a871b103160eac609aee14ab3820256acf04"
+			)),
+		);
+	}
+}
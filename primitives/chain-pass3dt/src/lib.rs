@@ -20,10 +20,11 @@
 
 mod pass3dt_hash;
 
+use bp_header_chain::justification::max_expected_justification_size;
 use bp_messages::{
 	InboundMessageDetails, LaneId, MessageNonce, MessagePayload, OutboundMessageDetails,
 };
-use bp_runtime::{decl_bridge_runtime_apis, Chain};
+use bp_runtime::{decl_bridge_conversion_rate_params, decl_bridge_runtime_apis, Chain, ChainId};
 use frame_support::{
 	weights::{constants::WEIGHT_PER_SECOND, DispatchClass, IdentityFee, Weight},
 	Parameter, RuntimeDebug,
@@ -32,8 +33,8 @@ use frame_system::limits;
 use scale_info::TypeInfo;
 use sp_core::{storage::StateVersion, Hasher as HasherT};
 use sp_runtime::{
-	traits::{IdentifyAccount, Verify},
-	FixedU128, MultiSignature, MultiSigner, Perbill,
+	traits::{BlakeTwo256, Hash as HashT, IdentifyAccount, Verify},
+	FixedPointNumber, FixedU128, MultiSignature, MultiSigner, Perbill,
 };
 use sp_std::prelude::*;
 use sp_trie::{LayoutV0, LayoutV1, TrieConfiguration};
@@ -48,10 +49,31 @@ pub use pass3dt_hash::Pass3dtHash;
 /// Some reserve is reserved to account future chain growth.
 pub const EXTRA_STORAGE_PROOF_SIZE: u32 = 1024;
 
+/// Maximal number of GRANDPA authorities at Pass3dt chain.
+///
+/// This is the same value as the `MaxAuthorities` of the Pass3dt runtime.
+pub const MAX_AUTHORITIES_COUNT: u32 = 10;
+
+/// Maximal size of encoded GRANDPA justification of Pass3dt headers.
+///
+/// Apart from precommits of all authorities, `EXTRA_STORAGE_PROOF_SIZE` bytes are reserved
+/// for headers in the votes ancestries.
+pub const MAX_JUSTIFICATION_SIZE: u32 =
+	max_expected_justification_size(MAX_AUTHORITIES_COUNT, EXTRA_STORAGE_PROOF_SIZE);
+
+/// Maximal size of encoded Pass3dt header.
+///
+/// Apart from 4096 bytes reserved for regular header fields and digest items, it leaves room
+/// for the GRANDPA authority set change digest with `MAX_AUTHORITIES_COUNT` authorities (40
+/// bytes per authority).
+pub const MAX_HEADER_SIZE: u32 = 4096 + MAX_AUTHORITIES_COUNT * 40;
+
 /// Number of bytes, included in the signed Pass3dt transaction apart from the encoded call itself.
 ///
-/// Can be computed by subtracting encoded call size from raw transaction size.
-pub const TX_EXTRA_BYTES: u32 = 103;
+/// Can be computed by subtracting encoded call size from raw transaction size. The value is
+/// computed for transaction, signed with mortal era, maximal nonce and zero tip, whose encoded
+/// size needs 4-byte length prefix.
+pub const TX_EXTRA_BYTES: u32 = 111;
 
 /// Maximum weight of single Pass3dt block.
 ///
@@ -245,7 +267,95 @@ pub const WITH_PASS3DT_MESSAGES_PALLET_NAME: &str = "BridgePass3dtMessages";
 /// Name of the transaction payment pallet at the Pass3dt runtime.
 pub const TRANSACTION_PAYMENT_PALLET_NAME: &str = "TransactionPayment";
 
-/// Name of the PASS3D->Pass3dt (actually DOT->KSM) conversion rate stored in the Pass3dt runtime.
-pub const PASS3D_TO_PASS3DT_CONVERSION_RATE_PARAMETER_NAME: &str = "Pass3dToPass3dtConversionRate";
+/// Lane that is used to send XCM messages from Pass3dt to Pass3d.
+pub const XCM_LANE: LaneId = LaneId([0, 0, 0, 0]);
+
+/// Returns all outbound lanes of the Pass3dt -> Pass3d bridge that are open at Pass3dt.
+///
+/// Relays are serving these lanes by default.
+pub fn all_active_lanes() -> &'static [LaneId] {
+	&[XCM_LANE, LaneId([0, 0, 0, 1])]
+}
+
+/// SS58 address format of Pass3dt accounts.
+pub const SS58_PREFIX: u16 = 60;
+
+/// Derive Pass3dt account that represents the account `remote` of the bridged chain `chain_id`.
+///
+/// The derived account is the `BlakeTwo256` hash of the SCALE-encoded
+/// `(chain_id, SS58_PREFIX, remote)` tuple, so it may be computed outside of the runtime.
+pub fn derive_account_id_from_remote(chain_id: ChainId, remote: AccountId) -> AccountId {
+	AccountId::new(BlakeTwo256::hash_of(&(chain_id, SS58_PREFIX, remote)).to_fixed_bytes())
+}
+
+/// Format Pass3dt account as SS58 address, using the Pass3dt `SS58_PREFIX`.
+#[cfg(feature = "std")]
+pub fn format_account(account: &AccountId) -> String {
+	use sp_core::crypto::Ss58Codec;
+	account.to_ss58check_with_version(SS58_PREFIX.into())
+}
+
+/// Estimate fee (in tokens of the sending chain) of delivering message to the Pass3dt chain.
+///
+/// The `delivery_transaction_fee` is the fee (in Pass3dt tokens) of the message delivery
+/// transaction at Pass3dt and `confirmation_transaction_fee` is the fee (in sending chain tokens)
+/// of the delivery confirmation transaction at the sending chain. The delivery fee is converted
+/// using `conversion_rate` and then `relayer_fee_percent` of the sum is added as the relayer
+/// interest. This mirrors the `estimate_message_dispatch_and_delivery_fee` function of the
+/// `bridge-runtime-common` crate, so that the same value may be computed outside of the runtime.
+///
+/// Returns `None` if overflow has happened.
+pub fn estimate_fee_from_components(
+	delivery_transaction_fee: Balance,
+	confirmation_transaction_fee: Balance,
+	conversion_rate: FixedU128,
+	relayer_fee_percent: u32,
+) -> Option<Balance> {
+	conversion_rate
+		.saturating_mul_int(delivery_transaction_fee)
+		.checked_add(confirmation_transaction_fee)
+		.and_then(|fee| {
+			fee.checked_mul(relayer_fee_percent.into())
+				.and_then(|interest| interest.checked_div(100))
+				.and_then(|interest| fee.checked_add(interest))
+		})
+}
+
+decl_bridge_conversion_rate_params!(pass3d, pass3dt);
 
 decl_bridge_runtime_apis!(pass3dt);
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use hex_literal::hex;
+	use sp_core::crypto::Ss58Codec;
+
+	#[test]
+	fn formatted_account_round_trips() {
+		let account = AccountId::new([1u8; 32]);
+		let (decoded, format) =
+			AccountId::from_ss58check_with_version(&format_account(&account)).unwrap();
+		assert_eq!(decoded, account);
+		assert_eq!(u16::from(format), SS58_PREFIX);
+	}
+
+	#[test]
+	fn estimate_fee_from_components_works() {
+		let rate = FixedU128::saturating_from_rational(3, 2);
+		assert_eq!(estimate_fee_from_components(100, 50, rate, 10), Some(220));
+		assert_eq!(estimate_fee_from_components(Balance::MAX, 1, rate, 0), None);
+		let rate = FixedU128::saturating_from_integer(1);
+		assert_eq!(estimate_fee_from_components(Balance::MAX / 2, 0, rate, 10), None);
+	}
+
+	#[test]
+	fn derive_account_id_from_remote_works() {
+		assert_eq!(
+			derive_account_id_from_remote(bp_runtime::PASS3D_CHAIN_ID, AccountId::new([1u8; 32])),
+			AccountId::new(hex!(
+				"c16f08eadfbb18e95ef6ee17fdab6c8f0c8a3963a99f2e63b5b8f2f1e030f82d"
+			)),
+		);
+	}
+}
@@ -18,6 +18,7 @@
 // RuntimeApi generated functions
 #![allow(clippy::too_many_arguments)]
 
+use bp_header_chain::justification::max_expected_justification_size;
 use bp_messages::{
 	InboundMessageDetails, LaneId, MessageNonce, MessagePayload, OutboundMessageDetails,
 };
@@ -39,6 +40,31 @@ use sp_std::prelude::*;
 /// Some reserve is reserved to account future chain growth.
 pub const EXTRA_STORAGE_PROOF_SIZE: u32 = 1024;
 
+/// Maximal number of GRANDPA authorities at Rialto chain.
+///
+/// This is the same value as the `MaxAuthorities` of the Rialto runtime.
+pub const MAX_AUTHORITIES_COUNT: u32 = 10;
+
+/// Maximal size of encoded GRANDPA justification of Rialto headers.
+///
+/// Apart from precommits of all authorities, `EXTRA_STORAGE_PROOF_SIZE` bytes are reserved
+/// for headers in the votes ancestries.
+pub const MAX_JUSTIFICATION_SIZE: u32 =
+	max_expected_justification_size(MAX_AUTHORITIES_COUNT, EXTRA_STORAGE_PROOF_SIZE);
+
+/// Maximal size of encoded Rialto header.
+///
+/// Apart from 4096 bytes reserved for regular header fields and digest items, it leaves room
+/// for the GRANDPA authority set change digest with `MAX_AUTHORITIES_COUNT` authorities (40
+/// bytes per authority).
+pub const MAX_HEADER_SIZE: u32 = 4096 + MAX_AUTHORITIES_COUNT * 40;
+
+/// Maximal size of the parachain heads storage proof, built at Rialto chain.
+///
+/// The proof may include heads of many parachains, so the limit is generous. It exists to
+/// reject obviously oversized proofs before they are verified.
+pub const MAX_PARA_HEADS_PROOF_SIZE: u32 = 1024 * 1024;
+
 /// Number of bytes, included in the signed Rialto transaction apart from the encoded call itself.
 ///
 /// Can be computed by subtracting encoded call size from raw transaction size.
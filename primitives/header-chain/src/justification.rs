@@ -18,6 +18,10 @@
 //!
 //! Adapted copy of substrate/client/finality-grandpa/src/justification.rs. If origin
 //! will ever be moved to the sp_finality_grandpa, we should reuse that implementation.
+//!
+//! The module is `no_std` and has no dependencies on pallet code, so it may be used by any
+//! justification consumer (bridge pallets, relays or wasm light clients). Everyone should
+//! use the [`verify_justification`] function, so that the verification logic never diverges.
 
 use codec::{Decode, Encode};
 use finality_grandpa::voter_set::VoterSet;
@@ -52,6 +56,34 @@ impl<H: HeaderT> crate::FinalityProof<H::Number> for GrandpaJustification<H> {
 	}
 }
 
+/// Size of the encoded justification fields that do not depend on number of authorities and
+/// votes ancestries: the round number, the commit target and lengths of the precommits and
+/// votes ancestries vectors.
+///
+/// Assumes that the chain uses 32-byte hashes and `u32` block numbers.
+const JUSTIFICATION_FIXED_SIZE: u32 = 8 + 32 + 4 + 5 + 5;
+
+/// Size of the encoded signed precommit: the precommit target, the authority signature and the
+/// authority id.
+///
+/// Assumes that the chain uses 32-byte hashes and `u32` block numbers.
+pub const SIGNED_PRECOMMIT_SIZE: u32 = 32 + 4 + 64 + 32;
+
+/// Returns maximal expected size of the encoded GRANDPA justification, generated by the
+/// authorities set of at most `max_authorities_count` authorities.
+///
+/// The `max_votes_ancestries_size` is the number of bytes that are reserved for headers in
+/// the votes ancestries. Larger justifications may still be valid, but the bridge pallets
+/// reject them without verification.
+pub const fn max_expected_justification_size(
+	max_authorities_count: u32,
+	max_votes_ancestries_size: u32,
+) -> u32 {
+	JUSTIFICATION_FIXED_SIZE
+		.saturating_add(max_authorities_count.saturating_mul(SIGNED_PRECOMMIT_SIZE))
+		.saturating_add(max_votes_ancestries_size)
+}
+
 /// Justification verification error.
 #[derive(Eq, RuntimeDebug, PartialEq)]
 pub enum Error {
@@ -81,6 +113,25 @@ pub fn decode_justification_target<Header: HeaderT>(
 }
 
 /// Verify that justification, that is generated by given authority set, finalizes given header.
+///
+/// Arguments are:
+///
+/// - `finalized_target` is the `(hash, number)` identifier of the header that the
+///   `justification` is expected to finalize;
+///
+/// - `authorities_set_id` is the identifier of the GRANDPA authorities set that has
+///   generated the `justification`;
+///
+/// - `authorities_set` is the set of GRANDPA authorities (with their weights) that has
+///   generated the `justification`;
+///
+/// - `justification` is the decoded justification itself (see [`decode_justification_target`]
+///   if you only need to know the target of encoded justification).
+///
+/// The function checks that the justification targets expected header, that all votes are
+/// signed by known authorities, that every precommit target is a descendant of the
+/// justification target and that there are no unused headers in the votes ancestries. Votes
+/// from unknown authorities and repeated votes of the same authority are ignored.
 pub fn verify_justification<Header: HeaderT>(
 	finalized_target: (Header::Hash, Header::Number),
 	authorities_set_id: SetId,
@@ -25,9 +25,14 @@ use core::{clone::Clone, cmp::Eq, default::Default, fmt::Debug};
 use scale_info::TypeInfo;
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
+use sp_core::H256;
 use sp_finality_grandpa::{AuthorityList, ConsensusLog, SetId, GRANDPA_ENGINE_ID};
-use sp_runtime::{generic::OpaqueDigestItemId, traits::Header as HeaderT, RuntimeDebug};
-use sp_std::boxed::Box;
+use sp_runtime::{
+	generic::OpaqueDigestItemId,
+	traits::{BlakeTwo256, Hash as HashT, Header as HeaderT, Saturating},
+	RuntimeDebug,
+};
+use sp_std::{boxed::Box, vec::Vec};
 
 pub mod justification;
 pub mod storage_keys;
@@ -53,6 +58,53 @@ impl AuthoritySet {
 	pub fn new(authorities: AuthorityList, set_id: SetId) -> Self {
 		Self { authorities, set_id }
 	}
+
+	/// Returns hash of the encoded authorities list.
+	pub fn authority_list_hash(&self) -> H256 {
+		BlakeTwo256::hash_of(&self.authorities)
+	}
+}
+
+/// GRANDPA authority set change that has been scheduled, but not yet enacted.
+///
+/// The header that enacts the change is still finalized by the current authority set. All its
+/// descendants are finalized by the next authority set.
+#[derive(Encode, Decode, RuntimeDebug, PartialEq, Eq, Clone, TypeInfo)]
+pub struct ScheduledAuthoritySetChange<Number> {
+	/// Authority set that is enacted by the change.
+	pub next_authority_set: AuthoritySet,
+	/// Number of the header that enacts the change.
+	pub enacted_at: Number,
+}
+
+impl<Number: Copy + PartialOrd + Saturating> ScheduledAuthoritySetChange<Number> {
+	/// Read authority set change, scheduled by the given header.
+	///
+	/// The change is enacted by the header `delay` blocks after the given header. Returns `None`
+	/// if the header doesn't schedule a (standard) authority set change.
+	pub fn from_header<H: HeaderT<Number = Number>>(
+		header: &H,
+		current_set_id: SetId,
+	) -> Option<Self> {
+		find_grandpa_authorities_scheduled_change(header).map(|change| {
+			ScheduledAuthoritySetChange {
+				// TODO [#788]: Stop manually increasing the `set_id` here.
+				next_authority_set: AuthoritySet::new(change.next_authorities, current_set_id + 1),
+				enacted_at: header.number().saturating_add(change.delay),
+			}
+		})
+	}
+
+	/// Returns true if the header with given number is finalized by the next authority set.
+	pub fn is_finalized_by_next_set(&self, number: Number) -> bool {
+		number > self.enacted_at
+	}
+
+	/// Returns true if the change is enacted by the header with given number or by one of its
+	/// ancestors.
+	pub fn is_enacted_by(&self, number: Number) -> bool {
+		number >= self.enacted_at
+	}
 }
 
 /// Data required for initializing the bridge pallet.
@@ -69,6 +121,23 @@ pub struct InitializationData<H: HeaderT> {
 	pub set_id: SetId,
 	/// Pallet operating mode.
 	pub operating_mode: BasicOperatingMode,
+	/// Historical headers that are trusted by the pallet without finality proofs.
+	///
+	/// Messages, anchored at these headers, may be verified even if the bridge has been
+	/// initialized with a later header.
+	pub checkpoints: Vec<TrustedCheckpoint<H::Number, H::Hash>>,
+}
+
+/// Header of the bridged chain that is agreed off-chain and is trusted by the bridge pallet.
+#[derive(Default, Encode, Decode, RuntimeDebug, PartialEq, Eq, Clone, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct TrustedCheckpoint<Number, Hash> {
+	/// Number of the checkpoint header.
+	pub number: Number,
+	/// Hash of the checkpoint header.
+	pub hash: Hash,
+	/// State root of the checkpoint header.
+	pub state_root: Hash,
 }
 
 /// base trait for verifying transaction inclusion proofs.
@@ -133,3 +202,22 @@ pub fn find_grandpa_authorities_scheduled_change<H: HeaderT>(
 	// the right kind of consensus log.
 	header.digest().convert_first(|l| l.try_to(id).and_then(filter_log))
 }
+
+/// Find header digest that schedules forced change of GRANDPA authorities set.
+///
+/// Returns the number of the median last finalized block and the change itself.
+pub fn find_grandpa_authorities_forced_change<H: HeaderT>(
+	header: &H,
+) -> Option<(H::Number, sp_finality_grandpa::ScheduledChange<H::Number>)> {
+	let id = OpaqueDigestItemId::Consensus(&GRANDPA_ENGINE_ID);
+
+	let filter_log = |log: ConsensusLog<H::Number>| match log {
+		ConsensusLog::ForcedChange(median_last_finalized, change) =>
+			Some((median_last_finalized, change)),
+		_ => None,
+	};
+
+	// find the first consensus digest with the right ID which converts to
+	// the right kind of consensus log.
+	header.digest().convert_first(|l| l.try_to(id).and_then(filter_log))
+}
@@ -20,6 +20,16 @@
 pub const PALLET_OPERATING_MODE_VALUE_NAME: &str = "PalletOperatingMode";
 /// Name of the `BestFinalized` storage value.
 pub const BEST_FINALIZED_VALUE_NAME: &str = "BestFinalized";
+/// Name of the `CurrentAuthoritySet` storage value.
+pub const CURRENT_AUTHORITY_SET_VALUE_NAME: &str = "CurrentAuthoritySet";
+/// Name of the `PendingAuthoritySetChange` storage value.
+pub const PENDING_AUTHORITY_SET_CHANGE_VALUE_NAME: &str = "PendingAuthoritySetChange";
+/// Name of the `FrozenAt` storage value.
+pub const FROZEN_AT_VALUE_NAME: &str = "FrozenAt";
+/// Name of the `MinHeaderGap` storage value.
+pub const MIN_HEADER_GAP_VALUE_NAME: &str = "MinHeaderGap";
+/// Name of the `RemainingRequestsWeight` storage value.
+pub const REMAINING_REQUESTS_WEIGHT_VALUE_NAME: &str = "RemainingRequestsWeight";
 
 use sp_core::storage::StorageKey;
 
@@ -45,6 +55,64 @@ pub fn best_finalized_key(pallet_prefix: &str) -> StorageKey {
 	)
 }
 
+/// Storage key of the current GRANDPA authority set value in the runtime storage.
+pub fn current_authority_set_key(pallet_prefix: &str) -> StorageKey {
+	StorageKey(
+		bp_runtime::storage_value_final_key(
+			pallet_prefix.as_bytes(),
+			CURRENT_AUTHORITY_SET_VALUE_NAME.as_bytes(),
+		)
+		.to_vec(),
+	)
+}
+
+/// Storage key of the scheduled, but not yet enacted GRANDPA authority set change in the runtime
+/// storage.
+pub fn pending_authority_set_change_key(pallet_prefix: &str) -> StorageKey {
+	StorageKey(
+		bp_runtime::storage_value_final_key(
+			pallet_prefix.as_bytes(),
+			PENDING_AUTHORITY_SET_CHANGE_VALUE_NAME.as_bytes(),
+		)
+		.to_vec(),
+	)
+}
+
+/// Storage key of the number of the best finalized header at the moment when the pallet has been
+/// halted.
+pub fn frozen_at_key(pallet_prefix: &str) -> StorageKey {
+	StorageKey(
+		bp_runtime::storage_value_final_key(
+			pallet_prefix.as_bytes(),
+			FROZEN_AT_VALUE_NAME.as_bytes(),
+		)
+		.to_vec(),
+	)
+}
+
+/// Storage key of the minimal gap between the best finalized header and the imported regular
+/// header.
+pub fn min_header_gap_key(pallet_prefix: &str) -> StorageKey {
+	StorageKey(
+		bp_runtime::storage_value_final_key(
+			pallet_prefix.as_bytes(),
+			MIN_HEADER_GAP_VALUE_NAME.as_bytes(),
+		)
+		.to_vec(),
+	)
+}
+
+/// Storage key of the weight that may still be spent on header imports in the current block.
+pub fn remaining_requests_weight_key(pallet_prefix: &str) -> StorageKey {
+	StorageKey(
+		bp_runtime::storage_value_final_key(
+			pallet_prefix.as_bytes(),
+			REMAINING_REQUESTS_WEIGHT_VALUE_NAME.as_bytes(),
+		)
+		.to_vec(),
+	)
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -75,4 +143,69 @@ mod tests {
 			hex::encode(&storage_key),
 		);
 	}
+
+	#[test]
+	fn current_authority_set_key_computed_properly() {
+		// If this test fails, then something has been changed in module storage that is breaking
+		// compatibility with previous pallet.
+		let storage_key = current_authority_set_key("BridgeGrandpa").0;
+		assert_eq!(
+			storage_key,
+			hex!("0b06f475eddb98cf933a12262e0388de24a7b8b5717ea33346fa595a66ccbcb0").to_vec(),
+			"Unexpected storage key: {}",
+			hex::encode(&storage_key),
+		);
+	}
+
+	#[test]
+	fn pending_authority_set_change_key_computed_properly() {
+		// If this test fails, then something has been changed in module storage that is breaking
+		// compatibility with previous pallet.
+		let storage_key = pending_authority_set_change_key("BridgeGrandpa").0;
+		assert_eq!(
+			storage_key,
+			hex!("0b06f475eddb98cf933a12262e0388de93130e0be648382381d7220e6fb4a1ef").to_vec(),
+			"Unexpected storage key: {}",
+			hex::encode(&storage_key),
+		);
+	}
+
+	#[test]
+	fn frozen_at_key_computed_properly() {
+		// If this test fails, then something has been changed in module storage that is breaking
+		// compatibility with previous pallet.
+		let storage_key = frozen_at_key("BridgeGrandpa").0;
+		assert_eq!(
+			storage_key,
+			hex!("0b06f475eddb98cf933a12262e0388de6b04fcca06fe06e19435f7118a5e85b3").to_vec(),
+			"Unexpected storage key: {}",
+			hex::encode(&storage_key),
+		);
+	}
+
+	#[test]
+	fn min_header_gap_key_computed_properly() {
+		// If this test fails, then something has been changed in module storage that is breaking
+		// compatibility with previous pallet.
+		let storage_key = min_header_gap_key("BridgeGrandpa").0;
+		assert_eq!(
+			storage_key,
+			hex!("0b06f475eddb98cf933a12262e0388ded1489adf322fb90bf2d084b3ef4474b9").to_vec(),
+			"Unexpected storage key: {}",
+			hex::encode(&storage_key),
+		);
+	}
+
+	#[test]
+	fn remaining_requests_weight_key_computed_properly() {
+		// If this test fails, then something has been changed in module storage that is breaking
+		// compatibility with previous pallet.
+		let storage_key = remaining_requests_weight_key("BridgeGrandpa").0;
+		assert_eq!(
+			storage_key,
+			hex!("0b06f475eddb98cf933a12262e0388deb160b563704413a18ec6f57631a652d3").to_vec(),
+			"Unexpected storage key: {}",
+			hex::encode(&storage_key),
+		);
+	}
 }
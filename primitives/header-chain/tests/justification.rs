@@ -16,8 +16,11 @@
 
 //! Tests for Grandpa Justification code.
 
-use bp_header_chain::justification::{verify_justification, Error};
+use bp_header_chain::justification::{
+	max_expected_justification_size, verify_justification, Error,
+};
 use bp_test_utils::*;
+use codec::Encode;
 
 type TestHeader = sp_runtime::testing::Header;
 
@@ -190,3 +193,108 @@ fn justification_is_invalid_if_we_dont_meet_threshold() {
 		Err(Error::TooLowCumulativeWeight),
 	);
 }
+
+#[test]
+fn justification_with_shuffled_precommits_accepted() {
+	let justification = make_default_justification::<TestHeader>(&test_header(1));
+
+	// order of precommits must not affect verification result
+	for rotation in 0..justification.commit.precommits.len() {
+		let mut shuffled_justification = justification.clone();
+		shuffled_justification.commit.precommits.rotate_left(rotation);
+		shuffled_justification.commit.precommits[1..].reverse();
+
+		assert_eq!(
+			verify_justification::<TestHeader>(
+				header_id::<TestHeader>(1),
+				TEST_GRANDPA_SET_ID,
+				&voter_set(),
+				&shuffled_justification,
+			),
+			Ok(()),
+		);
+	}
+}
+
+#[test]
+fn justification_with_duplicate_votes_accepted_if_threshold_is_met() {
+	let mut justification = make_default_justification::<TestHeader>(&test_header(1));
+	let duplicate_precommit = justification.commit.precommits[0].clone();
+	justification.commit.precommits.push(duplicate_precommit);
+
+	assert_eq!(
+		verify_justification::<TestHeader>(
+			header_id::<TestHeader>(1),
+			TEST_GRANDPA_SET_ID,
+			&voter_set(),
+			&justification,
+		),
+		Ok(()),
+	);
+}
+
+#[test]
+fn justification_with_duplicate_votes_rejected_if_threshold_is_not_met() {
+	// three out of five authorities are not enough to reach the threshold
+	let params = JustificationGeneratorParams {
+		header: test_header(1),
+		round: TEST_GRANDPA_ROUND,
+		set_id: TEST_GRANDPA_SET_ID,
+		authorities: vec![(ALICE, 1), (BOB, 1), (CHARLIE, 1)],
+		ancestors: 2,
+		forks: 1,
+	};
+	let mut justification = make_justification_for_header::<TestHeader>(params);
+
+	// votes of the same authority must be counted only once
+	let duplicate_precommits = justification.commit.precommits.clone();
+	justification.commit.precommits.extend(duplicate_precommits);
+
+	assert_eq!(
+		verify_justification::<TestHeader>(
+			header_id::<TestHeader>(1),
+			TEST_GRANDPA_SET_ID,
+			&voter_set(),
+			&justification,
+		),
+		Err(Error::TooLowCumulativeWeight),
+	);
+}
+
+#[test]
+fn justification_with_wrong_set_id_rejected() {
+	let justification = make_default_justification::<TestHeader>(&test_header(1));
+
+	for set_id in [TEST_GRANDPA_SET_ID - 1, TEST_GRANDPA_SET_ID + 1] {
+		assert_eq!(
+			verify_justification::<TestHeader>(
+				header_id::<TestHeader>(1),
+				set_id,
+				&voter_set(),
+				&justification,
+			),
+			Err(Error::InvalidAuthoritySignature),
+		);
+	}
+}
+
+#[test]
+fn justification_size_does_not_exceed_max_expected_size() {
+	type Header = sp_runtime::generic::Header<u32, sp_runtime::traits::BlakeTwo256>;
+
+	let authorities_count = 16;
+	let justification = make_justification_for_header::<Header>(JustificationGeneratorParams {
+		header: test_header(1),
+		round: TEST_GRANDPA_ROUND,
+		set_id: TEST_GRANDPA_SET_ID,
+		authorities: accounts(authorities_count).iter().map(|k| (*k, 1)).collect(),
+		ancestors: 5,
+		forks: 1,
+	});
+
+	let votes_ancestries_size = justification.votes_ancestries.encode().len() as u32;
+	assert!(
+		justification.encode().len() as u32 <=
+			max_expected_justification_size(authorities_count as u32, votes_ancestries_size),
+	);
+}
@@ -21,11 +21,16 @@
 #![allow(clippy::too_many_arguments)]
 
 use bitvec::prelude::*;
-use bp_runtime::{messages::DispatchFeePayment, BasicOperatingMode, OperatingMode};
-use codec::{Decode, Encode, MaxEncodedLen};
+use bp_runtime::{
+	messages::DispatchFeePayment, BasicOperatingMode, InvalidTransition, OperatingMode,
+};
+use codec::{Compact, CompactLen, Decode, Encode, MaxEncodedLen};
 use frame_support::RuntimeDebug;
 use scale_info::TypeInfo;
-use sp_std::{collections::vec_deque::VecDeque, prelude::*};
+use sp_std::{
+	collections::{btree_map::BTreeMap, vec_deque::VecDeque},
+	prelude::*,
+};
 
 pub mod source_chain;
 pub mod storage_keys;
@@ -48,6 +53,14 @@ pub enum MessagesOperatingMode {
 	/// queued messages to the bridged chain. Once upgrade is completed, the mode may be switched
 	/// back to `Normal`.
 	RejectingOutboundMessages,
+	/// The pallet is not accepting inbound messages. Outbound messages and receiving proofs
+	/// are still accepted.
+	///
+	/// This mode may be used e.g. when this chain expects upgrade. Then to avoid dispatching
+	/// messages in the middle of the upgrade, the pallet owner may stop accepting delivery
+	/// transactions, while users are still able to send new messages. Once upgrade is completed,
+	/// the mode may be switched back to `Normal`.
+	RejectingInboundMessages,
 }
 
 impl Default for MessagesOperatingMode {
@@ -63,6 +76,17 @@ impl OperatingMode for MessagesOperatingMode {
 			_ => false,
 		}
 	}
+
+	/// The pallet may always be halted or switched to the `Normal` mode. Partially restricted
+	/// modes may only be entered from the `Normal` mode, so that resuming a halted pallet, or
+	/// switching between restrictions, never enables some operations implicitly.
+	fn ensure_can_transition(from: Self, to: Self) -> Result<(), InvalidTransition> {
+		match (from, to) {
+			(from, to) if from == to => Ok(()),
+			(Self::Basic(BasicOperatingMode::Normal), _) | (_, Self::Basic(_)) => Ok(()),
+			_ => Err(InvalidTransition),
+		}
+	}
 }
 
 /// Messages pallet parameter.
@@ -76,7 +100,89 @@ impl Parameter for () {
 }
 
 /// Lane identifier.
-pub type LaneId = [u8; 4];
+///
+/// Lane identifier is encoded exactly as the raw `[u8; 4]` array. It is displayed and parsed as
+/// a hex string (e.g. `0x00000001`). Short `0x`-prefixed hex strings are also accepted when
+/// parsing: they're padded with zeroes from the left, so `0x1` means the same as `0x00000001`.
+#[derive(
+	Clone,
+	Copy,
+	Decode,
+	Default,
+	Encode,
+	Eq,
+	Hash,
+	MaxEncodedLen,
+	Ord,
+	PartialEq,
+	PartialOrd,
+	TypeInfo,
+)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct LaneId(pub [u8; 4]);
+
+impl AsRef<[u8]> for LaneId {
+	fn as_ref(&self) -> &[u8] {
+		&self.0[..]
+	}
+}
+
+impl From<[u8; 4]> for LaneId {
+	fn from(lane_id: [u8; 4]) -> Self {
+		LaneId(lane_id)
+	}
+}
+
+impl core::fmt::Debug for LaneId {
+	fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+		core::fmt::Display::fmt(self, fmt)
+	}
+}
+
+impl core::fmt::Display for LaneId {
+	fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+		write!(fmt, "0x")?;
+		self.0.iter().try_for_each(|byte| write!(fmt, "{:02x}", byte))
+	}
+}
+
+/// Error that may happen when lane identifier is parsed from string.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum ParseLaneIdError {
+	/// The string is either empty, or too long.
+	InvalidLength,
+	/// The string contains non-hex character.
+	InvalidCharacter,
+}
+
+impl core::fmt::Display for ParseLaneIdError {
+	fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+		match *self {
+			ParseLaneIdError::InvalidLength =>
+				write!(fmt, "lane id must be 8 hex digits or 0x-prefixed 1 to 8 hex digits"),
+			ParseLaneIdError::InvalidCharacter => write!(fmt, "lane id contains non-hex character"),
+		}
+	}
+}
+
+impl core::str::FromStr for LaneId {
+	type Err = ParseLaneIdError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		// short form is only allowed with the `0x` prefix to avoid ambiguity
+		let digits = match s.strip_prefix("0x") {
+			Some(digits) if !digits.is_empty() && digits.len() <= 8 => digits,
+			None if s.len() == 8 => s,
+			_ => return Err(ParseLaneIdError::InvalidLength),
+		};
+
+		let value = u32::from_str_radix(digits, 16)
+			.ok()
+			.filter(|_| digits.bytes().all(|c| c.is_ascii_hexdigit()))
+			.ok_or(ParseLaneIdError::InvalidCharacter)?;
+		Ok(LaneId(value.to_be_bytes()))
+	}
+}
 
 /// Message nonce. Valid messages will never have 0 nonce.
 pub type MessageNonce = u64;
@@ -87,6 +193,24 @@ pub type BridgeMessageId = (LaneId, MessageNonce);
 /// Opaque message payload. We only decode this payload when it is dispatched.
 pub type MessagePayload = Vec<u8>;
 
+/// Kind of the message event, recorded in the messages history offchain index.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub enum MessageEventKind {
+	/// Outbound message has been accepted by this chain.
+	Accepted,
+	/// Inbound message has been delivered to this chain.
+	Delivered,
+	/// Delivery of outbound message has been confirmed by the bridged chain.
+	Confirmed,
+}
+
+impl MessageEventKind {
+	/// All message event kinds.
+	pub const ALL: [MessageEventKind; 3] =
+		[MessageEventKind::Accepted, MessageEventKind::Delivered, MessageEventKind::Confirmed];
+}
+
 /// Message key (unique message identifier) as it is stored in the storage.
 #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
 pub struct MessageKey {
@@ -154,8 +278,8 @@ impl<RelayerId> Default for InboundLaneData<RelayerId> {
 }
 
 impl<RelayerId> InboundLaneData<RelayerId> {
-	/// Returns approximate size of the struct, given a number of entries in the `relayers` set and
-	/// size of each entry.
+	/// Returns maximal size of the encoded struct, given a maximal number of entries in the
+	/// `relayers` set and maximal number of messages in all entries.
 	///
 	/// Returns `None` if size overflows `usize` limits.
 	pub fn encoded_size_hint(relayers_entries: usize, messages_count: usize) -> Option<usize>
@@ -164,18 +288,27 @@ impl<RelayerId> InboundLaneData<RelayerId> {
 	{
 		let message_nonce_size = MessageNonce::max_encoded_len();
 		let relayer_id_encoded_size = RelayerId::max_encoded_len();
-		let relayers_entry_size = relayer_id_encoded_size.checked_add(2 * message_nonce_size)?;
-		let relayers_size = relayers_entries.checked_mul(relayers_entry_size)?;
+		// every entry has its own dispatch results, prefixed with the compact-encoded length
+		let dispatch_results_length_size = compact_len(messages_count)?;
+		let relayers_entry_size = relayer_id_encoded_size
+			.checked_add(2 * message_nonce_size)?
+			.checked_add(dispatch_results_length_size)?;
+		let relayers_size = relayers_entries
+			.checked_mul(relayers_entry_size)?
+			.checked_add(compact_len(relayers_entries)?)?;
+		// every dispatch result occupies a single bit, but the last byte of every entry may be
+		// partially filled
 		let dispatch_results_per_byte = 8;
-		let dispatch_result_size =
-			sp_std::cmp::max(relayers_entries, messages_count / dispatch_results_per_byte);
+		let dispatch_results_size = messages_count
+			.checked_add(relayers_entries.checked_mul(dispatch_results_per_byte - 1)?)? /
+			dispatch_results_per_byte;
 		relayers_size
 			.checked_add(message_nonce_size)
-			.and_then(|result| result.checked_add(dispatch_result_size))
+			.and_then(|result| result.checked_add(dispatch_results_size))
 	}
 
-	/// Returns the approximate size of the struct as u32, given a number of entries in the
-	/// `relayers` set and the size of each entry.
+	/// Returns maximal size of the encoded struct as u32, given a maximal number of entries in
+	/// the `relayers` set and maximal number of messages in all entries.
 	///
 	/// Returns `u32::MAX` if size overflows `u32` limits.
 	pub fn encoded_size_hint_u32(relayers_entries: usize, messages_count: usize) -> u32
@@ -194,11 +327,44 @@ impl<RelayerId> InboundLaneData<RelayerId> {
 			.map(|entry| entry.messages.end)
 			.unwrap_or(self.last_confirmed_nonce)
 	}
+
+	/// Returns number of messages that have been delivered by given relayer and are not yet
+	/// confirmed (rewarded). All entries of the relayer are taken into account.
+	pub fn unrewarded_messages_of(&self, relayer: &RelayerId) -> MessageNonce
+	where
+		RelayerId: PartialEq,
+	{
+		self.relayers
+			.iter()
+			.filter(|entry| entry.relayer == *relayer)
+			.fold(0, |total, entry| total.saturating_add(entry.messages.total_messages()))
+	}
+
+	/// Returns number of unrewarded messages, delivered by every relayer that is mentioned in the
+	/// `relayers` set. Relayers are ordered by their identifiers.
+	pub fn relayers_summary(&self) -> Vec<(RelayerId, MessageNonce)>
+	where
+		RelayerId: Ord + Clone,
+	{
+		let mut summary = BTreeMap::<RelayerId, MessageNonce>::new();
+		for entry in &self.relayers {
+			let total = summary.entry(entry.relayer.clone()).or_default();
+			*total = total.saturating_add(entry.messages.total_messages());
+		}
+		summary.into_iter().collect()
+	}
+}
+
+/// Returns size of the compact-encoded length of the collection with given number of items.
+///
+/// Returns `None` if the number of items doesn't fit into `u32`.
+fn compact_len(items: usize) -> Option<usize> {
+	u32::try_from(items).ok().map(|items| Compact::<u32>::compact_len(&items))
 }
 
 /// Outbound message details, returned by runtime APIs.
 #[derive(Clone, Encode, Decode, RuntimeDebug, PartialEq, Eq)]
-pub struct OutboundMessageDetails<OutboundMessageFee> {
+pub struct OutboundMessageDetails<OutboundMessageFee, Submitter = ()> {
 	/// Nonce assigned to the message.
 	pub nonce: MessageNonce,
 	/// Message dispatch weight.
@@ -212,6 +378,8 @@ pub struct OutboundMessageDetails<OutboundMessageFee> {
 	pub delivery_and_dispatch_fee: OutboundMessageFee,
 	/// Where the fee for dispatching message is paid?
 	pub dispatch_fee_payment: DispatchFeePayment,
+	/// Submitter of the message, if it is recorded by the source chain.
+	pub submitter: Option<Submitter>,
 }
 
 /// Inbound message details, returned by runtime APIs.
@@ -224,6 +392,23 @@ pub struct InboundMessageDetails {
 	/// has failed to decode the message, it will be zero - that's because `undecodable`
 	/// message cannot be dispatched.
 	pub dispatch_weight: Weight,
+	/// Whether the runtime is able to decode the message payload.
+	///
+	/// Undecodable messages are still delivered (so that the lane is not blocked), but they are
+	/// never dispatched. This allows to distinguish between messages that have been dispatched
+	/// with error and messages that have never been dispatched.
+	pub is_payload_decodable: bool,
+}
+
+/// Reason why inbound message has been received, but not (successfully) dispatched.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub enum MessageRejectionReason {
+	/// The runtime has failed to decode message payload, so it has not been dispatched.
+	UndecodablePayload,
+	/// Message payload exceeds maximal inbound payload size, so it has not been dispatched.
+	OversizedPayload,
+	/// Message has been dispatched, but dispatch has failed.
+	DispatchError,
 }
 
 /// Bit vector of message dispatch results.
@@ -272,12 +457,43 @@ impl DeliveredMessages {
 		}
 	}
 
+	/// Append delivered messages that immediately follow messages of this range.
+	///
+	/// Returns `false` and leaves `self` unchanged if `other` range doesn't start right after
+	/// the end of this range.
+	pub fn try_append(&mut self, other: &DeliveredMessages) -> bool {
+		if self.end.checked_add(1) != Some(other.begin) || other.end < other.begin {
+			return false
+		}
+
+		self.end = other.end;
+		self.dispatch_results.extend_from_bitslice(&other.dispatch_results);
+		true
+	}
+
 	/// Note new dispatched message.
+	#[deprecated(note = "may overflow; use `try_note_dispatched_message` instead")]
 	pub fn note_dispatched_message(&mut self, dispatch_result: bool) {
 		self.end += 1;
 		self.dispatch_results.push(dispatch_result);
 	}
 
+	/// Note new dispatched message.
+	///
+	/// Returns `false` and leaves `self` unchanged if the nonce of the new message would
+	/// overflow `MessageNonce`.
+	#[must_use]
+	pub fn try_note_dispatched_message(&mut self, dispatch_result: bool) -> bool {
+		match self.end.checked_add(1) {
+			Some(new_end) => {
+				self.end = new_end;
+				self.dispatch_results.push(dispatch_result);
+				true
+			},
+			None => false,
+		}
+	}
+
 	/// Returns true if delivered messages contain message with given nonce.
 	pub fn contains_message(&self, nonce: MessageNonce) -> bool {
 		(self.begin..=self.end).contains(&nonce)
@@ -291,11 +507,23 @@ impl DeliveredMessages {
 	///
 	/// Panics if message nonce is not in the `begin..=end` range. Typically you'll first
 	/// check if message is within the range by calling `contains_message`.
+	#[deprecated(note = "panics on invalid nonce; use `get_dispatch_result` instead")]
 	pub fn message_dispatch_result(&self, nonce: MessageNonce) -> bool {
 		const INVALID_NONCE: &str = "Invalid nonce used to index dispatch_results";
 
-		let index = nonce.checked_sub(self.begin).expect(INVALID_NONCE) as usize;
-		*self.dispatch_results.get(index).expect(INVALID_NONCE)
+		self.get_dispatch_result(nonce).expect(INVALID_NONCE)
+	}
+
+	/// Get dispatch result flag by message nonce.
+	///
+	/// Returns `None` if message nonce is not in the `begin..=end` range.
+	pub fn get_dispatch_result(&self, nonce: MessageNonce) -> Option<bool> {
+		if !self.contains_message(nonce) {
+			return None
+		}
+
+		let index = usize::try_from(nonce - self.begin).ok()?;
+		self.dispatch_results.get(index).map(|dispatch_result| *dispatch_result)
 	}
 }
 
@@ -316,6 +544,64 @@ pub struct UnrewardedRelayersState {
 	pub last_delivered_nonce: MessageNonce,
 }
 
+impl UnrewardedRelayersState {
+	/// Returns true if this state corresponds to the given inbound lane data.
+	///
+	/// We only care about fields that are affecting the delivery confirmation transaction
+	/// weight (number of entries and messages) and the `last_delivered_nonce`, which may
+	/// be used by signed extensions. The `messages_in_oldest_entry` is not checked.
+	pub fn is_valid_for<RelayerId>(&self, lane_data: &InboundLaneData<RelayerId>) -> bool {
+		let actual = UnrewardedRelayersState::from(lane_data);
+		self.unrewarded_relayer_entries == actual.unrewarded_relayer_entries &&
+			self.total_messages == actual.total_messages &&
+			self.last_delivered_nonce == actual.last_delivered_nonce
+	}
+}
+
+impl<RelayerId> From<&InboundLaneData<RelayerId>> for UnrewardedRelayersState {
+	fn from(lane_data: &InboundLaneData<RelayerId>) -> Self {
+		UnrewardedRelayersState {
+			unrewarded_relayer_entries: lane_data.relayers.len() as _,
+			messages_in_oldest_entry: lane_data
+				.relayers
+				.front()
+				.map(|entry| {
+					entry
+						.messages
+						.end
+						.checked_sub(entry.messages.begin)
+						.map(|difference| difference.saturating_add(1))
+						.unwrap_or(0)
+				})
+				.unwrap_or(0),
+			total_messages: exact_total_unrewarded_messages(&lane_data.relayers)
+				.unwrap_or(MessageNonce::MAX),
+			last_delivered_nonce: lane_data.last_delivered_nonce(),
+		}
+	}
+}
+
+/// Inbound lane state, returned by runtime APIs.
+#[derive(Clone, Default, Encode, Decode, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct InboundLaneState {
+	/// Nonce of the last message that has been confirmed to the bridged chain.
+	///
+	/// This corresponds to the `InboundLaneData::last_confirmed_nonce` field.
+	pub last_confirmed_nonce: MessageNonce,
+	/// State of unrewarded relayers. It also contains the nonce of the latest message that has
+	/// been delivered to this chain.
+	pub unrewarded_relayers: UnrewardedRelayersState,
+}
+
+impl<RelayerId> From<&InboundLaneData<RelayerId>> for InboundLaneState {
+	fn from(lane_data: &InboundLaneData<RelayerId>) -> Self {
+		InboundLaneState {
+			last_confirmed_nonce: lane_data.last_confirmed_nonce,
+			unrewarded_relayers: lane_data.into(),
+		}
+	}
+}
+
 /// Outbound lane data.
 #[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
 pub struct OutboundLaneData {
@@ -340,7 +626,11 @@ impl Default for OutboundLaneData {
 	}
 }
 
-/// Returns total number of messages in the `InboundLaneData::relayers` vector.
+/// Returns upper bound of total number of messages in the `InboundLaneData::relayers` vector.
+///
+/// The bound is computed using only the first and the last entries of the vector, so it
+/// overestimates the number of messages if there are gaps between entries. Use it when upper
+/// bound is enough (e.g. for weight pre-checks) and `exact_total_unrewarded_messages` otherwise.
 ///
 /// Returns `None` if there are more messages that `MessageNonce` may fit (i.e. `MessageNonce + 1`).
 pub fn total_unrewarded_messages<RelayerId>(
@@ -358,10 +648,124 @@ pub fn total_unrewarded_messages<RelayerId>(
 	}
 }
 
+/// Returns total number of messages in the `InboundLaneData::relayers` vector.
+///
+/// Unlike `total_unrewarded_messages`, this function visits every entry of the vector and
+/// doesn't count gaps between entries.
+///
+/// Returns `None` if there are more messages that `MessageNonce` may fit.
+pub fn exact_total_unrewarded_messages<RelayerId>(
+	relayers: &VecDeque<UnrewardedRelayer<RelayerId>>,
+) -> Option<MessageNonce> {
+	relayers.iter().try_fold(0, |total: MessageNonce, entry| {
+		total.checked_add(entry.messages.total_messages())
+	})
+}
+
+/// Error that is returned by the message verifiers (`TargetHeaderChain`, `LaneMessageVerifier`
+/// and `SourceHeaderChain`) when message or proof is rejected.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum VerificationError {
+	/// Provided fee is below the minimal threshold, required by the lane.
+	TooLowFee,
+	/// The outbound lane is closed for the message sender.
+	ClosedLane,
+	/// There are too many pending (undelivered) messages at the outbound lane.
+	TooManyPendingMessages,
+	/// The message is too large to be sent over the lane.
+	MessageTooLarge,
+	/// The message has declared incorrect dispatch weight.
+	InvalidDispatchWeight,
+	/// Unable to match the source origin to the expected target origin.
+	InvalidSourceOrigin,
+	/// The messages proof is empty.
+	EmptyMessageProof,
+	/// Declared messages count doesn't match actual number of messages in the proof.
+	MessagesCountMismatch,
+	/// Message is missing from the proof.
+	MissingRequiredMessage,
+	/// Failed to decode message from the proof.
+	FailedToDecodeMessage,
+	/// Failed to decode outbound lane data from the proof.
+	FailedToDecodeOutboundLaneState,
+	/// Inbound lane data is missing from the proof.
+	MissingInboundLaneState,
+	/// Failed to decode inbound lane data from the proof.
+	FailedToDecodeInboundLaneState,
+	/// Some other error (e.g. error of the underlying finality pallet).
+	Other(&'static str),
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
 
+	#[test]
+	fn messages_operating_mode_transitions() {
+		const NORMAL: MessagesOperatingMode =
+			MessagesOperatingMode::Basic(BasicOperatingMode::Normal);
+		const HALTED: MessagesOperatingMode =
+			MessagesOperatingMode::Basic(BasicOperatingMode::Halted);
+		const REJECTING_OUTBOUND: MessagesOperatingMode =
+			MessagesOperatingMode::RejectingOutboundMessages;
+		const REJECTING_INBOUND: MessagesOperatingMode =
+			MessagesOperatingMode::RejectingInboundMessages;
+
+		let allowed = |from, to| MessagesOperatingMode::ensure_can_transition(from, to).is_ok();
+		for mode in [NORMAL, HALTED, REJECTING_OUTBOUND, REJECTING_INBOUND] {
+			// every mode may be re-entered, and the pallet may always be halted or resumed
+			assert!(allowed(mode, mode));
+			assert!(allowed(mode, NORMAL));
+			assert!(allowed(mode, HALTED));
+		}
+		assert!(allowed(NORMAL, REJECTING_OUTBOUND));
+		assert!(allowed(NORMAL, REJECTING_INBOUND));
+		assert!(!allowed(HALTED, REJECTING_OUTBOUND));
+		assert!(!allowed(HALTED, REJECTING_INBOUND));
+		assert!(!allowed(REJECTING_OUTBOUND, REJECTING_INBOUND));
+		assert!(!allowed(REJECTING_INBOUND, REJECTING_OUTBOUND));
+	}
+
+	#[test]
+	fn lane_id_is_encoded_as_raw_array() {
+		let raw_lane_id: [u8; 4] = [0x73, 0x77, 0x61, 0x70];
+		let lane_id = LaneId(raw_lane_id);
+
+		assert_eq!(lane_id.encode(), raw_lane_id.encode());
+		assert_eq!(LaneId::decode(&mut &raw_lane_id.encode()[..]), Ok(lane_id));
+		assert_eq!(<[u8; 4]>::decode(&mut &lane_id.encode()[..]), Ok(raw_lane_id));
+		assert_eq!(LaneId::max_encoded_len(), <[u8; 4]>::max_encoded_len());
+		assert_eq!(MessageKey { lane_id, nonce: 1 }.encode(), (raw_lane_id, 1_u64).encode());
+	}
+
+	#[test]
+	fn lane_id_is_displayed_as_hex() {
+		assert_eq!(LaneId([0, 0, 0, 1]).to_string(), "0x00000001");
+		assert_eq!(format!("{:?}", LaneId([0x73, 0x77, 0x61, 0x70])), "0x73776170");
+	}
+
+	#[test]
+	fn lane_id_is_parsed() {
+		assert_eq!("00000001".parse(), Ok(LaneId([0, 0, 0, 1])));
+		assert_eq!("0x00000001".parse(), Ok(LaneId([0, 0, 0, 1])));
+		assert_eq!("0x73776170".parse(), Ok(LaneId([0x73, 0x77, 0x61, 0x70])));
+		assert_eq!("0x1".parse(), Ok(LaneId([0, 0, 0, 1])));
+		assert_eq!("0x0".parse(), Ok(LaneId([0, 0, 0, 0])));
+		assert_eq!("0x102".parse(), Ok(LaneId([0, 0, 1, 2])));
+
+		assert_eq!("0001".parse::<LaneId>(), Err(ParseLaneIdError::InvalidLength));
+		assert_eq!("0x".parse::<LaneId>(), Err(ParseLaneIdError::InvalidLength));
+		assert_eq!("0x000000001".parse::<LaneId>(), Err(ParseLaneIdError::InvalidLength));
+		assert_eq!("0x+1".parse::<LaneId>(), Err(ParseLaneIdError::InvalidCharacter));
+		assert_eq!("0000000x".parse::<LaneId>(), Err(ParseLaneIdError::InvalidCharacter));
+	}
+
+	#[test]
+	fn lane_id_display_and_parse_roundtrip() {
+		let lane_id = LaneId([0xde, 0xad, 0xbe, 0xef]);
+		assert_eq!(lane_id.to_string().parse(), Ok(lane_id));
+	}
+
 	#[test]
 	fn total_unrewarded_messages_does_not_overflow() {
 		assert_eq!(
@@ -380,6 +784,281 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn exact_total_unrewarded_messages_does_not_overflow() {
+		assert_eq!(
+			exact_total_unrewarded_messages(
+				&vec![
+					UnrewardedRelayer {
+						relayer: 1,
+						messages: DeliveredMessages {
+							begin: 1,
+							end: MessageNonce::MAX,
+							dispatch_results: BitVec::new(),
+						},
+					},
+					UnrewardedRelayer {
+						relayer: 2,
+						messages: DeliveredMessages::new(MessageNonce::MAX, true)
+					},
+				]
+				.into_iter()
+				.collect()
+			),
+			None,
+		);
+	}
+
+	#[test]
+	fn exact_total_unrewarded_messages_ignores_gaps() {
+		let relayers = vec![
+			unrewarded_relayer(1, 1, 2),
+			unrewarded_relayer(2, 5, 6),
+			unrewarded_relayer(3, 10, 10),
+		]
+		.into_iter()
+		.collect();
+		assert_eq!(total_unrewarded_messages(&relayers), Some(10));
+		assert_eq!(exact_total_unrewarded_messages(&relayers), Some(5));
+	}
+
+	#[test]
+	fn total_unrewarded_messages_are_equal_if_there_are_no_gaps() {
+		let relayers = vec![
+			unrewarded_relayer(1, 1, 2),
+			unrewarded_relayer(2, 3, 6),
+			unrewarded_relayer(3, 7, 10),
+		]
+		.into_iter()
+		.collect();
+		assert_eq!(total_unrewarded_messages(&relayers), Some(10));
+		assert_eq!(exact_total_unrewarded_messages(&relayers), Some(10));
+		assert_eq!(exact_total_unrewarded_messages::<u8>(&VecDeque::new()), Some(0));
+	}
+
+	#[test]
+	fn unrewarded_relayers_state_is_computed_for_gapped_relayers_set() {
+		let lane_data = InboundLaneData {
+			relayers: vec![unrewarded_relayer(1, 1, 2), unrewarded_relayer(2, 5, 6)]
+				.into_iter()
+				.collect(),
+			last_confirmed_nonce: 0,
+		};
+		let state = UnrewardedRelayersState::from(&lane_data);
+		assert_eq!(
+			state,
+			UnrewardedRelayersState {
+				unrewarded_relayer_entries: 2,
+				messages_in_oldest_entry: 2,
+				total_messages: 4,
+				last_delivered_nonce: 6,
+			},
+		);
+		assert!(state.is_valid_for(&lane_data));
+	}
+
+	#[test]
+	fn inbound_lane_state_is_computed_from_lane_data() {
+		let lane_data = InboundLaneData {
+			relayers: vec![unrewarded_relayer(1, 3, 4)].into_iter().collect(),
+			last_confirmed_nonce: 2,
+		};
+		assert_eq!(
+			InboundLaneState::from(&lane_data),
+			InboundLaneState {
+				last_confirmed_nonce: 2,
+				unrewarded_relayers: UnrewardedRelayersState {
+					unrewarded_relayer_entries: 1,
+					messages_in_oldest_entry: 2,
+					total_messages: 2,
+					last_delivered_nonce: 4,
+				},
+			},
+		);
+	}
+
+	fn unrewarded_relayer(
+		relayer: u8,
+		begin: MessageNonce,
+		end: MessageNonce,
+	) -> UnrewardedRelayer<u8> {
+		UnrewardedRelayer {
+			relayer,
+			messages: DeliveredMessages { begin, end, dispatch_results: BitVec::new() },
+		}
+	}
+
+	#[test]
+	fn unrewarded_relayers_state_is_computed_for_empty_relayers_set() {
+		let lane_data =
+			InboundLaneData::<u8> { relayers: VecDeque::new(), last_confirmed_nonce: 42 };
+		let state = UnrewardedRelayersState::from(&lane_data);
+		assert_eq!(
+			state,
+			UnrewardedRelayersState {
+				unrewarded_relayer_entries: 0,
+				messages_in_oldest_entry: 0,
+				total_messages: 0,
+				last_delivered_nonce: 42,
+			},
+		);
+		assert!(state.is_valid_for(&lane_data));
+	}
+
+	#[test]
+	fn unrewarded_relayers_state_is_computed_for_single_entry() {
+		for (begin, end) in [(1, 1), (1, 10), (MessageNonce::MAX, MessageNonce::MAX)] {
+			let lane_data = InboundLaneData {
+				relayers: vec![unrewarded_relayer(1, begin, end)].into_iter().collect(),
+				last_confirmed_nonce: begin - 1,
+			};
+			let state = UnrewardedRelayersState::from(&lane_data);
+			assert_eq!(
+				state,
+				UnrewardedRelayersState {
+					unrewarded_relayer_entries: 1,
+					messages_in_oldest_entry: end - begin + 1,
+					total_messages: end - begin + 1,
+					last_delivered_nonce: end,
+				},
+			);
+			assert!(state.is_valid_for(&lane_data));
+		}
+	}
+
+	#[test]
+	fn unrewarded_relayers_state_is_computed_for_multiple_entries() {
+		let lane_data = InboundLaneData {
+			relayers: vec![
+				unrewarded_relayer(1, 1, 3),
+				unrewarded_relayer(2, 4, 4),
+				unrewarded_relayer(1, 5, 10),
+			]
+			.into_iter()
+			.collect(),
+			last_confirmed_nonce: 0,
+		};
+		let state = UnrewardedRelayersState::from(&lane_data);
+		assert_eq!(
+			state,
+			UnrewardedRelayersState {
+				unrewarded_relayer_entries: 3,
+				messages_in_oldest_entry: 3,
+				total_messages: 10,
+				last_delivered_nonce: 10,
+			},
+		);
+		assert!(state.is_valid_for(&lane_data));
+	}
+
+	#[test]
+	fn unrewarded_messages_of_relayer_are_summed_over_all_entries() {
+		let lane_data = InboundLaneData {
+			relayers: vec![
+				unrewarded_relayer(1, 1, 3),
+				unrewarded_relayer(2, 4, 4),
+				unrewarded_relayer(1, 5, 10),
+				unrewarded_relayer(3, 11, 12),
+				unrewarded_relayer(1, 13, 13),
+			]
+			.into_iter()
+			.collect(),
+			last_confirmed_nonce: 0,
+		};
+		assert_eq!(lane_data.unrewarded_messages_of(&1), 10);
+		assert_eq!(lane_data.unrewarded_messages_of(&2), 1);
+		assert_eq!(lane_data.unrewarded_messages_of(&3), 2);
+		assert_eq!(lane_data.unrewarded_messages_of(&4), 0);
+		assert_eq!(lane_data.relayers_summary(), vec![(1, 10), (2, 1), (3, 2)]);
+	}
+
+	#[test]
+	fn relayers_summary_is_empty_for_empty_relayers_set() {
+		let lane_data =
+			InboundLaneData::<u8> { relayers: VecDeque::new(), last_confirmed_nonce: 42 };
+		assert_eq!(lane_data.unrewarded_messages_of(&1), 0);
+		assert_eq!(lane_data.relayers_summary(), vec![]);
+	}
+
+	#[test]
+	fn relayers_summary_does_not_overflow() {
+		let lane_data = InboundLaneData {
+			relayers: vec![
+				unrewarded_relayer(2, 0, MessageNonce::MAX - 1),
+				unrewarded_relayer(1, MessageNonce::MAX, MessageNonce::MAX),
+				unrewarded_relayer(2, 0, 1),
+			]
+			.into_iter()
+			.collect(),
+			last_confirmed_nonce: 0,
+		};
+		assert_eq!(lane_data.unrewarded_messages_of(&2), MessageNonce::MAX);
+		assert_eq!(lane_data.relayers_summary(), vec![(1, 1), (2, MessageNonce::MAX)]);
+	}
+
+	#[test]
+	fn unrewarded_relayers_state_does_not_overflow_at_nonce_max() {
+		// single entry that spans the whole nonces range
+		let lane_data = InboundLaneData {
+			relayers: vec![unrewarded_relayer(1, 0, MessageNonce::MAX)].into_iter().collect(),
+			last_confirmed_nonce: 0,
+		};
+		let state = UnrewardedRelayersState::from(&lane_data);
+		assert_eq!(
+			state,
+			UnrewardedRelayersState {
+				unrewarded_relayer_entries: 1,
+				messages_in_oldest_entry: MessageNonce::MAX,
+				total_messages: MessageNonce::MAX,
+				last_delivered_nonce: MessageNonce::MAX,
+			},
+		);
+		assert!(state.is_valid_for(&lane_data));
+
+		// multiple entries, where the last one ends at `MessageNonce::MAX`
+		let lane_data = InboundLaneData {
+			relayers: vec![
+				unrewarded_relayer(1, MessageNonce::MAX - 10, MessageNonce::MAX - 1),
+				unrewarded_relayer(2, MessageNonce::MAX, MessageNonce::MAX),
+			]
+			.into_iter()
+			.collect(),
+			last_confirmed_nonce: MessageNonce::MAX - 11,
+		};
+		let state = UnrewardedRelayersState::from(&lane_data);
+		assert_eq!(
+			state,
+			UnrewardedRelayersState {
+				unrewarded_relayer_entries: 2,
+				messages_in_oldest_entry: 10,
+				total_messages: 11,
+				last_delivered_nonce: MessageNonce::MAX,
+			},
+		);
+		assert!(state.is_valid_for(&lane_data));
+	}
+
+	#[test]
+	fn unrewarded_relayers_state_is_not_valid_for_different_lane_data() {
+		let lane_data = InboundLaneData {
+			relayers: vec![unrewarded_relayer(1, 1, 3), unrewarded_relayer(2, 4, 4)]
+				.into_iter()
+				.collect(),
+			last_confirmed_nonce: 0,
+		};
+		let state = UnrewardedRelayersState::from(&lane_data);
+
+		assert!(!UnrewardedRelayersState { unrewarded_relayer_entries: 1, ..state.clone() }
+			.is_valid_for(&lane_data));
+		assert!(!UnrewardedRelayersState { total_messages: 3, ..state.clone() }
+			.is_valid_for(&lane_data));
+		assert!(!UnrewardedRelayersState { last_delivered_nonce: 3, ..state.clone() }
+			.is_valid_for(&lane_data));
+		// `messages_in_oldest_entry` isn't checked
+		assert!(UnrewardedRelayersState { messages_in_oldest_entry: 1, ..state }
+			.is_valid_for(&lane_data));
+	}
+
 	#[test]
 	fn inbound_lane_data_returns_correct_hint() {
 		let test_cases = vec![
@@ -422,6 +1101,109 @@ mod tests {
 				messages_count,
 			);
 		}
+
+		// when the same relayer delivers all messages in consecutive batches, merged entries
+		// are taking less space than separate entries
+		let separate_entries_hint = InboundLaneData::<u8>::encoded_size_hint(128, 128).unwrap();
+		let merged_entries_hint = InboundLaneData::<u8>::encoded_size_hint(1, 128).unwrap();
+		assert!(merged_entries_hint < separate_entries_hint);
+
+		let separate_entries = InboundLaneData {
+			relayers: (1..=128)
+				.map(|nonce| UnrewardedRelayer {
+					relayer: 1u8,
+					messages: DeliveredMessages::new(nonce, true),
+				})
+				.collect::<VecDeque<_>>(),
+			last_confirmed_nonce: 0,
+		};
+		let mut merged_entries = InboundLaneData {
+			relayers: separate_entries.relayers.iter().take(1).cloned().collect(),
+			last_confirmed_nonce: 0,
+		};
+		for entry in separate_entries.relayers.iter().skip(1) {
+			assert!(merged_entries.relayers[0].messages.try_append(&entry.messages));
+		}
+		assert_eq!(merged_entries.relayers.len(), 1);
+		assert_eq!(merged_entries.relayers[0].messages.total_messages(), 128);
+		assert!(merged_entries.encoded_size() < separate_entries.encoded_size());
+		assert!(merged_entries.encoded_size() <= merged_entries_hint);
+	}
+
+	#[test]
+	fn delivered_messages_are_appended_only_if_ranges_are_contiguous() {
+		let mut messages = DeliveredMessages::new(10, true);
+
+		// gap between ranges
+		assert!(!messages.try_append(&DeliveredMessages::new(12, false)));
+		// overlapping ranges
+		assert!(!messages.try_append(&DeliveredMessages::new(10, false)));
+		assert_eq!(messages, DeliveredMessages::new(10, true));
+
+		// contiguous ranges
+		let mut other = DeliveredMessages::new(11, false);
+		assert!(other.try_note_dispatched_message(true));
+		assert!(messages.try_append(&other));
+		assert_eq!(
+			messages,
+			DeliveredMessages { begin: 10, end: 12, dispatch_results: bitvec![u8, Msb0; 1, 0, 1] },
+		);
+		assert_eq!(messages.get_dispatch_result(11), Some(false));
+		assert_eq!(messages.get_dispatch_result(12), Some(true));
+
+		// range that ends at `MessageNonce::MAX` can't be extended
+		let mut messages = DeliveredMessages::new(MessageNonce::MAX, true);
+		assert!(!messages.try_append(&DeliveredMessages::new(0, true)));
+	}
+
+	#[test]
+	fn inbound_lane_data_hint_is_upper_bound() {
+		let test_cases = vec![
+			// single relayer, single message
+			(1, 1),
+			// single relayer, multiple messages
+			(1, 128),
+			// multiple relayers, single message per relayer
+			(128, 128),
+			// several messages per relayer
+			(13, 128),
+			// compact-encoded lengths occupy more than single byte
+			(100, 100_000),
+		];
+		for (relayer_entries, messages_count) in test_cases {
+			let max_size =
+				InboundLaneData::<u64>::encoded_size_hint(relayer_entries, messages_count).unwrap();
+			// worst case is when every entry, except the last one, has a single partially filled
+			// byte of dispatch results, and the last entry has all remaining messages
+			let actual_size = InboundLaneData {
+				relayers: (1..=relayer_entries)
+					.map(|i| {
+						let entry_messages = if i == relayer_entries {
+							messages_count - relayer_entries + 1
+						} else {
+							1
+						};
+						let mut entry = UnrewardedRelayer {
+							relayer: u64::MAX,
+							messages: DeliveredMessages::new(i as _, true),
+						};
+						entry.messages.dispatch_results = bitvec![u8, Msb0; 1; entry_messages];
+						entry
+					})
+					.collect(),
+				last_confirmed_nonce: MessageNonce::MAX,
+			}
+			.encode()
+			.len();
+			assert!(
+				actual_size <= max_size,
+				"Actual ({}) inbound lane data size exceeds maximal ({}) size. Test case: {}+{}",
+				actual_size,
+				max_size,
+				relayer_entries,
+				messages_count,
+			);
+		}
 	}
 
 	#[test]
@@ -434,6 +1216,56 @@ mod tests {
 		assert!(delivered_messages.contains_message(150));
 		assert!(!delivered_messages.contains_message(151));
 
-		assert!(delivered_messages.message_dispatch_result(125));
+		assert_eq!(delivered_messages.get_dispatch_result(99), None);
+		assert_eq!(delivered_messages.get_dispatch_result(125), Some(true));
+		assert_eq!(delivered_messages.get_dispatch_result(151), None);
+	}
+
+	#[test]
+	#[allow(deprecated)]
+	fn deprecated_delivered_messages_methods_work() {
+		let mut delivered_messages = DeliveredMessages::new(100, true);
+		delivered_messages.note_dispatched_message(false);
+
+		assert_eq!(delivered_messages.total_messages(), 2);
+		assert!(delivered_messages.message_dispatch_result(100));
+		assert!(!delivered_messages.message_dispatch_result(101));
+	}
+
+	#[test]
+	fn try_note_dispatched_message_fails_at_nonce_overflow() {
+		let mut delivered_messages = DeliveredMessages::new(MessageNonce::MAX - 1, true);
+		assert!(delivered_messages.try_note_dispatched_message(false));
+		assert_eq!(
+			delivered_messages,
+			DeliveredMessages {
+				begin: MessageNonce::MAX - 1,
+				end: MessageNonce::MAX,
+				dispatch_results: bitvec![u8, Msb0; 1, 0],
+			},
+		);
+
+		// can't note message with nonce larger than `MessageNonce::MAX`
+		assert!(!delivered_messages.try_note_dispatched_message(true));
+		assert_eq!(delivered_messages.total_messages(), 2);
+		assert_eq!(delivered_messages.dispatch_results, bitvec![u8, Msb0; 1, 0]);
+	}
+
+	#[test]
+	fn get_dispatch_result_works_at_nonce_boundaries() {
+		let delivered_messages = DeliveredMessages {
+			begin: MessageNonce::MAX - 1,
+			end: MessageNonce::MAX,
+			dispatch_results: bitvec![u8, Msb0; 0, 1],
+		};
+		assert_eq!(delivered_messages.get_dispatch_result(MessageNonce::MAX - 2), None);
+		assert_eq!(delivered_messages.get_dispatch_result(MessageNonce::MAX - 1), Some(false));
+		assert_eq!(delivered_messages.get_dispatch_result(MessageNonce::MAX), Some(true));
+
+		// dispatch results vector is shorter than the range
+		let delivered_messages =
+			DeliveredMessages { begin: 0, end: MessageNonce::MAX, dispatch_results: bitvec![] };
+		assert_eq!(delivered_messages.get_dispatch_result(0), None);
+		assert_eq!(delivered_messages.get_dispatch_result(MessageNonce::MAX), None);
 	}
 }
@@ -16,14 +16,18 @@
 
 //! Primitives of messages module, that are used on the source chain.
 
-use crate::{DeliveredMessages, InboundLaneData, LaneId, MessageNonce, OutboundLaneData};
+use crate::{
+	DeliveredMessages, InboundLaneData, LaneId, MessageNonce, OutboundLaneData, VerificationError,
+};
 
 use crate::UnrewardedRelayer;
 use bp_runtime::Size;
-use frame_support::{weights::Weight, Parameter, RuntimeDebug};
+use codec::MaxEncodedLen;
+use frame_support::{traits::OriginTrait, weights::Weight, Parameter, RuntimeDebug};
 use sp_std::{
 	collections::{btree_map::BTreeMap, vec_deque::VecDeque},
 	fmt::Debug,
+	marker::PhantomData,
 	ops::RangeInclusive,
 };
 
@@ -44,6 +48,53 @@ pub trait SenderOrigin<AccountId> {
 	fn linked_account(&self) -> Option<AccountId>;
 }
 
+/// Identifies submitter of the outbound message.
+///
+/// The submitter is stored along with the message, so that it may be used later (e.g. to refund
+/// the message fee). Chains that don't need to know message submitters may use `()`, which
+/// never stores anything.
+pub trait MessageSubmitter<SenderOrigin> {
+	/// Identifier of the message submitter.
+	type Submitter: Parameter + MaxEncodedLen;
+
+	/// Return identifier of the submitter of the message, sent by given origin.
+	fn submitter(origin: &SenderOrigin) -> Option<Self::Submitter>;
+
+	/// Return origin that the fee of the message, sent by given submitter, may be refunded to.
+	fn refund_origin(submitter: &Self::Submitter) -> Option<SenderOrigin>;
+}
+
+impl<SenderOrigin> MessageSubmitter<SenderOrigin> for () {
+	type Submitter = ();
+
+	fn submitter(_origin: &SenderOrigin) -> Option<()> {
+		None
+	}
+
+	fn refund_origin(_submitter: &()) -> Option<SenderOrigin> {
+		None
+	}
+}
+
+/// Message submitter, which is the account that is linked to the message origin.
+pub struct LinkedAccountSubmitter<AccountId>(PhantomData<AccountId>);
+
+impl<Origin, AccountId> MessageSubmitter<Origin> for LinkedAccountSubmitter<AccountId>
+where
+	Origin: SenderOrigin<AccountId> + OriginTrait<AccountId = AccountId>,
+	AccountId: Parameter + MaxEncodedLen,
+{
+	type Submitter = AccountId;
+
+	fn submitter(origin: &Origin) -> Option<AccountId> {
+		origin.linked_account()
+	}
+
+	fn refund_origin(submitter: &AccountId) -> Option<Origin> {
+		Some(Origin::signed(submitter.clone()))
+	}
+}
+
 /// Relayers rewards, grouped by relayer account id.
 pub type RelayersRewards<AccountId, Balance> = BTreeMap<AccountId, RelayerRewards<Balance>>;
 
@@ -67,7 +118,7 @@ pub struct RelayerRewards<Balance> {
 /// type used by the source chain.
 pub trait TargetHeaderChain<Payload, AccountId> {
 	/// Error type.
-	type Error: Debug + Into<&'static str>;
+	type Error: Debug + Into<VerificationError>;
 
 	/// Proof that messages have been received by target chain.
 	type MessagesDeliveryProof: Parameter + Size;
@@ -102,7 +153,7 @@ pub trait TargetHeaderChain<Payload, AccountId> {
 /// Any fee requirements should also be enforced here.
 pub trait LaneMessageVerifier<SenderOrigin, Payload, Fee> {
 	/// Error type.
-	type Error: Debug + Into<&'static str>;
+	type Error: Debug + Into<VerificationError>;
 
 	/// Verify message payload and return Ok(()) if message is valid and allowed to be sent over the
 	/// lane.
@@ -139,6 +190,13 @@ pub trait MessageDeliveryAndDispatchPayment<SenderOrigin, AccountId, Balance> {
 		fee: &Balance,
 	) -> Result<(), Self::Error>;
 
+	/// Return delivery_and_dispatch_fee of the cancelled message from some relayers-fund
+	/// account back to the submitter account.
+	fn refund_delivery_and_dispatch_fee(
+		submitter: &SenderOrigin,
+		fee: &Balance,
+	) -> Result<(), Self::Error>;
+
 	/// Pay rewards for delivering messages to the given relayers.
 	///
 	/// The implementation may also choose to pay reward to the `confirmation_relayer`, which is
@@ -163,6 +221,13 @@ impl<SenderOrigin, AccountId, Balance>
 		Ok(())
 	}
 
+	fn refund_delivery_and_dispatch_fee(
+		_submitter: &SenderOrigin,
+		_fee: &Balance,
+	) -> Result<(), Self::Error> {
+		Ok(())
+	}
+
 	fn pay_relayers_rewards(
 		_lane_id: LaneId,
 		_messages_relayers: VecDeque<UnrewardedRelayer<AccountId>>,
@@ -267,25 +332,25 @@ const ALL_OUTBOUND_MESSAGES_REJECTED: &str =
 	"This chain is configured to reject all outbound messages";
 
 impl<Payload, AccountId> TargetHeaderChain<Payload, AccountId> for ForbidOutboundMessages {
-	type Error = &'static str;
+	type Error = VerificationError;
 
 	type MessagesDeliveryProof = ();
 
 	fn verify_message(_payload: &Payload) -> Result<(), Self::Error> {
-		Err(ALL_OUTBOUND_MESSAGES_REJECTED)
+		Err(VerificationError::Other(ALL_OUTBOUND_MESSAGES_REJECTED))
 	}
 
 	fn verify_messages_delivery_proof(
 		_proof: Self::MessagesDeliveryProof,
 	) -> Result<(LaneId, InboundLaneData<AccountId>), Self::Error> {
-		Err(ALL_OUTBOUND_MESSAGES_REJECTED)
+		Err(VerificationError::Other(ALL_OUTBOUND_MESSAGES_REJECTED))
 	}
 }
 
 impl<SenderOrigin, Payload, Fee> LaneMessageVerifier<SenderOrigin, Payload, Fee>
 	for ForbidOutboundMessages
 {
-	type Error = &'static str;
+	type Error = VerificationError;
 
 	fn verify_message(
 		_submitter: &SenderOrigin,
@@ -294,7 +359,7 @@ impl<SenderOrigin, Payload, Fee> LaneMessageVerifier<SenderOrigin, Payload, Fee>
 		_outbound_data: &OutboundLaneData,
 		_payload: &Payload,
 	) -> Result<(), Self::Error> {
-		Err(ALL_OUTBOUND_MESSAGES_REJECTED)
+		Err(VerificationError::Other(ALL_OUTBOUND_MESSAGES_REJECTED))
 	}
 }
 
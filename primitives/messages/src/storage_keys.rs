@@ -24,12 +24,70 @@ pub const OUTBOUND_MESSAGES_MAP_NAME: &str = "OutboundMessages";
 pub const OUTBOUND_LANES_MAP_NAME: &str = "OutboundLanes";
 /// Name of the `InboundLanes` storage map.
 pub const INBOUND_LANES_MAP_NAME: &str = "InboundLanes";
-
-use crate::{LaneId, MessageKey, MessageNonce};
-
-use codec::Encode;
+/// Name of the `LaneMode` storage map.
+pub const LANE_MODE_MAP_NAME: &str = "LaneMode";
+/// Prefix of all messages history entries in the offchain index.
+pub const MESSAGES_HISTORY_INDEX_PREFIX: &[u8] = b"bridge-messages-history";
+
+use crate::{
+	InboundLaneData, LaneId, MessageData, MessageEventKind, MessageKey, MessageNonce,
+	MessagesOperatingMode, OutboundLaneData,
+};
+
+use bp_runtime::StorageMapKeyProvider;
+use codec::{Encode, FullCodec};
 use frame_support::Blake2_128Concat;
 use sp_core::storage::StorageKey;
+use sp_std::{marker::PhantomData, vec::Vec};
+
+/// Can be used to access the runtime storage key of the outbound message.
+///
+/// The message is stored by the messages pallet in the `OutboundMessages` map.
+pub struct OutboundMessagesKeyProvider<Fee>(PhantomData<Fee>);
+impl<Fee: FullCodec> StorageMapKeyProvider for OutboundMessagesKeyProvider<Fee> {
+	const MAP_NAME: &'static str = OUTBOUND_MESSAGES_MAP_NAME;
+
+	type Hasher = Blake2_128Concat;
+	type Key = MessageKey;
+	type Value = MessageData<Fee>;
+}
+
+/// Can be used to access the runtime storage key of the outbound lane state.
+///
+/// The state is stored by the messages pallet in the `OutboundLanes` map.
+pub struct OutboundLanesKeyProvider;
+impl StorageMapKeyProvider for OutboundLanesKeyProvider {
+	const MAP_NAME: &'static str = OUTBOUND_LANES_MAP_NAME;
+
+	type Hasher = Blake2_128Concat;
+	type Key = LaneId;
+	type Value = OutboundLaneData;
+}
+
+/// Can be used to access the runtime storage key of the inbound lane state.
+///
+/// The state is stored by the messages pallet in the `InboundLanes` map.
+pub struct InboundLanesKeyProvider<RelayerId>(PhantomData<RelayerId>);
+impl<RelayerId: FullCodec> StorageMapKeyProvider for InboundLanesKeyProvider<RelayerId> {
+	const MAP_NAME: &'static str = INBOUND_LANES_MAP_NAME;
+
+	type Hasher = Blake2_128Concat;
+	type Key = LaneId;
+	type Value = InboundLaneData<RelayerId>;
+}
+
+/// Can be used to access the runtime storage key of the lane operating mode.
+///
+/// The mode is stored by the messages pallet in the `LaneMode` map. Lanes without an entry
+/// are operating normally.
+pub struct LaneModeKeyProvider;
+impl StorageMapKeyProvider for LaneModeKeyProvider {
+	const MAP_NAME: &'static str = LANE_MODE_MAP_NAME;
+
+	type Hasher = Blake2_128Concat;
+	type Key = LaneId;
+	type Value = MessagesOperatingMode;
+}
 
 /// Storage key of the `PalletOperatingMode` value in the runtime storage.
 pub fn operating_mode_key(pallet_prefix: &str) -> StorageKey {
@@ -56,7 +114,7 @@ pub fn outbound_lane_data_key(pallet_prefix: &str, lane: &LaneId) -> StorageKey
 	bp_runtime::storage_map_final_key::<Blake2_128Concat>(
 		pallet_prefix,
 		OUTBOUND_LANES_MAP_NAME,
-		lane,
+		lane.as_ref(),
 	)
 }
 
@@ -65,10 +123,23 @@ pub fn inbound_lane_data_key(pallet_prefix: &str, lane: &LaneId) -> StorageKey {
 	bp_runtime::storage_map_final_key::<Blake2_128Concat>(
 		pallet_prefix,
 		INBOUND_LANES_MAP_NAME,
-		lane,
+		lane.as_ref(),
 	)
 }
 
+/// Key of the messages history entry in the offchain index.
+///
+/// The entry is written by the pallet (if indexing is enabled) when message event of given kind
+/// happens. The value is the SCALE-encoded `(BlockNumber, MessageEventKind)` tuple.
+pub fn message_history_index_key(
+	pallet_prefix: &str,
+	lane: &LaneId,
+	nonce: MessageNonce,
+	kind: MessageEventKind,
+) -> Vec<u8> {
+	(MESSAGES_HISTORY_INDEX_PREFIX, pallet_prefix, lane, nonce, kind).encode()
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -91,7 +162,7 @@ mod tests {
 	fn storage_message_key_computed_properly() {
 		// If this test fails, then something has been changed in module storage that is breaking
 		// all previously crafted messages proofs.
-		let storage_key = message_key("BridgeMessages", b"test", 42).0;
+		let storage_key = message_key("BridgeMessages", &LaneId(*b"test"), 42).0;
 		assert_eq!(
 			storage_key,
 			hex!("dd16c784ebd3390a9bc0357c7511ed018a395e6242c6813b196ca31ed0547ea79446af0e09063bd4a7874aef8a997cec746573742a00000000000000").to_vec(),
@@ -104,7 +175,7 @@ mod tests {
 	fn outbound_lane_data_key_computed_properly() {
 		// If this test fails, then something has been changed in module storage that is breaking
 		// all previously crafted outbound lane state proofs.
-		let storage_key = outbound_lane_data_key("BridgeMessages", b"test").0;
+		let storage_key = outbound_lane_data_key("BridgeMessages", &LaneId(*b"test")).0;
 		assert_eq!(
 			storage_key,
 			hex!("dd16c784ebd3390a9bc0357c7511ed0196c246acb9b55077390e3ca723a0ca1f44a8995dd50b6657a037a7839304535b74657374").to_vec(),
@@ -117,7 +188,7 @@ mod tests {
 	fn inbound_lane_data_key_computed_properly() {
 		// If this test fails, then something has been changed in module storage that is breaking
 		// all previously crafted inbound lane state proofs.
-		let storage_key = inbound_lane_data_key("BridgeMessages", b"test").0;
+		let storage_key = inbound_lane_data_key("BridgeMessages", &LaneId(*b"test")).0;
 		assert_eq!(
 			storage_key,
 			hex!("dd16c784ebd3390a9bc0357c7511ed01e5f83cf83f2127eb47afdc35d6e43fab44a8995dd50b6657a037a7839304535b74657374").to_vec(),
@@ -125,4 +196,55 @@ mod tests {
 			hex::encode(&storage_key),
 		);
 	}
+
+	#[test]
+	fn lane_mode_key_computed_properly() {
+		// If this test fails, then something has been changed in module storage that is possibly
+		// breaking all existing message relays.
+		let storage_key = LaneModeKeyProvider::final_key("BridgeMessages", &LaneId(*b"test")).0;
+		assert_eq!(
+			storage_key,
+			hex!("dd16c784ebd3390a9bc0357c7511ed016c9a41f4747d01b9c8cf6a84683a50bd44a8995dd50b6657a037a7839304535b74657374").to_vec(),
+			"Unexpected storage key: {}",
+			hex::encode(&storage_key),
+		);
+	}
+
+	#[test]
+	fn key_providers_compute_same_keys() {
+		let lane = LaneId(*b"test");
+		assert_eq!(
+			OutboundMessagesKeyProvider::<u64>::final_key(
+				"BridgeMessages",
+				&MessageKey { lane_id: lane, nonce: 42 },
+			),
+			message_key("BridgeMessages", &lane, 42),
+		);
+		assert_eq!(
+			OutboundLanesKeyProvider::final_key("BridgeMessages", &lane),
+			outbound_lane_data_key("BridgeMessages", &lane),
+		);
+		assert_eq!(
+			InboundLanesKeyProvider::<u64>::final_key("BridgeMessages", &lane),
+			inbound_lane_data_key("BridgeMessages", &lane),
+		);
+	}
+
+	#[test]
+	fn message_history_index_key_computed_properly() {
+		// If this test fails, then something has been changed in the offchain index layout and
+		// explorers won't be able to read previously indexed messages history.
+		let key = message_history_index_key(
+			"BridgeMessages",
+			&LaneId(*b"test"),
+			42,
+			MessageEventKind::Delivered,
+		);
+		assert_eq!(
+			key,
+			hex!("5c6272696467652d6d657373616765732d686973746f7279384272696467654d65737361676573746573742a0000000000000001").to_vec(),
+			"Unexpected index key: {}",
+			hex::encode(&key),
+		);
+	}
 }
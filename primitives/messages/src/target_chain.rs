@@ -16,9 +16,14 @@
 
 //! Primitives of messages module, that are used on the target chain.
 
-use crate::{LaneId, Message, MessageData, MessageKey, OutboundLaneData};
+use crate::{
+	LaneId, Message, MessageData, MessageKey, MessagePayload, OutboundLaneData, VerificationError,
+};
 
-use bp_runtime::{messages::MessageDispatchResult, Size};
+use bp_runtime::{
+	messages::{MessageDispatchError, MessageDispatchResult},
+	Size,
+};
 use codec::{Decode, Encode, Error as CodecError};
 use frame_support::{weights::Weight, Parameter, RuntimeDebug};
 use scale_info::TypeInfo;
@@ -36,21 +41,66 @@ pub struct ProvedLaneMessages<Message> {
 	pub messages: Vec<Message>,
 }
 
-/// Message data with decoded dispatch payload.
+/// Dispatch payload that is decoded on first access.
+///
+/// The payload is decoded at most once, so both `MessageDispatch::dispatch_weight` and
+/// `MessageDispatch::dispatch` may access it without paying for the decoding twice.
+#[derive(RuntimeDebug)]
+pub struct LazyDispatchPayload<DispatchPayload> {
+	/// Encoded dispatch payload, as it has been sent by the source chain.
+	encoded: MessagePayload,
+	/// Result of dispatch payload decoding or `None` if payload has not been decoded yet.
+	decoded: Option<Result<DispatchPayload, CodecError>>,
+}
+
+impl<DispatchPayload> LazyDispatchPayload<DispatchPayload> {
+	/// Create new lazy payload from the encoded payload bytes.
+	pub fn new(encoded: MessagePayload) -> Self {
+		LazyDispatchPayload { encoded, decoded: None }
+	}
+
+	/// Returns encoded payload bytes.
+	pub fn encoded(&self) -> &[u8] {
+		&self.encoded
+	}
+
+	/// Returns true if payload has already been decoded.
+	pub fn is_decoded(&self) -> bool {
+		self.decoded.is_some()
+	}
+}
+
+impl<DispatchPayload: Decode> LazyDispatchPayload<DispatchPayload> {
+	/// Returns result of dispatch payload decoding. The payload is decoded on the first call.
+	pub fn decoded(&mut self) -> &mut Result<DispatchPayload, CodecError> {
+		let encoded = &self.encoded;
+		self.decoded.get_or_insert_with(|| DispatchPayload::decode(&mut &encoded[..]))
+	}
+
+	/// Consume self, returning result of dispatch payload decoding.
+	pub fn into_decoded(self) -> Result<DispatchPayload, CodecError> {
+		match self.decoded {
+			Some(decoded) => decoded,
+			None => DispatchPayload::decode(&mut &self.encoded[..]),
+		}
+	}
+}
+
+/// Message data with lazily decoded dispatch payload.
 #[derive(RuntimeDebug)]
 pub struct DispatchMessageData<DispatchPayload, Fee> {
-	/// Result of dispatch payload decoding.
-	pub payload: Result<DispatchPayload, CodecError>,
+	/// Dispatch payload, that is decoded on first access.
+	pub payload: LazyDispatchPayload<DispatchPayload>,
 	/// Message delivery and dispatch fee, paid by the submitter.
 	pub fee: Fee,
 }
 
-/// Message with decoded dispatch payload.
+/// Message with lazily decoded dispatch payload.
 #[derive(RuntimeDebug)]
 pub struct DispatchMessage<DispatchPayload, Fee> {
 	/// Message key.
 	pub key: MessageKey,
-	/// Message data with decoded dispatch payload.
+	/// Message data with lazily decoded dispatch payload.
 	pub data: DispatchMessageData<DispatchPayload, Fee>,
 }
 
@@ -61,7 +111,7 @@ pub struct DispatchMessage<DispatchPayload, Fee> {
 /// that's stuck) and/or processing messages without paying fees.
 pub trait SourceHeaderChain<Fee> {
 	/// Error type.
-	type Error: Debug + Into<&'static str>;
+	type Error: Debug + Into<VerificationError>;
 
 	/// Proof that messages are sent from source chain. This may also include proof
 	/// of corresponding outbound lane states.
@@ -117,20 +167,15 @@ impl<Message> Default for ProvedLaneMessages<Message> {
 	}
 }
 
-impl<DispatchPayload: Decode, Fee> From<Message<Fee>> for DispatchMessage<DispatchPayload, Fee> {
+impl<DispatchPayload, Fee> From<Message<Fee>> for DispatchMessage<DispatchPayload, Fee> {
 	fn from(message: Message<Fee>) -> Self {
 		DispatchMessage { key: message.key, data: message.data.into() }
 	}
 }
 
-impl<DispatchPayload: Decode, Fee> From<MessageData<Fee>>
-	for DispatchMessageData<DispatchPayload, Fee>
-{
+impl<DispatchPayload, Fee> From<MessageData<Fee>> for DispatchMessageData<DispatchPayload, Fee> {
 	fn from(data: MessageData<Fee>) -> Self {
-		DispatchMessageData {
-			payload: DispatchPayload::decode(&mut &data.payload[..]),
-			fee: data.fee,
-		}
+		DispatchMessageData { payload: LazyDispatchPayload::new(data.payload), fee: data.fee }
 	}
 }
 
@@ -143,14 +188,14 @@ const ALL_INBOUND_MESSAGES_REJECTED: &str =
 	"This chain is configured to reject all inbound messages";
 
 impl<Fee> SourceHeaderChain<Fee> for ForbidInboundMessages {
-	type Error = &'static str;
+	type Error = VerificationError;
 	type MessagesProof = ();
 
 	fn verify_messages_proof(
 		_proof: Self::MessagesProof,
 		_messages_count: u32,
 	) -> Result<ProvedMessages<Message<Fee>>, Self::Error> {
-		Err(ALL_INBOUND_MESSAGES_REJECTED)
+		Err(VerificationError::Other(ALL_INBOUND_MESSAGES_REJECTED))
 	}
 }
 
@@ -169,6 +214,76 @@ impl<AccountId, Fee> MessageDispatch<AccountId, Fee> for ForbidInboundMessages {
 			dispatch_result: false,
 			unspent_weight: 0,
 			dispatch_fee_paid_during_dispatch: false,
+			dispatch_error: Some(MessageDispatchError::Rejected),
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::cell::Cell;
+
+	thread_local! {
+		static DECODE_CALLS: Cell<usize> = Cell::new(0);
+	}
+
+	/// Payload that counts how many times it has been decoded.
+	#[derive(Debug, PartialEq)]
+	struct CountingPayload(u64);
+
+	impl Decode for CountingPayload {
+		fn decode<I: codec::Input>(input: &mut I) -> Result<Self, CodecError> {
+			DECODE_CALLS.with(|calls| calls.set(calls.get() + 1));
+			u64::decode(input).map(CountingPayload)
+		}
+	}
+
+	fn dispatch_message(payload: MessagePayload) -> DispatchMessage<CountingPayload, u64> {
+		DECODE_CALLS.with(|calls| calls.set(0));
+		Message {
+			key: MessageKey { lane_id: LaneId([0, 0, 0, 1]), nonce: 1 },
+			data: MessageData { payload, fee: 0 },
+		}
+		.into()
+	}
+
+	fn decode_calls() -> usize {
+		DECODE_CALLS.with(|calls| calls.get())
+	}
+
+	#[test]
+	fn payload_is_not_decoded_until_accessed() {
+		let message = dispatch_message(42u64.encode());
+		assert!(!message.data.payload.is_decoded());
+		assert_eq!(message.data.payload.encoded(), &42u64.encode()[..]);
+		assert_eq!(decode_calls(), 0);
+	}
+
+	#[test]
+	fn payload_is_decoded_exactly_once() {
+		let mut message = dispatch_message(42u64.encode());
+		assert_eq!(message.data.payload.decoded().as_ref().ok(), Some(&CountingPayload(42)));
+		// e.g. the weigher may update decoded payload
+		message.data.payload.decoded().as_mut().unwrap().0 = 43;
+		assert!(message.data.payload.is_decoded());
+		assert_eq!(message.data.payload.into_decoded().ok(), Some(CountingPayload(43)));
+		assert_eq!(decode_calls(), 1);
+	}
+
+	#[test]
+	fn undecodable_payload_is_decoded_exactly_once() {
+		let mut message = dispatch_message(vec![42]);
+		assert!(message.data.payload.decoded().is_err());
+		assert!(message.data.payload.decoded().is_err());
+		assert!(message.data.payload.into_decoded().is_err());
+		assert_eq!(decode_calls(), 1);
+	}
+
+	#[test]
+	fn payload_is_decoded_when_consumed_without_access() {
+		let message = dispatch_message(42u64.encode());
+		assert_eq!(message.data.payload.into_decoded().ok(), Some(CountingPayload(42)));
+		assert_eq!(decode_calls(), 1);
+	}
+}
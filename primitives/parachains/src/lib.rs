@@ -24,9 +24,10 @@ use bp_polkadot_core::{
 };
 use bp_runtime::{StorageDoubleMapKeyProvider, StorageMapKeyProvider};
 use codec::{Decode, Encode};
-use frame_support::{Blake2_128Concat, RuntimeDebug, Twox64Concat};
+use frame_support::{Blake2_128Concat, RuntimeDebug};
 use scale_info::TypeInfo;
-use sp_core::storage::StorageKey;
+
+pub use bp_polkadot_core::parachains::parachain_head_storage_key_at_source;
 
 /// Best known parachain head hash.
 #[derive(Clone, Decode, Encode, PartialEq, RuntimeDebug, TypeInfo)]
@@ -53,16 +54,6 @@ pub struct ParaInfo {
 	pub next_imported_hash_position: u32,
 }
 
-/// Returns runtime storage key of given parachain head at the source chain.
-///
-/// The head is stored by the `paras` pallet in the `Heads` map.
-pub fn parachain_head_storage_key_at_source(
-	paras_pallet_name: &str,
-	para_id: ParaId,
-) -> StorageKey {
-	bp_runtime::storage_map_final_key::<Twox64Concat>(paras_pallet_name, "Heads", &para_id.encode())
-}
-
 /// Can be use to access the runtime storage key of the parachains info at the target chain.
 ///
 /// The info is stored by the `pallet-bridge-parachains` pallet in the `ParasInfo` map.
@@ -88,3 +79,23 @@ impl StorageDoubleMapKeyProvider for ImportedParaHeadsKeyProvider {
 	type Key2 = ParaHash;
 	type Value = ParaHead;
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn para_info_encoding_is_not_changed() {
+		let para_info = ParaInfo {
+			best_head_hash: BestParaHeadHash {
+				at_relay_block_number: 42,
+				head_hash: ParaHash::from([1u8; 32]),
+			},
+			next_imported_hash_position: 7,
+		};
+		let encoded = (42 as RelayBlockNumber, ParaHash::from([1u8; 32]), 7u32).encode();
+
+		assert_eq!(para_info.encode(), encoded);
+		assert_eq!(ParaInfo::decode(&mut &encoded[..]), Ok(para_info));
+	}
+}
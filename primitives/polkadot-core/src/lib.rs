@@ -16,6 +16,7 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+use bp_header_chain::justification::max_expected_justification_size;
 use bp_messages::MessageNonce;
 use bp_runtime::{Chain, EncodedOrDecodedCall};
 use codec::Compact;
@@ -65,6 +66,38 @@ pub mod parachains;
 /// at next runtime upgrade.
 pub const EXTRA_STORAGE_PROOF_SIZE: u32 = 1024;
 
+/// Maximal number of GRANDPA authorities at Polkadot-like chains.
+///
+/// Kusama has the largest validator set (1000 validators) among Polkadot-like chains, so this
+/// value leaves some reserve for future growth.
+pub const MAX_AUTHORITIES_COUNT: u32 = 1_256;
+
+/// Maximal size of encoded GRANDPA justification of Polkadot-like chain headers.
+///
+/// Apart from precommits of all authorities, `EXTRA_STORAGE_PROOF_SIZE` bytes are reserved
+/// for headers in the votes ancestries.
+pub const MAX_JUSTIFICATION_SIZE: u32 =
+	max_expected_justification_size(MAX_AUTHORITIES_COUNT, EXTRA_STORAGE_PROOF_SIZE);
+
+/// Maximal size of encoded Polkadot-like chain header.
+///
+/// Apart from 4096 bytes reserved for regular header fields and digest items, it leaves room
+/// for the GRANDPA authority set change digest with `MAX_AUTHORITIES_COUNT` authorities (40
+/// bytes per authority).
+pub const MAX_HEADER_SIZE: u32 = 4096 + MAX_AUTHORITIES_COUNT * 40;
+
+/// Maximal size of the parachain heads storage proof, built at Polkadot-like chain.
+///
+/// The proof may include heads of many parachains, so the limit is generous. It exists to
+/// reject obviously oversized proofs before they are verified.
+pub const MAX_PARA_HEADS_PROOF_SIZE: u32 = 1024 * 1024;
+
+/// Maximal size of the parachain heads storage proof, built at Polkadot-like chain.
+///
+/// The proof may include heads of many parachains, so the limit is generous. It exists to
+/// reject obviously oversized proofs before they are verified.
+pub const MAX_PARA_HEADS_PROOF_SIZE: u32 = 1024 * 1024;
+
 /// All Polkadot-like chains allow normal extrinsics to fill block up to 75 percent.
 ///
 /// This is a copy-paste from the Polkadot repo's `polkadot-runtime-common` crate.
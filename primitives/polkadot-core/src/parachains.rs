@@ -23,11 +23,12 @@
 //! be two versions of polkadot crates included in the runtime. Which is bad.
 
 use bp_runtime::Size;
-use codec::{CompactAs, Decode, Encode, MaxEncodedLen};
-use frame_support::RuntimeDebug;
+use codec::{CompactAs, Decode, DecodeAll, Encode, MaxEncodedLen};
+use frame_support::{RuntimeDebug, Twox64Concat};
 use scale_info::TypeInfo;
-use sp_core::Hasher;
-use sp_std::vec::Vec;
+use sp_core::{storage::StorageKey, Hasher};
+use sp_runtime::traits::Header as HeaderT;
+use sp_std::{fmt, num::ParseIntError, str::FromStr, vec::Vec};
 
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
@@ -62,6 +63,20 @@ impl From<u32> for ParaId {
 	}
 }
 
+impl fmt::Display for ParaId {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}", self.0)
+	}
+}
+
+impl FromStr for ParaId {
+	type Err = ParseIntError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		s.parse().map(ParaId)
+	}
+}
+
 /// Parachain head.
 ///
 /// This is an equivalent of the `polkadot_parachain::HeadData`.
@@ -80,6 +95,56 @@ impl ParaHead {
 	pub fn hash(&self) -> crate::Hash {
 		sp_runtime::traits::BlakeTwo256::hash(&self.0)
 	}
+
+	/// Decodes the parachain header from this head data.
+	///
+	/// Returns an error if the head data isn't a SCALE-encoded header of given type or if
+	/// there are some extra bytes after the encoded header.
+	pub fn decode_header<H: HeaderT>(&self) -> Result<H, codec::Error> {
+		H::decode_all(&mut &self.0[..])
+	}
+
+	/// Returns state root of the parachain header, encoded in this head data.
+	pub fn state_root<H: HeaderT>(&self) -> Result<H::Hash, codec::Error> {
+		self.decode_header::<H>().map(|header| *header.state_root())
+	}
+
+	/// Returns number of the parachain header, encoded in this head data.
+	pub fn number<H: HeaderT>(&self) -> Result<H::Number, codec::Error> {
+		self.decode_header::<H>().map(|header| *header.number())
+	}
+
+	/// Returns parachain header data that is required by the bridge code.
+	pub fn stored_header_data<H: HeaderT>(
+		&self,
+	) -> Result<ParaStoredHeaderData<H::Number, H::Hash>, codec::Error> {
+		self.decode_header::<H>().map(|header| ParaStoredHeaderData {
+			number: *header.number(),
+			state_root: *header.state_root(),
+		})
+	}
+}
+
+/// Parachain header data that is required by the bridge code.
+///
+/// Parachain heads are opaque to the relay chain, so this data may only be extracted by the
+/// code that knows the header type of the parachain.
+#[derive(Clone, Copy, Decode, Encode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+pub struct ParaStoredHeaderData<Number, Hash> {
+	/// Number of the parachain header.
+	pub number: Number,
+	/// State root of the parachain header.
+	pub state_root: Hash,
+}
+
+/// Returns runtime storage key of given parachain head at the source chain.
+///
+/// The head is stored by the `paras` pallet in the `Heads` map.
+pub fn parachain_head_storage_key_at_source(
+	paras_pallet_name: &str,
+	para_id: ParaId,
+) -> StorageKey {
+	bp_runtime::storage_map_final_key::<Twox64Concat>(paras_pallet_name, "Heads", &para_id.encode())
 }
 
 /// Parachain head hash.
@@ -92,9 +157,118 @@ pub type ParaHasher = crate::Hasher;
 #[derive(Clone, Decode, Encode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
 pub struct ParaHeadsProof(pub Vec<Vec<u8>>);
 
+impl ParaHeadsProof {
+	/// Creates parachain heads proof from given trie nodes.
+	///
+	/// Returns an error if size of the proof exceeds `max_size` bytes. Such proof would be
+	/// rejected by the bridge parachains pallet anyway.
+	pub fn try_new(nodes: Vec<Vec<u8>>, max_size: u32) -> Result<Self, ParaHeadsProofIsTooLarge> {
+		let proof = ParaHeadsProof(nodes);
+		let size = proof.size();
+		if size > max_size {
+			return Err(ParaHeadsProofIsTooLarge { size, max_size })
+		}
+
+		Ok(proof)
+	}
+}
+
+/// Error that is returned when parachain heads proof exceeds the maximal size.
+#[derive(Clone, Copy, Eq, PartialEq, RuntimeDebug)]
+pub struct ParaHeadsProofIsTooLarge {
+	/// Actual size of the proof.
+	pub size: u32,
+	/// Maximal allowed size of the proof.
+	pub max_size: u32,
+}
+
 impl Size for ParaHeadsProof {
 	fn size(&self) -> u32 {
 		u32::try_from(self.0.iter().fold(0usize, |sum, node| sum.saturating_add(node.len())))
 			.unwrap_or(u32::MAX)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[frame_support::storage_alias]
+	type Heads = StorageMap<Paras, Twox64Concat, ParaId, ParaHead>;
+
+	#[test]
+	fn parachain_head_storage_key_at_source_is_computed_properly() {
+		assert_eq!(
+			parachain_head_storage_key_at_source("Paras", ParaId(42)).0,
+			Heads::hashed_key_for(ParaId(42)),
+		);
+	}
+
+	fn test_header() -> crate::Header {
+		crate::Header::new(
+			42,
+			Default::default(),
+			crate::Hash::repeat_byte(1),
+			crate::Hash::repeat_byte(2),
+			Default::default(),
+		)
+	}
+
+	#[test]
+	fn para_head_is_decoded_into_header() {
+		let header = test_header();
+		let para_head = ParaHead(header.encode());
+
+		assert_eq!(para_head.decode_header::<crate::Header>(), Ok(header));
+		assert_eq!(para_head.state_root::<crate::Header>(), Ok(crate::Hash::repeat_byte(1)));
+		assert_eq!(para_head.number::<crate::Header>(), Ok(42));
+		assert_eq!(
+			para_head.stored_header_data::<crate::Header>(),
+			Ok(ParaStoredHeaderData { number: 42, state_root: crate::Hash::repeat_byte(1) }),
+		);
+	}
+
+	#[test]
+	fn truncated_para_head_is_not_decoded() {
+		let mut encoded_header = test_header().encode();
+		encoded_header.truncate(encoded_header.len() - 1);
+		let para_head = ParaHead(encoded_header);
+
+		assert!(para_head.decode_header::<crate::Header>().is_err());
+		assert!(para_head.state_root::<crate::Header>().is_err());
+		assert!(para_head.number::<crate::Header>().is_err());
+		assert!(para_head.stored_header_data::<crate::Header>().is_err());
+	}
+
+	#[test]
+	fn para_id_is_formatted_and_parsed() {
+		assert_eq!(ParaId(2000).to_string(), "2000");
+		assert_eq!("2000".parse::<ParaId>(), Ok(ParaId(2000)));
+		assert!("".parse::<ParaId>().is_err());
+		assert!("-1".parse::<ParaId>().is_err());
+		assert!("para2000".parse::<ParaId>().is_err());
+	}
+
+	#[test]
+	fn para_heads_proof_size_is_checked() {
+		let nodes = vec![vec![42u8; 100], vec![42u8; 28]];
+
+		assert_eq!(ParaHeadsProof::try_new(nodes.clone(), 128), Ok(ParaHeadsProof(nodes.clone())));
+		assert_eq!(
+			ParaHeadsProof::try_new(nodes, 127),
+			Err(ParaHeadsProofIsTooLarge { size: 128, max_size: 127 }),
+		);
+	}
+
+	#[test]
+	fn over_long_para_head_is_not_decoded() {
+		let mut encoded_header = test_header().encode();
+		encoded_header.push(42);
+		let para_head = ParaHead(encoded_header);
+
+		assert!(para_head.decode_header::<crate::Header>().is_err());
+		assert!(para_head.state_root::<crate::Header>().is_err());
+		assert!(para_head.number::<crate::Header>().is_err());
+		assert!(para_head.stored_header_data::<crate::Header>().is_err());
+	}
+}
@@ -18,6 +18,7 @@ use crate::HeaderIdProvider;
 use codec::{Decode, Encode};
 use frame_support::{weights::Weight, Parameter};
 use num_traits::{AsPrimitive, Bounded, CheckedSub, Saturating, SaturatingAdd, Zero};
+use scale_info::{Type, TypeInfo};
 use sp_runtime::{
 	traits::{
 		AtLeast32Bit, AtLeast32BitUnsigned, Hash as HashT, Header as HeaderT, MaybeDisplay,
@@ -37,6 +38,16 @@ pub enum EncodedOrDecodedCall<ChainCall> {
 	Encoded(Vec<u8>),
 	/// The decoded call.
 	Decoded(ChainCall),
+	/// The call that has been both SCALE-encoded and decoded.
+	///
+	/// This variant is created by the `as_encoded` and `as_decoded` methods, so that repeated
+	/// accesses to the call don't require re-running the codec.
+	EncodedAndDecoded {
+		/// The SCALE-encoded call.
+		encoded: Vec<u8>,
+		/// The decoded call.
+		decoded: ChainCall,
+	},
 }
 
 impl<ChainCall: Clone + Decode> EncodedOrDecodedCall<ChainCall> {
@@ -45,7 +56,8 @@ impl<ChainCall: Clone + Decode> EncodedOrDecodedCall<ChainCall> {
 		match self {
 			Self::Encoded(ref encoded_call) =>
 				ChainCall::decode(&mut &encoded_call[..]).map_err(Into::into),
-			Self::Decoded(ref decoded_call) => Ok(decoded_call.clone()),
+			Self::Decoded(ref decoded_call) |
+			Self::EncodedAndDecoded { decoded: ref decoded_call, .. } => Ok(decoded_call.clone()),
 		}
 	}
 
@@ -54,7 +66,51 @@ impl<ChainCall: Clone + Decode> EncodedOrDecodedCall<ChainCall> {
 		match self {
 			Self::Encoded(encoded_call) =>
 				ChainCall::decode(&mut &encoded_call[..]).map_err(Into::into),
-			Self::Decoded(decoded_call) => Ok(decoded_call),
+			Self::Decoded(decoded_call) | Self::EncodedAndDecoded { decoded: decoded_call, .. } =>
+				Ok(decoded_call),
+		}
+	}
+}
+
+impl<ChainCall: Decode> EncodedOrDecodedCall<ChainCall> {
+	/// Returns reference to the decoded call.
+	///
+	/// The call is decoded at most once - the decoded call is cached next to its encoding.
+	/// If decoding fails, the call is left untouched.
+	pub fn as_decoded(&mut self) -> Result<&ChainCall, codec::Error> {
+		if let Self::Encoded(ref mut encoded_call) = *self {
+			let decoded = ChainCall::decode(&mut &encoded_call[..])?;
+			let encoded = sp_std::mem::take(encoded_call);
+			*self = Self::EncodedAndDecoded { encoded, decoded };
+		}
+
+		match *self {
+			Self::Encoded(_) =>
+				unreachable!("the call is never left in this state by the code above; qed"),
+			Self::Decoded(ref decoded_call) |
+			Self::EncodedAndDecoded { decoded: ref decoded_call, .. } => Ok(decoded_call),
+		}
+	}
+}
+
+impl<ChainCall: Encode> EncodedOrDecodedCall<ChainCall> {
+	/// Returns SCALE-encoded call.
+	///
+	/// The call is encoded at most once - the encoding is cached next to the decoded call.
+	pub fn as_encoded(&mut self) -> &[u8] {
+		if let Self::Decoded(_) = *self {
+			*self = match sp_std::mem::replace(self, Self::Encoded(Vec::new())) {
+				Self::Decoded(decoded) =>
+					Self::EncodedAndDecoded { encoded: decoded.encode(), decoded },
+				this => this,
+			};
+		}
+
+		match *self {
+			Self::Decoded(_) =>
+				unreachable!("the call is never left in this state by the code above; qed"),
+			Self::Encoded(ref encoded_call) |
+			Self::EncodedAndDecoded { encoded: ref encoded_call, .. } => encoded_call,
 		}
 	}
 }
@@ -83,12 +139,22 @@ impl<ChainCall: Decode> Decode for EncodedOrDecodedCall<ChainCall> {
 impl<ChainCall: Encode> Encode for EncodedOrDecodedCall<ChainCall> {
 	fn encode(&self) -> Vec<u8> {
 		match *self {
-			Self::Encoded(ref encoded_call) => encoded_call.clone(),
+			Self::Encoded(ref encoded_call) |
+			Self::EncodedAndDecoded { encoded: ref encoded_call, .. } => encoded_call.clone(),
 			Self::Decoded(ref decoded_call) => decoded_call.encode(),
 		}
 	}
 }
 
+// the call is encoded exactly as the `ChainCall`, so we may reuse its type information
+impl<ChainCall: TypeInfo + 'static> TypeInfo for EncodedOrDecodedCall<ChainCall> {
+	type Identity = ChainCall;
+
+	fn type_info() -> Type {
+		ChainCall::type_info()
+	}
+}
+
 /// Minimal Substrate-based chain representation that may be used from no_std environment.
 pub trait Chain: Send + Sync + 'static {
 	/// A type that fulfills the abstract idea of what a Substrate block number is.
@@ -215,12 +281,23 @@ pub type AccountPublicOf<C> = <SignatureOf<C> as Verify>::Signer;
 /// Transaction era used by the chain.
 pub type TransactionEraOf<C> = crate::TransactionEra<BlockNumberOf<C>, HashOf<C>>;
 
+/// SCALE-encoded finality justification of the bridged chain header.
+pub type EncodedJustification = Vec<u8>;
+
 /// Convenience macro that declares bridge finality runtime apis and related constants for a chain.
 /// This includes:
 /// - chain-specific bridge runtime APIs:
 ///     - `<ThisChain>FinalityApi`
+///     - `<ThisChain>FinalityOperatingModeApi`
+///     - `<ThisChain>SyncedHeadersGrandpaInfoApi`
 /// - constants that are stringified names of runtime API methods:
 ///     - `BEST_FINALIZED_<THIS_CHAIN>_HEADER_METHOD`
+///     - `FINALIZED_<THIS_CHAIN>_HEADER_METHOD`
+///     - `IS_KNOWN_<THIS_CHAIN>_HEADER_METHOD`
+///     - `STORED_<THIS_CHAIN>_JUSTIFICATION_METHOD`
+///     - `<THIS_CHAIN>_ACCEPTS_FREE_HEADER_METHOD`
+///     - `<THIS_CHAIN>_FINALITY_OPERATING_STATE_METHOD`
+///     - `<THIS_CHAIN>_SYNCED_HEADERS_GRANDPA_INFO_METHOD`
 /// The name of the chain has to be specified in snake case (e.g. `rialto_parachain`).
 #[macro_export]
 macro_rules! decl_bridge_finality_runtime_apis {
@@ -232,6 +309,25 @@ macro_rules! decl_bridge_finality_runtime_apis {
 				/// Name of the `<ThisChain>FinalityApi::best_finalized` runtime method.
 				pub const [<BEST_FINALIZED_ $chain:upper _HEADER_METHOD>]: &str =
 					stringify!([<$chain:camel FinalityApi_best_finalized>]);
+				/// Name of the `<ThisChain>FinalityApi::finalized_header` runtime method.
+				pub const [<FINALIZED_ $chain:upper _HEADER_METHOD>]: &str =
+					stringify!([<$chain:camel FinalityApi_finalized_header>]);
+				/// Name of the `<ThisChain>FinalityApi::is_known_header` runtime method.
+				pub const [<IS_KNOWN_ $chain:upper _HEADER_METHOD>]: &str =
+					stringify!([<$chain:camel FinalityApi_is_known_header>]);
+				/// Name of the `<ThisChain>FinalityApi::stored_justification` runtime method.
+				pub const [<STORED_ $chain:upper _JUSTIFICATION_METHOD>]: &str =
+					stringify!([<$chain:camel FinalityApi_stored_justification>]);
+				/// Name of the `<ThisChain>FinalityApi::accepts_free_header` runtime method.
+				pub const [<$chain:upper _ACCEPTS_FREE_HEADER_METHOD>]: &str =
+					stringify!([<$chain:camel FinalityApi_accepts_free_header>]);
+				/// Name of the `<ThisChain>FinalityOperatingModeApi::operating_state` runtime method.
+				pub const [<$chain:upper _FINALITY_OPERATING_STATE_METHOD>]: &str =
+					stringify!([<$chain:camel FinalityOperatingModeApi_operating_state>]);
+				/// Name of the `<ThisChain>SyncedHeadersGrandpaInfoApi::synced_headers_grandpa_info`
+				/// runtime method.
+				pub const [<$chain:upper _SYNCED_HEADERS_GRANDPA_INFO_METHOD>]: &str =
+					stringify!([<$chain:camel SyncedHeadersGrandpaInfoApi_synced_headers_grandpa_info>]);
 
 				sp_api::decl_runtime_apis! {
 					/// API for querying information about the finalized chain headers.
@@ -241,6 +337,39 @@ macro_rules! decl_bridge_finality_runtime_apis {
 					pub trait [<$chain:camel FinalityApi>] {
 						/// Returns number and hash of the best finalized header known to the bridge module.
 						fn best_finalized() -> Option<bp_runtime::HeaderId<Hash, BlockNumber>>;
+						/// Returns number and hash of the finalized header with given number, if it is
+						/// known to the bridge module.
+						fn finalized_header(number: BlockNumber) -> Option<bp_runtime::HeaderId<Hash, BlockNumber>>;
+						/// Returns true if the header with given hash is known to the bridge module, i.e. it
+						/// has been imported and not yet pruned, or it is a trusted checkpoint.
+						fn is_known_header(hash: Hash) -> bool;
+						/// Returns encoded justification of the recently imported header with given
+						/// hash, if it is still stored by the bridge module.
+						fn stored_justification(hash: Hash) -> Option<bp_runtime::EncodedJustification>;
+						/// Returns true if the given header may be submitted to the bridge module for free
+						/// right now. Relays may use it to select headers without estimating fees.
+						fn accepts_free_header(header: Header) -> bool;
+					}
+
+					/// API for querying operating mode of the module that is tracking finality of this chain.
+					///
+					/// This API is implemented by runtimes that are importing this chain headers using the
+					/// GRANDPA bridge module.
+					pub trait [<$chain:camel FinalityOperatingModeApi>] {
+						/// Returns operating mode of the bridge module and the best finalized header
+						/// number at the moment when the module has been halted.
+						fn operating_state() -> bp_runtime::FinalityOperatingState<BlockNumber>;
+					}
+
+					/// API for querying GRANDPA authority set of the module that is tracking finality
+					/// of this chain.
+					///
+					/// This API is implemented by runtimes that are importing this chain headers using the
+					/// GRANDPA bridge module.
+					pub trait [<$chain:camel SyncedHeadersGrandpaInfoApi>] {
+						/// Returns current GRANDPA authority set id, hash of the current authorities list
+						/// and the best finalized header, known to the bridge module.
+						fn synced_headers_grandpa_info() -> bp_runtime::SyncedHeadersGrandpaInfo<Hash, BlockNumber>;
 					}
 				}
 			}
@@ -258,7 +387,10 @@ macro_rules! decl_bridge_finality_runtime_apis {
 /// - constants that are stringified names of runtime API methods:
 ///     - `TO_<THIS_CHAIN>_ESTIMATE_MESSAGE_FEE_METHOD`
 ///     - `TO_<THIS_CHAIN>_MESSAGE_DETAILS_METHOD`
-///     - `FROM_<THIS_CHAIN>_MESSAGE_DETAILS_METHOD`,
+///     - `FROM_<THIS_CHAIN>_MESSAGE_DETAILS_METHOD`
+///     - `TO_<THIS_CHAIN>_OUTBOUND_LANE_STATE_METHOD`
+///     - `FROM_<THIS_CHAIN>_UNREWARDED_RELAYERS_SUMMARY_METHOD`
+///     - `FROM_<THIS_CHAIN>_INBOUND_LANE_STATE_METHOD`
 /// The name of the chain has to be specified in snake case (e.g. `rialto_parachain`).
 #[macro_export]
 macro_rules! decl_bridge_messages_runtime_apis {
@@ -275,16 +407,38 @@ macro_rules! decl_bridge_messages_runtime_apis {
 				pub const [<TO_ $chain:upper _MESSAGE_DETAILS_METHOD>]: &str =
 					stringify!([<To $chain:camel OutboundLaneApi_message_details>]);
 
+				/// Name of the `To<ThisChain>OutboundLaneApi::recent_relayers` runtime method.
+				pub const [<TO_ $chain:upper _RECENT_RELAYERS_METHOD>]: &str =
+					stringify!([<To $chain:camel OutboundLaneApi_recent_relayers>]);
+
+				/// Name of the `To<ThisChain>OutboundLaneApi::outbound_messages_allowance` runtime
+				/// method.
+				pub const [<TO_ $chain:upper _OUTBOUND_MESSAGES_ALLOWANCE_METHOD>]: &str =
+					stringify!([<To $chain:camel OutboundLaneApi_outbound_messages_allowance>]);
+
+				/// Name of the `To<ThisChain>OutboundLaneApi::outbound_lane_state` runtime method.
+				pub const [<TO_ $chain:upper _OUTBOUND_LANE_STATE_METHOD>]: &str =
+					stringify!([<To $chain:camel OutboundLaneApi_outbound_lane_state>]);
+
 				/// Name of the `From<ThisChain>InboundLaneApi::message_details` runtime method.
 				pub const [<FROM_ $chain:upper _MESSAGE_DETAILS_METHOD>]: &str =
 					stringify!([<From $chain:camel InboundLaneApi_message_details>]);
 
+				/// Name of the `From<ThisChain>InboundLaneApi::unrewarded_relayers_summary` runtime
+				/// method.
+				pub const [<FROM_ $chain:upper _UNREWARDED_RELAYERS_SUMMARY_METHOD>]: &str =
+					stringify!([<From $chain:camel InboundLaneApi_unrewarded_relayers_summary>]);
+
+				/// Name of the `From<ThisChain>InboundLaneApi::inbound_lane_state` runtime method.
+				pub const [<FROM_ $chain:upper _INBOUND_LANE_STATE_METHOD>]: &str =
+					stringify!([<From $chain:camel InboundLaneApi_inbound_lane_state>]);
+
 				sp_api::decl_runtime_apis! {
 					/// Outbound message lane API for messages that are sent to this chain.
 					///
 					/// This API is implemented by runtimes that are receiving messages from this chain, not by this
 					/// chain's runtime itself.
-					pub trait [<To $chain:camel OutboundLaneApi>]<OutboundMessageFee: Parameter, OutboundPayload: Parameter> {
+					pub trait [<To $chain:camel OutboundLaneApi>]<OutboundMessageFee: Parameter, OutboundPayload: Parameter, RelayerId: Parameter> {
 						/// Estimate message delivery and dispatch fee that needs to be paid by the sender on
 						/// this chain.
 						///
@@ -309,6 +463,20 @@ macro_rules! decl_bridge_messages_runtime_apis {
 							begin: MessageNonce,
 							end: MessageNonce,
 						) -> Vec<OutboundMessageDetails<OutboundMessageFee>>;
+						/// Returns relayers that have delivered messages of given lane within the recent
+						/// deliveries window, along with the number of messages they have delivered.
+						///
+						/// The vector may be used to monitor how many distinct relayers are serving the lane.
+						fn recent_relayers(lane: LaneId) -> Vec<(RelayerId, MessageNonce)>;
+						/// Returns number of messages that the sender may still send within the current
+						/// rate limit window.
+						///
+						/// Returns `None` if messages of the sender are not rate-limited.
+						fn outbound_messages_allowance(sender: RelayerId) -> Option<u32>;
+						/// Returns nonces of the outbound lane.
+						///
+						/// Relayers may use it instead of reading the lane data from the storage.
+						fn outbound_lane_state(lane: LaneId) -> bp_messages::OutboundLaneData;
 					}
 
 					/// Inbound message lane API for messages sent by this chain.
@@ -318,12 +486,21 @@ macro_rules! decl_bridge_messages_runtime_apis {
 					///
 					/// Entries of the resulting vector are matching entries of the `messages` vector. Entries of the
 					/// `messages` vector may (and need to) be read using `To<ThisChain>OutboundLaneApi::message_details`.
-					pub trait [<From $chain:camel InboundLaneApi>]<InboundMessageFee: Parameter> {
+					pub trait [<From $chain:camel InboundLaneApi>]<InboundMessageFee: Parameter, RelayerId: Parameter> {
 						/// Return details of given inbound messages.
 						fn message_details(
 							lane: LaneId,
 							messages: Vec<(MessagePayload, OutboundMessageDetails<InboundMessageFee>)>,
 						) -> Vec<InboundMessageDetails>;
+						/// Returns relayers that have delivered messages of given lane and are not yet
+						/// rewarded for that, along with the number of their unrewarded messages.
+						///
+						/// The vector is ordered by the relayer identifier.
+						fn unrewarded_relayers_summary(lane: LaneId) -> Vec<(RelayerId, MessageNonce)>;
+						/// Returns nonces of the inbound lane and the state of its unrewarded relayers.
+						///
+						/// Relayers may use it instead of reading the lane data from the storage.
+						fn inbound_lane_state(lane: LaneId) -> bp_messages::InboundLaneState;
 					}
 				}
 			}
@@ -333,6 +510,24 @@ macro_rules! decl_bridge_messages_runtime_apis {
 	}
 }
 
+/// Convenience macro that declares name of the conversion rate parameter, stored in this chain
+/// runtime and used to convert bridged chain tokens into this chain tokens:
+///     - `<BRIDGED_CHAIN>_TO_<THIS_CHAIN>_CONVERSION_RATE_PARAMETER_NAME`
+/// The value of the constant is `<BridgedChain>To<ThisChain>ConversionRate`, which must match the
+/// name of the `parameter_types!` storage item in this chain runtime.
+/// The names of the chains have to be specified in snake case (e.g. `rialto_parachain`).
+#[macro_export]
+macro_rules! decl_bridge_conversion_rate_params {
+	($bridged_chain: ident, $this_chain: ident) => {
+		bp_runtime::paste::item! {
+			/// Name of the bridged chain -> this chain conversion rate parameter, stored in this
+			/// chain runtime.
+			pub const [<$bridged_chain:upper _TO_ $this_chain:upper _CONVERSION_RATE_PARAMETER_NAME>]: &str =
+				stringify!([<$bridged_chain:camel To $this_chain:camel ConversionRate>]);
+		}
+	};
+}
+
 /// Convenience macro that declares bridge finality runtime apis, bridge messages runtime apis
 /// and related constants for a chain.
 /// The name of the chain has to be specified in snake case (e.g. `rialto_parachain`).
@@ -343,3 +538,70 @@ macro_rules! decl_bridge_runtime_apis {
 		bp_runtime::decl_bridge_messages_runtime_apis!($chain);
 	};
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::cell::Cell;
+
+	thread_local! {
+		static ENCODE_CALLS: Cell<usize> = Cell::new(0);
+		static DECODE_CALLS: Cell<usize> = Cell::new(0);
+	}
+
+	/// Call that counts how many times it has been encoded and decoded.
+	#[derive(Debug, Clone, PartialEq)]
+	struct CountingCall(u64);
+
+	impl Encode for CountingCall {
+		fn encode(&self) -> Vec<u8> {
+			ENCODE_CALLS.with(|calls| calls.set(calls.get() + 1));
+			self.0.encode()
+		}
+	}
+
+	impl Decode for CountingCall {
+		fn decode<I: codec::Input>(input: &mut I) -> Result<Self, codec::Error> {
+			DECODE_CALLS.with(|calls| calls.set(calls.get() + 1));
+			u64::decode(input).map(CountingCall)
+		}
+	}
+
+	fn codec_calls() -> (usize, usize) {
+		(ENCODE_CALLS.with(|calls| calls.get()), DECODE_CALLS.with(|calls| calls.get()))
+	}
+
+	#[test]
+	fn encoded_call_is_decoded_exactly_once() {
+		let mut call = EncodedOrDecodedCall::<CountingCall>::Encoded(42u64.encode());
+		assert_eq!(call.as_decoded().ok(), Some(&CountingCall(42)));
+		assert_eq!(call.as_decoded().ok(), Some(&CountingCall(42)));
+		assert_eq!(call.to_decoded().ok(), Some(CountingCall(42)));
+		assert_eq!(call.as_encoded(), &42u64.encode()[..]);
+		assert_eq!(call.encode(), 42u64.encode());
+		assert_eq!(call.into_decoded().ok(), Some(CountingCall(42)));
+		assert_eq!(codec_calls(), (0, 1));
+	}
+
+	#[test]
+	fn decoded_call_is_encoded_exactly_once() {
+		let mut call = EncodedOrDecodedCall::Decoded(CountingCall(42));
+		assert_eq!(call.as_encoded(), &42u64.encode()[..]);
+		assert_eq!(call.as_encoded(), &42u64.encode()[..]);
+		assert_eq!(call.encode(), 42u64.encode());
+		assert_eq!(call.as_decoded().ok(), Some(&CountingCall(42)));
+		assert_eq!(codec_calls(), (1, 0));
+	}
+
+	#[test]
+	fn undecodable_call_is_left_untouched() {
+		let mut call = EncodedOrDecodedCall::<CountingCall>::Encoded(vec![42]);
+		assert!(call.as_decoded().is_err());
+		assert_eq!(call, EncodedOrDecodedCall::Encoded(vec![42]));
+	}
+
+	#[test]
+	fn call_type_info_matches_chain_call_type_info() {
+		assert_eq!(EncodedOrDecodedCall::<u64>::type_info(), u64::type_info(),);
+	}
+}
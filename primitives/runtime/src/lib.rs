@@ -30,15 +30,15 @@ use sp_runtime::traits::{BadOrigin, Header as HeaderT};
 use sp_std::{convert::TryFrom, fmt::Debug, vec, vec::Vec};
 
 pub use chain::{
-	AccountIdOf, AccountPublicOf, BalanceOf, BlockNumberOf, Chain, EncodedOrDecodedCall, HashOf,
-	HasherOf, HeaderOf, IndexOf, SignatureOf, TransactionEraOf,
+	AccountIdOf, AccountPublicOf, BalanceOf, BlockNumberOf, Chain, EncodedJustification,
+	EncodedOrDecodedCall, HashOf, HasherOf, HeaderOf, IndexOf, SignatureOf, TransactionEraOf,
 };
 pub use frame_support::storage::storage_prefix as storage_value_final_key;
 use num_traits::{CheckedSub, One};
 use sp_runtime::transaction_validity::TransactionValidity;
 pub use storage_proof::{
-	record_all_keys as record_all_trie_keys, Error as StorageProofError,
-	ProofSize as StorageProofSize, StorageProofChecker,
+	deduplicate_storage_proof, record_all_keys as record_all_trie_keys, trim_storage_proof,
+	Error as StorageProofError, ProofSize as StorageProofSize, StorageProofChecker,
 };
 
 #[cfg(feature = "std")]
@@ -76,12 +76,54 @@ pub const ROCOCO_CHAIN_ID: ChainId = *b"roco";
 /// Bridge-with-Wococo instance id.
 pub const WOCOCO_CHAIN_ID: ChainId = *b"woco";
 
+/// Bridge-with-Westend instance id.
+pub const WESTEND_CHAIN_ID: ChainId = *b"wend";
+
+/// Bridge-with-Westmint instance id.
+pub const WESTMINT_CHAIN_ID: ChainId = *b"wmnt";
+
 /// Bridge-with-Pass3dt instance id.
 pub const PASS3DT_CHAIN_ID: ChainId = *b"p3dt";
 
 /// Bridge-with-PAss3d instance id.
 pub const PASS3D_CHAIN_ID: ChainId = *b"p3d_";
 
+/// Known chain ids along with their canonical display names.
+pub const KNOWN_CHAINS: &[(ChainId, &str)] = &[
+	(PASS3D_CHAIN_ID, "pass3d"),
+	(PASS3DT_CHAIN_ID, "pass3dt"),
+	(MILLAU_CHAIN_ID, "millau"),
+	(RIALTO_CHAIN_ID, "rialto"),
+	(RIALTO_PARACHAIN_CHAIN_ID, "rialto-parachain"),
+];
+
+/// Returns canonical display name of the known chain.
+pub fn chain_name(chain_id: ChainId) -> Option<&'static str> {
+	KNOWN_CHAINS.iter().find(|(id, _)| *id == chain_id).map(|(_, name)| *name)
+}
+
+/// Returns id of the known chain, given its canonical display name.
+pub fn chain_id_by_name(name: &str) -> Option<ChainId> {
+	KNOWN_CHAINS
+		.iter()
+		.find(|(_, known_name)| *known_name == name)
+		.map(|(id, _)| *id)
+}
+
+/// Chain id wrapper, that is formatted as the chain name, if the chain is known, or as raw
+/// chain id bytes otherwise.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct ChainIdName(pub ChainId);
+
+impl sp_std::fmt::Debug for ChainIdName {
+	fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		match chain_name(self.0) {
+			Some(name) => f.write_str(name),
+			None => write!(f, "{:?}", self.0),
+		}
+	}
+}
+
 /// Call-dispatch module prefix.
 pub const CALL_DISPATCH_MODULE_PREFIX: &[u8] = b"pallet-bridge/dispatch";
 
@@ -91,12 +133,37 @@ pub const ACCOUNT_DERIVATION_PREFIX: &[u8] = b"pallet-bridge/account-derivation/
 /// A unique prefix for entropy when generating a cross-chain account ID for the Root account.
 pub const ROOT_ACCOUNT_DERIVATION_PREFIX: &[u8] = b"pallet-bridge/account-derivation/root";
 
+/// A unique prefix for entropy when generating the bridge identifier.
+pub const BRIDGE_ID_DERIVATION_PREFIX: &[u8] = b"pallet-bridge/bridge-id";
+
+/// Prefix of the remark that is used to tag relay transactions with the bridge identifier.
+pub const BRIDGE_REMARK_PREFIX: &[u8] = b"pallet-bridge/remark";
+
 /// Generic header Id.
 #[derive(
 	RuntimeDebug, Default, Clone, Encode, Decode, Copy, Eq, Hash, PartialEq, PartialOrd, Ord,
 )]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 pub struct HeaderId<Hash, Number>(pub Number, pub Hash);
 
+impl<Hash: Debug, Number: sp_std::fmt::Display> sp_std::fmt::Display for HeaderId<Hash, Number> {
+	fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		write!(f, "#{} ({:?})", self.0, self.1)
+	}
+}
+
+impl<Hash, Number> From<(Number, Hash)> for HeaderId<Hash, Number> {
+	fn from((number, hash): (Number, Hash)) -> Self {
+		HeaderId(number, hash)
+	}
+}
+
+impl<Hash, Number> From<HeaderId<Hash, Number>> for (Number, Hash) {
+	fn from(id: HeaderId<Hash, Number>) -> Self {
+		(id.0, id.1)
+	}
+}
+
 /// Generic header id provider.
 pub trait HeaderIdProvider<Header: HeaderT> {
 	// Get the header id.
@@ -162,6 +229,63 @@ where
 	.into()
 }
 
+/// Deterministic identifier of the bridge.
+///
+/// The identifier is computed from identifiers of the source and target chains and the set of
+/// lanes, served by the bridge. So it only depends on the bridge configuration and indexers may
+/// use it to group on-chain activity of the same bridge, even if there are multiple bridges
+/// sharing the same chain.
+#[derive(Clone, Copy, Decode, Encode, Eq, MaxEncodedLen, PartialEq, RuntimeDebug, TypeInfo)]
+pub struct BridgeId(pub H256);
+
+impl BridgeId {
+	/// Compute identifier of the bridge between given chains, serving given lanes.
+	///
+	/// The order of lanes and lane duplicates don't affect the identifier.
+	pub fn new<Lane: Encode>(source_chain: ChainId, target_chain: ChainId, lanes: &[Lane]) -> Self {
+		let mut lanes = lanes.iter().map(Encode::encode).collect::<Vec<_>>();
+		lanes.sort();
+		lanes.dedup();
+
+		BridgeId(
+			(BRIDGE_ID_DERIVATION_PREFIX, source_chain, target_chain, lanes)
+				.using_encoded(blake2_256)
+				.into(),
+		)
+	}
+}
+
+/// Remark that is submitted by the relay to tag its transactions with the bridge identifier.
+#[derive(Clone, Decode, Encode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+pub struct BridgeRemark {
+	/// Identifier of the bridge, served by the relay.
+	pub bridge_id: BridgeId,
+	/// Version of the relay.
+	pub relay_version: Vec<u8>,
+}
+
+impl BridgeRemark {
+	/// Encode remark, so that it may be submitted using the `remark_with_event` call.
+	pub fn encode_remark(&self) -> Vec<u8> {
+		let mut remark = BRIDGE_REMARK_PREFIX.to_vec();
+		self.encode_to(&mut remark);
+		remark
+	}
+
+	/// Decode remark, previously encoded with `encode_remark`.
+	///
+	/// Returns `None` if the remark has not been submitted by the bridge relay.
+	pub fn decode_remark(remark: &[u8]) -> Option<Self> {
+		let mut encoded_remark = remark.strip_prefix(BRIDGE_REMARK_PREFIX)?;
+		let remark = Self::decode(&mut encoded_remark).ok()?;
+		if !encoded_remark.is_empty() {
+			return None
+		}
+
+		Some(remark)
+	}
+}
+
 /// Anything that has size.
 pub trait Size {
 	/// Return size of this object (in bytes).
@@ -189,6 +313,29 @@ impl Size for PreComputedSize {
 	}
 }
 
+/// Maximal mortality period of the transaction that is signed by relays.
+///
+/// Larger periods are quantized by the `sp_runtime::generic::Era`, so the birth block of the
+/// transaction differs from the block that has been used to sign the transaction.
+pub const MAX_MORTALITY_PERIOD: u32 = 1 << 12;
+
+/// Returns effective mortality period of the transaction, signed with given mortality period.
+///
+/// The `sp_runtime::generic::Era` rounds the period up to the power of two (and to at least 4
+/// blocks), so the transaction may stay alive longer than it has been requested.
+pub fn effective_mortality_period(mortality_period: u32) -> u32 {
+	mortality_period.next_power_of_two().max(4)
+}
+
+/// Error that is returned when transaction mortality period is invalid.
+#[derive(RuntimeDebug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidMortalityPeriod {
+	/// Mortality period is zero.
+	Zero,
+	/// Mortality period is larger than the `MAX_MORTALITY_PERIOD`.
+	TooLarge(u32),
+}
+
 /// Era of specific transaction.
 #[derive(RuntimeDebug, Clone, Copy, PartialEq)]
 pub enum TransactionEra<BlockNumber, BlockHash> {
@@ -209,6 +356,28 @@ impl<BlockNumber: Copy + Into<u64>, BlockHash: Copy> TransactionEra<BlockNumber,
 			.unwrap_or(TransactionEra::Immortal)
 	}
 
+	/// Prepare transaction era, based on mortality period and current best block number.
+	///
+	/// Unlike `new`, the mortality period is checked against `sp_runtime::generic::Era`
+	/// rounding rules. The resulting era has the effective mortality period (i.e. the
+	/// period, rounded up to the power of two) and the `best_block_id` is the birth block
+	/// of the transaction.
+	pub fn new_checked(
+		best_block_id: HeaderId<BlockHash, BlockNumber>,
+		mortality_period: Option<u32>,
+	) -> Result<Self, InvalidMortalityPeriod> {
+		match mortality_period {
+			Some(0) => Err(InvalidMortalityPeriod::Zero),
+			Some(mortality_period) if mortality_period > MAX_MORTALITY_PERIOD =>
+				Err(InvalidMortalityPeriod::TooLarge(mortality_period)),
+			Some(mortality_period) => Ok(TransactionEra::Mortal(
+				best_block_id,
+				effective_mortality_period(mortality_period),
+			)),
+			None => Ok(TransactionEra::Immortal),
+		}
+	}
+
 	/// Create new immortal transaction era.
 	pub fn immortal() -> Self {
 		TransactionEra::Immortal
@@ -357,17 +526,87 @@ pub trait StorageDoubleMapKeyProvider {
 	}
 }
 
+/// Can be use to access the runtime storage key of a `StorageNMap` with three keys.
+pub trait StorageTripleMapKeyProvider {
+	/// The name of the variable that holds the `StorageNMap`.
+	const MAP_NAME: &'static str;
+
+	/// The same as `StorageNMap::Key::Hasher` of the first key.
+	type Hasher1: StorageHasher;
+	/// The same as `StorageNMap::Key::Key` of the first key.
+	type Key1: FullCodec;
+	/// The same as `StorageNMap::Key::Hasher` of the second key.
+	type Hasher2: StorageHasher;
+	/// The same as `StorageNMap::Key::Key` of the second key.
+	type Key2: FullCodec;
+	/// The same as `StorageNMap::Key::Hasher` of the third key.
+	type Hasher3: StorageHasher;
+	/// The same as `StorageNMap::Key::Key` of the third key.
+	type Key3: FullCodec;
+	/// The same as `StorageNMap::Value`.
+	type Value: FullCodec;
+
+	/// This is a copy of the
+	/// `frame_support::storage::generator::StorageNMap::storage_n_map_final_key`.
+	///
+	/// We're using it because to call `storage_n_map_final_key` directly, we need access
+	/// to the runtime and pallet instance, which (sometimes) is impossible.
+	fn final_key(
+		pallet_prefix: &str,
+		key1: &Self::Key1,
+		key2: &Self::Key2,
+		key3: &Self::Key3,
+	) -> StorageKey {
+		let key1_hashed = Self::Hasher1::hash(&key1.encode());
+		let key2_hashed = Self::Hasher2::hash(&key2.encode());
+		let key3_hashed = Self::Hasher3::hash(&key3.encode());
+		let pallet_prefix_hashed = frame_support::Twox128::hash(pallet_prefix.as_bytes());
+		let storage_prefix_hashed = frame_support::Twox128::hash(Self::MAP_NAME.as_bytes());
+
+		let mut final_key = Vec::with_capacity(
+			pallet_prefix_hashed.len() +
+				storage_prefix_hashed.len() +
+				key1_hashed.as_ref().len() +
+				key2_hashed.as_ref().len() +
+				key3_hashed.as_ref().len(),
+		);
+
+		final_key.extend_from_slice(&pallet_prefix_hashed[..]);
+		final_key.extend_from_slice(&storage_prefix_hashed[..]);
+		final_key.extend_from_slice(key1_hashed.as_ref());
+		final_key.extend_from_slice(key2_hashed.as_ref());
+		final_key.extend_from_slice(key3_hashed.as_ref());
+
+		StorageKey(final_key)
+	}
+}
+
 /// Error generated by the `OwnedBridgeModule` trait.
 #[derive(Encode, Decode, TypeInfo, PalletError)]
 pub enum OwnedBridgeModuleError {
 	/// All pallet operations are halted.
 	Halted,
+	/// There's no pending owner proposal.
+	NoPendingOwner,
+	/// The caller is not the proposed (pending) owner.
+	NotPendingOwner,
+	/// The module can't be switched from its current operating mode to the requested one.
+	InvalidOperatingModeTransition,
 }
 
+/// Error returned when the operating mode of the bridge module can't be switched to the
+/// requested one.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub struct InvalidTransition;
+
 /// Operating mode for a bridge module.
 pub trait OperatingMode: Send + Copy + Debug + FullCodec {
 	// Returns true if the bridge module is halted.
 	fn is_halted(&self) -> bool;
+
+	/// Returns `Ok(())` if the bridge module may be switched from the `from` operating mode to
+	/// the `to` operating mode.
+	fn ensure_can_transition(from: Self, to: Self) -> Result<(), InvalidTransition>;
 }
 
 /// Basic operating modes for a bridges module (Normal/Halted).
@@ -390,14 +629,55 @@ impl OperatingMode for BasicOperatingMode {
 	fn is_halted(&self) -> bool {
 		*self == BasicOperatingMode::Halted
 	}
+
+	/// The module may always be halted or resumed.
+	fn ensure_can_transition(_from: Self, _to: Self) -> Result<(), InvalidTransition> {
+		Ok(())
+	}
+}
+
+/// Operating mode of the finality bridge module, along with the point where it has been halted.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct FinalityOperatingState<BlockNumber> {
+	/// Current operating mode of the module.
+	pub operating_mode: BasicOperatingMode,
+	/// Number of the best finalized bridged header at the moment when the module has been halted.
+	///
+	/// It is `None` if the module is not halted, or if it has been halted before importing any
+	/// headers.
+	pub frozen_at: Option<BlockNumber>,
+}
+
+/// GRANDPA authority set and best finalized header, known to the finality bridge module.
+///
+/// Relays may use it to check whether the justification may be accepted by the module, without
+/// reading (potentially large) authority set from the storage.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct SyncedHeadersGrandpaInfo<Hash, BlockNumber> {
+	/// Identifier of the current GRANDPA authority set.
+	pub set_id: u64,
+	/// Hash of the encoded list of current GRANDPA authorities.
+	pub authority_list_hash: H256,
+	/// Number and hash of the best finalized header.
+	///
+	/// It is `None` if the module has not been initialized yet.
+	pub best_finalized: Option<HeaderId<Hash, BlockNumber>>,
 }
 
-/// Bridge module that has owner and operating mode
+/// Bridge module that has owner and operating mode.
+///
+/// The trait provides all the owner and operating mode logic, shared by bridge pallets. Storage
+/// items and dispatchable calls are still declared by the pallet itself, because FRAME pallet
+/// macros don't expand storage items or calls that are generated by other macros. All bridge
+/// pallets are using the same names (`PalletOwner`, `PalletPendingOwner` and
+/// `PalletOperatingMode`), which allows them to share tests generated by the
+/// `bp_test_utils::generate_owned_bridge_module_tests` macro.
 pub trait OwnedBridgeModule<T: frame_system::Config> {
 	/// The target that will be used when publishing logs related to this module.
 	const LOG_TARGET: &'static str;
 
 	type OwnerStorage: StorageValue<T::AccountId, Query = Option<T::AccountId>>;
+	type PendingOwnerStorage: StorageValue<T::AccountId, Query = Option<T::AccountId>>;
 	type OperatingMode: OperatingMode;
 	type OperatingModeStorage: StorageValue<Self::OperatingMode, Query = Self::OperatingMode>;
 
@@ -426,8 +706,15 @@ pub trait OwnedBridgeModule<T: frame_system::Config> {
 	}
 
 	/// Change the owner of the module.
+	///
+	/// May only be called by root. Owners should use the two-step ownership transfer
+	/// (`propose_owner` + `accept_ownership`) instead.
 	fn set_owner(origin: T::Origin, maybe_owner: Option<T::AccountId>) -> DispatchResult {
-		Self::ensure_owner_or_root(origin)?;
+		match origin.into() {
+			Ok(RawOrigin::Root) => (),
+			_ => return Err(BadOrigin.into()),
+		}
+		Self::PendingOwnerStorage::kill();
 		match maybe_owner {
 			Some(owner) => {
 				Self::OwnerStorage::put(&owner);
@@ -442,15 +729,73 @@ pub trait OwnedBridgeModule<T: frame_system::Config> {
 		Ok(())
 	}
 
+	/// Propose new owner of the module.
+	///
+	/// The ownership is not transferred until the proposed account calls `accept_ownership`.
+	/// If there's already a pending proposal, it is replaced with the new one.
+	fn propose_owner(origin: T::Origin, new_owner: T::AccountId) -> DispatchResult {
+		Self::ensure_owner_or_root(origin)?;
+		Self::PendingOwnerStorage::put(&new_owner);
+		log::info!(target: Self::LOG_TARGET, "Proposed new pallet Owner: {:?}", new_owner);
+		Ok(())
+	}
+
+	/// Accept the module ownership, previously proposed by the current owner or root.
+	///
+	/// Returns previous and new owner of the module.
+	fn accept_ownership(
+		origin: T::Origin,
+	) -> Result<(Option<T::AccountId>, T::AccountId), OwnedBridgeModuleError> {
+		let pending_owner =
+			Self::PendingOwnerStorage::get().ok_or(OwnedBridgeModuleError::NoPendingOwner)?;
+		match origin.into() {
+			Ok(RawOrigin::Signed(ref signer)) if *signer == pending_owner => (),
+			_ => return Err(OwnedBridgeModuleError::NotPendingOwner),
+		}
+
+		let old_owner = Self::OwnerStorage::get();
+		Self::OwnerStorage::put(&pending_owner);
+		Self::PendingOwnerStorage::kill();
+		log::info!(target: Self::LOG_TARGET, "Setting pallet Owner to: {:?}", pending_owner);
+
+		Ok((old_owner, pending_owner))
+	}
+
+	/// Cancel pending ownership transfer.
+	///
+	/// Returns the account that has been proposed as the new owner, or `None` if there
+	/// has been no pending proposal.
+	fn cancel_pending_owner(origin: T::Origin) -> Result<Option<T::AccountId>, BadOrigin> {
+		Self::ensure_owner_or_root(origin)?;
+		let pending_owner = Self::PendingOwnerStorage::take();
+		if let Some(ref pending_owner) = pending_owner {
+			log::info!(
+				target: Self::LOG_TARGET,
+				"Cancelled pallet ownership transfer to: {:?}",
+				pending_owner,
+			);
+		}
+		Ok(pending_owner)
+	}
+
 	/// Halt or resume all/some module operations.
+	///
+	/// The caller must ensure that the origin is allowed to change the operating mode (see
+	/// `ensure_owner_or_root`). Returns the previous operating mode of the module.
 	fn set_operating_mode(
-		origin: T::Origin,
 		operating_mode: Self::OperatingMode,
-	) -> DispatchResult {
-		Self::ensure_owner_or_root(origin)?;
+	) -> Result<Self::OperatingMode, OwnedBridgeModuleError> {
+		let old_operating_mode = Self::OperatingModeStorage::get();
+		Self::OperatingMode::ensure_can_transition(old_operating_mode, operating_mode)
+			.map_err(|_| OwnedBridgeModuleError::InvalidOperatingModeTransition)?;
 		Self::OperatingModeStorage::put(operating_mode);
-		log::info!(target: Self::LOG_TARGET, "Setting operating mode to {:?}.", operating_mode);
-		Ok(())
+		log::info!(
+			target: Self::LOG_TARGET,
+			"Changing operating mode from {:?} to {:?}.",
+			old_operating_mode,
+			operating_mode,
+		);
+		Ok(old_operating_mode)
 	}
 }
 
@@ -463,6 +808,84 @@ pub trait FilterCall<Call> {
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use sp_runtime::traits::{BlakeTwo256, Hash as HashT};
+
+	#[test]
+	fn basic_operating_mode_transitions() {
+		use BasicOperatingMode::{Halted, Normal};
+
+		for (from, to) in [(Normal, Normal), (Normal, Halted), (Halted, Normal), (Halted, Halted)] {
+			assert_eq!(BasicOperatingMode::ensure_can_transition(from, to), Ok(()));
+		}
+	}
+
+	#[test]
+	fn known_chains_match_chain_id_constants() {
+		for (chain_id, name) in [
+			(PASS3D_CHAIN_ID, "pass3d"),
+			(PASS3DT_CHAIN_ID, "pass3dt"),
+			(MILLAU_CHAIN_ID, "millau"),
+			(RIALTO_CHAIN_ID, "rialto"),
+			(RIALTO_PARACHAIN_CHAIN_ID, "rialto-parachain"),
+		] {
+			assert_eq!(chain_name(chain_id), Some(name));
+			assert_eq!(chain_id_by_name(name), Some(chain_id));
+		}
+	}
+
+	#[test]
+	fn known_chains_have_unique_ids_and_names() {
+		for (index, (chain_id, name)) in KNOWN_CHAINS.iter().enumerate() {
+			assert!(KNOWN_CHAINS[index + 1..].iter().all(|(id, _)| id != chain_id));
+			assert!(KNOWN_CHAINS[index + 1..].iter().all(|(_, n)| n != name));
+		}
+	}
+
+	#[test]
+	fn chain_id_name_is_formatted() {
+		assert_eq!(format!("{:?}", ChainIdName(PASS3DT_CHAIN_ID)), "pass3dt");
+		assert_eq!(format!("{:?}", ChainIdName(*b"test")), "[116, 101, 115, 116]");
+	}
+
+	#[test]
+	fn transaction_era_new_checked_rejects_invalid_periods() {
+		let best_block_id = HeaderId(1_000_000u32, [42u8; 32]);
+		assert_eq!(
+			TransactionEra::new_checked(best_block_id, Some(0)),
+			Err(InvalidMortalityPeriod::Zero),
+		);
+		assert_eq!(
+			TransactionEra::new_checked(best_block_id, Some(MAX_MORTALITY_PERIOD + 1)),
+			Err(InvalidMortalityPeriod::TooLarge(MAX_MORTALITY_PERIOD + 1)),
+		);
+		assert_eq!(TransactionEra::new_checked(best_block_id, None), Ok(TransactionEra::Immortal));
+	}
+
+	#[test]
+	fn transaction_era_new_checked_matches_frame_era() {
+		for best_block_number in [0u32, 1, 99, 100, 1_000_000, 1_234_567] {
+			let best_block_id = HeaderId(best_block_number, [42u8; 32]);
+			for period in [1, 4, 5, 64, 100, 128, 1000, MAX_MORTALITY_PERIOD] {
+				let era = TransactionEra::new_checked(best_block_id, Some(period)).unwrap();
+				let frame_era = era.frame_era();
+
+				// this is what `CheckEra` computes when the transaction is validated
+				let current_block_number = best_block_number as u64 + 1;
+				assert_eq!(frame_era.birth(current_block_number), best_block_number as u64);
+				assert_eq!(
+					frame_era.death(current_block_number),
+					best_block_number as u64 + era.mortality_period().unwrap() as u64,
+				);
+				assert_eq!(
+					frame_era,
+					sp_runtime::generic::Era::mortal(period as _, best_block_number as _),
+				);
+			}
+		}
+
+		let era = TransactionEra::new_checked(HeaderId(1_000_000u32, [42u8; 32]), Some(100));
+		assert_eq!(era.unwrap().mortality_period(), Some(128));
+	}
 
 	#[test]
 	fn storage_parameter_key_works() {
@@ -472,6 +895,51 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn bridge_id_does_not_depend_on_lanes_order() {
+		assert_eq!(
+			BridgeId::new(PASS3D_CHAIN_ID, PASS3DT_CHAIN_ID, &[[0u8, 0, 0, 1], [0, 0, 0, 0]]),
+			BridgeId::new(
+				PASS3D_CHAIN_ID,
+				PASS3DT_CHAIN_ID,
+				&[[0u8, 0, 0, 0], [0, 0, 0, 1], [0, 0, 0, 0]]
+			),
+		);
+	}
+
+	#[test]
+	fn bridge_id_depends_on_bridge_configuration() {
+		let bridge_id = BridgeId::new(PASS3D_CHAIN_ID, PASS3DT_CHAIN_ID, &[[0u8, 0, 0, 0]]);
+		assert_ne!(bridge_id, BridgeId::new(PASS3DT_CHAIN_ID, PASS3D_CHAIN_ID, &[[0u8, 0, 0, 0]]));
+		assert_ne!(bridge_id, BridgeId::new(PASS3D_CHAIN_ID, MILLAU_CHAIN_ID, &[[0u8, 0, 0, 0]]));
+		assert_ne!(bridge_id, BridgeId::new(PASS3D_CHAIN_ID, PASS3DT_CHAIN_ID, &[[0u8, 0, 0, 1]]));
+	}
+
+	#[test]
+	fn bridge_remark_is_decoded() {
+		let remark = BridgeRemark {
+			bridge_id: BridgeId::new(PASS3D_CHAIN_ID, PASS3DT_CHAIN_ID, &[[0u8, 0, 0, 0]]),
+			relay_version: b"1.0.0".to_vec(),
+		};
+		let encoded_remark = remark.encode_remark();
+		assert!(encoded_remark.starts_with(BRIDGE_REMARK_PREFIX));
+		assert_eq!(BridgeRemark::decode_remark(&encoded_remark), Some(remark));
+	}
+
+	#[test]
+	fn unrelated_remark_is_not_decoded() {
+		let remark = BridgeRemark {
+			bridge_id: BridgeId::new(PASS3D_CHAIN_ID, PASS3DT_CHAIN_ID, &[[0u8, 0, 0, 0]]),
+			relay_version: b"1.0.0".to_vec(),
+		};
+		assert_eq!(BridgeRemark::decode_remark(b"Hello world!"), None);
+		assert_eq!(BridgeRemark::decode_remark(&remark.encode()), None);
+
+		let mut encoded_remark = remark.encode_remark();
+		encoded_remark.push(42);
+		assert_eq!(BridgeRemark::decode_remark(&encoded_remark), None);
+	}
+
 	#[test]
 	fn storage_value_key_works() {
 		assert_eq!(
@@ -484,4 +952,75 @@ mod tests {
 			),
 		);
 	}
+
+	#[test]
+	fn storage_triple_map_key_works() {
+		struct RelayerRewardsPrefix;
+
+		impl frame_support::traits::StorageInstance for RelayerRewardsPrefix {
+			fn pallet_prefix() -> &'static str {
+				"BridgeRelayers"
+			}
+
+			const STORAGE_PREFIX: &'static str = "RelayerRewards";
+		}
+
+		type RelayerRewards = frame_support::storage::types::StorageNMap<
+			RelayerRewardsPrefix,
+			(
+				frame_support::storage::types::Key<frame_support::Identity, [u8; 4]>,
+				frame_support::storage::types::Key<frame_support::Twox64Concat, ChainId>,
+				frame_support::storage::types::Key<frame_support::Blake2_128Concat, u64>,
+			),
+			u128,
+		>;
+
+		struct RelayerRewardsKeyProvider;
+
+		impl StorageTripleMapKeyProvider for RelayerRewardsKeyProvider {
+			const MAP_NAME: &'static str = "RelayerRewards";
+
+			type Hasher1 = frame_support::Identity;
+			type Key1 = [u8; 4];
+			type Hasher2 = frame_support::Twox64Concat;
+			type Key2 = ChainId;
+			type Hasher3 = frame_support::Blake2_128Concat;
+			type Key3 = u64;
+			type Value = u128;
+		}
+
+		assert_eq!(
+			RelayerRewardsKeyProvider::final_key(
+				"BridgeRelayers",
+				&[0, 0, 0, 1],
+				&PASS3DT_CHAIN_ID,
+				&42,
+			),
+			StorageKey(RelayerRewards::hashed_key_for(([0, 0, 0, 1], PASS3DT_CHAIN_ID, 42))),
+		);
+	}
+
+	#[test]
+	fn header_id_display_works() {
+		let hash = BlakeTwo256::hash(b"header");
+		assert_eq!(HeaderId(42u32, hash).to_string(), format!("#42 ({:?})", hash));
+	}
+
+	#[test]
+	fn header_id_serde_round_trip_works() {
+		let id = HeaderId(42u32, BlakeTwo256::hash(b"header"));
+		let serialized = serde_json::to_string(&id).unwrap();
+		assert_eq!(serde_json::from_str::<HeaderId<H256, u32>>(&serialized).unwrap(), id);
+
+		let ids = vec![id, HeaderId(43u32, BlakeTwo256::hash(b"next header"))];
+		let serialized = serde_json::to_string(&ids).unwrap();
+		assert_eq!(serde_json::from_str::<Vec<HeaderId<H256, u32>>>(&serialized).unwrap(), ids);
+	}
+
+	#[test]
+	fn header_id_tuple_conversions_work() {
+		let hash = BlakeTwo256::hash(b"header");
+		assert_eq!(HeaderId::from((42u32, hash)), HeaderId(42u32, hash));
+		assert_eq!(<(u32, H256)>::from(HeaderId(42u32, hash)), (42u32, hash));
+	}
 }
@@ -54,4 +54,26 @@ pub struct MessageDispatchResult {
 	/// configuration supports pay-dispatch-fee-at-target-chain option and message sender has
 	/// enabled this option.
 	pub dispatch_fee_paid_during_dispatch: bool,
+	/// Details of the message dispatch error, if dispatch has failed.
+	///
+	/// The field is appended to the end of the structure, so it doesn't change encoding of
+	/// other fields. It is only used to report dispatch errors in the messages pallet events
+	/// and is never relayed back to the source chain.
+	pub dispatch_error: Option<MessageDispatchError>,
+}
+
+/// Compact description of the message dispatch error.
+#[derive(Encode, Decode, RuntimeDebug, Clone, Copy, PartialEq, Eq, TypeInfo)]
+pub enum MessageDispatchError {
+	/// The dispatcher has failed to decode message payload.
+	UndecodablePayload,
+	/// Message has not been dispatched, because its dispatch weight exceeds the weight limit.
+	WeightLimitReached,
+	/// Message has been rejected by the dispatcher (e.g. by the XCM barrier).
+	Rejected,
+	/// Message dispatch has been explicitly trapped (e.g. by the XCM `Trap` instruction) with
+	/// given code.
+	Trapped(u64),
+	/// Message dispatch has failed with given dispatcher-specific error code.
+	Other(u8),
 }
@@ -16,10 +16,11 @@
 
 //! Logic for checking Substrate storage proofs.
 
+use crate::PreComputedSize;
 use codec::Decode;
 use hash_db::{HashDB, Hasher, EMPTY_PREFIX};
 use sp_runtime::RuntimeDebug;
-use sp_std::{boxed::Box, vec::Vec};
+use sp_std::{boxed::Box, collections::btree_set::BTreeSet, vec::Vec};
 use sp_trie::{
 	read_trie_value, LayoutV1, MemoryDB, Recorder, StorageProof, Trie, TrieConfiguration,
 	TrieDBBuilder, TrieError, TrieHash,
@@ -88,6 +89,62 @@ where
 	}
 }
 
+/// Removes exact-duplicate nodes from the raw storage proof.
+///
+/// Duplicate nodes are useless for proof verification, but they increase size of the proof and
+/// the weight of transactions that are verifying it. Order of remaining nodes is preserved.
+/// Returns the deduplicated proof along with its size.
+pub fn deduplicate_storage_proof(proof: Vec<Vec<u8>>) -> (Vec<Vec<u8>>, PreComputedSize) {
+	let mut known_nodes = BTreeSet::new();
+	let mut proof_size = 0usize;
+	let proof = proof
+		.into_iter()
+		.filter(|node| {
+			if known_nodes.contains(node) {
+				return false
+			}
+
+			known_nodes.insert(node.clone());
+			proof_size = proof_size.saturating_add(node.len());
+			true
+		})
+		.collect();
+	(proof, PreComputedSize(proof_size))
+}
+
+/// Removes nodes that are not required to read values of given keys from the storage proof.
+///
+/// The proof is verified against given state root and all nodes that are not accessed when
+/// reading values of given keys are dropped. This shall be used with the same set of keys that
+/// the proof verifier is reading, otherwise the verification will fail. Order of remaining nodes
+/// is preserved. Returns the trimmed proof along with its size.
+pub fn trim_storage_proof<H: Hasher>(
+	root: H::Out,
+	proof: StorageProof,
+	keys: impl IntoIterator<Item = impl AsRef<[u8]>>,
+) -> Result<(Vec<Vec<u8>>, PreComputedSize), Error> {
+	let db = proof.into_memory_db::<H>();
+	if !db.contains(&root, EMPTY_PREFIX) {
+		return Err(Error::StorageRootMismatch)
+	}
+
+	let mut recorder = Recorder::<LayoutV1<H>>::new();
+	{
+		// LayoutV1 or LayoutV0 is identical for proof that only read values.
+		let trie = TrieDBBuilder::<LayoutV1<H>>::new(&db, &root)
+			.with_recorder(&mut recorder)
+			.build();
+		for key in keys {
+			trie.get(key.as_ref()).map_err(|_| Error::StorageValueUnavailable)?;
+		}
+	}
+
+	// the same node may be accessed several times when reading different keys
+	Ok(deduplicate_storage_proof(
+		recorder.drain().into_iter().map(|record| record.data.to_vec()).collect(),
+	))
+}
+
 #[derive(Eq, RuntimeDebug, PartialEq)]
 pub enum Error {
 	StorageRootMismatch,
@@ -174,4 +231,79 @@ pub mod tests {
 			Some(Error::StorageRootMismatch)
 		);
 	}
+
+	#[test]
+	fn storage_proof_deduplication() {
+		let (root, proof) = craft_valid_storage_proof();
+		let nodes: Vec<Vec<u8>> = proof.iter_nodes().collect();
+		let nodes_size: usize = nodes.iter().map(Vec::len).sum();
+
+		// proof with duplicate nodes
+		let mut duplicated_nodes = nodes.clone();
+		duplicated_nodes.extend(nodes.iter().cloned());
+		duplicated_nodes.push(nodes[0].clone());
+
+		let (deduplicated_nodes, deduplicated_size) = deduplicate_storage_proof(duplicated_nodes);
+		assert_eq!(deduplicated_nodes, nodes);
+		assert_eq!(deduplicated_size.0, nodes_size);
+
+		// deduplicated proof is still valid
+		let checker = <StorageProofChecker<sp_core::Blake2Hasher>>::new(
+			root,
+			StorageProof::new(deduplicated_nodes),
+		)
+		.unwrap();
+		assert_eq!(checker.read_value(b"key1"), Ok(Some(b"value1".to_vec())));
+	}
+
+	#[test]
+	fn storage_proof_trimming() {
+		let (root, proof) = craft_valid_storage_proof();
+		let proof_size: usize = proof.iter_nodes().map(|node| node.len()).sum();
+
+		// trimmed proof only has nodes that are required to read given keys
+		let (trimmed_nodes, trimmed_size) =
+			trim_storage_proof::<sp_core::Blake2Hasher>(root, proof.clone(), [&b"key4"[..]])
+				.unwrap();
+		assert_eq!(trimmed_size.0, trimmed_nodes.iter().map(Vec::len).sum::<usize>());
+		assert!(trimmed_size.0 < proof_size);
+
+		// trimmed proof is still valid for given keys
+		let checker = <StorageProofChecker<sp_core::Blake2Hasher>>::new(
+			root,
+			StorageProof::new(trimmed_nodes),
+		)
+		.unwrap();
+		assert_eq!(checker.read_value(b"key4"), Ok(Some((42u64, 42u32, 42u16, 42u8).encode())));
+		assert_eq!(checker.read_value(b"key1"), Err(Error::StorageValueUnavailable));
+
+		// trimming with all proved keys keeps the proof as is
+		let (trimmed_nodes, _) = trim_storage_proof::<sp_core::Blake2Hasher>(
+			root,
+			proof.clone(),
+			[&b"key1"[..], &b"key2"[..], &b"key4"[..], &b"key22"[..]],
+		)
+		.unwrap();
+		assert_eq!(
+			trimmed_nodes.into_iter().collect::<BTreeSet<_>>(),
+			proof.iter_nodes().collect::<BTreeSet<_>>(),
+		);
+
+		// trimming fails if the key is missing from the proof
+		assert_eq!(
+			trim_storage_proof::<sp_core::Blake2Hasher>(root, proof.clone(), [&b"key11"[..]]).err(),
+			Some(Error::StorageValueUnavailable),
+		);
+
+		// trimming fails if the proof doesn't match the root
+		assert_eq!(
+			trim_storage_proof::<sp_core::Blake2Hasher>(
+				sp_core::H256::random(),
+				proof,
+				[&b"key1"[..]]
+			)
+			.err(),
+			Some(Error::StorageRootMismatch),
+		);
+	}
 }
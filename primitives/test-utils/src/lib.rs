@@ -212,11 +212,12 @@ pub fn header_id<H: HeaderT>(index: u8) -> (H::Hash, H::Number) {
 }
 
 #[macro_export]
-/// Adds methods for testing the `set_owner()` and `set_operating_mode()` for a pallet.
-/// Some values are hardcoded like:
+/// Adds methods for testing the `set_owner()`, ownership transfer and `set_operating_mode()` for
+/// a pallet. Some values are hardcoded like:
 /// - `run_test()`
 /// - `Pallet::<TestRuntime>`
 /// - `PalletOwner::<TestRuntime>`
+/// - `PalletPendingOwner::<TestRuntime>`
 /// - `PalletOperatingMode::<TestRuntime>`
 /// While this is not ideal, all the pallets use the same names, so it works for the moment.
 /// We can revisit this in the future if anything changes.
@@ -231,16 +232,156 @@ macro_rules! generate_owned_bridge_module_tests {
 				assert_ok!(Pallet::<TestRuntime>::set_owner(Origin::root(), Some(2)));
 				assert_eq!(PalletOwner::<TestRuntime>::get(), Some(2));
 
-				// The owner should be able to change the owner.
-				assert_ok!(Pallet::<TestRuntime>::set_owner(Origin::signed(2), Some(3)));
-				assert_eq!(PalletOwner::<TestRuntime>::get(), Some(3));
+				// The owner shouldn't be able to change the owner - it must use two-step transfer.
+				assert_noop!(
+					Pallet::<TestRuntime>::set_owner(Origin::signed(2), Some(3)),
+					DispatchError::BadOrigin
+				);
+				assert_eq!(PalletOwner::<TestRuntime>::get(), Some(2));
 
 				// Other users shouldn't be able to change the owner.
 				assert_noop!(
 					Pallet::<TestRuntime>::set_owner(Origin::signed(1), Some(4)),
 					DispatchError::BadOrigin
 				);
+				assert_eq!(PalletOwner::<TestRuntime>::get(), Some(2));
+
+				// The root should be able to change the owner, even if there's pending transfer.
+				PalletPendingOwner::<TestRuntime>::put(3);
+				assert_ok!(Pallet::<TestRuntime>::set_owner(Origin::root(), Some(4)));
+				assert_eq!(PalletOwner::<TestRuntime>::get(), Some(4));
+				assert_eq!(PalletPendingOwner::<TestRuntime>::get(), None);
+			});
+		}
+
+		#[test]
+		fn test_two_step_ownership_transfer() {
+			run_test(|| {
+				frame_system::Pallet::<TestRuntime>::set_block_number(1);
+				PalletOwner::<TestRuntime>::put(1);
+
+				// Other users shouldn't be able to propose the owner.
+				assert_noop!(
+					Pallet::<TestRuntime>::propose_owner(Origin::signed(2), 2),
+					DispatchError::BadOrigin
+				);
+
+				// The owner should be able to propose the new owner.
+				assert_ok!(Pallet::<TestRuntime>::propose_owner(Origin::signed(1), 2));
+				assert_eq!(PalletOwner::<TestRuntime>::get(), Some(1));
+				assert_eq!(PalletPendingOwner::<TestRuntime>::get(), Some(2));
+				frame_system::Pallet::<TestRuntime>::assert_last_event(
+					crate::Event::<TestRuntime>::OwnershipTransferProposed { pending_owner: 2 }
+						.into(),
+				);
+
+				// Other users shouldn't be able to accept the ownership.
+				assert_noop!(
+					Pallet::<TestRuntime>::accept_ownership(Origin::signed(1)),
+					crate::Error::<TestRuntime>::BridgeModule(
+						bp_runtime::OwnedBridgeModuleError::NotPendingOwner
+					)
+				);
+				assert_noop!(
+					Pallet::<TestRuntime>::accept_ownership(Origin::root()),
+					crate::Error::<TestRuntime>::BridgeModule(
+						bp_runtime::OwnedBridgeModuleError::NotPendingOwner
+					)
+				);
+
+				// The proposed owner should be able to accept the ownership.
+				assert_ok!(Pallet::<TestRuntime>::accept_ownership(Origin::signed(2)));
+				assert_eq!(PalletOwner::<TestRuntime>::get(), Some(2));
+				assert_eq!(PalletPendingOwner::<TestRuntime>::get(), None);
+				frame_system::Pallet::<TestRuntime>::assert_last_event(
+					crate::Event::<TestRuntime>::OwnershipTransferred {
+						old_owner: Some(1),
+						new_owner: 2,
+					}
+					.into(),
+				);
+
+				// The ownership can't be accepted twice.
+				assert_noop!(
+					Pallet::<TestRuntime>::accept_ownership(Origin::signed(2)),
+					crate::Error::<TestRuntime>::BridgeModule(
+						bp_runtime::OwnedBridgeModuleError::NoPendingOwner
+					)
+				);
+
+				// The root should be able to propose the new owner.
+				assert_ok!(Pallet::<TestRuntime>::propose_owner(Origin::root(), 3));
+				assert_eq!(PalletPendingOwner::<TestRuntime>::get(), Some(3));
+			});
+		}
+
+		#[test]
+		fn test_propose_owner_while_proposal_is_pending() {
+			run_test(|| {
+				PalletOwner::<TestRuntime>::put(1);
+
+				assert_ok!(Pallet::<TestRuntime>::propose_owner(Origin::signed(1), 2));
+				assert_eq!(PalletPendingOwner::<TestRuntime>::get(), Some(2));
+
+				// New proposal replaces the pending one.
+				assert_ok!(Pallet::<TestRuntime>::propose_owner(Origin::signed(1), 3));
+				assert_eq!(PalletPendingOwner::<TestRuntime>::get(), Some(3));
+
+				// Previously proposed account can't accept the ownership anymore.
+				assert_noop!(
+					Pallet::<TestRuntime>::accept_ownership(Origin::signed(2)),
+					crate::Error::<TestRuntime>::BridgeModule(
+						bp_runtime::OwnedBridgeModuleError::NotPendingOwner
+					)
+				);
+
+				assert_ok!(Pallet::<TestRuntime>::accept_ownership(Origin::signed(3)));
 				assert_eq!(PalletOwner::<TestRuntime>::get(), Some(3));
+				assert_eq!(PalletPendingOwner::<TestRuntime>::get(), None);
+			});
+		}
+
+		#[test]
+		fn test_cancel_pending_owner() {
+			run_test(|| {
+				frame_system::Pallet::<TestRuntime>::set_block_number(1);
+				PalletOwner::<TestRuntime>::put(1);
+
+				// There's nothing to cancel.
+				assert_noop!(
+					Pallet::<TestRuntime>::cancel_pending_owner(Origin::signed(1)),
+					crate::Error::<TestRuntime>::BridgeModule(
+						bp_runtime::OwnedBridgeModuleError::NoPendingOwner
+					)
+				);
+
+				assert_ok!(Pallet::<TestRuntime>::propose_owner(Origin::signed(1), 2));
+
+				// Other users (including the proposed owner) shouldn't be able to cancel transfer.
+				assert_noop!(
+					Pallet::<TestRuntime>::cancel_pending_owner(Origin::signed(2)),
+					DispatchError::BadOrigin
+				);
+
+				// The owner should be able to cancel transfer.
+				assert_ok!(Pallet::<TestRuntime>::cancel_pending_owner(Origin::signed(1)));
+				assert_eq!(PalletOwner::<TestRuntime>::get(), Some(1));
+				assert_eq!(PalletPendingOwner::<TestRuntime>::get(), None);
+				frame_system::Pallet::<TestRuntime>::assert_last_event(
+					crate::Event::<TestRuntime>::OwnershipTransferCancelled { pending_owner: 2 }
+						.into(),
+				);
+				assert_noop!(
+					Pallet::<TestRuntime>::accept_ownership(Origin::signed(2)),
+					crate::Error::<TestRuntime>::BridgeModule(
+						bp_runtime::OwnedBridgeModuleError::NoPendingOwner
+					)
+				);
+
+				// The root should be able to cancel transfer.
+				assert_ok!(Pallet::<TestRuntime>::propose_owner(Origin::signed(1), 3));
+				assert_ok!(Pallet::<TestRuntime>::cancel_pending_owner(Origin::root()));
+				assert_eq!(PalletPendingOwner::<TestRuntime>::get(), None);
 			});
 		}
 
@@ -90,6 +90,7 @@ impl CliEncodeMessage for Millau {
 }
 
 impl CliChain for Millau {
+	const ID: bp_runtime::ChainId = bp_runtime::MILLAU_CHAIN_ID;
 	const RUNTIME_VERSION: Option<RuntimeVersion> = Some(millau_runtime::VERSION);
 
 	type KeyPair = sp_core::sr25519::Pair;
@@ -16,7 +16,6 @@
 
 //! Millau-to-Rialto messages sync entrypoint.
 
-use messages_relay::relay_strategy::MixStrategy;
 use relay_millau_client::Millau;
 use relay_rialto_client::Rialto;
 use substrate_relay_helper::messages_lane::{
@@ -65,6 +64,4 @@ impl SubstrateMessageLane for MillauMessagesToRialto {
 
 	type TargetToSourceChainConversionRateUpdateBuilder =
 		MillauMessagesToRialtoUpdateConversionRateCallBuilder;
-
-	type RelayStrategy = MixStrategy;
 }
@@ -16,7 +16,6 @@
 
 //! Millau-to-RialtoParachain messages sync entrypoint.
 
-use messages_relay::relay_strategy::MixStrategy;
 use relay_millau_client::Millau;
 use relay_rialto_parachain_client::RialtoParachain;
 use substrate_relay_helper::messages_lane::{
@@ -70,6 +69,4 @@ impl SubstrateMessageLane for MillauMessagesToRialtoParachain {
 
 	type TargetToSourceChainConversionRateUpdateBuilder =
 		MillauMessagesToRialtoParachainUpdateConversionRateCallBuilder;
-
-	type RelayStrategy = MixStrategy;
 }
@@ -73,6 +73,7 @@ impl CliEncodeMessage for Pass3d {
 }
 
 impl CliChain for Pass3d {
+	const ID: bp_runtime::ChainId = bp_runtime::PASS3D_CHAIN_ID;
 	const RUNTIME_VERSION: Option<RuntimeVersion> = Some(pass3d_runtime::VERSION);
 
 	type KeyPair = sp_core::sr25519::Pair;
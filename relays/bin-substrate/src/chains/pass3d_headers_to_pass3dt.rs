@@ -55,4 +55,8 @@ impl MessagesCliBridge for Pass3dToPass3dtCliBridge {
 	const ESTIMATE_MESSAGE_FEE_METHOD: &'static str =
 		bp_pass3dt::TO_PASS3DT_ESTIMATE_MESSAGE_FEE_METHOD;
 	type MessagesLane = crate::chains::pass3d_messages_to_pass3dt::Pass3dMessagesToPass3dt;
+
+	fn default_lanes() -> Vec<bp_messages::LaneId> {
+		bp_pass3d::all_active_lanes().to_vec()
+	}
 }
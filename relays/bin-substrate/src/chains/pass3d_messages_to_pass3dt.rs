@@ -16,7 +16,6 @@
 
 //! Pass3d-to-Pass3dt messages sync entrypoint.
 
-use messages_relay::relay_strategy::MixStrategy;
 use relay_pass3dt_client::Pass3dt;
 use relay_pass3d_client::Pass3d;
 use substrate_relay_helper::messages_lane::{
@@ -65,6 +64,4 @@ impl SubstrateMessageLane for Pass3dMessagesToPass3dt {
 
 	type TargetToSourceChainConversionRateUpdateBuilder =
 		Pass3dMessagesToPass3dtUpdateConversionRateCallBuilder;
-
-	type RelayStrategy = MixStrategy;
 }
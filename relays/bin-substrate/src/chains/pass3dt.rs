@@ -73,6 +73,7 @@ impl CliEncodeMessage for Pass3dt {
 }
 
 impl CliChain for Pass3dt {
+	const ID: bp_runtime::ChainId = bp_runtime::PASS3DT_CHAIN_ID;
 	const RUNTIME_VERSION: Option<RuntimeVersion> = Some(pass3dt_runtime::VERSION);
 
 	type KeyPair = sp_core::sr25519::Pair;
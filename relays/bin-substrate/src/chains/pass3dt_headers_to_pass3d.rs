@@ -55,4 +55,8 @@ impl MessagesCliBridge for Pass3dtToPass3dCliBridge {
 	const ESTIMATE_MESSAGE_FEE_METHOD: &'static str =
 		bp_pass3d::TO_PASS3D_ESTIMATE_MESSAGE_FEE_METHOD;
 	type MessagesLane = crate::chains::pass3dt_messages_to_pass3d::Pass3dtMessagesToPass3d;
+
+	fn default_lanes() -> Vec<bp_messages::LaneId> {
+		bp_pass3dt::all_active_lanes().to_vec()
+	}
 }
@@ -16,7 +16,6 @@
 
 //! Pass3dt-to-Pass3d messages sync entrypoint.
 
-use messages_relay::relay_strategy::MixStrategy;
 use relay_pass3dt_client::Pass3dt;
 use relay_pass3d_client::Pass3d;
 use substrate_relay_helper::messages_lane::{
@@ -65,6 +64,4 @@ impl SubstrateMessageLane for Pass3dtMessagesToPass3d {
 
 	type TargetToSourceChainConversionRateUpdateBuilder =
 		Pass3dtMessagesToPass3dUpdateConversionRateCallBuilder;
-
-	type RelayStrategy = MixStrategy;
 }
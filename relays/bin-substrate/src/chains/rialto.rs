@@ -73,6 +73,7 @@ impl CliEncodeMessage for Rialto {
 }
 
 impl CliChain for Rialto {
+	const ID: bp_runtime::ChainId = bp_runtime::RIALTO_CHAIN_ID;
 	const RUNTIME_VERSION: Option<RuntimeVersion> = Some(rialto_runtime::VERSION);
 
 	type KeyPair = sp_core::sr25519::Pair;
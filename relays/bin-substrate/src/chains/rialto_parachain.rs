@@ -74,6 +74,7 @@ impl CliEncodeMessage for RialtoParachain {
 }
 
 impl CliChain for RialtoParachain {
+	const ID: bp_runtime::ChainId = bp_runtime::RIALTO_PARACHAIN_CHAIN_ID;
 	const RUNTIME_VERSION: Option<RuntimeVersion> = Some(rialto_parachain_runtime::VERSION);
 
 	type KeyPair = sp_core::sr25519::Pair;
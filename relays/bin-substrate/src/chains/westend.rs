@@ -21,6 +21,7 @@ use relay_westend_client::{Westend, Westmint};
 use sp_version::RuntimeVersion;
 
 impl CliChain for Westend {
+	const ID: bp_runtime::ChainId = bp_runtime::WESTEND_CHAIN_ID;
 	const RUNTIME_VERSION: Option<RuntimeVersion> = None;
 
 	type KeyPair = sp_core::sr25519::Pair;
@@ -35,6 +36,7 @@ impl CliChain for Westend {
 }
 
 impl CliChain for Westmint {
+	const ID: bp_runtime::ChainId = bp_runtime::WESTMINT_CHAIN_ID;
 	const RUNTIME_VERSION: Option<RuntimeVersion> = None;
 
 	type KeyPair = sp_core::sr25519::Pair;
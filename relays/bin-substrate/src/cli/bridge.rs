@@ -15,11 +15,16 @@
 // along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
 
 use crate::cli::CliChain;
-use messages_relay::relay_strategy::MixStrategy;
+use bp_messages::LaneId;
+use bp_runtime::{
+	chain_id_by_name, BridgeId, ChainId, MILLAU_CHAIN_ID, PASS3DT_CHAIN_ID, PASS3D_CHAIN_ID,
+	RIALTO_CHAIN_ID, RIALTO_PARACHAIN_CHAIN_ID,
+};
 use pallet_bridge_parachains::{RelayBlockHash, RelayBlockHasher, RelayBlockNumber};
 use parachains_relay::ParachainsPipeline;
 use relay_substrate_client::{AccountKeyPairOf, Chain, RelayChain, TransactionSignScheme};
-use strum::{EnumString, EnumVariantNames};
+use std::str::FromStr;
+use strum::{EnumString, EnumVariantNames, VariantNames};
 use substrate_relay_helper::{
 	finality::SubstrateFinalitySyncPipeline, messages_lane::SubstrateMessageLane,
 	parachains::SubstrateParachainsPipeline,
@@ -49,6 +54,27 @@ impl FullBridge {
 			Self::Pass3dToPass3dt => PASS3D_TO_PASS3DT_INDEX,
 		}
 	}
+
+	/// Return identifiers of the source and target chains of the bridge.
+	pub fn chain_ids(&self) -> (ChainId, ChainId) {
+		match self {
+			Self::MillauToRialto => (MILLAU_CHAIN_ID, RIALTO_CHAIN_ID),
+			Self::RialtoToMillau => (RIALTO_CHAIN_ID, MILLAU_CHAIN_ID),
+			Self::MillauToRialtoParachain => (MILLAU_CHAIN_ID, RIALTO_PARACHAIN_CHAIN_ID),
+			Self::RialtoParachainToMillau => (RIALTO_PARACHAIN_CHAIN_ID, MILLAU_CHAIN_ID),
+			Self::Pass3dtToPass3d => (PASS3DT_CHAIN_ID, PASS3D_CHAIN_ID),
+			Self::Pass3dToPass3dt => (PASS3D_CHAIN_ID, PASS3DT_CHAIN_ID),
+		}
+	}
+
+	/// Select the bridge by canonical names of its source and target chains.
+	pub fn from_chain_names(source: &str, target: &str) -> Option<Self> {
+		let chain_ids = (chain_id_by_name(source)?, chain_id_by_name(target)?);
+		Self::VARIANTS
+			.iter()
+			.filter_map(|variant| Self::from_str(variant).ok())
+			.find(|bridge| bridge.chain_ids() == chain_ids)
+	}
 }
 
 pub const RIALTO_TO_MILLAU_INDEX: u8 = 0;
@@ -67,6 +93,11 @@ pub trait CliBridgeBase: Sized {
 	type Target: Chain
 		+ TransactionSignScheme<Chain = Self::Target>
 		+ CliChain<KeyPair = AccountKeyPairOf<Self::Target>>;
+
+	/// Returns identifier of the bridge that serves given lanes.
+	fn bridge_id(lanes: &[LaneId]) -> BridgeId {
+		BridgeId::new(Self::Source::ID, Self::Target::ID, lanes)
+	}
 }
 
 /// Bridge representation that can be used from the CLI for relaying headers
@@ -114,6 +145,27 @@ pub trait MessagesCliBridge: CliBridgeBase {
 		TargetChain = Self::Target,
 		SourceTransactionSignScheme = Self::Source,
 		TargetTransactionSignScheme = Self::Target,
-		RelayStrategy = MixStrategy,
 	>;
+
+	/// Lanes that are served by the relay if no lanes are specified in the command line.
+	fn default_lanes() -> Vec<LaneId> {
+		vec![LaneId([0, 0, 0, 0])]
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn full_bridge_names_match_chain_names() {
+		for variant in FullBridge::VARIANTS {
+			let bridge = FullBridge::from_str(variant).unwrap();
+			let (source, target) = bridge.chain_ids();
+			let source = bp_runtime::chain_name(source).unwrap();
+			let target = bp_runtime::chain_name(target).unwrap();
+			assert_eq!(*variant, format!("{}-to-{}", source, target));
+			assert_eq!(FullBridge::from_chain_names(source, target), Some(bridge));
+		}
+	}
 }
@@ -0,0 +1,131 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+use async_trait::async_trait;
+use relay_substrate_client::{AccountIdOf, AccountKeyPairOf, ChainWithGrandpa};
+use sp_core::Pair;
+use structopt::StructOpt;
+use strum::VariantNames;
+
+use crate::chains::{
+	millau_headers_to_rialto::MillauToRialtoCliBridge,
+	millau_headers_to_rialto_parachain::MillauToRialtoParachainCliBridge,
+	pass3d_headers_to_pass3dt::Pass3dToPass3dtCliBridge,
+	pass3dt_headers_to_pass3d::Pass3dtToPass3dCliBridge,
+	rialto_headers_to_millau::RialtoToMillauCliBridge,
+	westend_headers_to_millau::WestendToMillauCliBridge,
+};
+use substrate_relay_helper::finality::{engine::Grandpa, SubstrateFinalitySyncPipeline};
+
+use crate::cli::{
+	bridge::*, chain_schema::*, relay_headers::RelayHeadersBridge, CallIndexCheckParams,
+};
+
+/// Submit all mandatory headers of the source chain up to given block, skipping all other
+/// headers.
+#[derive(StructOpt)]
+pub struct CatchUpHeaders {
+	/// A bridge instance to catch up headers for.
+	#[structopt(long, possible_values = RelayHeadersBridge::VARIANTS, case_insensitive = true)]
+	bridge: RelayHeadersBridge,
+	/// Number of the source chain block, up to which mandatory headers are submitted.
+	#[structopt(long)]
+	until: u32,
+	/// Maximal number of headers that are submitted at once.
+	#[structopt(long, default_value = "16")]
+	max_batch_size: usize,
+	#[structopt(flatten)]
+	source: SourceConnectionParams,
+	#[structopt(flatten)]
+	target: TargetConnectionParams,
+	#[structopt(flatten)]
+	target_sign: TargetSigningParams,
+	#[structopt(flatten)]
+	call_index_check_params: CallIndexCheckParams,
+}
+
+#[async_trait]
+trait HeadersCatchUp: RelayToRelayHeadersCliBridge
+where
+	Self::Source: ChainWithGrandpa,
+	Self::Finality: SubstrateFinalitySyncPipeline<FinalityEngine = Grandpa<Self::Source>>,
+	AccountIdOf<Self::Target>: From<<AccountKeyPairOf<Self::Target> as Pair>::Public>,
+{
+	/// Catch up headers.
+	async fn catch_up_headers(data: CatchUpHeaders) -> anyhow::Result<()> {
+		let source_client = data.source.into_client::<Self::Source>().await?;
+		let target_client = data.target.into_client::<Self::Target>().await?;
+		data.call_index_check_params.ensure_call_indices_match(&target_client).await?;
+
+		substrate_relay_helper::finality::catch_up::run::<Self::Finality>(
+			source_client,
+			target_client,
+			data.until.into(),
+			data.max_batch_size,
+			data.target_sign.transaction_params::<Self::Target>()?,
+		)
+		.await
+	}
+}
+
+impl HeadersCatchUp for MillauToRialtoCliBridge {}
+impl HeadersCatchUp for RialtoToMillauCliBridge {}
+impl HeadersCatchUp for WestendToMillauCliBridge {}
+impl HeadersCatchUp for MillauToRialtoParachainCliBridge {}
+impl HeadersCatchUp for Pass3dtToPass3dCliBridge {}
+impl HeadersCatchUp for Pass3dToPass3dtCliBridge {}
+
+impl CatchUpHeaders {
+	/// Run the command.
+	pub async fn run(self) -> anyhow::Result<()> {
+		match self.bridge {
+			RelayHeadersBridge::MillauToRialto => MillauToRialtoCliBridge::catch_up_headers(self),
+			RelayHeadersBridge::RialtoToMillau => RialtoToMillauCliBridge::catch_up_headers(self),
+			RelayHeadersBridge::WestendToMillau => WestendToMillauCliBridge::catch_up_headers(self),
+			RelayHeadersBridge::MillauToRialtoParachain =>
+				MillauToRialtoParachainCliBridge::catch_up_headers(self),
+			RelayHeadersBridge::Pass3dtToPass3d => Pass3dtToPass3dCliBridge::catch_up_headers(self),
+			RelayHeadersBridge::Pass3dToPass3dt => Pass3dToPass3dtCliBridge::catch_up_headers(self),
+		}
+		.await
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn should_parse_cli_options() {
+		let catch_up = CatchUpHeaders::from_iter(vec![
+			"catch-up-headers",
+			"--bridge",
+			"pass3d-to-pass3dt",
+			"--until",
+			"1000000",
+			"--source-port",
+			"9944",
+			"--target-port",
+			"9945",
+			"--target-signer",
+			"//Alice",
+		]);
+
+		assert!(matches!(catch_up.bridge, RelayHeadersBridge::Pass3dToPass3dt));
+		assert_eq!(catch_up.until, 1_000_000);
+		assert_eq!(catch_up.max_batch_size, 16);
+	}
+}
@@ -178,6 +178,9 @@ macro_rules! declare_chain_signing_params_cli_schema {
 				#[doc = "Transactions mortality period, in blocks. MUST be a power of two in [4; 65536] range. MAY NOT be larger than `BlockHashCount` parameter of the chain system module."]
 				#[structopt(long)]
 				pub [<$chain_prefix _transactions_mortality>]: Option<u32>,
+				#[doc = "SS58 address of the account on whose behalf transactions are submitted to the " $chain " node. If specified, the signer must be registered as a proxy of this account."]
+				#[structopt(long)]
+				pub [<$chain_prefix _real_account>]: Option<String>,
 			}
 
 			impl [<$chain SigningParams>] {
@@ -200,6 +203,31 @@ macro_rules! declare_chain_signing_params_cli_schema {
 						.transpose()
 				}
 
+				/// Return account on whose behalf transactions are submitted.
+				#[allow(dead_code)]
+				pub fn real_account<Chain: CliChain>(
+					&self,
+				) -> anyhow::Result<Option<<Chain::KeyPair as sp_core::crypto::Pair>::Public>> {
+					use sp_core::crypto::{ByteArray, Ss58Codec};
+
+					self.[<$chain_prefix _real_account>]
+						.as_ref()
+						.map(|real_account| {
+							let account_id = sp_core::crypto::AccountId32::from_ss58check(real_account)
+								.map_err(|e| anyhow::format_err!(
+									"Invalid real account address {}: {:?}",
+									real_account,
+									e,
+								))?;
+							ByteArray::from_slice(account_id.as_ref()).map_err(|_| anyhow::format_err!(
+								"Real account {} is not compatible with {} signer",
+								real_account,
+								Chain::NAME,
+							))
+						})
+						.transpose()
+				}
+
 				/// Parse signing params into chain-specific KeyPair.
 				#[allow(dead_code)]
 				pub fn to_keypair<Chain: CliChain>(&self) -> anyhow::Result<Chain::KeyPair> {
@@ -252,6 +280,7 @@ macro_rules! declare_chain_signing_params_cli_schema {
 					Ok(TransactionParams {
 						mortality: self.transactions_mortality()?,
 						signer: self.to_keypair::<Chain>()?,
+						real_account: self.real_account::<Chain>()?,
 					})
 				}
 			}
@@ -348,6 +377,7 @@ mod tests {
 				target_signer_password_file: None,
 
 				target_transactions_mortality: None,
+				target_real_account: None,
 			}
 			.to_keypair::<relay_rialto_client::Rialto>()
 			.map(|p| p.public())
@@ -365,6 +395,7 @@ mod tests {
 				target_signer_password_file: Some(password_file_path.clone()),
 
 				target_transactions_mortality: None,
+				target_real_account: None,
 			}
 			.to_keypair::<relay_rialto_client::Rialto>()
 			.map(|p| p.public())
@@ -382,6 +413,7 @@ mod tests {
 				target_signer_password_file: Some(password_file_path.clone()),
 
 				target_transactions_mortality: None,
+				target_real_account: None,
 			}
 			.to_keypair::<relay_rialto_client::Rialto>()
 			.map(|p| p.public())
@@ -399,6 +431,7 @@ mod tests {
 				target_signer_password_file: Some(password_file_path),
 
 				target_transactions_mortality: None,
+				target_real_account: None,
 			}
 			.to_keypair::<relay_rialto_client::Rialto>()
 			.map(|p| p.public())
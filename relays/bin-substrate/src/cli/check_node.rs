@@ -0,0 +1,501 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Check that the node exposes everything that the relay needs.
+
+use crate::{
+	chains::{
+		millau_headers_to_rialto::MillauToRialtoCliBridge,
+		millau_headers_to_rialto_parachain::MillauToRialtoParachainCliBridge,
+		pass3d_headers_to_pass3dt::Pass3dToPass3dtCliBridge,
+		pass3dt_headers_to_pass3d::Pass3dtToPass3dCliBridge,
+		rialto_headers_to_millau::RialtoToMillauCliBridge,
+		rialto_parachains_to_millau::RialtoParachainToMillauCliBridge,
+	},
+	cli::bridge::MessagesCliBridge,
+};
+
+use async_trait::async_trait;
+use bp_messages::{LaneId, MessageNonce, MessagePayload, OutboundMessageDetails};
+use codec::Encode;
+use futures::Future;
+use num_traits::Zero;
+use relay_substrate_client::{
+	BalanceOf, Chain, ChainRuntimeVersion, ChainWithGrandpa, ChainWithMessages, Client,
+	ConnectionParams,
+};
+use sp_core::Bytes;
+use sp_runtime::{generic::UncheckedExtrinsic, FixedU128};
+use std::{
+	sync::Arc,
+	time::{Duration, Instant},
+};
+use structopt::StructOpt;
+use strum::{EnumString, EnumVariantNames, VariantNames};
+
+/// Lane that is used in runtime API calls. The lane doesn't need to exist.
+const CHECK_LANE: LaneId = LaneId([0, 0, 0, 0]);
+
+/// Check that the node exposes all RPCs and runtime APIs that the relay needs.
+#[derive(StructOpt)]
+pub struct CheckNode {
+	/// Chain of the node that we're checking.
+	#[structopt(long, possible_values = CheckNodeChain::VARIANTS, case_insensitive = true)]
+	chain: CheckNodeChain,
+	/// Websocket URI of the node (e.g. `ws://127.0.0.1:9944`).
+	#[structopt(long)]
+	uri: String,
+	/// How long (in seconds) we wait for GRANDPA justification after subscribing.
+	#[structopt(long, default_value = "60")]
+	justification_timeout: u64,
+}
+
+/// Chain, which node may be checked.
+#[derive(Debug, PartialEq, Eq, EnumString, EnumVariantNames)]
+#[strum(serialize_all = "kebab_case")]
+pub enum CheckNodeChain {
+	Millau,
+	Rialto,
+	RialtoParachain,
+	Pass3d,
+	Pass3dt,
+}
+
+impl CheckNode {
+	/// Run the command.
+	pub async fn run(self) -> anyhow::Result<()> {
+		let params = parse_uri(&self.uri)?;
+		let justification_timeout = Duration::from_secs(self.justification_timeout);
+		let report = match self.chain {
+			CheckNodeChain::Millau =>
+				check_node::<relay_millau_client::Millau>(params, justification_timeout).await,
+			CheckNodeChain::Rialto =>
+				check_node::<relay_rialto_client::Rialto>(params, justification_timeout).await,
+			CheckNodeChain::RialtoParachain =>
+				check_node::<relay_rialto_parachain_client::RialtoParachain>(
+					params,
+					justification_timeout,
+				)
+				.await,
+			CheckNodeChain::Pass3d =>
+				check_node::<relay_pass3d_client::Pass3d>(params, justification_timeout).await,
+			CheckNodeChain::Pass3dt =>
+				check_node::<relay_pass3dt_client::Pass3dt>(params, justification_timeout).await,
+		};
+
+		report.print();
+
+		let failures = report.failures();
+		if failures != 0 {
+			return Err(anyhow::format_err!(
+				"{} of {} node checks have failed",
+				failures,
+				report.checks.len(),
+			))
+		}
+
+		Ok(())
+	}
+}
+
+/// Node checks that are specific to the chain.
+#[async_trait]
+trait ChainChecks: Chain {
+	/// Check runtime APIs and RPCs that the relay is using when working with this chain.
+	async fn check_chain(
+		client: &Client<Self>,
+		report: &mut CheckReport,
+		justification_timeout: Duration,
+	);
+}
+
+#[async_trait]
+impl ChainChecks for relay_millau_client::Millau {
+	async fn check_chain(
+		client: &Client<Self>,
+		report: &mut CheckReport,
+		justification_timeout: Duration,
+	) {
+		check_grandpa_justifications(client, report, justification_timeout).await;
+		check_messages_runtime_apis::<MillauToRialtoCliBridge>(client, report).await;
+		check_messages_runtime_apis::<MillauToRialtoParachainCliBridge>(client, report).await;
+	}
+}
+
+#[async_trait]
+impl ChainChecks for relay_rialto_client::Rialto {
+	async fn check_chain(
+		client: &Client<Self>,
+		report: &mut CheckReport,
+		justification_timeout: Duration,
+	) {
+		check_grandpa_justifications(client, report, justification_timeout).await;
+		check_messages_runtime_apis::<RialtoToMillauCliBridge>(client, report).await;
+	}
+}
+
+#[async_trait]
+impl ChainChecks for relay_rialto_parachain_client::RialtoParachain {
+	async fn check_chain(
+		client: &Client<Self>,
+		report: &mut CheckReport,
+		_justification_timeout: Duration,
+	) {
+		// parachain finality is relayed using relay chain GRANDPA justifications
+		check_messages_runtime_apis::<RialtoParachainToMillauCliBridge>(client, report).await;
+	}
+}
+
+#[async_trait]
+impl ChainChecks for relay_pass3d_client::Pass3d {
+	async fn check_chain(
+		client: &Client<Self>,
+		report: &mut CheckReport,
+		justification_timeout: Duration,
+	) {
+		check_grandpa_justifications(client, report, justification_timeout).await;
+		check_messages_runtime_apis::<Pass3dToPass3dtCliBridge>(client, report).await;
+	}
+}
+
+#[async_trait]
+impl ChainChecks for relay_pass3dt_client::Pass3dt {
+	async fn check_chain(
+		client: &Client<Self>,
+		report: &mut CheckReport,
+		justification_timeout: Duration,
+	) {
+		check_grandpa_justifications(client, report, justification_timeout).await;
+		check_messages_runtime_apis::<Pass3dtToPass3dCliBridge>(client, report).await;
+	}
+}
+
+/// Result of the single check.
+#[derive(Debug, PartialEq, Eq)]
+enum CheckStatus {
+	/// Check has passed.
+	Passed,
+	/// Check has failed with given error.
+	Failed(String),
+	/// Check has been skipped for given reason.
+	Skipped(&'static str),
+}
+
+/// Single check of the node.
+#[derive(Debug)]
+struct Check {
+	/// Check name.
+	name: String,
+	/// Check status.
+	status: CheckStatus,
+	/// Time that the check has taken.
+	duration: Duration,
+}
+
+/// Results of all node checks.
+#[derive(Debug, Default)]
+struct CheckReport {
+	/// All checks in the order they have been executed.
+	checks: Vec<Check>,
+}
+
+impl CheckReport {
+	/// Run the check and remember its result.
+	///
+	/// Returns the value, produced by the check, if it has passed.
+	async fn check<T, E: Into<anyhow::Error>>(
+		&mut self,
+		name: impl Into<String>,
+		future: impl Future<Output = Result<T, E>>,
+	) -> Option<T> {
+		let started_at = Instant::now();
+		let result = future.await.map_err(Into::into);
+		let duration = started_at.elapsed();
+		let (status, value) = match result {
+			Ok(value) => (CheckStatus::Passed, Some(value)),
+			Err(error) => (CheckStatus::Failed(format!("{:?}", error)), None),
+		};
+		self.checks.push(Check { name: name.into(), status, duration });
+		value
+	}
+
+	/// Remember that the check has been skipped.
+	fn skip(&mut self, name: impl Into<String>, reason: &'static str) {
+		self.checks.push(Check {
+			name: name.into(),
+			status: CheckStatus::Skipped(reason),
+			duration: Duration::default(),
+		});
+	}
+
+	/// Returns number of failed checks.
+	fn failures(&self) -> usize {
+		self.checks
+			.iter()
+			.filter(|check| matches!(check.status, CheckStatus::Failed(_)))
+			.count()
+	}
+
+	/// Print report to the stdout.
+	fn print(&self) {
+		println!("{:<80} {:<6} {:>10}", "Check", "Result", "Time");
+		for check in &self.checks {
+			let (result, details) = match check.status {
+				CheckStatus::Passed => ("PASS", None),
+				CheckStatus::Failed(ref error) => ("FAIL", Some(error.as_str())),
+				CheckStatus::Skipped(reason) => ("SKIP", Some(reason)),
+			};
+			println!("{:<80} {:<6} {:>8}ms", check.name, result, check.duration.as_millis());
+			if let Some(details) = details {
+				println!("    {}", details);
+			}
+		}
+	}
+}
+
+/// Run all checks of the node.
+async fn check_node<C: ChainChecks>(
+	params: ConnectionParams,
+	justification_timeout: Duration,
+) -> CheckReport {
+	let mut report = CheckReport::default();
+
+	let client = match report.check("connect", Client::<C>::try_connect(Arc::new(params))).await {
+		Some(client) => client,
+		None => return report,
+	};
+	report.check("sync state", client.ensure_synced()).await;
+	report.check("metadata", client.raw_metadata(None)).await;
+	report.check("genesis", check_genesis(&client)).await;
+	let best_finalized_hash =
+		report.check("best finalized header", client.best_finalized_header_hash()).await;
+	match best_finalized_hash {
+		Some(best_finalized_hash) => {
+			report
+				.check(
+					"storage proof",
+					client.prove_storage(
+						vec![bp_runtime::storage_value_key("System", "Number")],
+						best_finalized_hash,
+					),
+				)
+				.await;
+		},
+		None => report.skip("storage proof", "best finalized header is unknown"),
+	}
+	check_fee_rpc(&client, &mut report).await;
+	C::check_chain(&client, &mut report, justification_timeout).await;
+
+	report
+}
+
+/// Check that the node returns the same genesis hash that we have read when connecting.
+async fn check_genesis<C: Chain>(client: &Client<C>) -> anyhow::Result<()> {
+	let genesis_hash = client.block_hash_by_number(Zero::zero()).await?;
+	if genesis_hash != *client.genesis_hash() {
+		return Err(anyhow::format_err!(
+			"Node has returned different genesis hashes: {:?} and {:?}",
+			genesis_hash,
+			client.genesis_hash(),
+		))
+	}
+
+	Ok(())
+}
+
+/// Check that the node supports transaction fee RPCs.
+async fn check_fee_rpc<C: Chain>(client: &Client<C>, report: &mut CheckReport) {
+	const CHECK_NAME: &str = "fee RPC";
+
+	// we don't have a signer here, so we use unsigned transaction - it is enough to
+	// check that the RPC is supported
+	match C::remark_with_event_call(Vec::new()) {
+		Some(call) => {
+			let transaction = UncheckedExtrinsic::<(), C::Call, (), ()>::new_unsigned(call);
+			report
+				.check(CHECK_NAME, client.estimate_extrinsic_fee(Bytes(transaction.encode())))
+				.await;
+		},
+		None => report.skip(CHECK_NAME, "unable to build transaction for this chain"),
+	}
+}
+
+/// Check that the node supports GRANDPA justifications subscription.
+async fn check_grandpa_justifications<C: ChainWithGrandpa>(
+	client: &Client<C>,
+	report: &mut CheckReport,
+	justification_timeout: Duration,
+) {
+	report
+		.check(
+			"GRANDPA justifications subscription",
+			wait_grandpa_justification(client, justification_timeout),
+		)
+		.await;
+}
+
+/// Subscribe to GRANDPA justifications and wait for the first justification.
+async fn wait_grandpa_justification<C: ChainWithGrandpa>(
+	client: &Client<C>,
+	justification_timeout: Duration,
+) -> anyhow::Result<()> {
+	let subscription = client.subscribe_grandpa_justifications().await?;
+	match async_std::future::timeout(justification_timeout, subscription.next()).await {
+		Ok(Ok(Some(_))) => Ok(()),
+		Ok(Ok(None)) => Err(anyhow::format_err!("Subscription has been closed by the node")),
+		Ok(Err(error)) => Err(error.into()),
+		Err(_) => Err(anyhow::format_err!(
+			"No justifications have been received in {}s",
+			justification_timeout.as_secs(),
+		)),
+	}
+}
+
+/// Check that the node supports all runtime APIs that the messages relay is calling.
+///
+/// All methods are served by the source chain of the bridge, which node we're checking.
+async fn check_messages_runtime_apis<B>(client: &Client<B::Source>, report: &mut CheckReport)
+where
+	B: MessagesCliBridge,
+	B::Target: ChainWithMessages,
+{
+	check_runtime_api(client, report, B::Target::BEST_FINALIZED_HEADER_ID_METHOD, ()).await;
+	check_runtime_api(
+		client,
+		report,
+		B::Target::TO_CHAIN_MESSAGE_DETAILS_METHOD,
+		(CHECK_LANE, 1 as MessageNonce, 0 as MessageNonce),
+	)
+	.await;
+	check_runtime_api(client, report, B::Target::TO_CHAIN_RECENT_RELAYERS_METHOD, CHECK_LANE).await;
+	check_runtime_api(client, report, B::Target::TO_CHAIN_OUTBOUND_LANE_STATE_METHOD, CHECK_LANE)
+		.await;
+	check_runtime_api(client, report, B::Target::FROM_CHAIN_INBOUND_LANE_STATE_METHOD, CHECK_LANE)
+		.await;
+	check_runtime_api(
+		client,
+		report,
+		B::Target::FROM_CHAIN_MESSAGE_DETAILS_METHOD,
+		(CHECK_LANE, Vec::<(MessagePayload, OutboundMessageDetails<BalanceOf<B::Target>>)>::new()),
+	)
+	.await;
+	check_runtime_api(
+		client,
+		report,
+		B::ESTIMATE_MESSAGE_FEE_METHOD,
+		(CHECK_LANE, MessagePayload::new(), None::<FixedU128>),
+	)
+	.await;
+}
+
+/// Check that the node supports given runtime API method.
+async fn check_runtime_api<C: Chain>(
+	client: &Client<C>,
+	report: &mut CheckReport,
+	method: &str,
+	input: impl Encode,
+) {
+	report
+		.check(
+			format!("runtime API {}", method),
+			client.state_call(method.into(), Bytes(input.encode()), None),
+		)
+		.await;
+}
+
+/// Parse websocket URI of the node.
+fn parse_uri(uri: &str) -> anyhow::Result<ConnectionParams> {
+	let (secure, host_and_port) = if let Some(host_and_port) = uri.strip_prefix("wss://") {
+		(true, host_and_port)
+	} else if let Some(host_and_port) = uri.strip_prefix("ws://") {
+		(false, host_and_port)
+	} else {
+		return Err(anyhow::format_err!("Expected ws:// or wss:// URI, got: {}", uri))
+	};
+
+	let host_and_port = host_and_port.trim_end_matches('/');
+	if host_and_port.contains('/') {
+		return Err(anyhow::format_err!("URI paths are not supported: {}", uri))
+	}
+
+	let (host, port) = match host_and_port.rsplit_once(':') {
+		Some((host, port)) => (
+			host,
+			port.parse()
+				.map_err(|e| anyhow::format_err!("Invalid port in {}: {}", uri, e))?,
+		),
+		None => (host_and_port, if secure { 443 } else { 80 }),
+	};
+	if host.is_empty() {
+		return Err(anyhow::format_err!("Missing host in URI: {}", uri))
+	}
+
+	Ok(ConnectionParams {
+		host: host.into(),
+		port,
+		secure,
+		chain_runtime_version: ChainRuntimeVersion::Auto,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn should_parse_cli_options() {
+		let command = CheckNode::from_iter(vec![
+			"check-node",
+			"--chain",
+			"rialto-parachain",
+			"--uri",
+			"wss://rialto-parachain.example.com",
+		]);
+
+		assert_eq!(command.chain, CheckNodeChain::RialtoParachain);
+		assert_eq!(command.uri, "wss://rialto-parachain.example.com");
+		assert_eq!(command.justification_timeout, 60);
+	}
+
+	#[test]
+	fn parses_node_uri() {
+		let params = parse_uri("ws://127.0.0.1:9944").unwrap();
+		assert_eq!((params.host.as_str(), params.port, params.secure), ("127.0.0.1", 9944, false));
+
+		let params = parse_uri("wss://example.com/").unwrap();
+		assert_eq!((params.host.as_str(), params.port, params.secure), ("example.com", 443, true));
+
+		assert!(parse_uri("http://example.com").is_err());
+		assert!(parse_uri("ws://example.com:port").is_err());
+		assert!(parse_uri("ws://example.com/path").is_err());
+		assert!(parse_uri("ws://:9944").is_err());
+	}
+
+	#[test]
+	fn report_counts_failed_checks() {
+		let mut report = CheckReport::default();
+		async_std::task::block_on(async {
+			report.check("passed", async { Ok::<_, anyhow::Error>(()) }).await;
+			report
+				.check("failed", async { Err::<(), _>(anyhow::format_err!("error")) })
+				.await;
+		});
+		report.skip("skipped", "reason");
+
+		assert_eq!(report.checks.len(), 3);
+		assert_eq!(report.failures(), 1);
+	}
+}
@@ -27,10 +27,11 @@ use crate::{
 		bridge::{FullBridge, MessagesCliBridge},
 		chain_schema::*,
 		relay_headers_and_messages::CONVERSION_RATE_ALLOWED_DIFFERENCE_RATIO,
-		Balance, HexBytes, HexLaneId,
+		Balance, HexBytes,
 	},
 };
 use async_trait::async_trait;
+use bp_messages::LaneId;
 use bp_runtime::BalanceOf;
 use codec::{Decode, Encode};
 use relay_substrate_client::{Chain, ChainBase};
@@ -48,9 +49,10 @@ pub struct EstimateFee {
 	bridge: FullBridge,
 	#[structopt(flatten)]
 	source: SourceConnectionParams,
-	/// Hex-encoded id of lane that will be delivering the message.
+	/// Hex-encoded id of lane that will be delivering the message (e.g. `0x00000001`, or `0x1`
+	/// for short).
 	#[structopt(long, default_value = "00000000")]
-	lane: HexLaneId,
+	lane: LaneId,
 	/// A way to override conversion rate between bridge tokens.
 	///
 	/// If not specified, conversion rate from runtime storage is used. It may be obsolete and
@@ -92,7 +94,7 @@ where
 {
 	async fn estimate_fee(data: EstimateFee) -> anyhow::Result<()> {
 		let source_client = data.source.into_client::<Self::Source>().await?;
-		let lane = data.lane.into();
+		let lane = data.lane;
 		let payload =
 			crate::cli::encode_message::encode_message::<Self::Source, Self::Target>(&data.payload)
 				.map_err(|e| anyhow::format_err!("{:?}", e))?;
@@ -274,7 +276,7 @@ mod tests {
 			res,
 			EstimateFee {
 				bridge: FullBridge::RialtoToMillau,
-				lane: HexLaneId([0, 0, 0, 0]),
+				lane: LaneId([0, 0, 0, 0]),
 				conversion_rate_override: Some(ConversionRateOverride::Explicit(42.5)),
 				source: SourceConnectionParams {
 					source_host: "127.0.0.1".into(),
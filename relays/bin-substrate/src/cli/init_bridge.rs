@@ -25,9 +25,11 @@ use crate::{
 		pass3dt_headers_to_pass3d::Pass3dtToPass3dCliBridge,
 		pass3d_headers_to_pass3dt::Pass3dToPass3dtCliBridge,
 	},
-	cli::{bridge::CliBridgeBase, chain_schema::*},
+	cli::{bridge::CliBridgeBase, chain_schema::*, offline::TargetOfflineParams, HexBytes},
 };
-use bp_runtime::Chain as ChainBase;
+use bp_header_chain::{InitializationData, TrustedCheckpoint};
+use bp_runtime::{BlockNumberOf, Chain as ChainBase, HashOf, HeaderOf};
+use codec::Decode;
 use relay_substrate_client::{AccountKeyPairOf, Chain, SignParam, UnsignedTransaction};
 use sp_core::Pair;
 use structopt::StructOpt;
@@ -46,6 +48,95 @@ pub struct InitBridge {
 	target: TargetConnectionParams,
 	#[structopt(flatten)]
 	target_sign: TargetSigningParams,
+	#[structopt(flatten)]
+	offline: TargetOfflineParams,
+	/// Hex-encoded bridge initialization data.
+	///
+	/// Only used (and required) in offline mode, where it can't be read from the source node.
+	#[structopt(long)]
+	init_data: Option<HexBytes>,
+	/// Historical header of the source chain that is trusted by the bridge pallet, formatted as
+	/// `<number>:<hash>:<state_root>` (e.g. `42:0x1234..:0x5678..`).
+	///
+	/// Messages, anchored at checkpoint headers, may be verified by the target chain even if
+	/// the bridge is initialized with a later header. May be specified multiple times.
+	#[structopt(long = "checkpoint", number_of_values = 1)]
+	checkpoints: Vec<HexCheckpoint>,
+	/// Re-initialize already initialized bridge pallet.
+	///
+	/// May be used to recover the bridge that is stuck at the stale authority set. The
+	/// transaction must be signed by the bridge pallet owner.
+	#[structopt(long)]
+	reinit: bool,
+}
+
+/// Trusted checkpoint header, formatted as `<number>:<hash>:<state_root>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HexCheckpoint {
+	/// Number of the checkpoint header.
+	pub number: u64,
+	/// Hash of the checkpoint header.
+	pub hash: Vec<u8>,
+	/// State root of the checkpoint header.
+	pub state_root: Vec<u8>,
+}
+
+impl HexCheckpoint {
+	/// Convert checkpoint into the chain-specific checkpoint, checking hash sizes.
+	fn into_checkpoint<C: ChainBase>(
+		self,
+	) -> anyhow::Result<TrustedCheckpoint<BlockNumberOf<C>, HashOf<C>>> {
+		Ok(TrustedCheckpoint {
+			number: self.number.try_into().map_err(|_| {
+				anyhow::format_err!("Invalid checkpoint header number: {}", self.number)
+			})?,
+			hash: decode_checkpoint_hash::<C>("hash", &self.hash)?,
+			state_root: decode_checkpoint_hash::<C>("state root", &self.state_root)?,
+		})
+	}
+}
+
+impl std::str::FromStr for HexCheckpoint {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let mut parts = s.split(':');
+		let (number, hash, state_root) = match (parts.next(), parts.next(), parts.next()) {
+			(Some(number), Some(hash), Some(state_root)) if parts.next().is_none() =>
+				(number, hash, state_root),
+			_ => return Err(format!("Expected <number>:<hash>:<state_root>, got: {}", s)),
+		};
+
+		let number = number
+			.parse::<u64>()
+			.map_err(|e| format!("Invalid checkpoint header number {}: {}", number, e))?;
+		let decode_hex = |name: &str, value: &str| {
+			hex::decode(value.trim_start_matches("0x"))
+				.ok()
+				.filter(|bytes| !bytes.is_empty())
+				.ok_or_else(|| format!("Invalid checkpoint header {}: {}", name, value))
+		};
+		Ok(HexCheckpoint {
+			number,
+			hash: decode_hex("hash", hash)?,
+			state_root: decode_hex("state root", state_root)?,
+		})
+	}
+}
+
+/// Decode hash of the chain `C` from raw bytes, checking that it has expected size.
+fn decode_checkpoint_hash<C: ChainBase>(name: &str, bytes: &[u8]) -> anyhow::Result<HashOf<C>> {
+	let mut hash = HashOf::<C>::default();
+	if hash.as_ref().len() != bytes.len() {
+		return Err(anyhow::format_err!(
+			"Invalid size of checkpoint header {}: expected {} bytes, got {}",
+			name,
+			hash.as_ref().len(),
+			bytes.len(),
+		))
+	}
+	hash.as_mut().copy_from_slice(bytes);
+	Ok(hash)
 }
 
 #[derive(Debug, EnumString, EnumVariantNames)]
@@ -65,18 +156,51 @@ trait BridgeInitializer: CliBridgeBase
 where
 	<Self::Target as ChainBase>::AccountId: From<<AccountKeyPairOf<Self::Target> as Pair>::Public>,
 {
-	type Engine: Engine<Self::Source>;
+	type Engine: Engine<
+		Self::Source,
+		InitializationData = InitializationData<HeaderOf<Self::Source>>,
+	>;
 
 	/// Get the encoded call to init the bridge.
 	fn encode_init_bridge(
 		init_data: <Self::Engine as Engine<Self::Source>>::InitializationData,
 	) -> <Self::Target as Chain>::Call;
 
+	/// Get the encoded call to re-initialize the bridge.
+	fn encode_reinit_bridge(
+		init_data: <Self::Engine as Engine<Self::Source>>::InitializationData,
+	) -> <Self::Target as Chain>::Call;
+
+	/// Get the encoded call to init or to re-initialize the bridge.
+	fn encode_init_or_reinit_bridge(
+		init_data: <Self::Engine as Engine<Self::Source>>::InitializationData,
+		reinit: bool,
+	) -> <Self::Target as Chain>::Call {
+		if reinit {
+			Self::encode_reinit_bridge(init_data)
+		} else {
+			Self::encode_init_bridge(init_data)
+		}
+	}
+
 	/// Initialize the bridge.
-	async fn init_bridge(data: InitBridge) -> anyhow::Result<()> {
+	async fn init_bridge(data: InitBridge) -> anyhow::Result<()>
+	where
+		<Self::Engine as Engine<Self::Source>>::InitializationData: Decode,
+	{
+		if data.offline.offline {
+			return Self::init_bridge_offline(data)
+		}
+
+		let checkpoints = data
+			.checkpoints
+			.into_iter()
+			.map(HexCheckpoint::into_checkpoint::<Self::Source>)
+			.collect::<anyhow::Result<Vec<_>>>()?;
 		let source_client = data.source.into_client::<Self::Source>().await?;
 		let target_client = data.target.into_client::<Self::Target>().await?;
 		let target_sign = data.target_sign.to_keypair::<Self::Target>()?;
+		let reinit = data.reinit;
 
 		let (spec_version, transaction_version) = target_client.simple_runtime_version().await?;
 		substrate_relay_helper::finality::initialize::initialize::<Self::Engine, _, _, _>(
@@ -89,9 +213,11 @@ where
 				genesis_hash: *target_client.genesis_hash(),
 				signer: target_sign,
 			},
-			move |transaction_nonce, initialization_data| {
+			reinit,
+			move |transaction_nonce, mut initialization_data| {
+				initialization_data.checkpoints.extend(checkpoints);
 				Ok(UnsignedTransaction::new(
-					Self::encode_init_bridge(initialization_data).into(),
+					Self::encode_init_or_reinit_bridge(initialization_data, reinit).into(),
 					transaction_nonce,
 				))
 			},
@@ -100,6 +226,29 @@ where
 
 		Ok(())
 	}
+
+	/// Construct the bridge initialization transaction without connecting to nodes.
+	fn init_bridge_offline(data: InitBridge) -> anyhow::Result<()>
+	where
+		<Self::Engine as Engine<Self::Source>>::InitializationData: Decode,
+	{
+		let init_data = data.init_data.ok_or_else(|| {
+			anyhow::format_err!("The 'init-data' argument is required in offline mode")
+		})?;
+		let mut init_data: InitializationData<HeaderOf<Self::Source>> =
+			Decode::decode(&mut &init_data.0[..])
+				.map_err(|e| anyhow::format_err!("Invalid 'init-data' argument: {:?}", e))?;
+		for checkpoint in data.checkpoints {
+			init_data.checkpoints.push(checkpoint.into_checkpoint::<Self::Source>()?);
+		}
+		data.offline
+			.build_transaction::<Self::Target>(
+				data.target.target_runtime_version,
+				&data.target_sign,
+				Self::encode_init_or_reinit_bridge(init_data, data.reinit),
+			)?
+			.print()
+	}
 }
 
 impl BridgeInitializer for MillauToRialtoCliBridge {
@@ -113,6 +262,12 @@ impl BridgeInitializer for MillauToRialtoCliBridge {
 		}
 		.into()
 	}
+
+	fn encode_reinit_bridge(
+		init_data: <Self::Engine as Engine<Self::Source>>::InitializationData,
+	) -> <Self::Target as Chain>::Call {
+		rialto_runtime::BridgeGrandpaCall::reinitialize { init_data }.into()
+	}
 }
 
 impl BridgeInitializer for MillauToRialtoParachainCliBridge {
@@ -129,6 +284,18 @@ impl BridgeInitializer for MillauToRialtoParachainCliBridge {
 		};
 		rialto_parachain_runtime::SudoCall::sudo { call: Box::new(initialize_call.into()) }.into()
 	}
+
+	fn encode_reinit_bridge(
+		init_data: <Self::Engine as Engine<Self::Source>>::InitializationData,
+	) -> <Self::Target as Chain>::Call {
+		rialto_parachain_runtime::BridgeGrandpaCall::<
+			rialto_parachain_runtime::Runtime,
+			rialto_parachain_runtime::MillauGrandpaInstance,
+		>::reinitialize {
+			init_data,
+		}
+		.into()
+	}
 }
 
 impl BridgeInitializer for RialtoToMillauCliBridge {
@@ -145,6 +312,18 @@ impl BridgeInitializer for RialtoToMillauCliBridge {
 		};
 		millau_runtime::SudoCall::sudo { call: Box::new(initialize_call.into()) }.into()
 	}
+
+	fn encode_reinit_bridge(
+		init_data: <Self::Engine as Engine<Self::Source>>::InitializationData,
+	) -> <Self::Target as Chain>::Call {
+		millau_runtime::BridgeGrandpaCall::<
+			millau_runtime::Runtime,
+			millau_runtime::RialtoGrandpaInstance,
+		>::reinitialize {
+			init_data,
+		}
+		.into()
+	}
 }
 
 impl BridgeInitializer for WestendToMillauCliBridge {
@@ -165,6 +344,18 @@ impl BridgeInitializer for WestendToMillauCliBridge {
 		}
 		.into()
 	}
+
+	fn encode_reinit_bridge(
+		init_data: <Self::Engine as Engine<Self::Source>>::InitializationData,
+	) -> <Self::Target as Chain>::Call {
+		millau_runtime::BridgeGrandpaCall::<
+			millau_runtime::Runtime,
+			millau_runtime::WestendGrandpaInstance,
+		>::reinitialize {
+			init_data,
+		}
+		.into()
+	}
 }
 
 impl BridgeInitializer for Pass3dtToPass3dCliBridge {
@@ -178,6 +369,12 @@ impl BridgeInitializer for Pass3dtToPass3dCliBridge {
 		}
 			.into()
 	}
+
+	fn encode_reinit_bridge(
+		init_data: <Self::Engine as Engine<Self::Source>>::InitializationData,
+	) -> <Self::Target as Chain>::Call {
+		pass3d_runtime::BridgeGrandpaCall::reinitialize { init_data }.into()
+	}
 }
 
 impl BridgeInitializer for Pass3dToPass3dtCliBridge {
@@ -191,6 +388,12 @@ impl BridgeInitializer for Pass3dToPass3dtCliBridge {
 		}
 			.into()
 	}
+
+	fn encode_reinit_bridge(
+		init_data: <Self::Engine as Engine<Self::Source>>::InitializationData,
+	) -> <Self::Target as Chain>::Call {
+		pass3dt_runtime::BridgeGrandpaCall::reinitialize { init_data }.into()
+	}
 }
 
 impl InitBridge {
@@ -208,3 +411,64 @@ impl InitBridge {
 		.await
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn hex_checkpoint_is_parsed() {
+		assert_eq!(
+			"42:0x0102:0304".parse::<HexCheckpoint>(),
+			Ok(HexCheckpoint { number: 42, hash: vec![1, 2], state_root: vec![3, 4] }),
+		);
+	}
+
+	#[test]
+	fn malformed_hex_checkpoint_is_rejected() {
+		assert!("42:0x0102".parse::<HexCheckpoint>().is_err());
+		assert!("42:0x0102:0x0304:0x05".parse::<HexCheckpoint>().is_err());
+		assert!("x:0x0102:0x0304".parse::<HexCheckpoint>().is_err());
+		assert!("42:0xzz:0x0304".parse::<HexCheckpoint>().is_err());
+		assert!("42:0x0102:".parse::<HexCheckpoint>().is_err());
+	}
+
+	#[test]
+	fn hex_checkpoint_with_wrong_hash_size_is_rejected() {
+		let checkpoint = HexCheckpoint { number: 42, hash: vec![1; 32], state_root: vec![2; 32] };
+		assert!(checkpoint.clone().into_checkpoint::<relay_millau_client::Millau>().is_ok());
+
+		let checkpoint = HexCheckpoint { state_root: vec![2; 31], ..checkpoint };
+		assert!(checkpoint.into_checkpoint::<relay_millau_client::Millau>().is_err());
+	}
+
+	#[test]
+	fn reinit_flag_selects_reinitialize_call() {
+		use sp_runtime::traits::Header as HeaderT;
+
+		let init_data = || InitializationData {
+			header: Box::new(bp_pass3d::Header::new(
+				42,
+				Default::default(),
+				Default::default(),
+				Default::default(),
+				Default::default(),
+			)),
+			authority_list: vec![],
+			set_id: 1,
+			operating_mode: bp_runtime::BasicOperatingMode::Normal,
+			checkpoints: vec![],
+		};
+
+		assert!(matches!(
+			Pass3dToPass3dtCliBridge::encode_init_or_reinit_bridge(init_data(), false),
+			pass3dt_runtime::Call::Sudo(_),
+		));
+		assert!(matches!(
+			Pass3dToPass3dtCliBridge::encode_init_or_reinit_bridge(init_data(), true),
+			pass3dt_runtime::Call::BridgePass3dGrandpa(
+				pass3dt_runtime::BridgeGrandpaCall::reinitialize { .. }
+			),
+		));
+	}
+}
@@ -29,14 +29,19 @@ pub(crate) mod encode_message;
 pub(crate) mod estimate_fee;
 pub(crate) mod send_message;
 
+mod catch_up_headers;
 mod chain_schema;
+mod check_node;
 mod init_bridge;
+mod offline;
 mod register_parachain;
 mod relay_headers;
 mod relay_headers_and_messages;
 mod relay_messages;
 mod relay_parachains;
 mod resubmit_transactions;
+mod startup_report;
+mod submit_raw;
 
 /// Parse relay CLI args.
 pub fn parse_args() -> Command {
@@ -52,6 +57,13 @@ pub enum Command {
 	/// The on-chain bridge component should have been already initialized with
 	/// `init-bridge` sub-command.
 	RelayHeaders(relay_headers::RelayHeaders),
+	/// Submit all mandatory headers of the source chain up to given block.
+	///
+	/// Only headers that are changing GRANDPA authorities set are submitted, so it is much faster
+	/// than regular headers relay when the bridge pallet has been initialized with some old
+	/// header. If interrupted, the command may be restarted to resume from the best header, known
+	/// to the target chain.
+	CatchUpHeaders(catch_up_headers::CatchUpHeaders),
 	/// Start messages relay between two chains.
 	///
 	/// Ties up to `Messages` pallets on both chains and starts relaying messages.
@@ -67,7 +79,11 @@ pub enum Command {
 	/// Initialize on-chain bridge pallet with current header data.
 	///
 	/// Sends initialization transaction to bootstrap the bridge with current finalized block data.
+	/// In offline mode, the transaction is only constructed (and optionally signed) without
+	/// connecting to any node.
 	InitBridge(init_bridge::InitBridge),
+	/// Submit extrinsic that has been signed elsewhere and wait until it is finalized.
+	SubmitRaw(submit_raw::SubmitRaw),
 	/// Send custom message over the bridge.
 	///
 	/// Allows interacting with the bridge by sending messages over `Messages` component.
@@ -82,6 +98,10 @@ pub enum Command {
 	RegisterParachain(register_parachain::RegisterParachain),
 	///
 	RelayParachains(relay_parachains::RelayParachains),
+	/// Check that the node exposes all RPCs and runtime APIs that the relay needs.
+	///
+	/// Prints the table with results of all checks and fails if any check has failed.
+	CheckNode(check_node::CheckNode),
 }
 
 impl Command {
@@ -107,14 +127,17 @@ impl Command {
 		self.init_logger();
 		match self {
 			Self::RelayHeaders(arg) => arg.run().await?,
+			Self::CatchUpHeaders(arg) => arg.run().await?,
 			Self::RelayMessages(arg) => arg.run().await?,
 			Self::RelayHeadersAndMessages(arg) => arg.run().await?,
 			Self::InitBridge(arg) => arg.run().await?,
+			Self::SubmitRaw(arg) => arg.run().await?,
 			Self::SendMessage(arg) => arg.run().await?,
 			Self::EstimateFee(arg) => arg.run().await?,
 			Self::ResubmitTransactions(arg) => arg.run().await?,
 			Self::RegisterParachain(arg) => arg.run().await?,
 			Self::RelayParachains(arg) => arg.run().await?,
+			Self::CheckNode(arg) => arg.run().await?,
 		}
 		Ok(())
 	}
@@ -162,6 +185,9 @@ impl Balance {
 ///
 /// Used to abstract away CLI commands.
 pub trait CliChain: relay_substrate_client::Chain {
+	/// Identifier of the chain, used by bridge pallets.
+	const ID: bp_runtime::ChainId;
+
 	/// Current version of the chain runtime, known to relay.
 	///
 	/// can be `None` if relay is not going to submit transactions to that chain.
@@ -181,23 +207,35 @@ pub trait CliChain: relay_substrate_client::Chain {
 	fn ss58_format() -> u16;
 }
 
-/// Lane id.
+/// Lane id with optional weight, formatted as `<hex-encoded lane id>[:<weight>]`.
+///
+/// The weight defines share of the delivery transactions that the lane may submit when
+/// all lanes, served by the same signer, have messages to deliver.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct HexLaneId(pub LaneId);
-
-impl From<HexLaneId> for LaneId {
-	fn from(lane_id: HexLaneId) -> LaneId {
-		lane_id.0
-	}
+pub struct WeightedHexLaneId {
+	/// Lane id.
+	pub lane: LaneId,
+	/// Lane weight.
+	pub weight: u32,
 }
 
-impl std::str::FromStr for HexLaneId {
-	type Err = hex::FromHexError;
+impl std::str::FromStr for WeightedHexLaneId {
+	type Err = String;
 
 	fn from_str(s: &str) -> Result<Self, Self::Err> {
-		let mut lane_id = LaneId::default();
-		hex::decode_to_slice(s, &mut lane_id)?;
-		Ok(HexLaneId(lane_id))
+		let (lane, weight) = match s.split_once(':') {
+			Some((lane, weight)) => (
+				lane,
+				weight
+					.parse::<u32>()
+					.ok()
+					.filter(|weight| *weight != 0)
+					.ok_or_else(|| format!("Invalid lane weight: {}", weight))?,
+			),
+			None => (s, messages_relay::lane_scheduler::DEFAULT_LANE_WEIGHT),
+		};
+		let lane = lane.parse::<LaneId>().map_err(|e| format!("Invalid lane id: {}", e))?;
+		Ok(WeightedHexLaneId { lane, weight })
 	}
 }
 
@@ -253,6 +291,40 @@ impl From<PrometheusParams> for relay_utils::metrics::MetricsParams {
 	}
 }
 
+/// Generated proofs params.
+#[derive(Clone, Debug, Default, PartialEq, StructOpt)]
+pub struct ProofSizeParams {
+	/// Log warning if generated storage or finality proof is larger than given size (in bytes).
+	/// Defaults to 2/3 of the maximal extrinsic size at the chain that verifies the proof.
+	#[structopt(long)]
+	pub proof_size_warning_threshold: Option<usize>,
+}
+
+/// Runtime call indices check params.
+#[derive(Clone, Debug, PartialEq, StructOpt)]
+pub struct CallIndexCheckParams {
+	/// Do not abort if indices of calls, encoded by the relay, don't match the chain metadata.
+	#[structopt(long)]
+	pub skip_call_index_check: bool,
+}
+
+impl CallIndexCheckParams {
+	/// Ensure that indices of calls, encoded by the relay, match the chain metadata.
+	pub async fn ensure_call_indices_match<C: relay_substrate_client::Chain>(
+		&self,
+		client: &relay_substrate_client::Client<C>,
+	) -> anyhow::Result<()> {
+		match client.ensure_call_indices_match().await {
+			Ok(()) => Ok(()),
+			Err(e) if self.skip_call_index_check => {
+				log::warn!(target: "bridge", "{}: {}. Ignoring, as requested", C::NAME, e);
+				Ok(())
+			},
+			Err(e) => Err(anyhow::format_err!("{}: {}", C::NAME, e)),
+		}
+	}
+}
+
 /// Either explicit or maximal allowed value.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ExplicitOrMaximal<V> {
@@ -306,4 +378,23 @@ mod tests {
 		// then
 		assert_eq!(hex.0, hex2.0);
 	}
+
+	#[test]
+	fn weighted_hex_lane_id_is_parsed() {
+		assert_eq!(
+			"00000001".parse::<WeightedHexLaneId>(),
+			Ok(WeightedHexLaneId { lane: LaneId([0, 0, 0, 1]), weight: 1 }),
+		);
+		assert_eq!(
+			"00000001:3".parse::<WeightedHexLaneId>(),
+			Ok(WeightedHexLaneId { lane: LaneId([0, 0, 0, 1]), weight: 3 }),
+		);
+		assert!("00000001:0".parse::<WeightedHexLaneId>().is_err());
+		assert!("00000001:x".parse::<WeightedHexLaneId>().is_err());
+		assert!("0001:3".parse::<WeightedHexLaneId>().is_err());
+		assert_eq!(
+			"0x1:3".parse::<WeightedHexLaneId>(),
+			Ok(WeightedHexLaneId { lane: LaneId([0, 0, 0, 1]), weight: 3 }),
+		);
+	}
 }
@@ -0,0 +1,342 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Construction of target chain transactions without connecting to any node.
+//!
+//! Offline mode is used to sign bridge maintenance calls on the air-gapped machine. All
+//! parameters that are normally read from the node, must be provided explicitly. The signed
+//! extrinsic may then be submitted using the `submit-raw` command.
+
+use crate::cli::{chain_schema::*, CliChain, HexBytes};
+
+use bp_runtime::HeaderId;
+use codec::{Decode, Encode};
+use relay_substrate_client::{
+	AccountKeyPairOf, CallOf, ChainRuntimeVersion, HashOf, SignParam, TransactionEra,
+	TransactionSignScheme, UnsignedTransaction,
+};
+use serde::Serialize;
+use structopt::StructOpt;
+
+/// Parameters of the target chain transaction that is constructed offline.
+#[derive(StructOpt, Debug, PartialEq, Eq)]
+pub struct TargetOfflineParams {
+	/// Construct transaction without connecting to any node.
+	///
+	/// Target runtime version must be either `Bundle` or `Custom`. If mortal transaction is
+	/// requested, era block number and hash must be provided.
+	#[structopt(long)]
+	pub offline: bool,
+	/// Hex-encoded hash of the target chain genesis block.
+	#[structopt(long)]
+	pub target_genesis_hash: Option<HexBytes>,
+	/// Nonce of the transaction author account at the target chain.
+	#[structopt(long)]
+	pub target_nonce: Option<u32>,
+	/// Number of the target chain block, where mortality period of the transaction starts.
+	#[structopt(long)]
+	pub target_era_block_number: Option<u32>,
+	/// Hex-encoded hash of the target chain block, where mortality period of the transaction
+	/// starts.
+	#[structopt(long)]
+	pub target_era_block_hash: Option<HexBytes>,
+}
+
+/// Transaction that has been constructed offline.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct OfflineTransaction {
+	/// Hex-encoded runtime call.
+	pub call: String,
+	/// Hex-encoded payload that needs to be signed by the transaction author.
+	pub signing_payload: String,
+	/// Hex-encoded signed extrinsic. Only present if signer has been provided.
+	pub signed_extrinsic: Option<String>,
+}
+
+impl TargetOfflineParams {
+	/// Construct target chain transaction for given call.
+	///
+	/// The transaction is only signed if signer is provided in `target_sign`.
+	pub fn build_transaction<C>(
+		&self,
+		target_runtime_version: TargetRuntimeVersionParams,
+		target_sign: &TargetSigningParams,
+		call: CallOf<C>,
+	) -> anyhow::Result<OfflineTransaction>
+	where
+		C: CliChain<KeyPair = AccountKeyPairOf<C>> + TransactionSignScheme<Chain = C>,
+	{
+		let (spec_version, transaction_version) =
+			match target_runtime_version.into_runtime_version(C::RUNTIME_VERSION)? {
+				ChainRuntimeVersion::Custom(spec_version, transaction_version) =>
+					(spec_version, transaction_version),
+				ChainRuntimeVersion::Auto =>
+					return Err(anyhow::format_err!(
+						"Runtime version of {} can't be read from the node in offline mode",
+						C::NAME,
+					)),
+			};
+		let genesis_hash = decode_hash::<C>("target-genesis-hash", &self.target_genesis_hash)?;
+		let nonce = self.target_nonce.ok_or_else(|| missing_argument("target-nonce"))?;
+		let era = match target_sign.transactions_mortality()? {
+			Some(mortality_period) => {
+				let era_block_number = self
+					.target_era_block_number
+					.ok_or_else(|| missing_argument("target-era-block-number"))?;
+				let era_block_hash =
+					decode_hash::<C>("target-era-block-hash", &self.target_era_block_hash)?;
+				TransactionEra::new_checked(
+					HeaderId(era_block_number.into(), era_block_hash),
+					Some(mortality_period),
+				)
+				.map_err(|e| anyhow::format_err!("Invalid transaction era: {:?}", e))?
+			},
+			None => TransactionEra::Immortal,
+		};
+
+		let encoded_call = call.encode();
+		let unsigned = UnsignedTransaction::new(call.into(), nonce.into()).era(era);
+		let signing_payload = C::encode_signing_payload(
+			spec_version,
+			transaction_version,
+			genesis_hash,
+			unsigned.clone(),
+		);
+		let signed_extrinsic = if target_sign.is_defined() {
+			let signed_extrinsic = C::sign_transaction(
+				SignParam {
+					spec_version,
+					transaction_version,
+					genesis_hash,
+					signer: target_sign.to_keypair::<C>()?,
+				},
+				unsigned,
+			)?;
+			Some(hex_string(&signed_extrinsic.encode()))
+		} else {
+			None
+		};
+
+		Ok(OfflineTransaction {
+			call: hex_string(&encoded_call),
+			signing_payload: hex_string(&signing_payload),
+			signed_extrinsic,
+		})
+	}
+}
+
+impl OfflineTransaction {
+	/// Print transaction to the stdout as a JSON document.
+	pub fn print(&self) -> anyhow::Result<()> {
+		println!("{}", serde_json::to_string_pretty(self)?);
+		Ok(())
+	}
+}
+
+/// Decode chain hash from the hex-encoded argument.
+fn decode_hash<C: CliChain>(argument: &str, value: &Option<HexBytes>) -> anyhow::Result<HashOf<C>> {
+	let mut value = &value.as_ref().ok_or_else(|| missing_argument(argument))?.0[..];
+	let hash = HashOf::<C>::decode(&mut value)
+		.map_err(|e| anyhow::format_err!("Invalid '{}' argument: {:?}", argument, e))?;
+	if !value.is_empty() {
+		return Err(anyhow::format_err!(
+			"Invalid '{}' argument: unexpected trailing bytes",
+			argument
+		))
+	}
+	Ok(hash)
+}
+
+/// Returns error for the argument that is required in offline mode.
+fn missing_argument(argument: &str) -> anyhow::Error {
+	anyhow::format_err!("The '{}' argument is required in offline mode", argument)
+}
+
+/// Returns `0x`-prefixed hex representation of given bytes.
+fn hex_string(bytes: &[u8]) -> String {
+	format!("0x{}", hex::encode(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use relay_millau_client::Millau;
+	use sp_core::Pair;
+	use sp_runtime::traits::{IdentifyAccount, Verify};
+
+	// Millau is using 512-bit hashes
+	const GENESIS_HASH: [u8; 64] = [42u8; 64];
+	const ERA_BLOCK_HASH: [u8; 64] = [77u8; 64];
+
+	fn offline_params() -> TargetOfflineParams {
+		TargetOfflineParams {
+			offline: true,
+			target_genesis_hash: Some(HexBytes(GENESIS_HASH.to_vec())),
+			target_nonce: Some(5),
+			target_era_block_number: Some(100),
+			target_era_block_hash: Some(HexBytes(ERA_BLOCK_HASH.to_vec())),
+		}
+	}
+
+	fn runtime_version() -> TargetRuntimeVersionParams {
+		TargetRuntimeVersionParams {
+			target_version_mode: RuntimeVersionType::Custom,
+			target_spec_version: Some(1),
+			target_transaction_version: Some(2),
+		}
+	}
+
+	fn signing_params(signer: Option<&str>, mortality: Option<u32>) -> TargetSigningParams {
+		TargetSigningParams {
+			target_signer: signer.map(Into::into),
+			target_signer_password: None,
+			target_signer_file: None,
+			target_signer_password_file: None,
+			target_transactions_mortality: mortality,
+			target_real_account: None,
+		}
+	}
+
+	fn remark_call() -> millau_runtime::Call {
+		millau_runtime::Call::System(millau_runtime::SystemCall::remark {
+			remark: b"Hello world!".to_vec(),
+		})
+	}
+
+	fn decode_hex(value: &str) -> Vec<u8> {
+		hex::decode(value.trim_start_matches("0x")).unwrap()
+	}
+
+	fn assert_round_trip(mortality: Option<u32>) -> millau_runtime::UncheckedExtrinsic {
+		let alice = sp_core::sr25519::Pair::from_string("//Alice", None).unwrap();
+		let transaction = offline_params()
+			.build_transaction::<Millau>(
+				runtime_version(),
+				&signing_params(Some("//Alice"), mortality),
+				remark_call(),
+			)
+			.unwrap();
+
+		// signed extrinsic is decodable and is signed by the expected signer
+		let signed_extrinsic: millau_runtime::UncheckedExtrinsic =
+			Decode::decode(&mut &decode_hex(transaction.signed_extrinsic.as_ref().unwrap())[..])
+				.unwrap();
+		assert!(Millau::is_signed_by(&alice, &signed_extrinsic));
+
+		// signature is the signature of the reported signing payload
+		let (_, signature, _) = signed_extrinsic.signature.clone().unwrap();
+		let signer: sp_runtime::MultiSigner = alice.public().into();
+		assert!(
+			signature.verify(&decode_hex(&transaction.signing_payload)[..], &signer.into_account())
+		);
+
+		// call and nonce are preserved
+		assert_eq!(transaction.call, hex_string(&remark_call().encode()));
+		let unsigned = Millau::parse_transaction(signed_extrinsic.clone()).unwrap();
+		assert_eq!(unsigned.call.into_decoded().unwrap(), remark_call());
+		assert_eq!(unsigned.nonce, 5);
+
+		signed_extrinsic
+	}
+
+	#[test]
+	fn immortal_transaction_round_trip_works() {
+		let signed_extrinsic = assert_round_trip(None);
+		let (_, _, extra) = signed_extrinsic.signature.unwrap();
+		assert_eq!(extra.4.encode(), sp_runtime::generic::Era::immortal().encode());
+	}
+
+	#[test]
+	fn mortal_transaction_round_trip_works() {
+		let signed_extrinsic = assert_round_trip(Some(64));
+		let (_, _, extra) = signed_extrinsic.signature.unwrap();
+		assert_eq!(extra.4.encode(), sp_runtime::generic::Era::mortal(64, 100).encode());
+	}
+
+	#[test]
+	fn mortal_transaction_uses_effective_mortality_period() {
+		let signed_extrinsic = assert_round_trip(Some(100));
+		let (_, _, extra) = signed_extrinsic.signature.unwrap();
+		let era = sp_runtime::generic::Era::decode(&mut &extra.4.encode()[..]).unwrap();
+		assert_eq!(era, sp_runtime::generic::Era::mortal(128, 100));
+		// this is what `CheckEra` computes when transaction is included into the next block
+		assert_eq!(era.birth(101), 100);
+		assert_eq!(era.death(101), 228);
+	}
+
+	#[test]
+	fn transaction_with_too_large_mortality_period_is_rejected() {
+		assert!(offline_params()
+			.build_transaction::<Millau>(
+				runtime_version(),
+				&signing_params(Some("//Alice"), Some(bp_runtime::MAX_MORTALITY_PERIOD + 1)),
+				remark_call(),
+			)
+			.is_err());
+	}
+
+	#[test]
+	fn transaction_is_not_signed_without_signer() {
+		let transaction = offline_params()
+			.build_transaction::<Millau>(
+				runtime_version(),
+				&signing_params(None, None),
+				remark_call(),
+			)
+			.unwrap();
+		assert_eq!(transaction.signed_extrinsic, None);
+		assert!(!transaction.signing_payload.is_empty());
+	}
+
+	#[test]
+	fn genesis_hash_of_wrong_size_is_rejected() {
+		let mut params = offline_params();
+		params.target_genesis_hash = Some(HexBytes(vec![42u8; 32]));
+		assert!(params
+			.build_transaction::<Millau>(
+				runtime_version(),
+				&signing_params(Some("//Alice"), None),
+				remark_call(),
+			)
+			.is_err());
+	}
+
+	#[test]
+	fn mortal_transaction_requires_era_block() {
+		let mut params = offline_params();
+		params.target_era_block_hash = None;
+		assert!(params
+			.build_transaction::<Millau>(
+				runtime_version(),
+				&signing_params(Some("//Alice"), Some(64)),
+				remark_call(),
+			)
+			.is_err());
+	}
+
+	#[test]
+	fn runtime_version_is_not_queried_in_offline_mode() {
+		let mut runtime_version = runtime_version();
+		runtime_version.target_version_mode = RuntimeVersionType::Auto;
+		assert!(offline_params()
+			.build_transaction::<Millau>(
+				runtime_version,
+				&signing_params(Some("//Alice"), None),
+				remark_call(),
+			)
+			.is_err());
+	}
+}
@@ -342,6 +342,7 @@ mod tests {
 					relaychain_signer_file: None,
 					relaychain_signer_password_file: None,
 					relaychain_transactions_mortality: None,
+					relaychain_real_account: None,
 				},
 				para_connection: ParachainConnectionParams {
 					parachain_host: "127.0.0.1".into(),
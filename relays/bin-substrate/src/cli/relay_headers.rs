@@ -15,23 +15,28 @@
 // along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
 
 use async_trait::async_trait;
-use relay_substrate_client::{AccountIdOf, AccountKeyPairOf};
-use sp_core::Pair;
+use relay_substrate_client::{AccountIdOf, AccountKeyPairOf, ChainWithBalances};
+use sp_core::{crypto::Ss58Codec, Pair};
 use structopt::StructOpt;
 use strum::{EnumString, EnumVariantNames, VariantNames};
 
 use crate::chains::{
 	millau_headers_to_rialto::MillauToRialtoCliBridge,
 	millau_headers_to_rialto_parachain::MillauToRialtoParachainCliBridge,
+	pass3d_headers_to_pass3dt::Pass3dToPass3dtCliBridge,
+	pass3dt_headers_to_pass3d::Pass3dtToPass3dCliBridge,
 	rialto_headers_to_millau::RialtoToMillauCliBridge,
 	westend_headers_to_millau::WestendToMillauCliBridge,
-	pass3dt_headers_to_pass3d::Pass3dtToPass3dCliBridge,
-	pass3d_headers_to_pass3dt::Pass3dToPass3dtCliBridge,
 };
 use relay_utils::metrics::{GlobalMetrics, StandaloneMetric};
 use substrate_relay_helper::finality::SubstrateFinalitySyncPipeline;
 
-use crate::cli::{bridge::*, chain_schema::*, PrometheusParams};
+use crate::cli::{
+	bridge::*,
+	chain_schema::*,
+	startup_report::{ChainReport, OutputFormat, StartupReport},
+	CallIndexCheckParams, PrometheusParams, ProofSizeParams,
+};
 
 /// Start headers relayer process.
 #[derive(StructOpt)]
@@ -43,6 +48,10 @@ pub struct RelayHeaders {
 	/// are relayed.
 	#[structopt(long)]
 	only_mandatory_headers: bool,
+	/// Maximal number of mandatory headers that may be submitted in a single batch transaction.
+	/// Batching is disabled if set to 1. The target chain runtime must support batch calls.
+	#[structopt(long, default_value = "1")]
+	max_headers_in_batch: usize,
 	#[structopt(flatten)]
 	source: SourceConnectionParams,
 	#[structopt(flatten)]
@@ -51,6 +60,13 @@ pub struct RelayHeaders {
 	target_sign: TargetSigningParams,
 	#[structopt(flatten)]
 	prometheus_params: PrometheusParams,
+	#[structopt(flatten)]
+	call_index_check_params: CallIndexCheckParams,
+	#[structopt(flatten)]
+	proof_size_params: ProofSizeParams,
+	/// Format of the startup report.
+	#[structopt(long, possible_values = OutputFormat::VARIANTS, case_insensitive = true, default_value = "text")]
+	output: OutputFormat,
 }
 
 #[derive(Debug, EnumString, EnumVariantNames)]
@@ -68,21 +84,53 @@ pub enum RelayHeadersBridge {
 #[async_trait]
 trait HeadersRelayer: RelayToRelayHeadersCliBridge
 where
-	AccountIdOf<Self::Target>: From<<AccountKeyPairOf<Self::Target> as Pair>::Public>,
+	Self::Target: ChainWithBalances,
+	AccountIdOf<Self::Target>: From<<AccountKeyPairOf<Self::Target> as Pair>::Public> + Ss58Codec,
 {
 	/// Relay headers.
 	async fn relay_headers(data: RelayHeaders) -> anyhow::Result<()> {
 		let source_client = data.source.into_client::<Self::Source>().await?;
 		let target_client = data.target.into_client::<Self::Target>().await?;
+		data.call_index_check_params.ensure_call_indices_match(&target_client).await?;
 		let target_transactions_mortality = data.target_sign.target_transactions_mortality;
 		let target_sign = data.target_sign.to_keypair::<Self::Target>()?;
 
 		let metrics_params: relay_utils::metrics::MetricsParams = data.prometheus_params.into();
 		GlobalMetrics::new()?.register_and_spawn(&metrics_params.registry)?;
+		target_client.register_nonce_tracker_metrics(&metrics_params.registry).await?;
+		target_client.register_block_space_metrics(&metrics_params.registry)?;
+
+		StartupReport::new("relay-headers")
+			.with_chain(ChainReport::collect("source", &source_client).await)
+			.with_chain(
+				ChainReport::collect("target", &target_client)
+					.await
+					.with_signer(&target_client, target_sign.public().into())
+					.await,
+			)
+			.with_tuning(
+				"tick",
+				format!(
+					"{:?}",
+					std::cmp::max(
+						Self::Source::AVERAGE_BLOCK_INTERVAL,
+						Self::Target::AVERAGE_BLOCK_INTERVAL
+					)
+				),
+			)
+			.with_tuning(
+				"target_transactions_mortality",
+				format!("{:?}", target_transactions_mortality),
+			)
+			.with_tuning("max_headers_in_batch", data.max_headers_in_batch)
+			.with_feature("only_mandatory_headers", data.only_mandatory_headers)
+			.with_feature("prometheus", metrics_params.address.is_some())
+			.emit(data.output)?;
 
 		let target_transactions_params = substrate_relay_helper::TransactionParams {
 			signer: target_sign,
 			mortality: target_transactions_mortality,
+			real_account: data.target_sign.real_account::<Self::Target>()?,
 		};
 		Self::Finality::start_relay_guards(
 			&target_client,
@@ -95,8 +143,10 @@ where
 			source_client,
 			target_client,
 			data.only_mandatory_headers,
+			data.max_headers_in_batch,
 			target_transactions_params,
 			metrics_params,
+			data.proof_size_params.proof_size_warning_threshold,
 		)
 		.await
 	}
@@ -120,7 +170,6 @@ impl RelayHeaders {
 				MillauToRialtoParachainCliBridge::relay_headers(self),
 			RelayHeadersBridge::Pass3dtToPass3d => Pass3dtToPass3dCliBridge::relay_headers(self),
 			RelayHeadersBridge::Pass3dToPass3dt => Pass3dToPass3dtCliBridge::relay_headers(self),
-
 		}
 		.await
 	}
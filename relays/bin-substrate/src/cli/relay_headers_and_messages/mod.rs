@@ -29,7 +29,7 @@ mod relay_to_relay;
 mod relay_to_parachain;
 
 use async_trait::async_trait;
-use std::{marker::PhantomData, sync::Arc};
+use std::{marker::PhantomData, path::PathBuf, sync::Arc};
 use structopt::StructOpt;
 use strum::VariantNames;
 
@@ -52,19 +52,20 @@ use crate::{
 			RelayToRelayHeadersCliBridge,
 		},
 		chain_schema::*,
-		relay_messages::RelayerMode,
-		CliChain, HexLaneId, PrometheusParams,
+		relay_messages::{OutboundStateProofParams, RelayStrategyKind, RelayerMode},
+		startup_report::{ChainReport, OutputFormat, StartupReport},
+		CallIndexCheckParams, CliChain, PrometheusParams, ProofSizeParams, WeightedHexLaneId,
 	},
 	declare_chain_cli_schema,
 };
 use bp_messages::LaneId;
-use bp_runtime::{BalanceOf, BlockNumberOf};
-use messages_relay::relay_strategy::MixStrategy;
+use bp_runtime::{BalanceOf, BlockNumberOf, BridgeRemark};
+use messages_relay::lane_scheduler::LaneSubmissionScheduler;
 use relay_substrate_client::{
 	AccountIdOf, AccountKeyPairOf, Chain, ChainWithBalances, Client, TransactionSignScheme,
 };
 use relay_utils::metrics::MetricsParams;
-use sp_core::Pair;
+use sp_core::{crypto::Ss58Codec, Pair};
 use substrate_relay_helper::{
 	messages_lane::MessagesRelayParams, messages_metrics::StandaloneMessagesMetrics,
 	on_demand::OnDemandRelay, TaggedAccount, TransactionParams,
@@ -81,17 +82,46 @@ pub(crate) const CONVERSION_RATE_ALLOWED_DIFFERENCE_RATIO: f64 = 0.05;
 /// Parameters that have the same names across all bridges.
 #[derive(Debug, PartialEq, StructOpt)]
 pub struct HeadersAndMessagesSharedParams {
-	/// Hex-encoded lane identifiers that should be served by the complex relay.
-	#[structopt(long, default_value = "00000000")]
-	pub lane: Vec<HexLaneId>,
+	/// Hex-encoded lane identifiers that should be served by the complex relay, optionally
+	/// followed by the lane weight (e.g. `00000001:3`). When the same signer has to deliver
+	/// messages at multiple lanes, lanes are getting submission slots in proportion to their
+	/// weights. Defaults to all lanes that are known to be open at the left chain.
+	#[structopt(long)]
+	pub lane: Vec<WeightedHexLaneId>,
+	/// Maximal number of delivery transactions that may be submitted at all lanes by the same
+	/// signer, while previous transactions are not yet finalized. Defaults to the number of
+	/// lanes.
+	#[structopt(long)]
+	pub max_in_flight_deliveries: Option<usize>,
 	#[structopt(long, possible_values = RelayerMode::VARIANTS, case_insensitive = true, default_value = "rational")]
 	pub relayer_mode: RelayerMode,
+	/// Strategy that is used to decide whether the relayer wants to deliver messages.
+	#[structopt(long, possible_values = RelayStrategyKind::VARIANTS, case_insensitive = true, default_value = "mix")]
+	pub relay_strategy: RelayStrategyKind,
 	/// If passed, only mandatory headers (headers that are changing the GRANDPA authorities set)
 	/// are relayed.
 	#[structopt(long)]
 	pub only_mandatory_headers: bool,
 	#[structopt(flatten)]
 	pub prometheus_params: PrometheusParams,
+	#[structopt(flatten)]
+	pub call_index_check_params: CallIndexCheckParams,
+	#[structopt(flatten)]
+	pub proof_size_params: ProofSizeParams,
+	#[structopt(flatten)]
+	pub outbound_state_proof_params: OutboundStateProofParams,
+	/// Directory where relay-local state of every served lane is persisted between relay
+	/// restarts. If not specified, the state is not persisted.
+	#[structopt(long)]
+	pub persistent_state_dir: Option<PathBuf>,
+	/// Submit `remark_with_event` transaction, carrying the bridge id and relay version, to both
+	/// chains when the relay starts. It allows indexers to attribute relay transactions to the
+	/// bridge.
+	#[structopt(long)]
+	pub tag_transactions: bool,
+	/// Format of the startup report.
+	#[structopt(long, possible_values = OutputFormat::VARIANTS, case_insensitive = true, default_value = "text")]
+	pub output: OutputFormat,
 }
 
 pub struct Full2WayBridgeCommonParams<
@@ -111,10 +141,17 @@ impl<Left: TransactionSignScheme + CliChain, Right: TransactionSignScheme + CliC
 	Full2WayBridgeCommonParams<Left, Right>
 {
 	pub fn new<L2R: MessagesCliBridge<Source = Left, Target = Right>>(
-		shared: HeadersAndMessagesSharedParams,
+		mut shared: HeadersAndMessagesSharedParams,
 		left: BridgeEndCommonParams<Left>,
 		right: BridgeEndCommonParams<Right>,
 	) -> anyhow::Result<Self> {
+		if shared.lane.is_empty() {
+			shared.lane = L2R::default_lanes()
+				.into_iter()
+				.map(|lane| WeightedHexLaneId { lane, weight: 1 })
+				.collect();
+		}
+
 		// Create metrics registry.
 		let metrics_params = shared.prometheus_params.clone().into();
 		let metrics_params = relay_utils::relay_metrics(metrics_params).into_params();
@@ -138,6 +175,7 @@ pub struct BridgeEndCommonParams<Chain: TransactionSignScheme + CliChain> {
 	pub client: Client<Chain>,
 	pub sign: AccountKeyPairOf<Chain>,
 	pub transactions_mortality: Option<u32>,
+	pub real_account: Option<<AccountKeyPairOf<Chain> as Pair>::Public>,
 	pub messages_pallet_owner: Option<AccountKeyPairOf<Chain>>,
 	pub accounts: Vec<TaggedAccount<AccountIdOf<Chain>>>,
 }
@@ -194,6 +232,7 @@ where
 				TransactionParams {
 					signer: messages_pallet_owner.clone(),
 					mortality: self.source.transactions_mortality,
+					real_account: None,
 				},
 				self.metrics
 					.target_to_source_conversion_rate
@@ -220,25 +259,61 @@ where
 		Ok(())
 	}
 
+	fn start_bridge_heartbeat(&self) -> anyhow::Result<()> {
+		if !self.shared.tag_transactions {
+			return Ok(())
+		}
+
+		let lanes = self.shared.lane.iter().map(|lane| lane.lane).collect::<Vec<_>>();
+		substrate_relay_helper::bridge_heartbeat::start_bridge_heartbeat::<Target, Target>(
+			self.target.client.clone(),
+			TransactionParams {
+				signer: self.target.sign.clone(),
+				mortality: self.target.transactions_mortality,
+				real_account: self.target.real_account.clone(),
+			},
+			BridgeRemark {
+				bridge_id: Bridge::bridge_id(&lanes),
+				relay_version: env!("CARGO_PKG_VERSION").as_bytes().to_vec(),
+			},
+		)
+	}
+
+	fn submission_scheduler(&self) -> anyhow::Result<LaneSubmissionScheduler> {
+		let lane_weights =
+			self.shared.lane.iter().map(|lane| (lane.lane, lane.weight)).collect::<Vec<_>>();
+		LaneSubmissionScheduler::new(
+			self.shared.max_in_flight_deliveries.unwrap_or(lane_weights.len()),
+			&lane_weights,
+		)
+		.with_metrics(
+			Some(&format!("{}_to_{}_MessageLanes", Source::NAME, Target::NAME)),
+			&self.metrics_params.registry,
+		)
+		.map_err(|e| anyhow::format_err!("{}", e))
+	}
+
 	fn messages_relay_params(
 		&self,
 		source_to_target_headers_relay: Arc<dyn OnDemandRelay<BlockNumberOf<Source>>>,
 		target_to_source_headers_relay: Arc<dyn OnDemandRelay<BlockNumberOf<Target>>>,
 		lane_id: LaneId,
+		submission_scheduler: LaneSubmissionScheduler,
 	) -> MessagesRelayParams<Bridge::MessagesLane> {
-		let relayer_mode = self.shared.relayer_mode.into();
-		let relay_strategy = MixStrategy::new(relayer_mode);
+		let relay_strategy = self.shared.relay_strategy.relay_strategy(self.shared.relayer_mode);
 
 		MessagesRelayParams {
 			source_client: self.source.client.clone(),
 			source_transaction_params: TransactionParams {
 				signer: self.source.sign.clone(),
 				mortality: self.source.transactions_mortality,
+				real_account: self.source.real_account.clone(),
 			},
 			target_client: self.target.client.clone(),
 			target_transaction_params: TransactionParams {
 				signer: self.target.sign.clone(),
 				mortality: self.target.transactions_mortality,
+				real_account: self.target.real_account.clone(),
 			},
 			source_to_target_headers_relay: Some(source_to_target_headers_relay),
 			target_to_source_headers_relay: Some(target_to_source_headers_relay),
@@ -246,6 +321,13 @@ where
 			metrics_params: self.metrics_params.clone().disable(),
 			standalone_metrics: Some(self.metrics.clone()),
 			relay_strategy,
+			submission_scheduler: Some(submission_scheduler),
+			persistent_state_dir: self.shared.persistent_state_dir.clone(),
+			proof_size_warning_threshold: self
+				.shared
+				.proof_size_params
+				.proof_size_warning_threshold,
+			outbound_state_proof_params: self.shared.outbound_state_proof_params.clone().into(),
 		}
 	}
 }
@@ -295,8 +377,8 @@ trait Full2WayBridgeBase: Sized + Send + Sync {
 #[async_trait]
 trait Full2WayBridge: Sized + Sync
 where
-	AccountIdOf<Self::Left>: From<<AccountKeyPairOf<Self::Left> as Pair>::Public>,
-	AccountIdOf<Self::Right>: From<<AccountKeyPairOf<Self::Right> as Pair>::Public>,
+	AccountIdOf<Self::Left>: From<<AccountKeyPairOf<Self::Left> as Pair>::Public> + Ss58Codec,
+	AccountIdOf<Self::Right>: From<<AccountKeyPairOf<Self::Right> as Pair>::Public> + Ss58Codec,
 	BalanceOf<Self::Left>: TryFrom<BalanceOf<Self::Right>> + Into<u128>,
 	BalanceOf<Self::Right>: TryFrom<BalanceOf<Self::Left>> + Into<u128>,
 {
@@ -346,6 +428,56 @@ where
 		)
 	}
 
+	/// Build startup report of the complex relay.
+	async fn startup_report(&self) -> StartupReport {
+		let common = self.base().common();
+		let mut report = StartupReport::new("relay-headers-and-messages")
+			.with_chain(
+				ChainReport::collect("left", &common.left.client)
+					.await
+					.with_signer(&common.left.client, common.left.sign.public().into())
+					.await,
+			)
+			.with_chain(
+				ChainReport::collect("right", &common.right.client)
+					.await
+					.with_signer(&common.right.client, common.right.sign.public().into())
+					.await,
+			);
+		for lane in &common.shared.lane {
+			report = report.with_lane(lane.lane);
+		}
+		report
+			.with_tuning("left_tick", format!("{:?}", Self::Left::AVERAGE_BLOCK_INTERVAL))
+			.with_tuning("right_tick", format!("{:?}", Self::Right::AVERAGE_BLOCK_INTERVAL))
+			.with_tuning(
+				"left_transactions_mortality",
+				format!("{:?}", common.left.transactions_mortality),
+			)
+			.with_tuning(
+				"right_transactions_mortality",
+				format!("{:?}", common.right.transactions_mortality),
+			)
+			.with_tuning("relayer_mode", format!("{:?}", common.shared.relayer_mode))
+			.with_tuning("relay_strategy", format!("{:?}", common.shared.relay_strategy))
+			.with_tuning(
+				"max_in_flight_deliveries",
+				common.shared.max_in_flight_deliveries.unwrap_or(common.shared.lane.len()),
+			)
+			.with_feature("only_mandatory_headers", common.shared.only_mandatory_headers)
+			.with_feature("persistent_state", common.shared.persistent_state_dir.is_some())
+			.with_feature("tag_transactions", common.shared.tag_transactions)
+			.with_feature(
+				"left_conversion_rate_update",
+				common.left.messages_pallet_owner.is_some(),
+			)
+			.with_feature(
+				"right_conversion_rate_update",
+				common.right.messages_pallet_owner.is_some(),
+			)
+			.with_feature("prometheus", common.metrics_params.address.is_some())
+	}
+
 	async fn run(&mut self) -> anyhow::Result<()> {
 		// Register standalone metrics.
 		{
@@ -360,15 +492,29 @@ where
 			});
 		}
 
+		self.startup_report().await.emit(self.base().common().shared.output)?;
+
+		// ensure that calls, encoded by the relay, match runtimes of both chains
+		{
+			let common = self.base().common();
+			let check_params = &common.shared.call_index_check_params;
+			check_params.ensure_call_indices_match(&common.left.client).await?;
+			check_params.ensure_call_indices_match(&common.right.client).await?;
+		}
+
 		// start conversion rate update loops for left/right chains
 		self.left_to_right().start_conversion_rate_update_loop()?;
 		self.right_to_left().start_conversion_rate_update_loop()?;
 
+		// tag bridge transactions at both chains
+		self.left_to_right().start_bridge_heartbeat()?;
+		self.right_to_left().start_bridge_heartbeat()?;
+
 		// start on-demand header relays
 		let (left_to_right_on_demand_headers, right_to_left_on_demand_headers) =
 			self.mut_base().start_on_demand_headers_relayers().await?;
 
-		// add balance-related metrics
+		// add balance and nonce related metrics
 		{
 			let common = self.mut_base().mut_common();
 			substrate_relay_helper::messages_metrics::add_relay_balances_metrics(
@@ -383,13 +529,35 @@ where
 				&common.right.accounts,
 			)
 			.await?;
+			common
+				.left
+				.client
+				.register_nonce_tracker_metrics(&common.metrics_params.registry)
+				.await?;
+			common
+				.right
+				.client
+				.register_nonce_tracker_metrics(&common.metrics_params.registry)
+				.await?;
+			common
+				.left
+				.client
+				.register_block_space_metrics(&common.metrics_params.registry)?;
+			common
+				.right
+				.client
+				.register_block_space_metrics(&common.metrics_params.registry)?;
 		}
 
+		// lanes are sharing signers, so they're sharing submission schedulers
+		let left_to_right_submission_scheduler = self.left_to_right().submission_scheduler()?;
+		let right_to_left_submission_scheduler = self.right_to_left().submission_scheduler()?;
+
 		let lanes = self.base().common().shared.lane.clone();
 		// Need 2x capacity since we consider both directions for each lane
 		let mut message_relays = Vec::with_capacity(lanes.len() * 2);
 		for lane in lanes {
-			let lane = lane.into();
+			let lane = lane.lane.into();
 
 			let left_to_right_messages = substrate_relay_helper::messages_lane::run::<
 				<Self::L2R as MessagesCliBridge>::MessagesLane,
@@ -397,6 +565,7 @@ where
 				left_to_right_on_demand_headers.clone(),
 				right_to_left_on_demand_headers.clone(),
 				lane,
+				left_to_right_submission_scheduler.clone(),
 			))
 			.map_err(|e| anyhow::format_err!("{}", e))
 			.boxed();
@@ -408,6 +577,7 @@ where
 				right_to_left_on_demand_headers.clone(),
 				left_to_right_on_demand_headers.clone(),
 				lane,
+				right_to_left_submission_scheduler.clone(),
 			))
 			.map_err(|e| anyhow::format_err!("{}", e))
 			.boxed();
@@ -556,7 +726,7 @@ mod tests {
 			"--lane",
 			"00000000",
 			"--lane",
-			"73776170",
+			"73776170:3",
 			"--prometheus-host",
 			"0.0.0.0",
 		]);
@@ -567,16 +737,28 @@ mod tests {
 			RelayHeadersAndMessages::MillauRialto(MillauRialtoHeadersAndMessages {
 				shared: HeadersAndMessagesSharedParams {
 					lane: vec![
-						HexLaneId([0x00, 0x00, 0x00, 0x00]),
-						HexLaneId([0x73, 0x77, 0x61, 0x70])
+						WeightedHexLaneId { lane: LaneId([0x00, 0x00, 0x00, 0x00]), weight: 1 },
+						WeightedHexLaneId { lane: LaneId([0x73, 0x77, 0x61, 0x70]), weight: 3 },
 					],
+					max_in_flight_deliveries: None,
 					relayer_mode: RelayerMode::Rational,
+					relay_strategy: RelayStrategyKind::Mix,
 					only_mandatory_headers: false,
 					prometheus_params: PrometheusParams {
 						no_prometheus: false,
 						prometheus_host: "0.0.0.0".into(),
 						prometheus_port: 9616,
 					},
+					call_index_check_params: CallIndexCheckParams { skip_call_index_check: false },
+					proof_size_params: ProofSizeParams { proof_size_warning_threshold: None },
+					outbound_state_proof_params: OutboundStateProofParams {
+						unrewarded_relayer_entries_threshold_percent: 50,
+						unrewarded_messages_threshold_percent: 50,
+						max_deliveries_without_state_proof: 16,
+					},
+					persistent_state_dir: None,
+					tag_transactions: false,
+					output: OutputFormat::Text,
 				},
 				left: MillauConnectionParams {
 					millau_host: "millau-node-alice".into(),
@@ -594,6 +776,7 @@ mod tests {
 					millau_signer_file: None,
 					millau_signer_password_file: None,
 					millau_transactions_mortality: Some(64),
+					millau_real_account: None,
 				},
 				left_messages_pallet_owner: MillauMessagesPalletOwnerSigningParams {
 					millau_messages_pallet_owner: Some("//RialtoMessagesOwner".into()),
@@ -605,6 +788,7 @@ mod tests {
 					millau_headers_to_rialto_signer_file: None,
 					millau_headers_to_rialto_signer_password_file: None,
 					millau_headers_to_rialto_transactions_mortality: None,
+					millau_headers_to_rialto_real_account: None,
 				},
 				right: RialtoConnectionParams {
 					rialto_host: "rialto-node-alice".into(),
@@ -622,6 +806,7 @@ mod tests {
 					rialto_signer_file: None,
 					rialto_signer_password_file: None,
 					rialto_transactions_mortality: Some(64),
+					rialto_real_account: None,
 				},
 				right_messages_pallet_owner: RialtoMessagesPalletOwnerSigningParams {
 					rialto_messages_pallet_owner: Some("//MillauMessagesOwner".into()),
@@ -633,6 +818,7 @@ mod tests {
 					rialto_headers_to_millau_signer_file: None,
 					rialto_headers_to_millau_signer_password_file: None,
 					rialto_headers_to_millau_transactions_mortality: None,
+					rialto_headers_to_millau_real_account: None,
 				},
 			}),
 		);
@@ -682,14 +868,31 @@ mod tests {
 			RelayHeadersAndMessages::MillauRialtoParachain(
 				MillauRialtoParachainHeadersAndMessages {
 					shared: HeadersAndMessagesSharedParams {
-						lane: vec![HexLaneId([0x00, 0x00, 0x00, 0x00])],
+						lane: vec![WeightedHexLaneId {
+							lane: LaneId([0x00, 0x00, 0x00, 0x00]),
+							weight: 1
+						}],
+						max_in_flight_deliveries: None,
 						relayer_mode: RelayerMode::Rational,
+						relay_strategy: RelayStrategyKind::Mix,
 						only_mandatory_headers: false,
 						prometheus_params: PrometheusParams {
 							no_prometheus: false,
 							prometheus_host: "0.0.0.0".into(),
 							prometheus_port: 9616,
 						},
+						call_index_check_params: CallIndexCheckParams {
+							skip_call_index_check: false
+						},
+						proof_size_params: ProofSizeParams { proof_size_warning_threshold: None },
+						outbound_state_proof_params: OutboundStateProofParams {
+							unrewarded_relayer_entries_threshold_percent: 50,
+							unrewarded_messages_threshold_percent: 50,
+							max_deliveries_without_state_proof: 16,
+						},
+						persistent_state_dir: None,
+						tag_transactions: false,
+						output: OutputFormat::Text,
 					},
 					left: MillauConnectionParams {
 						millau_host: "millau-node-alice".into(),
@@ -707,6 +910,7 @@ mod tests {
 						millau_signer_file: None,
 						millau_signer_password_file: None,
 						millau_transactions_mortality: Some(64),
+						millau_real_account: None,
 					},
 					left_messages_pallet_owner: MillauMessagesPalletOwnerSigningParams {
 						millau_messages_pallet_owner: Some("//RialtoParachainMessagesOwner".into()),
@@ -719,6 +923,7 @@ mod tests {
 							millau_headers_to_rialto_parachain_signer_file: None,
 							millau_headers_to_rialto_parachain_signer_password_file: None,
 							millau_headers_to_rialto_parachain_transactions_mortality: None,
+							millau_headers_to_rialto_parachain_real_account: None,
 						},
 					right: RialtoParachainConnectionParams {
 						rialto_parachain_host: "rialto-parachain-collator-charlie".into(),
@@ -736,6 +941,7 @@ mod tests {
 						rialto_parachain_signer_file: None,
 						rialto_parachain_signer_password_file: None,
 						rialto_parachain_transactions_mortality: Some(64),
+						rialto_parachain_real_account: None,
 					},
 					right_messages_pallet_owner: RialtoParachainMessagesPalletOwnerSigningParams {
 						rialto_parachain_messages_pallet_owner: Some(
@@ -749,6 +955,7 @@ mod tests {
 						rialto_headers_to_millau_signer_file: None,
 						rialto_headers_to_millau_signer_password_file: None,
 						rialto_headers_to_millau_transactions_mortality: None,
+						rialto_headers_to_millau_real_account: None,
 					},
 					right_parachains_to_left_sign_override: RialtoParachainsToMillauSigningParams {
 						rialto_parachains_to_millau_signer: None,
@@ -756,6 +963,7 @@ mod tests {
 						rialto_parachains_to_millau_signer_file: None,
 						rialto_parachains_to_millau_signer_password_file: None,
 						rialto_parachains_to_millau_transactions_mortality: None,
+						rialto_parachains_to_millau_real_account: None,
 					},
 					right_relay: RialtoConnectionParams {
 						rialto_host: "rialto-node-alice".into(),
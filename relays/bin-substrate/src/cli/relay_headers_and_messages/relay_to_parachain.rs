@@ -29,9 +29,15 @@ use bp_polkadot_core::parachains::ParaHash;
 use bp_runtime::BlockNumberOf;
 use pallet_bridge_parachains::{RelayBlockHash, RelayBlockHasher, RelayBlockNumber};
 use relay_substrate_client::{AccountIdOf, AccountKeyPairOf, Chain, Client, TransactionSignScheme};
+use relay_utils::metrics::Metric;
 use sp_core::Pair;
 use substrate_relay_helper::{
-	finality::SubstrateFinalitySyncPipeline,
+	finality::{
+		fan_out::{
+			FinalityProofsFanOut, FinalityProofsFanOutMetrics, DEFAULT_SUBSCRIBER_BUFFER_SIZE,
+		},
+		SubstrateFinalitySyncPipeline,
+	},
 	on_demand::{
 		headers::OnDemandHeadersRelay, parachains::OnDemandParachainsRelay, OnDemandRelay,
 	},
@@ -112,6 +118,7 @@ macro_rules! declare_relay_to_parachain_bridge_schema {
 								client: self.left.into_client::<Left>().await?,
 								sign: self.left_sign.to_keypair::<Left>()?,
 								transactions_mortality: self.left_sign.transactions_mortality()?,
+								real_account: self.left_sign.real_account::<Left>()?,
 								messages_pallet_owner: self.left_messages_pallet_owner.to_keypair::<Left>()?,
 								accounts: vec![],
 							},
@@ -119,6 +126,7 @@ macro_rules! declare_relay_to_parachain_bridge_schema {
 								client: self.right.into_client::<Right>().await?,
 								sign: self.right_sign.to_keypair::<Right>()?,
 								transactions_mortality: self.right_sign.transactions_mortality()?,
+								real_account: self.right_sign.real_account::<Right>()?,
 								messages_pallet_owner: self.right_messages_pallet_owner.to_keypair::<Right>()?,
 								accounts: vec![],
 							},
@@ -208,12 +216,24 @@ where
 		)
 		.await?;
 
+		// there's single justifications subscription per source chain, shared by all finality
+		// relays that are reading this chain justifications
+		let fan_out_metrics = FinalityProofsFanOutMetrics::new()?;
+		fan_out_metrics.register(&self.common.metrics_params.registry)?;
+		let left_finality_proofs_fan_out = FinalityProofsFanOut::new(
+			DEFAULT_SUBSCRIBER_BUFFER_SIZE,
+			Some(fan_out_metrics.clone()),
+		);
+		let right_relay_finality_proofs_fan_out =
+			FinalityProofsFanOut::new(DEFAULT_SUBSCRIBER_BUFFER_SIZE, Some(fan_out_metrics));
+
 		let left_to_right_on_demand_headers =
 			OnDemandHeadersRelay::new::<<L2R as RelayToRelayHeadersCliBridge>::Finality>(
 				self.common.left.client.clone(),
 				self.common.right.client.clone(),
 				self.left_headers_to_right_transaction_params.clone(),
 				self.common.shared.only_mandatory_headers,
+				Some(left_finality_proofs_fan_out),
 			);
 		let right_relay_to_left_on_demand_headers =
 			OnDemandHeadersRelay::new::<<R2L as ParachainToRelayHeadersCliBridge>::RelayFinality>(
@@ -221,6 +241,7 @@ where
 				self.common.left.client.clone(),
 				self.right_headers_to_left_transaction_params.clone(),
 				self.common.shared.only_mandatory_headers,
+				Some(right_relay_finality_proofs_fan_out),
 			);
 		let right_to_left_on_demand_parachains = OnDemandParachainsRelay::new::<
 			<R2L as ParachainToRelayHeadersCliBridge>::ParachainFinality,
@@ -15,23 +15,39 @@
 // along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
 
 use async_trait::async_trait;
-use sp_core::Pair;
+use sp_core::{crypto::Ss58Codec, Pair};
+use std::path::PathBuf;
 use structopt::StructOpt;
 use strum::{EnumString, EnumVariantNames, VariantNames};
 
 use crate::chains::{
 	millau_headers_to_rialto::MillauToRialtoCliBridge,
 	millau_headers_to_rialto_parachain::MillauToRialtoParachainCliBridge,
+	pass3d_headers_to_pass3dt::Pass3dToPass3dtCliBridge,
+	pass3dt_headers_to_pass3d::Pass3dtToPass3dCliBridge,
 	rialto_headers_to_millau::RialtoToMillauCliBridge,
 	rialto_parachains_to_millau::RialtoParachainToMillauCliBridge,
-	pass3dt_headers_to_pass3d::Pass3dtToPass3dCliBridge,
-	pass3d_headers_to_pass3dt::Pass3dToPass3dtCliBridge,
 };
-use messages_relay::relay_strategy::MixStrategy;
-use relay_substrate_client::{AccountIdOf, AccountKeyPairOf, BalanceOf, TransactionSignScheme};
-use substrate_relay_helper::{messages_lane::MessagesRelayParams, TransactionParams};
+use bp_messages::{LaneId, MessageNonce};
+use bp_runtime::BridgeRemark;
+use messages_relay::relay_strategy::{
+	AltruisticStrategy, MixStrategy, RationalStrategy, SharedRelayStrategy,
+};
+use relay_substrate_client::{
+	AccountIdOf, AccountKeyPairOf, BalanceOf, ChainWithBalances, TransactionSignScheme,
+};
+use substrate_relay_helper::{
+	bridge_heartbeat::start_bridge_heartbeat,
+	messages_lane::{messages_batch_limits, MessagesRelayParams},
+	TransactionParams,
+};
 
-use crate::cli::{bridge::*, chain_schema::*, CliChain, HexLaneId, PrometheusParams};
+use crate::cli::{
+	bridge::*,
+	chain_schema::*,
+	startup_report::{ChainReport, OutputFormat, StartupReport},
+	CallIndexCheckParams, CliChain, PrometheusParams, ProofSizeParams,
+};
 
 /// Relayer operating mode.
 #[derive(Debug, EnumString, EnumVariantNames, Clone, Copy, PartialEq, Eq)]
@@ -53,17 +69,80 @@ impl From<RelayerMode> for messages_relay::message_lane_loop::RelayerMode {
 	}
 }
 
+/// Relay strategy, used to decide whether the relayer wants to deliver messages.
+#[derive(Debug, EnumString, EnumVariantNames, Clone, Copy, PartialEq, Eq)]
+#[strum(serialize_all = "kebab_case")]
+pub enum RelayStrategyKind {
+	/// The relayer will deliver all messages and confirmations as long as he's not losing any
+	/// funds.
+	Rational,
+	/// The relayer doesn't care about rewards.
+	Altruistic,
+	/// The strategy is selected by the `--relayer-mode` argument.
+	Mix,
+}
+
+impl RelayStrategyKind {
+	/// Create relay strategy of this kind.
+	pub fn relay_strategy<SourceChainBalance: PartialOrd + Send + Sync>(
+		self,
+		relayer_mode: RelayerMode,
+	) -> SharedRelayStrategy<SourceChainBalance> {
+		match self {
+			RelayStrategyKind::Rational => SharedRelayStrategy::new(RationalStrategy),
+			RelayStrategyKind::Altruistic => SharedRelayStrategy::new(AltruisticStrategy),
+			RelayStrategyKind::Mix =>
+				SharedRelayStrategy::new(MixStrategy::new(relayer_mode.into())),
+		}
+	}
+}
+
+/// Parameters that define when outbound lane state proof is included into the message delivery
+/// transaction.
+#[derive(Debug, Clone, PartialEq, Eq, StructOpt)]
+pub struct OutboundStateProofParams {
+	/// Include outbound lane state proof into the delivery transaction if the number of
+	/// unrewarded relayer entries at the target chain is at least this percent of the limit.
+	#[structopt(long, default_value = "50")]
+	pub unrewarded_relayer_entries_threshold_percent: MessageNonce,
+	/// Include outbound lane state proof into the delivery transaction if the number of
+	/// unrewarded messages at the target chain is at least this percent of the limit.
+	#[structopt(long, default_value = "50")]
+	pub unrewarded_messages_threshold_percent: MessageNonce,
+	/// Include outbound lane state proof into the delivery transaction if it hasn't been included
+	/// in that number of previous delivery transactions.
+	#[structopt(long, default_value = "16")]
+	pub max_deliveries_without_state_proof: u32,
+}
+
+impl From<OutboundStateProofParams>
+	for messages_relay::message_lane_loop::OutboundStateProofParams
+{
+	fn from(params: OutboundStateProofParams) -> Self {
+		Self {
+			unrewarded_relayer_entries_threshold_percent: params
+				.unrewarded_relayer_entries_threshold_percent,
+			unrewarded_messages_threshold_percent: params.unrewarded_messages_threshold_percent,
+			max_deliveries_without_proof: params.max_deliveries_without_state_proof,
+		}
+	}
+}
+
 /// Start messages relayer process.
 #[derive(StructOpt)]
 pub struct RelayMessages {
 	/// A bridge instance to relay messages for.
 	#[structopt(possible_values = FullBridge::VARIANTS, case_insensitive = true)]
 	bridge: FullBridge,
-	/// Hex-encoded lane id that should be served by the relay. Defaults to `00000000`.
+	/// Hex-encoded lane id that should be served by the relay (e.g. `0x00000001`, or `0x1` for
+	/// short). Defaults to `00000000`.
 	#[structopt(long, default_value = "00000000")]
-	lane: HexLaneId,
+	lane: LaneId,
 	#[structopt(long, possible_values = RelayerMode::VARIANTS, case_insensitive = true, default_value = "rational")]
 	relayer_mode: RelayerMode,
+	/// Strategy that is used to decide whether the relayer wants to deliver messages.
+	#[structopt(long, possible_values = RelayStrategyKind::VARIANTS, case_insensitive = true, default_value = "mix")]
+	relay_strategy: RelayStrategyKind,
 	#[structopt(flatten)]
 	source: SourceConnectionParams,
 	#[structopt(flatten)]
@@ -74,44 +153,142 @@ pub struct RelayMessages {
 	target_sign: TargetSigningParams,
 	#[structopt(flatten)]
 	prometheus_params: PrometheusParams,
+	#[structopt(flatten)]
+	call_index_check_params: CallIndexCheckParams,
+	#[structopt(flatten)]
+	proof_size_params: ProofSizeParams,
+	#[structopt(flatten)]
+	outbound_state_proof_params: OutboundStateProofParams,
+	/// Directory where relay-local lane state (e.g. the delivery attempts log) is persisted
+	/// between relay restarts. If not specified, the state is not persisted.
+	#[structopt(long)]
+	persistent_state_dir: Option<PathBuf>,
+	/// Submit `remark_with_event` transaction, carrying the bridge id and relay version, to both
+	/// chains when the relay starts. It allows indexers to attribute relay transactions to the
+	/// bridge.
+	#[structopt(long)]
+	tag_transactions: bool,
+	/// Format of the startup report.
+	#[structopt(long, possible_values = OutputFormat::VARIANTS, case_insensitive = true, default_value = "text")]
+	output: OutputFormat,
 }
 
 #[async_trait]
 trait MessagesRelayer: MessagesCliBridge
 where
 	Self::Source: TransactionSignScheme<Chain = Self::Source>
-		+ CliChain<KeyPair = AccountKeyPairOf<Self::Source>>,
-	AccountIdOf<Self::Source>: From<<AccountKeyPairOf<Self::Source> as Pair>::Public>,
-	AccountIdOf<Self::Target>: From<<AccountKeyPairOf<Self::Target> as Pair>::Public>,
+		+ CliChain<KeyPair = AccountKeyPairOf<Self::Source>>
+		+ ChainWithBalances,
+	Self::Target: ChainWithBalances,
+	AccountIdOf<Self::Source>: From<<AccountKeyPairOf<Self::Source> as Pair>::Public> + Ss58Codec,
+	AccountIdOf<Self::Target>: From<<AccountKeyPairOf<Self::Target> as Pair>::Public> + Ss58Codec,
 	BalanceOf<Self::Source>: TryFrom<BalanceOf<Self::Target>>,
 {
 	async fn relay_messages(data: RelayMessages) -> anyhow::Result<()> {
 		let source_client = data.source.into_client::<Self::Source>().await?;
 		let source_sign = data.source_sign.to_keypair::<Self::Source>()?;
 		let source_transactions_mortality = data.source_sign.transactions_mortality()?;
+		let source_real_account = data.source_sign.real_account::<Self::Source>()?;
 		let target_client = data.target.into_client::<Self::Target>().await?;
+		data.call_index_check_params.ensure_call_indices_match(&source_client).await?;
+		data.call_index_check_params.ensure_call_indices_match(&target_client).await?;
 		let target_sign = data.target_sign.to_keypair::<Self::Target>()?;
 		let target_transactions_mortality = data.target_sign.transactions_mortality()?;
-		let relayer_mode = data.relayer_mode.into();
-		let relay_strategy = MixStrategy::new(relayer_mode);
+		let target_real_account = data.target_sign.real_account::<Self::Target>()?;
+		let metrics_params: relay_utils::metrics::MetricsParams = data.prometheus_params.into();
+		source_client.register_nonce_tracker_metrics(&metrics_params.registry).await?;
+		target_client.register_nonce_tracker_metrics(&metrics_params.registry).await?;
+		source_client.register_block_space_metrics(&metrics_params.registry)?;
+		target_client.register_block_space_metrics(&metrics_params.registry)?;
+		let lane_id = data.lane;
+		let bridge_id = Self::bridge_id(&[lane_id]);
+
+		let batch_limits = messages_batch_limits::<Self::MessagesLane>();
+		StartupReport::new("relay-messages")
+			.with_chain(
+				ChainReport::collect("source", &source_client)
+					.await
+					.with_signer(&source_client, source_sign.public().into())
+					.await,
+			)
+			.with_chain(
+				ChainReport::collect("target", &target_client)
+					.await
+					.with_signer(&target_client, target_sign.public().into())
+					.await,
+			)
+			.with_lane(lane_id)
+			.with_tuning("source_tick", format!("{:?}", Self::Source::AVERAGE_BLOCK_INTERVAL))
+			.with_tuning("target_tick", format!("{:?}", Self::Target::AVERAGE_BLOCK_INTERVAL))
+			.with_tuning(
+				"source_transactions_mortality",
+				format!("{:?}", source_transactions_mortality),
+			)
+			.with_tuning(
+				"target_transactions_mortality",
+				format!("{:?}", target_transactions_mortality),
+			)
+			.with_tuning("max_messages_in_single_batch", batch_limits.max_messages_in_single_batch)
+			.with_tuning(
+				"max_messages_weight_in_single_batch",
+				batch_limits.max_messages_weight_in_single_batch,
+			)
+			.with_tuning(
+				"max_messages_size_in_single_batch",
+				batch_limits.max_messages_size_in_single_batch,
+			)
+			.with_tuning("relayer_mode", format!("{:?}", data.relayer_mode))
+			.with_tuning("relay_strategy", format!("{:?}", data.relay_strategy))
+			.with_tuning("bridge_id", format!("{:?}", bridge_id))
+			.with_feature("prometheus", metrics_params.address.is_some())
+			.with_feature("persistent_state", data.persistent_state_dir.is_some())
+			.with_feature("tag_transactions", data.tag_transactions)
+			.emit(data.output)?;
+
+		let relay_strategy = data.relay_strategy.relay_strategy(data.relayer_mode);
+		let source_transaction_params = TransactionParams {
+			signer: source_sign,
+			mortality: source_transactions_mortality,
+			real_account: source_real_account,
+		};
+		let target_transaction_params = TransactionParams {
+			signer: target_sign,
+			mortality: target_transactions_mortality,
+			real_account: target_real_account,
+		};
+
+		if data.tag_transactions {
+			let remark = BridgeRemark {
+				bridge_id,
+				relay_version: env!("CARGO_PKG_VERSION").as_bytes().to_vec(),
+			};
+			start_bridge_heartbeat::<Self::Source, Self::Source>(
+				source_client.clone(),
+				source_transaction_params.clone(),
+				remark.clone(),
+			)?;
+			start_bridge_heartbeat::<Self::Target, Self::Target>(
+				target_client.clone(),
+				target_transaction_params.clone(),
+				remark,
+			)?;
+		}
 
 		substrate_relay_helper::messages_lane::run::<Self::MessagesLane>(MessagesRelayParams {
 			source_client,
-			source_transaction_params: TransactionParams {
-				signer: source_sign,
-				mortality: source_transactions_mortality,
-			},
+			source_transaction_params,
 			target_client,
-			target_transaction_params: TransactionParams {
-				signer: target_sign,
-				mortality: target_transactions_mortality,
-			},
+			target_transaction_params,
 			source_to_target_headers_relay: None,
 			target_to_source_headers_relay: None,
-			lane_id: data.lane.into(),
-			metrics_params: data.prometheus_params.into(),
+			lane_id,
+			metrics_params,
 			standalone_metrics: None,
 			relay_strategy,
+			submission_scheduler: None,
+			persistent_state_dir: data.persistent_state_dir,
+			proof_size_warning_threshold: data.proof_size_params.proof_size_warning_threshold,
+			outbound_state_proof_params: data.outbound_state_proof_params.into(),
 		})
 		.await
 		.map_err(|e| anyhow::format_err!("{}", e))
@@ -180,4 +357,122 @@ mod tests {
 			RelayerMode::Altruistic,
 		);
 	}
+
+	#[test]
+	fn should_accept_persistent_state_dir() {
+		let params = RelayMessages::from_iter(vec![
+			"relay-messages",
+			"rialto-to-millau",
+			"--source-port=0",
+			"--source-signer=//Alice",
+			"--target-port=0",
+			"--target-signer=//Alice",
+			"--lane=00000000",
+		]);
+		assert_eq!(params.persistent_state_dir, None);
+
+		let params = RelayMessages::from_iter(vec![
+			"relay-messages",
+			"rialto-to-millau",
+			"--source-port=0",
+			"--source-signer=//Alice",
+			"--target-port=0",
+			"--target-signer=//Alice",
+			"--lane=00000000",
+			"--persistent-state-dir=/var/lib/relay",
+		]);
+		assert_eq!(params.persistent_state_dir, Some(PathBuf::from("/var/lib/relay")));
+	}
+
+	#[test]
+	fn should_accept_outbound_state_proof_params() {
+		let params = RelayMessages::from_iter(vec![
+			"relay-messages",
+			"rialto-to-millau",
+			"--source-port=0",
+			"--source-signer=//Alice",
+			"--target-port=0",
+			"--target-signer=//Alice",
+			"--lane=00000000",
+		]);
+		assert_eq!(
+			messages_relay::message_lane_loop::OutboundStateProofParams::from(
+				params.outbound_state_proof_params
+			),
+			Default::default(),
+		);
+
+		let params = RelayMessages::from_iter(vec![
+			"relay-messages",
+			"rialto-to-millau",
+			"--source-port=0",
+			"--source-signer=//Alice",
+			"--target-port=0",
+			"--target-signer=//Alice",
+			"--lane=00000000",
+			"--unrewarded-relayer-entries-threshold-percent=75",
+			"--unrewarded-messages-threshold-percent=80",
+			"--max-deliveries-without-state-proof=4",
+		]);
+		assert_eq!(
+			params.outbound_state_proof_params,
+			OutboundStateProofParams {
+				unrewarded_relayer_entries_threshold_percent: 75,
+				unrewarded_messages_threshold_percent: 80,
+				max_deliveries_without_state_proof: 4,
+			},
+		);
+	}
+
+	#[test]
+	fn should_accept_relay_strategy() {
+		let params = RelayMessages::from_iter(vec![
+			"relay-messages",
+			"rialto-to-millau",
+			"--source-port=0",
+			"--source-signer=//Alice",
+			"--target-port=0",
+			"--target-signer=//Alice",
+			"--lane=00000000",
+		]);
+		assert_eq!(params.relay_strategy, RelayStrategyKind::Mix);
+
+		let params = RelayMessages::from_iter(vec![
+			"relay-messages",
+			"rialto-to-millau",
+			"--source-port=0",
+			"--source-signer=//Alice",
+			"--target-port=0",
+			"--target-signer=//Alice",
+			"--lane=00000000",
+			"--relay-strategy=altruistic",
+		]);
+		assert_eq!(params.relay_strategy, RelayStrategyKind::Altruistic);
+	}
+
+	#[test]
+	fn should_accept_tag_transactions() {
+		let params = RelayMessages::from_iter(vec![
+			"relay-messages",
+			"rialto-to-millau",
+			"--source-port=0",
+			"--source-signer=//Alice",
+			"--target-port=0",
+			"--target-signer=//Alice",
+			"--lane=00000000",
+		]);
+		assert!(!params.tag_transactions);
+
+		let params = RelayMessages::from_iter(vec![
+			"relay-messages",
+			"rialto-to-millau",
+			"--source-port=0",
+			"--source-signer=//Alice",
+			"--target-port=0",
+			"--target-signer=//Alice",
+			"--lane=00000000",
+			"--tag-transactions",
+		]);
+		assert!(params.tag_transactions);
+	}
 }
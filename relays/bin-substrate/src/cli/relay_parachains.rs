@@ -24,7 +24,12 @@ use bp_polkadot_core::parachains::ParaId;
 use parachains_relay::parachains_loop::{
 	AvailableHeader, ParachainSyncParams, SourceClient, TargetClient,
 };
-use relay_utils::metrics::{GlobalMetrics, StandaloneMetric};
+use relay_substrate_client::{
+	metrics::{proof_size_warning_threshold, ProofGenerationMetrics, ProofType},
+	AccountIdOf, AccountKeyPairOf, ChainWithBalances,
+};
+use relay_utils::metrics::{GlobalMetrics, Metric, StandaloneMetric};
+use sp_core::{crypto::Ss58Codec, Pair};
 use std::sync::Arc;
 use structopt::StructOpt;
 use strum::{EnumString, EnumVariantNames, VariantNames};
@@ -33,7 +38,12 @@ use substrate_relay_helper::{
 	TransactionParams,
 };
 
-use crate::cli::{bridge::ParachainToRelayHeadersCliBridge, chain_schema::*, PrometheusParams};
+use crate::cli::{
+	bridge::ParachainToRelayHeadersCliBridge,
+	chain_schema::*,
+	startup_report::{ChainReport, OutputFormat, StartupReport},
+	CallIndexCheckParams, PrometheusParams, ProofSizeParams,
+};
 
 /// Start parachain heads relayer process.
 #[derive(StructOpt)]
@@ -49,6 +59,13 @@ pub struct RelayParachains {
 	target_sign: TargetSigningParams,
 	#[structopt(flatten)]
 	prometheus_params: PrometheusParams,
+	#[structopt(flatten)]
+	call_index_check_params: CallIndexCheckParams,
+	#[structopt(flatten)]
+	proof_size_params: ProofSizeParams,
+	/// Format of the startup report.
+	#[structopt(long, possible_values = OutputFormat::VARIANTS, case_insensitive = true, default_value = "text")]
+	output: OutputFormat,
 }
 
 /// Parachain heads relay bridge.
@@ -66,27 +83,74 @@ where
 		SourceClient<ParachainsPipelineAdapter<Self::ParachainFinality>>,
 	ParachainsTarget<Self::ParachainFinality>:
 		TargetClient<ParachainsPipelineAdapter<Self::ParachainFinality>>,
+	Self::Target: ChainWithBalances,
+	AccountIdOf<Self::Target>: From<<AccountKeyPairOf<Self::Target> as Pair>::Public> + Ss58Codec,
 {
 	async fn relay_headers(data: RelayParachains) -> anyhow::Result<()> {
+		let metrics_params: relay_utils::metrics::MetricsParams = data.prometheus_params.into();
+		GlobalMetrics::new()?.register_and_spawn(&metrics_params.registry)?;
+		let proof_metrics = ProofGenerationMetrics::new()?;
+		proof_metrics.register(&metrics_params.registry)?;
+
 		let source_client = data.source.into_client::<Self::SourceRelay>().await?;
+		let source_report = ChainReport::collect("source", &source_client).await;
 		let source_client = ParachainsSource::<Self::ParachainFinality>::new(
 			source_client,
 			Arc::new(Mutex::new(AvailableHeader::Missing)),
+		)
+		.with_proof_metrics(
+			proof_metrics
+				.proof_metrics::<Self::SourceRelay>(ProofType::ParachainHeads)
+				.with_size_warning_threshold(
+					data.proof_size_params
+						.proof_size_warning_threshold
+						.unwrap_or_else(proof_size_warning_threshold::<Self::Target>),
+				),
 		);
 
 		let target_transaction_params = TransactionParams {
 			signer: data.target_sign.to_keypair::<Self::Target>()?,
 			mortality: data.target_sign.target_transactions_mortality,
+			real_account: data.target_sign.real_account::<Self::Target>()?,
 		};
+		let stall_timeout = relay_substrate_client::transaction_stall_timeout(
+			target_transaction_params.mortality,
+			<Self::Target as relay_substrate_client::Chain>::AVERAGE_BLOCK_INTERVAL,
+			relay_utils::STALL_TIMEOUT,
+		);
 		let target_client = data.target.into_client::<Self::Target>().await?;
+		data.call_index_check_params.ensure_call_indices_match(&target_client).await?;
+		target_client.register_nonce_tracker_metrics(&metrics_params.registry).await?;
+		target_client.register_block_space_metrics(&metrics_params.registry)?;
+
+		StartupReport::new("relay-parachains")
+			.with_chain(source_report)
+			.with_chain(
+				ChainReport::collect("target", &target_client)
+					.await
+					.with_signer(&target_client, target_transaction_params.signer.public().into())
+					.await,
+			)
+			.with_tuning(
+				"tick",
+				format!(
+					"{:?}",
+					<Self::Target as relay_substrate_client::Chain>::AVERAGE_BLOCK_INTERVAL
+				),
+			)
+			.with_tuning("stall_timeout", format!("{:?}", stall_timeout))
+			.with_tuning(
+				"target_transactions_mortality",
+				format!("{:?}", target_transaction_params.mortality),
+			)
+			.with_feature("prometheus", metrics_params.address.is_some())
+			.emit(data.output)?;
+
 		let target_client = ParachainsTarget::<Self::ParachainFinality>::new(
 			target_client.clone(),
 			target_transaction_params,
 		);
 
-		let metrics_params: relay_utils::metrics::MetricsParams = data.prometheus_params.into();
-		GlobalMetrics::new()?.register_and_spawn(&metrics_params.registry)?;
-
 		parachains_relay::parachains_loop::run(
 			source_client,
 			target_client,
@@ -94,7 +158,7 @@ where
 				parachains: vec![
 					ParaId(<Self::ParachainFinality as substrate_relay_helper::parachains::SubstrateParachainsPipeline>::SOURCE_PARACHAIN_PARA_ID)
 				],
-				stall_timeout: std::time::Duration::from_secs(60),
+				stall_timeout,
 				strategy: parachains_relay::parachains_loop::ParachainSyncStrategy::Any,
 			},
 			metrics_params,
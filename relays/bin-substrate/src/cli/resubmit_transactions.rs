@@ -114,6 +114,7 @@ impl ResubmitTransactions {
 			let transaction_params = TransactionParams {
 				signer: self.target_sign.to_keypair::<Target>()?,
 				mortality: self.target_sign.target_transactions_mortality,
+				real_account: self.target_sign.real_account::<Target>()?,
 			};
 
 			relay_utils::relay_loop((), client)
@@ -463,10 +464,10 @@ async fn update_transaction_tip<C: Chain, S: TransactionSignScheme<Chain = C>>(
 				genesis_hash: *client.genesis_hash(),
 				signer: transaction_params.signer.clone(),
 			},
-			unsigned_tx.era(relay_substrate_client::TransactionEra::new(
+			unsigned_tx.era(relay_substrate_client::TransactionEra::new_checked(
 				at_block,
 				transaction_params.mortality,
-			)),
+			)?),
 		)?,
 	))
 }
@@ -28,10 +28,11 @@ use crate::{
 		chain_schema::*,
 		encode_message::{self, CliEncodeMessage, RawMessage},
 		estimate_fee::{estimate_message_delivery_and_dispatch_fee, ConversionRateOverride},
-		Balance, CliChain, HexLaneId,
+		Balance, CliChain,
 	},
 };
 use async_trait::async_trait;
+use bp_messages::LaneId;
 use codec::{Decode, Encode};
 use relay_substrate_client::{
 	AccountIdOf, AccountKeyPairOf, Chain, ChainBase, SignParam, TransactionSignScheme,
@@ -76,9 +77,9 @@ pub struct SendMessage {
 	/// bridge messages pallet.
 	#[structopt(long)]
 	use_xcm_pallet: bool,
-	/// Hex-encoded lane id. Defaults to `00000000`.
+	/// Hex-encoded lane id (e.g. `0x00000001`, or `0x1` for short). Defaults to `00000000`.
 	#[structopt(long, default_value = "00000000")]
-	lane: HexLaneId,
+	lane: LaneId,
 	/// A way to override conversion rate between bridge tokens.
 	///
 	/// If not specified, conversion rate from runtime storage is used. It may be obsolete and
@@ -113,7 +114,7 @@ where
 		let source_client = data.source.into_client::<Self::Source>().await?;
 		let source_sign = data.source_sign.to_keypair::<Self::Source>()?;
 
-		let lane = data.lane.clone().into();
+		let lane = data.lane;
 		let conversion_rate_override = data.conversion_rate_override;
 		let fee = match data.fee {
 			Some(fee) => fee,
@@ -137,7 +138,7 @@ where
 			)?
 		} else {
 			Self::Source::encode_send_message_call(
-				data.lane.0,
+				data.lane,
 				payload,
 				fee.cast().into(),
 				data.bridge.bridge_instance_index(),
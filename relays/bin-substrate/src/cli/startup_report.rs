@@ -0,0 +1,360 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Summary of the effective relay configuration, emitted once when relay starts.
+
+use relay_substrate_client::{AccountIdOf, Chain, ChainWithBalances, Client, ConnectionParams};
+use serde::Serialize;
+use sp_core::crypto::Ss58Codec;
+use std::{collections::BTreeMap, fmt::Display};
+use strum::{EnumString, EnumVariantNames};
+
+use crate::cli::CliChain;
+
+/// String that is used instead of redacted secrets.
+const REDACTED: &str = "***";
+
+/// Format of the startup report.
+#[derive(Debug, EnumString, EnumVariantNames, Clone, Copy, PartialEq, Eq)]
+#[strum(serialize_all = "kebab_case")]
+pub enum OutputFormat {
+	/// Aligned human-readable text, written to the log.
+	Text,
+	/// Single JSON document, written to the stdout.
+	Json,
+}
+
+/// Startup report of the relay command.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StartupReport {
+	/// Name of the relay command.
+	command: String,
+	/// Chains the relay is connected to.
+	chains: Vec<ChainReport>,
+	/// Hex-encoded identifiers of lanes that are served by the relay.
+	lanes: Vec<String>,
+	/// Values of relay tuning parameters.
+	tuning: BTreeMap<String, String>,
+	/// Optional relay features and their state.
+	features: BTreeMap<String, bool>,
+}
+
+/// Part of the startup report, related to the single chain.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChainReport {
+	/// Role of the chain in the relay (e.g. `source` or `target`).
+	role: String,
+	/// Name of the chain.
+	name: String,
+	/// Node endpoint with secrets redacted.
+	endpoint: String,
+	/// Genesis hash of the chain.
+	genesis_hash: String,
+	/// Runtime `spec_version`, if known.
+	spec_version: Option<u32>,
+	/// Runtime `transaction_version`, if known.
+	transaction_version: Option<u32>,
+	/// Relayer account at this chain, if relay is submitting transactions to the chain.
+	signer: Option<SignerReport>,
+}
+
+/// Part of the startup report, related to the relayer account.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SignerReport {
+	/// SS58 address of the account.
+	address: String,
+	/// Free balance of the account, if known.
+	free_balance: Option<String>,
+}
+
+impl StartupReport {
+	/// Create empty report for given command.
+	pub fn new(command: impl Into<String>) -> Self {
+		StartupReport {
+			command: command.into(),
+			chains: Vec::new(),
+			lanes: Vec::new(),
+			tuning: BTreeMap::new(),
+			features: BTreeMap::new(),
+		}
+	}
+
+	/// Add chain to the report.
+	pub fn with_chain(mut self, chain: ChainReport) -> Self {
+		self.chains.push(chain);
+		self
+	}
+
+	/// Add served lane to the report.
+	pub fn with_lane(mut self, lane: bp_messages::LaneId) -> Self {
+		self.lanes.push(hex::encode(lane));
+		self
+	}
+
+	/// Add tuning parameter value to the report.
+	pub fn with_tuning(mut self, name: impl Into<String>, value: impl Display) -> Self {
+		self.tuning.insert(name.into(), value.to_string());
+		self
+	}
+
+	/// Add optional feature state to the report.
+	pub fn with_feature(mut self, name: impl Into<String>, enabled: bool) -> Self {
+		self.features.insert(name.into(), enabled);
+		self
+	}
+
+	/// Emit report in given format.
+	pub fn emit(&self, format: OutputFormat) -> anyhow::Result<()> {
+		match format {
+			OutputFormat::Text => log::info!(target: "bridge", "{}", self.to_text()),
+			OutputFormat::Json => println!("{}", self.to_json()?),
+		}
+		Ok(())
+	}
+
+	/// Encode report as JSON document.
+	pub fn to_json(&self) -> anyhow::Result<String> {
+		serde_json::to_string(self).map_err(Into::into)
+	}
+
+	/// Format report as aligned human-readable text.
+	pub fn to_text(&self) -> String {
+		let mut lines = Vec::new();
+		for chain in &self.chains {
+			lines.push((format!("{} chain", chain.role), chain.name.clone()));
+			lines.push((format!("{} endpoint", chain.role), chain.endpoint.clone()));
+			lines.push((format!("{} genesis hash", chain.role), chain.genesis_hash.clone()));
+			lines.push((
+				format!("{} runtime version", chain.role),
+				format!(
+					"spec_version: {}, transaction_version: {}",
+					optional_to_string(chain.spec_version),
+					optional_to_string(chain.transaction_version),
+				),
+			));
+			if let Some(ref signer) = chain.signer {
+				lines.push((format!("{} signer", chain.role), signer.address.clone()));
+				lines.push((
+					format!("{} signer balance", chain.role),
+					optional_to_string(signer.free_balance.as_ref()),
+				));
+			}
+		}
+		if !self.lanes.is_empty() {
+			lines.push(("lanes".into(), self.lanes.join(", ")));
+		}
+		for (name, value) in &self.tuning {
+			lines.push((name.clone(), value.clone()));
+		}
+		for (name, enabled) in &self.features {
+			lines.push((name.clone(), if *enabled { "enabled" } else { "disabled" }.into()));
+		}
+
+		let width = lines.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+		let mut text = format!("Starting {} with:", self.command);
+		for (name, value) in lines {
+			text.push_str(&format!("\n\t{:width$} : {}", name, value, width = width));
+		}
+		text
+	}
+}
+
+impl ChainReport {
+	/// Collect chain report from the connected client.
+	///
+	/// Failures are not fatal here - the relay may still start when e.g. node is temporarily
+	/// unavailable, so missing values are simply reported as unknown.
+	pub async fn collect<C: Chain>(role: impl Into<String>, client: &Client<C>) -> Self {
+		let runtime_version = client
+			.simple_runtime_version()
+			.await
+			.map_err(|e| {
+				log::warn!(
+					target: "bridge",
+					"Failed to read {} runtime version for startup report: {:?}",
+					C::NAME,
+					e,
+				)
+			})
+			.ok();
+
+		ChainReport {
+			role: role.into(),
+			name: C::NAME.into(),
+			endpoint: redacted_endpoint(client.connection_params()),
+			genesis_hash: format!("{:?}", client.genesis_hash()),
+			spec_version: runtime_version.map(|(spec_version, _)| spec_version),
+			transaction_version: runtime_version
+				.map(|(_, transaction_version)| transaction_version),
+			signer: None,
+		}
+	}
+
+	/// Add relayer account to the chain report.
+	pub async fn with_signer<C: CliChain + ChainWithBalances>(
+		mut self,
+		client: &Client<C>,
+		signer: AccountIdOf<C>,
+	) -> Self
+	where
+		AccountIdOf<C>: Ss58Codec,
+	{
+		let address = signer.to_ss58check_with_version(C::ss58_format().into());
+		let free_balance = client
+			.free_native_balance(signer)
+			.await
+			.map(|balance| format!("{:?}", balance))
+			.map_err(|e| {
+				log::warn!(
+					target: "bridge",
+					"Failed to read {} relayer balance for startup report: {:?}",
+					C::NAME,
+					e,
+				)
+			})
+			.ok();
+		self.signer = Some(SignerReport { address, free_balance });
+		self
+	}
+}
+
+/// Returns node endpoint with all secrets (user credentials and query parameters) redacted.
+pub fn redacted_endpoint(params: &ConnectionParams) -> String {
+	let host = match params.host.rsplit_once('@') {
+		Some((_, host)) => format!("{}@{}", REDACTED, host),
+		None => params.host.clone(),
+	};
+	let host = match host.split_once('?') {
+		Some((host, _)) => format!("{}?{}", host, REDACTED),
+		None => host,
+	};
+	format!("{}://{}:{}", if params.secure { "wss" } else { "ws" }, host, params.port)
+}
+
+fn optional_to_string(value: Option<impl Display>) -> String {
+	value.map(|value| value.to_string()).unwrap_or_else(|| "unknown".into())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn test_report() -> StartupReport {
+		StartupReport::new("relay-messages")
+			.with_chain(ChainReport {
+				role: "source".into(),
+				name: "Millau".into(),
+				endpoint: "ws://127.0.0.1:9944".into(),
+				genesis_hash: "0x0101".into(),
+				spec_version: Some(1),
+				transaction_version: Some(2),
+				signer: Some(SignerReport {
+					address: "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY".into(),
+					free_balance: Some("1000".into()),
+				}),
+			})
+			.with_chain(ChainReport {
+				role: "target".into(),
+				name: "Rialto".into(),
+				endpoint: "wss://***@rialto.node:443".into(),
+				genesis_hash: "0x0202".into(),
+				spec_version: None,
+				transaction_version: None,
+				signer: Some(SignerReport {
+					address: "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty".into(),
+					free_balance: None,
+				}),
+			})
+			.with_lane(bp_messages::LaneId([0, 0, 0, 0]))
+			.with_tuning("source_tick", "6s")
+			.with_tuning("max_messages_in_single_batch", 128)
+			.with_feature("prometheus", true)
+	}
+
+	#[test]
+	fn startup_report_json_structure_is_stable() {
+		assert_eq!(
+			serde_json::from_str::<serde_json::Value>(&test_report().to_json().unwrap()).unwrap(),
+			serde_json::json!({
+				"command": "relay-messages",
+				"chains": [
+					{
+						"role": "source",
+						"name": "Millau",
+						"endpoint": "ws://127.0.0.1:9944",
+						"genesis_hash": "0x0101",
+						"spec_version": 1,
+						"transaction_version": 2,
+						"signer": {
+							"address": "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY",
+							"free_balance": "1000",
+						},
+					},
+					{
+						"role": "target",
+						"name": "Rialto",
+						"endpoint": "wss://***@rialto.node:443",
+						"genesis_hash": "0x0202",
+						"spec_version": null,
+						"transaction_version": null,
+						"signer": {
+							"address": "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty",
+							"free_balance": null,
+						},
+					},
+				],
+				"lanes": ["00000000"],
+				"tuning": {
+					"max_messages_in_single_batch": "128",
+					"source_tick": "6s",
+				},
+				"features": {
+					"prometheus": true,
+				},
+			}),
+		);
+	}
+
+	#[test]
+	fn startup_report_text_is_aligned() {
+		let text = test_report().to_text();
+		let lines = text.lines().skip(1).collect::<Vec<_>>();
+		let separator_position = lines[0].find(" : ").unwrap();
+		assert!(lines.iter().all(|line| line.find(" : ") == Some(separator_position)));
+		assert!(text.contains("target runtime version"));
+		assert!(text.contains("spec_version: unknown"));
+	}
+
+	#[test]
+	fn secrets_are_redacted_from_endpoint() {
+		let params = |host: &str, secure| ConnectionParams {
+			host: host.into(),
+			port: 443,
+			secure,
+			..Default::default()
+		};
+
+		assert_eq!(redacted_endpoint(&params("rialto.node", true)), "wss://rialto.node:443");
+		assert_eq!(
+			redacted_endpoint(&params("user:password@rialto.node", true)),
+			"wss://***@rialto.node:443",
+		);
+		assert_eq!(
+			redacted_endpoint(&params("rialto.node/?api_key=secret", false)),
+			"ws://rialto.node/?***:443",
+		);
+	}
+}
@@ -0,0 +1,102 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::cli::{chain_schema::*, HexBytes};
+
+use relay_substrate_client::Chain;
+use relay_utils::{TrackedTransactionStatus, TransactionTracker};
+use sp_core::Bytes;
+use structopt::StructOpt;
+use strum::{EnumString, EnumVariantNames, VariantNames};
+
+/// Submit extrinsic that has been constructed and signed elsewhere (e.g. in offline mode).
+#[derive(StructOpt)]
+pub struct SubmitRaw {
+	/// A chain to submit extrinsic to.
+	#[structopt(long, possible_values = SubmitRawChain::VARIANTS, case_insensitive = true)]
+	chain: SubmitRawChain,
+	/// Hex-encoded signed extrinsic.
+	#[structopt(long)]
+	hex: HexBytes,
+	#[structopt(flatten)]
+	target: TargetConnectionParams,
+}
+
+/// Chain to submit extrinsic to.
+#[derive(Debug, EnumString, EnumVariantNames)]
+#[strum(serialize_all = "kebab_case")]
+pub enum SubmitRawChain {
+	Millau,
+	Rialto,
+	RialtoParachain,
+	Pass3d,
+	Pass3dt,
+}
+
+macro_rules! select_chain {
+	($chain: expr, $generic: tt) => {
+		match $chain {
+			SubmitRawChain::Millau => {
+				type Target = relay_millau_client::Millau;
+
+				$generic
+			},
+			SubmitRawChain::Rialto => {
+				type Target = relay_rialto_client::Rialto;
+
+				$generic
+			},
+			SubmitRawChain::RialtoParachain => {
+				type Target = relay_rialto_parachain_client::RialtoParachain;
+
+				$generic
+			},
+			SubmitRawChain::Pass3d => {
+				type Target = relay_pass3d_client::Pass3d;
+
+				$generic
+			},
+			SubmitRawChain::Pass3dt => {
+				type Target = relay_pass3dt_client::Pass3dt;
+
+				$generic
+			},
+		}
+	};
+}
+
+impl SubmitRaw {
+	/// Run the command.
+	pub async fn run(self) -> anyhow::Result<()> {
+		select_chain!(self.chain, {
+			let client = self.target.into_client::<Target>().await?;
+			let tracker = client.submit_and_watch_raw_extrinsic(Bytes(self.hex.0)).await?;
+			match tracker.wait().await {
+				TrackedTransactionStatus::Finalized(block_id) => {
+					log::info!(
+						target: "bridge",
+						"Transaction has been finalized at {} block {:?}",
+						Target::NAME,
+						block_id,
+					);
+					Ok(())
+				},
+				TrackedTransactionStatus::Lost =>
+					Err(anyhow::format_err!("Transaction has been lost by {} node", Target::NAME)),
+			}
+		})
+	}
+}
@@ -27,5 +27,6 @@ fn main() {
 	let result = async_std::task::block_on(run);
 	if let Err(error) = result {
 		log::error!(target: "bridge", "substrate-relay: {}", error);
+		std::process::exit(1);
 	}
 }
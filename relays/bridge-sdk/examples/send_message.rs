@@ -0,0 +1,67 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Send message from Pass3d to Pass3dt and wait until it is delivered.
+//!
+//! Usage: `send_message [<pass3d-port>] [<pass3dt-port>] [<signer-uri>] [<hex-payload>]`.
+//!
+//! Both nodes are expected to run at `localhost` and headers and messages must be relayed
+//! between chains by some other relay.
+
+use bridge_sdk::{bridges::Pass3dToPass3dt, Bridge};
+use relay_substrate_client::{ChainRuntimeVersion, Client, ConnectionParams};
+use sp_core::Pair;
+use std::time::Duration;
+
+/// Maximal time we're waiting for the message delivery.
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(600);
+
+#[async_std::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+	relay_utils::initialize::initialize_logger(false);
+
+	let mut args = std::env::args().skip(1);
+	let source_port = args.next().map(|port| port.parse()).transpose()?.unwrap_or(9944);
+	let target_port = args.next().map(|port| port.parse()).transpose()?.unwrap_or(9945);
+	let signer_uri = args.next().unwrap_or_else(|| "//Alice".into());
+	let payload = match args.next() {
+		Some(payload) => hex::decode(payload.trim_start_matches("0x"))?,
+		None => b"Hello, Pass3dt!".to_vec(),
+	};
+
+	let connection_params = |port| ConnectionParams {
+		host: "localhost".into(),
+		port,
+		secure: false,
+		chain_runtime_version: ChainRuntimeVersion::Auto,
+	};
+	let bridge = Bridge::<Pass3dToPass3dt>::new(
+		Client::new(connection_params(source_port)).await,
+		Client::new(connection_params(target_port)).await,
+	);
+	let signer = sp_core::sr25519::Pair::from_string(&signer_uri, None)
+		.map_err(|e| format!("Invalid signer URI: {:?}", e))?;
+	let lane = bp_messages::LaneId([0, 0, 0, 0]);
+
+	let fee = bridge_sdk::estimate_fee(&bridge, lane, &payload).await?;
+	println!("Estimated message fee: {}", fee);
+
+	let message = bridge_sdk::send_message(&bridge, signer, lane, payload).await?;
+	let nonce = message.wait_delivered(DELIVERY_TIMEOUT).await?;
+	println!("Message {} has been delivered", nonce);
+
+	Ok(())
+}
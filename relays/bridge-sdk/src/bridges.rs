@@ -0,0 +1,190 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Messages bridges, supported by the SDK.
+
+use crate::MessagesBridge;
+
+use bp_messages::LaneId;
+use relay_millau_client::Millau;
+use relay_pass3d_client::Pass3d;
+use relay_pass3dt_client::Pass3dt;
+use relay_rialto_client::Rialto;
+use relay_substrate_client::{BalanceOf, CallOf};
+
+/// Pass3d -> Pass3dt messages bridge.
+pub struct Pass3dToPass3dt;
+
+impl MessagesBridge for Pass3dToPass3dt {
+	type Source = Pass3d;
+	type Target = Pass3dt;
+
+	const ESTIMATE_MESSAGE_FEE_METHOD: &'static str =
+		bp_pass3dt::TO_PASS3DT_ESTIMATE_MESSAGE_FEE_METHOD;
+
+	fn encode_send_message_call(
+		lane: LaneId,
+		payload: Vec<u8>,
+		fee: BalanceOf<Pass3d>,
+	) -> CallOf<Pass3d> {
+		pass3d_runtime::Call::BridgePass3dtMessages(pass3d_runtime::MessagesCall::send_message {
+			lane_id: lane,
+			payload,
+			delivery_and_dispatch_fee: fee,
+		})
+	}
+
+	fn is_delivery_call(call: &CallOf<Pass3dt>) -> bool {
+		matches!(
+			call,
+			pass3dt_runtime::Call::BridgePass3dMessages(
+				pass3dt_runtime::MessagesCall::receive_messages_proof { .. }
+			)
+		)
+	}
+
+	fn is_confirmation_call(call: &CallOf<Pass3d>) -> bool {
+		matches!(
+			call,
+			pass3d_runtime::Call::BridgePass3dtMessages(
+				pass3d_runtime::MessagesCall::receive_messages_delivery_proof { .. }
+			)
+		)
+	}
+}
+
+/// Pass3dt -> Pass3d messages bridge.
+pub struct Pass3dtToPass3d;
+
+impl MessagesBridge for Pass3dtToPass3d {
+	type Source = Pass3dt;
+	type Target = Pass3d;
+
+	const ESTIMATE_MESSAGE_FEE_METHOD: &'static str =
+		bp_pass3d::TO_PASS3D_ESTIMATE_MESSAGE_FEE_METHOD;
+
+	fn encode_send_message_call(
+		lane: LaneId,
+		payload: Vec<u8>,
+		fee: BalanceOf<Pass3dt>,
+	) -> CallOf<Pass3dt> {
+		pass3dt_runtime::Call::BridgePass3dMessages(pass3dt_runtime::MessagesCall::send_message {
+			lane_id: lane,
+			payload,
+			delivery_and_dispatch_fee: fee,
+		})
+	}
+
+	fn is_delivery_call(call: &CallOf<Pass3d>) -> bool {
+		matches!(
+			call,
+			pass3d_runtime::Call::BridgePass3dtMessages(
+				pass3d_runtime::MessagesCall::receive_messages_proof { .. }
+			)
+		)
+	}
+
+	fn is_confirmation_call(call: &CallOf<Pass3dt>) -> bool {
+		matches!(
+			call,
+			pass3dt_runtime::Call::BridgePass3dMessages(
+				pass3dt_runtime::MessagesCall::receive_messages_delivery_proof { .. }
+			)
+		)
+	}
+}
+
+/// Millau -> Rialto messages bridge.
+pub struct MillauToRialto;
+
+impl MessagesBridge for MillauToRialto {
+	type Source = Millau;
+	type Target = Rialto;
+
+	const ESTIMATE_MESSAGE_FEE_METHOD: &'static str =
+		bp_rialto::TO_RIALTO_ESTIMATE_MESSAGE_FEE_METHOD;
+
+	fn encode_send_message_call(
+		lane: LaneId,
+		payload: Vec<u8>,
+		fee: BalanceOf<Millau>,
+	) -> CallOf<Millau> {
+		millau_runtime::Call::BridgeRialtoMessages(millau_runtime::MessagesCall::send_message {
+			lane_id: lane,
+			payload,
+			delivery_and_dispatch_fee: fee,
+		})
+	}
+
+	fn is_delivery_call(call: &CallOf<Rialto>) -> bool {
+		matches!(
+			call,
+			rialto_runtime::Call::BridgeMillauMessages(
+				rialto_runtime::MessagesCall::receive_messages_proof { .. }
+			)
+		)
+	}
+
+	fn is_confirmation_call(call: &CallOf<Millau>) -> bool {
+		matches!(
+			call,
+			millau_runtime::Call::BridgeRialtoMessages(
+				millau_runtime::MessagesCall::receive_messages_delivery_proof { .. }
+			)
+		)
+	}
+}
+
+/// Rialto -> Millau messages bridge.
+pub struct RialtoToMillau;
+
+impl MessagesBridge for RialtoToMillau {
+	type Source = Rialto;
+	type Target = Millau;
+
+	const ESTIMATE_MESSAGE_FEE_METHOD: &'static str =
+		bp_millau::TO_MILLAU_ESTIMATE_MESSAGE_FEE_METHOD;
+
+	fn encode_send_message_call(
+		lane: LaneId,
+		payload: Vec<u8>,
+		fee: BalanceOf<Rialto>,
+	) -> CallOf<Rialto> {
+		rialto_runtime::Call::BridgeMillauMessages(rialto_runtime::MessagesCall::send_message {
+			lane_id: lane,
+			payload,
+			delivery_and_dispatch_fee: fee,
+		})
+	}
+
+	fn is_delivery_call(call: &CallOf<Millau>) -> bool {
+		matches!(
+			call,
+			millau_runtime::Call::BridgeRialtoMessages(
+				millau_runtime::MessagesCall::receive_messages_proof { .. }
+			)
+		)
+	}
+
+	fn is_confirmation_call(call: &CallOf<Rialto>) -> bool {
+		matches!(
+			call,
+			rialto_runtime::Call::BridgeMillauMessages(
+				rialto_runtime::MessagesCall::receive_messages_delivery_proof { .. }
+			)
+		)
+	}
+}
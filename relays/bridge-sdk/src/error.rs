@@ -0,0 +1,46 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+use relay_substrate_client::Error as SubstrateError;
+use sp_core::Bytes;
+use std::time::Duration;
+use thiserror::Error;
+
+/// Result type used by the bridge SDK.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Bridge SDK errors.
+#[derive(Error, Debug)]
+pub enum Error {
+	/// Error of the Substrate client.
+	#[error("{0}")]
+	Client(#[from] SubstrateError),
+	/// Runtime has failed to estimate message fee.
+	#[error("Unable to decode fee from: {0:?}")]
+	FeeEstimationFailed(Bytes),
+	/// We have lost track of the message transaction.
+	#[error("Message transaction has been lost")]
+	TransactionLost,
+	/// The message transaction has been finalized, but message has not been accepted.
+	#[error("Message has not been accepted by the source chain: {0}")]
+	MessageNotAccepted(String),
+	/// Unable to find fee, paid for the message transaction.
+	#[error("Unable to find fee, paid for the {0}")]
+	TransactionFeeNotFound(String),
+	/// The message has not been delivered within given timeout.
+	#[error("Message has not been delivered within {0:?}")]
+	Timeout(Duration),
+}
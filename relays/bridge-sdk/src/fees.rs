@@ -0,0 +1,392 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Measuring fees that are actually paid for message delivery and confirmation.
+//!
+//! Runtime upgrades may change weights of message delivery and confirmation transactions
+//! without updating the message fee estimation. The code in this module sends a message, finds
+//! transactions that have delivered and confirmed it and compares fees, paid for these
+//! transactions, with the estimated message fee.
+
+use crate::{
+	estimate_fee_with_conversion_rate, send_message, Bridge, Error, MessagesBridge, Result,
+};
+
+use bp_messages::{
+	storage_keys::{inbound_lane_data_key, outbound_lane_data_key},
+	InboundLaneData, LaneId, MessageNonce, OutboundLaneData,
+};
+use codec::Decode;
+use relay_substrate_client::{
+	AccountIdOf, AccountKeyPairOf, BalanceOf, BlockNumberOf, BlockWithJustification, CallOf, Chain,
+	Client, HashOf, TransactionSignScheme,
+};
+use serde::Serialize;
+use sp_core::{storage::StorageKey, Pair};
+use sp_runtime::{
+	traits::{Hash, One, Saturating, UniqueSaturatedInto},
+	FixedPointNumber, FixedU128,
+};
+use std::{fmt::Write, time::Duration};
+
+/// Name of the transaction payment pallet.
+const TRANSACTION_PAYMENT_PALLET_NAME: &str = "TransactionPayment";
+/// Name of the transaction payment pallet event that is deposited when fee is paid.
+const TRANSACTION_FEE_PAID_EVENT_NAME: &str = "TransactionFeePaid";
+
+/// Fees of a single message.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct MessageFees {
+	/// Name of the message.
+	pub name: String,
+	/// Size of the message payload.
+	pub payload_size: usize,
+	/// Fee, estimated by the source chain runtime (in source chain tokens).
+	pub estimated_fee: u128,
+	/// Fee, paid by the relayer for the message delivery transaction (in target chain tokens).
+	pub delivery_fee: u128,
+	/// Fee, paid by the relayer for the delivery confirmation transaction (in source chain
+	/// tokens).
+	pub confirmation_fee: u128,
+	/// Sum of delivery and confirmation fees (in source chain tokens).
+	pub actual_fee: u128,
+}
+
+impl MessageFees {
+	/// Create message fees using given target to source tokens conversion rate.
+	pub fn new(
+		name: String,
+		payload_size: usize,
+		estimated_fee: u128,
+		delivery_fee: u128,
+		confirmation_fee: u128,
+		conversion_rate: FixedU128,
+	) -> Self {
+		let actual_fee = conversion_rate
+			.saturating_mul_int(delivery_fee)
+			.saturating_add(confirmation_fee);
+		MessageFees {
+			name,
+			payload_size,
+			estimated_fee,
+			delivery_fee,
+			confirmation_fee,
+			actual_fee,
+		}
+	}
+
+	/// Returns relative difference between estimated and actual fees.
+	///
+	/// Positive value means that the fee has been overestimated.
+	pub fn estimate_margin(&self) -> f64 {
+		if self.actual_fee == 0 {
+			return if self.estimated_fee == 0 { 0.0 } else { f64::INFINITY }
+		}
+
+		(self.estimated_fee as f64 - self.actual_fee as f64) / self.actual_fee as f64
+	}
+
+	/// Returns true if the estimated fee covers the actual fee and is not larger than the
+	/// actual fee plus given tolerance.
+	pub fn is_within_tolerance(&self, tolerance: f64) -> bool {
+		let margin = self.estimate_margin();
+		(0.0..=tolerance).contains(&margin)
+	}
+}
+
+/// Report of fees, paid for messages, sent over the bridge.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct FeesReport {
+	/// Name of the source chain.
+	pub source: String,
+	/// Name of the target chain.
+	pub target: String,
+	/// Target to source tokens conversion rate, used to estimate fees.
+	pub conversion_rate: f64,
+	/// Maximal relative overestimation of the message fee.
+	pub tolerance: f64,
+	/// Fees of every sent message.
+	pub messages: Vec<MessageFees>,
+}
+
+impl FeesReport {
+	/// Create empty report for given bridge.
+	pub fn new<B: MessagesBridge>(conversion_rate: FixedU128, tolerance: f64) -> Self {
+		FeesReport {
+			source: B::Source::NAME.into(),
+			target: B::Target::NAME.into(),
+			conversion_rate: conversion_rate.to_float(),
+			tolerance,
+			messages: Vec::new(),
+		}
+	}
+
+	/// Returns all messages with estimated fee outside of the tolerated range.
+	pub fn violations(&self) -> Vec<&MessageFees> {
+		self.messages
+			.iter()
+			.filter(|fees| !fees.is_within_tolerance(self.tolerance))
+			.collect()
+	}
+
+	/// Render report as a markdown table.
+	pub fn to_markdown(&self) -> String {
+		let mut markdown = format!(
+			"### {} -> {}\n\nConversion rate: {}. Tolerance: {:.2}%.\n\n",
+			self.source,
+			self.target,
+			self.conversion_rate,
+			self.tolerance * 100.0,
+		);
+		markdown.push_str(
+			"| Message | Size | Estimated fee | Delivery fee | Confirmation fee | Actual fee | Margin | Status |\n",
+		);
+		markdown.push_str("|---|---|---|---|---|---|---|---|\n");
+		for fees in &self.messages {
+			let _ = writeln!(
+				markdown,
+				"| {} | {} | {} | {} | {} | {} | {:+.2}% | {} |",
+				fees.name,
+				fees.payload_size,
+				fees.estimated_fee,
+				fees.delivery_fee,
+				fees.confirmation_fee,
+				fees.actual_fee,
+				fees.estimate_margin() * 100.0,
+				if fees.is_within_tolerance(self.tolerance) { "ok" } else { "FAIL" },
+			);
+		}
+		markdown
+	}
+
+	/// Render report as a JSON document.
+	pub fn to_json(&self) -> String {
+		serde_json::to_string_pretty(self).expect("report only contains serializable fields; qed")
+	}
+}
+
+/// Send message with given payload and measure fees, paid by relayer for its delivery and
+/// confirmation.
+///
+/// Fees are read from the `TransactionFeePaid` events of the delivery and confirmation
+/// transactions. If these transactions are also delivering or confirming other messages, the
+/// whole fee is attributed to our message. So it is better to use dedicated lane for
+/// measurements.
+pub async fn measure_message_fees<B: MessagesBridge>(
+	bridge: &Bridge<B>,
+	signer: AccountKeyPairOf<B::Source>,
+	lane: LaneId,
+	name: String,
+	payload: Vec<u8>,
+	conversion_rate: FixedU128,
+	timeout: Duration,
+) -> Result<MessageFees>
+where
+	AccountIdOf<B::Source>: From<<AccountKeyPairOf<B::Source> as Pair>::Public>,
+{
+	let payload_size = payload.len();
+	let estimated_fee =
+		estimate_fee_with_conversion_rate(bridge, lane, &payload, Some(conversion_rate)).await?;
+
+	// remember where to start looking for delivery and confirmation transactions
+	let source_start = bridge.source.best_finalized_header_number().await?;
+	let target_start = bridge.target.best_finalized_header_number().await?;
+
+	let nonce = send_message(bridge, signer, lane, payload)
+		.await?
+		.wait_delivered(timeout)
+		.await?;
+
+	let delivery_block = find_lane_block::<B::Target, InboundLaneData<AccountIdOf<B::Source>>>(
+		&bridge.target,
+		target_start,
+		inbound_lane_data_key(B::Source::WITH_CHAIN_MESSAGES_PALLET_NAME, &lane),
+		|data| data.last_delivered_nonce(),
+		nonce,
+	)
+	.await?;
+	let delivery_fee = transaction_fee_paid(&bridge.target, delivery_block, B::is_delivery_call)
+		.await?
+		.ok_or_else(|| {
+			Error::TransactionFeeNotFound(format!(
+				"{:?}/{} delivery transaction at {}",
+				lane,
+				nonce,
+				B::Target::NAME
+			))
+		})?;
+
+	let confirmation_block = async_std::future::timeout(
+		timeout,
+		find_lane_block::<B::Source, OutboundLaneData>(
+			&bridge.source,
+			source_start,
+			outbound_lane_data_key(B::Target::WITH_CHAIN_MESSAGES_PALLET_NAME, &lane),
+			|data| data.latest_received_nonce,
+			nonce,
+		),
+	)
+	.await
+	.map_err(|_| Error::Timeout(timeout))??;
+	let confirmation_fee =
+		transaction_fee_paid(&bridge.source, confirmation_block, B::is_confirmation_call)
+			.await?
+			.ok_or_else(|| {
+				Error::TransactionFeeNotFound(format!(
+					"{:?}/{} confirmation transaction at {}",
+					lane,
+					nonce,
+					B::Source::NAME
+				))
+			})?;
+
+	let fees = MessageFees::new(
+		name,
+		payload_size,
+		estimated_fee.unique_saturated_into(),
+		delivery_fee.unique_saturated_into(),
+		confirmation_fee.unique_saturated_into(),
+		conversion_rate,
+	);
+	log::info!(
+		target: "bridge",
+		"Message {:?}/{} fees: estimated {}, actual {} (delivery {}, confirmation {})",
+		lane,
+		nonce,
+		fees.estimated_fee,
+		fees.actual_fee,
+		fees.delivery_fee,
+		fees.confirmation_fee,
+	);
+
+	Ok(fees)
+}
+
+/// Find the first finalized block, starting from given one, where the lane nonce (read from
+/// given storage value) has reached given nonce.
+///
+/// Waits until such block is finalized.
+async fn find_lane_block<C: Chain, LaneData: Decode + Send + 'static>(
+	client: &Client<C>,
+	mut block_number: BlockNumberOf<C>,
+	lane_data_key: StorageKey,
+	lane_nonce: fn(&LaneData) -> MessageNonce,
+	nonce: MessageNonce,
+) -> Result<HashOf<C>> {
+	loop {
+		let best_finalized_number = client.best_finalized_header_number().await?;
+		while block_number <= best_finalized_number {
+			let block_hash = client.block_hash_by_number(block_number).await?;
+			let lane_data: Option<LaneData> =
+				client.storage_value(lane_data_key.clone(), Some(block_hash)).await?;
+			if lane_data.map(|data| lane_nonce(&data)).unwrap_or(0) >= nonce {
+				return Ok(block_hash)
+			}
+
+			block_number = block_number.saturating_add(One::one());
+		}
+
+		async_std::task::sleep(C::AVERAGE_BLOCK_INTERVAL).await;
+	}
+}
+
+/// Returns fee, paid for the first transaction in given block that is matching given predicate.
+async fn transaction_fee_paid<C: TransactionSignScheme<Chain = C>>(
+	client: &Client<C>,
+	block_hash: HashOf<C>,
+	is_expected_call: fn(&CallOf<C>) -> bool,
+) -> Result<Option<BalanceOf<C>>> {
+	let block = client.get_block(Some(block_hash)).await?;
+	for encoded_extrinsic in block.extrinsics() {
+		let call = C::SignedTransaction::decode(&mut &encoded_extrinsic[..])
+			.ok()
+			.and_then(C::parse_transaction)
+			.and_then(|transaction| transaction.call.into_decoded().ok());
+		if !call.map(|call| is_expected_call(&call)).unwrap_or(false) {
+			continue
+		}
+
+		let fee_paid: Option<(AccountIdOf<C>, BalanceOf<C>, BalanceOf<C>)> = client
+			.extrinsic_event(
+				block_hash,
+				C::Hasher::hash(&encoded_extrinsic),
+				TRANSACTION_PAYMENT_PALLET_NAME,
+				TRANSACTION_FEE_PAID_EVENT_NAME,
+			)
+			.await?;
+		if let Some((_, actual_fee, _)) = fee_paid {
+			return Ok(Some(actual_fee))
+		}
+	}
+
+	Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn fees(estimated_fee: u128, delivery_fee: u128, confirmation_fee: u128) -> MessageFees {
+		MessageFees::new(
+			"test".into(),
+			42,
+			estimated_fee,
+			delivery_fee,
+			confirmation_fee,
+			FixedU128::saturating_from_rational(3, 2),
+		)
+	}
+
+	#[test]
+	fn actual_fee_is_computed_using_conversion_rate() {
+		assert_eq!(fees(0, 100, 50).actual_fee, 200);
+	}
+
+	#[test]
+	fn estimate_within_tolerance_is_accepted() {
+		assert!(fees(200, 100, 50).is_within_tolerance(0.1));
+		assert!(fees(220, 100, 50).is_within_tolerance(0.1));
+	}
+
+	#[test]
+	fn underestimated_fee_is_rejected() {
+		let fees = fees(199, 100, 50);
+		assert!(fees.estimate_margin() < 0.0);
+		assert!(!fees.is_within_tolerance(0.1));
+	}
+
+	#[test]
+	fn overestimated_fee_is_rejected() {
+		assert!(!fees(221, 100, 50).is_within_tolerance(0.1));
+	}
+
+	#[test]
+	fn report_lists_violations() {
+		let report = FeesReport {
+			source: "Source".into(),
+			target: "Target".into(),
+			conversion_rate: 1.5,
+			tolerance: 0.1,
+			messages: vec![fees(200, 100, 50), fees(100, 100, 50), fees(300, 100, 50)],
+		};
+
+		assert_eq!(report.violations(), vec![&report.messages[1], &report.messages[2]]);
+		let markdown = report.to_markdown();
+		assert_eq!(markdown.matches("| ok |").count(), 1);
+		assert_eq!(markdown.matches("| FAIL |").count(), 2);
+		assert!(report.to_json().contains("\"estimated_fee\": 300"));
+	}
+}
@@ -0,0 +1,167 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Minimal SDK for dApp backends that are sending messages over the bridge.
+//!
+//! The SDK allows to estimate message delivery and dispatch fee, to send message and to track
+//! its delivery to the target chain. It doesn't run any relay loops - someone else must be
+//! relaying headers and messages between chains.
+
+#![warn(missing_docs)]
+
+use bp_messages::LaneId;
+use codec::{Decode, Encode};
+use relay_substrate_client::{
+	AccountIdOf, AccountKeyPairOf, BalanceOf, CallOf, Chain, ChainWithMessages, Client, SignParam,
+	TransactionSignScheme, UnsignedTransaction,
+};
+use sp_core::Pair;
+use sp_runtime::FixedU128;
+
+pub use crate::{
+	error::{Error, Result},
+	message::MessageHandle,
+};
+
+pub mod bridges;
+pub mod fees;
+
+mod error;
+mod message;
+
+/// Messages bridge between two chains.
+pub trait MessagesBridge: 'static + Send + Sync {
+	/// Chain where messages are sent from.
+	type Source: ChainWithMessages + TransactionSignScheme<Chain = Self::Source>;
+	/// Chain where messages are delivered to.
+	type Target: ChainWithMessages + TransactionSignScheme<Chain = Self::Target>;
+
+	/// Name of the source chain runtime method used to estimate the message delivery and dispatch
+	/// fee.
+	const ESTIMATE_MESSAGE_FEE_METHOD: &'static str;
+
+	/// Encode source chain call that sends message over the bridge.
+	fn encode_send_message_call(
+		lane: LaneId,
+		payload: Vec<u8>,
+		fee: BalanceOf<Self::Source>,
+	) -> CallOf<Self::Source>;
+
+	/// Returns true if given target chain call delivers messages from the source chain.
+	fn is_delivery_call(call: &CallOf<Self::Target>) -> bool;
+
+	/// Returns true if given source chain call confirms messages delivery to the target chain.
+	fn is_confirmation_call(call: &CallOf<Self::Source>) -> bool;
+}
+
+/// Clients of chains, connected by the messages bridge.
+pub struct Bridge<B: MessagesBridge> {
+	/// Client of the source chain.
+	pub source: Client<B::Source>,
+	/// Client of the target chain.
+	pub target: Client<B::Target>,
+}
+
+impl<B: MessagesBridge> Bridge<B> {
+	/// Create bridge using given clients.
+	pub fn new(source: Client<B::Source>, target: Client<B::Target>) -> Self {
+		Bridge { source, target }
+	}
+}
+
+impl<B: MessagesBridge> Clone for Bridge<B> {
+	fn clone(&self) -> Self {
+		Bridge { source: self.source.clone(), target: self.target.clone() }
+	}
+}
+
+/// Estimate delivery and dispatch fee of the message with given payload, using conversion rate
+/// from the source chain runtime storage.
+pub async fn estimate_fee<B: MessagesBridge>(
+	bridge: &Bridge<B>,
+	lane: LaneId,
+	payload: &[u8],
+) -> Result<BalanceOf<B::Source>> {
+	estimate_fee_with_conversion_rate(bridge, lane, payload, None).await
+}
+
+/// Estimate delivery and dispatch fee of the message with given payload, using given target to
+/// source tokens conversion rate.
+///
+/// If the conversion rate is `None`, the rate from the source chain runtime storage is used.
+pub async fn estimate_fee_with_conversion_rate<B: MessagesBridge>(
+	bridge: &Bridge<B>,
+	lane: LaneId,
+	payload: &[u8],
+	conversion_rate_override: Option<FixedU128>,
+) -> Result<BalanceOf<B::Source>> {
+	let encoded_response = bridge
+		.source
+		.state_call(
+			B::ESTIMATE_MESSAGE_FEE_METHOD.into(),
+			(lane, payload, conversion_rate_override).encode().into(),
+			None,
+		)
+		.await?;
+	let decoded_response: Option<BalanceOf<B::Source>> =
+		Decode::decode(&mut &encoded_response.0[..])
+			.map_err(relay_substrate_client::Error::ResponseParseFailed)?;
+	decoded_response.ok_or(Error::FeeEstimationFailed(encoded_response))
+}
+
+/// Send message with given payload over the bridge.
+///
+/// The delivery and dispatch fee is estimated using [`estimate_fee`]. Returned handle may be
+/// used to wait until the message is delivered to the target chain.
+pub async fn send_message<B: MessagesBridge>(
+	bridge: &Bridge<B>,
+	signer: AccountKeyPairOf<B::Source>,
+	lane: LaneId,
+	payload: Vec<u8>,
+) -> Result<MessageHandle<B>>
+where
+	AccountIdOf<B::Source>: From<<AccountKeyPairOf<B::Source> as Pair>::Public>,
+{
+	let fee = estimate_fee(bridge, lane, &payload).await?;
+	log::info!(
+		target: "bridge",
+		"Sending message to {} over lane {:?}. Size: {}. Fee: {:?}",
+		B::Target::NAME,
+		lane,
+		payload.len(),
+		fee,
+	);
+
+	let (spec_version, transaction_version) = bridge.source.simple_runtime_version().await?;
+	let send_message_call = B::encode_send_message_call(lane, payload, fee);
+	let transaction = bridge
+		.source
+		.submit_and_watch_signed_extrinsic(
+			signer.public().into(),
+			SignParam::<B::Source> {
+				spec_version,
+				transaction_version,
+				genesis_hash: *bridge.source.genesis_hash(),
+				signer,
+			},
+			move |_, transaction_nonce| {
+				Ok(UnsignedTransaction::new(send_message_call.into(), transaction_nonce))
+			},
+		)
+		.await?;
+
+	Ok(MessageHandle::new(bridge.clone(), lane, transaction))
+}
@@ -0,0 +1,221 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Tracking of sent messages.
+
+use crate::{Bridge, Error, MessagesBridge, Result};
+
+use async_trait::async_trait;
+use bp_messages::{storage_keys::InboundLanesKeyProvider, LaneId, MessageNonce};
+use relay_substrate_client::{
+	AccountIdOf, Chain, ChainWithMessages, Client,
+	TransactionTracker as SubstrateTransactionTracker,
+};
+use relay_utils::{TrackedTransactionStatus, TransactionTracker};
+use std::time::Duration;
+
+/// Name of the messages pallet event that is deposited when the message is accepted.
+const MESSAGE_ACCEPTED_EVENT_NAME: &str = "MessageAccepted";
+
+/// Handle of the message that has been sent over the bridge.
+pub struct MessageHandle<B: MessagesBridge> {
+	bridge: Bridge<B>,
+	lane: LaneId,
+	transaction: SubstrateTransactionTracker<B::Source, Client<B::Source>>,
+}
+
+impl<B: MessagesBridge> MessageHandle<B> {
+	/// Create handle of the message, sent by given transaction.
+	pub(crate) fn new(
+		bridge: Bridge<B>,
+		lane: LaneId,
+		transaction: SubstrateTransactionTracker<B::Source, Client<B::Source>>,
+	) -> Self {
+		MessageHandle { bridge, lane, transaction }
+	}
+
+	/// Returns lane that the message has been sent over.
+	pub fn lane(&self) -> LaneId {
+		self.lane
+	}
+
+	/// Wait until the message is delivered to the target chain.
+	///
+	/// First we wait until the message transaction is finalized at the source chain and read
+	/// nonce, assigned to the message, from the source chain events. Then we're polling the
+	/// target chain inbound lane state until the message is delivered. Returns nonce of the
+	/// delivered message.
+	pub async fn wait_delivered(self, timeout: Duration) -> Result<MessageNonce> {
+		async_std::future::timeout(timeout, self.do_wait_delivered())
+			.await
+			.map_err(|_| Error::Timeout(timeout))?
+	}
+
+	async fn do_wait_delivered(self) -> Result<MessageNonce> {
+		let transaction_hash = self.transaction.transaction_hash();
+		let at_block = match self.transaction.wait().await {
+			TrackedTransactionStatus::Finalized(at_block) => at_block,
+			TrackedTransactionStatus::Lost => return Err(Error::TransactionLost),
+		};
+
+		let accepted: Option<(LaneId, MessageNonce)> = self
+			.bridge
+			.source
+			.extrinsic_event(
+				at_block.1,
+				transaction_hash,
+				B::Target::WITH_CHAIN_MESSAGES_PALLET_NAME,
+				MESSAGE_ACCEPTED_EVENT_NAME,
+			)
+			.await?;
+		let nonce = match accepted {
+			Some((_, nonce)) => nonce,
+			None => {
+				let dispatch_error = self
+					.bridge
+					.source
+					.extrinsic_dispatch_error(at_block.1, transaction_hash)
+					.await?;
+				return Err(Error::MessageNotAccepted(
+					dispatch_error.unwrap_or_else(|| "no MessageAccepted event".into()),
+				))
+			},
+		};
+		log::info!(
+			target: "bridge",
+			"Message {:?}/{} has been accepted by {} at block {:?}",
+			self.lane,
+			nonce,
+			B::Source::NAME,
+			at_block,
+		);
+
+		wait_nonce_delivered(
+			&InboundLane::<B> { client: self.bridge.target, lane: self.lane },
+			nonce,
+			B::Target::AVERAGE_BLOCK_INTERVAL,
+		)
+		.await?;
+		log::info!(
+			target: "bridge",
+			"Message {:?}/{} has been delivered to {}",
+			self.lane,
+			nonce,
+			B::Target::NAME,
+		);
+
+		Ok(nonce)
+	}
+}
+
+/// Inbound lane at the target chain.
+#[async_trait]
+trait InboundLaneState: Send + Sync {
+	/// Returns nonce of the latest message, delivered over the lane.
+	async fn last_delivered_nonce(&self) -> Result<MessageNonce>;
+}
+
+/// Inbound lane at the target chain of the bridge.
+struct InboundLane<B: MessagesBridge> {
+	client: Client<B::Target>,
+	lane: LaneId,
+}
+
+#[async_trait]
+impl<B: MessagesBridge> InboundLaneState for InboundLane<B> {
+	async fn last_delivered_nonce(&self) -> Result<MessageNonce> {
+		let inbound_lane_data = self
+			.client
+			.storage_map_value::<InboundLanesKeyProvider<AccountIdOf<B::Source>>>(
+				B::Source::WITH_CHAIN_MESSAGES_PALLET_NAME,
+				&self.lane,
+				None,
+			)
+			.await?;
+		Ok(inbound_lane_data.map(|data| data.last_delivered_nonce()).unwrap_or(0))
+	}
+}
+
+/// Poll inbound lane state until message with given nonce is delivered.
+async fn wait_nonce_delivered(
+	inbound_lane: &impl InboundLaneState,
+	nonce: MessageNonce,
+	poll_interval: Duration,
+) -> Result<()> {
+	loop {
+		if inbound_lane.last_delivered_nonce().await? >= nonce {
+			return Ok(())
+		}
+
+		async_std::task::sleep(poll_interval).await;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::sync::Mutex;
+
+	struct TestInboundLane {
+		responses: Mutex<Vec<Result<MessageNonce>>>,
+	}
+
+	impl TestInboundLane {
+		fn new(mut responses: Vec<Result<MessageNonce>>) -> Self {
+			responses.reverse();
+			TestInboundLane { responses: Mutex::new(responses) }
+		}
+
+		fn remaining_responses(&self) -> usize {
+			self.responses.lock().unwrap().len()
+		}
+	}
+
+	#[async_trait]
+	impl InboundLaneState for TestInboundLane {
+		async fn last_delivered_nonce(&self) -> Result<MessageNonce> {
+			self.responses.lock().unwrap().pop().expect("unexpected inbound lane read")
+		}
+	}
+
+	#[async_std::test]
+	async fn waits_until_nonce_is_delivered() {
+		let inbound_lane = TestInboundLane::new(vec![Ok(0), Ok(9), Ok(10), Ok(11)]);
+		wait_nonce_delivered(&inbound_lane, 10, Duration::from_millis(1)).await.unwrap();
+		assert_eq!(inbound_lane.remaining_responses(), 1);
+	}
+
+	#[async_std::test]
+	async fn returns_immediately_if_nonce_is_already_delivered() {
+		let inbound_lane = TestInboundLane::new(vec![Ok(20)]);
+		wait_nonce_delivered(&inbound_lane, 10, Duration::from_secs(3600))
+			.await
+			.unwrap();
+		assert_eq!(inbound_lane.remaining_responses(), 0);
+	}
+
+	#[async_std::test]
+	async fn fails_to_read_inbound_lane_state() {
+		let inbound_lane = TestInboundLane::new(vec![
+			Err(Error::Client(relay_substrate_client::Error::MissingMandatoryStorageValue)),
+			Ok(10),
+		]);
+		assert!(matches!(
+			wait_nonce_delivered(&inbound_lane, 10, Duration::from_millis(1)).await,
+			Err(Error::Client(relay_substrate_client::Error::MissingMandatoryStorageValue)),
+		));
+	}
+}
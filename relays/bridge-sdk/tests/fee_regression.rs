@@ -0,0 +1,243 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Regression tests that are comparing estimated message fees with fees, actually paid by
+//! relayers for message delivery and confirmation.
+//!
+//! Tests are only compiled with the `fee-regression` feature and require running bridge: nodes
+//! of both chains plus headers and messages relay between them. Every bridge is configured
+//! using environment variables:
+//!
+//! - `FEE_REGRESSION_<CHAIN>_ENDPOINT`: `host:port` of the chain node. Bridge is skipped if
+//!   endpoint of any of its chains is not set;
+//! - `FEE_REGRESSION_SIGNER`: URI of the message sender account (`//Alice` by default);
+//! - `FEE_REGRESSION_LANE`: hex-encoded lane id (`00000000` by default);
+//! - `FEE_REGRESSION_CONVERSION_RATE`: target to source tokens conversion rate (`1.0` by default);
+//! - `FEE_REGRESSION_TOLERANCE`: maximal relative overestimation of the message fee (`0.2` by
+//!   default);
+//! - `FEE_REGRESSION_REPORT_DIR`: directory where markdown and JSON reports are written to
+//!   (`target/fee-regression` by default).
+
+#![cfg(feature = "fee-regression")]
+
+use bridge_sdk::{
+	bridges::{MillauToRialto, Pass3dToPass3dt, Pass3dtToPass3d, RialtoToMillau},
+	fees::{measure_message_fees, FeesReport},
+	Bridge, MessagesBridge,
+};
+use codec::Encode;
+use relay_substrate_client::{
+	AccountIdOf, AccountKeyPairOf, Chain, ChainRuntimeVersion, Client, ConnectionParams,
+};
+use sp_core::{Get, Pair};
+use sp_runtime::FixedU128;
+use std::{path::PathBuf, time::Duration};
+use xcm::{
+	latest::prelude::{GlobalConsensus, Instruction, NetworkId, Xcm, X1},
+	VersionedInteriorMultiLocation, VersionedXcm,
+};
+
+/// Maximal time we're waiting for the message delivery and confirmation.
+const MESSAGE_TIMEOUT: Duration = Duration::from_secs(600);
+/// Size of the remark, dispatched by the mid-size `Transact` message.
+const TRANSACT_REMARK_SIZE: usize = 1024;
+/// Maximal weight of the call, dispatched by the mid-size `Transact` message.
+const TRANSACT_MAX_WEIGHT: u64 = 1_000_000_000;
+
+#[async_std::test]
+async fn pass3d_to_pass3dt_fee_estimate_matches_actual_fee() {
+	check_bridge_fees::<Pass3dToPass3dt>(
+		pass3d_runtime::xcm_config::Pass3dtNetwork::get(),
+		pass3dt_runtime::Call::System(pass3dt_runtime::SystemCall::remark {
+			remark: vec![42; TRANSACT_REMARK_SIZE],
+		})
+		.encode(),
+	)
+	.await;
+}
+
+#[async_std::test]
+async fn pass3dt_to_pass3d_fee_estimate_matches_actual_fee() {
+	check_bridge_fees::<Pass3dtToPass3d>(
+		pass3dt_runtime::xcm_config::Pass3dNetwork::get(),
+		pass3d_runtime::Call::System(pass3d_runtime::SystemCall::remark {
+			remark: vec![42; TRANSACT_REMARK_SIZE],
+		})
+		.encode(),
+	)
+	.await;
+}
+
+#[async_std::test]
+async fn millau_to_rialto_fee_estimate_matches_actual_fee() {
+	check_bridge_fees::<MillauToRialto>(
+		millau_runtime::xcm_config::RialtoNetwork::get(),
+		rialto_runtime::Call::System(rialto_runtime::SystemCall::remark {
+			remark: vec![42; TRANSACT_REMARK_SIZE],
+		})
+		.encode(),
+	)
+	.await;
+}
+
+#[async_std::test]
+async fn rialto_to_millau_fee_estimate_matches_actual_fee() {
+	check_bridge_fees::<RialtoToMillau>(
+		rialto_runtime::xcm_config::MillauNetwork::get(),
+		millau_runtime::Call::System(millau_runtime::SystemCall::remark {
+			remark: vec![42; TRANSACT_REMARK_SIZE],
+		})
+		.encode(),
+	)
+	.await;
+}
+
+/// Send all representative messages over the bridge, write report and check that all
+/// estimates are within tolerance.
+async fn check_bridge_fees<B: MessagesBridge>(target_network: NetworkId, transact_call: Vec<u8>)
+where
+	AccountIdOf<B::Source>: From<<AccountKeyPairOf<B::Source> as Pair>::Public>,
+{
+	let bridge = match connect::<B>().await {
+		Some(bridge) => bridge,
+		None => {
+			println!(
+				"Skipping {} -> {} fee regression test: endpoints are not configured",
+				B::Source::NAME,
+				B::Target::NAME,
+			);
+			return
+		},
+	};
+
+	let signer_uri = env_var("FEE_REGRESSION_SIGNER").unwrap_or_else(|| "//Alice".into());
+	let signer = AccountKeyPairOf::<B::Source>::from_string(&signer_uri, None)
+		.expect("invalid FEE_REGRESSION_SIGNER");
+	let lane = env_var("FEE_REGRESSION_LANE")
+		.map(|lane| lane.parse().expect("invalid FEE_REGRESSION_LANE"))
+		.unwrap_or_default();
+	let conversion_rate = FixedU128::from_float(
+		env_var("FEE_REGRESSION_CONVERSION_RATE")
+			.map(|rate| rate.parse().expect("invalid FEE_REGRESSION_CONVERSION_RATE"))
+			.unwrap_or(1.0),
+	);
+	let tolerance = env_var("FEE_REGRESSION_TOLERANCE")
+		.map(|tolerance| tolerance.parse().expect("invalid FEE_REGRESSION_TOLERANCE"))
+		.unwrap_or(0.2);
+
+	let mut report = FeesReport::new::<B>(conversion_rate, tolerance);
+	for (name, payload) in representative_messages::<B>(target_network, transact_call) {
+		let fees = measure_message_fees(
+			&bridge,
+			signer.clone(),
+			lane,
+			name.into(),
+			payload,
+			conversion_rate,
+			MESSAGE_TIMEOUT,
+		)
+		.await
+		.unwrap_or_else(|e| panic!("Failed to measure fees of {} message: {:?}", name, e));
+		report.messages.push(fees);
+	}
+
+	write_report(&report);
+	let violations = report.violations();
+	assert!(
+		violations.is_empty(),
+		"Estimated fees of {} -> {} messages are out of tolerance:\n{}",
+		B::Source::NAME,
+		B::Target::NAME,
+		report.to_markdown(),
+	);
+}
+
+/// Returns representative messages that are sent over the bridge.
+fn representative_messages<B: MessagesBridge>(
+	target_network: NetworkId,
+	transact_call: Vec<u8>,
+) -> Vec<(&'static str, Vec<u8>)> {
+	let universal_dest = VersionedInteriorMultiLocation::from(X1(GlobalConsensus(target_network)));
+	let tiny_trap =
+		(universal_dest.clone(), VersionedXcm::<()>::from(Xcm::<()>(vec![Instruction::Trap(42)])))
+			.encode();
+	let mid_size_transact = (
+		universal_dest,
+		VersionedXcm::<()>::V2(xcm::v2::Xcm(vec![xcm::v2::Instruction::Transact {
+			origin_type: xcm::v2::OriginKind::SovereignAccount,
+			require_weight_at_most: TRANSACT_MAX_WEIGHT,
+			call: transact_call.into(),
+		}])),
+	)
+		.encode();
+	// the max-size payload isn't a valid XCM message, but relayer pays for its delivery anyway
+	let max_size = maximal_message_size::<B>();
+	let max_size_payload = vec![42; max_size as usize];
+
+	vec![
+		("tiny Trap", tiny_trap),
+		("mid-size Transact", mid_size_transact),
+		("max-size payload", max_size_payload),
+	]
+}
+
+/// Returns maximal size of the message that may be sent over the bridge.
+fn maximal_message_size<B: MessagesBridge>() -> u32 {
+	// assume that both signed extensions and other arguments fit 1KB
+	let maximal_source_message_size = B::Source::max_extrinsic_size() - 1024;
+	let maximal_target_message_size =
+		bridge_runtime_common::messages::target::maximal_incoming_message_size(
+			B::Target::max_extrinsic_size(),
+		);
+	std::cmp::min(maximal_source_message_size, maximal_target_message_size)
+}
+
+/// Connect to bridged chains nodes, if their endpoints are configured.
+async fn connect<B: MessagesBridge>() -> Option<Bridge<B>> {
+	let source = connection_params(B::Source::NAME)?;
+	let target = connection_params(B::Target::NAME)?;
+	Some(Bridge::new(Client::new(source).await, Client::new(target).await))
+}
+
+/// Read connection params of the chain node from `FEE_REGRESSION_<CHAIN>_ENDPOINT`.
+fn connection_params(chain: &str) -> Option<ConnectionParams> {
+	let endpoint = env_var(&format!("FEE_REGRESSION_{}_ENDPOINT", chain.to_uppercase()))?;
+	let (host, port) = endpoint.rsplit_once(':').expect("endpoint must be in host:port format");
+	Some(ConnectionParams {
+		host: host.into(),
+		port: port.parse().expect("invalid endpoint port"),
+		secure: false,
+		chain_runtime_version: ChainRuntimeVersion::Auto,
+	})
+}
+
+/// Write markdown and JSON reports to the report directory.
+fn write_report(report: &FeesReport) {
+	let report_dir = env_var("FEE_REGRESSION_REPORT_DIR")
+		.map(PathBuf::from)
+		.unwrap_or_else(|| PathBuf::from("target/fee-regression"));
+	std::fs::create_dir_all(&report_dir).expect("failed to create report directory");
+
+	let file_name = format!("{}-to-{}", report.source, report.target).to_lowercase();
+	std::fs::write(report_dir.join(format!("{}.md", file_name)), report.to_markdown())
+		.expect("failed to write markdown report");
+	std::fs::write(report_dir.join(format!("{}.json", file_name)), report.to_json())
+		.expect("failed to write JSON report");
+}
+
+fn env_var(name: &str) -> Option<String> {
+	std::env::var(name).ok().filter(|value| !value.is_empty())
+}
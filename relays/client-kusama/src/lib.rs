@@ -62,6 +62,8 @@ impl Chain for Kusama {
 
 impl ChainWithGrandpa for Kusama {
 	const WITH_CHAIN_GRANDPA_PALLET_NAME: &'static str = bp_kusama::WITH_KUSAMA_GRANDPA_PALLET_NAME;
+	const SYNCED_HEADERS_GRANDPA_INFO_METHOD: &'static str =
+		bp_kusama::KUSAMA_SYNCED_HEADERS_GRANDPA_INFO_METHOD;
 }
 
 impl ChainWithBalances for Kusama {
@@ -21,7 +21,8 @@ use codec::{Compact, Decode, Encode};
 use frame_support::weights::Weight;
 use relay_substrate_client::{
 	BalanceOf, Chain, ChainBase, ChainWithBalances, ChainWithGrandpa, ChainWithMessages,
-	Error as SubstrateError, IndexOf, SignParam, TransactionSignScheme, UnsignedTransaction,
+	EncodedOrDecodedCall, Error as SubstrateError, IndexOf, SignParam, TransactionSignScheme,
+	UnsignedTransaction,
 };
 use sp_core::{storage::StorageKey, Pair};
 use sp_runtime::{generic::SignedPayload, traits::IdentifyAccount};
@@ -56,6 +57,8 @@ impl ChainBase for Millau {
 
 impl ChainWithGrandpa for Millau {
 	const WITH_CHAIN_GRANDPA_PALLET_NAME: &'static str = bp_millau::WITH_MILLAU_GRANDPA_PALLET_NAME;
+	const SYNCED_HEADERS_GRANDPA_INFO_METHOD: &'static str =
+		bp_millau::MILLAU_SYNCED_HEADERS_GRANDPA_INFO_METHOD;
 }
 
 impl ChainWithMessages for Millau {
@@ -63,8 +66,14 @@ impl ChainWithMessages for Millau {
 		bp_millau::WITH_MILLAU_MESSAGES_PALLET_NAME;
 	const TO_CHAIN_MESSAGE_DETAILS_METHOD: &'static str =
 		bp_millau::TO_MILLAU_MESSAGE_DETAILS_METHOD;
+	const TO_CHAIN_RECENT_RELAYERS_METHOD: &'static str =
+		bp_millau::TO_MILLAU_RECENT_RELAYERS_METHOD;
 	const FROM_CHAIN_MESSAGE_DETAILS_METHOD: &'static str =
 		bp_millau::FROM_MILLAU_MESSAGE_DETAILS_METHOD;
+	const TO_CHAIN_OUTBOUND_LANE_STATE_METHOD: &'static str =
+		bp_millau::TO_MILLAU_OUTBOUND_LANE_STATE_METHOD;
+	const FROM_CHAIN_INBOUND_LANE_STATE_METHOD: &'static str =
+		bp_millau::FROM_MILLAU_INBOUND_LANE_STATE_METHOD;
 	const PAY_INBOUND_DISPATCH_FEE_WEIGHT_AT_CHAIN: Weight =
 		bp_millau::PAY_INBOUND_DISPATCH_FEE_WEIGHT;
 	const MAX_UNREWARDED_RELAYERS_IN_CONFIRMATION_TX: MessageNonce =
@@ -97,6 +106,42 @@ impl ChainWithBalances for Millau {
 	}
 }
 
+/// Returns payload that is signed by the Millau transaction author.
+fn signed_payload(
+	spec_version: u32,
+	transaction_version: u32,
+	genesis_hash: millau_runtime::Hash,
+	unsigned: UnsignedTransaction<Millau>,
+) -> SignedPayload<EncodedOrDecodedCall<millau_runtime::Call>, millau_runtime::SignedExtra> {
+	SignedPayload::from_raw(
+		unsigned.call,
+		(
+			frame_system::CheckNonZeroSender::<millau_runtime::Runtime>::new(),
+			frame_system::CheckSpecVersion::<millau_runtime::Runtime>::new(),
+			frame_system::CheckTxVersion::<millau_runtime::Runtime>::new(),
+			frame_system::CheckGenesis::<millau_runtime::Runtime>::new(),
+			frame_system::CheckEra::<millau_runtime::Runtime>::from(unsigned.era.frame_era()),
+			frame_system::CheckNonce::<millau_runtime::Runtime>::from(unsigned.nonce),
+			frame_system::CheckWeight::<millau_runtime::Runtime>::new(),
+			pallet_transaction_payment::ChargeTransactionPayment::<millau_runtime::Runtime>::from(
+				unsigned.tip,
+			),
+			millau_runtime::BridgeRejectObsoleteHeadersAndMessages,
+		),
+		(
+			(),
+			spec_version,
+			transaction_version,
+			genesis_hash,
+			unsigned.era.signed_payload(genesis_hash),
+			(),
+			(),
+			(),
+			(),
+		),
+	)
+}
+
 impl TransactionSignScheme for Millau {
 	type Chain = Millau;
 	type AccountKeyPair = sp_core::sr25519::Pair;
@@ -106,30 +151,11 @@ impl TransactionSignScheme for Millau {
 		param: SignParam<Self>,
 		unsigned: UnsignedTransaction<Self::Chain>,
 	) -> Result<Self::SignedTransaction, SubstrateError> {
-		let raw_payload = SignedPayload::from_raw(
-			unsigned.call.clone(),
-			(
-				frame_system::CheckNonZeroSender::<millau_runtime::Runtime>::new(),
-				frame_system::CheckSpecVersion::<millau_runtime::Runtime>::new(),
-				frame_system::CheckTxVersion::<millau_runtime::Runtime>::new(),
-				frame_system::CheckGenesis::<millau_runtime::Runtime>::new(),
-				frame_system::CheckEra::<millau_runtime::Runtime>::from(unsigned.era.frame_era()),
-				frame_system::CheckNonce::<millau_runtime::Runtime>::from(unsigned.nonce),
-				frame_system::CheckWeight::<millau_runtime::Runtime>::new(),
-				pallet_transaction_payment::ChargeTransactionPayment::<millau_runtime::Runtime>::from(unsigned.tip),
-				millau_runtime::BridgeRejectObsoleteHeadersAndMessages,
-			),
-			(
-				(),
-				param.spec_version,
-				param.transaction_version,
-				param.genesis_hash,
-				unsigned.era.signed_payload(param.genesis_hash),
-				(),
-				(),
-				(),
-				(),
-			),
+		let raw_payload = signed_payload(
+			param.spec_version,
+			param.transaction_version,
+			param.genesis_hash,
+			unsigned,
 		);
 		let signature = raw_payload.using_encoded(|payload| param.signer.sign(payload));
 		let signer: sp_runtime::MultiSigner = param.signer.public().into();
@@ -143,6 +169,16 @@ impl TransactionSignScheme for Millau {
 		))
 	}
 
+	fn encode_signing_payload(
+		spec_version: u32,
+		transaction_version: u32,
+		genesis_hash: millau_runtime::Hash,
+		unsigned: UnsignedTransaction<Self::Chain>,
+	) -> Vec<u8> {
+		signed_payload(spec_version, transaction_version, genesis_hash, unsigned)
+			.using_encoded(|payload| payload.to_vec())
+	}
+
 	fn is_signed(tx: &Self::SignedTransaction) -> bool {
 		tx.signature.is_some()
 	}
@@ -18,10 +18,10 @@
 
 use bp_messages::MessageNonce;
 use codec::{Compact, Decode, Encode};
-use frame_support::weights::Weight;
+use frame_support::{dispatch::CallableCallFor, weights::Weight};
 use relay_substrate_client::{
-	BalanceOf, Chain, ChainBase, ChainWithBalances, ChainWithGrandpa, ChainWithMessages,
-	Error as SubstrateError, IndexOf, SignParam, TransactionSignScheme,
+	BalanceOf, CallIndex, Chain, ChainBase, ChainWithBalances, ChainWithGrandpa, ChainWithMessages,
+	EncodedOrDecodedCall, Error as SubstrateError, IndexOf, SignParam, TransactionSignScheme,
 	UnsignedTransaction,
 };
 use sp_core::{storage::StorageKey, Pair};
@@ -63,9 +63,56 @@ impl Chain for Pass3d {
 		bp_pass3d::BEST_FINALIZED_PASS3D_HEADER_METHOD;
 	const AVERAGE_BLOCK_INTERVAL: Duration = Duration::from_secs(5);
 	const STORAGE_PROOF_OVERHEAD: u32 = bp_pass3d::EXTRA_STORAGE_PROOF_SIZE;
+	const SS58_PREFIX: u16 = bp_pass3d::SS58_PREFIX;
 
 	type SignedBlock = pass3d_runtime::SignedBlock;
 	type Call = pass3d_runtime::Call;
+
+	fn proxy_call(real: Self::AccountId, call: Self::Call) -> Option<Self::Call> {
+		Some(pass3d_runtime::Call::Proxy(pass3d_runtime::ProxyCall::proxy {
+			real: real.into(),
+			force_proxy_type: None,
+			call: Box::new(call),
+		}))
+	}
+
+	fn remark_with_event_call(remark: Vec<u8>) -> Option<Self::Call> {
+		Some(pass3d_runtime::Call::System(pass3d_runtime::SystemCall::remark_with_event { remark }))
+	}
+
+	fn bundled_call_indices() -> Vec<CallIndex> {
+		// the runtime has no utility pallet, so there are no batch calls to check
+		vec![
+			CallIndex::bundled::<
+				pass3d_runtime::BridgePass3dtGrandpa,
+				CallableCallFor<pass3d_runtime::BridgePass3dtGrandpa, pass3d_runtime::Runtime>,
+			>("submit_finality_proof"),
+			CallIndex::bundled::<
+				pass3d_runtime::BridgePass3dtGrandpa,
+				CallableCallFor<pass3d_runtime::BridgePass3dtGrandpa, pass3d_runtime::Runtime>,
+			>("submit_finality_proof_batch"),
+			CallIndex::bundled::<
+				pass3d_runtime::BridgePass3dtMessages,
+				CallableCallFor<pass3d_runtime::BridgePass3dtMessages, pass3d_runtime::Runtime>,
+			>("receive_messages_proof"),
+			CallIndex::bundled::<
+				pass3d_runtime::BridgePass3dtMessages,
+				CallableCallFor<pass3d_runtime::BridgePass3dtMessages, pass3d_runtime::Runtime>,
+			>("receive_messages_delivery_proof"),
+			CallIndex::bundled::<
+				pass3d_runtime::BridgeRelayers,
+				CallableCallFor<pass3d_runtime::BridgeRelayers, pass3d_runtime::Runtime>,
+			>("claim_rewards"),
+			CallIndex::bundled::<
+				pass3d_runtime::Proxy,
+				CallableCallFor<pass3d_runtime::Proxy, pass3d_runtime::Runtime>,
+			>("proxy"),
+			CallIndex::bundled::<
+				pass3d_runtime::System,
+				CallableCallFor<pass3d_runtime::System, pass3d_runtime::Runtime>,
+			>("remark_with_event"),
+		]
+	}
 }
 //
 // impl RelayChain for Pass3d {
@@ -76,6 +123,8 @@ impl Chain for Pass3d {
 
 impl ChainWithGrandpa for Pass3d {
 	const WITH_CHAIN_GRANDPA_PALLET_NAME: &'static str = bp_pass3d::WITH_PASS3D_GRANDPA_PALLET_NAME;
+	const SYNCED_HEADERS_GRANDPA_INFO_METHOD: &'static str =
+		bp_pass3d::PASS3D_SYNCED_HEADERS_GRANDPA_INFO_METHOD;
 }
 
 impl ChainWithMessages for Pass3d {
@@ -83,8 +132,14 @@ impl ChainWithMessages for Pass3d {
 		bp_pass3d::WITH_PASS3D_MESSAGES_PALLET_NAME;
 	const TO_CHAIN_MESSAGE_DETAILS_METHOD: &'static str =
 		bp_pass3d::TO_PASS3D_MESSAGE_DETAILS_METHOD;
+	const TO_CHAIN_RECENT_RELAYERS_METHOD: &'static str =
+		bp_pass3d::TO_PASS3D_RECENT_RELAYERS_METHOD;
 	const FROM_CHAIN_MESSAGE_DETAILS_METHOD: &'static str =
 		bp_pass3d::FROM_PASS3D_MESSAGE_DETAILS_METHOD;
+	const TO_CHAIN_OUTBOUND_LANE_STATE_METHOD: &'static str =
+		bp_pass3d::TO_PASS3D_OUTBOUND_LANE_STATE_METHOD;
+	const FROM_CHAIN_INBOUND_LANE_STATE_METHOD: &'static str =
+		bp_pass3d::FROM_PASS3D_INBOUND_LANE_STATE_METHOD;
 	const PAY_INBOUND_DISPATCH_FEE_WEIGHT_AT_CHAIN: Weight =
 		bp_pass3d::PAY_INBOUND_DISPATCH_FEE_WEIGHT;
 	const MAX_UNREWARDED_RELAYERS_IN_CONFIRMATION_TX: MessageNonce =
@@ -104,6 +159,42 @@ impl ChainWithBalances for Pass3d {
 	}
 }
 
+/// Returns payload that is signed by the Pass3d transaction author.
+fn signed_payload(
+	spec_version: u32,
+	transaction_version: u32,
+	genesis_hash: pass3d_runtime::Hash,
+	unsigned: UnsignedTransaction<Pass3d>,
+) -> SignedPayload<EncodedOrDecodedCall<pass3d_runtime::Call>, pass3d_runtime::SignedExtra> {
+	SignedPayload::from_raw(
+		unsigned.call,
+		(
+			frame_system::CheckNonZeroSender::<pass3d_runtime::Runtime>::new(),
+			frame_system::CheckSpecVersion::<pass3d_runtime::Runtime>::new(),
+			frame_system::CheckTxVersion::<pass3d_runtime::Runtime>::new(),
+			frame_system::CheckGenesis::<pass3d_runtime::Runtime>::new(),
+			frame_system::CheckEra::<pass3d_runtime::Runtime>::from(unsigned.era.frame_era()),
+			frame_system::CheckNonce::<pass3d_runtime::Runtime>::from(unsigned.nonce),
+			frame_system::CheckWeight::<pass3d_runtime::Runtime>::new(),
+			pallet_transaction_payment::ChargeTransactionPayment::<pass3d_runtime::Runtime>::from(
+				unsigned.tip,
+			),
+			pass3d_runtime::BridgeRejectObsoleteHeadersAndMessages,
+		),
+		(
+			(),
+			spec_version,
+			transaction_version,
+			genesis_hash,
+			unsigned.era.signed_payload(genesis_hash),
+			(),
+			(),
+			(),
+			(),
+		),
+	)
+}
+
 impl TransactionSignScheme for Pass3d {
 	type Chain = Pass3d;
 	type AccountKeyPair = sp_core::sr25519::Pair;
@@ -113,28 +204,11 @@ impl TransactionSignScheme for Pass3d {
 		param: SignParam<Self>,
 		unsigned: UnsignedTransaction<Self::Chain>,
 	) -> Result<Self::SignedTransaction, SubstrateError> {
-		let raw_payload = SignedPayload::from_raw(
-			unsigned.call.clone(),
-			(
-				frame_system::CheckNonZeroSender::<pass3d_runtime::Runtime>::new(),
-				frame_system::CheckSpecVersion::<pass3d_runtime::Runtime>::new(),
-				frame_system::CheckTxVersion::<pass3d_runtime::Runtime>::new(),
-				frame_system::CheckGenesis::<pass3d_runtime::Runtime>::new(),
-				frame_system::CheckEra::<pass3d_runtime::Runtime>::from(unsigned.era.frame_era()),
-				frame_system::CheckNonce::<pass3d_runtime::Runtime>::from(unsigned.nonce),
-				frame_system::CheckWeight::<pass3d_runtime::Runtime>::new(),
-				pallet_transaction_payment::ChargeTransactionPayment::<pass3d_runtime::Runtime>::from(unsigned.tip),
-			),
-			(
-				(),
-				param.spec_version,
-				param.transaction_version,
-				param.genesis_hash,
-				unsigned.era.signed_payload(param.genesis_hash),
-				(),
-				(),
-				(),
-			),
+		let raw_payload = signed_payload(
+			param.spec_version,
+			param.transaction_version,
+			param.genesis_hash,
+			unsigned,
 		);
 		let signature = raw_payload.using_encoded(|payload| param.signer.sign(payload));
 		let signer: sp_runtime::MultiSigner = param.signer.public().into();
@@ -148,6 +222,16 @@ impl TransactionSignScheme for Pass3d {
 		))
 	}
 
+	fn encode_signing_payload(
+		spec_version: u32,
+		transaction_version: u32,
+		genesis_hash: pass3d_runtime::Hash,
+		unsigned: UnsignedTransaction<Self::Chain>,
+	) -> Vec<u8> {
+		signed_payload(spec_version, transaction_version, genesis_hash, unsigned)
+			.using_encoded(|payload| payload.to_vec())
+	}
+
 	fn is_signed(tx: &Self::SignedTransaction) -> bool {
 		tx.signature.is_some()
 	}
@@ -18,10 +18,11 @@
 
 use bp_messages::MessageNonce;
 use codec::{Compact, Decode, Encode};
-use frame_support::weights::Weight;
+use frame_support::{dispatch::CallableCallFor, weights::Weight};
 use relay_substrate_client::{
-	BalanceOf, Chain, ChainBase, ChainWithBalances, ChainWithGrandpa, ChainWithMessages,
-	Error as SubstrateError, IndexOf, SignParam, TransactionSignScheme, UnsignedTransaction,
+	BalanceOf, CallIndex, Chain, ChainBase, ChainWithBalances, ChainWithGrandpa, ChainWithMessages,
+	EncodedOrDecodedCall, Error as SubstrateError, IndexOf, SignParam, TransactionSignScheme,
+	UnsignedTransaction,
 };
 use sp_core::{storage::StorageKey, Pair};
 use sp_runtime::{generic::SignedPayload, traits::IdentifyAccount};
@@ -55,7 +56,10 @@ impl ChainBase for Pass3dt {
 }
 
 impl ChainWithGrandpa for Pass3dt {
-	const WITH_CHAIN_GRANDPA_PALLET_NAME: &'static str = bp_pass3dt::WITH_PASS3DT_GRANDPA_PALLET_NAME;
+	const WITH_CHAIN_GRANDPA_PALLET_NAME: &'static str =
+		bp_pass3dt::WITH_PASS3DT_GRANDPA_PALLET_NAME;
+	const SYNCED_HEADERS_GRANDPA_INFO_METHOD: &'static str =
+		bp_pass3dt::PASS3DT_SYNCED_HEADERS_GRANDPA_INFO_METHOD;
 }
 
 impl ChainWithMessages for Pass3dt {
@@ -63,8 +67,14 @@ impl ChainWithMessages for Pass3dt {
 		bp_pass3dt::WITH_PASS3DT_MESSAGES_PALLET_NAME;
 	const TO_CHAIN_MESSAGE_DETAILS_METHOD: &'static str =
 		bp_pass3dt::TO_PASS3DT_MESSAGE_DETAILS_METHOD;
+	const TO_CHAIN_RECENT_RELAYERS_METHOD: &'static str =
+		bp_pass3dt::TO_PASS3DT_RECENT_RELAYERS_METHOD;
 	const FROM_CHAIN_MESSAGE_DETAILS_METHOD: &'static str =
 		bp_pass3dt::FROM_PASS3DT_MESSAGE_DETAILS_METHOD;
+	const TO_CHAIN_OUTBOUND_LANE_STATE_METHOD: &'static str =
+		bp_pass3dt::TO_PASS3DT_OUTBOUND_LANE_STATE_METHOD;
+	const FROM_CHAIN_INBOUND_LANE_STATE_METHOD: &'static str =
+		bp_pass3dt::FROM_PASS3DT_INBOUND_LANE_STATE_METHOD;
 	const PAY_INBOUND_DISPATCH_FEE_WEIGHT_AT_CHAIN: Weight =
 		bp_pass3dt::PAY_INBOUND_DISPATCH_FEE_WEIGHT;
 	const MAX_UNREWARDED_RELAYERS_IN_CONFIRMATION_TX: MessageNonce =
@@ -83,9 +93,58 @@ impl Chain for Pass3dt {
 		bp_pass3dt::BEST_FINALIZED_PASS3DT_HEADER_METHOD;
 	const AVERAGE_BLOCK_INTERVAL: Duration = Duration::from_secs(5);
 	const STORAGE_PROOF_OVERHEAD: u32 = bp_pass3dt::EXTRA_STORAGE_PROOF_SIZE;
+	const SS58_PREFIX: u16 = bp_pass3dt::SS58_PREFIX;
 
 	type SignedBlock = pass3dt_runtime::SignedBlock;
 	type Call = pass3dt_runtime::Call;
+
+	fn proxy_call(real: Self::AccountId, call: Self::Call) -> Option<Self::Call> {
+		Some(pass3dt_runtime::Call::Proxy(pass3dt_runtime::ProxyCall::proxy {
+			real,
+			force_proxy_type: None,
+			call: Box::new(call),
+		}))
+	}
+
+	fn remark_with_event_call(remark: Vec<u8>) -> Option<Self::Call> {
+		Some(pass3dt_runtime::Call::System(pass3dt_runtime::SystemCall::remark_with_event {
+			remark,
+		}))
+	}
+
+	fn bundled_call_indices() -> Vec<CallIndex> {
+		// the runtime has no utility pallet, so there are no batch calls to check
+		vec![
+			CallIndex::bundled::<
+				pass3dt_runtime::BridgePass3dGrandpa,
+				CallableCallFor<pass3dt_runtime::BridgePass3dGrandpa, pass3dt_runtime::Runtime>,
+			>("submit_finality_proof"),
+			CallIndex::bundled::<
+				pass3dt_runtime::BridgePass3dGrandpa,
+				CallableCallFor<pass3dt_runtime::BridgePass3dGrandpa, pass3dt_runtime::Runtime>,
+			>("submit_finality_proof_batch"),
+			CallIndex::bundled::<
+				pass3dt_runtime::BridgePass3dMessages,
+				CallableCallFor<pass3dt_runtime::BridgePass3dMessages, pass3dt_runtime::Runtime>,
+			>("receive_messages_proof"),
+			CallIndex::bundled::<
+				pass3dt_runtime::BridgePass3dMessages,
+				CallableCallFor<pass3dt_runtime::BridgePass3dMessages, pass3dt_runtime::Runtime>,
+			>("receive_messages_delivery_proof"),
+			CallIndex::bundled::<
+				pass3dt_runtime::BridgeRelayers,
+				CallableCallFor<pass3dt_runtime::BridgeRelayers, pass3dt_runtime::Runtime>,
+			>("claim_rewards"),
+			CallIndex::bundled::<
+				pass3dt_runtime::Proxy,
+				CallableCallFor<pass3dt_runtime::Proxy, pass3dt_runtime::Runtime>,
+			>("proxy"),
+			CallIndex::bundled::<
+				pass3dt_runtime::System,
+				CallableCallFor<pass3dt_runtime::System, pass3dt_runtime::Runtime>,
+			>("remark_with_event"),
+		]
+	}
 }
 
 impl ChainWithBalances for Pass3dt {
@@ -97,6 +156,42 @@ impl ChainWithBalances for Pass3dt {
 	}
 }
 
+/// Returns payload that is signed by the Pass3dt transaction author.
+fn signed_payload(
+	spec_version: u32,
+	transaction_version: u32,
+	genesis_hash: pass3dt_runtime::Hash,
+	unsigned: UnsignedTransaction<Pass3dt>,
+) -> SignedPayload<EncodedOrDecodedCall<pass3dt_runtime::Call>, pass3dt_runtime::SignedExtra> {
+	SignedPayload::from_raw(
+		unsigned.call,
+		(
+			frame_system::CheckNonZeroSender::<pass3dt_runtime::Runtime>::new(),
+			frame_system::CheckSpecVersion::<pass3dt_runtime::Runtime>::new(),
+			frame_system::CheckTxVersion::<pass3dt_runtime::Runtime>::new(),
+			frame_system::CheckGenesis::<pass3dt_runtime::Runtime>::new(),
+			frame_system::CheckEra::<pass3dt_runtime::Runtime>::from(unsigned.era.frame_era()),
+			frame_system::CheckNonce::<pass3dt_runtime::Runtime>::from(unsigned.nonce),
+			frame_system::CheckWeight::<pass3dt_runtime::Runtime>::new(),
+			pallet_transaction_payment::ChargeTransactionPayment::<pass3dt_runtime::Runtime>::from(
+				unsigned.tip,
+			),
+			pass3dt_runtime::BridgeRejectObsoleteHeadersAndMessages,
+		),
+		(
+			(),
+			spec_version,
+			transaction_version,
+			genesis_hash,
+			unsigned.era.signed_payload(genesis_hash),
+			(),
+			(),
+			(),
+			(),
+		),
+	)
+}
+
 impl TransactionSignScheme for Pass3dt {
 	type Chain = Pass3dt;
 	type AccountKeyPair = sp_core::sr25519::Pair;
@@ -106,30 +201,11 @@ impl TransactionSignScheme for Pass3dt {
 		param: SignParam<Self>,
 		unsigned: UnsignedTransaction<Self::Chain>,
 	) -> Result<Self::SignedTransaction, SubstrateError> {
-		let raw_payload = SignedPayload::from_raw(
-			unsigned.call.clone(),
-			(
-				frame_system::CheckNonZeroSender::<pass3dt_runtime::Runtime>::new(),
-				frame_system::CheckSpecVersion::<pass3dt_runtime::Runtime>::new(),
-				frame_system::CheckTxVersion::<pass3dt_runtime::Runtime>::new(),
-				frame_system::CheckGenesis::<pass3dt_runtime::Runtime>::new(),
-				frame_system::CheckEra::<pass3dt_runtime::Runtime>::from(unsigned.era.frame_era()),
-				frame_system::CheckNonce::<pass3dt_runtime::Runtime>::from(unsigned.nonce),
-				frame_system::CheckWeight::<pass3dt_runtime::Runtime>::new(),
-				pallet_transaction_payment::ChargeTransactionPayment::<pass3dt_runtime::Runtime>::from(unsigned.tip),
-				pass3dt_runtime::BridgeRejectObsoleteHeadersAndMessages,
-			),
-			(
-				(),
-				param.spec_version,
-				param.transaction_version,
-				param.genesis_hash,
-				unsigned.era.signed_payload(param.genesis_hash),
-				(),
-				(),
-				(),
-				(),
-			),
+		let raw_payload = signed_payload(
+			param.spec_version,
+			param.transaction_version,
+			param.genesis_hash,
+			unsigned,
 		);
 		let signature = raw_payload.using_encoded(|payload| param.signer.sign(payload));
 		let signer: sp_runtime::MultiSigner = param.signer.public().into();
@@ -143,6 +219,16 @@ impl TransactionSignScheme for Pass3dt {
 		))
 	}
 
+	fn encode_signing_payload(
+		spec_version: u32,
+		transaction_version: u32,
+		genesis_hash: pass3dt_runtime::Hash,
+		unsigned: UnsignedTransaction<Self::Chain>,
+	) -> Vec<u8> {
+		signed_payload(spec_version, transaction_version, genesis_hash, unsigned)
+			.using_encoded(|payload| payload.to_vec())
+	}
+
 	fn is_signed(tx: &Self::SignedTransaction) -> bool {
 		tx.signature.is_some()
 	}
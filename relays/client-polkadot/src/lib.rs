@@ -63,6 +63,8 @@ impl Chain for Polkadot {
 impl ChainWithGrandpa for Polkadot {
 	const WITH_CHAIN_GRANDPA_PALLET_NAME: &'static str =
 		bp_polkadot::WITH_POLKADOT_GRANDPA_PALLET_NAME;
+	const SYNCED_HEADERS_GRANDPA_INFO_METHOD: &'static str =
+		bp_polkadot::POLKADOT_SYNCED_HEADERS_GRANDPA_INFO_METHOD;
 }
 
 impl ChainWithBalances for Polkadot {
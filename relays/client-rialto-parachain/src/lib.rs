@@ -20,8 +20,8 @@ use bp_messages::MessageNonce;
 use codec::Encode;
 use frame_support::weights::Weight;
 use relay_substrate_client::{
-	Chain, ChainBase, ChainWithBalances, ChainWithMessages, Error as SubstrateError, SignParam,
-	TransactionSignScheme, UnsignedTransaction,
+	Chain, ChainBase, ChainWithBalances, ChainWithMessages, EncodedOrDecodedCall,
+	Error as SubstrateError, SignParam, TransactionSignScheme, UnsignedTransaction,
 };
 use sp_core::{storage::StorageKey, Pair};
 use sp_runtime::{generic::SignedPayload, traits::IdentifyAccount};
@@ -84,8 +84,14 @@ impl ChainWithMessages for RialtoParachain {
 		bp_rialto_parachain::WITH_RIALTO_PARACHAIN_MESSAGES_PALLET_NAME;
 	const TO_CHAIN_MESSAGE_DETAILS_METHOD: &'static str =
 		bp_rialto_parachain::TO_RIALTO_PARACHAIN_MESSAGE_DETAILS_METHOD;
+	const TO_CHAIN_RECENT_RELAYERS_METHOD: &'static str =
+		bp_rialto_parachain::TO_RIALTO_PARACHAIN_RECENT_RELAYERS_METHOD;
 	const FROM_CHAIN_MESSAGE_DETAILS_METHOD: &'static str =
 		bp_rialto_parachain::FROM_RIALTO_PARACHAIN_MESSAGE_DETAILS_METHOD;
+	const TO_CHAIN_OUTBOUND_LANE_STATE_METHOD: &'static str =
+		bp_rialto_parachain::TO_RIALTO_PARACHAIN_OUTBOUND_LANE_STATE_METHOD;
+	const FROM_CHAIN_INBOUND_LANE_STATE_METHOD: &'static str =
+		bp_rialto_parachain::FROM_RIALTO_PARACHAIN_INBOUND_LANE_STATE_METHOD;
 	const PAY_INBOUND_DISPATCH_FEE_WEIGHT_AT_CHAIN: Weight =
 		bp_rialto_parachain::PAY_INBOUND_DISPATCH_FEE_WEIGHT;
 	const MAX_UNREWARDED_RELAYERS_IN_CONFIRMATION_TX: MessageNonce =
@@ -96,6 +102,45 @@ impl ChainWithMessages for RialtoParachain {
 	type WeightInfo = ();
 }
 
+/// Returns payload that is signed by the RialtoParachain transaction author.
+fn signed_payload(
+	spec_version: u32,
+	transaction_version: u32,
+	genesis_hash: rialto_parachain_runtime::Hash,
+	unsigned: UnsignedTransaction<RialtoParachain>,
+) -> SignedPayload<
+	EncodedOrDecodedCall<rialto_parachain_runtime::Call>,
+	rialto_parachain_runtime::SignedExtra,
+> {
+	SignedPayload::from_raw(
+		unsigned.call,
+		(
+			frame_system::CheckNonZeroSender::<rialto_parachain_runtime::Runtime>::new(),
+			frame_system::CheckSpecVersion::<rialto_parachain_runtime::Runtime>::new(),
+			frame_system::CheckTxVersion::<rialto_parachain_runtime::Runtime>::new(),
+			frame_system::CheckGenesis::<rialto_parachain_runtime::Runtime>::new(),
+			frame_system::CheckEra::<rialto_parachain_runtime::Runtime>::from(
+				unsigned.era.frame_era(),
+			),
+			frame_system::CheckNonce::<rialto_parachain_runtime::Runtime>::from(unsigned.nonce),
+			frame_system::CheckWeight::<rialto_parachain_runtime::Runtime>::new(),
+			pallet_transaction_payment::ChargeTransactionPayment::<
+				rialto_parachain_runtime::Runtime,
+			>::from(unsigned.tip),
+		),
+		(
+			(),
+			spec_version,
+			transaction_version,
+			genesis_hash,
+			unsigned.era.signed_payload(genesis_hash),
+			(),
+			(),
+			(),
+		),
+	)
+}
+
 impl TransactionSignScheme for RialtoParachain {
 	type Chain = RialtoParachain;
 	type AccountKeyPair = sp_core::sr25519::Pair;
@@ -105,32 +150,11 @@ impl TransactionSignScheme for RialtoParachain {
 		param: SignParam<Self>,
 		unsigned: UnsignedTransaction<Self::Chain>,
 	) -> Result<Self::SignedTransaction, SubstrateError> {
-		let raw_payload = SignedPayload::from_raw(
-			unsigned.call,
-			(
-				frame_system::CheckNonZeroSender::<rialto_parachain_runtime::Runtime>::new(),
-				frame_system::CheckSpecVersion::<rialto_parachain_runtime::Runtime>::new(),
-				frame_system::CheckTxVersion::<rialto_parachain_runtime::Runtime>::new(),
-				frame_system::CheckGenesis::<rialto_parachain_runtime::Runtime>::new(),
-				frame_system::CheckEra::<rialto_parachain_runtime::Runtime>::from(
-					unsigned.era.frame_era(),
-				),
-				frame_system::CheckNonce::<rialto_parachain_runtime::Runtime>::from(unsigned.nonce),
-				frame_system::CheckWeight::<rialto_parachain_runtime::Runtime>::new(),
-				pallet_transaction_payment::ChargeTransactionPayment::<
-					rialto_parachain_runtime::Runtime,
-				>::from(unsigned.tip),
-			),
-			(
-				(),
-				param.spec_version,
-				param.transaction_version,
-				param.genesis_hash,
-				unsigned.era.signed_payload(param.genesis_hash),
-				(),
-				(),
-				(),
-			),
+		let raw_payload = signed_payload(
+			param.spec_version,
+			param.transaction_version,
+			param.genesis_hash,
+			unsigned,
 		);
 		let signature = raw_payload.using_encoded(|payload| param.signer.sign(payload));
 		let signer: sp_runtime::MultiSigner = param.signer.public().into();
@@ -144,6 +168,16 @@ impl TransactionSignScheme for RialtoParachain {
 		))
 	}
 
+	fn encode_signing_payload(
+		spec_version: u32,
+		transaction_version: u32,
+		genesis_hash: rialto_parachain_runtime::Hash,
+		unsigned: UnsignedTransaction<Self::Chain>,
+	) -> Vec<u8> {
+		signed_payload(spec_version, transaction_version, genesis_hash, unsigned)
+			.using_encoded(|payload| payload.to_vec())
+	}
+
 	fn is_signed(tx: &Self::SignedTransaction) -> bool {
 		tx.signature.is_some()
 	}
@@ -21,8 +21,8 @@ use codec::{Compact, Decode, Encode};
 use frame_support::weights::Weight;
 use relay_substrate_client::{
 	BalanceOf, Chain, ChainBase, ChainWithBalances, ChainWithGrandpa, ChainWithMessages,
-	Error as SubstrateError, IndexOf, RelayChain, SignParam, TransactionSignScheme,
-	UnsignedTransaction,
+	EncodedOrDecodedCall, Error as SubstrateError, IndexOf, RelayChain, SignParam,
+	TransactionSignScheme, UnsignedTransaction,
 };
 use sp_core::{storage::StorageKey, Pair};
 use sp_runtime::{generic::SignedPayload, traits::IdentifyAccount};
@@ -72,10 +72,13 @@ impl RelayChain for Rialto {
 	const PARAS_PALLET_NAME: &'static str = bp_rialto::PARAS_PALLET_NAME;
 	const PARACHAINS_FINALITY_PALLET_NAME: &'static str =
 		bp_rialto::WITH_RIALTO_BRIDGE_PARAS_PALLET_NAME;
+	const MAX_PARA_HEADS_PROOF_SIZE: u32 = bp_rialto::MAX_PARA_HEADS_PROOF_SIZE;
 }
 
 impl ChainWithGrandpa for Rialto {
 	const WITH_CHAIN_GRANDPA_PALLET_NAME: &'static str = bp_rialto::WITH_RIALTO_GRANDPA_PALLET_NAME;
+	const SYNCED_HEADERS_GRANDPA_INFO_METHOD: &'static str =
+		bp_rialto::RIALTO_SYNCED_HEADERS_GRANDPA_INFO_METHOD;
 }
 
 impl ChainWithMessages for Rialto {
@@ -83,8 +86,14 @@ impl ChainWithMessages for Rialto {
 		bp_rialto::WITH_RIALTO_MESSAGES_PALLET_NAME;
 	const TO_CHAIN_MESSAGE_DETAILS_METHOD: &'static str =
 		bp_rialto::TO_RIALTO_MESSAGE_DETAILS_METHOD;
+	const TO_CHAIN_RECENT_RELAYERS_METHOD: &'static str =
+		bp_rialto::TO_RIALTO_RECENT_RELAYERS_METHOD;
 	const FROM_CHAIN_MESSAGE_DETAILS_METHOD: &'static str =
 		bp_rialto::FROM_RIALTO_MESSAGE_DETAILS_METHOD;
+	const TO_CHAIN_OUTBOUND_LANE_STATE_METHOD: &'static str =
+		bp_rialto::TO_RIALTO_OUTBOUND_LANE_STATE_METHOD;
+	const FROM_CHAIN_INBOUND_LANE_STATE_METHOD: &'static str =
+		bp_rialto::FROM_RIALTO_INBOUND_LANE_STATE_METHOD;
 	const PAY_INBOUND_DISPATCH_FEE_WEIGHT_AT_CHAIN: Weight =
 		bp_rialto::PAY_INBOUND_DISPATCH_FEE_WEIGHT;
 	const MAX_UNREWARDED_RELAYERS_IN_CONFIRMATION_TX: MessageNonce =
@@ -104,6 +113,40 @@ impl ChainWithBalances for Rialto {
 	}
 }
 
+/// Returns payload that is signed by the Rialto transaction author.
+fn signed_payload(
+	spec_version: u32,
+	transaction_version: u32,
+	genesis_hash: rialto_runtime::Hash,
+	unsigned: UnsignedTransaction<Rialto>,
+) -> SignedPayload<EncodedOrDecodedCall<rialto_runtime::Call>, rialto_runtime::SignedExtra> {
+	SignedPayload::from_raw(
+		unsigned.call,
+		(
+			frame_system::CheckNonZeroSender::<rialto_runtime::Runtime>::new(),
+			frame_system::CheckSpecVersion::<rialto_runtime::Runtime>::new(),
+			frame_system::CheckTxVersion::<rialto_runtime::Runtime>::new(),
+			frame_system::CheckGenesis::<rialto_runtime::Runtime>::new(),
+			frame_system::CheckEra::<rialto_runtime::Runtime>::from(unsigned.era.frame_era()),
+			frame_system::CheckNonce::<rialto_runtime::Runtime>::from(unsigned.nonce),
+			frame_system::CheckWeight::<rialto_runtime::Runtime>::new(),
+			pallet_transaction_payment::ChargeTransactionPayment::<rialto_runtime::Runtime>::from(
+				unsigned.tip,
+			),
+		),
+		(
+			(),
+			spec_version,
+			transaction_version,
+			genesis_hash,
+			unsigned.era.signed_payload(genesis_hash),
+			(),
+			(),
+			(),
+		),
+	)
+}
+
 impl TransactionSignScheme for Rialto {
 	type Chain = Rialto;
 	type AccountKeyPair = sp_core::sr25519::Pair;
@@ -113,28 +156,11 @@ impl TransactionSignScheme for Rialto {
 		param: SignParam<Self>,
 		unsigned: UnsignedTransaction<Self::Chain>,
 	) -> Result<Self::SignedTransaction, SubstrateError> {
-		let raw_payload = SignedPayload::from_raw(
-			unsigned.call.clone(),
-			(
-				frame_system::CheckNonZeroSender::<rialto_runtime::Runtime>::new(),
-				frame_system::CheckSpecVersion::<rialto_runtime::Runtime>::new(),
-				frame_system::CheckTxVersion::<rialto_runtime::Runtime>::new(),
-				frame_system::CheckGenesis::<rialto_runtime::Runtime>::new(),
-				frame_system::CheckEra::<rialto_runtime::Runtime>::from(unsigned.era.frame_era()),
-				frame_system::CheckNonce::<rialto_runtime::Runtime>::from(unsigned.nonce),
-				frame_system::CheckWeight::<rialto_runtime::Runtime>::new(),
-				pallet_transaction_payment::ChargeTransactionPayment::<rialto_runtime::Runtime>::from(unsigned.tip),
-			),
-			(
-				(),
-				param.spec_version,
-				param.transaction_version,
-				param.genesis_hash,
-				unsigned.era.signed_payload(param.genesis_hash),
-				(),
-				(),
-				(),
-			),
+		let raw_payload = signed_payload(
+			param.spec_version,
+			param.transaction_version,
+			param.genesis_hash,
+			unsigned,
 		);
 		let signature = raw_payload.using_encoded(|payload| param.signer.sign(payload));
 		let signer: sp_runtime::MultiSigner = param.signer.public().into();
@@ -148,6 +174,16 @@ impl TransactionSignScheme for Rialto {
 		))
 	}
 
+	fn encode_signing_payload(
+		spec_version: u32,
+		transaction_version: u32,
+		genesis_hash: rialto_runtime::Hash,
+		unsigned: UnsignedTransaction<Self::Chain>,
+	) -> Vec<u8> {
+		signed_payload(spec_version, transaction_version, genesis_hash, unsigned)
+			.using_encoded(|payload| payload.to_vec())
+	}
+
 	fn is_signed(tx: &Self::SignedTransaction) -> bool {
 		tx.signature.is_some()
 	}
@@ -0,0 +1,258 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Verification of runtime call indices, used by the relay to encode calls, against the
+//! live chain metadata.
+
+use crate::error::{Error, Result};
+
+use codec::Decode;
+use frame_metadata::{RuntimeMetadata, RuntimeMetadataPrefixed};
+use frame_support::{dispatch::GetCallName, traits::PalletInfoAccess};
+use scale_info::TypeDef;
+
+/// Index of the runtime call, used by the relay to encode this call.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CallIndex {
+	/// Name of the pallet (as it is declared in the `construct_runtime` macro call).
+	pub pallet_name: &'static str,
+	/// Name of the call.
+	pub call_name: &'static str,
+	/// Index of the pallet within the runtime.
+	pub pallet_index: u8,
+	/// Index of the call within the pallet.
+	pub call_index: u8,
+}
+
+impl CallIndex {
+	/// Returns index of the call, as it is encoded by the bundled runtime.
+	///
+	/// Panics if pallet has no call with given name.
+	pub fn bundled<Pallet, PalletCall>(call_name: &'static str) -> Self
+	where
+		Pallet: PalletInfoAccess,
+		PalletCall: GetCallName,
+	{
+		let call_index = PalletCall::get_call_names()
+			.iter()
+			.position(|name| *name == call_name)
+			.unwrap_or_else(|| panic!("{} has no call named {}", Pallet::name(), call_name));
+		CallIndex {
+			pallet_name: Pallet::name(),
+			call_name,
+			pallet_index: Pallet::index() as u8,
+			call_index: call_index as u8,
+		}
+	}
+}
+
+/// Verify that all given call indices match the encoded runtime metadata.
+///
+/// Returns error with the report of all mismatches, if there are any.
+pub fn check_call_indices(encoded_metadata: &[u8], expected: &[CallIndex]) -> Result<()> {
+	let metadata = match RuntimeMetadataPrefixed::decode(&mut &encoded_metadata[..])?.1 {
+		RuntimeMetadata::V14(metadata) => metadata,
+		_ => return Err(Error::Custom("Only V14 runtime metadata is supported".into())),
+	};
+
+	let mismatches = expected
+		.iter()
+		.filter_map(|expected| {
+			let pallet = match metadata.pallets.iter().find(|p| p.name == expected.pallet_name) {
+				Some(pallet) => pallet,
+				None => return Some(format!("{}: pallet is missing", expected.pallet_name)),
+			};
+			let call_index = pallet
+				.calls
+				.as_ref()
+				.and_then(|calls| match metadata.types.resolve(calls.ty.id()) {
+					Some(ty) => match ty.type_def() {
+						TypeDef::Variant(variant) => Some(variant.variants()),
+						_ => None,
+					},
+					None => None,
+				})
+				.and_then(|variants| {
+					variants.iter().find(|variant| variant.name() == expected.call_name)
+				})
+				.map(|variant| variant.index());
+
+			match call_index {
+				Some(call_index)
+					if pallet.index == expected.pallet_index &&
+						call_index == expected.call_index =>
+					None,
+				Some(call_index) => Some(format!(
+					"{}::{}: expected index ({}, {}), actual index ({}, {})",
+					expected.pallet_name,
+					expected.call_name,
+					expected.pallet_index,
+					expected.call_index,
+					pallet.index,
+					call_index,
+				)),
+				None => Some(format!(
+					"{}::{}: call is missing",
+					expected.pallet_name, expected.call_name
+				)),
+			}
+		})
+		.collect::<Vec<_>>();
+
+	if mismatches.is_empty() {
+		Ok(())
+	} else {
+		Err(Error::Custom(format!(
+			"Runtime call indices of the bundled runtime don't match the chain metadata:\n\t{}",
+			mismatches.join("\n\t"),
+		)))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::Encode;
+	use frame_metadata::v14::{
+		ExtrinsicMetadata, PalletCallMetadata, PalletMetadata, RuntimeMetadataV14,
+	};
+	use scale_info::{meta_type, TypeInfo};
+
+	#[allow(dead_code, non_camel_case_types)]
+	#[derive(TypeInfo)]
+	enum TestMessagesCall {
+		#[codec(index = 0)]
+		receive_messages_proof,
+		#[codec(index = 1)]
+		receive_messages_delivery_proof,
+	}
+
+	#[allow(dead_code, non_camel_case_types)]
+	#[derive(TypeInfo)]
+	enum TestGrandpaCall {
+		#[codec(index = 0)]
+		submit_finality_proof,
+		#[codec(index = 1)]
+		initialize,
+	}
+
+	#[allow(dead_code, non_camel_case_types)]
+	#[derive(TypeInfo)]
+	enum TestShuffledGrandpaCall {
+		#[codec(index = 0)]
+		initialize,
+		#[codec(index = 1)]
+		submit_finality_proof,
+	}
+
+	const EXPECTED: [CallIndex; 3] = [
+		CallIndex {
+			pallet_name: "BridgeGrandpa",
+			call_name: "submit_finality_proof",
+			pallet_index: 5,
+			call_index: 0,
+		},
+		CallIndex {
+			pallet_name: "BridgeMessages",
+			call_name: "receive_messages_proof",
+			pallet_index: 6,
+			call_index: 0,
+		},
+		CallIndex {
+			pallet_name: "BridgeMessages",
+			call_name: "receive_messages_delivery_proof",
+			pallet_index: 6,
+			call_index: 1,
+		},
+	];
+
+	fn pallet(
+		name: &'static str,
+		index: u8,
+		calls: Option<scale_info::MetaType>,
+	) -> PalletMetadata {
+		PalletMetadata {
+			name,
+			storage: None,
+			calls: calls.map(|ty| PalletCallMetadata { ty }),
+			event: None,
+			constants: vec![],
+			error: None,
+			index,
+		}
+	}
+
+	fn metadata(pallets: Vec<PalletMetadata>) -> Vec<u8> {
+		RuntimeMetadataPrefixed::from(RuntimeMetadataV14::new(
+			pallets,
+			ExtrinsicMetadata { ty: meta_type::<()>(), version: 4, signed_extensions: vec![] },
+			meta_type::<()>(),
+		))
+		.encode()
+	}
+
+	#[test]
+	fn matching_call_indices_are_accepted() {
+		let metadata = metadata(vec![
+			pallet("BridgeGrandpa", 5, Some(meta_type::<TestGrandpaCall>())),
+			pallet("BridgeMessages", 6, Some(meta_type::<TestMessagesCall>())),
+		]);
+		assert!(check_call_indices(&metadata, &EXPECTED).is_ok());
+	}
+
+	#[test]
+	fn shuffled_pallets_are_reported() {
+		let metadata = metadata(vec![
+			pallet("BridgeMessages", 5, Some(meta_type::<TestMessagesCall>())),
+			pallet("BridgeGrandpa", 6, Some(meta_type::<TestGrandpaCall>())),
+		]);
+		let error = check_call_indices(&metadata, &EXPECTED).unwrap_err().to_string();
+		assert!(error.contains(
+			"BridgeGrandpa::submit_finality_proof: expected index (5, 0), actual index (6, 0)"
+		));
+		assert!(error.contains(
+			"BridgeMessages::receive_messages_proof: expected index (6, 0), actual index (5, 0)"
+		));
+		assert!(error.contains(
+			"BridgeMessages::receive_messages_delivery_proof: expected index (6, 1), actual index \
+			(5, 1)"
+		));
+	}
+
+	#[test]
+	fn shuffled_calls_are_reported() {
+		let metadata = metadata(vec![
+			pallet("BridgeGrandpa", 5, Some(meta_type::<TestShuffledGrandpaCall>())),
+			pallet("BridgeMessages", 6, Some(meta_type::<TestMessagesCall>())),
+		]);
+		let error = check_call_indices(&metadata, &EXPECTED).unwrap_err().to_string();
+		assert!(error.contains(
+			"BridgeGrandpa::submit_finality_proof: expected index (5, 0), actual index (5, 1)"
+		));
+		assert!(!error.contains("BridgeMessages"));
+	}
+
+	#[test]
+	fn missing_pallets_and_calls_are_reported() {
+		let metadata = metadata(vec![
+			pallet("BridgeGrandpa", 5, None),
+			pallet("System", 0, Some(meta_type::<TestMessagesCall>())),
+		]);
+		let error = check_call_indices(&metadata, &EXPECTED).unwrap_err().to_string();
+		assert!(error.contains("BridgeGrandpa::submit_finality_proof: call is missing"));
+		assert!(error.contains("BridgeMessages: pallet is missing"));
+	}
+}
@@ -14,6 +14,8 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
 
+use crate::call_index::CallIndex;
+
 use bp_messages::MessageNonce;
 use bp_runtime::{
 	Chain as ChainBase, EncodedOrDecodedCall, HashOf, TransactionEra, TransactionEraOf,
@@ -54,11 +56,37 @@ pub trait Chain: ChainBase + Clone {
 	const AVERAGE_BLOCK_INTERVAL: Duration;
 	/// Maximal expected storage proof overhead (in bytes).
 	const STORAGE_PROOF_OVERHEAD: u32;
+	/// SS58 prefix of chain accounts, used when account ids are formatted by the relay.
+	///
+	/// Defaults to the generic Substrate prefix (42).
+	const SS58_PREFIX: u16 = 42;
 
 	/// Block type.
 	type SignedBlock: Member + Serialize + DeserializeOwned + BlockWithJustification<Self::Header>;
 	/// The aggregated `Call` type.
 	type Call: Clone + Codec + Dispatchable + Debug + Send;
+
+	/// Wrap given call into the `pallet_proxy::Call::proxy` call, dispatched on behalf of the
+	/// `real` account.
+	///
+	/// Returns `None` if chain runtime has no proxy pallet.
+	fn proxy_call(_real: Self::AccountId, _call: Self::Call) -> Option<Self::Call> {
+		None
+	}
+
+	/// Build the `frame_system::Call::remark_with_event` call with given remark.
+	///
+	/// Returns `None` if chain runtime doesn't support this call.
+	fn remark_with_event_call(_remark: Vec<u8>) -> Option<Self::Call> {
+		None
+	}
+
+	/// Indices of runtime calls, that the relay is encoding using the bundled runtime.
+	///
+	/// They're verified against the live chain metadata when relay starts.
+	fn bundled_call_indices() -> Vec<CallIndex> {
+		Vec::new()
+	}
 }
 
 /// Substrate-based relay chain that supports parachains.
@@ -73,6 +101,9 @@ pub trait RelayChain: Chain {
 	/// We assume that all chains that are bridging with this `ChainWithGrandpa` are using
 	/// the same name.
 	const PARACHAINS_FINALITY_PALLET_NAME: &'static str;
+	/// Maximal size of the parachain heads proof, built at this chain, that the bridge
+	/// parachains pallet at the bridged chain accepts.
+	const MAX_PARA_HEADS_PROOF_SIZE: u32;
 }
 
 /// Substrate-based chain that is using direct GRANDPA finality from minimal relay-client point of
@@ -87,6 +118,12 @@ pub trait ChainWithGrandpa: Chain {
 	/// We assume that all chains that are bridging with this `ChainWithGrandpa` are using
 	/// the same name.
 	const WITH_CHAIN_GRANDPA_PALLET_NAME: &'static str;
+	/// Name of the runtime API method that is returning GRANDPA authority set id, hash of the
+	/// authorities list and the best finalized header, known to the bridge GRANDPA pallet.
+	///
+	/// Keep in mind that this method is normally provided by the other chain, which is
+	/// bridged with this chain.
+	const SYNCED_HEADERS_GRANDPA_INFO_METHOD: &'static str;
 }
 
 /// Substrate-based chain with messaging support from minimal relay-client point of view.
@@ -102,10 +139,24 @@ pub trait ChainWithMessages: Chain {
 	/// The method is provided by the runtime that is bridged with this `ChainWithMessages`.
 	const TO_CHAIN_MESSAGE_DETAILS_METHOD: &'static str;
 
+	/// Name of the `To<ChainWithMessages>OutboundLaneApi::recent_relayers` runtime API method.
+	/// The method is provided by the runtime that is bridged with this `ChainWithMessages`.
+	const TO_CHAIN_RECENT_RELAYERS_METHOD: &'static str;
+
 	/// Name of the `From<ChainWithMessages>InboundLaneApi::message_details` runtime API method.
 	/// The method is provided by the runtime that is bridged with this `ChainWithMessages`.
 	const FROM_CHAIN_MESSAGE_DETAILS_METHOD: &'static str;
 
+	/// Name of the `To<ChainWithMessages>OutboundLaneApi::outbound_lane_state` runtime API
+	/// method. The method is provided by the runtime that is bridged with this
+	/// `ChainWithMessages`.
+	const TO_CHAIN_OUTBOUND_LANE_STATE_METHOD: &'static str;
+
+	/// Name of the `From<ChainWithMessages>InboundLaneApi::inbound_lane_state` runtime API
+	/// method. The method is provided by the runtime that is bridged with this
+	/// `ChainWithMessages`.
+	const FROM_CHAIN_INBOUND_LANE_STATE_METHOD: &'static str;
+
 	/// Additional weight of the dispatch fee payment if dispatch is paid at the target chain
 	/// and this `ChainWithMessages` is the target chain.
 	const PAY_INBOUND_DISPATCH_FEE_WEIGHT_AT_CHAIN: Weight;
@@ -204,6 +255,17 @@ pub trait TransactionSignScheme: 'static {
 	where
 		Self: Sized;
 
+	/// Encode payload that needs to be signed to authorize given transaction.
+	///
+	/// This is exactly the data that is signed by the `sign_transaction`, so the signature
+	/// may be produced elsewhere (e.g. on the air-gapped machine).
+	fn encode_signing_payload(
+		spec_version: u32,
+		transaction_version: u32,
+		genesis_hash: HashOf<Self::Chain>,
+		unsigned: UnsignedTransaction<Self::Chain>,
+	) -> Vec<u8>;
+
 	/// Returns true if transaction is signed.
 	fn is_signed(tx: &Self::SignedTransaction) -> bool;
 
@@ -17,20 +17,31 @@
 //! Substrate node client.
 
 use crate::{
-	chain::{Chain, ChainWithBalances},
+	call_index::check_call_indices,
+	chain::{BlockWithJustification, Chain, ChainWithBalances},
+	dispatch_error::{
+		EVENTS_VALUE_NAME, EXTRINSIC_FAILED_EVENT_NAME, EXTRINSIC_SUCCESS_EVENT_NAME,
+		SYSTEM_PALLET_NAME,
+	},
+	metrics::BlockSpaceMetrics,
+	nonce_tracker::NonceTracker,
 	rpc::{
 		SubstrateAuthorClient, SubstrateChainClient, SubstrateFrameSystemClient,
 		SubstrateGrandpaClient, SubstrateStateClient, SubstrateSystemClient,
 		SubstrateTransactionPaymentClient,
 	},
-	transaction_stall_timeout, ConnectionParams, Error, HashOf, HeaderIdOf, Result, SignParam,
-	TransactionSignScheme, TransactionTracker, UnsignedTransaction,
+	transaction_stall_timeout, ConnectionParams, DispatchErrorDecoder, Error, HashOf, HeaderIdOf,
+	Result, SignParam, TransactionSignScheme, TransactionTracker, UnsignedTransaction,
 };
 
-use async_std::sync::{Arc, Mutex};
+use async_std::sync::{Arc, Mutex, RwLock};
 use async_trait::async_trait;
-use bp_runtime::{HeaderIdProvider, StorageDoubleMapKeyProvider, StorageMapKeyProvider};
+use bp_runtime::{
+	HeaderIdProvider, StorageDoubleMapKeyProvider, StorageMapKeyProvider,
+	StorageTripleMapKeyProvider,
+};
 use codec::{Decode, Encode};
+use frame_support::weights::{DispatchInfo, Weight};
 use frame_system::AccountInfo;
 use futures::{SinkExt, StreamExt};
 use jsonrpsee::{
@@ -40,20 +51,27 @@ use jsonrpsee::{
 use num_traits::{Bounded, Zero};
 use pallet_balances::AccountData;
 use pallet_transaction_payment::InclusionFee;
-use relay_utils::{relay_loop::RECONNECT_DELAY, STALL_TIMEOUT};
+use relay_utils::{
+	metrics::{Metric, PrometheusError, Registry},
+	relay_loop::RECONNECT_DELAY,
+	STALL_TIMEOUT,
+};
 use sp_core::{
 	storage::{StorageData, StorageKey},
 	Bytes, Hasher,
 };
+use sp_finality_grandpa::SetId;
 use sp_runtime::{
 	traits::Header as HeaderT,
 	transaction_validity::{TransactionSource, TransactionValidity},
+	DispatchError,
 };
 use sp_trie::StorageProof;
 use sp_version::RuntimeVersion;
 use std::{convert::TryFrom, future::Future};
 
 const SUB_API_GRANDPA_AUTHORITIES: &str = "GrandpaApi_grandpa_authorities";
+const SUB_API_GRANDPA_CURRENT_SET_ID: &str = "GrandpaApi_current_set_id";
 const SUB_API_TXPOOL_VALIDATE_TRANSACTION: &str = "TaggedTransactionQueue_validate_transaction";
 const MAX_SUBSCRIPTION_CAPACITY: usize = 4096;
 
@@ -78,12 +96,16 @@ pub enum ChainRuntimeVersion {
 ///
 /// Cloning `Client` is a cheap operation.
 pub struct Client<C: Chain> {
-	/// Tokio runtime handle.
-	tokio: Arc<tokio::runtime::Runtime>,
 	/// Client connection params.
 	params: Arc<ConnectionParams>,
-	/// Substrate RPC client.
-	client: Arc<RpcClient>,
+	/// Connection to the node, shared by all clones of the client.
+	connection: Arc<RwLock<Connection>>,
+	/// Generation of the connection that this client has seen when it has been created or
+	/// reconnected.
+	generation: u64,
+	/// Reconnect lock. Only one clone of the client is reconnecting at a time, others are waiting
+	/// for it to complete.
+	reconnect_lock: Arc<Mutex<()>>,
 	/// Genesis block hash.
 	genesis_hash: HashOf<C>,
 	/// If several tasks are submitting their transactions simultaneously using
@@ -91,8 +113,26 @@ pub struct Client<C: Chain> {
 	/// transactions will be rejected from the pool. This lock is here to prevent situations like
 	/// that.
 	submit_signed_extrinsic_lock: Arc<Mutex<()>>,
+	/// Nonces of accounts that are submitting transactions using `submit_signed_extrinsic` and
+	/// `submit_and_watch_signed_extrinsic` methods.
+	nonce_tracker: Arc<Mutex<NonceTracker<C::AccountId, C::Index>>>,
 	/// Saved chain runtime version
 	chain_runtime_version: ChainRuntimeVersion,
+	/// Dispatch error decoder, built from the metadata of the runtime with given spec version.
+	dispatch_error_decoder: Arc<Mutex<Option<(u32, Arc<DispatchErrorDecoder>)>>>,
+	/// Metrics of the block space, consumed by transactions that are submitted using
+	/// `submit_and_watch_*` methods.
+	block_space_metrics: BlockSpaceMetrics,
+}
+
+/// Connection to the Substrate node.
+struct Connection {
+	/// Tokio runtime handle.
+	tokio: Arc<tokio::runtime::Runtime>,
+	/// Substrate RPC client.
+	client: Arc<RpcClient>,
+	/// Connection generation. It is incremented every time we reconnect to the node.
+	generation: u64,
 }
 
 #[async_trait]
@@ -100,9 +140,41 @@ impl<C: Chain> relay_utils::relay_loop::Client for Client<C> {
 	type Error = Error;
 
 	async fn reconnect(&mut self) -> Result<()> {
+		// the connection is shared by all loops that are using this client, so we only want to
+		// reconnect once, even if all loops have failed
+		let _reconnect_guard = self.reconnect_lock.lock().await;
+
+		// if some other loop has reconnected after we have seen the connection, and the new
+		// connection is functional, we don't need to reconnect
+		let generation = self.connection.read().await.generation;
+		if generation != self.generation {
+			match self.ensure_connected().await {
+				Ok(()) => {
+					log::debug!(
+						target: "bridge",
+						"Connection to {} node has already been reestablished",
+						C::NAME,
+					);
+					self.generation = generation;
+					return Ok(())
+				},
+				Err(error) => log::debug!(
+					target: "bridge",
+					"Connection to {} node has been reestablished, but it is not functional: {:?}",
+					C::NAME,
+					error,
+				),
+			}
+		}
+
+		// other loops are paused until we have reconnected, because they can't acquire the
+		// connection while we're holding the write lock
+		let mut connection = self.connection.write().await;
 		let (tokio, client) = Self::build_client(&self.params).await?;
-		self.tokio = tokio;
-		self.client = client;
+		connection.tokio = tokio;
+		connection.client = client;
+		connection.generation += 1;
+		self.generation = connection.generation;
 		Ok(())
 	}
 }
@@ -110,12 +182,16 @@ impl<C: Chain> relay_utils::relay_loop::Client for Client<C> {
 impl<C: Chain> Clone for Client<C> {
 	fn clone(&self) -> Self {
 		Client {
-			tokio: self.tokio.clone(),
 			params: self.params.clone(),
-			client: self.client.clone(),
+			connection: self.connection.clone(),
+			generation: self.generation,
+			reconnect_lock: self.reconnect_lock.clone(),
 			genesis_hash: self.genesis_hash,
 			submit_signed_extrinsic_lock: self.submit_signed_extrinsic_lock.clone(),
+			nonce_tracker: self.nonce_tracker.clone(),
 			chain_runtime_version: self.chain_runtime_version.clone(),
+			dispatch_error_decoder: self.dispatch_error_decoder.clone(),
+			block_space_metrics: self.block_space_metrics.clone(),
 		}
 	}
 }
@@ -162,14 +238,27 @@ impl<C: Chain> Client<C> {
 			})
 			.await??;
 
+		let nonce_tracker = NonceTracker::new(C::NAME).map_err(|e| {
+			Error::Custom(format!("Failed to create {} nonce tracker: {}", C::NAME, e))
+		})?;
+
+		let block_space_metrics = BlockSpaceMetrics::new(C::NAME, C::max_extrinsic_weight())
+			.map_err(|e| {
+				Error::Custom(format!("Failed to create {} block space metrics: {}", C::NAME, e))
+			})?;
+
 		let chain_runtime_version = params.chain_runtime_version.clone();
 		Ok(Self {
-			tokio,
 			params,
-			client,
+			connection: Arc::new(RwLock::new(Connection { tokio, client, generation: 0 })),
+			generation: 0,
+			reconnect_lock: Arc::new(Mutex::new(())),
 			genesis_hash,
 			submit_signed_extrinsic_lock: Arc::new(Mutex::new(())),
+			nonce_tracker: Arc::new(Mutex::new(nonce_tracker)),
 			chain_runtime_version,
+			dispatch_error_decoder: Arc::new(Mutex::new(None)),
+			block_space_metrics,
 		})
 	}
 
@@ -232,6 +321,11 @@ impl<C: Chain> Client<C> {
 		&self.genesis_hash
 	}
 
+	/// Return params that are used to connect to the node.
+	pub fn connection_params(&self) -> &ConnectionParams {
+		&self.params
+	}
+
 	/// Return hash of the best finalized block.
 	pub async fn best_finalized_header_hash(&self) -> Result<C::Hash> {
 		self.jsonrpsee_execute(|client| async move {
@@ -306,6 +400,138 @@ impl<C: Chain> Client<C> {
 		.await
 	}
 
+	/// Return encoded runtime metadata.
+	pub async fn raw_metadata(&self, at_block: Option<C::Hash>) -> Result<Bytes> {
+		self.jsonrpsee_execute(move |client| async move {
+			Ok(SubstrateStateClient::<C>::metadata(&*client, at_block).await?)
+		})
+		.await
+	}
+
+	/// Ensure that indices of calls, encoded by the relay, match the live chain metadata.
+	pub async fn ensure_call_indices_match(&self) -> Result<()> {
+		let metadata = self.raw_metadata(None).await?;
+		check_call_indices(&metadata.0, &C::bundled_call_indices())
+	}
+
+	/// Return dispatch error decoder for the current runtime.
+	///
+	/// The decoder is only rebuilt when the runtime spec version changes.
+	pub async fn dispatch_error_decoder(&self) -> Result<Arc<DispatchErrorDecoder>> {
+		let spec_version = self.runtime_version().await?.spec_version;
+		let mut dispatch_error_decoder = self.dispatch_error_decoder.lock().await;
+		match *dispatch_error_decoder {
+			Some((decoder_spec_version, ref decoder)) if decoder_spec_version == spec_version =>
+				Ok(decoder.clone()),
+			_ => {
+				let decoder = Arc::new(DispatchErrorDecoder::from_encoded_metadata(
+					&self.raw_metadata(None).await?.0,
+				)?);
+				*dispatch_error_decoder = Some((spec_version, decoder.clone()));
+				Ok(decoder)
+			},
+		}
+	}
+
+	/// Return human-readable dispatch error of the extrinsic that has been included into given
+	/// block.
+	///
+	/// Returns `None` if the extrinsic has been dispatched successfully, or if it is missing
+	/// from the block.
+	pub async fn extrinsic_dispatch_error(
+		&self,
+		block_hash: C::Hash,
+		extrinsic_hash: C::Hash,
+	) -> Result<Option<String>> {
+		let block = self.get_block(Some(block_hash)).await?;
+		let extrinsic_index = match block
+			.extrinsics()
+			.iter()
+			.position(|extrinsic| C::Hasher::hash(extrinsic) == extrinsic_hash)
+		{
+			Some(extrinsic_index) => extrinsic_index as u32,
+			None => return Ok(None),
+		};
+
+		let events_key = bp_runtime::storage_value_key(SYSTEM_PALLET_NAME, EVENTS_VALUE_NAME);
+		let encoded_events = match self.raw_storage_value(events_key, Some(block_hash)).await? {
+			Some(encoded_events) => encoded_events,
+			None => return Ok(None),
+		};
+
+		let decoder = self.dispatch_error_decoder().await?;
+		Ok(decoder
+			.find_extrinsic_error(&encoded_events.0, extrinsic_index)?
+			.map(|error| decoder.decode_error(&error)))
+	}
+
+	/// Return event with given name, that has been deposited by given pallet during dispatch of
+	/// the extrinsic, included into given block.
+	///
+	/// Returns `None` if there's no such event, or if the extrinsic is missing from the block.
+	pub async fn extrinsic_event<T: Decode>(
+		&self,
+		block_hash: C::Hash,
+		extrinsic_hash: C::Hash,
+		pallet_name: &str,
+		event_name: &str,
+	) -> Result<Option<T>> {
+		let block = self.get_block(Some(block_hash)).await?;
+		let extrinsic_index = match block
+			.extrinsics()
+			.iter()
+			.position(|extrinsic| C::Hasher::hash(extrinsic) == extrinsic_hash)
+		{
+			Some(extrinsic_index) => extrinsic_index as u32,
+			None => return Ok(None),
+		};
+
+		let events_key = bp_runtime::storage_value_key(SYSTEM_PALLET_NAME, EVENTS_VALUE_NAME);
+		let encoded_events = match self.raw_storage_value(events_key, Some(block_hash)).await? {
+			Some(encoded_events) => encoded_events,
+			None => return Ok(None),
+		};
+
+		self.dispatch_error_decoder()
+			.await?
+			.find_extrinsic_event(&encoded_events.0, extrinsic_index, pallet_name, event_name)?
+			.map(|encoded_event| {
+				T::decode(&mut &encoded_event[..]).map_err(Error::ResponseParseFailed)
+			})
+			.transpose()
+	}
+
+	/// Return actual (post-dispatch) weight of the extrinsic, included into given block.
+	///
+	/// Returns `None` if the extrinsic is missing from the block.
+	pub async fn extrinsic_actual_weight(
+		&self,
+		block_hash: C::Hash,
+		extrinsic_hash: C::Hash,
+	) -> Result<Option<Weight>> {
+		let success_info: Option<DispatchInfo> = self
+			.extrinsic_event(
+				block_hash,
+				extrinsic_hash,
+				SYSTEM_PALLET_NAME,
+				EXTRINSIC_SUCCESS_EVENT_NAME,
+			)
+			.await?;
+		if let Some(success_info) = success_info {
+			return Ok(Some(success_info.weight))
+		}
+
+		let failure_info: Option<(DispatchError, DispatchInfo)> = self
+			.extrinsic_event(
+				block_hash,
+				extrinsic_hash,
+				SYSTEM_PALLET_NAME,
+				EXTRINSIC_FAILED_EVENT_NAME,
+			)
+			.await?;
+		Ok(failure_info.map(|(_, failure_info)| failure_info.weight))
+	}
+
 	/// Read value from runtime storage.
 	pub async fn storage_value<T: Send + Decode + 'static>(
 		&self,
@@ -355,6 +581,25 @@ impl<C: Chain> Client<C> {
 			.transpose()
 	}
 
+	/// Read triple `NMapStorage` value from runtime storage.
+	pub async fn storage_triple_map_value<T: StorageTripleMapKeyProvider>(
+		&self,
+		pallet_prefix: &str,
+		key1: &T::Key1,
+		key2: &T::Key2,
+		key3: &T::Key3,
+		block_hash: Option<C::Hash>,
+	) -> Result<Option<T::Value>> {
+		let storage_key = T::final_key(pallet_prefix, key1, key2, key3);
+
+		self.raw_storage_value(storage_key, block_hash)
+			.await?
+			.map(|encoded_value| {
+				T::Value::decode(&mut &encoded_value.0[..]).map_err(Error::ResponseParseFailed)
+			})
+			.transpose()
+	}
+
 	/// Read raw value from runtime storage.
 	pub async fn raw_storage_value(
 		&self,
@@ -430,7 +675,8 @@ impl<C: Chain> Client<C> {
 			+ 'static,
 	) -> Result<C::Hash> {
 		let _guard = self.submit_signed_extrinsic_lock.lock().await;
-		let transaction_nonce = self.next_account_index(extrinsic_signer).await?;
+		let signer = signing_data.signer.clone();
+		let transaction_nonce = self.select_transaction_nonce(&extrinsic_signer).await?;
 		let best_header = self.best_header().await?;
 
 		// By using parent of best block here, we are protecing again best-block reorganizations.
@@ -440,20 +686,23 @@ impl<C: Chain> Client<C> {
 		// will be dropped from the pool.
 		let best_header_id = best_header.parent_id().unwrap_or_else(|| best_header.id());
 
-		self.jsonrpsee_execute(move |client| async move {
-			let extrinsic = prepare_extrinsic(best_header_id, transaction_nonce)?;
-			let signed_extrinsic = S::sign_transaction(signing_data, extrinsic)?.encode();
-			let tx_hash =
-				SubstrateAuthorClient::<C>::submit_extrinsic(&*client, Bytes(signed_extrinsic))
-					.await
-					.map_err(|e| {
-						log::error!(target: "bridge", "Failed to send transaction to {} node: {:?}", C::NAME, e);
-						e
-					})?;
-			log::trace!(target: "bridge", "Sent transaction to {} node: {:?}", C::NAME, tx_hash);
-			Ok(tx_hash)
-		})
-		.await
+		let result = self
+			.jsonrpsee_execute(move |client| async move {
+				let extrinsic = prepare_extrinsic(best_header_id, transaction_nonce)?;
+				let signed_extrinsic = S::sign_transaction(signing_data, extrinsic)?.encode();
+				let tx_hash =
+					SubstrateAuthorClient::<C>::submit_extrinsic(&*client, Bytes(signed_extrinsic))
+						.await
+						.map_err(|e| {
+							log::error!(target: "bridge", "Failed to send transaction to {} node: {:?}", C::NAME, e);
+							e
+						})?;
+				log::trace!(target: "bridge", "Sent transaction to {} node: {:?}", C::NAME, tx_hash);
+				Ok(tx_hash)
+			})
+			.await;
+		self.process_submission_result::<S, _>(extrinsic_signer, &signer, transaction_nonce, result)
+			.await
 	}
 
 	/// Does exactly the same as `submit_signed_extrinsic`, but keeps watching for extrinsic status
@@ -470,7 +719,8 @@ impl<C: Chain> Client<C> {
 	) -> Result<TransactionTracker<C, Self>> {
 		let self_clone = self.clone();
 		let _guard = self.submit_signed_extrinsic_lock.lock().await;
-		let transaction_nonce = self.next_account_index(extrinsic_signer).await?;
+		let signer = signing_data.signer.clone();
+		let transaction_nonce = self.select_transaction_nonce(&extrinsic_signer).await?;
 		let best_header = self.best_header().await?;
 		let best_header_id = best_header.id();
 		let (sender, receiver) = futures::channel::mpsc::channel(MAX_SUBSCRIPTION_CAPACITY);
@@ -484,6 +734,7 @@ impl<C: Chain> Client<C> {
 				);
 				let signed_extrinsic = S::sign_transaction(signing_data, extrinsic)?.encode();
 				let tx_hash = C::Hasher::hash(&signed_extrinsic);
+				let tx_size = u32::try_from(signed_extrinsic.len()).unwrap_or(u32::MAX);
 				let subscription = SubstrateAuthorClient::<C>::submit_and_watch_extrinsic(
 					&*client,
 					Bytes(signed_extrinsic),
@@ -494,16 +745,65 @@ impl<C: Chain> Client<C> {
 					e
 				})?;
 				log::trace!(target: "bridge", "Sent transaction to {} node: {:?}", C::NAME, tx_hash);
+				let block_space_metrics = self_clone.block_space_metrics.clone();
 				let tracker = TransactionTracker::new(
 					self_clone,
 					stall_timeout,
 					tx_hash,
+					tx_size,
+					Subscription(Mutex::new(receiver)),
+					block_space_metrics,
+				);
+				Ok((tracker, subscription))
+			})
+			.await;
+		let (tracker, subscription) = self
+			.process_submission_result::<S, _>(extrinsic_signer, &signer, transaction_nonce, result)
+			.await?;
+		self.connection().await.0.spawn(Subscription::background_worker(
+			C::NAME.into(),
+			"extrinsic".into(),
+			subscription,
+			sender,
+		));
+		Ok(tracker)
+	}
+
+	/// Submit extrinsic that has been signed elsewhere and keep watching for its status.
+	///
+	/// The client knows nothing about mortality of such extrinsic, so the default stall timeout
+	/// is used.
+	pub async fn submit_and_watch_raw_extrinsic(
+		&self,
+		extrinsic: Bytes,
+	) -> Result<TransactionTracker<C, Self>> {
+		let self_clone = self.clone();
+		let (sender, receiver) = futures::channel::mpsc::channel(MAX_SUBSCRIPTION_CAPACITY);
+		let (tracker, subscription) = self
+			.jsonrpsee_execute(move |client| async move {
+				let tx_hash = C::Hasher::hash(&extrinsic.0);
+				let tx_size = u32::try_from(extrinsic.0.len()).unwrap_or(u32::MAX);
+				let subscription =
+					SubstrateAuthorClient::<C>::submit_and_watch_extrinsic(&*client, extrinsic)
+						.await
+						.map_err(|e| {
+							log::error!(target: "bridge", "Failed to send transaction to {} node: {:?}", C::NAME, e);
+							e
+						})?;
+				log::trace!(target: "bridge", "Sent transaction to {} node: {:?}", C::NAME, tx_hash);
+				let block_space_metrics = self_clone.block_space_metrics.clone();
+				let tracker = TransactionTracker::new(
+					self_clone,
+					STALL_TIMEOUT,
+					tx_hash,
+					tx_size,
 					Subscription(Mutex::new(receiver)),
+					block_space_metrics,
 				);
 				Ok((tracker, subscription))
 			})
 			.await?;
-		self.tokio.spawn(Subscription::background_worker(
+		self.connection().await.0.spawn(Subscription::background_worker(
 			C::NAME.into(),
 			"extrinsic".into(),
 			subscription,
@@ -512,6 +812,96 @@ impl<C: Chain> Client<C> {
 		Ok(tracker)
 	}
 
+	/// Register metrics of the nonce tracker.
+	pub async fn register_nonce_tracker_metrics(
+		&self,
+		registry: &Registry,
+	) -> std::result::Result<(), PrometheusError> {
+		self.nonce_tracker.lock().await.register_metrics(registry)
+	}
+
+	/// Register metrics of the block space, consumed by relay transactions.
+	pub fn register_block_space_metrics(
+		&self,
+		registry: &Registry,
+	) -> std::result::Result<(), PrometheusError> {
+		self.block_space_metrics.register(registry)
+	}
+
+	/// Select nonce for the next transaction of given account.
+	async fn select_transaction_nonce(&self, account: &C::AccountId) -> Result<C::Index> {
+		let account_next_index = self.next_account_index(account.clone()).await?;
+		Ok(self.nonce_tracker.lock().await.select_nonce(account, account_next_index))
+	}
+
+	/// Update nonce tracker, given result of the transaction submission.
+	///
+	/// If transactions of the account are repeatedly rejected because of invalid nonce, the
+	/// account nonce is resynchronized with the chain.
+	async fn process_submission_result<S: TransactionSignScheme<Chain = C>, T>(
+		&self,
+		account: C::AccountId,
+		signer: &S::AccountKeyPair,
+		nonce: C::Index,
+		result: Result<T>,
+	) -> Result<T> {
+		match result {
+			Ok(result) => {
+				self.nonce_tracker.lock().await.note_accepted(account, nonce);
+				Ok(result)
+			},
+			Err(error) if error.is_invalid_nonce_error() => {
+				let resync_required =
+					self.nonce_tracker.lock().await.note_rejected(account.clone());
+				if resync_required {
+					if let Err(resync_error) = self.resync_nonce::<S>(account, signer).await {
+						log::error!(
+							target: "bridge",
+							"Failed to resynchronize {} relay account nonce: {:?}",
+							C::NAME,
+							resync_error,
+						);
+					}
+				}
+				Err(error)
+			},
+			Err(error) => Err(error),
+		}
+	}
+
+	/// Resynchronize account nonce with the chain.
+	///
+	/// Transactions of the account that are still in the pool are not replaced.
+	async fn resync_nonce<S: TransactionSignScheme<Chain = C>>(
+		&self,
+		account: C::AccountId,
+		signer: &S::AccountKeyPair,
+	) -> Result<()> {
+		let account_next_index = self.next_account_index(account.clone()).await?;
+		let own_pending_nonces = self
+			.pending_extrinsics()
+			.await?
+			.into_iter()
+			.filter_map(|tx| S::SignedTransaction::decode(&mut &tx.0[..]).ok())
+			.filter(|tx| S::is_signed_by(signer, tx))
+			.filter_map(S::parse_transaction)
+			.map(|tx| tx.nonce)
+			.collect::<Vec<_>>();
+		let next_nonce =
+			self.nonce_tracker
+				.lock()
+				.await
+				.resync(account, account_next_index, own_pending_nonces);
+		log::warn!(
+			target: "bridge",
+			"Transactions from the {} relay account have been repeatedly rejected because of invalid \
+			nonce. Resynchronized nonce with the chain: {:?}",
+			C::NAME,
+			next_nonce,
+		);
+		Ok(())
+	}
+
 	/// Returns pending extrinsics from transaction pool.
 	pub async fn pending_extrinsics(&self) -> Result<Vec<Bytes>> {
 		self.jsonrpsee_execute(move |client| async move {
@@ -589,6 +979,12 @@ impl<C: Chain> Client<C> {
 		.await
 	}
 
+	/// Get the GRANDPA authorities set id at given block.
+	pub async fn grandpa_current_set_id(&self, block: C::Hash) -> Result<SetId> {
+		self.typed_state_call(SUB_API_GRANDPA_CURRENT_SET_ID.into(), (), Some(block))
+			.await
+	}
+
 	/// Execute runtime call at given block, provided the input and output types.
 	/// It also performs the input encode and output decode.
 	pub async fn typed_state_call<Input: codec::Encode, Output: codec::Decode>(
@@ -650,7 +1046,7 @@ impl<C: Chain> Client<C> {
 			})
 			.await?;
 		let (sender, receiver) = futures::channel::mpsc::channel(MAX_SUBSCRIPTION_CAPACITY);
-		self.tokio.spawn(Subscription::background_worker(
+		self.connection().await.0.spawn(Subscription::background_worker(
 			C::NAME.into(),
 			"justification".into(),
 			subscription,
@@ -666,8 +1062,23 @@ impl<C: Chain> Client<C> {
 		F: Future<Output = Result<T>> + Send,
 		T: Send + 'static,
 	{
-		let client = self.client.clone();
-		self.tokio.spawn(async move { make_jsonrpsee_future(client).await }).await?
+		let (tokio, client) = self.connection().await;
+		tokio.spawn(async move { make_jsonrpsee_future(client).await }).await?
+	}
+
+	/// Returns current connection to the node.
+	async fn connection(&self) -> (Arc<tokio::runtime::Runtime>, Arc<RpcClient>) {
+		let connection = self.connection.read().await;
+		(connection.tokio.clone(), connection.client.clone())
+	}
+
+	/// Returns `Ok(())` if connection to the node is functional.
+	async fn ensure_connected(&self) -> Result<()> {
+		self.jsonrpsee_execute(|client| async move {
+			SubstrateSystemClient::<C>::health(&*client).await?;
+			Ok(())
+		})
+		.await
 	}
 
 	/// Returns `true` if version guard can be started.
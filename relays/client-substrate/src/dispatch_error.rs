@@ -0,0 +1,606 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Decoding dispatch errors of failed transactions using the runtime metadata.
+
+use crate::error::{Error, Result};
+
+use codec::{Compact, Decode};
+use frame_metadata::{
+	v14::{PalletMetadata, RuntimeMetadataV14, StorageEntryType},
+	RuntimeMetadata, RuntimeMetadataPrefixed,
+};
+use frame_system::Phase;
+use scale_info::{form::PortableForm, PortableRegistry, TypeDef, TypeDefPrimitive};
+use sp_runtime::DispatchError;
+use std::collections::BTreeMap;
+
+/// Name of the system pallet.
+pub(crate) const SYSTEM_PALLET_NAME: &str = "System";
+/// Name of the `Events` storage value of the system pallet.
+pub(crate) const EVENTS_VALUE_NAME: &str = "Events";
+/// Name of the system pallet event that is deposited when extrinsic dispatch succeeds.
+pub(crate) const EXTRINSIC_SUCCESS_EVENT_NAME: &str = "ExtrinsicSuccess";
+/// Name of the system pallet event that is deposited when extrinsic dispatch fails.
+pub(crate) const EXTRINSIC_FAILED_EVENT_NAME: &str = "ExtrinsicFailed";
+
+/// Details of the pallet error variant.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct ErrorVariant {
+	/// Name of the error variant.
+	name: String,
+	/// First line of the error variant documentation.
+	doc: Option<String>,
+}
+
+/// Pallet errors.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct PalletErrors {
+	/// Name of the pallet.
+	name: String,
+	/// Error variants, mapped by their index.
+	variants: BTreeMap<u8, ErrorVariant>,
+}
+
+/// Layout of the system pallet `Events` storage value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct EventsLayout {
+	/// Type of the `EventRecord`.
+	record_ty: u32,
+	/// Index of the system pallet.
+	system_pallet_index: u8,
+	/// Index of the `ExtrinsicFailed` event variant.
+	extrinsic_failed_index: u8,
+}
+
+/// Decoder of the dispatch errors of the failed transactions.
+///
+/// Converts module errors (pallet index and error index) into human-readable
+/// `Pallet::ErrorVariant` form, using the runtime metadata. If some index is unknown to the
+/// metadata, the numeric form is used instead. The decoder is also able to find events that
+/// have been deposited during extrinsic dispatch.
+#[derive(Clone, Debug)]
+pub struct DispatchErrorDecoder {
+	types: PortableRegistry,
+	pallets: BTreeMap<u8, PalletErrors>,
+	pallet_events: BTreeMap<(String, String), (u8, u8)>,
+	events: Option<EventsLayout>,
+}
+
+impl DispatchErrorDecoder {
+	/// Create decoder from the encoded runtime metadata.
+	pub fn from_encoded_metadata(encoded_metadata: &[u8]) -> Result<Self> {
+		Self::new(RuntimeMetadataPrefixed::decode(&mut &encoded_metadata[..])?)
+	}
+
+	/// Create decoder from the runtime metadata.
+	pub fn new(metadata: RuntimeMetadataPrefixed) -> Result<Self> {
+		let metadata = match metadata.1 {
+			RuntimeMetadata::V14(metadata) => metadata,
+			_ => return Err(Error::Custom("Only V14 runtime metadata is supported".into())),
+		};
+
+		let pallets = metadata
+			.pallets
+			.iter()
+			.filter_map(|pallet| {
+				let error_ty = pallet.error.as_ref()?.ty.id();
+				Some((
+					pallet.index,
+					PalletErrors {
+						name: pallet.name.clone(),
+						variants: enum_variants(&metadata.types, error_ty)
+							.map(|(index, name, docs)| {
+								(
+									index,
+									ErrorVariant {
+										name: name.clone(),
+										doc: docs.first().map(|doc| doc.trim().to_string()),
+									},
+								)
+							})
+							.collect(),
+					},
+				))
+			})
+			.collect();
+		let pallet_events = metadata
+			.pallets
+			.iter()
+			.filter_map(|pallet| Some((pallet, pallet.event.as_ref()?.ty.id())))
+			.flat_map(|(pallet, event_ty)| {
+				enum_variants(&metadata.types, event_ty).map(move |(index, name, _)| {
+					((pallet.name.clone(), name.clone()), (pallet.index, index))
+				})
+			})
+			.collect();
+		let events = events_layout(&metadata);
+
+		Ok(DispatchErrorDecoder { types: metadata.types, pallets, pallet_events, events })
+	}
+
+	/// Returns human-readable representation of the dispatch error.
+	pub fn decode_error(&self, error: &DispatchError) -> String {
+		let module_error = match *error {
+			DispatchError::Module(ref module_error) => module_error,
+			_ => return format!("{:?}", error),
+		};
+
+		let error_index = module_error.error[0];
+		let pallet = match self.pallets.get(&module_error.index) {
+			Some(pallet) => pallet,
+			None =>
+				return format!("Module {{ index: {}, error: {} }}", module_error.index, error_index),
+		};
+		match pallet.variants.get(&error_index) {
+			Some(ErrorVariant { name, doc: Some(doc) }) =>
+				format!("{}::{} ({})", pallet.name, name, doc),
+			Some(ErrorVariant { name, doc: None }) => format!("{}::{}", pallet.name, name),
+			None => format!("{}::{{ error: {} }}", pallet.name, error_index),
+		}
+	}
+
+	/// Find dispatch error of given extrinsic in the encoded value of the system pallet
+	/// `Events` storage value.
+	///
+	/// Returns `None` if the extrinsic has not failed.
+	pub fn find_extrinsic_error(
+		&self,
+		encoded_events: &[u8],
+		extrinsic_index: u32,
+	) -> Result<Option<DispatchError>> {
+		let layout = self.events_layout()?;
+		for encoded_event in self.extrinsic_events(encoded_events, extrinsic_index)? {
+			let input = &mut &encoded_event[..];
+			let pallet_index = u8::decode(input)?;
+			let event_index = u8::decode(input)?;
+			if pallet_index == layout.system_pallet_index &&
+				event_index == layout.extrinsic_failed_index
+			{
+				return Ok(Some(DispatchError::decode(input)?))
+			}
+		}
+
+		Ok(None)
+	}
+
+	/// Find event with given name, deposited by given pallet during given extrinsic dispatch, in
+	/// the encoded value of the system pallet `Events` storage value.
+	///
+	/// Returns encoded event fields of the first matching event, or `None` if there's no such
+	/// event.
+	pub fn find_extrinsic_event(
+		&self,
+		encoded_events: &[u8],
+		extrinsic_index: u32,
+		pallet_name: &str,
+		event_name: &str,
+	) -> Result<Option<Vec<u8>>> {
+		let (pallet_index, event_index) = self
+			.pallet_events
+			.get(&(pallet_name.into(), event_name.into()))
+			.copied()
+			.ok_or_else(|| {
+				Error::Custom(format!(
+					"Event {}::{} is missing from the runtime metadata",
+					pallet_name, event_name,
+				))
+			})?;
+		Ok(self
+			.extrinsic_events(encoded_events, extrinsic_index)?
+			.into_iter()
+			.find(|encoded_event| encoded_event.starts_with(&[pallet_index, event_index]))
+			.map(|encoded_event| encoded_event[2..].to_vec()))
+	}
+
+	/// Returns layout of the system pallet `Events` storage value.
+	fn events_layout(&self) -> Result<&EventsLayout> {
+		self.events.as_ref().ok_or_else(|| {
+			Error::Custom("System events are not described by the runtime metadata".into())
+		})
+	}
+
+	/// Returns encoded events, deposited during given extrinsic dispatch.
+	fn extrinsic_events<'a>(
+		&self,
+		encoded_events: &'a [u8],
+		extrinsic_index: u32,
+	) -> Result<Vec<&'a [u8]>> {
+		let layout = self.events_layout()?;
+		let record_fields = match self.types.resolve(layout.record_ty).map(|ty| ty.type_def()) {
+			Some(TypeDef::Composite(composite)) => composite.fields(),
+			_ => return Err(Error::Custom("Unexpected type of the system event record".into())),
+		};
+
+		let mut extrinsic_events = Vec::new();
+		let input = &mut &encoded_events[..];
+		let records_count = Compact::<u32>::decode(input)?.0;
+		for _ in 0..records_count {
+			let mut phase = None;
+			let mut event = None;
+			for field in record_fields {
+				let encoded_field = *input;
+				skip_value(&self.types, field.ty().id(), input)?;
+				let encoded_field = &encoded_field[..encoded_field.len() - input.len()];
+				match field.name().map(|name| name.as_str()) {
+					Some("phase") => phase = Some(Phase::decode(&mut &encoded_field[..])?),
+					Some("event") => event = Some(encoded_field),
+					_ => (),
+				}
+			}
+
+			if phase == Some(Phase::ApplyExtrinsic(extrinsic_index)) {
+				extrinsic_events.extend(event);
+			}
+		}
+
+		Ok(extrinsic_events)
+	}
+}
+
+/// Returns index, name and docs of all variants of given enum type.
+fn enum_variants<'a>(
+	types: &'a PortableRegistry,
+	ty: u32,
+) -> impl Iterator<Item = (u8, &'a String, &'a [String])> {
+	let variants = match types.resolve(ty).map(|ty| ty.type_def()) {
+		Some(TypeDef::Variant(variant)) => variant.variants(),
+		_ => &[],
+	};
+	variants.iter().map(|variant| (variant.index(), variant.name(), variant.docs()))
+}
+
+/// Returns layout of the system pallet `Events` storage value.
+fn events_layout(metadata: &RuntimeMetadataV14) -> Option<EventsLayout> {
+	let system_pallet: &PalletMetadata<PortableForm> =
+		metadata.pallets.iter().find(|pallet| pallet.name == SYSTEM_PALLET_NAME)?;
+	let events_ty = system_pallet
+		.storage
+		.as_ref()?
+		.entries
+		.iter()
+		.find(|entry| entry.name == EVENTS_VALUE_NAME)
+		.and_then(|entry| match entry.ty {
+			StorageEntryType::Plain(ref ty) => Some(ty.id()),
+			_ => None,
+		})?;
+	let record_ty = match metadata.types.resolve(events_ty)?.type_def() {
+		TypeDef::Sequence(sequence) => sequence.type_param().id(),
+		_ => return None,
+	};
+	let extrinsic_failed_index =
+		enum_variants(&metadata.types, system_pallet.event.as_ref()?.ty.id())
+			.find(|(_, name, _)| name.as_str() == EXTRINSIC_FAILED_EVENT_NAME)
+			.map(|(index, _, _)| index)?;
+
+	Some(EventsLayout {
+		record_ty,
+		system_pallet_index: system_pallet.index,
+		extrinsic_failed_index,
+	})
+}
+
+/// Skip encoded value of given type.
+fn skip_value(types: &PortableRegistry, ty: u32, input: &mut &[u8]) -> Result<()> {
+	let ty = types
+		.resolve(ty)
+		.ok_or_else(|| Error::Custom(format!("Type {} is missing from the metadata", ty)))?;
+	match ty.type_def() {
+		TypeDef::Composite(composite) =>
+			for field in composite.fields() {
+				skip_value(types, field.ty().id(), input)?;
+			},
+		TypeDef::Variant(variant) => {
+			let index = u8::decode(input)?;
+			let variant =
+				variant.variants().iter().find(|v| v.index() == index).ok_or_else(|| {
+					Error::Custom(format!("Unknown variant {} of the type {:?}", index, ty.path()))
+				})?;
+			for field in variant.fields() {
+				skip_value(types, field.ty().id(), input)?;
+			}
+		},
+		TypeDef::Sequence(sequence) => {
+			let len = Compact::<u32>::decode(input)?.0;
+			for _ in 0..len {
+				skip_value(types, sequence.type_param().id(), input)?;
+			}
+		},
+		TypeDef::Array(array) =>
+			for _ in 0..array.len() {
+				skip_value(types, array.type_param().id(), input)?;
+			},
+		TypeDef::Tuple(tuple) =>
+			for field in tuple.fields() {
+				skip_value(types, field.id(), input)?;
+			},
+		TypeDef::Primitive(primitive) => {
+			let len = match primitive {
+				TypeDefPrimitive::Bool | TypeDefPrimitive::U8 | TypeDefPrimitive::I8 => 1,
+				TypeDefPrimitive::U16 | TypeDefPrimitive::I16 => 2,
+				TypeDefPrimitive::Char | TypeDefPrimitive::U32 | TypeDefPrimitive::I32 => 4,
+				TypeDefPrimitive::U64 | TypeDefPrimitive::I64 => 8,
+				TypeDefPrimitive::U128 | TypeDefPrimitive::I128 => 16,
+				TypeDefPrimitive::U256 | TypeDefPrimitive::I256 => 32,
+				TypeDefPrimitive::Str => Compact::<u32>::decode(input)?.0 as usize,
+			};
+			skip_bytes(input, len)?;
+		},
+		TypeDef::Compact(_) => {
+			Compact::<u128>::decode(input)?;
+		},
+		TypeDef::BitSequence(_) =>
+			return Err(Error::Custom("Bit sequences are not supported".into())),
+	}
+
+	Ok(())
+}
+
+/// Skip given number of bytes.
+fn skip_bytes(input: &mut &[u8], len: usize) -> Result<()> {
+	if input.len() < len {
+		return Err(Error::ResponseParseFailed("Not enough data to skip".into()))
+	}
+	*input = &input[len..];
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::Encode;
+	use frame_metadata::v14::{
+		ExtrinsicMetadata, PalletErrorMetadata, PalletEventMetadata, PalletStorageMetadata,
+		StorageEntryMetadata, StorageEntryModifier,
+	};
+	use frame_support::weights::DispatchInfo;
+	use scale_info::{meta_type, TypeInfo};
+	use sp_core::H256;
+	use sp_runtime::ModuleError;
+
+	const MESSAGES_PALLET_INDEX: u8 = 7;
+
+	#[allow(dead_code)]
+	#[derive(TypeInfo)]
+	enum TestMessagesError {
+		/// Pallet is not in normal operating mode.
+		NotOperatingNormally,
+		/// The message someone is trying to work with (i.e. increase fee) is already-delivered.
+		///
+		/// Second line of the docs.
+		MessageIsAlreadyDelivered,
+		TooManyUnconfirmedMessages,
+	}
+
+	#[derive(Encode, TypeInfo)]
+	enum TestSystemEvent {
+		ExtrinsicSuccess { dispatch_info: DispatchInfo },
+		ExtrinsicFailed { dispatch_error: DispatchError, dispatch_info: DispatchInfo },
+		Remarked { sender: u64, hash: H256 },
+	}
+
+	#[derive(Encode, TypeInfo)]
+	enum TestMessagesEvent {
+		MessageAccepted(Vec<u8>, u64),
+		MessagesDelivered { lane_id: [u8; 4], messages: Vec<(u64, bool)> },
+	}
+
+	#[derive(Encode, TypeInfo)]
+	enum TestRuntimeEvent {
+		#[codec(index = 0)]
+		System(TestSystemEvent),
+		#[codec(index = 7)]
+		BridgePass3dtMessages(TestMessagesEvent),
+	}
+
+	#[derive(Encode, TypeInfo)]
+	struct TestEventRecord {
+		phase: Phase,
+		event: TestRuntimeEvent,
+		topics: Vec<H256>,
+	}
+
+	fn test_metadata() -> RuntimeMetadataPrefixed {
+		RuntimeMetadataV14::new(
+			vec![
+				PalletMetadata {
+					name: "System",
+					storage: Some(PalletStorageMetadata {
+						prefix: "System",
+						entries: vec![StorageEntryMetadata {
+							name: "Events",
+							modifier: StorageEntryModifier::Default,
+							ty: StorageEntryType::Plain(meta_type::<Vec<TestEventRecord>>()),
+							default: vec![0],
+							docs: vec![],
+						}],
+					}),
+					calls: None,
+					event: Some(PalletEventMetadata { ty: meta_type::<TestSystemEvent>() }),
+					constants: vec![],
+					error: None,
+					index: 0,
+				},
+				PalletMetadata {
+					name: "BridgePass3dtMessages",
+					storage: None,
+					calls: None,
+					event: Some(PalletEventMetadata { ty: meta_type::<TestMessagesEvent>() }),
+					constants: vec![],
+					error: Some(PalletErrorMetadata { ty: meta_type::<TestMessagesError>() }),
+					index: MESSAGES_PALLET_INDEX,
+				},
+			],
+			ExtrinsicMetadata { ty: meta_type::<()>(), version: 4, signed_extensions: vec![] },
+			meta_type::<()>(),
+		)
+		.into()
+	}
+
+	fn test_decoder() -> DispatchErrorDecoder {
+		DispatchErrorDecoder::from_encoded_metadata(&test_metadata().encode()).unwrap()
+	}
+
+	fn module_error(index: u8, error: u8) -> DispatchError {
+		DispatchError::Module(ModuleError { index, error: [error, 0, 0, 0], message: None })
+	}
+
+	fn failed_event(dispatch_error: DispatchError) -> TestRuntimeEvent {
+		TestRuntimeEvent::System(TestSystemEvent::ExtrinsicFailed {
+			dispatch_error,
+			dispatch_info: Default::default(),
+		})
+	}
+
+	fn record(extrinsic_index: u32, event: TestRuntimeEvent) -> TestEventRecord {
+		TestEventRecord {
+			phase: Phase::ApplyExtrinsic(extrinsic_index),
+			event,
+			topics: vec![H256::repeat_byte(extrinsic_index as u8)],
+		}
+	}
+
+	#[test]
+	fn decodes_known_module_error() {
+		let decoder = test_decoder();
+		assert_eq!(
+			decoder.decode_error(&module_error(MESSAGES_PALLET_INDEX, 1)),
+			"BridgePass3dtMessages::MessageIsAlreadyDelivered (The message someone is trying to \
+			work with (i.e. increase fee) is already-delivered.)",
+		);
+		assert_eq!(
+			decoder.decode_error(&module_error(MESSAGES_PALLET_INDEX, 2)),
+			"BridgePass3dtMessages::TooManyUnconfirmedMessages",
+		);
+	}
+
+	#[test]
+	fn decodes_unknown_module_error_as_numbers() {
+		let decoder = test_decoder();
+		assert_eq!(
+			decoder.decode_error(&module_error(MESSAGES_PALLET_INDEX, 42)),
+			"BridgePass3dtMessages::{ error: 42 }",
+		);
+		assert_eq!(decoder.decode_error(&module_error(42, 1)), "Module { index: 42, error: 1 }");
+	}
+
+	#[test]
+	fn decodes_non_module_error() {
+		assert_eq!(test_decoder().decode_error(&DispatchError::BadOrigin), "BadOrigin");
+	}
+
+	#[test]
+	fn finds_extrinsic_error_in_events() {
+		let events = vec![
+			TestEventRecord {
+				phase: Phase::Initialization,
+				event: TestRuntimeEvent::System(TestSystemEvent::Remarked {
+					sender: 1,
+					hash: Default::default(),
+				}),
+				topics: vec![],
+			},
+			record(
+				0,
+				TestRuntimeEvent::System(TestSystemEvent::ExtrinsicSuccess {
+					dispatch_info: Default::default(),
+				}),
+			),
+			record(
+				1,
+				TestRuntimeEvent::BridgePass3dtMessages(TestMessagesEvent::MessagesDelivered {
+					lane_id: [0, 0, 0, 1],
+					messages: vec![(1, true), (2, false)],
+				}),
+			),
+			record(1, failed_event(module_error(MESSAGES_PALLET_INDEX, 2))),
+			record(
+				2,
+				TestRuntimeEvent::BridgePass3dtMessages(TestMessagesEvent::MessageAccepted(
+					vec![1, 2, 3],
+					4,
+				)),
+			),
+			record(3, failed_event(DispatchError::BadOrigin)),
+		]
+		.encode();
+
+		let decoder = test_decoder();
+		assert_eq!(decoder.find_extrinsic_error(&events, 0).unwrap(), None);
+		assert_eq!(
+			decoder.find_extrinsic_error(&events, 1).unwrap(),
+			Some(module_error(MESSAGES_PALLET_INDEX, 2)),
+		);
+		assert_eq!(decoder.find_extrinsic_error(&events, 2).unwrap(), None);
+		assert_eq!(
+			decoder.find_extrinsic_error(&events, 3).unwrap(),
+			Some(DispatchError::BadOrigin),
+		);
+		assert_eq!(decoder.find_extrinsic_error(&events, 4).unwrap(), None);
+	}
+
+	#[test]
+	fn finds_extrinsic_event_in_events() {
+		let events = vec![
+			record(
+				0,
+				TestRuntimeEvent::BridgePass3dtMessages(TestMessagesEvent::MessageAccepted(
+					vec![1, 2, 3],
+					4,
+				)),
+			),
+			record(
+				1,
+				TestRuntimeEvent::BridgePass3dtMessages(TestMessagesEvent::MessagesDelivered {
+					lane_id: [0, 0, 0, 1],
+					messages: vec![],
+				}),
+			),
+			record(
+				1,
+				TestRuntimeEvent::BridgePass3dtMessages(TestMessagesEvent::MessageAccepted(
+					vec![4, 5, 6],
+					7,
+				)),
+			),
+		]
+		.encode();
+
+		let decoder = test_decoder();
+		assert_eq!(
+			decoder
+				.find_extrinsic_event(&events, 1, "BridgePass3dtMessages", "MessageAccepted")
+				.unwrap(),
+			Some((vec![4u8, 5, 6], 7u64).encode()),
+		);
+		assert_eq!(
+			decoder
+				.find_extrinsic_event(&events, 2, "BridgePass3dtMessages", "MessageAccepted")
+				.unwrap(),
+			None,
+		);
+		assert!(decoder
+			.find_extrinsic_event(&events, 1, "BridgePass3dtMessages", "UnknownEvent")
+			.is_err());
+	}
+
+	#[test]
+	fn fails_to_find_extrinsic_error_in_malformed_events() {
+		let mut events = vec![record(0, failed_event(DispatchError::BadOrigin))].encode();
+		events.truncate(events.len() - 1);
+		assert!(test_decoder().find_extrinsic_error(&events, 0).is_err());
+	}
+}
@@ -16,10 +16,11 @@
 
 //! Substrate node RPC errors.
 
+use bp_messages::LaneId;
 use jsonrpsee::core::Error as RpcError;
 use relay_utils::MaybeConnectionError;
 use sc_rpc_api::system::Health;
-use sp_runtime::transaction_validity::TransactionValidityError;
+use sp_runtime::transaction_validity::{InvalidTransaction, TransactionValidityError};
 use thiserror::Error;
 
 /// Result type used by Substrate client.
@@ -54,12 +55,26 @@ pub enum Error {
 	/// The bridge pallet is halted and all transactions will be rejected.
 	#[error("Bridge pallet is halted.")]
 	BridgePalletIsHalted,
+	/// The messages pallet is rejecting inbound messages and all delivery transactions will be
+	/// rejected.
+	#[error("Messages pallet is rejecting inbound messages.")]
+	BridgePalletIsRejectingInboundMessages,
+	/// The messages lane is halted and all lane transactions will be rejected.
+	#[error("Messages lane {0:?} is halted.")]
+	BridgeLaneIsHalted(LaneId),
+	/// The messages lane is rejecting inbound messages and all delivery transactions at this
+	/// lane will be rejected.
+	#[error("Messages lane {0:?} is rejecting inbound messages.")]
+	BridgeLaneIsRejectingInboundMessages(LaneId),
 	/// The bridge pallet is not yet initialized and all transactions will be rejected.
 	#[error("Bridge pallet is not initialized.")]
 	BridgePalletIsNotInitialized,
 	/// An error has happened when we have tried to parse storage proof.
 	#[error("Error when parsing storage proof: {0:?}.")]
 	StorageProofError(bp_runtime::StorageProofError),
+	/// Transaction mortality period is invalid.
+	#[error("Invalid transaction mortality period: {0:?}")]
+	InvalidMortalityPeriod(#[from] bp_runtime::InvalidMortalityPeriod),
 	/// The Substrate transaction is invalid.
 	#[error("Substrate transaction is invalid: {0:?}")]
 	TransactionInvalid(#[from] TransactionValidityError),
@@ -68,6 +83,34 @@ pub enum Error {
 	Custom(String),
 }
 
+impl Error {
+	/// Returns true if the error means that the node has already pruned state of the requested
+	/// block.
+	pub fn is_pruned_state_error(&self) -> bool {
+		match *self {
+			Error::RpcError(ref e) => e.to_string().contains("State already discarded"),
+			_ => false,
+		}
+	}
+
+	/// Returns true if the error means that the transaction has been rejected by the node,
+	/// because its nonce is either outdated (`InvalidTransaction::Stale`), or too large
+	/// (`InvalidTransaction::Future`).
+	pub fn is_invalid_nonce_error(&self) -> bool {
+		match *self {
+			Error::RpcError(ref e) => {
+				let e = format!("{:?}", e);
+				e.contains("Transaction is outdated") ||
+					e.contains("Transaction will be valid in the future")
+			},
+			Error::TransactionInvalid(TransactionValidityError::Invalid(
+				InvalidTransaction::Stale | InvalidTransaction::Future,
+			)) => true,
+			_ => false,
+		}
+	}
+}
+
 impl From<tokio::task::JoinError> for Error {
 	fn from(error: tokio::task::JoinError) -> Self {
 		Error::Custom(format!("Failed to wait tokio task: {}", error))
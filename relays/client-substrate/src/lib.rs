@@ -18,9 +18,12 @@
 
 #![warn(missing_docs)]
 
+mod call_index;
 mod chain;
 mod client;
+mod dispatch_error;
 mod error;
+mod nonce_tracker;
 mod rpc;
 mod sync_header;
 mod transaction_tracker;
@@ -32,19 +35,21 @@ pub mod test_chain;
 use std::time::Duration;
 
 pub use crate::{
+	call_index::{check_call_indices, CallIndex},
 	chain::{
 		AccountKeyPairOf, BlockWithJustification, CallOf, Chain, ChainWithBalances,
 		ChainWithGrandpa, ChainWithMessages, RelayChain, SignParam, TransactionSignScheme,
 		TransactionStatusOf, UnsignedTransaction, WeightToFeeOf,
 	},
 	client::{ChainRuntimeVersion, Client, OpaqueGrandpaAuthoritiesSet, Subscription},
+	dispatch_error::DispatchErrorDecoder,
 	error::{Error, Result},
 	sync_header::SyncHeader,
 	transaction_tracker::TransactionTracker,
 };
 pub use bp_runtime::{
-	AccountIdOf, AccountPublicOf, BalanceOf, BlockNumberOf, Chain as ChainBase, HashOf, HeaderOf,
-	IndexOf, SignatureOf, TransactionEra, TransactionEraOf,
+	AccountIdOf, AccountPublicOf, BalanceOf, BlockNumberOf, Chain as ChainBase,
+	EncodedOrDecodedCall, HashOf, HeaderOf, IndexOf, SignatureOf, TransactionEra, TransactionEraOf,
 };
 
 /// Header id used by the chain.
@@ -77,7 +82,8 @@ impl Default for ConnectionParams {
 /// Returns stall timeout for relay loop.
 ///
 /// Relay considers himself stalled if he has submitted transaction to the node, but it has not
-/// been mined for this period.
+/// been mined for this period. The timeout is computed from the effective mortality period of
+/// the transaction, because the requested period is rounded up when the transaction is signed.
 pub fn transaction_stall_timeout(
 	mortality_period: Option<u32>,
 	average_block_interval: Duration,
@@ -85,6 +91,38 @@ pub fn transaction_stall_timeout(
 ) -> Duration {
 	// 1 extra block for transaction to reach the pool && 1 for relayer to awake after it is mined
 	mortality_period
-		.map(|mortality_period| average_block_interval.saturating_mul(mortality_period + 1 + 1))
+		.map(|mortality_period| {
+			average_block_interval.saturating_mul(
+				bp_runtime::effective_mortality_period(mortality_period).saturating_add(1 + 1),
+			)
+		})
 		.unwrap_or(default_stall_timeout)
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn transaction_stall_timeout_uses_effective_mortality_period() {
+		let block_interval = Duration::from_millis(500);
+		let default_stall_timeout = Duration::from_secs(60);
+
+		assert_eq!(
+			transaction_stall_timeout(None, block_interval, default_stall_timeout),
+			default_stall_timeout,
+		);
+		assert_eq!(
+			transaction_stall_timeout(Some(1), block_interval, default_stall_timeout),
+			Duration::from_millis(3000),
+		);
+		assert_eq!(
+			transaction_stall_timeout(Some(5), block_interval, default_stall_timeout),
+			Duration::from_millis(5000),
+		);
+		assert_eq!(
+			transaction_stall_timeout(Some(8), block_interval, default_stall_timeout),
+			Duration::from_millis(5000),
+		);
+	}
+}
@@ -0,0 +1,227 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Metrics of the block space, consumed by transactions that the relay submits.
+
+use async_std::sync::{Arc, Mutex};
+use frame_support::weights::Weight;
+use relay_utils::metrics::{
+	metric_name, register, Counter, Gauge, Metric, Opts, PrometheusError, Registry, F64, U64,
+};
+use std::collections::VecDeque;
+
+/// Number of recent blocks that are used to compute the share of the block capacity, consumed
+/// by the relay transactions.
+pub const BLOCK_CAPACITY_WINDOW_IN_BLOCKS: u64 = 100;
+
+/// Metrics of the block space, consumed by the relay transactions at the single chain.
+///
+/// Only transactions that have been included into finalized blocks are accounted. Transactions
+/// that we have lost track of are counted separately.
+#[derive(Debug, Clone)]
+pub struct BlockSpaceMetrics {
+	/// Total size of all included relay transactions.
+	submitted_bytes: Counter<U64>,
+	/// Total actual (post-dispatch) weight of all included relay transactions.
+	consumed_weight: Counter<U64>,
+	/// Number of relay transactions that we have lost track of.
+	lost_transactions: Counter<U64>,
+	/// Share of the block capacity, consumed by relay transactions within recent blocks.
+	block_capacity_share: Gauge<F64>,
+	/// Weight that may be consumed by transactions of the single block.
+	max_block_weight: Weight,
+	/// Weights of relay transactions, included into recent blocks.
+	recent_weights: Arc<Mutex<RecentWeights>>,
+}
+
+/// Weights of relay transactions, included into recent blocks.
+#[derive(Debug, Default)]
+struct RecentWeights {
+	/// Best known number of block with included relay transaction.
+	best_block_number: u64,
+	/// Numbers of blocks and weights of relay transactions, included into these blocks.
+	weights: VecDeque<(u64, Weight)>,
+}
+
+impl BlockSpaceMetrics {
+	/// Create new metrics for the chain with given name.
+	///
+	/// The `max_block_weight` is used to compute share of the block capacity, consumed by relay
+	/// transactions.
+	pub fn new(chain_name: &str, max_block_weight: Weight) -> Result<Self, PrometheusError> {
+		Ok(BlockSpaceMetrics {
+			submitted_bytes: Counter::with_opts(
+				Opts::new(
+					metric_name(None, "bridge_submitted_bytes_total"),
+					"Total size of relay transactions, included into finalized blocks",
+				)
+				.const_label("chain", chain_name),
+			)?,
+			consumed_weight: Counter::with_opts(
+				Opts::new(
+					metric_name(None, "bridge_consumed_weight_total"),
+					"Total actual weight of relay transactions, included into finalized blocks",
+				)
+				.const_label("chain", chain_name),
+			)?,
+			lost_transactions: Counter::with_opts(
+				Opts::new(
+					metric_name(None, "bridge_lost_transactions_total"),
+					"Number of relay transactions that the relay has lost track of",
+				)
+				.const_label("chain", chain_name),
+			)?,
+			block_capacity_share: Gauge::with_opts(
+				Opts::new(
+					metric_name(None, "bridge_block_capacity_share"),
+					format!(
+						"Share of the block capacity, consumed by relay transactions within {} \
+						recent blocks",
+						BLOCK_CAPACITY_WINDOW_IN_BLOCKS,
+					),
+				)
+				.const_label("chain", chain_name),
+			)?,
+			max_block_weight,
+			recent_weights: Arc::new(Mutex::new(RecentWeights::default())),
+		})
+	}
+
+	/// Note that the relay transaction of given size has been included into finalized block.
+	///
+	/// The `weight` is the actual weight of the transaction or `None` if it is unknown.
+	pub async fn note_included(&self, block_number: u64, size: u32, weight: Option<Weight>) {
+		self.submitted_bytes.inc_by(size as u64);
+
+		let weight = match weight {
+			Some(weight) => weight,
+			None => return,
+		};
+		self.consumed_weight.inc_by(weight);
+
+		let mut recent_weights = self.recent_weights.lock().await;
+		recent_weights.best_block_number = recent_weights.best_block_number.max(block_number);
+		recent_weights.weights.push_back((block_number, weight));
+		let best_block_number = recent_weights.best_block_number;
+		recent_weights.weights.retain(|(block_number, _)| {
+			block_number.saturating_add(BLOCK_CAPACITY_WINDOW_IN_BLOCKS) > best_block_number
+		});
+
+		let window_weight = recent_weights
+			.weights
+			.iter()
+			.fold(0 as Weight, |total, (_, weight)| total.saturating_add(*weight));
+		let window_capacity = self.max_block_weight as f64 * BLOCK_CAPACITY_WINDOW_IN_BLOCKS as f64;
+		self.block_capacity_share.set(if window_capacity > 0.0 {
+			window_weight as f64 / window_capacity
+		} else {
+			0.0
+		});
+	}
+
+	/// Note that we have lost track of the relay transaction.
+	pub fn note_lost(&self) {
+		self.lost_transactions.inc();
+	}
+
+	/// Returns total size of all included relay transactions.
+	#[cfg(test)]
+	pub(crate) fn submitted_bytes(&self) -> u64 {
+		self.submitted_bytes.get()
+	}
+
+	/// Returns total actual weight of all included relay transactions.
+	#[cfg(test)]
+	pub(crate) fn consumed_weight(&self) -> u64 {
+		self.consumed_weight.get()
+	}
+
+	/// Returns number of lost relay transactions.
+	#[cfg(test)]
+	pub(crate) fn lost_transactions(&self) -> u64 {
+		self.lost_transactions.get()
+	}
+
+	/// Returns share of the block capacity, consumed by relay transactions within recent blocks.
+	#[cfg(test)]
+	pub(crate) fn block_capacity_share(&self) -> f64 {
+		self.block_capacity_share.get()
+	}
+}
+
+impl Metric for BlockSpaceMetrics {
+	fn register(&self, registry: &Registry) -> Result<(), PrometheusError> {
+		register(self.submitted_bytes.clone(), registry)?;
+		register(self.consumed_weight.clone(), registry)?;
+		register(self.lost_transactions.clone(), registry)?;
+		register(self.block_capacity_share.clone(), registry)?;
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const MAX_BLOCK_WEIGHT: Weight = 1_000;
+
+	fn metrics() -> BlockSpaceMetrics {
+		BlockSpaceMetrics::new("Test", MAX_BLOCK_WEIGHT).unwrap()
+	}
+
+	#[async_std::test]
+	async fn included_transactions_are_accumulated() {
+		let metrics = metrics();
+		metrics.note_included(1, 100, Some(200)).await;
+		metrics.note_included(2, 50, Some(300)).await;
+		metrics.note_included(2, 10, None).await;
+		metrics.note_lost();
+
+		assert_eq!(metrics.submitted_bytes(), 160);
+		assert_eq!(metrics.consumed_weight(), 500);
+		assert_eq!(metrics.lost_transactions(), 1);
+		assert_eq!(
+			metrics.block_capacity_share(),
+			500.0 / (MAX_BLOCK_WEIGHT * BLOCK_CAPACITY_WINDOW_IN_BLOCKS) as f64,
+		);
+	}
+
+	#[async_std::test]
+	async fn block_capacity_share_only_accounts_recent_blocks() {
+		let metrics = metrics();
+		metrics.note_included(1, 100, Some(200)).await;
+		metrics.note_included(BLOCK_CAPACITY_WINDOW_IN_BLOCKS, 100, Some(300)).await;
+		assert_eq!(
+			metrics.block_capacity_share(),
+			500.0 / (MAX_BLOCK_WEIGHT * BLOCK_CAPACITY_WINDOW_IN_BLOCKS) as f64,
+		);
+
+		// transaction at block#1 is out of the window now
+		metrics.note_included(BLOCK_CAPACITY_WINDOW_IN_BLOCKS + 1, 100, Some(400)).await;
+		assert_eq!(
+			metrics.block_capacity_share(),
+			700.0 / (MAX_BLOCK_WEIGHT * BLOCK_CAPACITY_WINDOW_IN_BLOCKS) as f64,
+		);
+
+		// late transaction from the block that is out of window is not accounted
+		metrics.note_included(1, 100, Some(1_000)).await;
+		assert_eq!(
+			metrics.block_capacity_share(),
+			700.0 / (MAX_BLOCK_WEIGHT * BLOCK_CAPACITY_WINDOW_IN_BLOCKS) as f64,
+		);
+		assert_eq!(metrics.consumed_weight(), 1_900);
+	}
+}
@@ -16,8 +16,14 @@
 
 //! Contains several Substrate-specific metrics that may be exposed by relay.
 
+pub use block_space::{BlockSpaceMetrics, BLOCK_CAPACITY_WINDOW_IN_BLOCKS};
 pub use float_storage_value::{FixedU128OrOne, FloatStorageValue, FloatStorageValueMetric};
+pub use proof_generation::{
+	proof_size_warning_threshold, ProofGenerationMetrics, ProofMetrics, ProofType,
+};
 pub use storage_proof_overhead::StorageProofOverheadMetric;
 
+mod block_space;
 mod float_storage_value;
+mod proof_generation;
 mod storage_proof_overhead;
@@ -0,0 +1,158 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Metrics of storage and finality proofs that are generated by relay source clients.
+
+use crate::chain::Chain;
+
+use bp_runtime::Chain as ChainBase;
+use relay_utils::metrics::{
+	exponential_buckets, metric_name, register, Histogram, HistogramOpts, HistogramVec, Metric,
+	PrometheusError, Registry,
+};
+use std::time::Duration;
+
+/// Type of the proof that is generated by the relay.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProofType {
+	/// Proof of messages from the outbound lane.
+	Messages,
+	/// Proof of the lane state (messages delivery proof).
+	LaneState,
+	/// Proof of parachain heads.
+	ParachainHeads,
+	/// Finality proof.
+	Finality,
+}
+
+impl ProofType {
+	/// Returns proof type label value.
+	pub fn as_str(&self) -> &'static str {
+		match *self {
+			ProofType::Messages => "messages",
+			ProofType::LaneState => "lane-state",
+			ProofType::ParachainHeads => "parachain-heads",
+			ProofType::Finality => "finality",
+		}
+	}
+}
+
+/// Returns default size of the proof that is likely to be rejected by the target chain `C`.
+///
+/// It is used when the threshold is not configured explicitly. The proof is not the only thing in
+/// the transaction, so we are using 2/3 of the maximal extrinsic size here.
+pub fn proof_size_warning_threshold<C: Chain>() -> usize {
+	(C::max_extrinsic_size() as usize).saturating_mul(2) / 3
+}
+
+/// Histograms of proofs generation time and proofs sizes.
+#[derive(Debug, Clone)]
+pub struct ProofGenerationMetrics {
+	generation_time: HistogramVec,
+	size: HistogramVec,
+}
+
+impl ProofGenerationMetrics {
+	/// Create new metrics.
+	pub fn new() -> Result<Self, PrometheusError> {
+		Ok(ProofGenerationMetrics {
+			generation_time: HistogramVec::new(
+				HistogramOpts::new(
+					metric_name(None, "bridge_proof_generation_seconds"),
+					"Time spent generating proofs at the source chain",
+				)
+				.buckets(exponential_buckets(0.01, 2.0, 12)?),
+				&["proof_type", "chain"],
+			)?,
+			size: HistogramVec::new(
+				HistogramOpts::new(
+					metric_name(None, "bridge_proof_size_bytes"),
+					"Size of proofs generated at the source chain",
+				)
+				.buckets(exponential_buckets(256.0, 2.0, 16)?),
+				&["proof_type", "chain"],
+			)?,
+		})
+	}
+
+	/// Returns metrics of the given proof type, generated at the chain `C`.
+	pub fn proof_metrics<C: Chain>(&self, proof_type: ProofType) -> ProofMetrics {
+		let labels = [proof_type.as_str(), C::NAME];
+		ProofMetrics {
+			proof_type,
+			chain: C::NAME,
+			generation_time: self.generation_time.with_label_values(&labels),
+			size: self.size.with_label_values(&labels),
+			size_warning_threshold: None,
+		}
+	}
+}
+
+impl Metric for ProofGenerationMetrics {
+	fn register(&self, registry: &Registry) -> Result<(), PrometheusError> {
+		// the same metrics may be shared by several relay loops
+		match register(self.generation_time.clone(), registry) {
+			Ok(_) | Err(PrometheusError::AlreadyReg) => (),
+			Err(e) => return Err(e),
+		}
+		match register(self.size.clone(), registry) {
+			Ok(_) | Err(PrometheusError::AlreadyReg) => Ok(()),
+			Err(e) => Err(e),
+		}
+	}
+}
+
+/// Metrics of single proof type, generated at the single chain.
+///
+/// Histograms are resolved when the instance is created, so observing values doesn't require
+/// any allocations.
+#[derive(Debug, Clone)]
+pub struct ProofMetrics {
+	proof_type: ProofType,
+	chain: &'static str,
+	generation_time: Histogram,
+	size: Histogram,
+	size_warning_threshold: Option<usize>,
+}
+
+impl ProofMetrics {
+	/// Log warning if generated proof is larger than given size (in bytes).
+	#[must_use]
+	pub fn with_size_warning_threshold(mut self, size_warning_threshold: usize) -> Self {
+		self.size_warning_threshold = Some(size_warning_threshold);
+		self
+	}
+
+	/// Observe proof generation time and proof size.
+	pub fn observe(&self, generation_time: Duration, proof_size: usize) {
+		self.generation_time.observe(generation_time.as_secs_f64());
+		self.size.observe(proof_size as f64);
+
+		if let Some(size_warning_threshold) = self.size_warning_threshold {
+			if proof_size > size_warning_threshold {
+				log::warn!(
+					target: "bridge-metrics",
+					"Generated {} {} proof has size {} bytes, which is larger than {} bytes. \
+					It may be rejected by the target chain",
+					self.chain,
+					self.proof_type.as_str(),
+					proof_size,
+					size_warning_threshold,
+				);
+			}
+		}
+	}
+}
@@ -0,0 +1,211 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Tracking of nonces, used by transactions that the relay submits.
+
+use relay_utils::metrics::{metric_name, register, Counter, PrometheusError, Registry, U64};
+use sp_runtime::traits::{AtLeast32Bit, One, Zero};
+use std::collections::BTreeMap;
+
+/// Number of consecutive `Future`/`Stale` transaction rejections after which we resynchronize
+/// nonce with the chain.
+const REJECTIONS_BEFORE_RESYNC: u32 = 2;
+
+/// Nonce tracker of relay accounts.
+///
+/// The node may return outdated `system_accountNextIndex` (e.g. when the previous transaction
+/// has not yet been imported into its pool), so the tracker remembers the nonce that the account
+/// has used last time. But when some transaction is sent from the same account by someone else
+/// (e.g. operator is sending manual transactions), or when our own transaction is dropped,
+/// the remembered nonce becomes invalid and all our transactions are rejected. In this case the
+/// tracker resynchronizes account nonce with the chain.
+pub struct NonceTracker<AccountId, Index> {
+	/// Nonces of accounts that have submitted transactions.
+	accounts: BTreeMap<AccountId, AccountNonce<Index>>,
+	/// Number of nonce resyncs.
+	resyncs: Counter<U64>,
+}
+
+/// Nonce of the single account.
+struct AccountNonce<Index> {
+	/// Nonce that we're going to use in the next transaction.
+	next_nonce: Index,
+	/// Number of consecutive nonce-related rejections.
+	rejections: u32,
+}
+
+impl<AccountId: Ord, Index: AtLeast32Bit + Copy> NonceTracker<AccountId, Index> {
+	/// Create new nonce tracker.
+	pub fn new(chain_name: &str) -> Result<Self, PrometheusError> {
+		Ok(NonceTracker {
+			accounts: BTreeMap::new(),
+			resyncs: Counter::new(
+				metric_name(None, &format!("{}_relay_account_nonce_resyncs", chain_name)),
+				format!(
+					"Number of times the relay account nonce has been resynchronized with {}",
+					chain_name,
+				),
+			)?,
+		})
+	}
+
+	/// Register nonce tracker metrics.
+	pub fn register_metrics(&self, registry: &Registry) -> Result<(), PrometheusError> {
+		register(self.resyncs.clone(), registry).map(drop)
+	}
+
+	/// Select nonce for the next account transaction, given nonce that is returned by the node.
+	pub fn select_nonce(&self, account: &AccountId, account_next_index: Index) -> Index {
+		match self.accounts.get(account) {
+			Some(nonce) if nonce.next_nonce > account_next_index => nonce.next_nonce,
+			_ => account_next_index,
+		}
+	}
+
+	/// Note that the account transaction with given nonce has been accepted by the node.
+	pub fn note_accepted(&mut self, account: AccountId, nonce: Index) {
+		self.accounts
+			.insert(account, AccountNonce { next_nonce: nonce + Index::one(), rejections: 0 });
+	}
+
+	/// Note that the account transaction has been rejected because of invalid nonce.
+	///
+	/// Returns true if account nonce needs to be resynchronized.
+	pub fn note_rejected(&mut self, account: AccountId) -> bool {
+		let nonce = self
+			.accounts
+			.entry(account)
+			.or_insert(AccountNonce { next_nonce: Index::zero(), rejections: 0 });
+		nonce.rejections += 1;
+		nonce.rejections >= REJECTIONS_BEFORE_RESYNC
+	}
+
+	/// Resynchronize account nonce with the chain.
+	///
+	/// `own_pending_nonces` are nonces of account transactions that are still in the node pool.
+	/// We don't want to reuse them, because that would mean double-submitting or replacing our
+	/// own transactions.
+	pub fn resync(
+		&mut self,
+		account: AccountId,
+		account_next_index: Index,
+		own_pending_nonces: impl IntoIterator<Item = Index>,
+	) -> Index {
+		let own_pending_nonces = own_pending_nonces.into_iter().collect::<Vec<_>>();
+		let mut next_nonce = account_next_index;
+		while own_pending_nonces.contains(&next_nonce) {
+			next_nonce += Index::one();
+		}
+
+		self.accounts.insert(account, AccountNonce { next_nonce, rejections: 0 });
+		self.resyncs.inc();
+		next_nonce
+	}
+
+	/// Returns number of nonce resyncs.
+	#[cfg(test)]
+	fn resyncs(&self) -> u64 {
+		self.resyncs.get()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const ALICE: u64 = 1;
+	const BOB: u64 = 2;
+
+	fn tracker() -> NonceTracker<u64, u32> {
+		NonceTracker::new("Test").unwrap()
+	}
+
+	#[test]
+	fn nonce_is_not_reused_when_node_returns_outdated_nonce() {
+		let mut tracker = tracker();
+		assert_eq!(tracker.select_nonce(&ALICE, 10), 10);
+		tracker.note_accepted(ALICE, 10);
+
+		// our transaction is not yet in the pool, so node returns the same nonce
+		assert_eq!(tracker.select_nonce(&ALICE, 10), 11);
+	}
+
+	#[test]
+	fn nonce_is_resynced_after_out_of_band_nonce_bump() {
+		let mut tracker = tracker();
+
+		// relay submits transaction with nonce 10
+		assert_eq!(tracker.select_nonce(&ALICE, 10), 10);
+		tracker.note_accepted(ALICE, 10);
+
+		// operator sends manual transaction with nonce 11 from the relay account, using another
+		// node. The node that we're connected to is lagging, so it still returns nonce 11 and
+		// our transactions are rejected as `Stale`
+		assert_eq!(tracker.select_nonce(&ALICE, 11), 11);
+		assert!(!tracker.note_rejected(ALICE));
+		assert_eq!(tracker.select_nonce(&ALICE, 11), 11);
+		assert!(tracker.note_rejected(ALICE));
+
+		// we resync and resume from the actual nonce without restart
+		assert_eq!(tracker.resync(ALICE, 12, vec![]), 12);
+		assert_eq!(tracker.select_nonce(&ALICE, 12), 12);
+		tracker.note_accepted(ALICE, 12);
+		assert_eq!(tracker.select_nonce(&ALICE, 12), 13);
+		assert_eq!(tracker.resyncs(), 1);
+	}
+
+	#[test]
+	fn nonce_is_resynced_when_our_transaction_has_been_dropped() {
+		let mut tracker = tracker();
+		tracker.note_accepted(ALICE, 10);
+		tracker.note_accepted(ALICE, 11);
+
+		// transactions 10 and 11 have been dropped, so transaction with nonce 12 is `Future`
+		assert_eq!(tracker.select_nonce(&ALICE, 10), 12);
+		assert!(!tracker.note_rejected(ALICE));
+		assert!(tracker.note_rejected(ALICE));
+
+		assert_eq!(tracker.resync(ALICE, 10, vec![]), 10);
+		assert_eq!(tracker.select_nonce(&ALICE, 10), 10);
+	}
+
+	#[test]
+	fn resync_skips_own_pending_transactions() {
+		let mut tracker = tracker();
+		assert_eq!(tracker.resync(ALICE, 10, vec![10, 11, 13]), 12);
+		assert_eq!(tracker.select_nonce(&ALICE, 10), 12);
+	}
+
+	#[test]
+	fn accepted_transaction_resets_rejections_counter() {
+		let mut tracker = tracker();
+		assert!(!tracker.note_rejected(ALICE));
+		tracker.note_accepted(ALICE, 10);
+		assert!(!tracker.note_rejected(ALICE));
+		assert_eq!(tracker.resyncs(), 0);
+	}
+
+	#[test]
+	fn nonces_of_different_accounts_are_tracked_separately() {
+		let mut tracker = tracker();
+		tracker.note_accepted(ALICE, 10);
+		assert_eq!(tracker.select_nonce(&ALICE, 10), 11);
+		assert_eq!(tracker.select_nonce(&BOB, 10), 10);
+
+		assert!(!tracker.note_rejected(ALICE));
+		assert!(!tracker.note_rejected(BOB));
+	}
+}
@@ -76,6 +76,9 @@ pub(crate) trait SubstrateState<C> {
 	/// Get current runtime version.
 	#[method(name = "getRuntimeVersion")]
 	async fn runtime_version(&self) -> RpcResult<RuntimeVersion>;
+	/// Get encoded runtime metadata.
+	#[method(name = "getMetadata")]
+	async fn metadata(&self, at_block: Option<C::Hash>) -> RpcResult<Bytes>;
 	/// Call given runtime method.
 	#[method(name = "call")]
 	async fn call(
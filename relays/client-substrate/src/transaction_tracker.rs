@@ -16,11 +16,18 @@
 
 //! Helper for tracking transaction invalidation events.
 
-use crate::{Chain, Client, Error, HashOf, HeaderIdOf, Subscription, TransactionStatusOf};
+use crate::{
+	metrics::BlockSpaceMetrics, Chain, Client, Error, HashOf, HeaderIdOf, Subscription,
+	TransactionStatusOf,
+};
 
 use async_trait::async_trait;
+use frame_support::weights::Weight;
 use futures::{future::Either, Future, FutureExt, Stream, StreamExt};
-use relay_utils::{HeaderId, TrackedTransactionStatus};
+use relay_utils::{
+	clock::{MonotonicClock, StallTimer, DEFAULT_TIME_WARP_THRESHOLD, STALL_TIMER_CHECK_INTERVAL},
+	HeaderId, TrackedTransactionStatus,
+};
 use sp_runtime::traits::Header as _;
 use std::time::Duration;
 
@@ -29,6 +36,20 @@ use std::time::Duration;
 pub trait Environment<C: Chain>: Send + Sync {
 	/// Returns header id by its hash.
 	async fn header_id_by_hash(&self, hash: HashOf<C>) -> Result<HeaderIdOf<C>, Error>;
+	/// Returns human-readable dispatch error of the transaction, included into given block, or
+	/// `None` if the transaction has been dispatched successfully.
+	async fn transaction_dispatch_error(
+		&self,
+		block_hash: HashOf<C>,
+		transaction_hash: HashOf<C>,
+	) -> Result<Option<String>, Error>;
+	/// Returns actual (post-dispatch) weight of the transaction, included into given block, or
+	/// `None` if the weight is unknown.
+	async fn transaction_actual_weight(
+		&self,
+		block_hash: HashOf<C>,
+		transaction_hash: HashOf<C>,
+	) -> Result<Option<Weight>, Error>;
 }
 
 #[async_trait]
@@ -36,6 +57,22 @@ impl<C: Chain> Environment<C> for Client<C> {
 	async fn header_id_by_hash(&self, hash: HashOf<C>) -> Result<HeaderIdOf<C>, Error> {
 		self.header_by_hash(hash).await.map(|h| HeaderId(*h.number(), hash))
 	}
+
+	async fn transaction_dispatch_error(
+		&self,
+		block_hash: HashOf<C>,
+		transaction_hash: HashOf<C>,
+	) -> Result<Option<String>, Error> {
+		self.extrinsic_dispatch_error(block_hash, transaction_hash).await
+	}
+
+	async fn transaction_actual_weight(
+		&self,
+		block_hash: HashOf<C>,
+		transaction_hash: HashOf<C>,
+	) -> Result<Option<Weight>, Error> {
+		self.extrinsic_actual_weight(block_hash, transaction_hash).await
+	}
 }
 
 /// Substrate transaction tracker implementation.
@@ -54,15 +91,20 @@ impl<C: Chain> Environment<C> for Client<C> {
 /// 2) assume that the transaction is lost and resubmit another transaction instantly;
 ///
 /// 3) wait for some time (if transaction is mortal - then until block where it dies; if it is
-///    immortal - then for some time that we assume is long enough to mine it) and assume that
-///    it is lost.
+///    immortal - then for some time that we assume is long enough to mine it) and assume that it is
+///    lost.
 ///
 /// This struct implements third option as it seems to be the most optimal.
+///
+/// When the transaction is finalized, its size and actual weight are reported to the
+/// block space metrics. Lost transactions are counted separately.
 pub struct TransactionTracker<C: Chain, E> {
 	environment: E,
 	transaction_hash: HashOf<C>,
+	transaction_size: u32,
 	stall_timeout: Duration,
 	subscription: Subscription<TransactionStatusOf<C>>,
+	block_space_metrics: BlockSpaceMetrics,
 }
 
 impl<C: Chain, E: Environment<C>> TransactionTracker<C, E> {
@@ -71,9 +113,23 @@ impl<C: Chain, E: Environment<C>> TransactionTracker<C, E> {
 		environment: E,
 		stall_timeout: Duration,
 		transaction_hash: HashOf<C>,
+		transaction_size: u32,
 		subscription: Subscription<TransactionStatusOf<C>>,
+		block_space_metrics: BlockSpaceMetrics,
 	) -> Self {
-		Self { environment, stall_timeout, transaction_hash, subscription }
+		Self {
+			environment,
+			stall_timeout,
+			transaction_hash,
+			transaction_size,
+			subscription,
+			block_space_metrics,
+		}
+	}
+
+	/// Returns hash of the tracked transaction.
+	pub fn transaction_hash(&self) -> HashOf<C> {
+		self.transaction_hash
 	}
 
 	/// Wait for final transaction status and return it along with last known internal invalidation
@@ -83,16 +139,20 @@ impl<C: Chain, E: Environment<C>> TransactionTracker<C, E> {
 		wait_for_stall_timeout: impl Future<Output = ()>,
 		wait_for_stall_timeout_rest: impl Future<Output = ()>,
 	) -> (TrackedTransactionStatus<HeaderIdOf<C>>, Option<InvalidationStatus<HeaderIdOf<C>>>) {
+		let block_space_metrics = self.block_space_metrics;
 		// sometimes we want to wait for the rest of the stall timeout even if
 		// `wait_for_invalidation` has been "select"ed first => it is shared
 		let wait_for_invalidation = watch_transaction_status::<_, C, _>(
 			self.environment,
 			self.transaction_hash,
+			self.transaction_size,
+			&block_space_metrics,
 			self.subscription.into_stream(),
 		);
 		futures::pin_mut!(wait_for_stall_timeout, wait_for_invalidation);
 
-		match futures::future::select(wait_for_stall_timeout, wait_for_invalidation).await {
+		let status = futures::future::select(wait_for_stall_timeout, wait_for_invalidation).await;
+		let result = match status {
 			Either::Left((_, _)) => {
 				log::trace!(
 					target: "bridge",
@@ -124,7 +184,13 @@ impl<C: Chain, E: Environment<C>> TransactionTracker<C, E> {
 					(TrackedTransactionStatus::Lost, Some(invalidation_status))
 				},
 			},
+		};
+
+		if result.0 == TrackedTransactionStatus::Lost {
+			block_space_metrics.note_lost();
 		}
+
+		result
 	}
 }
 
@@ -133,7 +199,10 @@ impl<C: Chain, E: Environment<C>> relay_utils::TransactionTracker for Transactio
 	type HeaderId = HeaderIdOf<C>;
 
 	async fn wait(self) -> TrackedTransactionStatus<HeaderIdOf<C>> {
-		let wait_for_stall_timeout = async_std::task::sleep(self.stall_timeout).shared();
+		let wait_for_stall_timeout =
+			StallTimer::new(MonotonicClock, self.stall_timeout, DEFAULT_TIME_WARP_THRESHOLD)
+				.wait(STALL_TIMER_CHECK_INTERVAL)
+				.shared();
 		let wait_for_stall_timeout_rest = wait_for_stall_timeout.clone();
 		self.do_wait(wait_for_stall_timeout, wait_for_stall_timeout_rest).await.0
 	}
@@ -162,6 +231,8 @@ async fn watch_transaction_status<
 >(
 	environment: E,
 	transaction_hash: HashOf<C>,
+	transaction_size: u32,
+	block_space_metrics: &BlockSpaceMetrics,
 	subscription: S,
 ) -> InvalidationStatus<HeaderIdOf<C>> {
 	futures::pin_mut!(subscription);
@@ -194,6 +265,51 @@ async fn watch_transaction_status<
 						return InvalidationStatus::Lost
 					},
 				};
+
+				// the transaction may be finalized, but its dispatch may have failed. The relay
+				// loops are detecting that on their own, but let's log the actual reason here
+				match environment.transaction_dispatch_error(block_hash, transaction_hash).await {
+					Ok(Some(dispatch_error)) => log::error!(
+						target: "bridge",
+						"{} transaction {:?} has been finalized at block {}, but its dispatch \
+						has failed: {}",
+						C::NAME,
+						transaction_hash,
+						header_id,
+						dispatch_error,
+					),
+					Ok(None) => (),
+					Err(e) => log::debug!(
+						target: "bridge",
+						"Failed to read dispatch result of {} transaction {:?} at block {:?}: {:?}",
+						C::NAME,
+						transaction_hash,
+						block_hash,
+						e,
+					),
+				}
+
+				// account block space, consumed by the transaction
+				let actual_weight =
+					environment.transaction_actual_weight(block_hash, transaction_hash).await;
+				if let Err(ref e) = actual_weight {
+					log::debug!(
+						target: "bridge",
+						"Failed to read actual weight of {} transaction {:?} at block {:?}: {:?}",
+						C::NAME,
+						transaction_hash,
+						block_hash,
+						e,
+					);
+				}
+				block_space_metrics
+					.note_included(
+						header_id.0.into(),
+						transaction_size,
+						actual_weight.ok().flatten(),
+					)
+					.await;
+
 				return InvalidationStatus::Finalized(header_id)
 			},
 			Some(TransactionStatusOf::<C>::Invalid) => {
@@ -217,9 +333,6 @@ async fn watch_transaction_status<
 				// nothing important (for us) has happened
 			},
 			Some(TransactionStatusOf::<C>::InBlock(block_hash)) => {
-				// TODO: read matching system event (ExtrinsicSuccess or ExtrinsicFailed), log it
-				// here and use it later (on finality) for reporting invalid transaction
-				// https://github.com/paritytech/parity-bridges-common/issues/1464
 				log::trace!(
 					target: "bridge",
 					"{} transaction {:?} has been included in block: {:?}",
@@ -284,10 +397,15 @@ async fn watch_transaction_status<
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use crate::test_chain::TestChain;
+	use crate::{test_chain::TestChain, BlockNumberOf};
 	use futures::{FutureExt, SinkExt};
+	use relay_utils::clock::TestClock;
 	use sc_transaction_pool_api::TransactionStatus;
 
+	const TEST_STALL_TIMEOUT: Duration = Duration::from_secs(30);
+	const TEST_TRANSACTION_WEIGHT: Weight = 100;
+	const TEST_MAX_BLOCK_WEIGHT: Weight = 1_000;
+
 	struct TestEnvironment(Result<HeaderIdOf<TestChain>, Error>);
 
 	#[async_trait]
@@ -298,6 +416,26 @@ mod tests {
 		) -> Result<HeaderIdOf<TestChain>, Error> {
 			self.0.as_ref().map_err(|_| Error::UninitializedBridgePallet).cloned()
 		}
+
+		async fn transaction_dispatch_error(
+			&self,
+			_block_hash: HashOf<TestChain>,
+			_transaction_hash: HashOf<TestChain>,
+		) -> Result<Option<String>, Error> {
+			Ok(None)
+		}
+
+		async fn transaction_actual_weight(
+			&self,
+			_block_hash: HashOf<TestChain>,
+			_transaction_hash: HashOf<TestChain>,
+		) -> Result<Option<Weight>, Error> {
+			Ok(Some(TEST_TRANSACTION_WEIGHT))
+		}
+	}
+
+	fn block_space_metrics() -> BlockSpaceMetrics {
+		BlockSpaceMetrics::new(TestChain::NAME, TEST_MAX_BLOCK_WEIGHT).unwrap()
 	}
 
 	async fn on_transaction_status(
@@ -305,13 +443,27 @@ mod tests {
 	) -> Option<(
 		TrackedTransactionStatus<HeaderIdOf<TestChain>>,
 		InvalidationStatus<HeaderIdOf<TestChain>>,
+	)> {
+		on_transaction_status_with_metrics(status, 0, 0, &block_space_metrics()).await
+	}
+
+	async fn on_transaction_status_with_metrics(
+		status: TransactionStatus<HashOf<TestChain>, HashOf<TestChain>>,
+		block_number: BlockNumberOf<TestChain>,
+		transaction_size: u32,
+		block_space_metrics: &BlockSpaceMetrics,
+	) -> Option<(
+		TrackedTransactionStatus<HeaderIdOf<TestChain>>,
+		InvalidationStatus<HeaderIdOf<TestChain>>,
 	)> {
 		let (mut sender, receiver) = futures::channel::mpsc::channel(1);
 		let tx_tracker = TransactionTracker::<TestChain, TestEnvironment>::new(
-			TestEnvironment(Ok(HeaderId(0, Default::default()))),
+			TestEnvironment(Ok(HeaderId(block_number, Default::default()))),
 			Duration::from_secs(0),
 			Default::default(),
+			transaction_size,
 			Subscription(async_std::sync::Mutex::new(receiver)),
+			block_space_metrics.clone(),
 		);
 
 		let wait_for_stall_timeout = futures::future::pending();
@@ -334,12 +486,58 @@ mod tests {
 		);
 	}
 
+	#[async_std::test]
+	async fn block_space_metrics_are_accumulated() {
+		let metrics = block_space_metrics();
+
+		// two transactions are finalized at block#1
+		on_transaction_status_with_metrics(
+			TransactionStatus::Finalized(Default::default()),
+			1,
+			100,
+			&metrics,
+		)
+		.await;
+		on_transaction_status_with_metrics(
+			TransactionStatus::Finalized(Default::default()),
+			1,
+			200,
+			&metrics,
+		)
+		.await;
+		// one transaction is dropped
+		on_transaction_status_with_metrics(TransactionStatus::Dropped, 0, 300, &metrics).await;
+		// one transaction is invalid
+		on_transaction_status_with_metrics(TransactionStatus::Invalid, 0, 400, &metrics).await;
+		// one transaction is still in the pool
+		on_transaction_status_with_metrics(TransactionStatus::Ready, 0, 500, &metrics).await;
+		// and another transaction is finalized at block#2
+		on_transaction_status_with_metrics(
+			TransactionStatus::Finalized(Default::default()),
+			2,
+			600,
+			&metrics,
+		)
+		.await;
+
+		assert_eq!(metrics.submitted_bytes(), 900);
+		assert_eq!(metrics.consumed_weight(), 3 * TEST_TRANSACTION_WEIGHT);
+		assert_eq!(metrics.lost_transactions(), 2);
+		assert_eq!(
+			metrics.block_capacity_share(),
+			(3 * TEST_TRANSACTION_WEIGHT) as f64 /
+				(TEST_MAX_BLOCK_WEIGHT * crate::metrics::BLOCK_CAPACITY_WINDOW_IN_BLOCKS) as f64,
+		);
+	}
+
 	#[async_std::test]
 	async fn returns_lost_on_finalized_and_environment_error() {
 		assert_eq!(
 			watch_transaction_status::<_, TestChain, _>(
 				TestEnvironment(Err(Error::UninitializedBridgePallet)),
 				Default::default(),
+				0,
+				&block_space_metrics(),
 				futures::stream::iter([TransactionStatus::Finalized(Default::default())])
 			)
 			.now_or_never(),
@@ -419,6 +617,8 @@ mod tests {
 			watch_transaction_status::<_, TestChain, _>(
 				TestEnvironment(Ok(HeaderId(0, Default::default()))),
 				Default::default(),
+				0,
+				&block_space_metrics(),
 				futures::stream::iter([])
 			)
 			.now_or_never(),
@@ -426,22 +626,48 @@ mod tests {
 		);
 	}
 
-	#[async_std::test]
-	async fn lost_on_timeout_when_waiting_for_invalidation_status() {
+	async fn wait_for_invalidation_status_with_clock(
+		advance_clock_by: Duration,
+	) -> Option<(
+		TrackedTransactionStatus<HeaderIdOf<TestChain>>,
+		Option<InvalidationStatus<HeaderIdOf<TestChain>>>,
+	)> {
 		let (_sender, receiver) = futures::channel::mpsc::channel(1);
 		let tx_tracker = TransactionTracker::<TestChain, TestEnvironment>::new(
 			TestEnvironment(Ok(HeaderId(0, Default::default()))),
-			Duration::from_secs(0),
+			TEST_STALL_TIMEOUT,
 			Default::default(),
+			0,
 			Subscription(async_std::sync::Mutex::new(receiver)),
+			block_space_metrics(),
 		);
 
-		let wait_for_stall_timeout = futures::future::ready(()).shared();
+		let clock = TestClock::default();
+		let stall_timer =
+			StallTimer::new(clock.clone(), TEST_STALL_TIMEOUT, DEFAULT_TIME_WARP_THRESHOLD);
+		clock.advance(advance_clock_by);
+
+		let wait_for_stall_timeout = stall_timer.wait(STALL_TIMER_CHECK_INTERVAL).shared();
 		let wait_for_stall_timeout_rest = wait_for_stall_timeout.clone();
-		let wait_result = tx_tracker
+		tx_tracker
 			.do_wait(wait_for_stall_timeout, wait_for_stall_timeout_rest)
-			.now_or_never();
+			.now_or_never()
+	}
+
+	#[async_std::test]
+	async fn lost_on_timeout_when_waiting_for_invalidation_status() {
+		assert_eq!(
+			wait_for_invalidation_status_with_clock(TEST_STALL_TIMEOUT).await,
+			Some((TrackedTransactionStatus::Lost, None)),
+		);
+	}
 
-		assert_eq!(wait_result, Some((TrackedTransactionStatus::Lost, None)));
+	#[async_std::test]
+	async fn not_lost_when_clock_jumps_after_resume() {
+		// the process has been suspended for an hour => it isn't a stall
+		assert_eq!(
+			wait_for_invalidation_status_with_clock(Duration::from_secs(60 * 60)).await,
+			None,
+		);
 	}
 }
@@ -67,11 +67,14 @@ impl RelayChain for Westend {
 	const PARAS_PALLET_NAME: &'static str = bp_westend::PARAS_PALLET_NAME;
 	const PARACHAINS_FINALITY_PALLET_NAME: &'static str =
 		bp_westend::WITH_WESTEND_BRIDGE_PARAS_PALLET_NAME;
+	const MAX_PARA_HEADS_PROOF_SIZE: u32 = bp_westend::MAX_PARA_HEADS_PROOF_SIZE;
 }
 
 impl ChainWithGrandpa for Westend {
 	const WITH_CHAIN_GRANDPA_PALLET_NAME: &'static str =
 		bp_westend::WITH_WESTEND_GRANDPA_PALLET_NAME;
+	const SYNCED_HEADERS_GRANDPA_INFO_METHOD: &'static str =
+		bp_westend::WESTEND_SYNCED_HEADERS_GRANDPA_INFO_METHOD;
 }
 
 impl ChainWithBalances for Westend {
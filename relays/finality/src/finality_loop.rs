@@ -28,14 +28,23 @@ use backoff::backoff::Backoff;
 use futures::{select, Future, FutureExt, Stream, StreamExt};
 use num_traits::{One, Saturating};
 use relay_utils::{
-	metrics::MetricsParams, relay_loop::Client as RelayClient, retry_backoff, FailedClient,
-	HeaderId, MaybeConnectionError, TrackedTransactionStatus, TransactionTracker,
+	clock::{MonotonicClock, RelayClock},
+	metrics::MetricsParams,
+	relay_loop::Client as RelayClient,
+	retry_backoff, FailedClient, HeaderId, MaybeConnectionError, TrackedTransactionStatus,
+	TransactionTracker,
 };
 use std::{
 	pin::Pin,
 	time::{Duration, Instant},
 };
 
+/// Number of loop ticks between two sync progress reports.
+///
+/// With the usual 5-6 seconds blocks, this gives the same ~10 seconds interval that we have been
+/// using before, but chains with faster blocks won't have their progress reported too rarely.
+const PROGRESS_LOG_TICKS: u32 = 2;
+
 /// Finality proof synchronization loop parameters.
 #[derive(Debug, Clone)]
 pub struct FinalitySyncParams {
@@ -61,6 +70,18 @@ pub struct FinalitySyncParams {
 	pub stall_timeout: Duration,
 	/// If true, only mandatory headers are relayed.
 	pub only_mandatory_headers: bool,
+	/// Maximal number of headers that may be submitted in a single batch transaction.
+	///
+	/// Only mandatory headers are batched: if the selected header is mandatory, following
+	/// mandatory headers are submitted along with it. This speeds up the catch-up after relay
+	/// downtime. Batching is disabled if this value is `1` (or `0`).
+	pub max_headers_in_batch: usize,
+	/// Maximal number of source chain blocks that may be built on top of the best finalized
+	/// source block before we start suspecting that the source chain finality is stalled.
+	pub source_finality_stall_blocks: u32,
+	/// If the source chain finality lag exceeds `source_finality_stall_blocks` for longer than
+	/// this duration, we believe that the source chain finality is stalled.
+	pub source_finality_stall_timeout: Duration,
 }
 
 /// Source client used in finality synchronization loop.
@@ -73,6 +94,12 @@ pub trait SourceClient<P: FinalitySyncPipeline>: RelayClient {
 	/// Get best finalized block number.
 	async fn best_finalized_block_number(&self) -> Result<P::Number, Self::Error>;
 
+	/// Get numbers of the best and the best finalized blocks of the source chain.
+	///
+	/// Unlike `best_finalized_block_number`, this method shall return actual on-chain numbers,
+	/// without any relay-level limits. They are only used to check the source finality health.
+	async fn best_block_numbers(&self) -> Result<(P::Number, P::Number), Self::Error>;
+
 	/// Get canonical header and its finality proof by number.
 	async fn header_and_finality_proof(
 		&self,
@@ -100,6 +127,18 @@ pub trait TargetClient<P: FinalitySyncPipeline>: RelayClient {
 		header: P::Header,
 		proof: P::FinalityProof,
 	) -> Result<Self::TransactionTracker, Self::Error>;
+
+	/// Get minimal gap between the best finalized source header and the regular (non-mandatory)
+	/// source header, accepted by the target chain.
+	async fn min_header_gap(&self) -> Result<P::Number, Self::Error>;
+
+	/// Submit finality proofs of multiple headers in a single transaction.
+	///
+	/// Headers are ordered by their numbers.
+	async fn submit_finality_proofs_batch(
+		&self,
+		proofs: Vec<(P::Header, P::FinalityProof)>,
+	) -> Result<Self::TransactionTracker, Self::Error>;
 }
 
 /// Return prefix that will be used by default to expose Prometheus metrics of the finality proofs
@@ -197,10 +236,84 @@ impl<S> From<S> for RestartableFinalityProofsStream<S> {
 	}
 }
 
+/// Health of the source chain finality.
+///
+/// If the source chain stops finalizing blocks, the finality relay lag is growing - exactly as if
+/// the relay itself is broken. But restarting the relay won't help in this case, so we're
+/// tracking the source finality separately.
+#[derive(Debug, Default)]
+pub(crate) struct SourceFinalityHealth {
+	/// Time when we have first seen that the source finality lag exceeds the threshold.
+	lagging_since: Option<Instant>,
+	/// True if we believe that the source chain finality is stalled.
+	is_stalled: bool,
+}
+
+impl SourceFinalityHealth {
+	/// Returns true if we believe that the source chain finality is stalled.
+	pub(crate) fn is_stalled(&self) -> bool {
+		self.is_stalled
+	}
+
+	/// Update source finality health, given best and best finalized source block numbers.
+	///
+	/// Returns true if the source chain finality is stalled.
+	pub(crate) fn update<P: FinalitySyncPipeline>(
+		&mut self,
+		now: Instant,
+		best_number: P::Number,
+		best_finalized_number: P::Number,
+		sync_params: &FinalitySyncParams,
+	) -> bool {
+		let finality_lag = best_number.saturating_sub(best_finalized_number);
+		if finality_lag <= sync_params.source_finality_stall_blocks.into() {
+			if self.is_stalled {
+				log::info!(
+					target: "bridge",
+					"{} chain is finalizing blocks again: best block {:?}, best finalized block {:?}",
+					P::SOURCE_NAME,
+					best_number,
+					best_finalized_number,
+				);
+			}
+
+			self.lagging_since = None;
+			self.is_stalled = false;
+			return false
+		}
+
+		let lagging_since = *self.lagging_since.get_or_insert(now);
+		let lagging_for = now.saturating_duration_since(lagging_since);
+		if !self.is_stalled && lagging_for >= sync_params.source_finality_stall_timeout {
+			log::error!(
+				target: "bridge",
+				"{} chain is not finalizing: best block {:?} is {:?} blocks ahead of the best \
+				finalized block {:?} for {}s. Finality relay from {} to {} is healthy and won't \
+				be restarted",
+				P::SOURCE_NAME,
+				best_number,
+				finality_lag,
+				best_finalized_number,
+				lagging_for.as_secs(),
+				P::SOURCE_NAME,
+				P::TARGET_NAME,
+			);
+
+			self.is_stalled = true;
+		}
+
+		self.is_stalled
+	}
+}
+
 /// Finality synchronization loop state.
 pub(crate) struct FinalityLoopState<'a, P: FinalitySyncPipeline, FinalityProofsStream> {
+	/// Clock that is used to track synchronization progress.
+	pub(crate) clock: &'a dyn RelayClock,
 	/// Synchronization loop progress.
 	pub(crate) progress: &'a mut (Instant, Option<P::Number>),
+	/// Health of the source chain finality.
+	pub(crate) source_finality_health: &'a mut SourceFinalityHealth,
 	/// Finality proofs stream.
 	pub(crate) finality_proofs_stream:
 		&'a mut RestartableFinalityProofsStream<FinalityProofsStream>,
@@ -241,7 +354,9 @@ pub(crate) async fn run_until_connection_lost<P: FinalitySyncPipeline>(
 	};
 	let mut recent_finality_proofs = Vec::new();
 
-	let mut progress = (Instant::now(), None);
+	let clock = MonotonicClock;
+	let mut progress = (clock.now(), None);
+	let mut source_finality_health = SourceFinalityHealth::default();
 	let mut retry_backoff = retry_backoff();
 	let mut last_submitted_header_number = None;
 
@@ -251,7 +366,9 @@ pub(crate) async fn run_until_connection_lost<P: FinalitySyncPipeline>(
 			&source_client,
 			&target_client,
 			FinalityLoopState {
+				clock: &clock,
 				progress: &mut progress,
+				source_finality_health: &mut source_finality_health,
 				finality_proofs_stream: &mut finality_proofs_stream,
 				recent_finality_proofs: &mut recent_finality_proofs,
 				submitted_header_number: last_submitted_header_number,
@@ -280,7 +397,16 @@ pub(crate) async fn run_until_connection_lost<P: FinalitySyncPipeline>(
 				retry_backoff.next_backoff().unwrap_or(relay_utils::relay_loop::RECONNECT_DELAY)
 			},
 		};
-		if finality_proofs_stream.needs_restart {
+		if finality_proofs_stream.needs_restart && source_finality_health.is_stalled() {
+			// if source chain is not finalizing blocks, there are no justifications, so
+			// resubscribing won't help
+			log::debug!(
+				target: "bridge",
+				"{} finality proofs stream is not restarted, because {} finality is stalled",
+				P::SOURCE_NAME,
+				P::SOURCE_NAME,
+			);
+		} else if finality_proofs_stream.needs_restart {
 			log::warn!(target: "bridge", "{} finality proofs stream is being restarted", P::SOURCE_NAME);
 
 			finality_proofs_stream.needs_restart = false;
@@ -326,7 +452,7 @@ pub(crate) async fn run_until_connection_lost<P: FinalitySyncPipeline>(
 									e,
 								);
 
-								FailedClient::Both
+								FailedClient::Neither
 							})?;
 					},
 					TrackedTransactionStatus::Lost => {
@@ -337,7 +463,7 @@ pub(crate) async fn run_until_connection_lost<P: FinalitySyncPipeline>(
 							P::TARGET_NAME,
 						);
 
-						return Err(FailedClient::Both);
+						return Err(FailedClient::Neither);
 					},
 				}
 			},
@@ -366,6 +492,16 @@ where
 		target_client.best_finalized_source_block_id().await.map_err(Error::Target)?;
 	let best_number_at_target = best_id_at_target.0;
 
+	// check whether source chain is still finalizing blocks
+	let (best_number, best_finalized_number) =
+		source_client.best_block_numbers().await.map_err(Error::Source)?;
+	let is_source_finality_stalled = state.source_finality_health.update::<P>(
+		state.clock.now(),
+		best_number,
+		best_finalized_number,
+		sync_params,
+	);
+
 	let different_hash_at_source = ensure_same_fork::<P, _>(&best_id_at_target, source_client)
 		.await
 		.map_err(Error::Source)?;
@@ -387,9 +523,15 @@ where
 		metrics_sync.update_best_block_at_source(best_number_at_source);
 		metrics_sync.update_best_block_at_target(best_number_at_target);
 		metrics_sync.update_using_same_fork(using_same_fork);
+		metrics_sync.update_source_finality_stalled(is_source_finality_stalled);
 	}
-	*state.progress =
-		print_sync_progress::<P>(*state.progress, best_number_at_source, best_number_at_target);
+	*state.progress = print_sync_progress::<P>(
+		*state.progress,
+		state.clock.now(),
+		sync_params.tick.saturating_mul(PROGRESS_LOG_TICKS),
+		best_number_at_source,
+		&best_id_at_target,
+	);
 
 	// if we have already submitted header, then we just need to wait for it
 	// if we're waiting too much, then we believe our transaction has been lost and restart sync
@@ -414,17 +556,42 @@ where
 	.await?
 	{
 		Some((header, justification)) => {
-			let submitted_header_number = header.number();
+			// if the selected header is mandatory, following mandatory headers may be submitted
+			// in the same transaction
+			let batch = if header.is_mandatory() && sync_params.max_headers_in_batch > 1 {
+				read_mandatory_headers_batch::<P, SC, TC>(
+					source_client,
+					header.number() + One::one(),
+					best_number_at_source,
+					sync_params.max_headers_in_batch - 1,
+				)
+				.await?
+			} else {
+				Vec::new()
+			};
+			if batch.is_empty() {
+				return submit_finality_proof::<P, SC, TC>(target_client, header, justification)
+					.await
+					.map(Some)
+			}
+
+			let first_header_number = header.number();
+			let mut proofs = Vec::with_capacity(batch.len() + 1);
+			proofs.push((header, justification));
+			proofs.extend(batch);
+			let submitted_header_number = proofs[proofs.len() - 1].0.number();
 			log::debug!(
 				target: "bridge",
-				"Going to submit finality proof of {} header #{:?} to {}",
+				"Going to submit finality proofs of {} {} headers {:?}..={:?} to {}",
+				proofs.len(),
 				P::SOURCE_NAME,
+				first_header_number,
 				submitted_header_number,
 				P::TARGET_NAME,
 			);
 
 			let tracker = target_client
-				.submit_finality_proof(header, justification)
+				.submit_finality_proofs_batch(proofs)
 				.await
 				.map_err(Error::Target)?;
 			Ok(Some(Transaction { tracker, submitted_header_number }))
@@ -433,6 +600,68 @@ where
 	}
 }
 
+/// Submit finality proof of single header to the target client.
+async fn submit_finality_proof<P, SC, TC>(
+	target_client: &TC,
+	header: P::Header,
+	justification: P::FinalityProof,
+) -> Result<Transaction<TC::TransactionTracker, P::Number>, Error<P, SC::Error, TC::Error>>
+where
+	P: FinalitySyncPipeline,
+	SC: SourceClient<P>,
+	TC: TargetClient<P>,
+{
+	let submitted_header_number = header.number();
+	log::debug!(
+		target: "bridge",
+		"Going to submit finality proof of {} header {} to {}",
+		P::SOURCE_NAME,
+		HeaderId(submitted_header_number, header.hash()),
+		P::TARGET_NAME,
+	);
+
+	let tracker = target_client
+		.submit_finality_proof(header, justification)
+		.await
+		.map_err(Error::Target)?;
+	Ok(Transaction { tracker, submitted_header_number })
+}
+
+/// Read mandatory headers and their persistent finality proofs, starting from the given header.
+///
+/// Regular headers are skipped. Reading stops once `max_headers` headers are collected or if we
+/// meet mandatory header without persistent finality proof. Such header will be reported by the
+/// `read_missing_headers` during next iteration.
+pub(crate) async fn read_mandatory_headers_batch<
+	P: FinalitySyncPipeline,
+	SC: SourceClient<P>,
+	TC: TargetClient<P>,
+>(
+	source_client: &SC,
+	first_header_number: P::Number,
+	best_number_at_source: P::Number,
+	max_headers: usize,
+) -> Result<Vec<(P::Header, P::FinalityProof)>, Error<P, SC::Error, TC::Error>> {
+	let mut proofs = Vec::new();
+	let mut header_number = first_header_number;
+	while header_number <= best_number_at_source && proofs.len() < max_headers {
+		let (header, finality_proof) = source_client
+			.header_and_finality_proof(header_number)
+			.await
+			.map_err(Error::Source)?;
+		if header.is_mandatory() {
+			match finality_proof {
+				Some(finality_proof) => proofs.push((header, finality_proof)),
+				None => break,
+			}
+		}
+
+		header_number = header_number + One::one();
+	}
+
+	Ok(proofs)
+}
+
 pub(crate) async fn select_header_to_submit<P, SC, TC>(
 	source_client: &SC,
 	target_client: &TC,
@@ -498,6 +727,23 @@ where
 		sync_params.recent_finality_proofs_limit,
 	);
 
+	// the target chain rejects regular headers that are too close to its best finalized header,
+	// so we'll wait for better header instead of wasting transaction
+	if let Some((ref header, _)) = selected_finality_proof {
+		let min_header_gap = target_client.min_header_gap().await.map_err(Error::Target)?;
+		if header.number() < best_number_at_target.saturating_add(min_header_gap) {
+			log::trace!(
+				target: "bridge",
+				"Not submitting {} header {:?}: minimal header gap at {} is {:?}",
+				P::SOURCE_NAME,
+				header.number(),
+				P::TARGET_NAME,
+				min_header_gap,
+			);
+			return Ok(None)
+		}
+	}
+
 	Ok(selected_finality_proof)
 }
 
@@ -714,13 +960,15 @@ pub(crate) fn prune_recent_finality_proofs<P: FinalitySyncPipeline>(
 
 fn print_sync_progress<P: FinalitySyncPipeline>(
 	progress_context: (Instant, Option<P::Number>),
+	now: Instant,
+	progress_log_interval: Duration,
 	best_number_at_source: P::Number,
-	best_number_at_target: P::Number,
+	best_id_at_target: &HeaderId<P::Hash, P::Number>,
 ) -> (Instant, Option<P::Number>) {
 	let (prev_time, prev_best_number_at_target) = progress_context;
-	let now = Instant::now();
+	let best_number_at_target = best_id_at_target.0;
 
-	let need_update = now - prev_time > Duration::from_secs(10) ||
+	let need_update = now.saturating_duration_since(prev_time) > progress_log_interval ||
 		prev_best_number_at_target
 			.map(|prev_best_number_at_target| {
 				best_number_at_target.saturating_sub(prev_best_number_at_target) > 10.into()
@@ -733,9 +981,12 @@ fn print_sync_progress<P: FinalitySyncPipeline>(
 
 	log::info!(
 		target: "bridge",
-		"Synced {:?} of {:?} headers",
+		"Synced {:?} of {:?} headers. Best {} header at {}: {}",
 		best_number_at_target,
 		best_number_at_source,
+		P::SOURCE_NAME,
+		P::TARGET_NAME,
+		best_id_at_target,
 	);
 	(now, Some(best_number_at_target))
 }
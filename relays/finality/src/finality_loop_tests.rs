@@ -23,7 +23,7 @@ use crate::{
 		prune_recent_finality_proofs, read_finality_proofs_from_stream, run_loop_iteration,
 		run_until_connection_lost, select_better_recent_finality_proof, select_header_to_submit,
 		FinalityLoopState, FinalityProofs, FinalitySyncParams, RestartableFinalityProofsStream,
-		SourceClient, TargetClient,
+		SourceClient, SourceFinalityHealth, TargetClient,
 	},
 	sync_loop_metrics::SyncLoopMetrics,
 	FinalityProof, FinalitySyncPipeline, SourceHeader,
@@ -33,15 +33,11 @@ use async_trait::async_trait;
 use futures::{FutureExt, Stream, StreamExt};
 use parking_lot::Mutex;
 use relay_utils::{
-	relay_loop::Client as RelayClient, FailedClient, HeaderId, MaybeConnectionError,
-	TrackedTransactionStatus, TransactionTracker,
-};
-use std::{
-	collections::HashMap,
-	pin::Pin,
-	sync::Arc,
-	time::{Duration, Instant},
+	clock::{RelayClock, TestClock},
+	relay_loop::Client as RelayClient,
+	FailedClient, HeaderId, MaybeConnectionError, TrackedTransactionStatus, TransactionTracker,
 };
+use std::{collections::HashMap, pin::Pin, sync::Arc, time::Duration};
 
 type IsMandatory = bool;
 type TestNumber = u64;
@@ -118,11 +114,15 @@ impl FinalityProof<TestNumber> for TestFinalityProof {
 #[derive(Debug, Clone, Default)]
 struct ClientsData {
 	source_best_block_number: TestNumber,
+	source_best_unfinalized_block_number: TestNumber,
 	source_headers: HashMap<TestNumber, (TestSourceHeader, Option<TestFinalityProof>)>,
 	source_proofs: Vec<TestFinalityProof>,
+	source_proofs_subscriptions: usize,
 
 	target_best_block_id: HeaderId<TestHash, TestNumber>,
 	target_headers: Vec<(TestSourceHeader, TestFinalityProof)>,
+	target_batch_transactions: usize,
+	target_min_header_gap: TestNumber,
 	target_transaction_tracker: TestTransactionTracker,
 }
 
@@ -151,6 +151,15 @@ impl SourceClient<TestFinalitySyncPipeline> for TestSourceClient {
 		Ok(data.source_best_block_number)
 	}
 
+	async fn best_block_numbers(&self) -> Result<(TestNumber, TestNumber), TestError> {
+		let mut data = self.data.lock();
+		(self.on_method_call)(&mut data);
+		Ok((
+			std::cmp::max(data.source_best_unfinalized_block_number, data.source_best_block_number),
+			data.source_best_block_number,
+		))
+	}
+
 	async fn header_and_finality_proof(
 		&self,
 		number: TestNumber,
@@ -163,6 +172,7 @@ impl SourceClient<TestFinalitySyncPipeline> for TestSourceClient {
 	async fn finality_proofs(&self) -> Result<Self::FinalityProofsStream, TestError> {
 		let mut data = self.data.lock();
 		(self.on_method_call)(&mut data);
+		data.source_proofs_subscriptions += 1;
 		Ok(futures::stream::iter(data.source_proofs.clone()).boxed())
 	}
 }
@@ -206,6 +216,27 @@ impl TargetClient<TestFinalitySyncPipeline> for TestTargetClient {
 		(self.on_method_call)(&mut data);
 		Ok(data.target_transaction_tracker.clone())
 	}
+
+	async fn min_header_gap(&self) -> Result<TestNumber, TestError> {
+		let mut data = self.data.lock();
+		(self.on_method_call)(&mut data);
+		Ok(data.target_min_header_gap)
+	}
+
+	async fn submit_finality_proofs_batch(
+		&self,
+		proofs: Vec<(TestSourceHeader, TestFinalityProof)>,
+	) -> Result<TestTransactionTracker, TestError> {
+		let mut data = self.data.lock();
+		(self.on_method_call)(&mut data);
+		if let Some((header, _)) = proofs.last() {
+			data.target_best_block_id = HeaderId(header.number(), header.hash());
+		}
+		data.target_headers.extend(proofs);
+		data.target_batch_transactions += 1;
+		(self.on_method_call)(&mut data);
+		Ok(data.target_transaction_tracker.clone())
+	}
 }
 
 fn prepare_test_clients(
@@ -221,11 +252,15 @@ fn prepare_test_clients(
 		});
 	let clients_data = Arc::new(Mutex::new(ClientsData {
 		source_best_block_number: 10,
+		source_best_unfinalized_block_number: 10,
 		source_headers,
 		source_proofs: vec![TestFinalityProof(12), TestFinalityProof(14)],
+		source_proofs_subscriptions: 0,
 
 		target_best_block_id: HeaderId(5, 5),
 		target_headers: vec![],
+		target_batch_transactions: 0,
+		target_min_header_gap: 0,
 		target_transaction_tracker: TestTransactionTracker(TrackedTransactionStatus::Finalized(
 			Default::default(),
 		)),
@@ -245,6 +280,9 @@ fn test_sync_params() -> FinalitySyncParams {
 		recent_finality_proofs_limit: 1024,
 		stall_timeout: Duration::from_secs(1),
 		only_mandatory_headers: false,
+		max_headers_in_batch: 1,
+		source_finality_stall_blocks: 8,
+		source_finality_stall_timeout: Duration::from_secs(0),
 	}
 }
 
@@ -358,11 +396,66 @@ fn run_only_mandatory_headers_mode_test(
 			recent_finality_proofs_limit: 0,
 			stall_timeout: Duration::from_secs(0),
 			only_mandatory_headers,
+			max_headers_in_batch: 1,
+			source_finality_stall_blocks: 8,
+			source_finality_stall_timeout: Duration::from_secs(0),
 		},
 	))
 	.unwrap()
 }
 
+fn run_min_header_gap_test(
+	min_header_gap: TestNumber,
+	has_mandatory_headers: bool,
+) -> Option<(TestSourceHeader, TestFinalityProof)> {
+	let (exit_sender, _) = futures::channel::mpsc::unbounded();
+	let (source_client, target_client) = prepare_test_clients(
+		exit_sender,
+		|_| false,
+		vec![
+			(6, (TestSourceHeader(false, 6, 6), None)),
+			(7, (TestSourceHeader(has_mandatory_headers, 7, 7), Some(TestFinalityProof(7)))),
+			(8, (TestSourceHeader(false, 8, 8), Some(TestFinalityProof(8)))),
+			(9, (TestSourceHeader(false, 9, 9), None)),
+			(10, (TestSourceHeader(false, 10, 10), None)),
+		]
+		.into_iter()
+		.collect(),
+	);
+	target_client.data.lock().target_min_header_gap = min_header_gap;
+	async_std::task::block_on(select_header_to_submit(
+		&source_client,
+		&target_client,
+		&mut RestartableFinalityProofsStream::from(futures::stream::empty().boxed()),
+		&mut vec![],
+		10,
+		5,
+		&test_sync_params(),
+	))
+	.unwrap()
+}
+
+#[test]
+fn select_header_to_submit_respects_min_header_gap() {
+	// zero gap doesn't restrict anything
+	assert_eq!(
+		run_min_header_gap_test(0, false),
+		Some((TestSourceHeader(false, 8, 8), TestFinalityProof(8))),
+	);
+	// header#8 is exactly at the gap
+	assert_eq!(
+		run_min_header_gap_test(3, false),
+		Some((TestSourceHeader(false, 8, 8), TestFinalityProof(8))),
+	);
+	// header#8 is below the gap
+	assert_eq!(run_min_header_gap_test(4, false), None);
+	// mandatory headers are submitted regardless of the gap
+	assert_eq!(
+		run_min_header_gap_test(4, true),
+		Some((TestSourceHeader(true, 7, 7), TestFinalityProof(7))),
+	);
+}
+
 #[test]
 fn select_header_to_submit_skips_non_mandatory_headers_when_only_mandatory_headers_are_required() {
 	assert_eq!(run_only_mandatory_headers_mode_test(true, false), None);
@@ -551,7 +644,9 @@ fn different_forks_at_source_and_at_target_are_detected() {
 		.collect(),
 	);
 
-	let mut progress = (Instant::now(), None);
+	let clock = TestClock::default();
+	let mut progress = (clock.now(), None);
+	let mut source_finality_health = SourceFinalityHealth::default();
 	let mut finality_proofs_stream = RestartableFinalityProofsStream {
 		needs_restart: false,
 		stream: Box::pin(futures::stream::iter(vec![]).boxed()),
@@ -562,7 +657,9 @@ fn different_forks_at_source_and_at_target_are_detected() {
 		&source_client,
 		&target_client,
 		FinalityLoopState {
+			clock: &clock,
 			progress: &mut progress,
+			source_finality_health: &mut source_finality_health,
 			finality_proofs_stream: &mut finality_proofs_stream,
 			recent_finality_proofs: &mut recent_finality_proofs,
 			submitted_header_number: None,
@@ -583,7 +680,7 @@ fn stalls_when_transaction_tracker_returns_error() {
 		data.target_best_block_id.0 == 16
 	});
 
-	assert_eq!(result, Err(FailedClient::Both));
+	assert_eq!(result, Err(FailedClient::Neither));
 }
 
 #[test]
@@ -593,5 +690,186 @@ fn stalls_when_transaction_tracker_returns_finalized_but_transaction_fails() {
 		data.target_best_block_id.0 == 16
 	});
 
-	assert_eq!(result, Err(FailedClient::Both));
+	assert_eq!(result, Err(FailedClient::Neither));
+}
+
+#[test]
+fn source_finality_stall_is_detected() {
+	let (exit_sender, _exit_receiver) = futures::channel::mpsc::unbounded();
+	let (source_client, target_client) = prepare_test_clients(
+		exit_sender,
+		|_| false,
+		(5..=30)
+			.map(|number| (number, (TestSourceHeader(false, number, number), None)))
+			.collect(),
+	);
+	let sync_params = FinalitySyncParams {
+		source_finality_stall_timeout: Duration::from_secs(60),
+		..test_sync_params()
+	};
+
+	let clock = TestClock::default();
+	let mut progress = (clock.now(), None);
+	let mut source_finality_health = SourceFinalityHealth::default();
+	let mut finality_proofs_stream = RestartableFinalityProofsStream {
+		needs_restart: false,
+		stream: Box::pin(futures::stream::pending().boxed()),
+	};
+	let mut recent_finality_proofs = Vec::new();
+	let metrics_sync = SyncLoopMetrics::new(None, "source", "target").unwrap();
+	let mut run_iteration = || {
+		async_std::task::block_on(run_loop_iteration::<TestFinalitySyncPipeline, _, _>(
+			&source_client,
+			&target_client,
+			FinalityLoopState {
+				clock: &clock,
+				progress: &mut progress,
+				source_finality_health: &mut source_finality_health,
+				finality_proofs_stream: &mut finality_proofs_stream,
+				recent_finality_proofs: &mut recent_finality_proofs,
+				submitted_header_number: None,
+			},
+			&sync_params,
+			&Some(metrics_sync.clone()),
+		))
+		.unwrap();
+	};
+
+	// source finality lag is within the limit => finality is healthy
+	source_client.data.lock().source_best_unfinalized_block_number = 18;
+	run_iteration();
+	assert!(!metrics_sync.is_source_finality_stalled());
+
+	// finalization halts, while best blocks keep advancing => finality is not yet stalled,
+	// because the timeout has not elapsed yet
+	source_client.data.lock().source_best_unfinalized_block_number = 19;
+	run_iteration();
+	assert!(!metrics_sync.is_source_finality_stalled());
+	clock.advance(Duration::from_secs(30));
+	source_client.data.lock().source_best_unfinalized_block_number = 24;
+	run_iteration();
+	assert!(!metrics_sync.is_source_finality_stalled());
+
+	// the lag exceeds the limit for longer than the timeout => finality is stalled
+	clock.advance(Duration::from_secs(30));
+	source_client.data.lock().source_best_unfinalized_block_number = 29;
+	run_iteration();
+	assert!(metrics_sync.is_source_finality_stalled());
+
+	// source chain finalizes blocks again => finality is healthy
+	source_client.data.lock().source_best_block_number = 25;
+	run_iteration();
+	assert!(!metrics_sync.is_source_finality_stalled());
+}
+
+#[test]
+fn finality_proofs_stream_is_not_restarted_while_source_finality_is_stalled() {
+	fn run_test(source_best_unfinalized_block_number: TestNumber) -> usize {
+		let (client_data, result) = run_sync_loop(move |data| {
+			data.source_best_unfinalized_block_number = source_best_unfinalized_block_number;
+			data.target_best_block_id.0 == 9
+		});
+		assert_eq!(result, Ok(()));
+		assert_eq!(
+			client_data.target_headers,
+			vec![
+				(TestSourceHeader(true, 8, 8), TestFinalityProof(8)),
+				(TestSourceHeader(false, 9, 9), TestFinalityProof(9)),
+			],
+		);
+		client_data.source_proofs_subscriptions
+	}
+
+	// when source chain is finalizing blocks, the ended finality proofs stream is restarted
+	assert!(run_test(10) > 1);
+	// when source chain is not finalizing blocks, we keep using the same stream
+	assert_eq!(run_test(100), 1);
+}
+
+fn run_batching_test(max_headers_in_batch: usize) -> ClientsData {
+	let (exit_sender, _exit_receiver) = futures::channel::mpsc::unbounded();
+	let (source_client, target_client) = prepare_test_clients(
+		exit_sender,
+		|_| false,
+		vec![
+			(5, (TestSourceHeader(false, 5, 5), None)),
+			(6, (TestSourceHeader(false, 6, 6), None)),
+			(7, (TestSourceHeader(true, 7, 7), Some(TestFinalityProof(7)))),
+			(8, (TestSourceHeader(false, 8, 8), Some(TestFinalityProof(8)))),
+			(9, (TestSourceHeader(true, 9, 9), Some(TestFinalityProof(9)))),
+			(10, (TestSourceHeader(true, 10, 10), Some(TestFinalityProof(10)))),
+			(11, (TestSourceHeader(true, 11, 11), None)),
+			(12, (TestSourceHeader(true, 12, 12), Some(TestFinalityProof(12)))),
+		]
+		.into_iter()
+		.collect(),
+	);
+	source_client.data.lock().source_best_block_number = 12;
+	let sync_params = FinalitySyncParams { max_headers_in_batch, ..test_sync_params() };
+
+	let clock = TestClock::default();
+	let mut progress = (clock.now(), None);
+	let mut source_finality_health = SourceFinalityHealth::default();
+	let mut finality_proofs_stream = RestartableFinalityProofsStream {
+		needs_restart: false,
+		stream: Box::pin(futures::stream::pending().boxed()),
+	};
+	let mut recent_finality_proofs = Vec::new();
+	let transaction =
+		async_std::task::block_on(run_loop_iteration::<TestFinalitySyncPipeline, _, _>(
+			&source_client,
+			&target_client,
+			FinalityLoopState {
+				clock: &clock,
+				progress: &mut progress,
+				source_finality_health: &mut source_finality_health,
+				finality_proofs_stream: &mut finality_proofs_stream,
+				recent_finality_proofs: &mut recent_finality_proofs,
+				submitted_header_number: None,
+			},
+			&sync_params,
+			&None,
+		))
+		.unwrap()
+		.unwrap();
+
+	let clients_data = source_client.data.lock().clone();
+	assert_eq!(transaction.submitted_header_number, clients_data.target_best_block_id.0);
+	clients_data
+}
+
+#[test]
+fn mandatory_headers_are_submitted_one_by_one_if_batching_is_disabled() {
+	let clients_data = run_batching_test(1);
+	assert_eq!(clients_data.target_batch_transactions, 0);
+	assert_eq!(
+		clients_data.target_headers,
+		vec![(TestSourceHeader(true, 7, 7), TestFinalityProof(7))],
+	);
+}
+
+#[test]
+fn mandatory_headers_are_submitted_in_batch() {
+	// batch is limited by the `max_headers_in_batch`
+	let clients_data = run_batching_test(2);
+	assert_eq!(clients_data.target_batch_transactions, 1);
+	assert_eq!(
+		clients_data.target_headers,
+		vec![
+			(TestSourceHeader(true, 7, 7), TestFinalityProof(7)),
+			(TestSourceHeader(true, 9, 9), TestFinalityProof(9)),
+		],
+	);
+
+	// batch ends before mandatory header without persistent finality proof
+	let clients_data = run_batching_test(16);
+	assert_eq!(clients_data.target_batch_transactions, 1);
+	assert_eq!(
+		clients_data.target_headers,
+		vec![
+			(TestSourceHeader(true, 7, 7), TestFinalityProof(7)),
+			(TestSourceHeader(true, 9, 9), TestFinalityProof(9)),
+			(TestSourceHeader(true, 10, 10), TestFinalityProof(10)),
+		],
+	);
 }
@@ -28,6 +28,8 @@ pub struct SyncLoopMetrics {
 	/// Flag that has `0` value when best source headers at the source node and at-target-chain
 	/// are matching and `1` otherwise.
 	using_different_forks: IntGauge,
+	/// Flag that has `1` value when the source chain finality is stalled and `0` otherwise.
+	source_finality_stalled: IntGauge,
 }
 
 impl SyncLoopMetrics {
@@ -51,6 +53,13 @@ impl SyncLoopMetrics {
 				"Whether the best finalized source block at target node is different (value 1) from the \
 				corresponding block at the source node",
 			)?,
+			source_finality_stalled: IntGauge::new(
+				metric_name(prefix, &format!("{}_finality_stalled", at_source_chain_label)),
+				format!(
+					"Whether the {} chain has stopped finalizing blocks (value 1)",
+					at_source_chain_label,
+				),
+			)?,
 		})
 	}
 
@@ -60,6 +69,12 @@ impl SyncLoopMetrics {
 		self.using_different_forks.get() == 0
 	}
 
+	/// Returns current value of the source-finality-stalled flag.
+	#[cfg(test)]
+	pub(crate) fn is_source_finality_stalled(&self) -> bool {
+		self.source_finality_stalled.get() == 1
+	}
+
 	/// Update best block number at source.
 	pub fn update_best_block_at_source<Number: Into<u64>>(&self, source_best_number: Number) {
 		self.best_source_block_number.set(source_best_number.into());
@@ -74,6 +89,11 @@ impl SyncLoopMetrics {
 	pub fn update_using_same_fork(&self, using_same_fork: bool) {
 		self.using_different_forks.set(if using_same_fork { 0 } else { 1 })
 	}
+
+	/// Update source-finality-stalled flag.
+	pub fn update_source_finality_stalled(&self, is_stalled: bool) {
+		self.source_finality_stalled.set(if is_stalled { 1 } else { 0 })
+	}
 }
 
 impl Metric for SyncLoopMetrics {
@@ -81,6 +101,7 @@ impl Metric for SyncLoopMetrics {
 		register(self.best_source_block_number.clone(), registry)?;
 		register(self.best_target_block_number.clone(), registry)?;
 		register(self.using_different_forks.clone(), registry)?;
+		register(self.source_finality_stalled.clone(), registry)?;
 		Ok(())
 	}
 }
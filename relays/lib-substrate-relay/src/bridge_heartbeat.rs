@@ -0,0 +1,142 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Bridge heartbeat - the `frame_system::Call::remark_with_event` transaction that carries the
+//! bridge identifier and relay version. It is submitted once per relay session, so indexers
+//! may attribute on-chain activity to the specific bridge (and relay deployment).
+
+use crate::TransactionParams;
+
+use bp_runtime::BridgeRemark;
+use relay_substrate_client::{
+	AccountIdOf, AccountKeyPairOf, CallOf, Chain, Client, SignParam, TransactionEra,
+	TransactionSignScheme, UnsignedTransaction,
+};
+use sp_core::Pair;
+
+/// Build `remark_with_event` call, carrying encoded bridge remark.
+pub fn bridge_heartbeat_call<C: Chain>(remark: &BridgeRemark) -> anyhow::Result<CallOf<C>> {
+	C::remark_with_event_call(remark.encode_remark()).ok_or_else(|| {
+		anyhow::format_err!("Unable to tag transactions: {} has no remark_with_event call", C::NAME)
+	})
+}
+
+/// Submit bridge heartbeat transaction to the chain.
+///
+/// The call is built before any network activity, so the error is returned immediately if
+/// the chain doesn't support remarks. The transaction itself is submitted in the background
+/// and its failure is only logged - it must never stop the relay.
+pub fn start_bridge_heartbeat<C, S>(
+	client: Client<C>,
+	transaction_params: TransactionParams<AccountKeyPairOf<S>>,
+	remark: BridgeRemark,
+) -> anyhow::Result<()>
+where
+	C: Chain,
+	S: TransactionSignScheme<Chain = C>,
+	AccountIdOf<C>: From<<AccountKeyPairOf<S> as Pair>::Public>,
+{
+	let call = transaction_params.prepare_call::<C>(bridge_heartbeat_call::<C>(&remark)?)?;
+
+	log::info!(
+		target: "bridge",
+		"Submitting bridge heartbeat to {}. Bridge id: {:?}",
+		C::NAME,
+		remark.bridge_id,
+	);
+
+	async_std::task::spawn(async move {
+		let result = submit_bridge_heartbeat::<C, S>(client, transaction_params, call).await;
+		match result {
+			Ok(()) => log::info!(
+				target: "bridge",
+				"Submitted bridge heartbeat to {}. Bridge id: {:?}",
+				C::NAME,
+				remark.bridge_id,
+			),
+			Err(error) => log::error!(
+				target: "bridge",
+				"Failed to submit bridge heartbeat to {}: {:?}",
+				C::NAME,
+				error,
+			),
+		}
+	});
+
+	Ok(())
+}
+
+/// Sign and submit prepared heartbeat call.
+async fn submit_bridge_heartbeat<C, S>(
+	client: Client<C>,
+	transaction_params: TransactionParams<AccountKeyPairOf<S>>,
+	call: CallOf<C>,
+) -> anyhow::Result<()>
+where
+	C: Chain,
+	S: TransactionSignScheme<Chain = C>,
+	AccountIdOf<C>: From<<AccountKeyPairOf<S> as Pair>::Public>,
+{
+	let genesis_hash = *client.genesis_hash();
+	let signer_id = transaction_params.signer.public().into();
+	let (spec_version, transaction_version) = client.simple_runtime_version().await?;
+	client
+		.submit_signed_extrinsic(
+			signer_id,
+			SignParam::<S> {
+				spec_version,
+				transaction_version,
+				genesis_hash,
+				signer: transaction_params.signer,
+			},
+			move |best_block_id, transaction_nonce| {
+				Ok(UnsignedTransaction::new(call.into(), transaction_nonce)
+					.era(TransactionEra::new_checked(best_block_id, transaction_params.mortality)?))
+			},
+		)
+		.await
+		.map(drop)
+		.map_err(|err| anyhow::format_err!("{:?}", err))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use bp_runtime::BridgeId;
+	use codec::{Decode, Encode};
+	use relay_pass3dt_client::Pass3dt;
+
+	#[test]
+	fn bridge_heartbeat_remark_round_trips() {
+		let remark = BridgeRemark {
+			bridge_id: BridgeId::new(
+				bp_runtime::PASS3DT_CHAIN_ID,
+				bp_runtime::PASS3D_CHAIN_ID,
+				&[[0u8; 4]],
+			),
+			relay_version: b"1.0.0".to_vec(),
+		};
+
+		let call = bridge_heartbeat_call::<Pass3dt>(&remark).unwrap();
+		let decoded_call = pass3dt_runtime::Call::decode(&mut &call.encode()[..]).unwrap();
+		match decoded_call {
+			pass3dt_runtime::Call::System(pass3dt_runtime::SystemCall::remark_with_event {
+				remark: encoded_remark,
+			}) => assert_eq!(BridgeRemark::decode_remark(&encoded_remark), Some(remark)),
+			_ => panic!("unexpected call: {:?}", decoded_call),
+		}
+	}
+}
@@ -281,7 +281,7 @@ where
 			},
 			move |best_block_id, transaction_nonce| {
 				Ok(UnsignedTransaction::new(call.into(), transaction_nonce)
-					.era(TransactionEra::new(best_block_id, transaction_params.mortality)))
+					.era(TransactionEra::new_checked(best_block_id, transaction_params.mortality)?))
 			},
 		)
 		.await
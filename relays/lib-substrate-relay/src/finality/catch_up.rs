@@ -0,0 +1,362 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Catching up with GRANDPA authorities set changes of the source chain.
+//!
+//! When the finality pallet is initialized with some old header of the chain with long
+//! history, regular finality relay needs to submit every mandatory header (header that is
+//! changing the GRANDPA authorities set) since that header. It reads all intermediate
+//! headers and takes hours to complete. Instead, the catch-up routine is looking for
+//! authorities set changes using `GrandpaApi::current_set_id` and submits only mandatory
+//! headers with their justifications, in ordered batches.
+//!
+//! The routine is resumable - it always starts from the best source header, known to the
+//! target chain.
+
+use crate::{
+	finality::{engine::Grandpa, target::SubstrateFinalityTarget, SubstrateFinalitySyncPipeline},
+	TransactionParams,
+};
+
+use bp_header_chain::{
+	find_grandpa_authorities_scheduled_change,
+	justification::{verify_justification, GrandpaJustification},
+	AuthoritySet,
+};
+use codec::Decode;
+use finality_grandpa::voter_set::VoterSet;
+use finality_relay::TargetClient;
+use num_traits::{One, Zero};
+use relay_substrate_client::{
+	AccountIdOf, AccountKeyPairOf, BlockNumberOf, BlockWithJustification, Chain, ChainWithGrandpa,
+	Client, HeaderIdOf, HeaderOf, SyncHeader, TransactionSignScheme,
+};
+use relay_utils::{HeaderId, TrackedTransactionStatus, TransactionTracker};
+use sp_core::Pair;
+use sp_finality_grandpa::SetId;
+use sp_runtime::traits::{AtLeast32BitUnsigned, Header as _};
+use std::future::Future;
+
+/// Mandatory header with its justification.
+type MandatoryHeader<C> = (HeaderOf<C>, GrandpaJustification<HeaderOf<C>>);
+
+/// Submit all mandatory headers of the source chain up to given block to the target chain.
+///
+/// Headers are submitted in batches of at most `max_batch_size` transactions. All headers of the
+/// batch are submitted at once and then we're waiting until all batch transactions are
+/// finalized.
+pub async fn run<P>(
+	source_client: Client<P::SourceChain>,
+	target_client: Client<P::TargetChain>,
+	until: BlockNumberOf<P::SourceChain>,
+	max_batch_size: usize,
+	transaction_params: TransactionParams<AccountKeyPairOf<P::TransactionSignScheme>>,
+) -> anyhow::Result<()>
+where
+	P: SubstrateFinalitySyncPipeline<
+		FinalityEngine = Grandpa<<P as SubstrateFinalitySyncPipeline>::SourceChain>,
+	>,
+	P::SourceChain: ChainWithGrandpa,
+	AccountIdOf<P::TargetChain>: From<<AccountKeyPairOf<P::TransactionSignScheme> as Pair>::Public>,
+	P::TransactionSignScheme: TransactionSignScheme<Chain = P::TargetChain>,
+{
+	let target = SubstrateFinalityTarget::<P>::new(target_client.clone(), transaction_params);
+	let initial_header = target.best_finalized_source_block_id().await?;
+	let mut submitted_headers = 0;
+	log::info!(
+		target: "bridge",
+		"Catching up with {} authorities set changes at {}. Best known {} header: {:?}. Until: {}",
+		P::SourceChain::NAME,
+		P::TargetChain::NAME,
+		P::SourceChain::NAME,
+		initial_header,
+		until,
+	);
+
+	loop {
+		let best_finalized = target.best_finalized_source_block_id().await?;
+		if best_finalized.0 >= until {
+			break
+		}
+
+		let batch = collect_mandatory_headers(
+			&source_client,
+			read_authority_set::<P>(&target_client).await?,
+			best_finalized,
+			until,
+			max_batch_size,
+		)
+		.await?;
+		let last_header_id = match batch.last() {
+			Some((last_header, _)) => HeaderId(*last_header.number(), last_header.hash()),
+			None => break,
+		};
+
+		let mut transactions = Vec::with_capacity(batch.len());
+		for (header, justification) in batch {
+			transactions.push(
+				target
+					.submit_finality_proof_unchecked(SyncHeader::from(header), justification)
+					.await?,
+			);
+		}
+		let batch_size = transactions.len();
+		let statuses =
+			futures::future::join_all(transactions.into_iter().map(|tx| tx.wait())).await;
+		if statuses.iter().any(|status| matches!(status, TrackedTransactionStatus::Lost)) {
+			anyhow::bail!(
+				"Some of {} mandatory headers, submitted to {}, have been lost. Restart the \
+				command to resume from the best {} header, known to {}",
+				P::SourceChain::NAME,
+				P::TargetChain::NAME,
+				P::SourceChain::NAME,
+				P::TargetChain::NAME,
+			);
+		}
+
+		submitted_headers += batch_size;
+		log::info!(
+			target: "bridge",
+			"Submitted {} {} mandatory headers to {}. Best known header: {:?}. Progress: {}/{}",
+			submitted_headers,
+			P::SourceChain::NAME,
+			P::TargetChain::NAME,
+			last_header_id,
+			last_header_id.0,
+			until,
+		);
+	}
+
+	log::info!(
+		target: "bridge",
+		"{} has caught up with {} authorities set changes. Submitted {} mandatory headers",
+		P::TargetChain::NAME,
+		P::SourceChain::NAME,
+		submitted_headers,
+	);
+
+	Ok(())
+}
+
+/// Read current authorities set of the finality pallet at the target chain.
+async fn read_authority_set<P>(
+	target_client: &Client<P::TargetChain>,
+) -> anyhow::Result<AuthoritySet>
+where
+	P: SubstrateFinalitySyncPipeline,
+	P::SourceChain: ChainWithGrandpa,
+{
+	target_client
+		.storage_value(
+			bp_header_chain::storage_keys::current_authority_set_key(
+				P::SourceChain::WITH_CHAIN_GRANDPA_PALLET_NAME,
+			),
+			None,
+		)
+		.await?
+		.ok_or_else(|| {
+			anyhow::format_err!(
+				"{} finality pallet at {} is not initialized",
+				P::SourceChain::NAME,
+				P::TargetChain::NAME,
+			)
+		})
+}
+
+/// Collect at most `max_headers` mandatory headers after `best_finalized` header and before or at
+/// `until` header.
+///
+/// Justifications of all headers are verified using authorities sets, enacted by previous
+/// headers.
+async fn collect_mandatory_headers<C: ChainWithGrandpa>(
+	source_client: &Client<C>,
+	mut authority_set: AuthoritySet,
+	best_finalized: HeaderIdOf<C>,
+	until: BlockNumberOf<C>,
+	max_headers: usize,
+) -> anyhow::Result<Vec<MandatoryHeader<C>>> {
+	let mut headers = Vec::new();
+	let mut from = best_finalized.0;
+	let mut from_set_id = source_client.grandpa_current_set_id(best_finalized.1).await?;
+	while headers.len() < max_headers {
+		let (number, set_id) =
+			match find_next_set_change(from, from_set_id, until, |number| async move {
+				let hash = source_client.block_hash_by_number(number).await?;
+				Ok(source_client.grandpa_current_set_id(hash).await?)
+			})
+			.await?
+			{
+				Some(set_change) => set_change,
+				None => break,
+			};
+
+		let header = source_client.header_by_number(number).await?;
+		let header_id = HeaderId(number, header.hash());
+		match find_grandpa_authorities_scheduled_change(&header) {
+			Some(change) if change.delay.is_zero() => {
+				let justification = read_justification(source_client, header_id).await?;
+				let voter_set =
+					VoterSet::new(authority_set.authorities.clone()).ok_or_else(|| {
+						anyhow::format_err!(
+							"Invalid {} authorities set: {:?}",
+							C::NAME,
+							authority_set
+						)
+					})?;
+				verify_justification::<C::Header>(
+					(header_id.1, header_id.0),
+					authority_set.set_id,
+					&voter_set,
+					&justification,
+				)
+				.map_err(|e| {
+					anyhow::format_err!(
+						"Invalid justification of {} mandatory header {:?}: {:?}",
+						C::NAME,
+						header_id,
+						e,
+					)
+				})?;
+
+				authority_set =
+					AuthoritySet::new(change.next_authorities, authority_set.set_id + 1);
+				headers.push((header, justification));
+			},
+			change => anyhow::bail!(
+				"{} authorities set has been changed at {:?}, but the header is scheduling {:?}. \
+				Only scheduled changes with zero delay are supported",
+				C::NAME,
+				header_id,
+				change.map(|change| change.delay),
+			),
+		}
+
+		from = number;
+		from_set_id = set_id;
+	}
+
+	Ok(headers)
+}
+
+/// Read and decode GRANDPA justification of given header.
+async fn read_justification<C: Chain>(
+	source_client: &Client<C>,
+	header_id: HeaderIdOf<C>,
+) -> anyhow::Result<GrandpaJustification<HeaderOf<C>>> {
+	let signed_block = source_client.get_block(Some(header_id.1)).await?;
+	let raw_justification = signed_block.justification().ok_or_else(|| {
+		anyhow::format_err!(
+			"Justification of {} mandatory header {:?} is missing. Is the node warp-synced?",
+			C::NAME,
+			header_id,
+		)
+	})?;
+	GrandpaJustification::decode(&mut raw_justification.as_slice()).map_err(|e| {
+		anyhow::format_err!(
+			"Failed to decode justification of {} mandatory header {:?}: {:?}",
+			C::NAME,
+			header_id,
+			e,
+		)
+	})
+}
+
+/// Find first block after `from` and before or at `until`, where the authorities set id is
+/// changed.
+///
+/// The set id is read using `set_id_at` function. Since the set id never decreases, we're using
+/// binary search here. Note that we never compare absolute values of the set id with the set id
+/// at the finality pallet, because `GrandpaApi::current_set_id` may return invalid values for some
+/// chains. We only rely on the fact that it is changed at every mandatory header.
+async fn find_next_set_change<Number, F, Fut>(
+	from: Number,
+	from_set_id: SetId,
+	until: Number,
+	set_id_at: F,
+) -> anyhow::Result<Option<(Number, SetId)>>
+where
+	Number: AtLeast32BitUnsigned + Copy,
+	F: Fn(Number) -> Fut,
+	Fut: Future<Output = anyhow::Result<SetId>>,
+{
+	if until <= from {
+		return Ok(None)
+	}
+
+	let mut high_set_id = set_id_at(until).await?;
+	if high_set_id == from_set_id {
+		return Ok(None)
+	}
+
+	// set id at `low` is always equal to `from_set_id` and set id at `high` is always different
+	let (mut low, mut high) = (from, until);
+	while high - low > One::one() {
+		let mid = low + (high - low) / Number::from(2u32);
+		let mid_set_id = set_id_at(mid).await?;
+		if mid_set_id == from_set_id {
+			low = mid;
+		} else {
+			high = mid;
+			high_set_id = mid_set_id;
+		}
+	}
+
+	Ok(Some((high, high_set_id)))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::sync::Mutex;
+
+	fn find(set_changes: &[u32], from: u32, until: u32) -> (Option<(u32, SetId)>, usize) {
+		let reads = Mutex::new(0);
+		let set_id_at = |number: u32| {
+			*reads.lock().unwrap() += 1;
+			let set_id = set_changes.iter().filter(|change| **change <= number).count() as SetId;
+			async move { Ok(set_id) }
+		};
+		let from_set_id = set_changes.iter().filter(|change| **change <= from).count() as SetId;
+		let result =
+			async_std::task::block_on(find_next_set_change(from, from_set_id, until, set_id_at))
+				.unwrap();
+		let reads = *reads.lock().unwrap();
+		(result, reads)
+	}
+
+	#[test]
+	fn finds_next_set_change() {
+		assert_eq!(find(&[100, 200, 300], 0, 1_000).0, Some((100, 1)));
+		assert_eq!(find(&[100, 200, 300], 100, 1_000).0, Some((200, 2)));
+		assert_eq!(find(&[100, 200, 300], 250, 1_000).0, Some((300, 3)));
+		assert_eq!(find(&[100, 200, 300], 299, 300).0, Some((300, 3)));
+	}
+
+	#[test]
+	fn returns_none_when_set_is_not_changed_until_given_block() {
+		assert_eq!(find(&[100, 200, 300], 300, 1_000).0, None);
+		assert_eq!(find(&[100, 200, 300], 100, 199).0, None);
+		assert_eq!(find(&[100, 200, 300], 1_000, 1_000).0, None);
+		assert_eq!(find(&[100, 200, 300], 1_000, 500).0, None);
+	}
+
+	#[test]
+	fn set_change_search_takes_logarithmic_number_of_reads() {
+		let (result, reads) = find(&[999_999], 0, 1_000_000);
+		assert_eq!(result, Some((999_999, 1)));
+		assert!(reads <= 21, "Too many set id reads: {}", reads);
+	}
+}
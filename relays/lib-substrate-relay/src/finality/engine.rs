@@ -21,9 +21,9 @@ use async_trait::async_trait;
 use bp_header_chain::{
 	find_grandpa_authorities_scheduled_change,
 	justification::{verify_justification, GrandpaJustification},
-	FinalityProof,
+	AuthoritySet, FinalityProof, ScheduledAuthoritySetChange,
 };
-use bp_runtime::{BasicOperatingMode, OperatingMode};
+use bp_runtime::{BasicOperatingMode, OperatingMode, SyncedHeadersGrandpaInfo};
 use codec::{Decode, Encode};
 use finality_grandpa::voter_set::VoterSet;
 use num_traits::{One, Zero};
@@ -51,17 +51,34 @@ pub trait Engine<C: Chain>: Send {
 	/// Returns storage key at the bridged (target) chain that corresponds to the variable
 	/// that holds the operating mode of the pallet.
 	fn pallet_operating_mode_key() -> StorageKey;
+	/// Returns storage key at the bridged (target) chain that corresponds to the variable
+	/// that holds the number of the best finalized header at the moment when the pallet has been
+	/// halted.
+	fn pallet_frozen_at_key() -> StorageKey;
 	/// Returns storage at the bridged (target) chain that corresponds to some value that is
 	/// missing from the storage until bridge pallet is initialized.
 	///
 	/// Note that we don't care about type of the value - just if it present or not.
 	fn is_initialized_key() -> StorageKey;
+	/// Returns storage key at the bridged (target) chain that corresponds to the variable
+	/// that holds the minimal gap between the best finalized header and the imported regular
+	/// header.
+	fn min_header_gap_key() -> StorageKey;
 	/// A method to subscribe to encoded finality proofs, given source client.
 	async fn finality_proofs(client: Client<C>) -> Result<Subscription<Bytes>, SubstrateError>;
 	/// Prepare initialization data for the finality bridge pallet.
 	async fn prepare_initialization_data(
 		client: Client<C>,
 	) -> Result<Self::InitializationData, Error<HashOf<C>, BlockNumberOf<C>>>;
+	/// Verify finality proof of given header before submitting it to the bridged (target) chain.
+	///
+	/// The proof is verified using the same code and the same authorities set that the
+	/// finality pallet at the bridged chain is using.
+	async fn verify_finality_proof<TargetChain: Chain>(
+		target_client: &Client<TargetChain>,
+		header: &HeaderOf<C>,
+		proof: &Self::FinalityProof,
+	) -> Result<(), SubstrateError>;
 
 	/// Returns `Ok(true)` if finality pallet at the bridged chain has already been initialized.
 	async fn is_initialized<TargetChain: Chain>(
@@ -83,6 +100,28 @@ pub trait Engine<C: Chain>: Send {
 			.map(|operating_mode| operating_mode.is_halted())
 			.unwrap_or(false))
 	}
+
+	/// Returns number of the best finalized header, known to the finality pallet at the bridged
+	/// chain at the moment when the pallet has been halted.
+	///
+	/// Returns `None` if the pallet is not halted, or if it has been halted before importing any
+	/// headers.
+	async fn frozen_at<TargetChain: Chain>(
+		target_client: &Client<TargetChain>,
+	) -> Result<Option<BlockNumberOf<C>>, SubstrateError> {
+		target_client.storage_value(Self::pallet_frozen_at_key(), None).await
+	}
+
+	/// Returns minimal gap between the best finalized header and the regular header, imported by
+	/// the finality pallet at the bridged chain.
+	async fn min_header_gap<TargetChain: Chain>(
+		target_client: &Client<TargetChain>,
+	) -> Result<BlockNumberOf<C>, SubstrateError> {
+		Ok(target_client
+			.storage_value(Self::min_header_gap_key(), None)
+			.await?
+			.unwrap_or_else(Zero::zero))
+	}
 }
 
 /// GRANDPA finality engine.
@@ -125,14 +164,104 @@ impl<C: ChainWithGrandpa> Engine<C> for Grandpa<C> {
 		bp_header_chain::storage_keys::pallet_operating_mode_key(C::WITH_CHAIN_GRANDPA_PALLET_NAME)
 	}
 
+	fn pallet_frozen_at_key() -> StorageKey {
+		bp_header_chain::storage_keys::frozen_at_key(C::WITH_CHAIN_GRANDPA_PALLET_NAME)
+	}
+
 	fn is_initialized_key() -> StorageKey {
 		bp_header_chain::storage_keys::best_finalized_key(C::WITH_CHAIN_GRANDPA_PALLET_NAME)
 	}
 
+	fn min_header_gap_key() -> StorageKey {
+		bp_header_chain::storage_keys::min_header_gap_key(C::WITH_CHAIN_GRANDPA_PALLET_NAME)
+	}
+
 	async fn finality_proofs(client: Client<C>) -> Result<Subscription<Bytes>, SubstrateError> {
 		client.subscribe_grandpa_justifications().await
 	}
 
+	async fn verify_finality_proof<TargetChain: Chain>(
+		target_client: &Client<TargetChain>,
+		header: &HeaderOf<C>,
+		proof: &Self::FinalityProof,
+	) -> Result<(), SubstrateError> {
+		// first check if the justification may be accepted by the pallet at all - if header is
+		// not better than the best finalized header, there's no point in verifying it
+		let at_block = target_client.best_header().await?.hash();
+		let info: SyncedHeadersGrandpaInfo<HashOf<C>, BlockNumberOf<C>> = target_client
+			.typed_state_call(C::SYNCED_HEADERS_GRANDPA_INFO_METHOD.into(), (), Some(at_block))
+			.await?;
+		let best_finalized =
+			info.best_finalized.ok_or(SubstrateError::UninitializedBridgePallet)?;
+		if *header.number() <= best_finalized.0 {
+			return Err(SubstrateError::Custom(format!(
+				"{} header {:?} is not better than the best finalized header {:?} at {}",
+				C::NAME,
+				header.hash(),
+				best_finalized,
+				TargetChain::NAME,
+			)))
+		}
+
+		// the current authority set of the pallet must match the set, reported by the runtime
+		let authority_set = target_client
+			.storage_value::<AuthoritySet>(
+				bp_header_chain::storage_keys::current_authority_set_key(
+					C::WITH_CHAIN_GRANDPA_PALLET_NAME,
+				),
+				Some(at_block),
+			)
+			.await?
+			.ok_or(SubstrateError::UninitializedBridgePallet)?;
+		if authority_set.set_id != info.set_id ||
+			authority_set.authority_list_hash() != info.authority_list_hash
+		{
+			return Err(SubstrateError::Custom(format!(
+				"{} GRANDPA authorities set {} at {} doesn't match the set {} reported by the runtime",
+				C::NAME,
+				authority_set.set_id,
+				TargetChain::NAME,
+				info.set_id,
+			)))
+		}
+
+		// the justification must be signed by the set that finalizes the header in the pallet
+		let pending_change = target_client
+			.storage_value::<ScheduledAuthoritySetChange<BlockNumberOf<C>>>(
+				bp_header_chain::storage_keys::pending_authority_set_change_key(
+					C::WITH_CHAIN_GRANDPA_PALLET_NAME,
+				),
+				Some(at_block),
+			)
+			.await?;
+		let authority_set =
+			finalizing_authority_set(authority_set, pending_change, *header.number());
+		let voter_set = VoterSet::new(authority_set.authorities.clone()).ok_or_else(|| {
+			SubstrateError::Custom(format!(
+				"Invalid {} GRANDPA authorities set at {}: {:?}",
+				C::NAME,
+				TargetChain::NAME,
+				authority_set.authorities,
+			))
+		})?;
+
+		// this is exactly the same function that is used by the pallet
+		verify_justification::<C::Header>(
+			(header.hash(), *header.number()),
+			authority_set.set_id,
+			&voter_set,
+			proof,
+		)
+		.map_err(|e| {
+			SubstrateError::Custom(format!(
+				"Invalid {} GRANDPA justification of header {:?}: {:?}",
+				C::NAME,
+				header.hash(),
+				e,
+			))
+		})
+	}
+
 	/// Prepare initialization data for the GRANDPA verifier pallet.
 	async fn prepare_initialization_data(
 		source_client: Client<C>,
@@ -246,6 +375,57 @@ impl<C: ChainWithGrandpa> Engine<C> for Grandpa<C> {
 				initial_authorities_set_id
 			},
 			operating_mode: BasicOperatingMode::Normal,
+			checkpoints: Vec::new(),
 		})
 	}
 }
+
+/// Returns authority set that finalizes the header with given number.
+///
+/// This mirrors the selection, made by the GRANDPA pallet: the header that enacts the pending
+/// change is still finalized by the current set and all its descendants are finalized by the
+/// next set.
+fn finalizing_authority_set<Number: Copy + PartialOrd + sp_runtime::traits::Saturating>(
+	current_authority_set: AuthoritySet,
+	pending_change: Option<ScheduledAuthoritySetChange<Number>>,
+	number: Number,
+) -> AuthoritySet {
+	match pending_change {
+		Some(change) if change.is_finalized_by_next_set(number) => change.next_authority_set,
+		_ => current_authority_set,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn pending_change(enacted_at: u64) -> ScheduledAuthoritySetChange<u64> {
+		ScheduledAuthoritySetChange { next_authority_set: AuthoritySet::new(vec![], 2), enacted_at }
+	}
+
+	#[test]
+	fn header_is_verified_using_current_set_if_there_is_no_pending_change() {
+		let current = AuthoritySet::new(vec![], 1);
+		assert_eq!(finalizing_authority_set::<u64>(current.clone(), None, 100), current);
+	}
+
+	#[test]
+	fn header_is_verified_using_current_set_until_pending_change_is_enacted() {
+		let current = AuthoritySet::new(vec![], 1);
+		assert_eq!(finalizing_authority_set(current.clone(), Some(pending_change(10)), 9), current);
+		assert_eq!(
+			finalizing_authority_set(current.clone(), Some(pending_change(10)), 10),
+			current
+		);
+	}
+
+	#[test]
+	fn header_past_enacted_at_is_verified_using_next_set() {
+		let current = AuthoritySet::new(vec![], 1);
+		assert_eq!(
+			finalizing_authority_set(current, Some(pending_change(10)), 11),
+			AuthoritySet::new(vec![], 2),
+		);
+	}
+}
@@ -0,0 +1,344 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Sharing single finality proofs subscription among multiple finality relays.
+//!
+//! Every finality relay is subscribing to justifications of its source chain. If there are several
+//! relays, serving the same source chain, the source node is doing the same work several times. The
+//! [`FinalityProofsFanOut`] owns single subscription and broadcasts decoded proofs to all its
+//! subscribers. Every subscriber has its own buffer, so slow subscriber never stalls other
+//! subscribers - it just misses some proofs (which are not essential for the finality relay).
+
+use crate::finality::{engine::Engine, source::decoded_finality_proofs};
+
+use async_std::sync::{Arc, Mutex};
+use futures::{
+	channel::mpsc,
+	stream::{Stream, StreamExt},
+};
+use relay_substrate_client::{Chain, Client, Error};
+use relay_utils::metrics::{
+	metric_name, register, CounterVec, GaugeVec, Metric, Opts, PrometheusError, Registry, U64,
+};
+use std::{marker::PhantomData, pin::Pin};
+
+/// Default number of finality proofs that are buffered for every subscriber.
+pub const DEFAULT_SUBSCRIBER_BUFFER_SIZE: usize = 16;
+
+/// Stream of finality proofs, returned to the fan-out subscriber.
+pub type FinalityProofsFanOutStream<T> = Pin<Box<dyn Stream<Item = T> + Send>>;
+
+/// Finality proofs fan-out metrics.
+#[derive(Debug, Clone)]
+pub struct FinalityProofsFanOutMetrics {
+	/// Number of proofs that have been broadcasted, but not yet read by the subscriber.
+	buffered_proofs: GaugeVec<U64>,
+	/// Number of proofs that have been dropped because the subscriber buffer was full.
+	dropped_proofs: CounterVec<U64>,
+}
+
+impl FinalityProofsFanOutMetrics {
+	/// Create new metrics.
+	pub fn new() -> Result<Self, PrometheusError> {
+		Ok(FinalityProofsFanOutMetrics {
+			buffered_proofs: GaugeVec::new(
+				Opts::new(
+					metric_name(None, "finality_proofs_fan_out_buffered"),
+					"Number of finality proofs, buffered for the subscriber",
+				),
+				&["chain", "subscriber"],
+			)?,
+			dropped_proofs: CounterVec::new(
+				Opts::new(
+					metric_name(None, "finality_proofs_fan_out_dropped"),
+					"Number of finality proofs, dropped because the subscriber is lagging behind",
+				),
+				&["chain", "subscriber"],
+			)?,
+		})
+	}
+}
+
+impl Metric for FinalityProofsFanOutMetrics {
+	fn register(&self, registry: &Registry) -> Result<(), PrometheusError> {
+		// the same metrics may be shared by fan-outs of several source chains
+		match register(self.buffered_proofs.clone(), registry) {
+			Ok(_) | Err(PrometheusError::AlreadyReg) => (),
+			Err(e) => return Err(e),
+		}
+		match register(self.dropped_proofs.clone(), registry) {
+			Ok(_) | Err(PrometheusError::AlreadyReg) => Ok(()),
+			Err(e) => Err(e),
+		}
+	}
+}
+
+/// Single finality proofs subscription, shared by multiple finality relays.
+///
+/// Cloning `FinalityProofsFanOut` is a cheap operation and all clones are sharing the same
+/// subscription.
+pub struct FinalityProofsFanOut<C: Chain, E: Engine<C>> {
+	broadcast: Arc<Mutex<Broadcast<E::FinalityProof>>>,
+	_phantom: PhantomData<fn() -> (C, E)>,
+}
+
+impl<C: Chain, E: Engine<C>> Clone for FinalityProofsFanOut<C, E> {
+	fn clone(&self) -> Self {
+		FinalityProofsFanOut { broadcast: self.broadcast.clone(), _phantom: Default::default() }
+	}
+}
+
+impl<C: Chain, E: Engine<C> + 'static> FinalityProofsFanOut<C, E> {
+	/// Create new fan-out.
+	///
+	/// The subscription is started when the first subscriber is registered.
+	pub fn new(
+		subscriber_buffer_size: usize,
+		metrics: Option<FinalityProofsFanOutMetrics>,
+	) -> Self {
+		FinalityProofsFanOut {
+			broadcast: Arc::new(Mutex::new(Broadcast::new(
+				C::NAME,
+				subscriber_buffer_size,
+				metrics,
+			))),
+			_phantom: Default::default(),
+		}
+	}
+
+	/// Register new subscriber and return stream of finality proofs for it.
+	///
+	/// If the source subscription is not active (it has never been started or source stream has
+	/// ended), it is (re)started using given client. Subscribers are reconnecting their clients
+	/// when the source stream ends, so the fresh connection is used here.
+	pub async fn subscribe(
+		&self,
+		client: &Client<C>,
+		subscriber: &str,
+	) -> Result<FinalityProofsFanOutStream<E::FinalityProof>, Error> {
+		let mut broadcast = self.broadcast.lock().await;
+		if !broadcast.is_active {
+			let source = decoded_finality_proofs::<C, E>(client.clone()).await?;
+			broadcast.is_active = true;
+			async_std::task::spawn(run_broadcast(source, self.broadcast.clone()));
+		}
+
+		Ok(broadcast.subscribe(subscriber))
+	}
+}
+
+/// Finality proofs broadcast state.
+struct Broadcast<T> {
+	/// Name of the source chain.
+	chain: &'static str,
+	/// Maximal number of buffered proofs of every subscriber.
+	subscriber_buffer_size: usize,
+	/// Registered subscribers.
+	subscribers: Vec<Subscriber<T>>,
+	/// True if the broadcast task is running.
+	is_active: bool,
+	/// Fan-out metrics.
+	metrics: Option<FinalityProofsFanOutMetrics>,
+}
+
+/// Broadcast subscriber.
+struct Subscriber<T> {
+	/// Subscriber name (used in logs and metrics).
+	name: String,
+	/// Sending end of the subscriber buffer.
+	sender: mpsc::Sender<T>,
+}
+
+impl<T: Clone + Send + 'static> Broadcast<T> {
+	/// Create new broadcast state.
+	fn new(
+		chain: &'static str,
+		subscriber_buffer_size: usize,
+		metrics: Option<FinalityProofsFanOutMetrics>,
+	) -> Self {
+		Broadcast {
+			chain,
+			subscriber_buffer_size,
+			subscribers: Vec::new(),
+			is_active: false,
+			metrics,
+		}
+	}
+
+	/// Register new subscriber.
+	fn subscribe(&mut self, subscriber: &str) -> FinalityProofsFanOutStream<T> {
+		// `mpsc::channel` reserves one additional slot for every sender
+		let (sender, receiver) = mpsc::channel(self.subscriber_buffer_size.saturating_sub(1));
+		self.subscribers.push(Subscriber { name: subscriber.into(), sender });
+
+		match self.metrics {
+			Some(ref metrics) => {
+				let buffered_proofs =
+					metrics.buffered_proofs.with_label_values(&[self.chain, subscriber]);
+				buffered_proofs.set(0);
+				receiver.inspect(move |_| buffered_proofs.dec()).boxed()
+			},
+			None => receiver.boxed(),
+		}
+	}
+
+	/// Send proof to all subscribers.
+	///
+	/// If subscriber buffer is full, the proof is dropped for this subscriber. If subscriber has
+	/// dropped its stream, it is unregistered.
+	fn broadcast(&mut self, proof: T) {
+		let chain = self.chain;
+		let metrics = self.metrics.as_ref();
+		self.subscribers
+			.retain_mut(|subscriber| match subscriber.sender.try_send(proof.clone()) {
+				Ok(()) => {
+					if let Some(metrics) = metrics {
+						metrics.buffered_proofs.with_label_values(&[chain, &subscriber.name]).inc();
+					}
+					true
+				},
+				Err(e) if e.is_full() => {
+					log::trace!(
+						target: "bridge",
+						"{} finality proofs subscriber {} is lagging behind. Dropping proof",
+						chain,
+						subscriber.name,
+					);
+					if let Some(metrics) = metrics {
+						metrics.dropped_proofs.with_label_values(&[chain, &subscriber.name]).inc();
+					}
+					true
+				},
+				Err(_) => {
+					log::trace!(
+						target: "bridge",
+						"{} finality proofs subscriber {} has been dropped",
+						chain,
+						subscriber.name,
+					);
+					false
+				},
+			});
+	}
+
+	/// Unregister all subscribers after source stream has ended.
+	///
+	/// Subscriber streams are ended too, so they are able to subscribe again.
+	fn finish(&mut self) {
+		self.subscribers.clear();
+		self.is_active = false;
+	}
+}
+
+/// Read proofs from the source stream and broadcast them to all subscribers.
+async fn run_broadcast<T: Clone + Send + 'static>(
+	source: impl Stream<Item = T> + Send,
+	broadcast: Arc<Mutex<Broadcast<T>>>,
+) {
+	futures::pin_mut!(source);
+	while let Some(proof) = source.next().await {
+		broadcast.lock().await.broadcast(proof);
+	}
+
+	let mut broadcast = broadcast.lock().await;
+	log::warn!(
+		target: "bridge",
+		"{} finality proofs stream has ended. Unregistering {} subscribers",
+		broadcast.chain,
+		broadcast.subscribers.len(),
+	);
+	broadcast.finish();
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use futures::FutureExt;
+
+	const CHAIN: &str = "TestChain";
+
+	fn test_broadcast(subscriber_buffer_size: usize) -> Broadcast<u32> {
+		Broadcast::new(
+			CHAIN,
+			subscriber_buffer_size,
+			Some(FinalityProofsFanOutMetrics::new().unwrap()),
+		)
+	}
+
+	fn dropped_proofs(broadcast: &Broadcast<u32>, subscriber: &str) -> u64 {
+		broadcast
+			.metrics
+			.as_ref()
+			.unwrap()
+			.dropped_proofs
+			.with_label_values(&[CHAIN, subscriber])
+			.get()
+	}
+
+	#[test]
+	fn slow_subscriber_does_not_stall_other_subscribers() {
+		let mut broadcast = test_broadcast(2);
+		let mut fast = broadcast.subscribe("fast");
+		let mut slow = broadcast.subscribe("slow");
+
+		for proof in 1..=10 {
+			broadcast.broadcast(proof);
+			assert_eq!(fast.next().now_or_never(), Some(Some(proof)));
+		}
+
+		assert_eq!(dropped_proofs(&broadcast, "fast"), 0);
+		assert_eq!(dropped_proofs(&broadcast, "slow"), 8);
+		assert_eq!(slow.next().now_or_never(), Some(Some(1)));
+		assert_eq!(slow.next().now_or_never(), Some(Some(2)));
+		assert_eq!(slow.next().now_or_never(), None);
+	}
+
+	#[test]
+	fn dropped_subscriber_is_unregistered() {
+		let mut broadcast = test_broadcast(2);
+		let mut alive = broadcast.subscribe("alive");
+		let dropped = broadcast.subscribe("dropped");
+		drop(dropped);
+
+		broadcast.broadcast(1);
+		assert_eq!(broadcast.subscribers.len(), 1);
+		assert_eq!(alive.next().now_or_never(), Some(Some(1)));
+	}
+
+	#[test]
+	fn metrics_may_be_registered_twice() {
+		let registry = Registry::new();
+		let metrics = FinalityProofsFanOutMetrics::new().unwrap();
+		assert!(metrics.register(&registry).is_ok());
+		assert!(FinalityProofsFanOutMetrics::new().unwrap().register(&registry).is_ok());
+	}
+
+	#[async_std::test]
+	fn subscriber_streams_are_ended_with_the_source_stream() {
+		let broadcast = Arc::new(Mutex::new(test_broadcast(16)));
+		let first = broadcast.lock().await.subscribe("first");
+		let second = broadcast.lock().await.subscribe("second");
+		broadcast.lock().await.is_active = true;
+
+		run_broadcast(futures::stream::iter(vec![1, 2, 3]), broadcast.clone()).await;
+
+		assert_eq!(first.collect::<Vec<_>>().await, vec![1, 2, 3]);
+		assert_eq!(second.collect::<Vec<_>>().await, vec![1, 2, 3]);
+		let broadcast = broadcast.lock().await;
+		assert!(broadcast.subscribers.is_empty());
+		assert!(!broadcast.is_active);
+	}
+}
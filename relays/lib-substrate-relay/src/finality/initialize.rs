@@ -29,6 +29,9 @@ use relay_substrate_client::{
 use sp_runtime::traits::Header as HeaderT;
 
 /// Submit headers-bridge initialization transaction.
+///
+/// If `reinitialize` is true, the transaction is only submitted if the bridge is already
+/// initialized. Otherwise it is only submitted if the bridge is not yet initialized.
 pub async fn initialize<
 	E: Engine<SourceChain>,
 	SourceChain: Chain,
@@ -39,6 +42,7 @@ pub async fn initialize<
 	target_client: Client<TargetChain>,
 	target_transactions_signer: TargetChain::AccountId,
 	target_signing_data: SignParam<TargetChain>,
+	reinitialize: bool,
 	prepare_initialize_transaction: F,
 ) where
 	F: FnOnce(
@@ -53,6 +57,7 @@ pub async fn initialize<
 		target_client,
 		target_transactions_signer,
 		target_signing_data,
+		reinitialize,
 		prepare_initialize_transaction,
 	)
 	.await;
@@ -87,6 +92,7 @@ async fn do_initialize<
 	target_client: Client<TargetChain>,
 	target_transactions_signer: TargetChain::AccountId,
 	target_signing_data: SignParam<TargetChain>,
+	reinitialize: bool,
 	prepare_initialize_transaction: F,
 ) -> Result<
 	Option<TargetChain::Hash>,
@@ -103,7 +109,7 @@ where
 	let is_initialized = E::is_initialized(&target_client)
 		.await
 		.map_err(|e| Error::IsInitializedRetrieve(SourceChain::NAME, TargetChain::NAME, e))?;
-	if is_initialized {
+	if is_initialized && !reinitialize {
 		log::info!(
 			target: "bridge",
 			"{}-headers bridge at {} is already initialized. Skipping",
@@ -112,6 +118,15 @@ where
 		);
 		return Ok(None)
 	}
+	if !is_initialized && reinitialize {
+		log::info!(
+			target: "bridge",
+			"{}-headers bridge at {} is not yet initialized. Nothing to re-initialize",
+			SourceChain::NAME,
+			TargetChain::NAME,
+		);
+		return Ok(None)
+	}
 
 	let initialization_data = E::prepare_initialization_data(source_client).await?;
 	log::info!(
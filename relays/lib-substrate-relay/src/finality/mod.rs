@@ -31,14 +31,20 @@ use bp_header_chain::justification::GrandpaJustification;
 use finality_relay::FinalitySyncPipeline;
 use pallet_bridge_grandpa::{Call as BridgeGrandpaCall, Config as BridgeGrandpaConfig};
 use relay_substrate_client::{
+	metrics::{
+		proof_size_warning_threshold as default_proof_size_warning_threshold,
+		ProofGenerationMetrics, ProofType,
+	},
 	transaction_stall_timeout, AccountIdOf, AccountKeyPairOf, BlockNumberOf, CallOf, Chain, Client,
 	HashOf, HeaderOf, SyncHeader, TransactionSignScheme,
 };
-use relay_utils::metrics::MetricsParams;
+use relay_utils::metrics::{Metric, MetricsParams};
 use sp_core::Pair;
-use std::{fmt::Debug, marker::PhantomData};
+use std::{fmt::Debug, marker::PhantomData, time::Duration};
 
+pub mod catch_up;
 pub mod engine;
+pub mod fan_out;
 pub mod guards;
 pub mod initialize;
 pub mod source;
@@ -50,6 +56,14 @@ pub mod target;
 /// Substrate+GRANDPA based chains (good to know).
 pub(crate) const RECENT_FINALITY_PROOFS_LIMIT: usize = 4096;
 
+/// Default number of source blocks that may be built on top of the best finalized source block
+/// before we start suspecting that the source chain finality is stalled.
+pub(crate) const SOURCE_FINALITY_STALL_BLOCKS: u32 = 64;
+
+/// Default duration of the large source finality lag, after which we believe that the source
+/// chain finality is stalled.
+pub(crate) const SOURCE_FINALITY_STALL_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
 /// Substrate -> Substrate finality proofs synchronization pipeline.
 #[async_trait]
 pub trait SubstrateFinalitySyncPipeline: 'static + Clone + Debug + Send + Sync {
@@ -99,6 +113,12 @@ pub trait SubmitFinalityProofCallBuilder<P: SubstrateFinalitySyncPipeline> {
 		header: SyncHeader<HeaderOf<P::SourceChain>>,
 		proof: SubstrateFinalityProof<P>,
 	) -> CallOf<P::TargetChain>;
+
+	/// Given source chain headers and their finality proofs, build call of
+	/// `submit_finality_proof_batch` function of bridge GRANDPA module at the target chain.
+	fn build_submit_finality_proof_batch_call(
+		proofs: Vec<(SyncHeader<HeaderOf<P::SourceChain>>, SubstrateFinalityProof<P>)>,
+	) -> CallOf<P::TargetChain>;
 }
 
 /// Building `submit_finality_proof` call when you have direct access to the target
@@ -128,16 +148,38 @@ where
 		}
 		.into()
 	}
+
+	fn build_submit_finality_proof_batch_call(
+		proofs: Vec<(
+			SyncHeader<HeaderOf<P::SourceChain>>,
+			GrandpaJustification<HeaderOf<P::SourceChain>>,
+		)>,
+	) -> CallOf<P::TargetChain> {
+		BridgeGrandpaCall::<R, I>::submit_finality_proof_batch {
+			finality_proofs: proofs
+				.into_iter()
+				.map(|(header, proof)| (Box::new(header.into_inner()), proof))
+				.collect(),
+		}
+		.into()
+	}
 }
 
 /// Macro that generates `SubmitFinalityProofCallBuilder` implementation for the case when
 /// you only have an access to the mocked version of target chain runtime. In this case you
-/// should provide "name" of the call variant for the bridge GRANDPA calls and the "name" of
-/// the variant for the `submit_finality_proof` call within that first option.
+/// should provide "name" of the call variant for the bridge GRANDPA calls and the "names" of
+/// the variants for the `submit_finality_proof` and `submit_finality_proof_batch` calls within
+/// that first option.
 #[rustfmt::skip]
 #[macro_export]
 macro_rules! generate_mocked_submit_finality_proof_call_builder {
-	($pipeline:ident, $mocked_builder:ident, $bridge_grandpa:path, $submit_finality_proof:path) => {
+	(
+		$pipeline:ident,
+		$mocked_builder:ident,
+		$bridge_grandpa:path,
+		$submit_finality_proof:path,
+		$submit_finality_proof_batch:path
+	) => {
 		pub struct $mocked_builder;
 
 		impl $crate::finality::SubmitFinalityProofCallBuilder<$pipeline>
@@ -159,6 +201,30 @@ macro_rules! generate_mocked_submit_finality_proof_call_builder {
 			> {
 				$bridge_grandpa($submit_finality_proof(Box::new(header.into_inner()), proof))
 			}
+
+			fn build_submit_finality_proof_batch_call(
+				proofs: Vec<(
+					relay_substrate_client::SyncHeader<
+						relay_substrate_client::HeaderOf<
+							<$pipeline as $crate::finality::SubstrateFinalitySyncPipeline>::SourceChain
+						>
+					>,
+					bp_header_chain::justification::GrandpaJustification<
+						relay_substrate_client::HeaderOf<
+							<$pipeline as $crate::finality::SubstrateFinalitySyncPipeline>::SourceChain
+						>
+					>,
+				)>,
+			) -> relay_substrate_client::CallOf<
+				<$pipeline as $crate::finality::SubstrateFinalitySyncPipeline>::TargetChain
+			> {
+				$bridge_grandpa($submit_finality_proof_batch(
+					proofs
+						.into_iter()
+						.map(|(header, proof)| (Box::new(header.into_inner()), proof))
+						.collect()
+				))
+			}
 		}
 	};
 }
@@ -168,8 +234,10 @@ pub async fn run<P: SubstrateFinalitySyncPipeline>(
 	source_client: Client<P::SourceChain>,
 	target_client: Client<P::TargetChain>,
 	only_mandatory_headers: bool,
+	max_headers_in_batch: usize,
 	transaction_params: TransactionParams<AccountKeyPairOf<P::TransactionSignScheme>>,
 	metrics_params: MetricsParams,
+	proof_size_warning_threshold: Option<usize>,
 ) -> anyhow::Result<()>
 where
 	AccountIdOf<P::TargetChain>: From<<AccountKeyPairOf<P::TransactionSignScheme> as Pair>::Public>,
@@ -182,8 +250,18 @@ where
 		P::TargetChain::NAME,
 	);
 
+	let proof_metrics = ProofGenerationMetrics::new()?;
+	proof_metrics.register(&metrics_params.registry)?;
+
 	finality_relay::run(
-		SubstrateFinalitySource::<P>::new(source_client, None),
+		SubstrateFinalitySource::<P>::new(source_client, None).with_proof_metrics(
+			proof_metrics
+				.proof_metrics::<P::SourceChain>(ProofType::Finality)
+				.with_size_warning_threshold(
+					proof_size_warning_threshold
+						.unwrap_or_else(default_proof_size_warning_threshold::<P::TargetChain>),
+				),
+		),
 		SubstrateFinalityTarget::<P>::new(target_client, transaction_params.clone()),
 		finality_relay::FinalitySyncParams {
 			tick: std::cmp::max(
@@ -197,6 +275,9 @@ where
 				relay_utils::STALL_TIMEOUT,
 			),
 			only_mandatory_headers,
+			max_headers_in_batch,
+			source_finality_stall_blocks: SOURCE_FINALITY_STALL_BLOCKS,
+			source_finality_stall_timeout: SOURCE_FINALITY_STALL_TIMEOUT,
 		},
 		metrics_params,
 		futures::future::pending(),
@@ -16,7 +16,10 @@
 
 //! Default generic implementation of finality source for basic Substrate client.
 
-use crate::finality::{engine::Engine, FinalitySyncPipelineAdapter, SubstrateFinalitySyncPipeline};
+use crate::finality::{
+	engine::Engine, fan_out::FinalityProofsFanOut, FinalitySyncPipelineAdapter,
+	SubstrateFinalitySyncPipeline,
+};
 
 use async_std::sync::{Arc, Mutex};
 use async_trait::async_trait;
@@ -24,10 +27,11 @@ use codec::Decode;
 use finality_relay::SourceClient;
 use futures::stream::{unfold, Stream, StreamExt};
 use relay_substrate_client::{
-	BlockNumberOf, BlockWithJustification, Chain, Client, Error, HeaderOf,
+	metrics::ProofMetrics, BlockNumberOf, BlockWithJustification, Chain, Client, Error, HeaderOf,
 };
 use relay_utils::relay_loop::Client as RelayClient;
-use std::pin::Pin;
+use sp_runtime::traits::Header as HeaderT;
+use std::{pin::Pin, time::Instant};
 
 /// Shared updatable reference to the maximal header number that we want to sync from the source.
 pub type RequiredHeaderNumberRef<C> = Arc<Mutex<<C as bp_runtime::Chain>::BlockNumber>>;
@@ -53,6 +57,8 @@ pub type SubstrateFinalityProof<P> =
 pub struct SubstrateFinalitySource<P: SubstrateFinalitySyncPipeline> {
 	client: Client<P::SourceChain>,
 	maximal_header_number: Option<RequiredHeaderNumberRef<P::SourceChain>>,
+	proof_metrics: Option<ProofMetrics>,
+	finality_proofs_fan_out: Option<FinalityProofsFanOut<P::SourceChain, P::FinalityEngine>>,
 }
 
 impl<P: SubstrateFinalitySyncPipeline> SubstrateFinalitySource<P> {
@@ -61,7 +67,30 @@ impl<P: SubstrateFinalitySyncPipeline> SubstrateFinalitySource<P> {
 		client: Client<P::SourceChain>,
 		maximal_header_number: Option<RequiredHeaderNumberRef<P::SourceChain>>,
 	) -> Self {
-		SubstrateFinalitySource { client, maximal_header_number }
+		SubstrateFinalitySource {
+			client,
+			maximal_header_number,
+			proof_metrics: None,
+			finality_proofs_fan_out: None,
+		}
+	}
+
+	/// Observe finality proofs, read by the `header_and_finality_proof`, using given metrics.
+	#[must_use]
+	pub fn with_proof_metrics(mut self, proof_metrics: ProofMetrics) -> Self {
+		self.proof_metrics = Some(proof_metrics);
+		self
+	}
+
+	/// Read finality proofs from the shared subscription instead of subscribing to source chain
+	/// justifications directly.
+	#[must_use]
+	pub fn with_finality_proofs_fan_out(
+		mut self,
+		fan_out: FinalityProofsFanOut<P::SourceChain, P::FinalityEngine>,
+	) -> Self {
+		self.finality_proofs_fan_out = Some(fan_out);
+		self
 	}
 
 	/// Returns reference to the underlying RPC client.
@@ -84,6 +113,8 @@ impl<P: SubstrateFinalitySyncPipeline> Clone for SubstrateFinalitySource<P> {
 		SubstrateFinalitySource {
 			client: self.client.clone(),
 			maximal_header_number: self.maximal_header_number.clone(),
+			proof_metrics: self.proof_metrics.clone(),
+			finality_proofs_fan_out: self.finality_proofs_fan_out.clone(),
 		}
 	}
 }
@@ -116,6 +147,14 @@ impl<P: SubstrateFinalitySyncPipeline> SourceClient<FinalitySyncPipelineAdapter<
 		Ok(finalized_header_number)
 	}
 
+	async fn best_block_numbers(
+		&self,
+	) -> Result<(BlockNumberOf<P::SourceChain>, BlockNumberOf<P::SourceChain>), Error> {
+		let best_header = self.client.best_header().await?;
+		let best_finalized_header_number = self.on_chain_best_finalized_block_number().await?;
+		Ok((*best_header.number(), best_finalized_header_number))
+	}
+
 	async fn header_and_finality_proof(
 		&self,
 		number: BlockNumberOf<P::SourceChain>,
@@ -126,8 +165,14 @@ impl<P: SubstrateFinalitySyncPipeline> SourceClient<FinalitySyncPipelineAdapter<
 		),
 		Error,
 	> {
+		let proof_started_at = Instant::now();
 		let header_hash = self.client.block_hash_by_number(number).await?;
 		let signed_block = self.client.get_block(Some(header_hash)).await?;
+		if let (Some(proof_metrics), Some(raw_justification)) =
+			(self.proof_metrics.as_ref(), signed_block.justification())
+		{
+			proof_metrics.observe(proof_started_at.elapsed(), raw_justification.len());
+		}
 
 		let justification = signed_block
 			.justification()
@@ -141,42 +186,47 @@ impl<P: SubstrateFinalitySyncPipeline> SourceClient<FinalitySyncPipelineAdapter<
 	}
 
 	async fn finality_proofs(&self) -> Result<Self::FinalityProofsStream, Error> {
-		Ok(unfold(
-			P::FinalityEngine::finality_proofs(self.client.clone()).await?,
-			move |subscription| async move {
-				loop {
-					let log_error = |err| {
-						log::error!(
-							target: "bridge",
-							"Failed to read justification target from the {} justifications stream: {:?}",
-							P::SourceChain::NAME,
-							err,
-						);
-					};
-
-					let next_justification = subscription
-						.next()
-						.await
-						.map_err(|err| log_error(err.to_string()))
-						.ok()??;
-
-					let decoded_justification =
-						<P::FinalityEngine as Engine<P::SourceChain>>::FinalityProof::decode(
-							&mut &next_justification[..],
-						);
-
-					let justification = match decoded_justification {
-						Ok(j) => j,
-						Err(err) => {
-							log_error(format!("decode failed with error {:?}", err));
-							continue
-						},
-					};
-
-					return Some((justification, subscription))
-				}
-			},
-		)
-		.boxed())
+		match self.finality_proofs_fan_out {
+			Some(ref fan_out) => fan_out.subscribe(&self.client, P::TargetChain::NAME).await,
+			None =>
+				decoded_finality_proofs::<P::SourceChain, P::FinalityEngine>(self.client.clone())
+					.await,
+		}
 	}
 }
+
+/// Subscribe to finality proofs of given chain and decode them.
+///
+/// Proofs that can't be decoded are logged and skipped.
+pub(crate) async fn decoded_finality_proofs<C: Chain, E: Engine<C> + 'static>(
+	client: Client<C>,
+) -> Result<Pin<Box<dyn Stream<Item = E::FinalityProof> + Send>>, Error> {
+	Ok(unfold(E::finality_proofs(client).await?, move |subscription| async move {
+		loop {
+			let log_error = |err| {
+				log::error!(
+					target: "bridge",
+					"Failed to read justification target from the {} justifications stream: {:?}",
+					C::NAME,
+					err,
+				);
+			};
+
+			let next_justification =
+				subscription.next().await.map_err(|err| log_error(err.to_string())).ok()??;
+
+			let decoded_justification = E::FinalityProof::decode(&mut &next_justification[..]);
+
+			let justification = match decoded_justification {
+				Ok(j) => j,
+				Err(err) => {
+					log_error(format!("decode failed with error {:?}", err));
+					continue
+				},
+			};
+
+			return Some((justification, subscription))
+		}
+	})
+	.boxed())
+}
@@ -27,8 +27,9 @@ use crate::{
 use async_trait::async_trait;
 use finality_relay::TargetClient;
 use relay_substrate_client::{
-	AccountIdOf, AccountKeyPairOf, Chain, Client, Error, HeaderIdOf, HeaderOf, SignParam,
-	SyncHeader, TransactionEra, TransactionSignScheme, TransactionTracker, UnsignedTransaction,
+	AccountIdOf, AccountKeyPairOf, BlockNumberOf, CallOf, Chain, Client, Error, HeaderIdOf,
+	HeaderOf, SignParam, SyncHeader, TransactionEra, TransactionSignScheme, TransactionTracker,
+	UnsignedTransaction,
 };
 use relay_utils::relay_loop::Client as RelayClient;
 use sp_core::Pair;
@@ -52,6 +53,14 @@ impl<P: SubstrateFinalitySyncPipeline> SubstrateFinalityTarget<P> {
 	pub async fn ensure_pallet_active(&self) -> Result<(), Error> {
 		let is_halted = P::FinalityEngine::is_halted(&self.client).await?;
 		if is_halted {
+			let frozen_at = P::FinalityEngine::frozen_at(&self.client).await?;
+			log::info!(
+				target: "bridge",
+				"{} finality pallet at {} is halted. Best finalized header at halt time: {:?}",
+				P::SourceChain::NAME,
+				P::TargetChain::NAME,
+				frozen_at,
+			);
 			return Err(Error::BridgePalletIsHalted)
 		}
 
@@ -62,6 +71,60 @@ impl<P: SubstrateFinalitySyncPipeline> SubstrateFinalityTarget<P> {
 
 		Ok(())
 	}
+
+	/// Submit finality proof without verifying it against the current state of the bridge
+	/// pallet.
+	///
+	/// The caller is responsible for verifying the proof - e.g. when several headers, changing
+	/// authorities set, are submitted at once, the proof of the next header may only be verified
+	/// using the set, enacted by the previous header.
+	pub(crate) async fn submit_finality_proof_unchecked(
+		&self,
+		header: SyncHeader<HeaderOf<P::SourceChain>>,
+		proof: SubstrateFinalityProof<P>,
+	) -> Result<TransactionTracker<P::TargetChain, Client<P::TargetChain>>, Error>
+	where
+		AccountIdOf<P::TargetChain>:
+			From<<AccountKeyPairOf<P::TransactionSignScheme> as Pair>::Public>,
+		P::TransactionSignScheme: TransactionSignScheme<Chain = P::TargetChain>,
+	{
+		self.submit_call(P::SubmitFinalityProofCallBuilder::build_submit_finality_proof_call(
+			header, proof,
+		))
+		.await
+	}
+
+	/// Sign and submit given finality call to the target chain.
+	async fn submit_call(
+		&self,
+		call: CallOf<P::TargetChain>,
+	) -> Result<TransactionTracker<P::TargetChain, Client<P::TargetChain>>, Error>
+	where
+		AccountIdOf<P::TargetChain>:
+			From<<AccountKeyPairOf<P::TransactionSignScheme> as Pair>::Public>,
+		P::TransactionSignScheme: TransactionSignScheme<Chain = P::TargetChain>,
+	{
+		let genesis_hash = *self.client.genesis_hash();
+		let transaction_params = self.transaction_params.clone();
+		let call = transaction_params.prepare_call::<P::TargetChain>(call)?;
+		let (spec_version, transaction_version) = self.client.simple_runtime_version().await?;
+		self.client
+			.submit_and_watch_signed_extrinsic(
+				self.transaction_params.signer.public().into(),
+				SignParam::<P::TransactionSignScheme> {
+					spec_version,
+					transaction_version,
+					genesis_hash,
+					signer: transaction_params.signer.clone(),
+				},
+				move |best_block_id, transaction_nonce| {
+					Ok(UnsignedTransaction::new(call.into(), transaction_nonce).era(
+						TransactionEra::new_checked(best_block_id, transaction_params.mortality)?,
+					))
+				},
+			)
+			.await
+	}
 }
 
 impl<P: SubstrateFinalitySyncPipeline> Clone for SubstrateFinalityTarget<P> {
@@ -112,25 +175,28 @@ where
 		header: SyncHeader<HeaderOf<P::SourceChain>>,
 		proof: SubstrateFinalityProof<P>,
 	) -> Result<Self::TransactionTracker, Error> {
-		let genesis_hash = *self.client.genesis_hash();
-		let transaction_params = self.transaction_params.clone();
-		let call =
-			P::SubmitFinalityProofCallBuilder::build_submit_finality_proof_call(header, proof);
-		let (spec_version, transaction_version) = self.client.simple_runtime_version().await?;
-		self.client
-			.submit_and_watch_signed_extrinsic(
-				self.transaction_params.signer.public().into(),
-				SignParam::<P::TransactionSignScheme> {
-					spec_version,
-					transaction_version,
-					genesis_hash,
-					signer: transaction_params.signer.clone(),
-				},
-				move |best_block_id, transaction_nonce| {
-					Ok(UnsignedTransaction::new(call.into(), transaction_nonce)
-						.era(TransactionEra::new(best_block_id, transaction_params.mortality)))
-				},
-			)
-			.await
+		// verify proof before submitting it, so that we never pay for invalid transaction
+		P::FinalityEngine::verify_finality_proof(&self.client, &header, &proof).await?;
+		self.submit_finality_proof_unchecked(header, proof).await
+	}
+
+	async fn min_header_gap(&self) -> Result<BlockNumberOf<P::SourceChain>, Error> {
+		P::FinalityEngine::min_header_gap(&self.client).await
+	}
+
+	async fn submit_finality_proofs_batch(
+		&self,
+		proofs: Vec<(SyncHeader<HeaderOf<P::SourceChain>>, SubstrateFinalityProof<P>)>,
+	) -> Result<Self::TransactionTracker, Error> {
+		// we may only verify the first proof here, because proofs of next headers may be signed
+		// by authorities that are enacted by previous headers of the batch. The pallet stops
+		// importing the batch at the first invalid proof, so we only pay for proofs up to it
+		if let Some((header, proof)) = proofs.first() {
+			P::FinalityEngine::verify_finality_proof(&self.client, header, proof).await?;
+		}
+		self.submit_call(P::SubmitFinalityProofCallBuilder::build_submit_finality_proof_batch_call(
+			proofs,
+		))
+		.await
 	}
 }
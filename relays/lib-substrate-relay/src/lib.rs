@@ -18,6 +18,7 @@
 
 #![warn(missing_docs)]
 
+pub mod bridge_heartbeat;
 pub mod conversion_rate_update;
 pub mod error;
 pub mod finality;
@@ -29,13 +30,40 @@ pub mod messages_target;
 pub mod on_demand;
 pub mod parachains;
 
+use relay_substrate_client::{AccountIdOf, CallOf, Chain, Error as SubstrateError};
+use sp_core::{crypto::Ss58Codec, Pair};
+
 /// Transaction creation parameters.
 #[derive(Clone, Debug)]
-pub struct TransactionParams<TS> {
+pub struct TransactionParams<TS: Pair> {
 	/// Transactions author.
 	pub signer: TS,
 	/// Transactions mortality.
 	pub mortality: Option<u32>,
+	/// Account on whose behalf transactions are submitted.
+	///
+	/// If specified, every call is wrapped into the `pallet_proxy::Call::proxy` call, so the
+	/// `signer` must be registered as a proxy of this account at the target chain.
+	pub real_account: Option<TS::Public>,
+}
+
+impl<TS: Pair> TransactionParams<TS> {
+	/// Prepare call for submission, wrapping it into the proxy call if required.
+	pub fn prepare_call<C: Chain>(&self, call: CallOf<C>) -> Result<CallOf<C>, SubstrateError>
+	where
+		AccountIdOf<C>: From<TS::Public>,
+	{
+		match self.real_account {
+			Some(ref real_account) =>
+				C::proxy_call(real_account.clone().into(), call).ok_or_else(|| {
+					SubstrateError::Custom(format!(
+						"Unable to submit transaction on behalf of other account: {} has no proxy pallet",
+						C::NAME,
+					))
+				}),
+			None => Ok(call),
+		}
+	}
 }
 
 /// Tagged relay account, which balance may be exposed as metrics by the relay.
@@ -82,6 +110,14 @@ impl<AccountId> TaggedAccount<AccountId> {
 		}
 	}
 
+	/// Returns SS58 address of the account, formatted using prefix of the chain `C`.
+	pub fn address<C: Chain<AccountId = AccountId>>(&self) -> String
+	where
+		AccountId: Ss58Codec,
+	{
+		self.id().to_ss58check_with_version(C::SS58_PREFIX.into())
+	}
+
 	/// Returns stringified account tag.
 	pub fn tag(&self) -> String {
 		match *self {
@@ -98,3 +134,74 @@ impl<AccountId> TaggedAccount<AccountId> {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use relay_pass3dt_client::Pass3dt;
+	use relay_rialto_client::Rialto;
+
+	fn transaction_params(
+		real_account: Option<sp_core::sr25519::Public>,
+	) -> TransactionParams<sp_core::sr25519::Pair> {
+		TransactionParams {
+			signer: sp_core::sr25519::Pair::from_string("//Alice", None).unwrap(),
+			mortality: None,
+			real_account,
+		}
+	}
+
+	fn real_account() -> sp_core::sr25519::Public {
+		sp_core::sr25519::Pair::from_string("//Bob", None).unwrap().public()
+	}
+
+	fn pass3dt_remark_call() -> pass3dt_runtime::Call {
+		pass3dt_runtime::Call::System(pass3dt_runtime::SystemCall::remark {
+			remark: b"Hello world!".to_vec(),
+		})
+	}
+
+	#[test]
+	fn call_is_not_wrapped_without_real_account() {
+		assert_eq!(
+			transaction_params(None).prepare_call::<Pass3dt>(pass3dt_remark_call()).unwrap(),
+			pass3dt_remark_call(),
+		);
+	}
+
+	#[test]
+	fn call_is_wrapped_into_proxy_call_with_real_account() {
+		assert_eq!(
+			transaction_params(Some(real_account()))
+				.prepare_call::<Pass3dt>(pass3dt_remark_call())
+				.unwrap(),
+			pass3dt_runtime::Call::Proxy(pass3dt_runtime::ProxyCall::proxy {
+				real: real_account().into(),
+				force_proxy_type: None,
+				call: Box::new(pass3dt_remark_call()),
+			}),
+		);
+	}
+
+	#[test]
+	fn call_is_not_prepared_if_chain_has_no_proxy_pallet() {
+		let call = rialto_runtime::Call::System(rialto_runtime::SystemCall::remark {
+			remark: b"Hello world!".to_vec(),
+		});
+		assert!(transaction_params(Some(real_account())).prepare_call::<Rialto>(call).is_err());
+	}
+
+	#[test]
+	fn tagged_account_address_uses_chain_ss58_prefix() {
+		let account = TaggedAccount::Headers {
+			id: sp_core::crypto::AccountId32::new([1u8; 32]),
+			bridged_chain: "Pass3d".into(),
+		};
+		let (decoded, format) = sp_core::crypto::AccountId32::from_ss58check_with_version(
+			&account.address::<Pass3dt>(),
+		)
+		.unwrap();
+		assert_eq!(&decoded, account.id());
+		assert_eq!(u16::from(format), pass3dt_runtime::SS58Prefix::get());
+	}
+}
@@ -33,15 +33,22 @@ use bridge_runtime_common::messages::{
 };
 use codec::Encode;
 use frame_support::weights::{GetDispatchInfo, Weight};
-use messages_relay::{message_lane::MessageLane, relay_strategy::RelayStrategy};
+use messages_relay::{
+	lane_scheduler::LaneSubmissionScheduler, message_lane::MessageLane,
+	message_lane_loop::OutboundStateProofParams, relay_strategy::SharedRelayStrategy,
+};
 use pallet_bridge_messages::{Call as BridgeMessagesCall, Config as BridgeMessagesConfig};
 use relay_substrate_client::{
+	metrics::{proof_size_warning_threshold, ProofType},
 	transaction_stall_timeout, AccountKeyPairOf, BalanceOf, BlockNumberOf, CallOf, Chain,
 	ChainWithMessages, Client, HashOf, TransactionSignScheme,
 };
-use relay_utils::{metrics::MetricsParams, STALL_TIMEOUT};
+use relay_utils::{
+	metrics::{MetricsParams, StandaloneMetric},
+	STALL_TIMEOUT,
+};
 use sp_core::Pair;
-use std::{convert::TryFrom, fmt::Debug, marker::PhantomData};
+use std::{convert::TryFrom, fmt::Debug, marker::PhantomData, path::PathBuf};
 
 /// Substrate -> Substrate messages synchronization pipeline.
 pub trait SubstrateMessageLane: 'static + Clone + Debug + Send + Sync {
@@ -97,9 +104,6 @@ pub trait SubstrateMessageLane: 'static + Clone + Debug + Send + Sync {
 	type TargetToSourceChainConversionRateUpdateBuilder: UpdateConversionRateCallBuilder<
 		Self::SourceChain,
 	>;
-
-	/// Message relay strategy.
-	type RelayStrategy: RelayStrategy;
 }
 
 /// Adapter that allows all `SubstrateMessageLane` to act as `MessageLane`.
@@ -148,7 +152,47 @@ pub struct MessagesRelayParams<P: SubstrateMessageLane> {
 	/// Pre-registered standalone metrics.
 	pub standalone_metrics: Option<StandaloneMessagesMetrics<P::SourceChain, P::TargetChain>>,
 	/// Relay strategy.
-	pub relay_strategy: P::RelayStrategy,
+	pub relay_strategy: SharedRelayStrategy<BalanceOf<P::SourceChain>>,
+	/// Scheduler of delivery transactions, shared with other lanes that are using the same
+	/// target chain signer.
+	pub submission_scheduler: Option<LaneSubmissionScheduler>,
+	/// Directory where relay-local lane state is persisted between relay restarts.
+	pub persistent_state_dir: Option<PathBuf>,
+	/// Log warning if generated proof is larger than given size (in bytes). If `None`, 2/3 of
+	/// the maximal extrinsic size at the chain that verifies the proof is used.
+	pub proof_size_warning_threshold: Option<usize>,
+	/// Parameters that define when outbound lane state proof is included into the message
+	/// delivery transaction.
+	pub outbound_state_proof_params: OutboundStateProofParams,
+}
+
+/// Limits of the single messages delivery transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MessagesBatchLimits {
+	/// Maximal number of messages in the single delivery transaction.
+	pub max_messages_in_single_batch: MessageNonce,
+	/// Maximal cumulative dispatch weight of messages in the single delivery transaction.
+	pub max_messages_weight_in_single_batch: Weight,
+	/// Maximal cumulative size of messages in the single delivery transaction.
+	pub max_messages_size_in_single_batch: u32,
+}
+
+/// Returns limits of the single messages delivery transaction for given lane.
+pub fn messages_batch_limits<P: SubstrateMessageLane>() -> MessagesBatchLimits {
+	// 2/3 is reserved for proofs and tx overhead
+	let max_messages_size_in_single_batch = P::TargetChain::max_extrinsic_size() / 3;
+	// we don't know exact weights of the Polkadot runtime. So to guess weights we'll be using
+	// weights from Rialto and then simply dividing it by x2.
+	let (max_messages_in_single_batch, max_messages_weight_in_single_batch) =
+		select_delivery_transaction_limits::<<P::TargetChain as ChainWithMessages>::WeightInfo>(
+			P::TargetChain::max_extrinsic_weight(),
+			P::SourceChain::MAX_UNREWARDED_RELAYERS_IN_CONFIRMATION_TX,
+		);
+	MessagesBatchLimits {
+		max_messages_in_single_batch: max_messages_in_single_batch / 2,
+		max_messages_weight_in_single_batch: max_messages_weight_in_single_batch / 2,
+		max_messages_size_in_single_batch,
+	}
 }
 
 /// Run Substrate-to-Substrate messages sync loop.
@@ -167,19 +211,11 @@ where
 	let relayer_id_at_source: AccountIdOf<P::SourceChain> =
 		params.source_transaction_params.signer.public().into();
 
-	// 2/3 is reserved for proofs and tx overhead
-	let max_messages_size_in_single_batch = P::TargetChain::max_extrinsic_size() / 3;
-	// we don't know exact weights of the Polkadot runtime. So to guess weights we'll be using
-	// weights from Rialto and then simply dividing it by x2.
-	let (max_messages_in_single_batch, max_messages_weight_in_single_batch) =
-		crate::messages_lane::select_delivery_transaction_limits::<
-			<P::TargetChain as ChainWithMessages>::WeightInfo,
-		>(
-			P::TargetChain::max_extrinsic_weight(),
-			P::SourceChain::MAX_UNREWARDED_RELAYERS_IN_CONFIRMATION_TX,
-		);
-	let (max_messages_in_single_batch, max_messages_weight_in_single_batch) =
-		(max_messages_in_single_batch / 2, max_messages_weight_in_single_batch / 2);
+	let MessagesBatchLimits {
+		max_messages_in_single_batch,
+		max_messages_weight_in_single_batch,
+		max_messages_size_in_single_batch,
+	} = messages_batch_limits::<P>();
 
 	let standalone_metrics = params.standalone_metrics.map(Ok).unwrap_or_else(|| {
 		crate::messages_metrics::standalone_metrics::<P>(
@@ -187,6 +223,14 @@ where
 			target_client.clone(),
 		)
 	})?;
+	crate::messages_metrics::DistinctRelayersMetric::<P::SourceChain, P::TargetChain>::new(
+		source_client.clone(),
+		params.lane_id,
+		Some(&messages_relay::message_lane_loop::metrics_prefix::<MessageLaneAdapter<P>>(
+			&params.lane_id,
+		)),
+	)?
+	.register_and_spawn(&params.metrics_params.registry)?;
 
 	log::info!(
 		target: "bridge",
@@ -231,7 +275,10 @@ where
 				max_messages_in_single_batch,
 				max_messages_weight_in_single_batch,
 				max_messages_size_in_single_batch,
+				outbound_state_proof_params: params.outbound_state_proof_params,
 				relay_strategy: params.relay_strategy,
+				submission_scheduler: params.submission_scheduler,
+				persistent_state_dir: params.persistent_state_dir,
 			},
 		},
 		SubstrateMessagesSource::<P>::new(
@@ -240,6 +287,16 @@ where
 			params.lane_id,
 			params.source_transaction_params,
 			params.target_to_source_headers_relay,
+		)
+		.with_proof_metrics(
+			standalone_metrics
+				.proofs
+				.proof_metrics::<P::SourceChain>(ProofType::Messages)
+				.with_size_warning_threshold(
+					params
+						.proof_size_warning_threshold
+						.unwrap_or_else(proof_size_warning_threshold::<P::TargetChain>),
+				),
 		),
 		SubstrateMessagesTarget::<P>::new(
 			target_client,
@@ -249,6 +306,16 @@ where
 			params.target_transaction_params,
 			standalone_metrics.clone(),
 			params.source_to_target_headers_relay,
+		)
+		.with_proof_metrics(
+			standalone_metrics
+				.proofs
+				.proof_metrics::<P::TargetChain>(ProofType::LaneState)
+				.with_size_warning_threshold(
+					params
+						.proof_size_warning_threshold
+						.unwrap_or_else(proof_size_warning_threshold::<P::SourceChain>),
+				),
 		),
 		standalone_metrics.register_and_spawn(params.metrics_params)?,
 		futures::future::pending(),
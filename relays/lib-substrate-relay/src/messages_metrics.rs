@@ -18,30 +18,39 @@
 
 use crate::{helpers::tokens_conversion_rate, messages_lane::SubstrateMessageLane, TaggedAccount};
 
+use async_trait::async_trait;
+use bp_messages::{LaneId, MessageNonce};
 use codec::Decode;
 use frame_system::AccountInfo;
 use pallet_balances::AccountData;
 use relay_substrate_client::{
 	metrics::{
-		FixedU128OrOne, FloatStorageValue, FloatStorageValueMetric, StorageProofOverheadMetric,
+		FixedU128OrOne, FloatStorageValue, FloatStorageValueMetric, ProofGenerationMetrics,
+		StorageProofOverheadMetric,
 	},
-	AccountIdOf, BalanceOf, Chain, ChainWithBalances, Client, Error as SubstrateError, IndexOf,
+	AccountIdOf, BalanceOf, Chain, ChainWithBalances, ChainWithMessages, Client,
+	Error as SubstrateError, IndexOf,
 };
 use relay_utils::metrics::{
-	FloatJsonValueMetric, GlobalMetrics, MetricsParams, PrometheusError, StandaloneMetric,
+	metric_name, register, FloatJsonValueMetric, Gauge, GlobalMetrics, Metric, MetricsParams,
+	PrometheusError, Registry, StandaloneMetric, U64,
 };
-use sp_core::storage::StorageData;
+use sp_core::{crypto::Ss58Codec, storage::StorageData};
 use sp_runtime::{FixedPointNumber, FixedU128};
-use std::{convert::TryFrom, fmt::Debug, marker::PhantomData};
+use std::{convert::TryFrom, fmt::Debug, marker::PhantomData, time::Duration};
 
 /// Name of the `NextFeeMultiplier` storage value within the transaction payment pallet.
 const NEXT_FEE_MULTIPLIER_VALUE_NAME: &str = "NextFeeMultiplier";
+/// Distinct relayers metric is updated once per this number of source chain blocks.
+const DISTINCT_RELAYERS_UPDATE_INTERVAL_IN_BLOCKS: u32 = 10;
 
 /// Shared references to the standalone metrics of the message lane relay loop.
 #[derive(Debug, Clone)]
 pub struct StandaloneMessagesMetrics<SC: Chain, TC: Chain> {
 	/// Global metrics.
 	pub global: GlobalMetrics,
+	/// Proofs generation metrics.
+	pub proofs: ProofGenerationMetrics,
 	/// Storage chain proof overhead metric.
 	pub source_storage_proof_overhead: StorageProofOverheadMetric<SC>,
 	/// Target chain proof overhead metric.
@@ -72,6 +81,7 @@ impl<SC: Chain, TC: Chain> StandaloneMessagesMetrics<SC, TC> {
 	pub fn reverse(self) -> StandaloneMessagesMetrics<TC, SC> {
 		StandaloneMessagesMetrics {
 			global: self.global,
+			proofs: self.proofs,
 			source_storage_proof_overhead: self.target_storage_proof_overhead,
 			target_storage_proof_overhead: self.source_storage_proof_overhead,
 			source_to_base_conversion_rate: self.target_to_base_conversion_rate,
@@ -91,6 +101,7 @@ impl<SC: Chain, TC: Chain> StandaloneMessagesMetrics<SC, TC> {
 		metrics: MetricsParams,
 	) -> Result<MetricsParams, PrometheusError> {
 		self.global.register_and_spawn(&metrics.registry)?;
+		self.proofs.register(&metrics.registry)?;
 		self.source_storage_proof_overhead.register_and_spawn(&metrics.registry)?;
 		self.target_storage_proof_overhead.register_and_spawn(&metrics.registry)?;
 		if let Some(m) = self.source_to_base_conversion_rate {
@@ -139,6 +150,7 @@ pub fn standalone_metrics<P: SubstrateMessageLane>(
 ) -> anyhow::Result<StandaloneMessagesMetrics<P::SourceChain, P::TargetChain>> {
 	Ok(StandaloneMessagesMetrics {
 		global: GlobalMetrics::new()?,
+		proofs: ProofGenerationMetrics::new()?,
 		source_storage_proof_overhead: StorageProofOverheadMetric::new(
 			source_client.clone(),
 			format!("{}_storage_proof_overhead", P::SourceChain::NAME.to_lowercase()),
@@ -277,6 +289,7 @@ pub async fn add_relay_balances_metrics<C: ChainWithBalances>(
 	relay_accounts: &Vec<TaggedAccount<AccountIdOf<C>>>,
 ) -> anyhow::Result<()>
 where
+	AccountIdOf<C>: Ss58Codec,
 	BalanceOf<C>: Into<u128> + std::fmt::Debug,
 {
 	if relay_accounts.is_empty() {
@@ -307,12 +320,26 @@ where
 	})?;
 
 	for account in relay_accounts {
+		let address = account.address::<C>();
+		log::info!(
+			target: "bridge",
+			"Tracking balance of the {} relay account {} at {}",
+			account.tag(),
+			address,
+			C::NAME,
+		);
+
 		let relay_account_balance_metric = FloatStorageValueMetric::new(
 			FreeAccountBalance::<C> { token_decimals, _phantom: Default::default() },
 			client.clone(),
 			C::account_info_storage_key(account.id()),
 			format!("at_{}_relay_{}_balance", C::NAME, account.tag()),
-			format!("Balance of the {} relay account at the {}", account.tag(), C::NAME),
+			format!(
+				"Balance of the {} relay account {} at the {}",
+				account.tag(),
+				address,
+				C::NAME
+			),
 		)?;
 		relay_account_balance_metric.register_and_spawn(&metrics.registry)?;
 	}
@@ -320,6 +347,80 @@ where
 	Ok(())
 }
 
+/// Number of distinct relayers that have recently delivered confirmed messages over the lane.
+///
+/// The value is read from the source chain, which tracks relayers of the last confirmed
+/// deliveries.
+pub struct DistinctRelayersMetric<SC: Chain, TC> {
+	client: Client<SC>,
+	lane: LaneId,
+	metric: Gauge<U64>,
+	_phantom: PhantomData<TC>,
+}
+
+impl<SC: Chain, TC> Clone for DistinctRelayersMetric<SC, TC> {
+	fn clone(&self) -> Self {
+		DistinctRelayersMetric {
+			client: self.client.clone(),
+			lane: self.lane,
+			metric: self.metric.clone(),
+			_phantom: Default::default(),
+		}
+	}
+}
+
+impl<SC: Chain, TC: ChainWithMessages> DistinctRelayersMetric<SC, TC> {
+	/// Create new metric instance with given name and help.
+	pub fn new(
+		client: Client<SC>,
+		lane: LaneId,
+		prefix: Option<&str>,
+	) -> Result<Self, PrometheusError> {
+		Ok(DistinctRelayersMetric {
+			client,
+			lane,
+			metric: Gauge::new(
+				metric_name(prefix, "bridge_distinct_relayers"),
+				format!(
+					"Number of distinct relayers that have recently delivered {} -> {} messages",
+					SC::NAME,
+					TC::NAME,
+				),
+			)?,
+			_phantom: Default::default(),
+		})
+	}
+
+	/// Read relayers of recent confirmed deliveries from the source chain.
+	async fn recent_relayers(
+		&self,
+	) -> Result<Vec<(AccountIdOf<SC>, MessageNonce)>, SubstrateError> {
+		self.client
+			.typed_state_call(TC::TO_CHAIN_RECENT_RELAYERS_METHOD.into(), self.lane, None)
+			.await
+	}
+}
+
+impl<SC: Chain, TC: ChainWithMessages> Metric for DistinctRelayersMetric<SC, TC> {
+	fn register(&self, registry: &Registry) -> Result<(), PrometheusError> {
+		register(self.metric.clone(), registry).map(drop)
+	}
+}
+
+#[async_trait]
+impl<SC: Chain, TC: ChainWithMessages> StandaloneMetric for DistinctRelayersMetric<SC, TC> {
+	fn update_interval(&self) -> Duration {
+		SC::AVERAGE_BLOCK_INTERVAL * DISTINCT_RELAYERS_UPDATE_INTERVAL_IN_BLOCKS
+	}
+
+	async fn update(&self) {
+		relay_utils::metrics::set_gauge_value(
+			&self.metric,
+			self.recent_relayers().await.map(|relayers| Some(relayers.len() as u64)),
+		);
+	}
+}
+
 /// Adapter for `FloatStorageValueMetric` to decode account free balance.
 #[derive(Clone, Debug)]
 struct FreeAccountBalance<C> {
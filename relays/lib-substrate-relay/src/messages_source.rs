@@ -30,11 +30,14 @@ use crate::{
 use async_std::sync::Arc;
 use async_trait::async_trait;
 use bp_messages::{
-	storage_keys::{operating_mode_key, outbound_lane_data_key},
-	InboundMessageDetails, LaneId, MessageData, MessageNonce, MessagePayload,
+	storage_keys::{
+		operating_mode_key, LaneModeKeyProvider, OutboundLanesKeyProvider,
+		OutboundMessagesKeyProvider,
+	},
+	InboundMessageDetails, LaneId, MessageData, MessageKey, MessageNonce, MessagePayload,
 	MessagesOperatingMode, OutboundLaneData, OutboundMessageDetails, UnrewardedRelayersState,
 };
-use bp_runtime::{messages::DispatchFeePayment, BasicOperatingMode, HeaderIdProvider};
+use bp_runtime::{trim_storage_proof, BasicOperatingMode, HasherOf, HeaderIdProvider};
 use bridge_runtime_common::messages::{
 	source::FromBridgedChainMessagesDeliveryProof, target::FromBridgedChainMessagesProof,
 };
@@ -49,14 +52,14 @@ use messages_relay::{
 };
 use num_traits::{Bounded, Zero};
 use relay_substrate_client::{
-	AccountIdOf, AccountKeyPairOf, BalanceOf, BlockNumberOf, Chain, ChainWithMessages, Client,
-	Error as SubstrateError, HashOf, HeaderIdOf, IndexOf, SignParam, TransactionEra,
-	TransactionSignScheme, TransactionTracker, UnsignedTransaction,
+	metrics::ProofMetrics, AccountIdOf, AccountKeyPairOf, BalanceOf, BlockNumberOf, Chain,
+	ChainWithMessages, Client, Error as SubstrateError, HashOf, HeaderIdOf, IndexOf, SignParam,
+	TransactionEra, TransactionSignScheme, TransactionTracker, UnsignedTransaction,
 };
 use relay_utils::{relay_loop::Client as RelayClient, HeaderId};
 use sp_core::{Bytes, Pair};
 use sp_runtime::{traits::Header as HeaderT, DeserializeOwned};
-use std::ops::RangeInclusive;
+use std::{ops::RangeInclusive, time::Instant};
 
 /// Intermediate message proof returned by the source Substrate node. Includes everything
 /// required to submit to the target node: cumulative dispatch weight of bundled messages and
@@ -71,6 +74,7 @@ pub struct SubstrateMessagesSource<P: SubstrateMessageLane> {
 	lane_id: LaneId,
 	transaction_params: TransactionParams<AccountKeyPairOf<P::SourceTransactionSignScheme>>,
 	target_to_source_headers_relay: Option<Arc<dyn OnDemandRelay<BlockNumberOf<P::TargetChain>>>>,
+	proof_metrics: Option<ProofMetrics>,
 }
 
 impl<P: SubstrateMessageLane> SubstrateMessagesSource<P> {
@@ -90,20 +94,49 @@ impl<P: SubstrateMessageLane> SubstrateMessagesSource<P> {
 			lane_id,
 			transaction_params,
 			target_to_source_headers_relay,
+			proof_metrics: None,
 		}
 	}
 
-	/// Read outbound lane state from the on-chain storage at given block.
+	/// Observe generated messages proofs using given metrics.
+	#[must_use]
+	pub fn with_proof_metrics(mut self, proof_metrics: ProofMetrics) -> Self {
+		self.proof_metrics = Some(proof_metrics);
+		self
+	}
+
+	/// Read outbound lane state at given block.
+	///
+	/// The state is read using the `outbound_lane_state` runtime API method. If the source
+	/// runtime doesn't support it yet, we fall back to reading it from the on-chain storage.
 	async fn outbound_lane_data(
 		&self,
 		id: SourceHeaderIdOf<MessageLaneAdapter<P>>,
 	) -> Result<Option<OutboundLaneData>, SubstrateError> {
+		let api_result = self
+			.source_client
+			.typed_state_call::<_, OutboundLaneData>(
+				P::TargetChain::TO_CHAIN_OUTBOUND_LANE_STATE_METHOD.into(),
+				self.lane_id,
+				Some(id.1),
+			)
+			.await;
+		match api_result {
+			Ok(lane_data) => return Ok(Some(lane_data)),
+			Err(e) => log::debug!(
+				target: "bridge",
+				"Failed to call {} at {} ({:?}). Reading outbound lane {:?} state from the storage",
+				P::TargetChain::TO_CHAIN_OUTBOUND_LANE_STATE_METHOD,
+				P::SourceChain::NAME,
+				e,
+				self.lane_id,
+			),
+		}
+
 		self.source_client
-			.storage_value(
-				outbound_lane_data_key(
-					P::TargetChain::WITH_CHAIN_MESSAGES_PALLET_NAME,
-					&self.lane_id,
-				),
+			.storage_map_value::<OutboundLanesKeyProvider>(
+				P::TargetChain::WITH_CHAIN_MESSAGES_PALLET_NAME,
+				&self.lane_id,
 				Some(id.1),
 			)
 			.await
@@ -113,6 +146,15 @@ impl<P: SubstrateMessageLane> SubstrateMessagesSource<P> {
 	async fn ensure_pallet_active(&self) -> Result<(), SubstrateError> {
 		ensure_messages_pallet_active::<P::SourceChain, P::TargetChain>(&self.source_client).await
 	}
+
+	/// Ensure that the served lane at source chain is not halted.
+	async fn ensure_lane_active(&self) -> Result<(), SubstrateError> {
+		ensure_messages_lane_active::<P::SourceChain, P::TargetChain>(
+			&self.source_client,
+			self.lane_id,
+		)
+		.await
+	}
 }
 
 impl<P: SubstrateMessageLane> Clone for SubstrateMessagesSource<P> {
@@ -123,6 +165,7 @@ impl<P: SubstrateMessageLane> Clone for SubstrateMessagesSource<P> {
 			lane_id: self.lane_id,
 			transaction_params: self.transaction_params.clone(),
 			target_to_source_headers_relay: self.target_to_source_headers_relay.clone(),
+			proof_metrics: self.proof_metrics.clone(),
 		}
 	}
 }
@@ -156,6 +199,8 @@ where
 		self.target_client.ensure_synced().await?;
 		// we can't relay confirmations if messages pallet at source chain is halted
 		self.ensure_pallet_active().await?;
+		// the same applies to the halted lane, but other lanes are still served
+		self.ensure_lane_active().await?;
 
 		read_client_state(
 			&self.source_client,
@@ -209,19 +254,18 @@ where
 		// prepare arguments of the inbound message details call (if we need it)
 		let mut msgs_to_refine = vec![];
 		for out_msg_details in out_msgs_details.iter_mut() {
-			if out_msg_details.dispatch_fee_payment != DispatchFeePayment::AtTargetChain {
-				continue
-			}
-
-			// for pay-at-target messages we may want to ask target chain for
-			// refined dispatch weight
-			let msg_key = bp_messages::storage_keys::message_key(
-				P::TargetChain::WITH_CHAIN_MESSAGES_PALLET_NAME,
-				&self.lane_id,
-				out_msg_details.nonce,
-			);
-			let msg_data: MessageData<BalanceOf<P::SourceChain>> =
-				self.source_client.storage_value(msg_key, Some(id.1)).await?.ok_or_else(|| {
+			// source chain doesn't know the dispatch weight of XCM messages, so we ask
+			// target chain for refined dispatch weight, no matter where dispatch fee is paid
+			let msg_key = MessageKey { lane_id: self.lane_id, nonce: out_msg_details.nonce };
+			let msg_data: MessageData<BalanceOf<P::SourceChain>> = self
+				.source_client
+				.storage_map_value::<OutboundMessagesKeyProvider<BalanceOf<P::SourceChain>>>(
+					P::TargetChain::WITH_CHAIN_MESSAGES_PALLET_NAME,
+					&msg_key,
+					Some(id.1),
+				)
+				.await?
+				.ok_or_else(|| {
 					SubstrateError::Custom(format!(
 						"Message to {} {:?}/{} is missing from runtime the storage of {} at {:?}",
 						P::TargetChain::NAME,
@@ -268,6 +312,18 @@ where
 					out_msg_details.dispatch_weight,
 					in_msg_details.dispatch_weight,
 				);
+				if !in_msg_details.is_payload_decodable {
+					log::warn!(
+						target: "bridge",
+						"{}->{} message {:?}/{} can't be decoded at {}. It will be delivered, but \
+						won't be dispatched",
+						P::SourceChain::NAME,
+						P::TargetChain::NAME,
+						self.lane_id,
+						out_msg_details.nonce,
+						P::TargetChain::NAME,
+					);
+				}
 				out_msg_details.dispatch_weight = in_msg_details.dispatch_weight;
 			}
 		}
@@ -320,12 +376,17 @@ where
 			));
 		}
 
-		let proof = self
-			.source_client
-			.prove_storage(storage_keys, id.1)
-			.await?
-			.iter_nodes()
-			.collect();
+		let proof_started_at = Instant::now();
+		let state_root = *self.source_client.header_by_hash(id.1).await?.state_root();
+		let (proof, proof_size) = trim_storage_proof::<HasherOf<P::SourceChain>>(
+			state_root,
+			self.source_client.prove_storage(storage_keys.clone(), id.1).await?,
+			&storage_keys,
+		)
+		.map_err(SubstrateError::StorageProofError)?;
+		if let Some(ref proof_metrics) = self.proof_metrics {
+			proof_metrics.observe(proof_started_at.elapsed(), proof_size.0);
+		}
 		let proof = FromBridgedChainMessagesProof {
 			bridged_header_hash: id.1,
 			storage_proof: proof,
@@ -336,6 +397,42 @@ where
 		Ok((id, nonces, (proof_parameters.dispatch_weight, proof)))
 	}
 
+	fn is_pruned_state_error(&self, error: &SubstrateError) -> bool {
+		error.is_pruned_state_error()
+	}
+
+	async fn ensure_finalized_ancestor(
+		&self,
+		id: SourceHeaderIdOf<MessageLaneAdapter<P>>,
+	) -> Result<(), SubstrateError> {
+		let best_finalized_header_number =
+			self.source_client.best_finalized_header_number().await?;
+		let canonical_header_hash = if id.0 <= best_finalized_header_number {
+			Some(self.source_client.block_hash_by_number(id.0).await?)
+		} else {
+			None
+		};
+		if canonical_header_hash != Some(id.1) {
+			log::error!(
+				target: "bridge",
+				"{} header {:?} is not an ancestor of the best finalized header {}. Canonical header \
+				at the same height: {:?}. Finality of {} is violated!",
+				P::SourceChain::NAME,
+				id,
+				best_finalized_header_number,
+				canonical_header_hash,
+				P::SourceChain::NAME,
+			);
+			return Err(SubstrateError::Custom(format!(
+				"{} header {:?} is not an ancestor of the best finalized header",
+				P::SourceChain::NAME,
+				id,
+			)))
+		}
+
+		Ok(())
+	}
+
 	async fn submit_messages_receiving_proof(
 		&self,
 		_generated_at_block: TargetHeaderIdOf<MessageLaneAdapter<P>>,
@@ -427,6 +524,46 @@ where
 	}
 }
 
+/// Ensure that the messages lane at given chain is not halted.
+///
+/// The lane mode is only checked in addition to the pallet operating mode. Returned error
+/// only stops relaying at the given lane, so it isn't treated as a client failure.
+pub(crate) async fn ensure_messages_lane_active<AtChain, WithChain>(
+	client: &Client<AtChain>,
+	lane_id: LaneId,
+) -> Result<(), SubstrateError>
+where
+	AtChain: ChainWithMessages,
+	WithChain: ChainWithMessages,
+{
+	let lane_mode = lane_operating_mode::<AtChain, WithChain>(client, lane_id).await?;
+	if lane_mode == MessagesOperatingMode::Basic(BasicOperatingMode::Halted) {
+		Err(SubstrateError::BridgeLaneIsHalted(lane_id))
+	} else {
+		Ok(())
+	}
+}
+
+/// Read operating mode of the messages lane at given chain.
+pub(crate) async fn lane_operating_mode<AtChain, WithChain>(
+	client: &Client<AtChain>,
+	lane_id: LaneId,
+) -> Result<MessagesOperatingMode, SubstrateError>
+where
+	AtChain: ChainWithMessages,
+	WithChain: ChainWithMessages,
+{
+	// lanes without explicit mode are operating normally
+	Ok(client
+		.storage_map_value::<LaneModeKeyProvider>(
+			WithChain::WITH_CHAIN_MESSAGES_PALLET_NAME,
+			&lane_id,
+			None,
+		)
+		.await?
+		.unwrap_or_default())
+}
+
 /// Make messages delivery proof transaction from given proof.
 fn make_messages_delivery_proof_transaction<P: SubstrateMessageLane>(
 	source_transaction_params: &TransactionParams<AccountKeyPairOf<P::SourceTransactionSignScheme>>,
@@ -437,13 +574,18 @@ fn make_messages_delivery_proof_transaction<P: SubstrateMessageLane>(
 ) -> Result<UnsignedTransaction<P::SourceChain>, SubstrateError>
 where
 	P::SourceTransactionSignScheme: TransactionSignScheme<Chain = P::SourceChain>,
+	AccountIdOf<P::SourceChain>:
+		From<<AccountKeyPairOf<P::SourceTransactionSignScheme> as Pair>::Public>,
 {
-	let call =
+	let call = source_transaction_params.prepare_call::<P::SourceChain>(
 		P::ReceiveMessagesDeliveryProofCallBuilder::build_receive_messages_delivery_proof_call(
 			proof, trace_call,
-		);
-	Ok(UnsignedTransaction::new(call.into(), transaction_nonce)
-		.era(TransactionEra::new(source_best_block_id, source_transaction_params.mortality)))
+		),
+	)?;
+	Ok(UnsignedTransaction::new(call.into(), transaction_nonce).era(TransactionEra::new_checked(
+		source_best_block_id,
+		source_transaction_params.mortality,
+	)?))
 }
 
 /// Prepare 'dummy' messages delivery proof that will compose the delivery confirmation transaction.
@@ -657,6 +799,7 @@ mod tests {
 				size: 0,
 				delivery_and_dispatch_fee: 0,
 				dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+				submitter: None,
 			})
 			.collect()
 	}
@@ -734,6 +877,7 @@ mod tests {
 				size: 0,
 				delivery_and_dispatch_fee: 0,
 				dispatch_fee_payment: DispatchFeePayment::AtTargetChain,
+				submitter: None,
 			});
 		}
 
@@ -745,7 +889,8 @@ mod tests {
 			msgs_to_refine.push((payload, out_msg_details));
 		}
 
-		let maybe_batches = split_msgs_to_refine::<Rialto, Rococo>([0, 0, 0, 0], msgs_to_refine);
+		let maybe_batches =
+			split_msgs_to_refine::<Rialto, Rococo>(LaneId([0, 0, 0, 0]), msgs_to_refine);
 		match expected_batches {
 			Ok(expected_batches) => {
 				let batches = maybe_batches.unwrap();
@@ -21,7 +21,10 @@
 use crate::{
 	messages_lane::{MessageLaneAdapter, ReceiveMessagesProofCallBuilder, SubstrateMessageLane},
 	messages_metrics::StandaloneMessagesMetrics,
-	messages_source::{ensure_messages_pallet_active, read_client_state, SubstrateMessagesProof},
+	messages_source::{
+		ensure_messages_pallet_active, lane_operating_mode, read_client_state,
+		SubstrateMessagesProof,
+	},
 	on_demand::OnDemandRelay,
 	TransactionParams,
 };
@@ -29,9 +32,11 @@ use crate::{
 use async_std::sync::Arc;
 use async_trait::async_trait;
 use bp_messages::{
-	storage_keys::inbound_lane_data_key, total_unrewarded_messages, InboundLaneData, LaneId,
-	MessageNonce, UnrewardedRelayersState,
+	storage_keys::{operating_mode_key, InboundLanesKeyProvider},
+	InboundLaneData, InboundLaneState, LaneId, MessageNonce, MessagesOperatingMode,
+	UnrewardedRelayersState,
 };
+use bp_runtime::{trim_storage_proof, BasicOperatingMode, HasherOf};
 use bridge_runtime_common::messages::{
 	source::FromBridgedChainMessagesDeliveryProof, target::FromBridgedChainMessagesProof,
 };
@@ -43,14 +48,17 @@ use messages_relay::{
 };
 use num_traits::{Bounded, Zero};
 use relay_substrate_client::{
-	AccountIdOf, AccountKeyPairOf, BalanceOf, BlockNumberOf, Chain, ChainWithMessages, Client,
-	Error as SubstrateError, HashOf, HeaderIdOf, IndexOf, SignParam, TransactionEra,
-	TransactionSignScheme, TransactionTracker, UnsignedTransaction, WeightToFeeOf,
+	metrics::ProofMetrics, AccountIdOf, AccountKeyPairOf, BalanceOf, BlockNumberOf, Chain,
+	ChainWithMessages, Client, Error as SubstrateError, HashOf, HeaderIdOf, IndexOf, SignParam,
+	TransactionEra, TransactionSignScheme, TransactionTracker, UnsignedTransaction, WeightToFeeOf,
 };
 use relay_utils::{relay_loop::Client as RelayClient, HeaderId};
 use sp_core::{Bytes, Pair};
-use sp_runtime::{traits::Saturating, FixedPointNumber, FixedU128};
-use std::{collections::VecDeque, convert::TryFrom, ops::RangeInclusive};
+use sp_runtime::{
+	traits::{Header as HeaderT, Saturating},
+	FixedPointNumber, FixedU128,
+};
+use std::{convert::TryFrom, ops::RangeInclusive, time::Instant};
 
 /// Message receiving proof returned by the target Substrate node.
 pub type SubstrateMessagesDeliveryProof<C> =
@@ -65,6 +73,7 @@ pub struct SubstrateMessagesTarget<P: SubstrateMessageLane> {
 	transaction_params: TransactionParams<AccountKeyPairOf<P::TargetTransactionSignScheme>>,
 	metric_values: StandaloneMessagesMetrics<P::SourceChain, P::TargetChain>,
 	source_to_target_headers_relay: Option<Arc<dyn OnDemandRelay<BlockNumberOf<P::SourceChain>>>>,
+	proof_metrics: Option<ProofMetrics>,
 }
 
 impl<P: SubstrateMessageLane> SubstrateMessagesTarget<P> {
@@ -88,29 +97,99 @@ impl<P: SubstrateMessageLane> SubstrateMessagesTarget<P> {
 			transaction_params,
 			metric_values,
 			source_to_target_headers_relay,
+			proof_metrics: None,
 		}
 	}
 
-	/// Read inbound lane state from the on-chain storage at given block.
-	async fn inbound_lane_data(
+	/// Observe generated messages delivery proofs using given metrics.
+	#[must_use]
+	pub fn with_proof_metrics(mut self, proof_metrics: ProofMetrics) -> Self {
+		self.proof_metrics = Some(proof_metrics);
+		self
+	}
+
+	/// Read inbound lane state at given block.
+	///
+	/// The state is read using the `inbound_lane_state` runtime API method. If the target
+	/// runtime doesn't support it yet, we fall back to reading lane data from the on-chain
+	/// storage.
+	async fn inbound_lane_state(
 		&self,
 		id: TargetHeaderIdOf<MessageLaneAdapter<P>>,
-	) -> Result<Option<InboundLaneData<AccountIdOf<P::SourceChain>>>, SubstrateError> {
-		self.target_client
-			.storage_value(
-				inbound_lane_data_key(
-					P::SourceChain::WITH_CHAIN_MESSAGES_PALLET_NAME,
-					&self.lane_id,
-				),
+	) -> Result<InboundLaneState, SubstrateError> {
+		let api_result = self
+			.target_client
+			.typed_state_call::<_, InboundLaneState>(
+				P::SourceChain::FROM_CHAIN_INBOUND_LANE_STATE_METHOD.into(),
+				self.lane_id,
 				Some(id.1),
 			)
-			.await
+			.await;
+		match api_result {
+			Ok(lane_state) => return Ok(lane_state),
+			Err(e) => log::debug!(
+				target: "bridge",
+				"Failed to call {} at {} ({:?}). Reading inbound lane {:?} state from the storage",
+				P::SourceChain::FROM_CHAIN_INBOUND_LANE_STATE_METHOD,
+				P::TargetChain::NAME,
+				e,
+				self.lane_id,
+			),
+		}
+
+		let inbound_lane_data = self
+			.target_client
+			.storage_map_value::<InboundLanesKeyProvider<AccountIdOf<P::SourceChain>>>(
+				P::SourceChain::WITH_CHAIN_MESSAGES_PALLET_NAME,
+				&self.lane_id,
+				Some(id.1),
+			)
+			.await?;
+		Ok(inbound_lane_state_from_storage(inbound_lane_data))
 	}
 
 	/// Ensure that the messages pallet at target chain is active.
 	async fn ensure_pallet_active(&self) -> Result<(), SubstrateError> {
 		ensure_messages_pallet_active::<P::TargetChain, P::SourceChain>(&self.target_client).await
 	}
+
+	/// Ensure that the messages pallet at target chain accepts delivery transactions.
+	///
+	/// Delivery transactions are rejected by the pallet in `RejectingInboundMessages` mode, so
+	/// there's no point in submitting (and paying for) them.
+	async fn ensure_inbound_messages_accepted(&self) -> Result<(), SubstrateError> {
+		let operating_mode = self
+			.target_client
+			.storage_value(
+				operating_mode_key(P::SourceChain::WITH_CHAIN_MESSAGES_PALLET_NAME),
+				None,
+			)
+			.await?;
+		if operating_mode == Some(MessagesOperatingMode::RejectingInboundMessages) {
+			Err(SubstrateError::BridgePalletIsRejectingInboundMessages)
+		} else {
+			Ok(())
+		}
+	}
+
+	/// Ensure that the served lane at target chain accepts delivery transactions.
+	///
+	/// Delivery transactions are rejected by the halted lane and by the lane in
+	/// `RejectingInboundMessages` mode. Other lanes are still served by their own loops.
+	async fn ensure_lane_accepts_inbound_messages(&self) -> Result<(), SubstrateError> {
+		match lane_operating_mode::<P::TargetChain, P::SourceChain>(
+			&self.target_client,
+			self.lane_id,
+		)
+		.await?
+		{
+			MessagesOperatingMode::Basic(BasicOperatingMode::Halted) =>
+				Err(SubstrateError::BridgeLaneIsHalted(self.lane_id)),
+			MessagesOperatingMode::RejectingInboundMessages =>
+				Err(SubstrateError::BridgeLaneIsRejectingInboundMessages(self.lane_id)),
+			_ => Ok(()),
+		}
+	}
 }
 
 impl<P: SubstrateMessageLane> Clone for SubstrateMessagesTarget<P> {
@@ -123,6 +202,7 @@ impl<P: SubstrateMessageLane> Clone for SubstrateMessagesTarget<P> {
 			transaction_params: self.transaction_params.clone(),
 			metric_values: self.metric_values.clone(),
 			source_to_target_headers_relay: self.source_to_target_headers_relay.clone(),
+			proof_metrics: self.proof_metrics.clone(),
 		}
 	}
 }
@@ -157,6 +237,9 @@ where
 		self.target_client.ensure_synced().await?;
 		// we can't relay messages if messages pallet at target chain is halted
 		self.ensure_pallet_active().await?;
+		// the same applies to the lane that doesn't accept messages, but other lanes are still
+		// served
+		self.ensure_lane_accepts_inbound_messages().await?;
 
 		read_client_state(
 			&self.target_client,
@@ -171,11 +254,8 @@ where
 		id: TargetHeaderIdOf<MessageLaneAdapter<P>>,
 	) -> Result<(TargetHeaderIdOf<MessageLaneAdapter<P>>, MessageNonce), SubstrateError> {
 		// lane data missing from the storage is fine until first message is received
-		let latest_received_nonce = self
-			.inbound_lane_data(id)
-			.await?
-			.map(|data| data.last_delivered_nonce())
-			.unwrap_or(0);
+		let latest_received_nonce =
+			self.inbound_lane_state(id).await?.unrewarded_relayers.last_delivered_nonce;
 		Ok((id, latest_received_nonce))
 	}
 
@@ -184,11 +264,7 @@ where
 		id: TargetHeaderIdOf<MessageLaneAdapter<P>>,
 	) -> Result<(TargetHeaderIdOf<MessageLaneAdapter<P>>, MessageNonce), SubstrateError> {
 		// lane data missing from the storage is fine until first message is received
-		let last_confirmed_nonce = self
-			.inbound_lane_data(id)
-			.await?
-			.map(|data| data.last_confirmed_nonce)
-			.unwrap_or(0);
+		let last_confirmed_nonce = self.inbound_lane_state(id).await?.last_confirmed_nonce;
 		Ok((id, last_confirmed_nonce))
 	}
 
@@ -197,20 +273,8 @@ where
 		id: TargetHeaderIdOf<MessageLaneAdapter<P>>,
 	) -> Result<(TargetHeaderIdOf<MessageLaneAdapter<P>>, UnrewardedRelayersState), SubstrateError>
 	{
-		let inbound_lane_data = self.inbound_lane_data(id).await?;
-		let last_delivered_nonce =
-			inbound_lane_data.as_ref().map(|data| data.last_delivered_nonce()).unwrap_or(0);
-		let relayers = inbound_lane_data.map(|data| data.relayers).unwrap_or_else(VecDeque::new);
-		let unrewarded_relayers_state = bp_messages::UnrewardedRelayersState {
-			unrewarded_relayer_entries: relayers.len() as _,
-			messages_in_oldest_entry: relayers
-				.front()
-				.map(|entry| 1 + entry.messages.end - entry.messages.begin)
-				.unwrap_or(0),
-			total_messages: total_unrewarded_messages(&relayers).unwrap_or(MessageNonce::MAX),
-			last_delivered_nonce,
-		};
-		Ok((id, unrewarded_relayers_state))
+		let unrewarded_relayers = self.inbound_lane_state(id).await?.unrewarded_relayers;
+		Ok((id, unrewarded_relayers))
 	}
 
 	async fn prove_messages_receiving(
@@ -228,12 +292,17 @@ where
 			P::SourceChain::WITH_CHAIN_MESSAGES_PALLET_NAME,
 			&self.lane_id,
 		);
-		let proof = self
-			.target_client
-			.prove_storage(vec![inbound_data_key], id.1)
-			.await?
-			.iter_nodes()
-			.collect();
+		let proof_started_at = Instant::now();
+		let state_root = *self.target_client.header_by_hash(id.1).await?.state_root();
+		let (proof, proof_size) = trim_storage_proof::<HasherOf<P::TargetChain>>(
+			state_root,
+			self.target_client.prove_storage(vec![inbound_data_key.clone()], id.1).await?,
+			[&inbound_data_key],
+		)
+		.map_err(SubstrateError::StorageProofError)?;
+		if let Some(ref proof_metrics) = self.proof_metrics {
+			proof_metrics.observe(proof_started_at.elapsed(), proof_size.0);
+		}
 		let proof = FromBridgedChainMessagesDeliveryProof {
 			bridged_header_hash: id.1,
 			storage_proof: proof,
@@ -248,6 +317,8 @@ where
 		nonces: RangeInclusive<MessageNonce>,
 		proof: <MessageLaneAdapter<P> as MessageLane>::MessagesProof,
 	) -> Result<NoncesSubmitArtifacts<Self::TransactionTracker>, SubstrateError> {
+		self.ensure_inbound_messages_accepted().await?;
+
 		let genesis_hash = *self.target_client.genesis_hash();
 		let transaction_params = self.transaction_params.clone();
 		let relayer_id_at_source = self.relayer_id_at_source.clone();
@@ -391,9 +462,10 @@ where
 		};
 
 		let delivery_fee_in_source_tokens =
-			convert_target_tokens_to_source_tokens::<P::SourceChain, P::TargetChain>(
+			compute_delivery_fee_in_source_tokens::<P::SourceChain, P::TargetChain>(
 				FixedU128::from_float(conversion_rate),
-				inclusion_fee_in_target_tokens.saturating_sub(expected_refund_in_target_tokens),
+				inclusion_fee_in_target_tokens,
+				expected_refund_in_target_tokens,
 			);
 
 		log::trace!(
@@ -435,18 +507,34 @@ fn make_messages_delivery_transaction<P: SubstrateMessageLane>(
 ) -> Result<UnsignedTransaction<P::TargetChain>, SubstrateError>
 where
 	P::TargetTransactionSignScheme: TransactionSignScheme<Chain = P::TargetChain>,
+	AccountIdOf<P::TargetChain>:
+		From<<AccountKeyPairOf<P::TargetTransactionSignScheme> as Pair>::Public>,
 {
 	let messages_count = nonces.end() - nonces.start() + 1;
 	let dispatch_weight = proof.0;
-	let call = P::ReceiveMessagesProofCallBuilder::build_receive_messages_proof_call(
-		relayer_id_at_source,
-		proof,
-		messages_count as _,
-		dispatch_weight,
-		trace_call,
-	);
-	Ok(UnsignedTransaction::new(call.into(), transaction_nonce)
-		.era(TransactionEra::new(target_best_block_id, target_transaction_params.mortality)))
+	let call = target_transaction_params.prepare_call::<P::TargetChain>(
+		P::ReceiveMessagesProofCallBuilder::build_receive_messages_proof_call(
+			relayer_id_at_source,
+			proof,
+			messages_count as _,
+			dispatch_weight,
+			trace_call,
+		),
+	)?;
+	Ok(UnsignedTransaction::new(call.into(), transaction_nonce).era(TransactionEra::new_checked(
+		target_best_block_id,
+		target_transaction_params.mortality,
+	)?))
+}
+
+/// Compute inbound lane state from the lane data, read from the on-chain storage.
+///
+/// Lane data missing from the storage is fine until first message is received, so the
+/// default state is returned then.
+fn inbound_lane_state_from_storage<RelayerId>(
+	inbound_lane_data: Option<InboundLaneData<RelayerId>>,
+) -> InboundLaneState {
+	inbound_lane_data.map(|data| (&data).into()).unwrap_or_default()
 }
 
 /// Prepare 'dummy' messages proof that will compose the delivery transaction.
@@ -513,6 +601,25 @@ fn compute_fee_multiplier<C: ChainWithMessages>(
 	)
 }
 
+/// Compute cost of the delivery transaction in source chain tokens.
+///
+/// The `expected_refund` is the fee that is refunded to the relayer by the target chain, because
+/// dispatch of some messages has already been paid at the source chain. It is zero if all messages
+/// are paying for their dispatch at the target chain.
+fn compute_delivery_fee_in_source_tokens<SC: Chain, TC: Chain>(
+	target_to_source_conversion_rate: FixedU128,
+	inclusion_fee: TC::Balance,
+	expected_refund: TC::Balance,
+) -> SC::Balance
+where
+	SC::Balance: TryFrom<TC::Balance>,
+{
+	convert_target_tokens_to_source_tokens::<SC, TC>(
+		target_to_source_conversion_rate,
+		inclusion_fee.saturating_sub(expected_refund),
+	)
+}
+
 /// Compute fee that will be refunded to the relayer because dispatch of `total_prepaid_nonces`
 /// messages has been paid at the source chain.
 fn compute_prepaid_messages_refund<C: ChainWithMessages>(
@@ -531,6 +638,39 @@ mod tests {
 	use relay_rococo_client::Rococo;
 	use relay_wococo_client::Wococo;
 
+	#[test]
+	fn inbound_lane_state_from_storage_works() {
+		assert_eq!(inbound_lane_state_from_storage::<u64>(None), InboundLaneState::default());
+
+		let inbound_lane_data = InboundLaneData {
+			relayers: vec![
+				bp_messages::UnrewardedRelayer {
+					relayer: 1u64,
+					messages: bp_messages::DeliveredMessages::new(6, true),
+				},
+				bp_messages::UnrewardedRelayer {
+					relayer: 2u64,
+					messages: bp_messages::DeliveredMessages::new(7, true),
+				},
+			]
+			.into_iter()
+			.collect(),
+			last_confirmed_nonce: 5,
+		};
+		assert_eq!(
+			inbound_lane_state_from_storage(Some(inbound_lane_data)),
+			InboundLaneState {
+				last_confirmed_nonce: 5,
+				unrewarded_relayers: UnrewardedRelayersState {
+					unrewarded_relayer_entries: 2,
+					messages_in_oldest_entry: 1,
+					total_messages: 2,
+					last_delivered_nonce: 7,
+				},
+			},
+		);
+	}
+
 	#[test]
 	fn prepare_dummy_messages_proof_works() {
 		const DISPATCH_WEIGHT: Weight = 1_000_000;
@@ -592,4 +732,44 @@ mod tests {
 			) > (10 * Rialto::PAY_INBOUND_DISPATCH_FEE_WEIGHT_AT_CHAIN).into()
 		);
 	}
+
+	#[test]
+	fn delivery_fee_includes_dispatch_fee_payment_only_if_it_is_paid_at_target_chain() {
+		let conversion_rate = FixedU128::saturating_from_rational(150, 100);
+		let fee_multiplier = FixedU128::saturating_from_rational(110, 100);
+		let inclusion_fee: BalanceOf<Rialto> = 1_000_000_000_000;
+		let pay_dispatch_fee_cost = compute_prepaid_messages_refund::<Rialto>(10, fee_multiplier);
+		assert!(pay_dispatch_fee_cost > 0);
+
+		// all messages are paying for dispatch at the target chain => relayer pays for
+		// `PAY_INBOUND_DISPATCH_FEE_WEIGHT_AT_CHAIN` and it isn't refunded
+		let pay_at_target_cost = compute_delivery_fee_in_source_tokens::<Rococo, Rialto>(
+			conversion_rate,
+			inclusion_fee,
+			compute_prepaid_messages_refund::<Rialto>(0, fee_multiplier),
+		);
+		assert_eq!(
+			pay_at_target_cost,
+			convert_target_tokens_to_source_tokens::<Rococo, Rialto>(
+				conversion_rate,
+				inclusion_fee
+			),
+		);
+
+		// all messages have paid for dispatch at the source chain => cost of
+		// `PAY_INBOUND_DISPATCH_FEE_WEIGHT_AT_CHAIN` is refunded to relayer
+		let pay_at_source_cost = compute_delivery_fee_in_source_tokens::<Rococo, Rialto>(
+			conversion_rate,
+			inclusion_fee,
+			pay_dispatch_fee_cost,
+		);
+		assert_eq!(
+			pay_at_source_cost,
+			convert_target_tokens_to_source_tokens::<Rococo, Rialto>(
+				conversion_rate,
+				inclusion_fee - pay_dispatch_fee_cost,
+			),
+		);
+		assert!(pay_at_source_cost < pay_at_target_cost);
+	}
 }
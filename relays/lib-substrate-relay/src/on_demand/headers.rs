@@ -33,9 +33,11 @@ use relay_utils::{
 
 use crate::{
 	finality::{
+		fan_out::FinalityProofsFanOut,
 		source::{RequiredHeaderNumberRef, SubstrateFinalitySource},
 		target::SubstrateFinalityTarget,
-		SubstrateFinalitySyncPipeline, RECENT_FINALITY_PROOFS_LIMIT,
+		SubstrateFinalitySyncPipeline, RECENT_FINALITY_PROOFS_LIMIT, SOURCE_FINALITY_STALL_BLOCKS,
+		SOURCE_FINALITY_STALL_TIMEOUT,
 	},
 	on_demand::OnDemandRelay,
 	TransactionParams,
@@ -56,11 +58,15 @@ pub struct OnDemandHeadersRelay<SourceChain: Chain> {
 
 impl<SourceChain: Chain> OnDemandHeadersRelay<SourceChain> {
 	/// Create new on-demand headers relay.
+	///
+	/// If `finality_proofs_fan_out` is `Some(_)`, finality proofs are read from the shared
+	/// subscription. Otherwise, the relay subscribes to source chain justifications directly.
 	pub fn new<P: SubstrateFinalitySyncPipeline<SourceChain = SourceChain>>(
 		source_client: Client<P::SourceChain>,
 		target_client: Client<P::TargetChain>,
 		target_transaction_params: TransactionParams<AccountKeyPairOf<P::TransactionSignScheme>>,
 		only_mandatory_headers: bool,
+		finality_proofs_fan_out: Option<FinalityProofsFanOut<P::SourceChain, P::FinalityEngine>>,
 	) -> Self
 	where
 		AccountIdOf<P::TargetChain>:
@@ -79,6 +85,7 @@ impl<SourceChain: Chain> OnDemandHeadersRelay<SourceChain> {
 				target_transaction_params,
 				only_mandatory_headers,
 				required_header_number,
+				finality_proofs_fan_out,
 			)
 			.await;
 		});
@@ -114,6 +121,7 @@ async fn background_task<P: SubstrateFinalitySyncPipeline>(
 	target_transaction_params: TransactionParams<AccountKeyPairOf<P::TransactionSignScheme>>,
 	only_mandatory_headers: bool,
 	required_header_number: RequiredHeaderNumberRef<P::SourceChain>,
+	finality_proofs_fan_out: Option<FinalityProofsFanOut<P::SourceChain, P::FinalityEngine>>,
 ) where
 	AccountIdOf<P::TargetChain>:
 		From<<AccountKeyPairOf<P::TransactionSignScheme> as sp_core::Pair>::Public>,
@@ -125,17 +133,27 @@ async fn background_task<P: SubstrateFinalitySyncPipeline>(
 		source_client.clone(),
 		Some(required_header_number.clone()),
 	);
+	if let Some(finality_proofs_fan_out) = finality_proofs_fan_out {
+		finality_source = finality_source.with_finality_proofs_fan_out(finality_proofs_fan_out);
+	}
 	let mut finality_target =
 		SubstrateFinalityTarget::new(target_client.clone(), target_transaction_params);
 	let mut latest_non_mandatory_at_source = Zero::zero();
 
+	// there's no point in checking headers more often than they are produced at the source chain,
+	// so this is the same tick that is used by the finality relay itself
+	let tick = std::cmp::max(
+		P::SourceChain::AVERAGE_BLOCK_INTERVAL,
+		P::TargetChain::AVERAGE_BLOCK_INTERVAL,
+	);
+
 	let mut restart_relay = true;
 	let finality_relay_task = futures::future::Fuse::terminated();
 	futures::pin_mut!(finality_relay_task);
 
 	loop {
 		select! {
-			_ = async_std::task::sleep(P::TargetChain::AVERAGE_BLOCK_INTERVAL).fuse() => {},
+			_ = async_std::task::sleep(tick).fuse() => {},
 			_ = finality_relay_task => {
 				// this should never happen in practice given the current code
 				restart_relay = true;
@@ -270,13 +288,15 @@ async fn background_task<P: SubstrateFinalitySyncPipeline>(
 					finality_source.clone(),
 					finality_target.clone(),
 					FinalitySyncParams {
-						tick: std::cmp::max(
-							P::SourceChain::AVERAGE_BLOCK_INTERVAL,
-							P::TargetChain::AVERAGE_BLOCK_INTERVAL,
-						),
+						tick,
 						recent_finality_proofs_limit: RECENT_FINALITY_PROOFS_LIMIT,
 						stall_timeout,
 						only_mandatory_headers,
+						// on-demand relay submits (mostly mandatory) headers on demand, so there's
+						// nothing to batch
+						max_headers_in_batch: 1,
+						source_finality_stall_blocks: SOURCE_FINALITY_STALL_BLOCKS,
+						source_finality_stall_timeout: SOURCE_FINALITY_STALL_TIMEOUT,
 					},
 					MetricsParams::disabled(),
 					futures::future::pending(),
@@ -144,6 +144,12 @@ async fn background_task<P: SubstrateParachainsPipeline>(
 	let mut relay_state = RelayState::Idle;
 	let mut required_parachain_header_number = Zero::zero();
 	let required_para_header_number_ref = Arc::new(Mutex::new(AvailableHeader::Unavailable));
+	// new parachain heads only appear at the source relay chain blocks, so there's no point in
+	// checking them more often than relay chain blocks are produced
+	let tick = std::cmp::max(
+		P::SourceRelayChain::AVERAGE_BLOCK_INTERVAL,
+		P::TargetChain::AVERAGE_BLOCK_INTERVAL,
+	);
 
 	let mut restart_relay = true;
 	let parachains_relay_task = futures::future::Fuse::terminated();
@@ -181,7 +187,7 @@ async fn background_task<P: SubstrateParachainsPipeline>(
 					new_required_parachain_header_number,
 				);
 			},
-			_ = async_std::task::sleep(P::TargetChain::AVERAGE_BLOCK_INTERVAL).fuse() => {},
+			_ = async_std::task::sleep(tick).fuse() => {},
 			_ = parachains_relay_task => {
 				// this should never happen in practice given the current code
 				restart_relay = true;
@@ -283,7 +289,7 @@ async fn background_task<P: SubstrateParachainsPipeline>(
 					parachains_target.clone(),
 					ParachainSyncParams {
 						parachains: vec![P::SOURCE_PARACHAIN_PARA_ID.into()],
-						stall_timeout: std::time::Duration::from_secs(60),
+						stall_timeout,
 						strategy: parachains_relay::parachains_loop::ParachainSyncStrategy::Any,
 					},
 					MetricsParams::disabled(),
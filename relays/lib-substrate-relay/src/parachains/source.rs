@@ -20,18 +20,21 @@ use crate::parachains::{ParachainsPipelineAdapter, SubstrateParachainsPipeline};
 
 use async_std::sync::{Arc, Mutex};
 use async_trait::async_trait;
-use bp_parachains::parachain_head_storage_key_at_source;
-use bp_polkadot_core::parachains::{ParaHash, ParaHead, ParaHeadsProof, ParaId};
-use bp_runtime::HeaderIdProvider;
+use bp_polkadot_core::parachains::{
+	parachain_head_storage_key_at_source, ParaHash, ParaHead, ParaHeadsProof, ParaId,
+};
+use bp_runtime::{trim_storage_proof, HasherOf, HeaderIdProvider};
 use codec::Decode;
 use parachains_relay::{
 	parachains_loop::{AvailableHeader, SourceClient},
 	parachains_loop_metrics::ParachainsLoopMetrics,
 };
 use relay_substrate_client::{
-	Chain, Client, Error as SubstrateError, HeaderIdOf, HeaderOf, RelayChain,
+	metrics::ProofMetrics, Chain, Client, Error as SubstrateError, HeaderIdOf, HeaderOf, RelayChain,
 };
 use relay_utils::relay_loop::Client as RelayClient;
+use sp_runtime::traits::Header as HeaderT;
+use std::time::Instant;
 
 /// Shared updatable reference to the maximal parachain header id that we want to sync from the
 /// source.
@@ -42,6 +45,7 @@ pub type RequiredHeaderIdRef<C> = Arc<Mutex<AvailableHeader<HeaderIdOf<C>>>>;
 pub struct ParachainsSource<P: SubstrateParachainsPipeline> {
 	client: Client<P::SourceRelayChain>,
 	max_head_id: RequiredHeaderIdRef<P::SourceParachain>,
+	proof_metrics: Option<ProofMetrics>,
 }
 
 impl<P: SubstrateParachainsPipeline> ParachainsSource<P> {
@@ -50,7 +54,14 @@ impl<P: SubstrateParachainsPipeline> ParachainsSource<P> {
 		client: Client<P::SourceRelayChain>,
 		max_head_id: RequiredHeaderIdRef<P::SourceParachain>,
 	) -> Self {
-		ParachainsSource { client, max_head_id }
+		ParachainsSource { client, max_head_id, proof_metrics: None }
+	}
+
+	/// Observe generated parachain heads proofs using given metrics.
+	#[must_use]
+	pub fn with_proof_metrics(mut self, proof_metrics: ProofMetrics) -> Self {
+		self.proof_metrics = Some(proof_metrics);
+		self
 	}
 
 	/// Returns reference to the underlying RPC client.
@@ -72,7 +83,7 @@ impl<P: SubstrateParachainsPipeline> ParachainsSource<P> {
 			Some(para_head) => para_head,
 			None => return Ok(None),
 		};
-		let para_head: HeaderOf<P::SourceParachain> = Decode::decode(&mut &para_head.0[..])?;
+		let para_head: HeaderOf<P::SourceParachain> = para_head.decode_header()?;
 		Ok(Some(para_head.id()))
 	}
 }
@@ -155,12 +166,30 @@ where
 		let parachain = parachains[0];
 		let storage_key =
 			parachain_head_storage_key_at_source(P::SourceRelayChain::PARAS_PALLET_NAME, parachain);
-		let parachain_heads_proof = self
-			.client
-			.prove_storage(vec![storage_key.clone()], at_block.1)
-			.await?
-			.iter_nodes()
-			.collect();
+		let proof_started_at = Instant::now();
+		let state_root = *self.client.header_by_hash(at_block.1).await?.state_root();
+		let (parachain_heads_proof, parachain_heads_proof_size) =
+			trim_storage_proof::<HasherOf<P::SourceRelayChain>>(
+				state_root,
+				self.client.prove_storage(vec![storage_key.clone()], at_block.1).await?,
+				[&storage_key],
+			)
+			.map_err(SubstrateError::StorageProofError)?;
+		if let Some(ref proof_metrics) = self.proof_metrics {
+			proof_metrics.observe(proof_started_at.elapsed(), parachain_heads_proof_size.0);
+		}
+		// the proof is rejected by the target chain if it is too large, so let's not waste funds
+		// on submitting it
+		let parachain_heads_proof = ParaHeadsProof::try_new(
+			parachain_heads_proof,
+			P::SourceRelayChain::MAX_PARA_HEADS_PROOF_SIZE,
+		)
+		.map_err(|e| {
+			SubstrateError::Custom(format!(
+				"Proof of parachain {:?} head at {:?} is too large: {:?}",
+				parachain, at_block, e,
+			))
+		})?;
 
 		// why we're reading parachain head here once again (it has already been read at the
 		// `parachain_head`)? that's because `parachain_head` sometimes returns obsolete parachain
@@ -183,6 +212,6 @@ where
 			})?;
 		let parachain_head_hash = parachain_head.hash();
 
-		Ok((ParaHeadsProof(parachain_heads_proof), vec![parachain_head_hash]))
+		Ok((parachain_heads_proof, vec![parachain_head_hash]))
 	}
 }
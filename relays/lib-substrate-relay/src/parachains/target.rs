@@ -143,10 +143,10 @@ where
 				)
 				.await
 				.and_then(|maybe_encoded_head| match maybe_encoded_head {
-					Some(encoded_head) =>
-						HeaderOf::<P::SourceParachain>::decode(&mut &encoded_head.0[..])
-							.map(Some)
-							.map_err(Self::Error::ResponseParseFailed),
+					Some(encoded_head) => encoded_head
+						.decode_header::<HeaderOf<P::SourceParachain>>()
+						.map(Some)
+						.map_err(Self::Error::ResponseParseFailed),
 					None => Ok(None),
 				})
 				.map_err(|e| {
@@ -178,11 +178,13 @@ where
 		let genesis_hash = *self.client.genesis_hash();
 		let transaction_params = self.transaction_params.clone();
 		let (spec_version, transaction_version) = self.client.simple_runtime_version().await?;
-		let call = P::SubmitParachainHeadsCallBuilder::build_submit_parachain_heads_call(
-			at_relay_block,
-			updated_parachains,
-			proof,
-		);
+		let call = transaction_params.prepare_call::<P::TargetChain>(
+			P::SubmitParachainHeadsCallBuilder::build_submit_parachain_heads_call(
+				at_relay_block,
+				updated_parachains,
+				proof,
+			),
+		)?;
 		self.client
 			.submit_and_watch_signed_extrinsic(
 				self.transaction_params.signer.public().into(),
@@ -193,8 +195,9 @@ where
 					signer: transaction_params.signer,
 				},
 				move |best_block_id, transaction_nonce| {
-					Ok(UnsignedTransaction::new(call.into(), transaction_nonce)
-						.era(TransactionEra::new(best_block_id, transaction_params.mortality)))
+					Ok(UnsignedTransaction::new(call.into(), transaction_nonce).era(
+						TransactionEra::new_checked(best_block_id, transaction_params.mortality)?,
+					))
 				},
 			)
 			.await
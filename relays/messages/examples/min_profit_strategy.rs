@@ -0,0 +1,92 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Example of custom relay strategy, which delivers messages only if the relayer gets at least
+//! some minimal profit from the delivery.
+//!
+//! Usage: `min_profit_strategy [<min-profit>]`.
+//!
+//! The strategy may be plugged into the relay by passing
+//! `SharedRelayStrategy::from_boxed(Box::new(MinProfitStrategy { .. }))` as the
+//! `MessageDeliveryParams::relay_strategy`.
+
+use async_trait::async_trait;
+use bp_messages::LaneId;
+use bp_runtime::messages::DispatchFeePayment;
+use messages_relay::{
+	message_lane_loop::MessageDetails,
+	relay_strategy::{RelayDecisionContext, RelayStrategy, SharedRelayStrategy},
+};
+
+/// Balance type of the source chain.
+type Balance = u64;
+
+/// Relay strategy that only delivers messages if the relayer profit is at least `min_profit`.
+struct MinProfitStrategy {
+	/// Minimal profit of the single delivery transaction.
+	min_profit: Balance,
+}
+
+#[async_trait]
+impl RelayStrategy<Balance> for MinProfitStrategy {
+	async fn decide(&self, context: &RelayDecisionContext<Balance>) -> bool {
+		match context.total_cost {
+			Some(total_cost) => context.total_reward >= total_cost.saturating_add(self.min_profit),
+			None => false,
+		}
+	}
+
+	fn on_final_decision(&self, context: &RelayDecisionContext<Balance>) {
+		println!(
+			"Delivering messages {:?} at lane {:?}: total cost = {:?}, total reward = {:?}",
+			context.nonces, context.lane, context.total_cost, context.total_reward,
+		);
+	}
+}
+
+#[async_std::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+	let min_profit = std::env::args().nth(1).map(|arg| arg.parse()).transpose()?.unwrap_or(10);
+	let strategy: SharedRelayStrategy<Balance> =
+		SharedRelayStrategy::from_boxed(Box::new(MinProfitStrategy { min_profit }));
+
+	// in the real relay, the context is prepared by the messages relay
+	let context = RelayDecisionContext {
+		lane: LaneId([0, 0, 0, 0]),
+		nonces: 1..=1,
+		messages: vec![(
+			1,
+			MessageDetails {
+				dispatch_weight: 1_000,
+				size: 64,
+				reward: 100,
+				dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+			},
+		)]
+		.into_iter()
+		.collect(),
+		total_cost: Some(80),
+		total_reward: 100,
+	};
+
+	if strategy.decide(&context).await {
+		strategy.on_final_decision(&context);
+	} else {
+		println!("Messages {:?} are not profitable enough to deliver", context.nonces);
+	}
+
+	Ok(())
+}
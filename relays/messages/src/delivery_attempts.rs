@@ -0,0 +1,497 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Bookkeeping of message delivery attempts.
+//!
+//! When the message needs many delivery attempts, operators want to know why previous attempts
+//! have failed. The log remembers number of delivery attempts and several recent failures of
+//! every undelivered nonce. The log is bounded: only the `MAX_TRACKED_NONCES` lowest nonces are
+//! tracked and every nonce only keeps `MAX_FAILURES_PER_NONCE` recent failures, so the nonce that
+//! is failing forever never grows the log. Entries are pruned when nonces are confirmed.
+//!
+//! The log may be backed by the lane state file (see `persistent_state` module), so that delivery
+//! attempts survive relay restarts.
+
+use crate::persistent_state::{LaneStateFile, PersistedLaneState};
+
+use async_trait::async_trait;
+use bp_messages::MessageNonce;
+use codec::{Decode, Encode};
+use parking_lot::Mutex;
+use relay_utils::{TrackedTransactionStatus, TransactionTracker};
+use std::{
+	collections::{BTreeMap, VecDeque},
+	fmt::{Display, Formatter},
+	ops::RangeInclusive,
+	sync::Arc,
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Maximal number of nonces that are tracked by the log.
+const MAX_TRACKED_NONCES: usize = 1024;
+/// Maximal number of recent failures that are remembered for every nonce.
+const MAX_FAILURES_PER_NONCE: usize = 4;
+
+/// Reason of the delivery attempt failure.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Encode, Decode)]
+pub enum DeliveryFailure {
+	/// Source client has failed to generate messages proof.
+	ProofGeneration,
+	/// Target client has failed to submit delivery transaction.
+	Submission,
+	/// We have lost track of the delivery transaction.
+	TransactionLost,
+	/// Delivery transaction has been finalized, but messages have not been delivered.
+	TransactionFailed,
+}
+
+impl Display for DeliveryFailure {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		f.write_str(match *self {
+			DeliveryFailure::ProofGeneration => "proof-generation",
+			DeliveryFailure::Submission => "submission",
+			DeliveryFailure::TransactionLost => "transaction-lost",
+			DeliveryFailure::TransactionFailed => "transaction-failed",
+		})
+	}
+}
+
+/// Delivery attempts of the single nonce.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NonceDeliveryAttempts {
+	/// Number of delivery transactions, that have included the nonce.
+	pub attempts: u32,
+	/// Time of the last delivery attempt.
+	pub last_attempt: SystemTime,
+	/// Recent delivery failures, starting from the oldest one.
+	pub recent_failures: VecDeque<(SystemTime, DeliveryFailure)>,
+	/// True if delivery transaction has been finalized and we're waiting for the target nonces
+	/// to check whether it has been successful.
+	awaiting_outcome: bool,
+}
+
+impl NonceDeliveryAttempts {
+	/// Returns the last delivery failure.
+	pub fn last_failure(&self) -> Option<DeliveryFailure> {
+		self.recent_failures.back().map(|(_, failure)| *failure)
+	}
+
+	fn note_failure(&mut self, failure: DeliveryFailure) {
+		if self.recent_failures.len() == MAX_FAILURES_PER_NONCE {
+			self.recent_failures.pop_front();
+		}
+		self.recent_failures.push_back((SystemTime::now(), failure));
+		self.awaiting_outcome = false;
+	}
+}
+
+/// Delivery attempts of the single nonce, as they're stored in the lane state file.
+///
+/// Times are stored as number of seconds since the UNIX epoch.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub struct PersistedNonceDeliveryAttempts {
+	/// Message nonce.
+	pub nonce: MessageNonce,
+	/// Number of delivery transactions, that have included the nonce.
+	pub attempts: u32,
+	/// Time of the last delivery attempt.
+	pub last_attempt: u64,
+	/// Recent delivery failures, starting from the oldest one.
+	pub recent_failures: Vec<(u64, DeliveryFailure)>,
+}
+
+impl PersistedNonceDeliveryAttempts {
+	fn new(nonce: MessageNonce, entry: &NonceDeliveryAttempts) -> Self {
+		PersistedNonceDeliveryAttempts {
+			nonce,
+			attempts: entry.attempts,
+			last_attempt: to_unix_secs(entry.last_attempt),
+			recent_failures: entry
+				.recent_failures
+				.iter()
+				.map(|(time, failure)| (to_unix_secs(*time), *failure))
+				.collect(),
+		}
+	}
+
+	fn into_entry(self) -> NonceDeliveryAttempts {
+		let skip_failures = self.recent_failures.len().saturating_sub(MAX_FAILURES_PER_NONCE);
+		// we don't know the outcome of transactions that have been submitted before restart
+		NonceDeliveryAttempts {
+			attempts: self.attempts,
+			last_attempt: from_unix_secs(self.last_attempt),
+			recent_failures: self
+				.recent_failures
+				.into_iter()
+				.skip(skip_failures)
+				.map(|(time, failure)| (from_unix_secs(time), failure))
+				.collect(),
+			awaiting_outcome: false,
+		}
+	}
+}
+
+/// Log of message delivery attempts.
+///
+/// Cloning only clones references.
+#[derive(Clone, Default)]
+pub struct DeliveryAttemptsLog {
+	/// Tracked entries.
+	entries: Arc<Mutex<BTreeMap<MessageNonce, NonceDeliveryAttempts>>>,
+	/// Entries that have been loaded from the lane state file. They are merged into the log when
+	/// we first learn the latest confirmed nonce, so that already confirmed nonces are discarded.
+	restored: Arc<Mutex<Option<Vec<PersistedNonceDeliveryAttempts>>>>,
+	/// Lane state file, backing this log.
+	state_file: Option<Arc<LaneStateFile>>,
+}
+
+impl DeliveryAttemptsLog {
+	/// Create log, backed by the given lane state file.
+	///
+	/// Entries are loaded from the file (if it exists and is valid) and are merged into the log
+	/// on the first `prune_confirmed` call.
+	pub fn with_state_file(state_file: LaneStateFile) -> Self {
+		let restored = state_file.load().map(|state| state.delivery_attempts);
+		DeliveryAttemptsLog {
+			entries: Default::default(),
+			restored: Arc::new(Mutex::new(restored)),
+			state_file: Some(Arc::new(state_file)),
+		}
+	}
+
+	/// Save log entries to the lane state file, if the log is backed by the file.
+	pub fn persist(&self) {
+		let state_file = match self.state_file {
+			Some(ref state_file) => state_file,
+			None => return,
+		};
+
+		let delivery_attempts = self.persisted_entries();
+		if let Err(e) = state_file.save(&PersistedLaneState { delivery_attempts }) {
+			log::warn!(
+				target: "bridge",
+				"Failed to save relay state to {}: {:?}",
+				state_file.path().display(),
+				e,
+			);
+		}
+	}
+
+	/// Returns all tracked entries in the form, suitable for persisting.
+	pub fn persisted_entries(&self) -> Vec<PersistedNonceDeliveryAttempts> {
+		self.entries
+			.lock()
+			.iter()
+			.map(|(nonce, entry)| PersistedNonceDeliveryAttempts::new(*nonce, entry))
+			.collect()
+	}
+
+	/// Merge previously persisted entries into the log.
+	///
+	/// Entries of nonces that are already confirmed are discarded. Returns number of restored
+	/// entries.
+	fn restore(
+		&self,
+		persisted: Vec<PersistedNonceDeliveryAttempts>,
+		latest_confirmed_nonce: MessageNonce,
+	) -> usize {
+		let mut entries = self.entries.lock();
+		let mut restored = 0;
+		for persisted_entry in persisted {
+			if persisted_entry.nonce <= latest_confirmed_nonce ||
+				entries.contains_key(&persisted_entry.nonce)
+			{
+				continue
+			}
+			if entries.len() >= MAX_TRACKED_NONCES {
+				break
+			}
+
+			entries.insert(persisted_entry.nonce, persisted_entry.into_entry());
+			restored += 1;
+		}
+		restored
+	}
+
+	/// Note that we're submitting delivery transaction with given nonces.
+	///
+	/// Returns delivery attempts of the first nonce, if it has already been attempted before.
+	pub fn note_attempt(
+		&self,
+		nonces: RangeInclusive<MessageNonce>,
+	) -> Option<NonceDeliveryAttempts> {
+		let mut entries = self.entries.lock();
+		let previous_attempts = entries.get(nonces.start()).cloned();
+		let now = SystemTime::now();
+		for nonce in nonces {
+			if !entries.contains_key(&nonce) && entries.len() >= MAX_TRACKED_NONCES {
+				// we only track the lowest nonces, because they're blocking delivery of all other
+				// nonces
+				let max_tracked_nonce =
+					*entries.keys().next_back().expect("entries.len() > 0; qed");
+				if nonce > max_tracked_nonce {
+					break
+				}
+				entries.remove(&max_tracked_nonce);
+			}
+
+			let entry = entries.entry(nonce).or_insert_with(|| NonceDeliveryAttempts {
+				attempts: 0,
+				last_attempt: now,
+				recent_failures: VecDeque::new(),
+				awaiting_outcome: false,
+			});
+			entry.attempts = entry.attempts.saturating_add(1);
+			entry.last_attempt = now;
+			entry.awaiting_outcome = false;
+		}
+		previous_attempts
+	}
+
+	/// Note that the delivery attempt of given nonces has failed.
+	pub fn note_failure(&self, nonces: RangeInclusive<MessageNonce>, failure: DeliveryFailure) {
+		for (_, entry) in self.entries.lock().range_mut(nonces) {
+			entry.note_failure(failure);
+		}
+	}
+
+	/// Note that the delivery transaction with given nonces has been finalized.
+	pub fn note_transaction_finalized(&self, nonces: RangeInclusive<MessageNonce>) {
+		for (_, entry) in self.entries.lock().range_mut(nonces) {
+			entry.awaiting_outcome = true;
+		}
+	}
+
+	/// Note the latest nonce that has been received by the target chain.
+	///
+	/// All finalized attempts to deliver greater nonces are considered failed.
+	pub fn note_target_latest_received_nonce(&self, latest_received_nonce: MessageNonce) {
+		for (nonce, entry) in self.entries.lock().iter_mut() {
+			if entry.awaiting_outcome {
+				entry.awaiting_outcome = false;
+				if *nonce > latest_received_nonce {
+					entry.note_failure(DeliveryFailure::TransactionFailed);
+				}
+			}
+		}
+	}
+
+	/// Prune entries of all nonces up to given confirmed nonce.
+	///
+	/// Returns number of delivery attempts of every pruned nonce.
+	pub fn prune_confirmed(&self, latest_confirmed_nonce: MessageNonce) -> Vec<u32> {
+		if let Some(persisted) = self.restored.lock().take() {
+			let restored = self.restore(persisted, latest_confirmed_nonce);
+			log::info!(
+				target: "bridge",
+				"Restored delivery attempts of {} nonces from the relay state file",
+				restored,
+			);
+		}
+
+		let mut entries = self.entries.lock();
+		let retained = entries.split_off(&latest_confirmed_nonce.saturating_add(1));
+		std::mem::replace(&mut *entries, retained)
+			.into_values()
+			.map(|entry| entry.attempts)
+			.collect()
+	}
+
+	/// Returns all tracked entries.
+	#[cfg(test)]
+	pub fn entries(&self) -> Vec<(MessageNonce, NonceDeliveryAttempts)> {
+		self.entries
+			.lock()
+			.iter()
+			.map(|(nonce, entry)| (*nonce, entry.clone()))
+			.collect()
+	}
+}
+
+fn to_unix_secs(time: SystemTime) -> u64 {
+	time.duration_since(UNIX_EPOCH).map(|duration| duration.as_secs()).unwrap_or(0)
+}
+
+fn from_unix_secs(secs: u64) -> SystemTime {
+	UNIX_EPOCH + Duration::from_secs(secs)
+}
+
+/// Transaction tracker that notes delivery transaction outcome in the delivery attempts log.
+pub struct LoggedTransactionTracker<T> {
+	tracker: T,
+	nonces: RangeInclusive<MessageNonce>,
+	log: DeliveryAttemptsLog,
+}
+
+impl<T> LoggedTransactionTracker<T> {
+	/// Create new tracker.
+	pub fn new(tracker: T, nonces: RangeInclusive<MessageNonce>, log: DeliveryAttemptsLog) -> Self {
+		LoggedTransactionTracker { tracker, nonces, log }
+	}
+}
+
+#[async_trait]
+impl<T: TransactionTracker> TransactionTracker for LoggedTransactionTracker<T> {
+	type HeaderId = T::HeaderId;
+
+	async fn wait(self) -> TrackedTransactionStatus<Self::HeaderId> {
+		let status = self.tracker.wait().await;
+		match status {
+			TrackedTransactionStatus::Lost =>
+				self.log.note_failure(self.nonces, DeliveryFailure::TransactionLost),
+			TrackedTransactionStatus::Finalized(_) =>
+				self.log.note_transaction_finalized(self.nonces),
+		}
+		status
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn attempts_and_failures_are_recorded() {
+		let log = DeliveryAttemptsLog::default();
+		assert_eq!(log.note_attempt(1..=2), None);
+		log.note_failure(1..=2, DeliveryFailure::Submission);
+		let previous_attempts = log.note_attempt(1..=3).unwrap();
+		assert_eq!(previous_attempts.attempts, 1);
+		assert_eq!(previous_attempts.last_failure(), Some(DeliveryFailure::Submission));
+
+		let entries = log.entries();
+		assert_eq!(
+			entries
+				.iter()
+				.map(|(nonce, entry)| (*nonce, entry.attempts))
+				.collect::<Vec<_>>(),
+			vec![(1, 2), (2, 2), (3, 1)],
+		);
+		assert_eq!(entries[2].1.last_failure(), None);
+	}
+
+	#[test]
+	fn finalized_transaction_is_failed_if_nonces_are_not_received() {
+		let log = DeliveryAttemptsLog::default();
+		log.note_attempt(1..=4);
+		log.note_transaction_finalized(1..=4);
+		log.note_target_latest_received_nonce(2);
+
+		let failures = log
+			.entries()
+			.into_iter()
+			.map(|(nonce, entry)| (nonce, entry.last_failure()))
+			.collect::<Vec<_>>();
+		assert_eq!(
+			failures,
+			vec![
+				(1, None),
+				(2, None),
+				(3, Some(DeliveryFailure::TransactionFailed)),
+				(4, Some(DeliveryFailure::TransactionFailed)),
+			],
+		);
+
+		// outcome is only checked once
+		log.note_target_latest_received_nonce(2);
+		assert_eq!(log.entries()[2].1.recent_failures.len(), 1);
+	}
+
+	#[test]
+	fn entries_are_pruned_when_nonces_are_confirmed() {
+		let log = DeliveryAttemptsLog::default();
+		log.note_attempt(1..=3);
+		log.note_attempt(1..=1);
+		assert_eq!(log.prune_confirmed(2), vec![2, 1]);
+		assert_eq!(log.entries().len(), 1);
+		assert_eq!(log.prune_confirmed(2), Vec::<u32>::new());
+		assert_eq!(log.prune_confirmed(10), vec![1]);
+		assert!(log.entries().is_empty());
+	}
+
+	#[test]
+	fn permanently_failing_nonce_does_not_grow_the_log() {
+		let log = DeliveryAttemptsLog::default();
+		for _ in 0..10_000 {
+			log.note_attempt(1..=1);
+			log.note_failure(1..=1, DeliveryFailure::TransactionLost);
+		}
+
+		let entries = log.entries();
+		assert_eq!(entries.len(), 1);
+		assert_eq!(entries[0].1.attempts, 10_000);
+		assert_eq!(entries[0].1.recent_failures.len(), MAX_FAILURES_PER_NONCE);
+	}
+
+	#[test]
+	fn only_lowest_nonces_are_tracked() {
+		let log = DeliveryAttemptsLog::default();
+		let max_nonce = MAX_TRACKED_NONCES as MessageNonce;
+		log.note_attempt(100..=100 + max_nonce);
+		assert_eq!(log.entries().len(), MAX_TRACKED_NONCES);
+		assert_eq!(log.entries().last().unwrap().0, 99 + max_nonce);
+
+		// lower nonces are evicting higher nonces
+		log.note_attempt(1..=10);
+		let entries = log.entries();
+		assert_eq!(entries.len(), MAX_TRACKED_NONCES);
+		assert_eq!(entries.first().unwrap().0, 1);
+		assert_eq!(entries.last().unwrap().0, 89 + max_nonce);
+	}
+
+	#[test]
+	fn persisted_entries_are_restored() {
+		let log = DeliveryAttemptsLog::default();
+		log.note_attempt(1..=3);
+		log.note_failure(1..=2, DeliveryFailure::Submission);
+		log.note_attempt(1..=1);
+		log.note_failure(1..=1, DeliveryFailure::TransactionLost);
+		let persisted = log.persisted_entries();
+
+		let restored_log = DeliveryAttemptsLog::default();
+		assert_eq!(restored_log.restore(persisted.clone(), 0), 3);
+		assert_eq!(restored_log.persisted_entries(), persisted);
+		assert_eq!(
+			restored_log.entries()[0].1.last_failure(),
+			Some(DeliveryFailure::TransactionLost)
+		);
+	}
+
+	#[test]
+	fn stale_persisted_nonces_are_discarded() {
+		let log = DeliveryAttemptsLog::default();
+		log.note_attempt(1..=5);
+		let persisted = log.persisted_entries();
+
+		// nonces 1..=3 have been confirmed while relay was offline
+		let restored_log = DeliveryAttemptsLog {
+			restored: Arc::new(Mutex::new(Some(persisted))),
+			..Default::default()
+		};
+		assert_eq!(restored_log.prune_confirmed(3), Vec::<u32>::new());
+		assert_eq!(
+			restored_log.entries().into_iter().map(|(nonce, _)| nonce).collect::<Vec<_>>(),
+			vec![4, 5],
+		);
+
+		// persisted entries are only restored once
+		assert_eq!(restored_log.prune_confirmed(4), vec![1]);
+		assert_eq!(
+			restored_log.entries().into_iter().map(|(nonce, _)| nonce).collect::<Vec<_>>(),
+			vec![5],
+		);
+	}
+}
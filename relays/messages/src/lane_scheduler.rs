@@ -0,0 +1,470 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Scheduler of message delivery transactions, submitted by the same signer at multiple lanes.
+//!
+//! Before submitting delivery transaction, the lane must acquire submission slot from the
+//! scheduler. The slot is held until the transaction is either finalized or lost. When there are
+//! no free slots, lanes are waiting in queues and every released slot is given to the waiting
+//! lane that has been served the least, relative to its weight. Idle lanes are not occupying
+//! any slots, so busy lanes may use all slots when other lanes have nothing to deliver.
+
+use async_trait::async_trait;
+use bp_messages::LaneId;
+use futures::channel::oneshot;
+use parking_lot::Mutex;
+use relay_utils::{
+	metrics::{metric_name, register, GaugeVec, Metric, Opts, PrometheusError, Registry, U64},
+	TrackedTransactionStatus, TransactionTracker,
+};
+use std::{
+	collections::{BTreeMap, VecDeque},
+	fmt::Debug,
+	sync::Arc,
+	time::{Duration, Instant},
+};
+
+/// Weight of the lane that has no explicitly configured weight.
+pub const DEFAULT_LANE_WEIGHT: u32 = 1;
+
+/// Window that is used to compute lane submissions rate.
+const SUBMISSIONS_RATE_WINDOW: Duration = Duration::from_secs(60);
+/// Every submission advances lane pass by `PASS_STRIDE / lane_weight`.
+const PASS_STRIDE: u64 = 1_000_000;
+
+/// Submission slots scheduler, shared by all lanes that are using the same signer.
+///
+/// Cloning only clones references.
+#[derive(Clone)]
+pub struct LaneSubmissionScheduler {
+	state: Arc<Mutex<SchedulerState>>,
+	metrics: Option<LaneSchedulerMetrics>,
+}
+
+impl LaneSubmissionScheduler {
+	/// Create new scheduler with given number of submission slots and weights of lanes.
+	///
+	/// Lanes that are not in the `lane_weights` are served with the `DEFAULT_LANE_WEIGHT`.
+	pub fn new(max_in_flight_submissions: usize, lane_weights: &[(LaneId, u32)]) -> Self {
+		LaneSubmissionScheduler {
+			state: Arc::new(Mutex::new(SchedulerState {
+				free_slots: std::cmp::max(max_in_flight_submissions, 1),
+				virtual_time: 0,
+				lanes: lane_weights
+					.iter()
+					.map(|(lane, weight)| (*lane, LaneState::new(*weight)))
+					.collect(),
+			})),
+			metrics: None,
+		}
+	}
+
+	/// Create and register scheduler metrics.
+	pub fn with_metrics(
+		mut self,
+		prefix: Option<&str>,
+		registry: &Registry,
+	) -> Result<Self, PrometheusError> {
+		let metrics = LaneSchedulerMetrics::new(prefix)?;
+		metrics.register(registry)?;
+		self.metrics = Some(metrics);
+		Ok(self)
+	}
+
+	/// Wait until submission slot is available for the given lane.
+	///
+	/// Slots are given to the same lane in the order they are requested.
+	pub async fn acquire(&self, lane: LaneId) -> LaneSubmissionSlot {
+		let slot_receiver = {
+			let mut state = self.state.lock();
+			let (slot_sender, slot_receiver) = oneshot::channel();
+			state.enqueue(lane, slot_sender);
+			self.dispatch(&mut state);
+			slot_receiver
+		};
+
+		slot_receiver.await.expect(
+			"sender is only dropped after sending the slot or when receiver is dropped; qed",
+		)
+	}
+
+	/// Give free slots to waiting lanes.
+	fn dispatch(&self, state: &mut SchedulerState) {
+		let now = Instant::now();
+		while state.free_slots > 0 {
+			let lane = match state.next_waiting_lane() {
+				Some(lane) => lane,
+				None => break,
+			};
+			let lane_state =
+				state.lanes.get_mut(&lane).expect("lane is selected from the map; qed");
+			let slot_sender = lane_state
+				.waiters
+				.pop_front()
+				.expect("only lanes with waiters are selected; qed");
+			let slot = LaneSubmissionSlot { scheduler: Some(self.clone()), lane };
+			match slot_sender.send(slot) {
+				Ok(()) => {
+					state.free_slots -= 1;
+					state.virtual_time = lane_state.pass;
+					lane_state.pass += PASS_STRIDE / lane_state.weight as u64;
+					lane_state.submissions.push_back(now);
+				},
+				Err(mut slot) => {
+					// the acquirer has gone away => the slot is still free
+					slot.scheduler = None;
+				},
+			}
+		}
+
+		if let Some(ref metrics) = self.metrics {
+			for (lane, lane_state) in state.lanes.iter_mut() {
+				while lane_state
+					.submissions
+					.front()
+					.map(|submitted_at| now.duration_since(*submitted_at) > SUBMISSIONS_RATE_WINDOW)
+					.unwrap_or(false)
+				{
+					lane_state.submissions.pop_front();
+				}
+				metrics.update_lane_submissions_per_minute(lane, lane_state.submissions.len());
+			}
+		}
+	}
+
+	/// Return slot back to the scheduler.
+	fn release(&self) {
+		let mut state = self.state.lock();
+		state.free_slots += 1;
+		self.dispatch(&mut state);
+	}
+}
+
+impl Debug for LaneSubmissionScheduler {
+	fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let state = self.state.lock();
+		fmt.debug_struct("LaneSubmissionScheduler")
+			.field("free_slots", &state.free_slots)
+			.field(
+				"lane_weights",
+				&state
+					.lanes
+					.iter()
+					.map(|(lane, state)| (*lane, state.weight))
+					.collect::<Vec<_>>(),
+			)
+			.finish()
+	}
+}
+
+/// Submission slot that is held by the lane. The slot is released when dropped.
+#[derive(Debug)]
+pub struct LaneSubmissionSlot {
+	scheduler: Option<LaneSubmissionScheduler>,
+	lane: LaneId,
+}
+
+impl LaneSubmissionSlot {
+	/// Lane that holds the slot.
+	pub fn lane(&self) -> LaneId {
+		self.lane
+	}
+}
+
+impl Drop for LaneSubmissionSlot {
+	fn drop(&mut self) {
+		if let Some(scheduler) = self.scheduler.take() {
+			scheduler.release();
+		}
+	}
+}
+
+/// Transaction tracker that holds submission slot until the transaction is finalized or lost.
+pub struct ScheduledTransactionTracker<T> {
+	tracker: T,
+	slot: Option<LaneSubmissionSlot>,
+}
+
+impl<T> ScheduledTransactionTracker<T> {
+	/// Create new tracker.
+	pub fn new(tracker: T, slot: Option<LaneSubmissionSlot>) -> Self {
+		ScheduledTransactionTracker { tracker, slot }
+	}
+}
+
+#[async_trait]
+impl<T: TransactionTracker> TransactionTracker for ScheduledTransactionTracker<T> {
+	type HeaderId = T::HeaderId;
+
+	async fn wait(self) -> TrackedTransactionStatus<Self::HeaderId> {
+		let status = self.tracker.wait().await;
+		drop(self.slot);
+		status
+	}
+}
+
+/// Scheduler state.
+struct SchedulerState {
+	/// Number of free submission slots.
+	free_slots: usize,
+	/// Pass of the lane that has been served last.
+	virtual_time: u64,
+	/// State of all known lanes.
+	lanes: BTreeMap<LaneId, LaneState>,
+}
+
+impl SchedulerState {
+	/// Add waiter to the lane queue.
+	fn enqueue(&mut self, lane: LaneId, slot_sender: oneshot::Sender<LaneSubmissionSlot>) {
+		let virtual_time = self.virtual_time;
+		let lane_state =
+			self.lanes.entry(lane).or_insert_with(|| LaneState::new(DEFAULT_LANE_WEIGHT));
+		// lane that has been idle must not use slots that it has 'saved' while it had nothing
+		// to deliver
+		if lane_state.waiters.is_empty() {
+			lane_state.pass = std::cmp::max(lane_state.pass, virtual_time);
+		}
+		lane_state.waiters.push_back(slot_sender);
+	}
+
+	/// Select waiting lane with the smallest pass.
+	fn next_waiting_lane(&self) -> Option<LaneId> {
+		self.lanes
+			.iter()
+			.filter(|(_, lane_state)| !lane_state.waiters.is_empty())
+			.min_by_key(|(_, lane_state)| lane_state.pass)
+			.map(|(lane, _)| *lane)
+	}
+}
+
+/// State of the single lane.
+struct LaneState {
+	/// Lane weight.
+	weight: u32,
+	/// Lane pass. Waiting lane with the smallest pass is served first.
+	pass: u64,
+	/// Lane submissions that are waiting for the free slot.
+	waiters: VecDeque<oneshot::Sender<LaneSubmissionSlot>>,
+	/// Moments when lane has received submission slots within the `SUBMISSIONS_RATE_WINDOW`.
+	submissions: VecDeque<Instant>,
+}
+
+impl LaneState {
+	fn new(weight: u32) -> Self {
+		LaneState {
+			weight: std::cmp::max(weight, 1),
+			pass: 0,
+			waiters: VecDeque::new(),
+			submissions: VecDeque::new(),
+		}
+	}
+}
+
+/// Lane scheduler metrics.
+///
+/// Cloning only clones references.
+#[derive(Clone)]
+struct LaneSchedulerMetrics {
+	/// Number of submission slots that have been given to every lane within last minute.
+	lane_submissions_per_minute: GaugeVec<U64>,
+}
+
+impl LaneSchedulerMetrics {
+	fn new(prefix: Option<&str>) -> Result<Self, PrometheusError> {
+		Ok(LaneSchedulerMetrics {
+			lane_submissions_per_minute: GaugeVec::new(
+				Opts::new(
+					metric_name(prefix, "lane_submissions_per_minute"),
+					"Number of delivery transactions submitted at the lane within last minute",
+				),
+				&["lane"],
+			)?,
+		})
+	}
+
+	fn update_lane_submissions_per_minute(&self, lane: &LaneId, submissions: usize) {
+		self.lane_submissions_per_minute
+			.with_label_values(&[&hex::encode(lane)])
+			.set(submissions as u64);
+	}
+}
+
+impl Metric for LaneSchedulerMetrics {
+	fn register(&self, registry: &Registry) -> Result<(), PrometheusError> {
+		register(self.lane_submissions_per_minute.clone(), registry)?;
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use futures::{future::FutureExt, task::noop_waker_ref};
+	use std::{
+		future::Future,
+		pin::Pin,
+		task::{Context, Poll},
+	};
+
+	const LANE_A: LaneId = LaneId([0, 0, 0, 0]);
+	const LANE_B: LaneId = LaneId([0, 0, 0, 1]);
+
+	/// Slot that has been requested by the lane.
+	struct PendingSlot {
+		future: Option<Pin<Box<dyn Future<Output = LaneSubmissionSlot> + Send>>>,
+		slot: Option<LaneSubmissionSlot>,
+	}
+
+	impl PendingSlot {
+		fn poll(&mut self) {
+			if let Some(ref mut future) = self.future {
+				if let Poll::Ready(slot) =
+					future.as_mut().poll(&mut Context::from_waker(noop_waker_ref()))
+				{
+					self.future = None;
+					self.slot = Some(slot);
+				}
+			}
+		}
+
+		fn take(&mut self) -> Option<LaneSubmissionSlot> {
+			self.poll();
+			self.slot.take()
+		}
+	}
+
+	fn request_slot(scheduler: &LaneSubmissionScheduler, lane: LaneId) -> PendingSlot {
+		let scheduler = scheduler.clone();
+		let mut pending_slot = PendingSlot {
+			future: Some(async move { scheduler.acquire(lane).await }.boxed()),
+			slot: None,
+		};
+		// register in the lane queue
+		pending_slot.poll();
+		pending_slot
+	}
+
+	/// Simulates `submissions` deliveries using single slot, where both lanes always have
+	/// something to deliver. Returns lanes in order they have been served.
+	fn serve_busy_lanes(scheduler: &LaneSubmissionScheduler, submissions: usize) -> Vec<LaneId> {
+		let mut served = Vec::new();
+		let mut pending_a = request_slot(scheduler, LANE_A);
+		let mut pending_b = request_slot(scheduler, LANE_B);
+		let mut slot = pending_a.take().or_else(|| pending_b.take());
+		while served.len() < submissions {
+			let current_slot = slot.take().expect("one lane always holds the slot");
+			served.push(current_slot.lane());
+			// lane immediately has another batch to deliver
+			if current_slot.lane() == LANE_A {
+				pending_a = request_slot(scheduler, LANE_A);
+			} else {
+				pending_b = request_slot(scheduler, LANE_B);
+			}
+			// transaction is finalized => slot is released
+			drop(current_slot);
+			slot = pending_a.take().or_else(|| pending_b.take());
+		}
+		served
+	}
+
+	#[test]
+	fn slot_is_granted_immediately_when_available() {
+		let scheduler = LaneSubmissionScheduler::new(2, &[]);
+		let _slot_a = request_slot(&scheduler, LANE_A).take().unwrap();
+		let _slot_b = futures::executor::block_on(scheduler.acquire(LANE_B));
+	}
+
+	#[test]
+	fn busy_lane_is_not_starving_other_lanes() {
+		let scheduler = LaneSubmissionScheduler::new(1, &[]);
+
+		// lane A has a huge backlog and it is holding the only slot
+		let slot_a = futures::executor::block_on(scheduler.acquire(LANE_A));
+		let mut pending_a = request_slot(&scheduler, LANE_A);
+		let mut pending_a2 = request_slot(&scheduler, LANE_A);
+		// lane B has a tiny message to deliver
+		let mut pending_b = request_slot(&scheduler, LANE_B);
+
+		// when lane A transaction is finalized, the slot goes to lane B, not to lane A
+		drop(slot_a);
+		assert!(pending_a.take().is_none());
+		let slot_b = pending_b.take().unwrap();
+		assert_eq!(slot_b.lane(), LANE_B);
+
+		// and then lane A continues delivering in order
+		drop(slot_b);
+		let slot_a = pending_a.take().unwrap();
+		assert!(pending_a2.take().is_none());
+		drop(slot_a);
+		assert!(pending_a2.take().is_some());
+	}
+
+	#[test]
+	fn slots_are_shared_according_to_lane_weights() {
+		let scheduler = LaneSubmissionScheduler::new(1, &[(LANE_A, 3), (LANE_B, 1)]);
+		let served = serve_busy_lanes(&scheduler, 40);
+		assert_eq!(served.iter().filter(|lane| **lane == LANE_A).count(), 30);
+		assert_eq!(served.iter().filter(|lane| **lane == LANE_B).count(), 10);
+	}
+
+	#[test]
+	fn equal_weights_lead_to_round_robin() {
+		let scheduler = LaneSubmissionScheduler::new(1, &[]);
+		let served = serve_busy_lanes(&scheduler, 6);
+		assert_eq!(served, vec![LANE_A, LANE_B, LANE_A, LANE_B, LANE_A, LANE_B]);
+	}
+
+	#[test]
+	fn busy_lane_uses_all_slots_when_other_lanes_are_idle() {
+		let scheduler = LaneSubmissionScheduler::new(3, &[(LANE_A, 1), (LANE_B, 100)]);
+
+		// lane B is idle, so lane A may use all slots
+		let slots = (0..3)
+			.map(|_| futures::executor::block_on(scheduler.acquire(LANE_A)))
+			.collect::<Vec<_>>();
+		let mut pending_a = request_slot(&scheduler, LANE_A);
+		drop(slots);
+		assert!(pending_a.take().is_some());
+	}
+
+	#[test]
+	fn idle_lane_does_not_accumulate_credit() {
+		let scheduler = LaneSubmissionScheduler::new(1, &[]);
+
+		// lane A delivers alone for a while
+		for _ in 0..10 {
+			drop(futures::executor::block_on(scheduler.acquire(LANE_A)));
+		}
+
+		// when lane B wakes up, lanes are served in turns, instead of lane B being served
+		// 10 times in a row
+		let served = serve_busy_lanes(&scheduler, 6);
+		assert_eq!(served.iter().filter(|lane| **lane == LANE_B).count(), 3);
+	}
+
+	#[test]
+	fn slot_is_not_lost_when_waiter_has_gone() {
+		let scheduler = LaneSubmissionScheduler::new(1, &[]);
+		let slot_a = futures::executor::block_on(scheduler.acquire(LANE_A));
+		let pending_b = request_slot(&scheduler, LANE_B);
+		let mut pending_a = request_slot(&scheduler, LANE_A);
+
+		// lane B loop has been restarted
+		drop(pending_b);
+		drop(slot_a);
+		assert!(pending_a.take().is_some());
+	}
+}
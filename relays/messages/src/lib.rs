@@ -27,11 +27,14 @@
 
 mod metrics;
 
+pub mod lane_scheduler;
 pub mod message_lane;
 pub mod message_lane_loop;
 pub mod relay_strategy;
 
+mod delivery_attempts;
 mod message_race_delivery;
 mod message_race_loop;
 mod message_race_receiving;
 mod message_race_strategy;
+mod persistent_state;
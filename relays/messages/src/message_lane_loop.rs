@@ -24,7 +24,10 @@
 //! finalized header. I.e. when talking about headers in lane context, we
 //! only care about finalized headers.
 
-use std::{collections::BTreeMap, fmt::Debug, future::Future, ops::RangeInclusive, time::Duration};
+use std::{
+	collections::BTreeMap, fmt::Debug, future::Future, ops::RangeInclusive, path::PathBuf,
+	time::Duration,
+};
 
 use async_trait::async_trait;
 use futures::{channel::mpsc::unbounded, future::FutureExt, stream::StreamExt};
@@ -37,16 +40,19 @@ use relay_utils::{
 };
 
 use crate::{
+	delivery_attempts::DeliveryAttemptsLog,
+	lane_scheduler::LaneSubmissionScheduler,
 	message_lane::{MessageLane, SourceHeaderIdOf, TargetHeaderIdOf},
 	message_race_delivery::run as run_message_delivery_race,
 	message_race_receiving::run as run_message_receiving_race,
 	metrics::MessageLaneLoopMetrics,
-	relay_strategy::RelayStrategy,
+	persistent_state::LaneStateFile,
+	relay_strategy::SharedRelayStrategy,
 };
 
 /// Message lane loop configuration params.
 #[derive(Debug, Clone)]
-pub struct Params<Strategy: RelayStrategy> {
+pub struct Params<SourceChainBalance> {
 	/// Id of lane this loop is servicing.
 	pub lane: LaneId,
 	/// Interval at which we ask target node about its updates.
@@ -56,7 +62,7 @@ pub struct Params<Strategy: RelayStrategy> {
 	/// Delay between moments when connection error happens and our reconnect attempt.
 	pub reconnect_delay: Duration,
 	/// Message delivery race parameters.
-	pub delivery_params: MessageDeliveryParams<Strategy>,
+	pub delivery_params: MessageDeliveryParams<SourceChainBalance>,
 }
 
 /// Relayer operating mode.
@@ -71,7 +77,7 @@ pub enum RelayerMode {
 
 /// Message delivery race parameters.
 #[derive(Debug, Clone)]
-pub struct MessageDeliveryParams<Strategy: RelayStrategy> {
+pub struct MessageDeliveryParams<SourceChainBalance> {
 	/// Maximal number of unconfirmed relayer entries at the inbound lane. If there's that number
 	/// of entries in the `InboundLaneData::relayers` set, all new messages will be rejected until
 	/// reward payment will be proved (by including outbound lane state to the message delivery
@@ -87,8 +93,47 @@ pub struct MessageDeliveryParams<Strategy: RelayStrategy> {
 	pub max_messages_weight_in_single_batch: Weight,
 	/// Maximal cumulative size of relayed messages in single delivery transaction.
 	pub max_messages_size_in_single_batch: u32,
+	/// Parameters that define when outbound lane state proof is included into the message
+	/// delivery transaction.
+	pub outbound_state_proof_params: OutboundStateProofParams,
 	/// Relay strategy
-	pub relay_strategy: Strategy,
+	pub relay_strategy: SharedRelayStrategy<SourceChainBalance>,
+	/// Scheduler of delivery transactions, shared by all lanes that are using the same signer.
+	/// If `None`, delivery transactions are submitted as soon as they're ready.
+	pub submission_scheduler: Option<LaneSubmissionScheduler>,
+	/// Directory where relay-local lane state (e.g. the delivery attempts log) is persisted
+	/// between relay restarts. If `None`, the state is not persisted.
+	pub persistent_state_dir: Option<PathBuf>,
+}
+
+/// Parameters that define when outbound lane state proof is included into the message delivery
+/// transaction.
+///
+/// The outbound lane state carries the latest nonce that has been confirmed at the source chain.
+/// The target chain uses it to prune its unrewarded relayers set. Including the proof increases
+/// the transaction size and weight, so we only include it if the target chain would reject new
+/// messages without it, or if one of the following thresholds has been reached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutboundStateProofParams {
+	/// Proof is included if the number of unrewarded relayer entries at the target is at least
+	/// this percent of `max_unrewarded_relayer_entries_at_target`.
+	pub unrewarded_relayer_entries_threshold_percent: MessageNonce,
+	/// Proof is included if the number of unrewarded messages at the target is at least this
+	/// percent of `max_unconfirmed_nonces_at_target`.
+	pub unrewarded_messages_threshold_percent: MessageNonce,
+	/// Proof is included if it hasn't been included in that number of previous message delivery
+	/// transactions.
+	pub max_deliveries_without_proof: u32,
+}
+
+impl Default for OutboundStateProofParams {
+	fn default() -> Self {
+		OutboundStateProofParams {
+			unrewarded_relayer_entries_threshold_percent: 50,
+			unrewarded_messages_threshold_percent: 50,
+			max_deliveries_without_proof: 16,
+		}
+	}
 }
 
 /// Message details.
@@ -109,7 +154,7 @@ pub type MessageDetailsMap<SourceChainBalance> =
 	BTreeMap<MessageNonce, MessageDetails<SourceChainBalance>>;
 
 /// Message delivery race proof parameters.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct MessageProofParameters {
 	/// Include outbound lane state proof?
 	pub outbound_state_proof_required: bool,
@@ -164,6 +209,16 @@ pub trait SourceClient<P: MessageLane>: RelayClient {
 		proof_parameters: MessageProofParameters,
 	) -> Result<(SourceHeaderIdOf<P>, RangeInclusive<MessageNonce>, P::MessagesProof), Self::Error>;
 
+	/// Returns true if given error means that the state, required to generate proof, has already
+	/// been pruned by the node.
+	fn is_pruned_state_error(&self, error: &Self::Error) -> bool;
+
+	/// Ensure that given header is still an ancestor of the best finalized header.
+	///
+	/// Returns error if some other header has been finalized at the same height, which means that
+	/// the finality of the source chain has been violated.
+	async fn ensure_finalized_ancestor(&self, id: SourceHeaderIdOf<P>) -> Result<(), Self::Error>;
+
 	/// Submit messages receiving proof.
 	async fn submit_messages_receiving_proof(
 		&self,
@@ -272,14 +327,22 @@ pub fn metrics_prefix<P: MessageLane>(lane: &LaneId) -> String {
 }
 
 /// Run message lane service loop.
-pub async fn run<P: MessageLane, Strategy: RelayStrategy>(
-	params: Params<Strategy>,
+pub async fn run<P: MessageLane>(
+	params: Params<P::SourceChainBalance>,
 	source_client: impl SourceClient<P>,
 	target_client: impl TargetClient<P>,
 	metrics_params: MetricsParams,
 	exit_signal: impl Future<Output = ()> + Send + 'static,
 ) -> Result<(), relay_utils::Error> {
 	let exit_signal = exit_signal.shared();
+	// the log outlives reconnects, so that attempts are not forgotten when connection is lost
+	let delivery_attempts = match params.delivery_params.persistent_state_dir {
+		Some(ref dir) => DeliveryAttemptsLog::with_state_file(LaneStateFile::new(
+			dir,
+			&metrics_prefix::<P>(&params.lane),
+		)),
+		None => DeliveryAttemptsLog::default(),
+	};
 	relay_utils::relay_loop(source_client, target_client)
 		.reconnect_delay(params.reconnect_delay)
 		.with_metrics(metrics_params)
@@ -296,6 +359,7 @@ pub async fn run<P: MessageLane, Strategy: RelayStrategy>(
 				source_client,
 				target_client,
 				metrics,
+				delivery_attempts.clone(),
 				exit_signal.clone(),
 			)
 		})
@@ -304,16 +368,12 @@ pub async fn run<P: MessageLane, Strategy: RelayStrategy>(
 
 /// Run one-way message delivery loop until connection with target or source node is lost, or exit
 /// signal is received.
-async fn run_until_connection_lost<
-	P: MessageLane,
-	Strategy: RelayStrategy,
-	SC: SourceClient<P>,
-	TC: TargetClient<P>,
->(
-	params: Params<Strategy>,
+async fn run_until_connection_lost<P: MessageLane, SC: SourceClient<P>, TC: TargetClient<P>>(
+	params: Params<P::SourceChainBalance>,
 	source_client: SC,
 	target_client: TC,
 	metrics_msg: Option<MessageLaneLoopMetrics>,
+	delivery_attempts: DeliveryAttemptsLog,
 	exit_signal: impl Future<Output = ()>,
 ) -> Result<(), FailedClient> {
 	let mut source_retry_backoff = retry_backoff();
@@ -330,6 +390,9 @@ async fn run_until_connection_lost<
 	let target_go_offline_future = futures::future::Fuse::terminated();
 	let target_tick_stream = interval(params.target_tick).fuse();
 
+	// races are receiving updates from both clients, so they're ticking at the slowest rate
+	let race_tick = std::cmp::max(params.source_tick, params.target_tick);
+
 	let (
 		(delivery_source_state_sender, delivery_source_state_receiver),
 		(delivery_target_state_sender, delivery_target_state_receiver),
@@ -340,7 +403,10 @@ async fn run_until_connection_lost<
 		target_client.clone(),
 		delivery_target_state_receiver,
 		metrics_msg.clone(),
+		delivery_attempts,
+		params.lane,
 		params.delivery_params,
+		race_tick,
 	)
 	.fuse();
 
@@ -354,6 +420,7 @@ async fn run_until_connection_lost<
 		target_client.clone(),
 		receiving_target_state_receiver,
 		metrics_msg.clone(),
+		race_tick,
 	)
 	.fuse();
 
@@ -554,6 +621,10 @@ pub(crate) mod tests {
 		source_latest_generated_nonce: MessageNonce,
 		source_latest_confirmed_received_nonce: MessageNonce,
 		source_tracked_transaction_status: TrackedTransactionStatus<TestTargetHeaderId>,
+		source_pruned_state_below: TestSourceHeaderNumber,
+		is_source_state_pruned: bool,
+		source_non_canonical_headers: Vec<TestSourceHeaderId>,
+		source_proofs_generated_at: Vec<TestSourceHeaderId>,
 		submitted_messages_receiving_proofs: Vec<TestMessagesReceivingProof>,
 		is_target_fails: bool,
 		is_target_reconnected: bool,
@@ -580,6 +651,10 @@ pub(crate) mod tests {
 					0,
 					Default::default(),
 				)),
+				source_pruned_state_below: 0,
+				is_source_state_pruned: false,
+				source_non_canonical_headers: Vec::new(),
+				source_proofs_generated_at: Vec::new(),
 				submitted_messages_receiving_proofs: Vec::new(),
 				is_target_fails: false,
 				is_target_reconnected: false,
@@ -699,6 +774,11 @@ pub(crate) mod tests {
 		> {
 			let mut data = self.data.lock();
 			(self.tick)(&mut data);
+			if id.0 < data.source_pruned_state_below {
+				data.is_source_state_pruned = true;
+				return Err(TestError)
+			}
+			data.source_proofs_generated_at.push(id);
 			(self.post_tick)(&mut data);
 			Ok((
 				id,
@@ -714,6 +794,21 @@ pub(crate) mod tests {
 			))
 		}
 
+		fn is_pruned_state_error(&self, _error: &TestError) -> bool {
+			std::mem::take(&mut self.data.lock().is_source_state_pruned)
+		}
+
+		async fn ensure_finalized_ancestor(
+			&self,
+			id: SourceHeaderIdOf<TestMessageLane>,
+		) -> Result<(), TestError> {
+			let data = self.data.lock();
+			if data.source_non_canonical_headers.contains(&id) {
+				return Err(TestError)
+			}
+			Ok(())
+		}
+
 		async fn submit_messages_receiving_proof(
 			&self,
 			_generated_at_block: TargetHeaderIdOf<TestMessageLane>,
@@ -892,38 +987,38 @@ pub(crate) mod tests {
 		target_tick: Arc<dyn Fn(&mut TestClientData) + Send + Sync>,
 		target_post_tick: Arc<dyn Fn(&mut TestClientData) + Send + Sync>,
 		exit_signal: impl Future<Output = ()> + 'static + Send,
+	) -> TestClientData {
+		run_loop_test_with_block_interval(
+			Duration::from_millis(100),
+			data,
+			source_tick,
+			source_post_tick,
+			target_tick,
+			target_post_tick,
+			exit_signal,
+		)
+	}
+
+	fn run_loop_test_with_block_interval(
+		block_interval: Duration,
+		data: TestClientData,
+		source_tick: Arc<dyn Fn(&mut TestClientData) + Send + Sync>,
+		source_post_tick: Arc<dyn Fn(&mut TestClientData) + Send + Sync>,
+		target_tick: Arc<dyn Fn(&mut TestClientData) + Send + Sync>,
+		target_post_tick: Arc<dyn Fn(&mut TestClientData) + Send + Sync>,
+		exit_signal: impl Future<Output = ()> + 'static + Send,
 	) -> TestClientData {
 		async_std::task::block_on(async {
 			let data = Arc::new(Mutex::new(data));
-
-			let source_client = TestSourceClient {
-				data: data.clone(),
-				tick: source_tick,
-				post_tick: source_post_tick,
-			};
-			let target_client = TestTargetClient {
-				data: data.clone(),
-				tick: target_tick,
-				post_tick: target_post_tick,
-			};
-			let _ = run(
-				Params {
-					lane: [0, 0, 0, 0],
-					source_tick: Duration::from_millis(100),
-					target_tick: Duration::from_millis(100),
-					reconnect_delay: Duration::from_millis(0),
-					delivery_params: MessageDeliveryParams {
-						max_unrewarded_relayer_entries_at_target: 4,
-						max_unconfirmed_nonces_at_target: 4,
-						max_messages_in_single_batch: 4,
-						max_messages_weight_in_single_batch: 4,
-						max_messages_size_in_single_batch: 4,
-						relay_strategy: AltruisticStrategy,
-					},
-				},
-				source_client,
-				target_client,
-				MetricsParams::disabled(),
+			run_test_lane_loop(
+				LaneId([0, 0, 0, 0]),
+				block_interval,
+				data.clone(),
+				source_tick,
+				source_post_tick,
+				target_tick,
+				target_post_tick,
+				None,
 				exit_signal,
 			)
 			.await;
@@ -932,6 +1027,139 @@ pub(crate) mod tests {
 		})
 	}
 
+	#[allow(clippy::too_many_arguments)]
+	async fn run_test_lane_loop(
+		lane: LaneId,
+		block_interval: Duration,
+		data: Arc<Mutex<TestClientData>>,
+		source_tick: Arc<dyn Fn(&mut TestClientData) + Send + Sync>,
+		source_post_tick: Arc<dyn Fn(&mut TestClientData) + Send + Sync>,
+		target_tick: Arc<dyn Fn(&mut TestClientData) + Send + Sync>,
+		target_post_tick: Arc<dyn Fn(&mut TestClientData) + Send + Sync>,
+		submission_scheduler: Option<LaneSubmissionScheduler>,
+		exit_signal: impl Future<Output = ()> + 'static + Send,
+	) {
+		let source_client =
+			TestSourceClient { data: data.clone(), tick: source_tick, post_tick: source_post_tick };
+		let target_client =
+			TestTargetClient { data, tick: target_tick, post_tick: target_post_tick };
+		let _ = run(
+			Params {
+				lane,
+				source_tick: block_interval,
+				target_tick: block_interval,
+				reconnect_delay: Duration::from_millis(0),
+				delivery_params: MessageDeliveryParams {
+					max_unrewarded_relayer_entries_at_target: 4,
+					max_unconfirmed_nonces_at_target: 4,
+					max_messages_in_single_batch: 4,
+					max_messages_weight_in_single_batch: 4,
+					max_messages_size_in_single_batch: 4,
+					outbound_state_proof_params: Default::default(),
+					relay_strategy: SharedRelayStrategy::new(AltruisticStrategy),
+					submission_scheduler,
+					persistent_state_dir: None,
+				},
+			},
+			source_client,
+			target_client,
+			MetricsParams::disabled(),
+			exit_signal,
+		)
+		.await;
+	}
+
+	/// Test data of the lane, where `source_latest_generated_nonce` messages are queued.
+	fn busy_lane_test_data(source_latest_generated_nonce: MessageNonce) -> TestClientData {
+		TestClientData {
+			source_state: ClientState {
+				best_self: HeaderId(10, 10),
+				best_finalized_self: HeaderId(10, 10),
+				best_finalized_peer_at_best_self: HeaderId(0, 0),
+				actual_best_finalized_peer_at_best_self: HeaderId(0, 0),
+			},
+			source_latest_generated_nonce,
+			target_state: ClientState {
+				best_self: HeaderId(0, 0),
+				best_finalized_self: HeaderId(0, 0),
+				best_finalized_peer_at_best_self: HeaderId(0, 0),
+				actual_best_finalized_peer_at_best_self: HeaderId(0, 0),
+			},
+			target_latest_received_nonce: 0,
+			..Default::default()
+		}
+	}
+
+	/// Tick that produces new block and imports required headers of the bridged chain.
+	fn busy_lane_source_tick(data: &mut TestClientData) {
+		data.source_state.best_self =
+			HeaderId(data.source_state.best_self.0 + 1, data.source_state.best_self.1 + 1);
+		data.source_state.best_finalized_self = data.source_state.best_self;
+		if let Some(last_requirement) = data.target_to_source_header_requirements.last() {
+			data.source_state.best_finalized_peer_at_best_self = *last_requirement;
+		}
+	}
+
+	/// Tick that produces new block and imports required headers of the bridged chain.
+	fn busy_lane_target_tick(data: &mut TestClientData) {
+		data.target_state.best_self =
+			HeaderId(data.target_state.best_self.0 + 1, data.target_state.best_self.1 + 1);
+		data.target_state.best_finalized_self = data.target_state.best_self;
+		if let Some(last_requirement) = data.source_to_target_header_requirements.last() {
+			data.target_state.best_finalized_peer_at_best_self = *last_requirement;
+		}
+	}
+
+	/// Run two lanes that are sharing single-slot scheduler until all messages of `lane_b` are
+	/// delivered.
+	fn run_two_lanes_test(
+		scheduler: LaneSubmissionScheduler,
+		lane_a_messages: MessageNonce,
+		lane_b_messages: MessageNonce,
+	) -> (TestClientData, TestClientData) {
+		async_std::task::block_on(async {
+			let (exit_sender_a, exit_receiver_a) = unbounded();
+			let (exit_sender_b, exit_receiver_b) = unbounded();
+			let data_a = Arc::new(Mutex::new(busy_lane_test_data(lane_a_messages)));
+			let data_b = Arc::new(Mutex::new(busy_lane_test_data(lane_b_messages)));
+			let stop_when_lane_b_is_served = Arc::new(move |data: &mut TestClientData| {
+				busy_lane_target_tick(data);
+				if data.target_latest_received_nonce == lane_b_messages {
+					let _ = exit_sender_a.unbounded_send(());
+					let _ = exit_sender_b.unbounded_send(());
+				}
+			});
+			futures::future::join(
+				run_test_lane_loop(
+					LaneId([0, 0, 0, 0]),
+					Duration::from_millis(100),
+					data_a.clone(),
+					Arc::new(busy_lane_source_tick),
+					Arc::new(|_| {}),
+					Arc::new(busy_lane_target_tick),
+					Arc::new(|_| {}),
+					Some(scheduler.clone()),
+					exit_receiver_a.into_future().map(|(_, _)| ()),
+				),
+				run_test_lane_loop(
+					LaneId([0, 0, 0, 1]),
+					Duration::from_millis(100),
+					data_b.clone(),
+					Arc::new(busy_lane_source_tick),
+					Arc::new(|_| {}),
+					stop_when_lane_b_is_served,
+					Arc::new(|_| {}),
+					Some(scheduler),
+					exit_receiver_b.into_future().map(|(_, _)| ()),
+				),
+			)
+			.await;
+			let result_a = data_a.lock().clone();
+			let result_b = data_b.lock().clone();
+			(result_a, result_b)
+		})
+	}
+
 	#[test]
 	fn message_lane_loop_is_able_to_recover_from_connection_errors() {
 		// with this configuration, source client will return Err, making source client
@@ -1196,4 +1424,185 @@ pub(crate) mod tests {
 		assert!(!result.target_to_source_header_requirements.is_empty());
 		assert!(!result.source_to_target_header_requirements.is_empty());
 	}
+
+	#[test]
+	fn lane_with_huge_backlog_is_not_starving_other_lanes() {
+		// both lanes are sharing the only submission slot; lane A has a huge backlog and lane B
+		// has only one batch of messages
+		let (result_a, result_b) =
+			run_two_lanes_test(LaneSubmissionScheduler::new(1, &[]), 1000, 4);
+
+		// lane B messages are delivered while lane A is still busy
+		assert_eq!(result_b.submitted_messages_proofs.len(), 1);
+		assert_eq!(result_b.target_latest_received_nonce, 4);
+		assert!(result_a.target_latest_received_nonce < 1000);
+	}
+
+	#[test]
+	fn busy_lane_uses_all_submissions_when_other_lanes_are_idle() {
+		// lane B has larger weight, but it has nothing to deliver
+		let (exit_sender, exit_receiver) = unbounded();
+		let scheduler = LaneSubmissionScheduler::new(
+			1,
+			&[(LaneId([0, 0, 0, 0]), 1), (LaneId([0, 0, 0, 1]), 100)],
+		);
+		let result = async_std::task::block_on(async {
+			let data = Arc::new(Mutex::new(busy_lane_test_data(10)));
+			run_test_lane_loop(
+				LaneId([0, 0, 0, 0]),
+				Duration::from_millis(100),
+				data.clone(),
+				Arc::new(busy_lane_source_tick),
+				Arc::new(|_| {}),
+				Arc::new(move |data: &mut TestClientData| {
+					busy_lane_target_tick(data);
+					if data.target_latest_received_nonce == 10 {
+						exit_sender.unbounded_send(()).unwrap();
+					}
+				}),
+				Arc::new(|_| {}),
+				Some(scheduler),
+				exit_receiver.into_future().map(|(_, _)| ()),
+			)
+			.await;
+			let result = data.lock().clone();
+			result
+		});
+
+		// lane A delivers messages at full speed
+		assert_eq!(result.submitted_messages_proofs[0].0, 1..=4);
+		assert_eq!(result.submitted_messages_proofs[1].0, 5..=8);
+		assert_eq!(result.submitted_messages_proofs[2].0, 9..=10);
+	}
+
+	#[test]
+	fn message_lane_loop_works_with_fast_block_times() {
+		// chains with asynchronous backing are producing blocks every 500ms (or even faster), so
+		// the loop must be able to deliver messages without stalling/reconnecting
+		let (exit_sender, exit_receiver) = unbounded();
+		let result = run_loop_test_with_block_interval(
+			Duration::from_millis(500),
+			TestClientData {
+				source_state: ClientState {
+					best_self: HeaderId(10, 10),
+					best_finalized_self: HeaderId(10, 10),
+					best_finalized_peer_at_best_self: HeaderId(0, 0),
+					actual_best_finalized_peer_at_best_self: HeaderId(0, 0),
+				},
+				source_latest_generated_nonce: 4,
+				target_state: ClientState {
+					best_self: HeaderId(0, 0),
+					best_finalized_self: HeaderId(0, 0),
+					best_finalized_peer_at_best_self: HeaderId(0, 0),
+					actual_best_finalized_peer_at_best_self: HeaderId(0, 0),
+				},
+				target_latest_received_nonce: 0,
+				..Default::default()
+			},
+			Arc::new(|data: &mut TestClientData| {
+				// blocks are produced on every tick
+				data.source_state.best_self =
+					HeaderId(data.source_state.best_self.0 + 1, data.source_state.best_self.1 + 1);
+				data.source_state.best_finalized_self = data.source_state.best_self;
+				// syncing target headers -> source chain
+				if let Some(last_requirement) = data.target_to_source_header_requirements.last() {
+					data.source_state.best_finalized_peer_at_best_self = *last_requirement;
+				}
+			}),
+			Arc::new(|_| {}),
+			Arc::new(move |data: &mut TestClientData| {
+				// blocks are produced on every tick
+				data.target_state.best_self =
+					HeaderId(data.target_state.best_self.0 + 1, data.target_state.best_self.1 + 1);
+				data.target_state.best_finalized_self = data.target_state.best_self;
+				// syncing source headers -> target chain
+				if let Some(last_requirement) = data.source_to_target_header_requirements.last() {
+					data.target_state.best_finalized_peer_at_best_self = *last_requirement;
+				}
+				// if source has received all messages receiving confirmations => stop
+				if data.source_latest_confirmed_received_nonce == 4 {
+					exit_sender.unbounded_send(()).unwrap();
+				}
+			}),
+			Arc::new(|_| {}),
+			exit_receiver.into_future().map(|(_, _)| ()),
+		);
+
+		assert_eq!(result.submitted_messages_proofs[0].0, 1..=4);
+		assert!(!result.submitted_messages_receiving_proofs.is_empty());
+		assert!(!result.is_source_reconnected);
+		assert!(!result.is_target_reconnected);
+	}
+
+	fn prove_messages_at_anchor(
+		data: TestClientData,
+		anchor: TestSourceHeaderId,
+	) -> (
+		Result<(TestSourceHeaderId, RangeInclusive<MessageNonce>, TestMessagesProof), TestError>,
+		TestClientData,
+	) {
+		async_std::task::block_on(async {
+			let source_client = TestSourceClient {
+				data: Arc::new(Mutex::new(data)),
+				tick: Arc::new(|_| {}),
+				post_tick: Arc::new(|_| {}),
+			};
+			let result = crate::message_race_delivery::prove_messages_at_anchor(
+				&source_client,
+				anchor,
+				1..=1,
+				MessageProofParameters { outbound_state_proof_required: false, dispatch_weight: 0 },
+			)
+			.await;
+			let data = source_client.data.lock().clone();
+			(result, data)
+		})
+	}
+
+	fn source_finalized_at(number: TestSourceHeaderNumber) -> TestClientData {
+		TestClientData {
+			source_state: ClientState {
+				best_self: HeaderId(number, number),
+				best_finalized_self: HeaderId(number, number),
+				best_finalized_peer_at_best_self: HeaderId(0, 0),
+				actual_best_finalized_peer_at_best_self: HeaderId(0, 0),
+			},
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn messages_are_proved_at_anchor_header() {
+		let (result, data) = prove_messages_at_anchor(source_finalized_at(20), HeaderId(10, 10));
+
+		assert_eq!(result.map(|(at, _, _)| at).ok(), Some(HeaderId(10, 10)));
+		assert_eq!(data.source_proofs_generated_at, vec![HeaderId(10, 10)]);
+	}
+
+	#[test]
+	fn messages_are_proved_at_best_finalized_header_if_anchor_state_is_pruned() {
+		// the anchor header state has been pruned while we were selecting nonces
+		let (result, data) = prove_messages_at_anchor(
+			TestClientData { source_pruned_state_below: 15, ..source_finalized_at(20) },
+			HeaderId(10, 10),
+		);
+
+		assert_eq!(result.map(|(at, _, _)| at).ok(), Some(HeaderId(20, 20)));
+		assert_eq!(data.source_proofs_generated_at, vec![HeaderId(20, 20)]);
+	}
+
+	#[test]
+	fn messages_proof_is_rejected_if_anchor_is_not_finalized_ancestor() {
+		// the anchor header has been replaced with other header at the same height
+		let (result, data) = prove_messages_at_anchor(
+			TestClientData {
+				source_non_canonical_headers: vec![HeaderId(10, 10)],
+				..source_finalized_at(20)
+			},
+			HeaderId(10, 10),
+		);
+
+		assert!(result.is_err());
+		assert_eq!(data.source_proofs_generated_at, vec![HeaderId(10, 10)]);
+	}
 }
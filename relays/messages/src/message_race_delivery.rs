@@ -13,19 +13,21 @@
 
 //! Message delivery race delivers proof-of-messages from "lane.source" to "lane.target".
 
-use std::{collections::VecDeque, marker::PhantomData, ops::RangeInclusive};
+use std::{collections::VecDeque, marker::PhantomData, ops::RangeInclusive, time::Duration};
 
 use async_trait::async_trait;
 use futures::stream::FusedStream;
 
-use bp_messages::{MessageNonce, UnrewardedRelayersState, Weight};
+use bp_messages::{LaneId, MessageNonce, UnrewardedRelayersState, Weight};
 use relay_utils::FailedClient;
 
 use crate::{
+	delivery_attempts::{DeliveryAttemptsLog, DeliveryFailure, LoggedTransactionTracker},
+	lane_scheduler::{LaneSubmissionScheduler, ScheduledTransactionTracker},
 	message_lane::{MessageLane, SourceHeaderIdOf, TargetHeaderIdOf},
 	message_lane_loop::{
 		MessageDeliveryParams, MessageDetailsMap, MessageProofParameters, NoncesSubmitArtifacts,
-		SourceClient as MessageLaneSourceClient, SourceClientState,
+		OutboundStateProofParams, SourceClient as MessageLaneSourceClient, SourceClientState,
 		TargetClient as MessageLaneTargetClient, TargetClientState,
 	},
 	message_race_loop::{
@@ -34,32 +36,43 @@ use crate::{
 	},
 	message_race_strategy::BasicStrategy,
 	metrics::MessageLaneLoopMetrics,
-	relay_strategy::{EnforcementStrategy, RelayMessagesBatchReference, RelayStrategy},
+	relay_strategy::{EnforcementStrategy, RelayMessagesBatchReference, SharedRelayStrategy},
 };
 
 /// Run message delivery race.
-pub async fn run<P: MessageLane, Strategy: RelayStrategy>(
+pub async fn run<P: MessageLane>(
 	source_client: impl MessageLaneSourceClient<P>,
 	source_state_updates: impl FusedStream<Item = SourceClientState<P>>,
 	target_client: impl MessageLaneTargetClient<P>,
 	target_state_updates: impl FusedStream<Item = TargetClientState<P>>,
 	metrics_msg: Option<MessageLaneLoopMetrics>,
-	params: MessageDeliveryParams<Strategy>,
+	delivery_attempts: DeliveryAttemptsLog,
+	lane: LaneId,
+	params: MessageDeliveryParams<P::SourceChainBalance>,
+	tick: Duration,
 ) -> Result<(), FailedClient> {
 	crate::message_race_loop::run(
 		MessageDeliveryRaceSource {
 			client: source_client.clone(),
 			metrics_msg: metrics_msg.clone(),
+			delivery_attempts: delivery_attempts.clone(),
+			max_refreshed_nonces: params
+				.max_unconfirmed_nonces_at_target
+				.saturating_add(params.max_messages_in_single_batch),
 			_phantom: Default::default(),
 		},
 		source_state_updates,
 		MessageDeliveryRaceTarget {
 			client: target_client.clone(),
 			metrics_msg: metrics_msg.clone(),
+			delivery_attempts,
+			lane,
+			submission_scheduler: params.submission_scheduler,
 			_phantom: Default::default(),
 		},
 		target_state_updates,
-		MessageDeliveryStrategy::<P, Strategy, _, _> {
+		MessageDeliveryStrategy::<P, _, _> {
+			lane,
 			lane_source_client: source_client,
 			lane_target_client: target_client,
 			max_unrewarded_relayer_entries_at_target: params
@@ -68,12 +81,15 @@ pub async fn run<P: MessageLane, Strategy: RelayStrategy>(
 			max_messages_in_single_batch: params.max_messages_in_single_batch,
 			max_messages_weight_in_single_batch: params.max_messages_weight_in_single_batch,
 			max_messages_size_in_single_batch: params.max_messages_size_in_single_batch,
+			outbound_state_proof_params: params.outbound_state_proof_params,
+			deliveries_without_outbound_state_proof: 0,
 			relay_strategy: params.relay_strategy,
 			latest_confirmed_nonces_at_source: VecDeque::new(),
 			target_nonces: None,
 			strategy: BasicStrategy::new(),
 			metrics_msg,
 		},
+		tick,
 	)
 	.await
 }
@@ -101,6 +117,10 @@ impl<P: MessageLane> MessageRace for MessageDeliveryRace<P> {
 struct MessageDeliveryRaceSource<P: MessageLane, C> {
 	client: C,
 	metrics_msg: Option<MessageLaneLoopMetrics>,
+	delivery_attempts: DeliveryAttemptsLog,
+	/// Maximal number of oldest unconfirmed messages, which details are re-read on every
+	/// `nonces` call.
+	max_refreshed_nonces: MessageNonce,
 	_phantom: PhantomData<P>,
 }
 
@@ -128,17 +148,23 @@ where
 			metrics_msg.update_source_latest_generated_nonce::<P>(latest_generated_nonce);
 			metrics_msg.update_source_latest_confirmed_nonce::<P>(latest_confirmed_nonce);
 		}
-
-		let new_nonces = if latest_generated_nonce > prev_latest_nonce {
-			self.client
-				.generated_message_details(
-					at_block.clone(),
-					prev_latest_nonce + 1..=latest_generated_nonce,
-				)
-				.await?
-		} else {
-			MessageDetailsMap::new()
-		};
+		for attempts in self.delivery_attempts.prune_confirmed(latest_confirmed_nonce) {
+			if let Some(metrics_msg) = self.metrics_msg.as_ref() {
+				metrics_msg.observe_message_delivery_attempts(attempts);
+			}
+		}
+		self.delivery_attempts.persist();
+
+		let mut new_nonces = MessageDetailsMap::new();
+		for nonces in nonces_to_read(
+			prev_latest_nonce,
+			latest_confirmed_nonce,
+			latest_generated_nonce,
+			self.max_refreshed_nonces,
+		) {
+			new_nonces
+				.extend(self.client.generated_message_details(at_block.clone(), nonces).await?);
+		}
 
 		Ok((
 			at_block,
@@ -153,14 +179,98 @@ where
 		proof_parameters: Self::ProofParameters,
 	) -> Result<(SourceHeaderIdOf<P>, RangeInclusive<MessageNonce>, P::MessagesProof), Self::Error>
 	{
-		self.client.prove_messages(at_block, nonces, proof_parameters).await
+		prove_messages_at_anchor(&self.client, at_block, nonces.clone(), proof_parameters)
+			.await
+			.map_err(|e| {
+				self.delivery_attempts.note_failure(nonces, DeliveryFailure::ProofGeneration);
+				e
+			})
+	}
+}
+
+/// Returns ranges of nonces which details need to be read from the source client.
+///
+/// Apart from new nonces (generated after `prev_latest_nonce`), it also includes oldest known,
+/// but not yet confirmed nonces, because their fees may be increased by their submitters
+/// (`increase_message_fee` call). The strategy is then able to reconsider messages it has
+/// refused to deliver. At most `max_refreshed_nonces` are refreshed - there may be at most
+/// `max_unconfirmed_nonces_at_target` delivered, but not yet confirmed messages and the next
+/// delivery transaction may include at most `max_messages_in_single_batch` messages, so there's
+/// no need to refresh messages that follow.
+fn nonces_to_read(
+	prev_latest_nonce: MessageNonce,
+	latest_confirmed_nonce: MessageNonce,
+	latest_generated_nonce: MessageNonce,
+	max_refreshed_nonces: MessageNonce,
+) -> Vec<RangeInclusive<MessageNonce>> {
+	let first_refreshed_nonce = latest_confirmed_nonce.saturating_add(1);
+	let last_refreshed_nonce = std::cmp::min(
+		prev_latest_nonce,
+		latest_confirmed_nonce.saturating_add(max_refreshed_nonces),
+	);
+	let first_new_nonce = prev_latest_nonce.saturating_add(1);
+
+	let mut ranges = Vec::with_capacity(2);
+	if last_refreshed_nonce == prev_latest_nonce && first_refreshed_nonce <= first_new_nonce {
+		// refreshed nonces are followed by new nonces => read them at once
+		if latest_generated_nonce >= first_refreshed_nonce {
+			ranges.push(first_refreshed_nonce..=latest_generated_nonce);
+		}
+		return ranges
+	}
+
+	if last_refreshed_nonce >= first_refreshed_nonce {
+		ranges.push(first_refreshed_nonce..=last_refreshed_nonce);
 	}
+	if latest_generated_nonce >= first_new_nonce {
+		ranges.push(first_new_nonce..=latest_generated_nonce);
+	}
+	ranges
+}
+
+/// Prove messages at given (anchor) source header.
+///
+/// If the state at the anchor header has already been pruned, the proof is generated at the
+/// current best finalized source header instead. After the proof is generated, we verify that
+/// the header it has been generated at is still an ancestor of the best finalized source header.
+pub(crate) async fn prove_messages_at_anchor<P: MessageLane, C: MessageLaneSourceClient<P>>(
+	client: &C,
+	anchor: SourceHeaderIdOf<P>,
+	nonces: RangeInclusive<MessageNonce>,
+	proof_parameters: MessageProofParameters,
+) -> Result<(SourceHeaderIdOf<P>, RangeInclusive<MessageNonce>, P::MessagesProof), C::Error> {
+	let proof = match client
+		.prove_messages(anchor.clone(), nonces.clone(), proof_parameters.clone())
+		.await
+	{
+		Err(error) if client.is_pruned_state_error(&error) => {
+			let best_finalized_header_id = client.state().await?.best_finalized_self;
+			log::debug!(
+				target: "bridge",
+				"State of {} header {:?} has been pruned. Retrying to prove nonces {:?} at {:?}",
+				P::SOURCE_NAME,
+				anchor,
+				nonces,
+				best_finalized_header_id,
+			);
+			client
+				.prove_messages(best_finalized_header_id, nonces, proof_parameters)
+				.await?
+		},
+		result => result?,
+	};
+
+	client.ensure_finalized_ancestor(proof.0.clone()).await?;
+	Ok(proof)
 }
 
 /// Message delivery race target, which is a target of the lane.
 struct MessageDeliveryRaceTarget<P: MessageLane, C> {
 	client: C,
 	metrics_msg: Option<MessageLaneLoopMetrics>,
+	delivery_attempts: DeliveryAttemptsLog,
+	lane: LaneId,
+	submission_scheduler: Option<LaneSubmissionScheduler>,
 	_phantom: PhantomData<P>,
 }
 
@@ -172,7 +282,8 @@ where
 {
 	type Error = C::Error;
 	type TargetNoncesData = DeliveryRaceTargetNoncesData;
-	type TransactionTracker = C::TransactionTracker;
+	type TransactionTracker =
+		LoggedTransactionTracker<ScheduledTransactionTracker<C::TransactionTracker>>;
 
 	async fn require_source_header(&self, id: SourceHeaderIdOf<P>) {
 		self.client.require_source_header_on_target(id).await
@@ -190,6 +301,8 @@ where
 		let (at_block, unrewarded_relayers) =
 			self.client.unrewarded_relayers_state(at_block).await?;
 
+		self.delivery_attempts.note_target_latest_received_nonce(latest_received_nonce);
+
 		if update_metrics {
 			if let Some(metrics_msg) = self.metrics_msg.as_ref() {
 				metrics_msg.update_target_latest_received_nonce::<P>(latest_received_nonce);
@@ -215,7 +328,42 @@ where
 		nonces: RangeInclusive<MessageNonce>,
 		proof: P::MessagesProof,
 	) -> Result<NoncesSubmitArtifacts<Self::TransactionTracker>, Self::Error> {
-		self.client.submit_messages_proof(generated_at_block, nonces, proof).await
+		let slot = match self.submission_scheduler {
+			Some(ref submission_scheduler) => Some(submission_scheduler.acquire(self.lane).await),
+			None => None,
+		};
+		if let Some(previous_attempts) = self.delivery_attempts.note_attempt(nonces.clone()) {
+			log::warn!(
+				target: "bridge",
+				"Retrying delivery of {} -> {} messages {:?}. Previous attempts: {}, \
+				last attempt: {:?} ago, last failure: {}",
+				P::SOURCE_NAME,
+				P::TARGET_NAME,
+				nonces,
+				previous_attempts.attempts,
+				previous_attempts.last_attempt.elapsed().unwrap_or_default(),
+				previous_attempts
+					.last_failure()
+					.map(|failure| failure.to_string())
+					.unwrap_or_else(|| "unknown".into()),
+			);
+		}
+		let artifacts = self
+			.client
+			.submit_messages_proof(generated_at_block, nonces.clone(), proof)
+			.await
+			.map_err(|e| {
+				self.delivery_attempts.note_failure(nonces, DeliveryFailure::Submission);
+				e
+			})?;
+		Ok(NoncesSubmitArtifacts {
+			nonces: artifacts.nonces.clone(),
+			tx_tracker: LoggedTransactionTracker::new(
+				ScheduledTransactionTracker::new(artifacts.tx_tracker, slot),
+				artifacts.nonces,
+				self.delivery_attempts.clone(),
+			),
+		})
 	}
 }
 
@@ -231,7 +379,9 @@ struct DeliveryRaceTargetNoncesData {
 }
 
 /// Messages delivery strategy.
-struct MessageDeliveryStrategy<P: MessageLane, Strategy: RelayStrategy, SC, TC> {
+struct MessageDeliveryStrategy<P: MessageLane, SC, TC> {
+	/// Lane, which messages are delivered.
+	lane: LaneId,
 	/// The client that is connected to the message lane source node.
 	lane_source_client: SC,
 	/// The client that is connected to the message lane target node.
@@ -246,8 +396,13 @@ struct MessageDeliveryStrategy<P: MessageLane, Strategy: RelayStrategy, SC, TC>
 	max_messages_weight_in_single_batch: Weight,
 	/// Maximal messages size in the single delivery transaction.
 	max_messages_size_in_single_batch: u32,
+	/// Parameters that define when outbound lane state proof is included into the transaction.
+	outbound_state_proof_params: OutboundStateProofParams,
+	/// Number of delivery transactions that we have prepared since we have included the outbound
+	/// lane state proof last time.
+	deliveries_without_outbound_state_proof: u32,
 	/// Relayer operating mode.
-	relay_strategy: Strategy,
+	relay_strategy: SharedRelayStrategy<P::SourceChainBalance>,
 	/// Latest confirmed nonces at the source client + the header id where we have first met this
 	/// nonce.
 	latest_confirmed_nonces_at_source: VecDeque<(SourceHeaderIdOf<P>, MessageNonce)>,
@@ -268,11 +423,10 @@ type MessageDeliveryStrategyBase<P> = BasicStrategy<
 	<P as MessageLane>::MessagesProof,
 >;
 
-impl<P: MessageLane, Strategy: RelayStrategy, SC, TC> std::fmt::Debug
-	for MessageDeliveryStrategy<P, Strategy, SC, TC>
-{
+impl<P: MessageLane, SC, TC> std::fmt::Debug for MessageDeliveryStrategy<P, SC, TC> {
 	fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
 		fmt.debug_struct("MessageDeliveryStrategy")
+			.field("lane", &self.lane)
 			.field(
 				"max_unrewarded_relayer_entries_at_target",
 				&self.max_unrewarded_relayer_entries_at_target,
@@ -281,6 +435,11 @@ impl<P: MessageLane, Strategy: RelayStrategy, SC, TC> std::fmt::Debug
 			.field("max_messages_in_single_batch", &self.max_messages_in_single_batch)
 			.field("max_messages_weight_in_single_batch", &self.max_messages_weight_in_single_batch)
 			.field("max_messages_size_in_single_batch", &self.max_messages_size_in_single_batch)
+			.field("outbound_state_proof_params", &self.outbound_state_proof_params)
+			.field(
+				"deliveries_without_outbound_state_proof",
+				&self.deliveries_without_outbound_state_proof,
+			)
 			.field("latest_confirmed_nonces_at_source", &self.latest_confirmed_nonces_at_source)
 			.field("target_nonces", &self.target_nonces)
 			.field("strategy", &self.strategy)
@@ -288,7 +447,7 @@ impl<P: MessageLane, Strategy: RelayStrategy, SC, TC> std::fmt::Debug
 	}
 }
 
-impl<P: MessageLane, Strategy: RelayStrategy, SC, TC> MessageDeliveryStrategy<P, Strategy, SC, TC> {
+impl<P: MessageLane, SC, TC> MessageDeliveryStrategy<P, SC, TC> {
 	/// Returns total weight of all undelivered messages.
 	fn total_queued_dispatch_weight(&self) -> Weight {
 		self.strategy
@@ -297,12 +456,54 @@ impl<P: MessageLane, Strategy: RelayStrategy, SC, TC> MessageDeliveryStrategy<P,
 			.flat_map(|(_, range)| range.values().map(|details| details.dispatch_weight))
 			.fold(0, |total, weight| total.saturating_add(weight))
 	}
+
+	/// Returns true if we need to include outbound lane state proof into the delivery transaction.
+	fn is_outbound_state_proof_required(
+		&self,
+		target_nonces: &TargetClientNonces<DeliveryRaceTargetNoncesData>,
+		latest_confirmed_nonce_at_source: MessageNonce,
+	) -> bool {
+		// if target already knows about all confirmations, there's nothing to prove
+		let latest_confirmed_nonce_at_target = target_nonces.nonces_data.confirmed_nonce;
+		if latest_confirmed_nonce_at_target >= latest_confirmed_nonce_at_source {
+			return false
+		}
+
+		// target rejects all new messages if there are too many unrewarded relayer entries
+		let unrewarded_relayers = &target_nonces.nonces_data.unrewarded_relayers;
+		if unrewarded_relayers.unrewarded_relayer_entries >=
+			self.max_unrewarded_relayer_entries_at_target
+		{
+			return true
+		}
+
+		// target rejects all new messages if there are too many unconfirmed messages
+		let unconfirmed_nonces_at_target =
+			target_nonces.latest_nonce.saturating_sub(latest_confirmed_nonce_at_target);
+		if unconfirmed_nonces_at_target >= self.max_unconfirmed_nonces_at_target {
+			return true
+		}
+
+		// otherwise the proof is optional and we only include it if some threshold is reached
+		let is_threshold_reached = |value: MessageNonce, limit: MessageNonce, percent| {
+			value.saturating_mul(100) >= limit.saturating_mul(percent)
+		};
+		let params = &self.outbound_state_proof_params;
+		is_threshold_reached(
+			unrewarded_relayers.unrewarded_relayer_entries,
+			self.max_unrewarded_relayer_entries_at_target,
+			params.unrewarded_relayer_entries_threshold_percent,
+		) || is_threshold_reached(
+			unrewarded_relayers.total_messages,
+			self.max_unconfirmed_nonces_at_target,
+			params.unrewarded_messages_threshold_percent,
+		) || self.deliveries_without_outbound_state_proof >= params.max_deliveries_without_proof
+	}
 }
 
 #[async_trait]
-impl<P, Strategy: RelayStrategy, SC, TC>
-	RaceStrategy<SourceHeaderIdOf<P>, TargetHeaderIdOf<P>, P::MessagesProof>
-	for MessageDeliveryStrategy<P, Strategy, SC, TC>
+impl<P, SC, TC> RaceStrategy<SourceHeaderIdOf<P>, TargetHeaderIdOf<P>, P::MessagesProof>
+	for MessageDeliveryStrategy<P, SC, TC>
 where
 	P: MessageLane,
 	SC: MessageLaneSourceClient<P>,
@@ -357,6 +558,17 @@ where
 					.push_back((at_block.clone(), confirmed_nonce));
 			}
 		}
+
+		// details of messages that are already in the queue may have been updated (e.g. because
+		// the message fee has been increased), so let's update them in the queue
+		for (_, queued_nonces) in self.strategy.source_queue_mut().iter_mut() {
+			for (nonce, details) in queued_nonces.iter_mut() {
+				if let Some(updated_details) = nonces.new_nonces.get(nonce) {
+					*details = *updated_details;
+				}
+			}
+		}
+
 		self.strategy.source_nonces_updated(at_block, nonces)
 	}
 
@@ -453,12 +665,12 @@ where
 		// we haven't notified it that (some) messages have been confirmed. So we may want to
 		// include updated `source.latest_confirmed` in the proof.
 		//
-		// Important note: we're including outbound state lane proof whenever there are unconfirmed
-		// nonces on the target chain. Other strategy is to include it only if it's absolutely
-		// necessary.
+		// Important note: we're only including outbound state lane proof if target would reject
+		// new messages without it, or if configured thresholds are reached. Otherwise the proof
+		// only increases the transaction size and weight.
 		let latest_confirmed_nonce_at_target = target_nonces.nonces_data.confirmed_nonce;
 		let outbound_state_proof_required =
-			latest_confirmed_nonce_at_target < latest_confirmed_nonce_at_source;
+			self.is_outbound_state_proof_required(target_nonces, latest_confirmed_nonce_at_source);
 
 		// The target node would also reject messages if there are too many entries in the
 		// "unrewarded relayers" set. If we are unable to prove new rewards to the target node, then
@@ -514,6 +726,7 @@ where
 		let source_queue = self.strategy.source_queue();
 
 		let reference = RelayMessagesBatchReference {
+			lane: self.lane,
 			max_messages_in_this_batch: max_nonces,
 			max_messages_weight_in_single_batch,
 			max_messages_size_in_single_batch,
@@ -534,6 +747,12 @@ where
 		let new_total_dispatch_weight = self.total_queued_dispatch_weight();
 		let dispatch_weight = previous_total_dispatch_weight - new_total_dispatch_weight;
 
+		self.deliveries_without_outbound_state_proof = if outbound_state_proof_required {
+			0
+		} else {
+			self.deliveries_without_outbound_state_proof.saturating_add(1)
+		};
+
 		Some((
 			selected_nonces,
 			MessageProofParameters { outbound_state_proof_required, dispatch_weight },
@@ -573,11 +792,13 @@ mod tests {
 			},
 			MessageDetails, RelayerMode,
 		},
-		relay_strategy::MixStrategy,
+		relay_strategy::{MixStrategy, RelayDecisionContext, RelayStrategy},
 	};
+	use std::sync::Arc;
 
 	use super::*;
 
+	const TEST_LANE: LaneId = LaneId([0, 0, 0, 0]);
 	const DEFAULT_DISPATCH_WEIGHT: Weight = 1;
 	const DEFAULT_SIZE: u32 = 1;
 	const DEFAULT_REWARD: TestSourceChainBalance = CONFIRMATION_TRANSACTION_COST +
@@ -587,7 +808,7 @@ mod tests {
 
 	type TestRaceState = RaceState<TestSourceHeaderId, TestTargetHeaderId, TestMessagesProof>;
 	type TestStrategy =
-		MessageDeliveryStrategy<TestMessageLane, MixStrategy, TestSourceClient, TestTargetClient>;
+		MessageDeliveryStrategy<TestMessageLane, TestSourceClient, TestTargetClient>;
 
 	fn source_nonces(
 		new_nonces: RangeInclusive<MessageNonce>,
@@ -626,11 +847,18 @@ mod tests {
 		};
 
 		let mut race_strategy = TestStrategy {
+			lane: TEST_LANE,
 			max_unrewarded_relayer_entries_at_target: 4,
 			max_unconfirmed_nonces_at_target: 4,
 			max_messages_in_single_batch: 4,
 			max_messages_weight_in_single_batch: 4,
 			max_messages_size_in_single_batch: 4,
+			outbound_state_proof_params: OutboundStateProofParams {
+				unrewarded_relayer_entries_threshold_percent: 50,
+				unrewarded_messages_threshold_percent: 50,
+				max_deliveries_without_proof: 4,
+			},
+			deliveries_without_outbound_state_proof: 0,
 			latest_confirmed_nonces_at_source: vec![(header_id(1), 19)].into_iter().collect(),
 			lane_source_client: TestSourceClient::default(),
 			lane_target_client: TestTargetClient::default(),
@@ -648,7 +876,7 @@ mod tests {
 				},
 			}),
 			strategy: BasicStrategy::new(),
-			relay_strategy: MixStrategy::new(RelayerMode::Altruistic),
+			relay_strategy: SharedRelayStrategy::new(MixStrategy::new(RelayerMode::Altruistic)),
 		};
 
 		race_strategy.strategy.source_nonces_updated(
@@ -734,20 +962,134 @@ mod tests {
 	}
 
 	#[async_std::test]
-	async fn message_delivery_strategy_includes_outbound_state_proof_when_new_nonces_are_available()
-	{
+	async fn message_delivery_strategy_does_not_include_outbound_state_proof_when_it_is_not_required(
+	) {
+		let (state, mut strategy) = prepare_strategy();
+
+		// if there are new confirmed nonces on source, but target is far from its limits, we
+		// don't want to include outbound state proof
+		let prev_confirmed_nonce_at_source =
+			strategy.latest_confirmed_nonces_at_source.back().unwrap().1;
+		strategy.target_nonces.as_mut().unwrap().nonces_data.confirmed_nonce =
+			prev_confirmed_nonce_at_source - 1;
+		assert_eq!(
+			strategy.select_nonces_to_deliver(state).await,
+			Some(((20..=22), proof_parameters(false, 3)))
+		);
+	}
+
+	#[async_std::test]
+	async fn message_delivery_strategy_includes_outbound_state_proof_when_relayer_entries_threshold_is_reached(
+	) {
+		// 2 entries out of 4 is exactly 50%, so the proof is included
+		let (state, mut strategy) = prepare_strategy();
+		let prev_confirmed_nonce_at_source =
+			strategy.latest_confirmed_nonces_at_source.back().unwrap().1;
+		{
+			let nonces_data = &mut strategy.target_nonces.as_mut().unwrap().nonces_data;
+			nonces_data.confirmed_nonce = prev_confirmed_nonce_at_source - 1;
+			nonces_data.unrewarded_relayers.unrewarded_relayer_entries = 2;
+		}
+		assert_eq!(
+			strategy.select_nonces_to_deliver(state).await,
+			Some(((20..=23), proof_parameters(true, 4)))
+		);
+
+		// 1 entry out of 4 is below the threshold, so the proof is not included
+		let (state, mut strategy) = prepare_strategy();
+		{
+			let nonces_data = &mut strategy.target_nonces.as_mut().unwrap().nonces_data;
+			nonces_data.confirmed_nonce = prev_confirmed_nonce_at_source - 1;
+			nonces_data.unrewarded_relayers.unrewarded_relayer_entries = 1;
+		}
+		assert_eq!(
+			strategy.select_nonces_to_deliver(state).await,
+			Some(((20..=22), proof_parameters(false, 3)))
+		);
+	}
+
+	#[async_std::test]
+	async fn message_delivery_strategy_includes_outbound_state_proof_when_messages_threshold_is_reached(
+	) {
+		// 2 messages out of 4 is exactly 50%, so the proof is included
+		let (state, mut strategy) = prepare_strategy();
+		let prev_confirmed_nonce_at_source =
+			strategy.latest_confirmed_nonces_at_source.back().unwrap().1;
+		{
+			let nonces_data = &mut strategy.target_nonces.as_mut().unwrap().nonces_data;
+			nonces_data.confirmed_nonce = prev_confirmed_nonce_at_source - 1;
+			nonces_data.unrewarded_relayers.total_messages = 2;
+		}
+		assert_eq!(
+			strategy.select_nonces_to_deliver(state).await,
+			Some(((20..=23), proof_parameters(true, 4)))
+		);
+
+		// 1 message out of 4 is below the threshold, so the proof is not included
 		let (state, mut strategy) = prepare_strategy();
+		{
+			let nonces_data = &mut strategy.target_nonces.as_mut().unwrap().nonces_data;
+			nonces_data.confirmed_nonce = prev_confirmed_nonce_at_source - 1;
+			nonces_data.unrewarded_relayers.total_messages = 1;
+		}
+		assert_eq!(
+			strategy.select_nonces_to_deliver(state).await,
+			Some(((20..=22), proof_parameters(false, 3)))
+		);
+	}
 
-		// if there are new confirmed nonces on source, we want to relay this information
-		// to target to prune rewards queue
+	#[async_std::test]
+	async fn message_delivery_strategy_includes_outbound_state_proof_after_max_deliveries_without_it(
+	) {
+		let (state, mut strategy) = prepare_strategy();
 		let prev_confirmed_nonce_at_source =
 			strategy.latest_confirmed_nonces_at_source.back().unwrap().1;
 		strategy.target_nonces.as_mut().unwrap().nonces_data.confirmed_nonce =
 			prev_confirmed_nonce_at_source - 1;
+
+		// we have prepared one delivery less than maximal => proof is not included
+		strategy.deliveries_without_outbound_state_proof =
+			strategy.outbound_state_proof_params.max_deliveries_without_proof - 1;
+		assert_eq!(
+			strategy.select_nonces_to_deliver(state.clone()).await,
+			Some(((20..=22), proof_parameters(false, 3)))
+		);
+		assert_eq!(
+			strategy.deliveries_without_outbound_state_proof,
+			strategy.outbound_state_proof_params.max_deliveries_without_proof,
+		);
+
+		// and now the proof is included
 		assert_eq!(
 			strategy.select_nonces_to_deliver(state).await,
 			Some(((20..=23), proof_parameters(true, 4)))
 		);
+		assert_eq!(strategy.deliveries_without_outbound_state_proof, 0);
+	}
+
+	#[async_std::test]
+	async fn message_delivery_strategy_includes_outbound_state_proof_when_target_rejects_messages_without_it(
+	) {
+		// there are already `max_unconfirmed_nonces_at_target` unconfirmed messages at the target,
+		// so it'll reject any new message unless we prove that some messages are confirmed
+		let (state, mut strategy) = prepare_strategy();
+		let prev_confirmed_nonce_at_source =
+			strategy.latest_confirmed_nonces_at_source.back().unwrap().1;
+		strategy.target_nonces.as_mut().unwrap().nonces_data.confirmed_nonce =
+			prev_confirmed_nonce_at_source - strategy.max_unconfirmed_nonces_at_target;
+		assert_eq!(
+			strategy.select_nonces_to_deliver(state).await,
+			Some(((20..=23), proof_parameters(true, 4)))
+		);
+
+		// one unconfirmed message less and the target would accept new message without the proof
+		let (state, mut strategy) = prepare_strategy();
+		strategy.target_nonces.as_mut().unwrap().nonces_data.confirmed_nonce =
+			prev_confirmed_nonce_at_source - strategy.max_unconfirmed_nonces_at_target + 1;
+		assert_eq!(
+			strategy.select_nonces_to_deliver(state).await,
+			Some(((20..=20), proof_parameters(false, 1)))
+		);
 	}
 
 	#[async_std::test]
@@ -776,7 +1118,7 @@ mod tests {
 		let prev_confirmed_nonce_at_source =
 			strategy.latest_confirmed_nonces_at_source.back().unwrap().1;
 		{
-			let mut nonces_data = &mut strategy.target_nonces.as_mut().unwrap().nonces_data;
+			let nonces_data = &mut strategy.target_nonces.as_mut().unwrap().nonces_data;
 			nonces_data.confirmed_nonce = prev_confirmed_nonce_at_source - 1;
 			let mut unrewarded_relayers = &mut nonces_data.unrewarded_relayers;
 			unrewarded_relayers.unrewarded_relayer_entries =
@@ -795,7 +1137,7 @@ mod tests {
 		let prev_confirmed_nonce_at_source =
 			strategy.latest_confirmed_nonces_at_source.back().unwrap().1;
 		{
-			let mut nonces_data = &mut strategy.target_nonces.as_mut().unwrap().nonces_data;
+			let nonces_data = &mut strategy.target_nonces.as_mut().unwrap().nonces_data;
 			nonces_data.confirmed_nonce = prev_confirmed_nonce_at_source - 3;
 			let mut unrewarded_relayers = &mut nonces_data.unrewarded_relayers;
 			unrewarded_relayers.unrewarded_relayer_entries =
@@ -927,8 +1269,11 @@ mod tests {
 		]
 		.into_iter()
 		.collect();
-		strategy.target_nonces.as_mut().unwrap().nonces_data.confirmed_nonce =
-			prev_confirmed_nonce_at_source - 1;
+		{
+			let nonces_data = &mut strategy.target_nonces.as_mut().unwrap().nonces_data;
+			nonces_data.confirmed_nonce = prev_confirmed_nonce_at_source - 1;
+			nonces_data.unrewarded_relayers.unrewarded_relayer_entries = 2;
+		}
 		state.best_finalized_source_header_id_at_source = Some(header_id(2));
 		state.best_finalized_source_header_id_at_best_target = Some(header_id(2));
 		assert_eq!(
@@ -980,7 +1325,7 @@ mod tests {
 	#[async_std::test]
 	async fn rational_relayer_is_delivering_messages_if_cost_is_equal_to_reward() {
 		let (state, mut strategy) = prepare_strategy();
-		strategy.relay_strategy = MixStrategy::new(RelayerMode::Rational);
+		strategy.relay_strategy = SharedRelayStrategy::new(MixStrategy::new(RelayerMode::Rational));
 
 		// so now we have:
 		// - 20..=23 with reward = cost
@@ -1002,7 +1347,7 @@ mod tests {
 		);
 		strategy.strategy.source_nonces_updated(header_id(2), nonces);
 		state.best_finalized_source_header_id_at_best_target = Some(header_id(2));
-		strategy.relay_strategy = MixStrategy::new(RelayerMode::Rational);
+		strategy.relay_strategy = SharedRelayStrategy::new(MixStrategy::new(RelayerMode::Rational));
 
 		// so now we have:
 		// - 20..=23 with reward = cost
@@ -1033,7 +1378,8 @@ mod tests {
 			strategy.max_messages_in_single_batch = 100;
 			strategy.max_messages_weight_in_single_batch = 100;
 			strategy.max_messages_size_in_single_batch = 100;
-			strategy.relay_strategy = MixStrategy::new(RelayerMode::Rational);
+			strategy.relay_strategy =
+				SharedRelayStrategy::new(MixStrategy::new(RelayerMode::Rational));
 
 			// so now we have:
 			// - 20..=23 with reward = cost
@@ -1054,6 +1400,49 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn nonces_to_read_includes_limited_number_of_unconfirmed_nonces() {
+		// refreshed nonces are followed by new nonces
+		assert_eq!(nonces_to_read(0, 0, 5, 10), vec![1..=5]);
+		assert_eq!(nonces_to_read(5, 0, 7, 10), vec![1..=7]);
+		assert_eq!(nonces_to_read(10, 0, 12, 10), vec![1..=12]);
+		// only first `max_refreshed_nonces` are refreshed
+		assert_eq!(nonces_to_read(100, 0, 105, 10), vec![1..=10, 101..=105]);
+		assert_eq!(nonces_to_read(100, 50, 100, 10), vec![51..=60]);
+		// no new nonces
+		assert_eq!(nonces_to_read(5, 2, 5, 10), vec![3..=5]);
+		assert_eq!(nonces_to_read(5, 5, 5, 10), Vec::<RangeInclusive<MessageNonce>>::new());
+		// confirmed nonce is ahead of previously known nonce
+		assert_eq!(nonces_to_read(3, 5, 7, 10), vec![4..=7]);
+	}
+
+	#[async_std::test]
+	async fn rational_relayer_is_delivering_messages_after_their_fee_is_increased() {
+		let (state, mut strategy) = prepare_strategy();
+		strategy.relay_strategy = SharedRelayStrategy::new(MixStrategy::new(RelayerMode::Rational));
+
+		// initially all queued messages have reward that is less than the cost
+		for (_, queued_nonces) in strategy.strategy.source_queue_mut().iter_mut() {
+			for details in queued_nonces.values_mut() {
+				details.reward = DEFAULT_REWARD - BASE_MESSAGE_DELIVERY_TRANSACTION_COST;
+			}
+		}
+		assert_eq!(strategy.select_nonces_to_deliver(state.clone()).await, None);
+
+		// then submitters are increasing message fees and source client is reporting updated
+		// details of already queued messages
+		strategy.source_nonces_updated(
+			header_id(2),
+			source_nonces(20..=23, 19, DEFAULT_REWARD, DispatchFeePayment::AtSourceChain),
+		);
+
+		// => strategy shall now select all 20..=23
+		assert_eq!(
+			strategy.select_nonces_to_deliver(state).await,
+			Some(((20..=23), proof_parameters(false, 4)))
+		);
+	}
+
 	#[async_std::test]
 	async fn relayer_uses_flattened_view_of_the_source_queue_to_select_nonces() {
 		// Real scenario that has happened on test deployments:
@@ -1079,4 +1468,57 @@ mod tests {
 			Some(((20..=24), proof_parameters(false, 5)))
 		);
 	}
+
+	#[derive(Default)]
+	struct RecordingStrategy {
+		max_nonce: MessageNonce,
+		decisions: parking_lot::Mutex<Vec<RelayDecisionContext<TestSourceChainBalance>>>,
+		final_decision: parking_lot::Mutex<Option<RelayDecisionContext<TestSourceChainBalance>>>,
+	}
+
+	#[async_trait]
+	impl RelayStrategy<TestSourceChainBalance> for Arc<RecordingStrategy> {
+		async fn decide(&self, context: &RelayDecisionContext<TestSourceChainBalance>) -> bool {
+			self.decisions.lock().push(context.clone());
+			*context.nonces.end() <= self.max_nonce
+		}
+
+		fn on_final_decision(&self, context: &RelayDecisionContext<TestSourceChainBalance>) {
+			*self.final_decision.lock() = Some(context.clone());
+		}
+	}
+
+	#[async_std::test]
+	async fn relay_strategy_receives_accurate_decision_context() {
+		let (state, mut strategy) = prepare_strategy();
+		let recording_strategy =
+			Arc::new(RecordingStrategy { max_nonce: 21, ..Default::default() });
+		strategy.relay_strategy =
+			SharedRelayStrategy::from_boxed(Box::new(recording_strategy.clone()));
+
+		assert_eq!(
+			strategy.select_nonces_to_deliver(state).await,
+			Some(((20..=21), proof_parameters(false, 2)))
+		);
+
+		// every message costs exactly `DEFAULT_REWARD` (see `TestTargetClient`)
+		let expected_context = |end: MessageNonce| RelayDecisionContext {
+			lane: TEST_LANE,
+			nonces: 20..=end,
+			messages: source_nonces(
+				20..=end,
+				19,
+				DEFAULT_REWARD,
+				DispatchFeePayment::AtSourceChain,
+			)
+			.new_nonces,
+			total_cost: Some(DEFAULT_REWARD * (end - 19)),
+			total_reward: DEFAULT_REWARD * (end - 19),
+		};
+		assert_eq!(
+			*recording_strategy.decisions.lock(),
+			(20..=23).map(expected_context).collect::<Vec<_>>(),
+		);
+		assert_eq!(*recording_strategy.final_decision.lock(), Some(expected_context(21)));
+	}
 }
@@ -29,6 +29,7 @@ use futures::{
 	stream::{FusedStream, StreamExt},
 };
 use relay_utils::{
+	clock::{MonotonicClock, RelayClock},
 	process_future_result, retry_backoff, FailedClient, MaybeConnectionError,
 	TrackedTransactionStatus, TransactionTracker,
 };
@@ -38,6 +39,12 @@ use std::{
 	time::{Duration, Instant},
 };
 
+/// Number of race ticks between two consecutive race progress log records.
+///
+/// The interval is expressed in ticks (and not in seconds), so that the progress is reported
+/// at the same rate (in blocks) regardless of the chains block time.
+const PROGRESS_LOG_TICKS: u32 = 10;
+
 /// One of races within lane.
 pub trait MessageRace {
 	/// Header id of the race source.
@@ -79,7 +86,8 @@ pub trait NoncesRange: Debug + Sized {
 #[derive(Debug, Clone)]
 pub struct SourceClientNonces<NoncesRange> {
 	/// New nonces range known to the client. `New` here means all nonces generated after
-	/// `prev_latest_nonce` passed to the `SourceClient::nonces` method.
+	/// `prev_latest_nonce` passed to the `SourceClient::nonces` method. The range may also
+	/// include some older nonces, if the client wants to report their updated details.
 	pub new_nonces: NoncesRange,
 	/// The latest nonce that is confirmed to the bridged client. This nonce only makes
 	/// sense in some races. In other races it is `None`.
@@ -222,6 +230,8 @@ pub struct RaceState<SourceHeaderId, TargetHeaderId, Proof> {
 }
 
 /// Run race loop until connection with target or source node is lost.
+///
+/// The `tick` is the (maximal) interval at which the race receives client state updates.
 pub async fn run<P: MessageRace, SC: SourceClient<P>, TC: TargetClient<P>>(
 	race_source: SC,
 	race_source_updated: impl FusedStream<Item = SourceClientState<P>>,
@@ -235,8 +245,11 @@ pub async fn run<P: MessageRace, SC: SourceClient<P>, TC: TargetClient<P>>(
 		ProofParameters = SC::ProofParameters,
 		TargetNoncesData = TC::TargetNoncesData,
 	>,
+	tick: Duration,
 ) -> Result<(), FailedClient> {
-	let mut progress_context = Instant::now();
+	let clock = MonotonicClock;
+	let progress_log_interval = tick.saturating_mul(PROGRESS_LOG_TICKS);
+	let mut progress_context = clock.now();
 	let mut race_state = RaceState::default();
 
 	let mut source_retry_backoff = retry_backoff();
@@ -446,7 +459,7 @@ pub async fn run<P: MessageRace, SC: SourceClient<P>, TC: TargetClient<P>>(
 									e,
 								);
 
-								FailedClient::Both
+								FailedClient::Neither
 							})?;
 					},
 					(TrackedTransactionStatus::Lost, _) => {
@@ -459,7 +472,7 @@ pub async fn run<P: MessageRace, SC: SourceClient<P>, TC: TargetClient<P>>(
 							strategy,
 						);
 
-						return Err(FailedClient::Both);
+						return Err(FailedClient::Neither);
 					},
 					_ => (),
 				}
@@ -474,7 +487,12 @@ pub async fn run<P: MessageRace, SC: SourceClient<P>, TC: TargetClient<P>>(
 			},
 		}
 
-		progress_context = print_race_progress::<P, _>(progress_context, &strategy);
+		progress_context = print_race_progress::<P, _>(
+			progress_context,
+			clock.now(),
+			progress_log_interval,
+			&strategy,
+		);
 
 		if source_client_is_online {
 			source_client_is_online = false;
@@ -571,14 +589,17 @@ impl<SourceHeaderId, TargetHeaderId, Proof> Default
 }
 
 /// Print race progress.
-fn print_race_progress<P, S>(prev_time: Instant, strategy: &S) -> Instant
+fn print_race_progress<P, S>(
+	prev_time: Instant,
+	now_time: Instant,
+	progress_log_interval: Duration,
+	strategy: &S,
+) -> Instant
 where
 	P: MessageRace,
 	S: RaceStrategy<P::SourceHeaderId, P::TargetHeaderId, P::Proof>,
 {
-	let now_time = Instant::now();
-
-	let need_update = now_time.saturating_duration_since(prev_time) > Duration::from_secs(10);
+	let need_update = now_time.saturating_duration_since(prev_time) > progress_log_interval;
 	if !need_update {
 		return prev_time
 	}
@@ -31,7 +31,7 @@ use async_trait::async_trait;
 use bp_messages::MessageNonce;
 use futures::stream::FusedStream;
 use relay_utils::FailedClient;
-use std::{marker::PhantomData, ops::RangeInclusive};
+use std::{marker::PhantomData, ops::RangeInclusive, time::Duration};
 
 /// Message receiving confirmations delivery strategy.
 type ReceivingConfirmationsBasicStrategy<P> = BasicStrategy<
@@ -50,6 +50,7 @@ pub async fn run<P: MessageLane>(
 	target_client: impl MessageLaneTargetClient<P>,
 	target_state_updates: impl FusedStream<Item = TargetClientState<P>>,
 	metrics_msg: Option<MessageLaneLoopMetrics>,
+	tick: Duration,
 ) -> Result<(), FailedClient> {
 	crate::message_race_loop::run(
 		ReceivingConfirmationsRaceSource {
@@ -65,6 +66,7 @@ pub async fn run<P: MessageLane>(
 		},
 		source_state_updates,
 		ReceivingConfirmationsBasicStrategy::<P>::new(),
+		tick,
 	)
 	.await
 }
@@ -85,8 +85,7 @@ impl<
 		&self.source_queue
 	}
 
-	/// Mutable reference to source queue to use in tests.
-	#[cfg(test)]
+	/// Mutable reference to source queue.
 	pub(crate) fn source_queue_mut(
 		&mut self,
 	) -> &mut VecDeque<(HeaderId<SourceHeaderHash, SourceHeaderNumber>, SourceNoncesRange)> {
@@ -24,7 +24,8 @@ use crate::{
 use bp_messages::MessageNonce;
 use finality_relay::SyncLoopMetrics;
 use relay_utils::metrics::{
-	metric_name, register, Counter, GaugeVec, Metric, Opts, PrometheusError, Registry, U64,
+	metric_name, register, Counter, GaugeVec, Histogram, HistogramOpts, Metric, Opts,
+	PrometheusError, Registry, U64,
 };
 
 /// Message lane relay metrics.
@@ -41,6 +42,8 @@ pub struct MessageLaneLoopMetrics {
 	lane_state_nonces: GaugeVec<U64>,
 	/// Count of unprofitable message delivery transactions that we have submitted so far.
 	unprofitable_delivery_transactions: Counter<U64>,
+	/// Number of delivery attempts that every confirmed message has required.
+	message_delivery_attempts: Histogram,
 }
 
 impl MessageLaneLoopMetrics {
@@ -72,6 +75,16 @@ impl MessageLaneLoopMetrics {
 					source_name, target_name
 				),
 			)?,
+			message_delivery_attempts: Histogram::with_opts(
+				HistogramOpts::new(
+					metric_name(prefix, "bridge_message_delivery_attempts"),
+					format!(
+						"Number of attempts required to deliver message from {} to {}",
+						source_name, target_name
+					),
+				)
+				.buckets(vec![1.0, 2.0, 3.0, 5.0, 10.0, 20.0, 50.0]),
+			)?,
 		})
 	}
 
@@ -145,6 +158,11 @@ impl MessageLaneLoopMetrics {
 	pub fn note_unprofitable_delivery_transactions(&self) {
 		self.unprofitable_delivery_transactions.inc()
 	}
+
+	/// Note number of delivery attempts of the confirmed message.
+	pub fn observe_message_delivery_attempts(&self, attempts: u32) {
+		self.message_delivery_attempts.observe(attempts as f64)
+	}
 }
 
 impl Metric for MessageLaneLoopMetrics {
@@ -153,6 +171,7 @@ impl Metric for MessageLaneLoopMetrics {
 		self.target_to_source_finality_metrics.register(registry)?;
 		register(self.lane_state_nonces.clone(), registry)?;
 		register(self.unprofitable_delivery_transactions.clone(), registry)?;
+		register(self.message_delivery_attempts.clone(), registry)?;
 		Ok(())
 	}
 }
@@ -0,0 +1,220 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Persistent relay-local state of the messages lane.
+//!
+//! After restart, the relay re-derives everything from the chain state, but the relay-local data
+//! (e.g. the delivery attempts log) is lost. This module saves such data to the single file per
+//! lane. The file starts with the format version, followed by the SCALE-encoded state. If the file
+//! is missing, corrupted or has been written by the incompatible relay version, the relay starts
+//! with the empty state.
+
+use crate::delivery_attempts::PersistedNonceDeliveryAttempts;
+
+use codec::{Decode, DecodeAll, Encode};
+use std::path::{Path, PathBuf};
+
+/// Version of the lane state file format. Must be bumped whenever `PersistedLaneState` changes.
+const STATE_FORMAT_VERSION: u32 = 1;
+
+/// Relay-local state of the messages lane.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Encode, Decode)]
+pub struct PersistedLaneState {
+	/// Delivery attempts of undelivered nonces.
+	pub delivery_attempts: Vec<PersistedNonceDeliveryAttempts>,
+}
+
+/// File, where the relay-local state of the messages lane is stored.
+#[derive(Debug, Clone)]
+pub struct LaneStateFile {
+	path: PathBuf,
+}
+
+impl LaneStateFile {
+	/// Create lane state file reference. The file itself is created on first save.
+	pub fn new(dir: &Path, lane_name: &str) -> Self {
+		LaneStateFile { path: dir.join(format!("{}.state", lane_name)) }
+	}
+
+	/// Returns path to the state file.
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	/// Load lane state from the file.
+	///
+	/// Returns `None` if the file doesn't exist or if it is invalid.
+	pub fn load(&self) -> Option<PersistedLaneState> {
+		let encoded_state = match std::fs::read(&self.path) {
+			Ok(encoded_state) => encoded_state,
+			Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+			Err(e) => {
+				log::warn!(
+					target: "bridge",
+					"Failed to read relay state from {}: {:?}. Starting with empty state",
+					self.path.display(),
+					e,
+				);
+				return None
+			},
+		};
+
+		let mut input = &encoded_state[..];
+		match u32::decode(&mut input) {
+			Ok(STATE_FORMAT_VERSION) => (),
+			Ok(version) => {
+				log::warn!(
+					target: "bridge",
+					"Relay state at {} has unsupported version {} (expected {}). Starting with empty state",
+					self.path.display(),
+					version,
+					STATE_FORMAT_VERSION,
+				);
+				return None
+			},
+			Err(e) => {
+				log::warn!(
+					target: "bridge",
+					"Relay state at {} is corrupted: {:?}. Starting with empty state",
+					self.path.display(),
+					e,
+				);
+				return None
+			},
+		}
+
+		PersistedLaneState::decode_all(&mut input)
+			.map_err(|e| {
+				log::warn!(
+					target: "bridge",
+					"Relay state at {} is corrupted: {:?}. Starting with empty state",
+					self.path.display(),
+					e,
+				);
+			})
+			.ok()
+	}
+
+	/// Save lane state to the file.
+	///
+	/// The state is first written to the temporary file, which then replaces the state file, so
+	/// that we never leave partially written state behind.
+	pub fn save(&self, state: &PersistedLaneState) -> std::io::Result<()> {
+		if let Some(dir) = self.path.parent() {
+			std::fs::create_dir_all(dir)?;
+		}
+
+		let tmp_path = self.path.with_extension("state.tmp");
+		std::fs::write(&tmp_path, (STATE_FORMAT_VERSION, state).encode())?;
+		std::fs::rename(&tmp_path, &self.path)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::delivery_attempts::DeliveryFailure;
+
+	struct TestDir(PathBuf);
+
+	impl TestDir {
+		fn new(name: &str) -> Self {
+			let path = std::env::temp_dir().join(format!(
+				"messages-relay-{}-{}",
+				name,
+				std::process::id()
+			));
+			std::fs::create_dir_all(&path).unwrap();
+			TestDir(path)
+		}
+	}
+
+	impl Drop for TestDir {
+		fn drop(&mut self) {
+			let _ = std::fs::remove_dir_all(&self.0);
+		}
+	}
+
+	fn test_state() -> PersistedLaneState {
+		PersistedLaneState {
+			delivery_attempts: vec![
+				PersistedNonceDeliveryAttempts {
+					nonce: 1,
+					attempts: 3,
+					last_attempt: 1_000,
+					recent_failures: vec![
+						(900, DeliveryFailure::Submission),
+						(1_000, DeliveryFailure::TransactionLost),
+					],
+				},
+				PersistedNonceDeliveryAttempts {
+					nonce: 2,
+					attempts: 1,
+					last_attempt: 1_000,
+					recent_failures: vec![],
+				},
+			],
+		}
+	}
+
+	#[test]
+	fn state_is_saved_and_loaded() {
+		let dir = TestDir::new("save-load");
+		let file = LaneStateFile::new(&dir.0, "lane");
+		file.save(&test_state()).unwrap();
+		assert_eq!(file.load(), Some(test_state()));
+
+		// state is overwritten on next save
+		file.save(&PersistedLaneState::default()).unwrap();
+		assert_eq!(file.load(), Some(PersistedLaneState::default()));
+	}
+
+	#[test]
+	fn missing_state_is_not_loaded() {
+		let dir = TestDir::new("missing");
+		assert_eq!(LaneStateFile::new(&dir.0, "lane").load(), None);
+	}
+
+	#[test]
+	fn corrupted_state_is_not_loaded() {
+		let dir = TestDir::new("corrupted");
+		let file = LaneStateFile::new(&dir.0, "lane");
+
+		// truncated state
+		let mut encoded_state = (STATE_FORMAT_VERSION, test_state()).encode();
+		encoded_state.truncate(encoded_state.len() - 1);
+		std::fs::write(file.path(), &encoded_state).unwrap();
+		assert_eq!(file.load(), None);
+
+		// trailing garbage
+		let mut encoded_state = (STATE_FORMAT_VERSION, test_state()).encode();
+		encoded_state.push(42);
+		std::fs::write(file.path(), &encoded_state).unwrap();
+		assert_eq!(file.load(), None);
+
+		// not even a version
+		std::fs::write(file.path(), &[1, 2]).unwrap();
+		assert_eq!(file.load(), None);
+	}
+
+	#[test]
+	fn state_of_unsupported_version_is_not_loaded() {
+		let dir = TestDir::new("version");
+		let file = LaneStateFile::new(&dir.0, "lane");
+		std::fs::write(file.path(), (STATE_FORMAT_VERSION + 1, test_state()).encode()).unwrap();
+		assert_eq!(file.load(), None);
+	}
+}
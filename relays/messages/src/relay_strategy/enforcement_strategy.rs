@@ -17,6 +17,7 @@
 //! enforcement strategy
 
 use num_traits::Zero;
+use sp_arithmetic::traits::Saturating;
 
 use bp_messages::{MessageNonce, Weight};
 use bp_runtime::messages::DispatchFeePayment;
@@ -24,28 +25,28 @@ use bp_runtime::messages::DispatchFeePayment;
 use crate::{
 	message_lane::MessageLane,
 	message_lane_loop::{
-		MessageDetails, SourceClient as MessageLaneSourceClient,
+		MessageDetailsMap, SourceClient as MessageLaneSourceClient,
 		TargetClient as MessageLaneTargetClient,
 	},
 	message_race_loop::NoncesRange,
-	relay_strategy::{RelayMessagesBatchReference, RelayReference, RelayStrategy},
+	relay_strategy::{RelayDecisionContext, RelayMessagesBatchReference, SharedRelayStrategy},
 };
 
 /// Do hard check and run soft check strategy
 #[derive(Clone)]
-pub struct EnforcementStrategy<Strategy: RelayStrategy> {
-	strategy: Strategy,
+pub struct EnforcementStrategy<SourceChainBalance> {
+	strategy: SharedRelayStrategy<SourceChainBalance>,
 }
 
-impl<Strategy: RelayStrategy> EnforcementStrategy<Strategy> {
-	pub fn new(strategy: Strategy) -> Self {
+impl<SourceChainBalance> EnforcementStrategy<SourceChainBalance> {
+	pub fn new(strategy: SharedRelayStrategy<SourceChainBalance>) -> Self {
 		Self { strategy }
 	}
 }
 
-impl<Strategy: RelayStrategy> EnforcementStrategy<Strategy> {
+impl<SourceChainBalance> EnforcementStrategy<SourceChainBalance> {
 	pub async fn decide<
-		P: MessageLane,
+		P: MessageLane<SourceChainBalance = SourceChainBalance>,
 		SourceClient: MessageLaneSourceClient<P>,
 		TargetClient: MessageLaneTargetClient<P>,
 	>(
@@ -56,37 +57,25 @@ impl<Strategy: RelayStrategy> EnforcementStrategy<Strategy> {
 		let mut soft_selected_count = 0;
 
 		let mut selected_weight: Weight = 0;
+		let mut selected_size: u32 = 0;
 		let mut selected_count: MessageNonce = 0;
+		let mut selected_prepaid_nonces: MessageNonce = 0;
+		let mut selected_unpaid_weight: Weight = 0;
+
+		let mut total_confirmations_cost = P::SourceChainBalance::zero();
+		let mut selected_cost = None;
+		let mut selected_reward = P::SourceChainBalance::zero();
 
 		let hard_selected_begin_nonce =
 			reference.nonces_queue[reference.nonces_queue_range.start].1.begin();
 
-		// relay reference
-		let mut relay_reference = RelayReference {
-			lane_source_client: reference.lane_source_client.clone(),
-			lane_target_client: reference.lane_target_client.clone(),
-			metrics: reference.metrics.clone(),
-
-			selected_reward: P::SourceChainBalance::zero(),
-			selected_cost: P::SourceChainBalance::zero(),
-			selected_size: 0,
-
+		// context that is passed to the relay strategy
+		let mut context = RelayDecisionContext {
+			lane: reference.lane,
+			nonces: hard_selected_begin_nonce..=hard_selected_begin_nonce,
+			messages: MessageDetailsMap::new(),
+			total_cost: Some(P::SourceChainBalance::zero()),
 			total_reward: P::SourceChainBalance::zero(),
-			total_confirmations_cost: P::SourceChainBalance::zero(),
-			total_cost: P::SourceChainBalance::zero(),
-
-			hard_selected_begin_nonce,
-			selected_prepaid_nonces: 0,
-			selected_unpaid_weight: 0,
-
-			index: 0,
-			nonce: 0,
-			details: MessageDetails {
-				dispatch_weight: 0,
-				size: 0,
-				reward: P::SourceChainBalance::zero(),
-				dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
-			},
 		};
 
 		let all_ready_nonces = reference
@@ -95,10 +84,6 @@ impl<Strategy: RelayStrategy> EnforcementStrategy<Strategy> {
 			.flat_map(|(_, ready_nonces)| ready_nonces.iter())
 			.enumerate();
 		for (index, (nonce, details)) in all_ready_nonces {
-			relay_reference.index = index;
-			relay_reference.nonce = *nonce;
-			relay_reference.details = *details;
-
 			// Since we (hopefully) have some reserves in `max_messages_weight_in_single_batch`
 			// and `max_messages_size_in_single_batch`, we may still try to submit transaction
 			// with single message if message overflows these limits. The worst case would be if
@@ -123,7 +108,7 @@ impl<Strategy: RelayStrategy> EnforcementStrategy<Strategy> {
 			};
 
 			// limit messages in the batch by size
-			let new_selected_size = match relay_reference.selected_size.checked_add(details.size) {
+			let new_selected_size = match selected_size.checked_add(details.size) {
 				Some(new_selected_size)
 					if new_selected_size <= reference.max_messages_size_in_single_batch =>
 					new_selected_size,
@@ -145,7 +130,7 @@ impl<Strategy: RelayStrategy> EnforcementStrategy<Strategy> {
 			if new_selected_count > reference.max_messages_in_this_batch {
 				break
 			}
-			relay_reference.selected_size = new_selected_size;
+			selected_size = new_selected_size;
 
 			// If dispatch fee has been paid at the source chain, it means that it is **relayer**
 			// who's paying for dispatch at the target chain AND reward must cover this dispatch
@@ -156,21 +141,89 @@ impl<Strategy: RelayStrategy> EnforcementStrategy<Strategy> {
 			//
 			// So in the latter case we're not adding the dispatch weight to the delivery
 			// transaction weight.
-			let mut new_selected_prepaid_nonces = relay_reference.selected_prepaid_nonces;
-			let new_selected_unpaid_weight = match details.dispatch_fee_payment {
+			match details.dispatch_fee_payment {
 				DispatchFeePayment::AtSourceChain => {
-					new_selected_prepaid_nonces += 1;
-					relay_reference.selected_unpaid_weight.saturating_add(details.dispatch_weight)
+					selected_prepaid_nonces += 1;
+					selected_unpaid_weight =
+						selected_unpaid_weight.saturating_add(details.dispatch_weight);
+				},
+				DispatchFeePayment::AtTargetChain => (),
+			}
+
+			// update the decision context with this message
+			let prev_is_profitable = context.is_profitable();
+			let prev_total_cost = context.total_cost;
+			let prev_total_reward = context.total_reward;
+
+			// technically, multiple confirmations will be delivered in a single transaction,
+			// meaning less loses for relayer. But here we don't know the final relayer yet, so
+			// we're adding a separate transaction for every message. Normally, this cost is
+			// covered by the message sender. Probably reconsider this?
+			total_confirmations_cost = total_confirmations_cost.saturating_add(
+				reference.lane_source_client.estimate_confirmation_transaction().await,
+			);
+			let delivery_transaction_cost = reference
+				.lane_target_client
+				.estimate_delivery_transaction_in_source_tokens(
+					hard_selected_begin_nonce..=*nonce,
+					selected_prepaid_nonces,
+					selected_unpaid_weight,
+					selected_size,
+				)
+				.await;
+
+			context.nonces = hard_selected_begin_nonce..=*nonce;
+			context.messages.insert(*nonce, *details);
+			context.total_reward = context.total_reward.saturating_add(details.reward);
+			context.total_cost = match delivery_transaction_cost {
+				Ok(delivery_transaction_cost) =>
+					Some(total_confirmations_cost.saturating_add(delivery_transaction_cost)),
+				Err(e) => {
+					log::debug!(
+						target: "bridge",
+						"Failed to estimate delivery transaction cost: {:?}. Total cost of \
+						delivering nonces {:?} is unknown",
+						e,
+						context.nonces,
+					);
+					None
 				},
-				DispatchFeePayment::AtTargetChain => relay_reference.selected_unpaid_weight,
 			};
-			relay_reference.selected_prepaid_nonces = new_selected_prepaid_nonces;
-			relay_reference.selected_unpaid_weight = new_selected_unpaid_weight;
+
+			if prev_is_profitable && !context.is_profitable() {
+				// if it is the first message that makes reward less than cost, let's log it
+				log::debug!(
+					target: "bridge",
+					"Message with nonce {} (reward = {:?}) changes total cost {:?}->{:?} and makes it larger than \
+					total reward {:?}->{:?}",
+					nonce,
+					details.reward,
+					prev_total_cost,
+					context.total_cost,
+					prev_total_reward,
+					context.total_reward,
+				);
+			} else if !prev_is_profitable && context.is_profitable() {
+				// if this message makes batch profitable again, let's log it
+				log::debug!(
+					target: "bridge",
+					"Message with nonce {} (reward = {:?}) changes total cost {:?}->{:?} and makes it less than or \
+					equal to the total reward {:?}->{:?} (again)",
+					nonce,
+					details.reward,
+					prev_total_cost,
+					context.total_cost,
+					prev_total_reward,
+					context.total_reward,
+				);
+			}
 
 			// now the message has passed all 'strong' checks, and we CAN deliver it. But do we WANT
 			// to deliver it? It depends on the relayer strategy.
-			if self.strategy.decide(&mut relay_reference).await {
+			if self.strategy.decide(&context).await {
 				soft_selected_count = index + 1;
+				selected_cost = context.total_cost;
+				selected_reward = context.total_reward;
 			}
 
 			hard_selected_count = index + 1;
@@ -198,23 +251,47 @@ impl<Strategy: RelayStrategy> EnforcementStrategy<Strategy> {
 		}
 
 		if hard_selected_count != 0 {
-			if relay_reference.selected_reward != P::SourceChainBalance::zero() &&
-				relay_reference.selected_cost != P::SourceChainBalance::zero()
-			{
-				log::trace!(
-					target: "bridge",
-					"Expected reward from delivering nonces [{:?}; {:?}] is: {:?} - {:?} = {:?}",
-					hard_selected_begin_nonce,
-					hard_selected_begin_nonce + hard_selected_count as MessageNonce - 1,
-					&relay_reference.selected_reward,
-					&relay_reference.selected_cost,
-					relay_reference.selected_reward - relay_reference.selected_cost,
-				);
-			}
-
 			let selected_max_nonce =
 				hard_selected_begin_nonce + hard_selected_count as MessageNonce - 1;
-			self.strategy.on_final_decision(&relay_reference);
+
+			// the final context only contains messages that have been selected for delivery
+			context.nonces = hard_selected_begin_nonce..=selected_max_nonce;
+			context.messages.retain(|nonce, _| *nonce <= selected_max_nonce);
+			context.total_cost = selected_cost;
+			context.total_reward = selected_reward;
+
+			match context.total_cost {
+				Some(total_cost) if total_cost > context.total_reward => {
+					log::debug!(
+						target: "bridge",
+						"The relayer is submitting unprofitable {} -> {} message delivery transaction \
+						with {} messages: total cost = {:?}, total reward = {:?}",
+						P::SOURCE_NAME,
+						P::TARGET_NAME,
+						hard_selected_count,
+						total_cost,
+						context.total_reward,
+					);
+
+					if let Some(ref metrics) = reference.metrics {
+						metrics.note_unprofitable_delivery_transactions();
+					}
+				},
+				Some(total_cost) => {
+					log::trace!(
+						target: "bridge",
+						"Expected reward from delivering nonces [{:?}; {:?}] is: {:?} - {:?} = {:?}",
+						hard_selected_begin_nonce,
+						selected_max_nonce,
+						&context.total_reward,
+						&total_cost,
+						context.total_reward - total_cost,
+					);
+				},
+				None => (),
+			}
+
+			self.strategy.on_final_decision(&context);
 			Some(selected_max_nonce)
 		} else {
 			None
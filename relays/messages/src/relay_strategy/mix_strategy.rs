@@ -19,12 +19,8 @@
 use async_trait::async_trait;
 
 use crate::{
-	message_lane::MessageLane,
-	message_lane_loop::{
-		RelayerMode, SourceClient as MessageLaneSourceClient,
-		TargetClient as MessageLaneTargetClient,
-	},
-	relay_strategy::{AltruisticStrategy, RationalStrategy, RelayReference, RelayStrategy},
+	message_lane_loop::RelayerMode,
+	relay_strategy::{AltruisticStrategy, RationalStrategy, RelayDecisionContext, RelayStrategy},
 };
 
 /// `RelayerMode` adapter.
@@ -41,32 +37,20 @@ impl MixStrategy {
 }
 
 #[async_trait]
-impl RelayStrategy for MixStrategy {
-	async fn decide<
-		P: MessageLane,
-		SourceClient: MessageLaneSourceClient<P>,
-		TargetClient: MessageLaneTargetClient<P>,
-	>(
-		&mut self,
-		reference: &mut RelayReference<P, SourceClient, TargetClient>,
-	) -> bool {
+impl<SourceChainBalance: PartialOrd + Send + Sync> RelayStrategy<SourceChainBalance>
+	for MixStrategy
+{
+	async fn decide(&self, context: &RelayDecisionContext<SourceChainBalance>) -> bool {
 		match self.relayer_mode {
-			RelayerMode::Altruistic => AltruisticStrategy.decide(reference).await,
-			RelayerMode::Rational => RationalStrategy.decide(reference).await,
+			RelayerMode::Altruistic => AltruisticStrategy.decide(context).await,
+			RelayerMode::Rational => RationalStrategy.decide(context).await,
 		}
 	}
 
-	fn on_final_decision<
-		P: MessageLane,
-		SourceClient: MessageLaneSourceClient<P>,
-		TargetClient: MessageLaneTargetClient<P>,
-	>(
-		&self,
-		reference: &RelayReference<P, SourceClient, TargetClient>,
-	) {
+	fn on_final_decision(&self, context: &RelayDecisionContext<SourceChainBalance>) {
 		match self.relayer_mode {
-			RelayerMode::Altruistic => AltruisticStrategy.on_final_decision(reference),
-			RelayerMode::Rational => RationalStrategy.on_final_decision(reference),
+			RelayerMode::Altruistic => AltruisticStrategy.on_final_decision(context),
+			RelayerMode::Rational => RationalStrategy.on_final_decision(context),
 		}
 	}
 }
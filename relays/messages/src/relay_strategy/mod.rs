@@ -15,16 +15,23 @@
 // along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
 
 //! Relayer strategy
+//!
+//! The relay strategy decides whether the relayer wants to deliver given messages. Strategies
+//! are object-safe, so custom strategies may be plugged into the relay without forking this
+//! crate. See `examples/min_profit_strategy.rs` for an example.
 
 use async_trait::async_trait;
-use bp_messages::{MessageNonce, Weight};
-use sp_arithmetic::traits::Saturating;
-use std::ops::Range;
+use bp_messages::{LaneId, MessageNonce, Weight};
+use std::{
+	fmt::{Debug, Formatter},
+	ops::{Deref, Range, RangeInclusive},
+	sync::Arc,
+};
 
 use crate::{
 	message_lane::MessageLane,
 	message_lane_loop::{
-		MessageDetails, MessageDetailsMap, SourceClient as MessageLaneSourceClient,
+		MessageDetailsMap, SourceClient as MessageLaneSourceClient,
 		TargetClient as MessageLaneTargetClient,
 	},
 	message_race_strategy::SourceRangesQueue,
@@ -39,159 +46,81 @@ mod enforcement_strategy;
 mod mix_strategy;
 mod rational_strategy;
 
-/// Relayer strategy trait
+/// Relayer strategy trait.
 #[async_trait]
-pub trait RelayStrategy: 'static + Clone + Send + Sync {
-	/// The relayer decide how to process nonce by reference.
-	/// From given set of source nonces, that are ready to be delivered, select nonces
-	/// to fit into single delivery transaction.
+pub trait RelayStrategy<SourceChainBalance>: Send + Sync {
+	/// Decide whether the relayer wants to deliver all messages from the `context.nonces` range
+	/// in a single delivery transaction.
 	///
-	/// The function returns last nonce that must be delivered to the target chain.
-	async fn decide<
-		P: MessageLane,
-		SourceClient: MessageLaneSourceClient<P>,
-		TargetClient: MessageLaneTargetClient<P>,
-	>(
-		&mut self,
-		reference: &mut RelayReference<P, SourceClient, TargetClient>,
-	) -> bool;
-
-	/// Notification that the following maximal nonce has been selected for the delivery.
-	fn on_final_decision<
-		P: MessageLane,
-		SourceClient: MessageLaneSourceClient<P>,
-		TargetClient: MessageLaneTargetClient<P>,
-	>(
-		&self,
-		reference: &RelayReference<P, SourceClient, TargetClient>,
-	);
+	/// The method is called for every message that may be appended to the delivery transaction,
+	/// starting from the first undelivered message. The largest range that has been accepted
+	/// by the strategy is delivered.
+	async fn decide(&self, context: &RelayDecisionContext<SourceChainBalance>) -> bool;
+
+	/// Notification that messages from the `context.nonces` range have been selected for the
+	/// delivery.
+	fn on_final_decision(&self, _context: &RelayDecisionContext<SourceChainBalance>) {}
 }
 
-/// Total cost of mesage delivery and confirmation.
-struct MessagesDeliveryCost<SourceChainBalance> {
-	/// Cost of message delivery transaction.
-	pub delivery_transaction_cost: SourceChainBalance,
-	/// Cost of confirmation delivery transaction.
-	pub confirmation_transaction_cost: SourceChainBalance,
+/// Relay strategy that may be shared by multiple message lane loops.
+pub struct SharedRelayStrategy<SourceChainBalance>(Arc<dyn RelayStrategy<SourceChainBalance>>);
+
+impl<SourceChainBalance> SharedRelayStrategy<SourceChainBalance> {
+	/// Create shared strategy from the given strategy.
+	pub fn new(strategy: impl RelayStrategy<SourceChainBalance> + 'static) -> Self {
+		SharedRelayStrategy(Arc::new(strategy))
+	}
+
+	/// Create shared strategy from the boxed custom strategy.
+	pub fn from_boxed(strategy: Box<dyn RelayStrategy<SourceChainBalance>>) -> Self {
+		SharedRelayStrategy(strategy.into())
+	}
 }
 
-/// Reference data for participating in relay
-pub struct RelayReference<
-	P: MessageLane,
-	SourceClient: MessageLaneSourceClient<P>,
-	TargetClient: MessageLaneTargetClient<P>,
-> {
-	/// The client that is connected to the message lane source node.
-	pub lane_source_client: SourceClient,
-	/// The client that is connected to the message lane target node.
-	pub lane_target_client: TargetClient,
-	/// Metrics reference.
-	pub metrics: Option<MessageLaneLoopMetrics>,
-	/// Current block reward summary
-	pub selected_reward: P::SourceChainBalance,
-	/// Current block cost summary
-	pub selected_cost: P::SourceChainBalance,
-	/// Messages size summary
-	pub selected_size: u32,
-
-	/// Current block reward summary
-	pub total_reward: P::SourceChainBalance,
-	/// All confirmations cost
-	pub total_confirmations_cost: P::SourceChainBalance,
-	/// Current block cost summary
-	pub total_cost: P::SourceChainBalance,
-
-	/// Hard check begin nonce
-	pub hard_selected_begin_nonce: MessageNonce,
-	/// Count prepaid nonces
-	pub selected_prepaid_nonces: MessageNonce,
-	/// Unpaid nonces weight summary
-	pub selected_unpaid_weight: Weight,
-
-	/// Index by all ready nonces
-	pub index: usize,
-	/// Current nonce
-	pub nonce: MessageNonce,
-	/// Current nonce details
-	pub details: MessageDetails<P::SourceChainBalance>,
+impl<SourceChainBalance> Clone for SharedRelayStrategy<SourceChainBalance> {
+	fn clone(&self) -> Self {
+		SharedRelayStrategy(self.0.clone())
+	}
 }
 
-impl<
-		P: MessageLane,
-		SourceClient: MessageLaneSourceClient<P>,
-		TargetClient: MessageLaneTargetClient<P>,
-	> RelayReference<P, SourceClient, TargetClient>
-{
-	/// Returns whether the current `RelayReference` is profitable.
-	pub fn is_profitable(&self) -> bool {
-		self.total_reward >= self.total_cost
+impl<SourceChainBalance> Debug for SharedRelayStrategy<SourceChainBalance> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		f.write_str("SharedRelayStrategy")
 	}
+}
 
-	async fn estimate_messages_delivery_cost(
-		&self,
-	) -> Result<MessagesDeliveryCost<P::SourceChainBalance>, TargetClient::Error> {
-		// technically, multiple confirmations will be delivered in a single transaction,
-		// meaning less loses for relayer. But here we don't know the final relayer yet, so
-		// we're adding a separate transaction for every message. Normally, this cost is covered
-		// by the message sender. Probably reconsider this?
-		let confirmation_transaction_cost =
-			self.lane_source_client.estimate_confirmation_transaction().await;
-
-		let delivery_transaction_cost = self
-			.lane_target_client
-			.estimate_delivery_transaction_in_source_tokens(
-				self.hard_selected_begin_nonce..=
-					(self.hard_selected_begin_nonce + self.index as MessageNonce),
-				self.selected_prepaid_nonces,
-				self.selected_unpaid_weight,
-				self.selected_size as u32,
-			)
-			.await?;
-
-		Ok(MessagesDeliveryCost { confirmation_transaction_cost, delivery_transaction_cost })
+impl<SourceChainBalance> Deref for SharedRelayStrategy<SourceChainBalance> {
+	type Target = dyn RelayStrategy<SourceChainBalance>;
+
+	fn deref(&self) -> &Self::Target {
+		&*self.0
 	}
+}
+
+/// Data that is available to the relay strategy when it makes a decision.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RelayDecisionContext<SourceChainBalance> {
+	/// Lane, which messages are going to be delivered.
+	pub lane: LaneId,
+	/// Nonces of messages that are going to be delivered in a single transaction.
+	pub nonces: RangeInclusive<MessageNonce>,
+	/// Details of every message from the `nonces` range.
+	pub messages: MessageDetailsMap<SourceChainBalance>,
+	/// Estimated cost of the delivery transaction and of confirmation transactions for all
+	/// messages from the `nonces` range, in source chain tokens. `None` if we have failed to
+	/// estimate the cost.
+	pub total_cost: Option<SourceChainBalance>,
+	/// Total reward that the relayer receives for delivering all messages from the `nonces`
+	/// range, in source chain tokens.
+	pub total_reward: SourceChainBalance,
+}
 
-	async fn update_cost_and_reward(&mut self) -> Result<(), TargetClient::Error> {
-		let prev_is_profitable = self.is_profitable();
-		let prev_total_cost = self.total_cost;
-		let prev_total_reward = self.total_reward;
-
-		let MessagesDeliveryCost { confirmation_transaction_cost, delivery_transaction_cost } =
-			self.estimate_messages_delivery_cost().await?;
-		self.total_confirmations_cost =
-			self.total_confirmations_cost.saturating_add(confirmation_transaction_cost);
-		self.total_reward = self.total_reward.saturating_add(self.details.reward);
-		self.total_cost = self.total_confirmations_cost.saturating_add(delivery_transaction_cost);
-
-		if prev_is_profitable && !self.is_profitable() {
-			// if it is the first message that makes reward less than cost, let's log it
-			log::debug!(
-				target: "bridge",
-				"Message with nonce {} (reward = {:?}) changes total cost {:?}->{:?} and makes it larger than \
-				total reward {:?}->{:?}",
-				self.nonce,
-				self.details.reward,
-				prev_total_cost,
-				self.total_cost,
-				prev_total_reward,
-				self.total_reward,
-			);
-		} else if !prev_is_profitable && self.is_profitable() {
-			// if this message makes batch profitable again, let's log it
-			log::debug!(
-				target: "bridge",
-				"Message with nonce {} (reward = {:?}) changes total cost {:?}->{:?} and makes it less than or \
-				equal to the total reward {:?}->{:?} (again)",
-				self.nonce,
-				self.details.reward,
-				prev_total_cost,
-				self.total_cost,
-				prev_total_reward,
-				self.total_reward,
-			);
-		}
-
-		Ok(())
+impl<SourceChainBalance: PartialOrd> RelayDecisionContext<SourceChainBalance> {
+	/// Returns true if the relayer is not losing any funds by delivering messages.
+	///
+	/// Returns false if the cost is unknown.
+	pub fn is_profitable(&self) -> bool {
+		matches!(self.total_cost, Some(ref total_cost) if self.total_reward >= *total_cost)
 	}
 }
 
@@ -201,6 +130,8 @@ pub struct RelayMessagesBatchReference<
 	SourceClient: MessageLaneSourceClient<P>,
 	TargetClient: MessageLaneTargetClient<P>,
 > {
+	/// Lane, which messages are going to be delivered.
+	pub lane: LaneId,
 	/// Maximal number of relayed messages in single delivery transaction.
 	pub max_messages_in_this_batch: MessageNonce,
 	/// Maximal cumulative dispatch weight of relayed messages in single delivery transaction.
@@ -18,13 +18,7 @@
 
 use async_trait::async_trait;
 
-use crate::{
-	message_lane::MessageLane,
-	message_lane_loop::{
-		SourceClient as MessageLaneSourceClient, TargetClient as MessageLaneTargetClient,
-	},
-	relay_strategy::{RelayReference, RelayStrategy},
-};
+use crate::relay_strategy::{RelayDecisionContext, RelayStrategy};
 
 /// The relayer will deliver all messages and confirmations as long as he's not losing any
 /// funds.
@@ -32,44 +26,12 @@ use crate::{
 pub struct RationalStrategy;
 
 #[async_trait]
-impl RelayStrategy for RationalStrategy {
-	async fn decide<
-		P: MessageLane,
-		SourceClient: MessageLaneSourceClient<P>,
-		TargetClient: MessageLaneTargetClient<P>,
-	>(
-		&mut self,
-		reference: &mut RelayReference<P, SourceClient, TargetClient>,
-	) -> bool {
-		if let Err(e) = reference.update_cost_and_reward().await {
-			log::debug!(
-				target: "bridge",
-				"Failed to update transaction cost and reward: {:?}. No nonces selected for delivery",
-				e,
-			);
-
-			return false
-		}
-
-		// Rational relayer never wants to lose his funds.
-		if reference.is_profitable() {
-			reference.selected_reward = reference.total_reward;
-			reference.selected_cost = reference.total_cost;
-			return true
-		}
-
-		false
-	}
-
-	fn on_final_decision<
-		P: MessageLane,
-		SourceClient: MessageLaneSourceClient<P>,
-		TargetClient: MessageLaneTargetClient<P>,
-	>(
-		&self,
-		_reference: &RelayReference<P, SourceClient, TargetClient>,
-	) {
-		// rational relayer would never submit unprofitable transactions, so we don't need to do
-		// anything here
+impl<SourceChainBalance: PartialOrd + Send + Sync> RelayStrategy<SourceChainBalance>
+	for RationalStrategy
+{
+	async fn decide(&self, context: &RelayDecisionContext<SourceChainBalance>) -> bool {
+		// Rational relayer never wants to lose his funds. If we have failed to estimate the
+		// cost, then nothing is selected
+		context.is_profitable()
 	}
 }
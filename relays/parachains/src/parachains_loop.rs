@@ -277,7 +277,7 @@ where
 						P::TargetChain::NAME,
 					);
 
-					return Err(FailedClient::Both)
+					return Err(FailedClient::Neither)
 				},
 			}
 		}
@@ -438,7 +438,7 @@ where
 			if needs_update {
 				log::trace!(
 					target: "bridge",
-					"{} parachain {:?} needs update at {}: {:?} vs {:?}",
+					"{} parachain {} needs update at {}: {:?} vs {:?}",
 					P::SourceChain::NAME,
 					para,
 					P::TargetChain::NAME,
@@ -480,7 +480,7 @@ async fn read_heads_at_source<P: ParachainsPipeline>(
 			Err(e) => {
 				log::warn!(
 					target: "bridge",
-					"Failed to read head of {} parachain {:?}: {:?}",
+					"Failed to read head of {} parachain {}: {:?}",
 					P::SourceChain::NAME,
 					para,
 					e,
@@ -511,7 +511,7 @@ async fn read_heads_at_target<P: ParachainsPipeline>(
 			Err(e) => {
 				log::warn!(
 					target: "bridge",
-					"Failed to read head of {} parachain {:?} at {}: {:?}",
+					"Failed to read head of {} parachain {} at {}: {:?}",
 					P::SourceChain::NAME,
 					para,
 					P::TargetChain::NAME,
@@ -595,7 +595,7 @@ where
 
 				log::trace!(
 					target: "bridge",
-					"Head of parachain {:?} has been updated at {}: {:?}. Outdated parachains remaining: {}",
+					"Head of parachain {} has been updated at {}: {:?}. Outdated parachains remaining: {}",
 					para,
 					P::TargetChain::NAME,
 					best_para_head,
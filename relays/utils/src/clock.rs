@@ -0,0 +1,196 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Clock that is used by relay loops to track their progress and to detect stalls.
+//!
+//! When the relay is running on a machine that may be suspended (laptops, preemptible cloud
+//! instances, ...), the clock may jump forward right after resume. If stall timers are using
+//! such clock directly, they are firing spuriously and healthy loops are restarted. So instead
+//! we are treating large jumps as "time warp" events, which restart stall timers.
+
+use std::{
+	sync::{Arc, Mutex},
+	time::{Duration, Instant},
+};
+
+/// Default maximal time between two subsequent checks of the `StallTimer`. If more time has
+/// passed between checks, we believe that the process has been suspended.
+pub const DEFAULT_TIME_WARP_THRESHOLD: Duration = Duration::from_secs(60);
+/// Interval at which `StallTimer::wait` checks the timer.
+pub const STALL_TIMER_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Clock used by relay loops.
+pub trait RelayClock: Send + Sync {
+	/// Returns current time.
+	fn now(&self) -> Instant;
+}
+
+/// Default relay clock, backed by the monotonic `Instant`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MonotonicClock;
+
+impl RelayClock for MonotonicClock {
+	fn now(&self) -> Instant {
+		Instant::now()
+	}
+}
+
+/// Manually controlled clock, mostly useful for tests.
+#[derive(Clone, Debug)]
+pub struct TestClock(Arc<Mutex<Instant>>);
+
+impl Default for TestClock {
+	fn default() -> Self {
+		TestClock(Arc::new(Mutex::new(Instant::now())))
+	}
+}
+
+impl TestClock {
+	/// Move clock forward by given duration.
+	pub fn advance(&self, duration: Duration) {
+		let mut now = self.0.lock().expect("TestClock mutex is never poisoned; qed");
+		*now += duration;
+	}
+}
+
+impl RelayClock for TestClock {
+	fn now(&self) -> Instant {
+		*self.0.lock().expect("TestClock mutex is never poisoned; qed")
+	}
+}
+
+/// Stall timer that is aware of time warps.
+#[derive(Clone, Debug)]
+pub struct StallTimer<C> {
+	clock: C,
+	stall_timeout: Duration,
+	time_warp_threshold: Duration,
+	started_at: Instant,
+	checked_at: Instant,
+}
+
+impl<C: RelayClock> StallTimer<C> {
+	/// Create and start new stall timer.
+	pub fn new(clock: C, stall_timeout: Duration, time_warp_threshold: Duration) -> Self {
+		let now = clock.now();
+		StallTimer { clock, stall_timeout, time_warp_threshold, started_at: now, checked_at: now }
+	}
+
+	/// Restart the timer.
+	pub fn reset(&mut self) {
+		let now = self.clock.now();
+		self.started_at = now;
+		self.checked_at = now;
+	}
+
+	/// Returns true if stall timeout has elapsed since the timer has been (re)started.
+	///
+	/// If more than `time_warp_threshold` has passed since previous check, the timer is
+	/// restarted instead.
+	pub fn is_stalled(&mut self) -> bool {
+		let now = self.clock.now();
+		let since_last_check = now.saturating_duration_since(self.checked_at);
+		if since_last_check > self.time_warp_threshold {
+			log::warn!(
+				target: "bridge",
+				"Time warp of {}s has been detected (has the process been suspended?). \
+				Restarting stall timer",
+				since_last_check.as_secs(),
+			);
+			self.started_at = now;
+		}
+
+		self.checked_at = now;
+		now.saturating_duration_since(self.started_at) >= self.stall_timeout
+	}
+
+	/// Wait until the timer is stalled.
+	pub async fn wait(mut self, check_interval: Duration) {
+		while !self.is_stalled() {
+			async_std::task::sleep(check_interval).await;
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use futures::FutureExt;
+
+	const STALL_TIMEOUT: Duration = Duration::from_secs(60);
+	const TIME_WARP_THRESHOLD: Duration = Duration::from_secs(30);
+
+	fn stall_timer() -> (TestClock, StallTimer<TestClock>) {
+		let clock = TestClock::default();
+		(clock.clone(), StallTimer::new(clock, STALL_TIMEOUT, TIME_WARP_THRESHOLD))
+	}
+
+	#[test]
+	fn stall_timer_is_stalled_after_timeout() {
+		let (clock, mut timer) = stall_timer();
+		for _ in 0..5 {
+			clock.advance(Duration::from_secs(10));
+			assert!(!timer.is_stalled());
+		}
+
+		clock.advance(Duration::from_secs(10));
+		assert!(timer.is_stalled());
+	}
+
+	#[test]
+	fn stall_timer_is_not_stalled_after_reset() {
+		let (clock, mut timer) = stall_timer();
+		clock.advance(Duration::from_secs(25));
+		assert!(!timer.is_stalled());
+		clock.advance(Duration::from_secs(25));
+		timer.reset();
+
+		clock.advance(Duration::from_secs(25));
+		assert!(!timer.is_stalled());
+		clock.advance(Duration::from_secs(25));
+		assert!(!timer.is_stalled());
+		clock.advance(Duration::from_secs(10));
+		assert!(timer.is_stalled());
+	}
+
+	#[test]
+	fn stall_timer_is_restarted_after_resume() {
+		let (clock, mut timer) = stall_timer();
+		clock.advance(Duration::from_secs(20));
+		assert!(!timer.is_stalled());
+
+		// process has been suspended for an hour => timer is restarted
+		clock.advance(Duration::from_secs(60 * 60));
+		assert!(!timer.is_stalled());
+
+		// and stall is detected once the full timeout has elapsed since resume
+		clock.advance(Duration::from_secs(30));
+		assert!(!timer.is_stalled());
+		clock.advance(Duration::from_secs(30));
+		assert!(timer.is_stalled());
+	}
+
+	#[test]
+	fn stall_timer_wait_resolves_when_stalled() {
+		let (clock, mut timer) = stall_timer();
+		clock.advance(Duration::from_secs(20));
+		assert!(!timer.is_stalled());
+		clock.advance(Duration::from_secs(20));
+		assert!(!timer.is_stalled());
+		clock.advance(Duration::from_secs(20));
+		assert_eq!(timer.wait(Duration::from_secs(1)).now_or_never(), Some(()));
+	}
+}
@@ -42,6 +42,7 @@ pub const MAX_BACKOFF_INTERVAL: Duration = Duration::from_secs(60);
 /// reconnection again.
 pub const CONNECTION_ERROR_DELAY: Duration = Duration::from_secs(10);
 
+pub mod clock;
 pub mod error;
 pub mod initialize;
 pub mod metrics;
@@ -217,6 +218,10 @@ pub enum FailedClient {
 	/// Both clients are failing, or we just encountered some other error that
 	/// should be treated like that.
 	Both,
+	/// Both clients are healthy, but the loop needs to be restarted (e.g. because it has
+	/// stalled). Connections are not touched, so other loops that are sharing clients are not
+	/// affected.
+	Neither,
 }
 
 /// Future process result.
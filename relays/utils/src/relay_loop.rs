@@ -227,6 +227,9 @@ impl<SC, TC, LM> LoopMetrics<SC, TC, LM> {
 }
 
 /// Deal with the client who has returned connection error.
+///
+/// Only failed clients are reconnected. If no client has failed, we just sleep for
+/// `reconnect_delay` before the loop is restarted.
 pub async fn reconnect_failed_client(
 	failed_client: FailedClient,
 	reconnect_delay: Duration,
@@ -235,6 +238,9 @@ pub async fn reconnect_failed_client(
 ) {
 	loop {
 		async_std::task::sleep(reconnect_delay).await;
+		if failed_client == FailedClient::Neither {
+			break
+		}
 		if failed_client == FailedClient::Both || failed_client == FailedClient::Source {
 			match source_client.reconnect().await {
 				Ok(()) => (),
@@ -267,3 +273,51 @@ pub async fn reconnect_failed_client(
 		break
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::sync::{
+		atomic::{AtomicUsize, Ordering},
+		Arc,
+	};
+
+	#[derive(Clone, Default)]
+	struct TestClient {
+		reconnects: Arc<AtomicUsize>,
+	}
+
+	#[async_trait]
+	impl Client for TestClient {
+		type Error = crate::StringifiedMaybeConnectionError;
+
+		async fn reconnect(&mut self) -> Result<(), Self::Error> {
+			self.reconnects.fetch_add(1, Ordering::SeqCst);
+			Ok(())
+		}
+	}
+
+	async fn reconnects_after_failure(failed_client: FailedClient) -> (usize, usize) {
+		let mut source_client = TestClient::default();
+		let mut target_client = TestClient::default();
+		reconnect_failed_client(
+			failed_client,
+			Duration::from_millis(1),
+			&mut source_client,
+			&mut target_client,
+		)
+		.await;
+		(
+			source_client.reconnects.load(Ordering::SeqCst),
+			target_client.reconnects.load(Ordering::SeqCst),
+		)
+	}
+
+	#[async_std::test]
+	async fn only_failed_clients_are_reconnected() {
+		assert_eq!(reconnects_after_failure(FailedClient::Source).await, (1, 0));
+		assert_eq!(reconnects_after_failure(FailedClient::Target).await, (0, 1));
+		assert_eq!(reconnects_after_failure(FailedClient::Both).await, (1, 1));
+		assert_eq!(reconnects_after_failure(FailedClient::Neither).await, (0, 0));
+	}
+}
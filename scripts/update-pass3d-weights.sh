@@ -0,0 +1,33 @@
+#!/bin/sh
+#
+# Runtime benchmarks of bridge pallets, deployed at the Pass3d runtime.
+#
+# Run this script from root of the repo on the Pass3d reference hardware.
+
+set -eux
+
+mkdir -p ./bin/pass3d/runtime/src/weights
+
+time cargo run --release -p pass3d-bridge-node --features=runtime-benchmarks -- benchmark pallet \
+	--chain=dev \
+	--steps=50 \
+	--repeat=20 \
+	--pallet=pallet_bridge_messages \
+	--extrinsic=* \
+	--execution=wasm \
+	--wasm-execution=Compiled \
+	--heap-pages=4096 \
+	--output=./bin/pass3d/runtime/src/weights/pallet_bridge_messages.rs \
+	--template=./.maintain/millau-weight-template.hbs
+
+time cargo run --release -p pass3d-bridge-node --features=runtime-benchmarks -- benchmark pallet \
+	--chain=dev \
+	--steps=50 \
+	--repeat=20 \
+	--pallet=pallet_bridge_grandpa \
+	--extrinsic=* \
+	--execution=wasm \
+	--wasm-execution=Compiled \
+	--heap-pages=4096 \
+	--output=./bin/pass3d/runtime/src/weights/pallet_bridge_grandpa.rs \
+	--template=./.maintain/millau-weight-template.hbs